@@ -0,0 +1,85 @@
+//! Build script: OpenAPI examples from recorded fixtures
+//!
+//! Every `fixtures/openapi/<name>.json` (a sanitized recording of a real
+//! upstream response) becomes `crate::models::examples::<name>()`, which the
+//! larger response models reference via `#[schema(example = ...)]`.
+//!
+//! Before embedding, fixtures are re-sanitized (credential-like values are
+//! replaced) and long arrays are trimmed so the Swagger page stays readable.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+const FIXTURE_DIR: &str = "fixtures/openapi";
+
+/// Keys whose values must never end up in the published OpenAPI document
+const SENSITIVE_KEYS: &[&str] = &[
+    "xsec_token", "web_session", "a1", "webId", "web_id", "cookie", "cookies",
+    "token", "access_token", "galaxy_creator_session_id",
+];
+
+/// Arrays are cut down to this many items
+const MAX_ARRAY_ITEMS: usize = 2;
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", FIXTURE_DIR);
+
+    let out_dir = std::env::var("OUT_DIR").expect("OUT_DIR is set by cargo");
+    let examples_dir = Path::new(&out_dir).join("openapi_examples");
+    fs::create_dir_all(&examples_dir).expect("create OUT_DIR/openapi_examples");
+
+    let mut entries: Vec<_> = fs::read_dir(FIXTURE_DIR)
+        .map(|dir| dir.filter_map(Result::ok).map(|e| e.path()).collect())
+        .unwrap_or_default();
+    entries.retain(|p| p.extension().is_some_and(|e| e == "json"));
+    entries.sort();
+
+    let mut generated = String::new();
+    for path in entries {
+        println!("cargo:rerun-if-changed={}", path.display());
+        let name = path.file_stem().unwrap().to_string_lossy().to_string();
+        assert!(
+            name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_'),
+            "fixture name must be snake_case: {}", path.display()
+        );
+
+        let content = fs::read_to_string(&path).unwrap();
+        let mut value: serde_json::Value = serde_json::from_str(&content)
+            .unwrap_or_else(|e| panic!("invalid fixture {}: {}", path.display(), e));
+        sanitize(&mut value);
+
+        let target = examples_dir.join(format!("{}.json", name));
+        fs::write(&target, serde_json::to_string_pretty(&value).unwrap()).unwrap();
+
+        writeln!(
+            generated,
+            "/// Example generated from `{}/{}.json`\n\
+             pub fn {}() -> serde_json::Value {{\n    \
+                 serde_json::from_str(include_str!({:?})).expect(\"fixture validated by build.rs\")\n\
+             }}\n",
+            FIXTURE_DIR, name, name, target.display().to_string()
+        ).unwrap();
+    }
+
+    fs::write(Path::new(&out_dir).join("openapi_examples.rs"), generated).unwrap();
+}
+
+fn sanitize(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, v) in map.iter_mut() {
+                if SENSITIVE_KEYS.contains(&key.as_str()) && v.is_string() {
+                    *v = serde_json::Value::String(format!("<{}>", key));
+                } else {
+                    sanitize(v);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => {
+            items.truncate(MAX_ARRAY_ITEMS);
+            items.iter_mut().for_each(sanitize);
+        }
+        _ => {}
+    }
+}
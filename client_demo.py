@@ -49,11 +49,11 @@ def guest_init():
             data = json.loads(response.read().decode('utf-8'))
             
         if data.get("success"):
-            cookies = data.get("cookies", {})
+            cookies = data["data"].get("cookies", {})
             print(f"    ✅ 获取访客 Cookie 成功 (数量: {len(cookies)})")
             return True
         else:
-            print(f"    ❌ 失败: {data.get('error')}")
+            print(f"    ❌ 失败: {data.get('msg')}")
             return False
     except Exception as e:
         print(f"    ❌ 错误: {e}")
@@ -69,8 +69,8 @@ def create_qrcode():
             data = json.loads(response.read().decode('utf-8'))
         
         if data.get("success"):
-            qr_url = data.get("qr_url")
-            qr_id = data.get("qr_id")
+            qr_url = data["data"].get("qr_url")
+            qr_id = data["data"].get("qr_id")
             
             print(f"    ✅ 二维码创建成功")
             print(f"    QR ID: {qr_id}")
@@ -88,7 +88,7 @@ def create_qrcode():
             
             return True
         else:
-            print(f"    ❌ 失败: {data.get('error')}")
+            print(f"    ❌ 失败: {data.get('msg')}")
             return False
     except Exception as e:
         print(f"    ❌ 错误: {e}")
@@ -110,15 +110,16 @@ def poll_qrcode_status(timeout=120):
                 data = json.loads(response.read().decode('utf-8'))
             
             if data.get("success"):
-                code_status = data.get("code_status", -1)
+                status = data["data"]
+                code_status = status.get("code_status", -1)
                 
                 if code_status == 2:
                     print("\n")
                     print("    ✅ 登录成功!")
-                    login_info = data.get("login_info", {})
+                    login_info = status.get("login_info") or {}
                     if login_info:
                         print(f"    User ID: {login_info.get('user_id', 'N/A')}")
-                    new_cookies = data.get("new_cookies", {})
+                    new_cookies = status.get("new_cookies") or {}
                     if new_cookies:
                         print(f"    获取新 Cookie: {len(new_cookies)} 个")
                     return True
@@ -176,12 +177,12 @@ def test_creator_login():
             data = json.loads(response.read().decode('utf-8'))
             
         if data.get("success"):
-            cookies = data.get("cookies", {})
+            cookies = data["data"].get("cookies", {})
             print(f"    ✅ 获取 ugc 访客 Cookie 成功 (数量: {len(cookies)})")
             if 'xsecappid' in cookies:
                 print(f"    Context check: xsecappid={cookies['xsecappid']}")
         else:
-            print(f"    ❌ 失败: {data.get('error')}")
+            print(f"    ❌ 失败: {data.get('msg')}")
             return
     except Exception as e:
         print(f"    ❌ 错误: {e}")
@@ -200,8 +201,8 @@ def test_creator_login():
             data = json.loads(response.read().decode('utf-8'))
         
         if data.get("success"):
-            qr_url = data.get("qr_url")
-            qr_id = data.get("qr_id")
+            qr_url = data["data"].get("qr_url")
+            qr_id = data["data"].get("qr_id")
             
             print(f"    ✅ 二维码创建成功")
             print(f"    QR ID: {qr_id}")
@@ -222,7 +223,7 @@ def test_creator_login():
             # print("\n⚠️  注意: 请立刻手动扫码，并在浏览器 F12 中捕获轮询请求 (status)！")
             
         else:
-            print(f"    ❌ 失败: {data.get('error')}")
+            print(f"    ❌ 失败: {data.get('msg')}")
             return 
     except Exception as e:
         print(f"    ❌ 错误: {e}")
@@ -321,7 +322,7 @@ def test_creator_apis():
             print(f"    Role: {info.get('role')}")
             print(f"    Permissions: {len(info.get('permissions', []))} items")
         else:
-            print(f"    ❌ 失败: {data.get('error')}")
+            print(f"    ❌ 失败: {data.get('msg')}")
     except Exception as e:
         print(f"    ❌ 请求错误: {e}")
 
@@ -340,7 +341,7 @@ def test_creator_apis():
             print(f"    Likes: {info.get('faved_count')}")
             print(f"    Desc: {info.get('personal_desc')}")
         else:
-            print(f"    ❌ 失败: {data.get('error')}")
+            print(f"    ❌ 失败: {data.get('msg')}")
     except Exception as e:
         print(f"    ❌ 请求错误: {e}")
 
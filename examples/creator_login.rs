@@ -0,0 +1,100 @@
+//! End-to-end workflow: creator center login → account overview → drafts → publish
+//!
+//! Usage:
+//!   cargo run --example creator_login -- [title image.jpg [image2.png ...]]
+//!
+//! Reuses cookie-creator.json when present, otherwise prints a creator
+//! center QR code and waits for the scan. Lists the draft box, then, when a
+//! title and image files are given, publishes them as a private image note
+//! (only visible to the account) so the run does not post publicly.
+
+use anyhow::{anyhow, Result};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use xhs_rs::api::creator::auth::{check_creator_qrcode_status, create_creator_qrcode, fetch_creator_guest_cookies};
+use xhs_rs::api::creator::drafts::{list_drafts, DraftListParams};
+use xhs_rs::api::creator::info::{get_creator_home_info, get_creator_user_info};
+use xhs_rs::api::creator::publish::{load_image, publish_image_note, ImageSource, PublishImageRequest};
+use xhs_rs::auth::AuthService;
+use xhs_rs::utils::print_qr_to_terminal;
+use xhs_rs::UserCredentials;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt::init();
+    let mut args = std::env::args().skip(1);
+    let title = args.next();
+    let image_paths: Vec<String> = args.collect();
+
+    // 1. Login (reuse cookie-creator.json when possible)
+    let auth = AuthService::new(PathBuf::from("cookie-creator.json")).await?;
+    let creds = match auth.try_get_credentials().await? {
+        Some(creds) => creds,
+        None => creator_qr_login(&auth).await?,
+    };
+
+    // 2. Account overview
+    let user = get_creator_user_info(&creds.cookies).await?;
+    println!("创作者: {:?} ({:?})", user.user_name, user.user_id);
+
+    let home = get_creator_home_info(&creds.cookies).await?;
+    println!("粉丝: {:?}  获赞与收藏: {:?}", home.fans_count, home.faved_count);
+
+    // 3. Draft box
+    let params = DraftListParams { page: 1, page_size: 10, note_type: None };
+    let (drafts, has_more) = list_drafts(&creds.cookies, &params).await?;
+    println!("草稿箱: {} 篇{}", drafts.len(), if has_more { "（还有更多）" } else { "" });
+    for draft in &drafts {
+        println!("  - {} [{}] {}", draft.draft_id, draft.note_type.as_deref().unwrap_or("?"), draft.title.as_deref().unwrap_or(""));
+    }
+
+    // 4. Publish a private image note from local files
+    let Some(title) = title.filter(|_| !image_paths.is_empty()) else {
+        println!("未提供标题与图片，跳过发布");
+        return Ok(());
+    };
+    let req = PublishImageRequest {
+        title,
+        desc: String::new(),
+        topics: Vec::new(),
+        images: image_paths.into_iter().map(|path| ImageSource::Path { path }).collect(),
+        is_private: true,
+    };
+    let mut images = Vec::with_capacity(req.images.len());
+    for source in &req.images {
+        images.push(load_image(source, |path| Ok(PathBuf::from(path))).await?);
+    }
+    let published = publish_image_note(&creds.cookies, &req, images).await?;
+    println!("✅ 已发布（仅自己可见）: {}", published.note_id);
+
+    Ok(())
+}
+
+/// Creator center QR login (status: 2=waiting, 3=scanned, 1=success)
+async fn creator_qr_login(auth: &AuthService) -> Result<UserCredentials> {
+    let guest_cookies = fetch_creator_guest_cookies().await?;
+    let qr = create_creator_qrcode(&guest_cookies).await?
+        .data
+        .ok_or_else(|| anyhow!("QR code data missing"))?;
+    print_qr_to_terminal(&qr.url, "请使用小红书 App 扫码登录创作者中心")?;
+
+    loop {
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        let (json, new_cookies) = check_creator_qrcode_status(&qr.qr_id, &guest_cookies).await?;
+        let Some(cookies) = new_cookies.filter(|_| {
+            json.pointer("/data/status").and_then(|s| s.as_i64()) == Some(1)
+        }) else {
+            continue;
+        };
+
+        let user_id = json.pointer("/data/user_id")
+            .and_then(|u| u.as_str())
+            .unwrap_or("unknown")
+            .to_string();
+        let creds = UserCredentials::new(user_id, cookies, None);
+        auth.save_credentials(&creds).await?;
+        println!("✅ 创作者登录成功: {}", creds.user_id);
+        return Ok(creds);
+    }
+}
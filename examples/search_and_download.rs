@@ -0,0 +1,110 @@
+//! End-to-end workflow: login → search → hydrate details → download images
+//!
+//! Usage:
+//!   cargo run --example search_and_download -- <keyword> [max_notes]
+//!
+//! Requires the Python Agent (signature + guest cookies) to be running.
+//! If cookie.json holds no valid session, a QR code is printed to the
+//! terminal and the example waits for the scan.
+
+use anyhow::{anyhow, Result};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use xhs_rs::api::login::{self, QrCodeState};
+use xhs_rs::api::media::download::{download_media, DownloadRequest};
+use xhs_rs::api::media::images::{get_image_urls, ImagesRequest};
+use xhs_rs::api::search::search_notes;
+use xhs_rs::api::XhsApiClient;
+use xhs_rs::auth::AuthService;
+use xhs_rs::models::search::SearchNotesRequest;
+use xhs_rs::utils::print_qr_to_terminal;
+use xhs_rs::{UserCredentials, XhsClient};
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let mut args = std::env::args().skip(1);
+    let keyword = args.next().ok_or_else(|| anyhow!("usage: search_and_download <keyword> [max_notes]"))?;
+    let max_notes: usize = args.next().and_then(|n| n.parse().ok()).unwrap_or(3);
+
+    // 1. Login (reuse cookie.json when possible)
+    let auth = Arc::new(AuthService::new(PathBuf::from("cookie.json")).await?);
+    if auth.try_get_credentials().await?.is_none() {
+        qr_login(&auth).await?;
+    }
+    let api = XhsApiClient::new(XhsClient::new()?, auth.clone());
+
+    // 2. Search
+    let req: SearchNotesRequest = serde_json::from_value(serde_json::json!({ "keyword": keyword }))?;
+    let result = search_notes(&api, req).await?;
+    let items = result.data.map(|d| d.items).unwrap_or_default();
+    println!("搜索 \"{}\": {} 条结果", keyword, items.len());
+
+    // 3. Hydrate details and 4. download images
+    for item in items.iter().filter(|i| i.xsec_token.is_some()).take(max_notes) {
+        let images = get_image_urls(&api, ImagesRequest {
+            note_id: item.id.clone(),
+            xsec_token: item.xsec_token.clone().unwrap_or_default(),
+        }).await?;
+
+        let Some(data) = images.data else {
+            println!("跳过 {}: {}", item.id, images.msg.unwrap_or_default());
+            continue;
+        };
+        println!("\n{} - {} ({} 张图片)", data.title, data.author, data.image_count);
+
+        for image in &data.images {
+            let save_path = format!("./downloads/{}/{}.jpg", data.note_id, image.index);
+            let resp = download_media(DownloadRequest {
+                url: image.url_original.clone(),
+                save_path,
+                proxy: None,
+                ..Default::default()
+            }).await?;
+            match resp.data {
+                Some(saved) => println!("  ✓ {} ({} bytes)", saved.saved_path, saved.file_size),
+                None => println!("  ✗ {}", resp.msg.unwrap_or_default()),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// QR code login through the official web API
+async fn qr_login(auth: &AuthService) -> Result<()> {
+    let guest_cookies = login::fetch_guest_cookies().await?;
+    let created = login::create_qrcode(&guest_cookies).await?;
+    let mut qr = created.data.ok_or_else(|| anyhow!("QR code data missing"))?;
+    print_qr_to_terminal(&qr.url, "请使用小红书 App 扫码登录")?;
+
+    loop {
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        let (resp, new_cookies) = login::check_qrcode_status(&guest_cookies, &qr.qr_id, &qr.code).await?;
+        match resp.state() {
+            QrCodeState::Waiting | QrCodeState::Scanned | QrCodeState::Cancelled => continue,
+            QrCodeState::Expired => {
+                qr = login::create_qrcode(&guest_cookies).await?
+                    .data
+                    .ok_or_else(|| anyhow!("QR code data missing"))?;
+                print_qr_to_terminal(&qr.url, "二维码已过期，请重新扫码")?;
+            }
+            QrCodeState::Confirmed => {
+                let cookies = new_cookies.ok_or_else(|| anyhow!("Login confirmed but no cookies returned"))?;
+                let user_id = resp.data
+                    .and_then(|d| d.login_info)
+                    .and_then(|info| info.user_id)
+                    .unwrap_or_else(|| "unknown".to_string());
+                auth.save_credentials(&UserCredentials::new(user_id.clone(), cookies, None)).await?;
+                println!("✅ 登录成功: {}", user_id);
+                return Ok(());
+            }
+            QrCodeState::Conflict | QrCodeState::Error => {
+                return Err(anyhow!("QR login failed: {:?} {}", resp.state(), resp.msg.unwrap_or_default()));
+            }
+        }
+    }
+}
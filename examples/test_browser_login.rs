@@ -8,6 +8,7 @@
 
 use xhs_rs::auth::AuthService;
 use xhs_rs::utils::print_qr_to_terminal;
+use std::path::PathBuf;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -19,7 +20,7 @@ async fn main() -> anyhow::Result<()> {
     
     // Initialize auth service with MongoDB
     println!("正在连接 MongoDB...");
-    let auth_service = AuthService::new("mongodb://localhost:27017").await?;
+    let auth_service = AuthService::new(PathBuf::from("cookie.json")).await?;
     
     // Get or trigger login
     println!("正在检查凭据...\n");
@@ -28,7 +29,7 @@ async fn main() -> anyhow::Result<()> {
     println!("\n✅ 登录成功!");
     println!("   用户 ID: {}", credentials.user_id);
     println!("   Cookies: {} 个", credentials.cookies.len());
-    println!("   x-s-common: {:.50}...", credentials.x_s_common);
+    println!("   x-s-common: {:?}", credentials.x_s_common);
     
     // Test QR code display
     println!("\n测试终端二维码显示:");
@@ -1,5 +1,6 @@
 use xhs_rs::auth::{CredentialStorage, UserCredentials};
 use std::collections::HashMap;
+use std::path::PathBuf;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -8,7 +9,7 @@ async fn main() -> anyhow::Result<()> {
     println!("Testing MongoDB connection...");
     
     // Connect to MongoDB
-    let storage = CredentialStorage::new("mongodb://localhost:27017").await?;
+    let storage = CredentialStorage::new(PathBuf::from("cookie-test.json")).await?;
     
     println!("✅ MongoDB connected successfully!");
     
@@ -20,7 +21,7 @@ async fn main() -> anyhow::Result<()> {
     let creds = UserCredentials::new(
         "test_user_123".to_string(),
         cookies,
-        "test_x_s_common".to_string(),
+        Some("test_x_s_common".to_string()),
     );
     
     // Save credentials
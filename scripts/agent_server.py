@@ -12,6 +12,7 @@ Usage:
     
 Endpoints:
     POST /sign - Generate signatures for a given request
+    POST /sign/batch - Generate signatures for several requests in one round trip
     GET /guest-cookies - Get guest cookies via UC
     POST /sync-login-cookies - Sync full browser cookies via UC
     GET /health - Health check
@@ -77,9 +78,18 @@ class GuestCookiesResponse(BaseModel):
     error: Optional[str] = None
 
 
-@app.post("/sign", response_model=SignResponse)
-async def generate_signature(request: SignRequest):
-    """Generate XHS API signatures"""
+class BatchSignRequest(BaseModel):
+    """Request model for batch signature generation"""
+    requests: List[SignRequest]
+
+
+class BatchSignResponse(BaseModel):
+    """Response model with one result per request, in request order"""
+    results: List[SignResponse]
+
+
+def sign_request(request: SignRequest) -> SignResponse:
+    """Sign a single request with xhshow"""
     try:
         from urllib.parse import urlparse, parse_qs
         parsed = urlparse(request.uri)
@@ -111,6 +121,18 @@ async def generate_signature(request: SignRequest):
         return SignResponse(success=False, error=str(e))
 
 
+@app.post("/sign", response_model=SignResponse)
+async def generate_signature(request: SignRequest):
+    """Generate XHS API signatures"""
+    return sign_request(request)
+
+
+@app.post("/sign/batch", response_model=BatchSignResponse)
+async def generate_signatures(request: BatchSignRequest):
+    """Generate XHS API signatures for several requests (results keep request order)"""
+    return BatchSignResponse(results=[sign_request(r) for r in request.requests])
+
+
 def get_chrome_options():
     options = uc.ChromeOptions()
     # options.add_argument('--headless=new') # Headless often triggers anti-bot, but might be needed in Docker.
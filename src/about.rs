@@ -0,0 +1,212 @@
+//! 实例信息 (About)
+//!
+//! 启动时打印版本、已启用的 features、存储后端、签名策略与路由清单，
+//! 同样的内容通过 `/api/about` 提供，便于区分多套部署各自的能力。
+//!
+//! 启动横幅由 `XHS_STARTUP_BANNER` 控制：`full`（默认，含路由清单）/ `compact` / `off`
+
+use serde::Serialize;
+use utoipa::{OpenApi, ToSchema};
+
+use crate::openapi::ApiDoc;
+use crate::server::AppState;
+use crate::signature::SignatureService;
+
+/// 未写入 OpenAPI 文档的路由
+const UNDOCUMENTED_ROUTES: &[(&str, &str)] = &[
+    ("GET", "/swagger-ui"),
+    ("GET", "/api-docs/openapi.json"),
+];
+
+/// 已挂载的路由
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RouteInfo {
+    pub method: String,
+    pub path: String,
+}
+
+/// 存储后端
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StorageInfo {
+    /// 凭据存储
+    pub credentials: String,
+    /// 原始响应归档（未启用时为 None）
+    pub archive: Option<String>,
+    /// 上游请求审计日志（未启用时为 None）
+    pub audit: Option<String>,
+    /// 读接口响应缓存（未启用时为 None）
+    pub response_cache: Option<String>,
+    /// 多实例协调队列（单实例模式时为 None）
+    pub coordination: Option<String>,
+    /// 定时发布队列
+    pub publish_schedule: String,
+    /// 定时抓取任务
+    pub crawl_jobs: String,
+    /// 关键词监控
+    pub monitors: String,
+    /// 用户关注列表
+    pub watch_list: String,
+    /// 扫码登录会话
+    pub login_sessions: String,
+}
+
+/// 签名策略
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SigningStrategy {
+    /// algo (Python Agent 纯算法) / stored (浏览器捕获的存储签名) / prewarm (签名预热) / policy (签名来源策略)
+    pub name: String,
+    pub available: bool,
+    pub detail: String,
+}
+
+/// 实例信息
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AboutInfo {
+    pub version: String,
+    /// 当前配置 profile
+    pub profile: String,
+    /// 编译时启用的 cargo features
+    pub features: Vec<String>,
+    pub storage: StorageInfo,
+    pub signing: Vec<SigningStrategy>,
+    pub routes: Vec<RouteInfo>,
+}
+
+impl AboutInfo {
+    /// 收集当前实例信息（会探测一次签名 Agent）
+    pub async fn collect(state: &AppState) -> Self {
+        let agent_available = SignatureService::new().is_agent_available().await;
+        let agent_mode = if crate::config::is_container_mode() { "remote" } else { "local" };
+        let prewarm = crate::config::signature_prewarm_endpoints();
+
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            profile: crate::config::active_profile().to_string(),
+            features: enabled_features(),
+            storage: StorageInfo {
+                credentials: if crate::config::is_memory_storage() {
+                    "memory: cookie.json, cookie-creator.json".to_string()
+                } else {
+                    "json: cookie.json, cookie-creator.json".to_string()
+                },
+                archive: state.api.archive().map(|archive| archive.describe().to_string()),
+                audit: state.api.audit().map(|audit| audit.describe().to_string()),
+                response_cache: state.response_cache.as_ref().map(|cache| cache.describe().to_string()),
+                coordination: state
+                    .coordinator
+                    .as_ref()
+                    .map(|queue| format!("mongo: instance {}", queue.instance_id())),
+                publish_schedule: state.publish_schedule.describe().to_string(),
+                crawl_jobs: state.crawl_jobs.describe().to_string(),
+                monitors: state.monitors.describe().to_string(),
+                watch_list: state.watch_list.describe().to_string(),
+                login_sessions: state.login_sessions.describe().to_string(),
+            },
+            signing: vec![
+                SigningStrategy {
+                    name: "algo".to_string(),
+                    available: agent_available,
+                    detail: format!("{} agent at {}", agent_mode, crate::config::get_agent_url()),
+                },
+                SigningStrategy {
+                    name: "stored".to_string(),
+                    available: true,
+                    detail: "browser-captured signatures (HAR import)".to_string(),
+                },
+                SigningStrategy {
+                    name: "prewarm".to_string(),
+                    available: !prewarm.is_empty(),
+                    detail: if prewarm.is_empty() {
+                        "disabled".to_string()
+                    } else {
+                        format!("{} every {}s", prewarm.join(", "), crate::config::signature_prewarm_interval().as_secs())
+                    },
+                },
+                SigningStrategy {
+                    name: "policy".to_string(),
+                    available: true,
+                    detail: state.api.signature_policy().describe(),
+                },
+            ],
+            routes: routes(),
+        }
+    }
+
+    /// 启动横幅
+    pub fn log_banner(&self) {
+        let mode = std::env::var("XHS_STARTUP_BANNER").unwrap_or_else(|_| "full".to_string());
+        if mode == "off" {
+            return;
+        }
+
+        tracing::info!("==================== xhs-rs {} ====================", self.version);
+        tracing::info!("Profile:      {}", self.profile);
+        tracing::info!("Features:     {}", self.features.join(", "));
+        tracing::info!("Credentials:  {}", self.storage.credentials);
+        tracing::info!("Archive:      {}", self.storage.archive.as_deref().unwrap_or("off"));
+        tracing::info!("Audit log:    {}", self.storage.audit.as_deref().unwrap_or("off"));
+        tracing::info!("Cache:        {}", self.storage.response_cache.as_deref().unwrap_or("off"));
+        tracing::info!("Coordination: {}", self.storage.coordination.as_deref().unwrap_or("standalone"));
+        tracing::info!("Schedule:     {}", self.storage.publish_schedule);
+        tracing::info!("Crawl jobs:   {}", self.storage.crawl_jobs);
+        tracing::info!("Monitors:     {}", self.storage.monitors);
+        tracing::info!("Watch list:   {}", self.storage.watch_list);
+        tracing::info!("Login:        {}", self.storage.login_sessions);
+        for strategy in &self.signing {
+            tracing::info!(
+                "Signing:      {} ({}) - {}",
+                strategy.name,
+                if strategy.available { "available" } else { "unavailable" },
+                strategy.detail
+            );
+        }
+        if mode == "compact" {
+            tracing::info!("Routes:       {} mounted (see /api/about)", self.routes.len());
+            return;
+        }
+        tracing::info!("Routes ({}):", self.routes.len());
+        for route in &self.routes {
+            tracing::info!("  {:<6} {}", route.method, route.path);
+        }
+    }
+}
+
+fn enabled_features() -> Vec<String> {
+    [
+        ("server", cfg!(feature = "server")),
+        ("swagger", cfg!(feature = "swagger")),
+        ("mongodb", cfg!(feature = "mongodb")),
+        ("qrcode", cfg!(feature = "qrcode")),
+        ("agent-manager", cfg!(feature = "agent-manager")),
+        ("strict-parse", cfg!(feature = "strict-parse")),
+    ]
+    .into_iter()
+    .filter(|(_, enabled)| *enabled)
+    .map(|(name, _)| name.to_string())
+    .collect()
+}
+
+/// 路由清单（来自 OpenAPI 文档，axum Router 不提供路由枚举）
+fn routes() -> Vec<RouteInfo> {
+    let mut routes: Vec<RouteInfo> = UNDOCUMENTED_ROUTES
+        .iter()
+        .map(|(method, path)| RouteInfo { method: method.to_string(), path: path.to_string() })
+        .collect();
+
+    for (path, item) in ApiDoc::openapi().paths.paths {
+        let methods = [
+            ("GET", item.get.is_some()),
+            ("POST", item.post.is_some()),
+            ("PUT", item.put.is_some()),
+            ("DELETE", item.delete.is_some()),
+            ("PATCH", item.patch.is_some()),
+        ];
+        for (method, present) in methods {
+            if present {
+                routes.push(RouteInfo { method: method.to_string(), path: path.clone() });
+            }
+        }
+    }
+    routes.sort_by(|a, b| a.path.cmp(&b.path).then_with(|| a.method.cmp(&b.method)));
+    routes
+}
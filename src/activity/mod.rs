@@ -0,0 +1,301 @@
+//! 账号行为画像 (Activity Profiles)
+//!
+//! 为每个账号定义"像真人"的流量约束：活跃时段、请求类型配比、每日上限。
+//! 每个出站请求经 `ActivityGovernor::admit` 检查；关键词监控、采集任务、用户关注与定时发布的
+//! 后台调度器在主账号活跃时段外跳过本轮，到期任务留到活跃时段再执行。配置文件修改后随配置热加载生效。
+//!
+//! 配置文件 (XHS_ACTIVITY_PROFILES，默认 activity_profiles.json，不存在时不做限制):
+//! ```json
+//! {
+//!   "profiles": {
+//!     "casual": {
+//!       "active_hours": [{"start": 8, "end": 12}, {"start": 19, "end": 24}],
+//!       "daily_cap": 400,
+//!       "category_caps": {"search": 60},
+//!       "request_mix": {"feed": 0.5, "note": 0.3, "search": 0.15, "user": 0.05}
+//!     }
+//!   },
+//!   "accounts": {"user": "casual", "5ff0e6410000000001008400": "night_owl"}
+//! }
+//! ```
+//!
+//! 用量按账号 (用户 ID) 分别计数；`accounts` 中没有单独绑定的账号使用 `"user"` 的画像。
+
+use anyhow::{anyhow, Result};
+use chrono::{Local, NaiveDate, Timelike};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Mutex, RwLock};
+use utoipa::ToSchema;
+
+use crate::error::XhsError;
+
+/// 配比检查的最小样本量，避免当日前几个请求就触发配比限制
+const MIX_MIN_SAMPLES: u32 = 20;
+
+/// 请求类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RequestCategory {
+    Feed,
+    Search,
+    Note,
+    User,
+    Notification,
+    Other,
+}
+
+impl RequestCategory {
+    /// 根据 endpoint key 或 URI 归类
+    pub fn classify(endpoint: &str) -> Self {
+        if endpoint.contains("homefeed") || endpoint.starts_with("home_feed") || endpoint.contains("/page/notes") {
+            RequestCategory::Feed
+        } else if endpoint.contains("search") {
+            RequestCategory::Search
+        } else if endpoint.contains("/feed") || endpoint.starts_with("note") {
+            RequestCategory::Note
+        } else if endpoint.contains("/you/") || endpoint.starts_with("notification") {
+            RequestCategory::Notification
+        } else if endpoint.contains("user") {
+            RequestCategory::User
+        } else {
+            RequestCategory::Other
+        }
+    }
+}
+
+/// 活跃时段 [start, end)，本地时间小时
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ActiveWindow {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl ActiveWindow {
+    fn contains(&self, hour: u32) -> bool {
+        if self.start <= self.end {
+            hour >= self.start && hour < self.end
+        } else {
+            // 跨零点，例如 22 -> 2
+            hour >= self.start || hour < self.end
+        }
+    }
+}
+
+/// 账号行为画像
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ActivityProfile {
+    /// 活跃时段（为空表示全天）
+    #[serde(default)]
+    pub active_hours: Vec<ActiveWindow>,
+    /// 每日请求总上限
+    #[serde(default)]
+    pub daily_cap: Option<u32>,
+    /// 各类型每日上限
+    #[serde(default)]
+    pub category_caps: HashMap<RequestCategory, u32>,
+    /// 目标请求配比 (0~1)，未列出的类型不受配比约束
+    #[serde(default)]
+    pub request_mix: HashMap<RequestCategory, f64>,
+    /// 配比允许的偏差
+    #[serde(default = "default_mix_tolerance")]
+    pub mix_tolerance: f64,
+}
+
+fn default_mix_tolerance() -> f64 { 0.15 }
+
+impl ActivityProfile {
+    pub fn is_active_at(&self, hour: u32) -> bool {
+        self.active_hours.is_empty() || self.active_hours.iter().any(|w| w.contains(hour))
+    }
+}
+
+/// 配置文件结构
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ActivityConfig {
+    #[serde(default)]
+    pub profiles: HashMap<String, ActivityProfile>,
+    /// 用户 ID -> 画像名；`"user"` 为未单独绑定账号的默认画像
+    #[serde(default)]
+    pub accounts: HashMap<String, String>,
+}
+
+/// 画像文件路径（XHS_ACTIVITY_PROFILES，默认 activity_profiles.json）
+pub fn profiles_path() -> String {
+    std::env::var("XHS_ACTIVITY_PROFILES").unwrap_or_else(|_| "activity_profiles.json".to_string())
+}
+
+impl ActivityConfig {
+    /// 读取画像文件（文件不存在时为空配置）
+    pub fn from_env() -> Result<Self> {
+        let path = profiles_path();
+        if !Path::new(&path).exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)?;
+        serde_json::from_str(&content).map_err(|e| anyhow!("Invalid activity profiles {}: {}", path, e))
+    }
+}
+
+/// 当日用量
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ActivityUsage {
+    pub account: String,
+    pub profile: Option<String>,
+    pub active_now: bool,
+    pub total: u32,
+    pub daily_cap: Option<u32>,
+    pub by_category: HashMap<RequestCategory, u32>,
+}
+
+struct DailyCounter {
+    date: NaiveDate,
+    total: u32,
+    by_category: HashMap<RequestCategory, u32>,
+}
+
+impl DailyCounter {
+    fn today() -> Self {
+        Self { date: Local::now().date_naive(), total: 0, by_category: HashMap::new() }
+    }
+
+    fn rollover(&mut self) {
+        if self.date != Local::now().date_naive() {
+            *self = Self::today();
+        }
+    }
+}
+
+/// 未单独绑定画像的账号使用的默认绑定
+pub const DEFAULT_ACCOUNT: &str = "user";
+
+/// 行为画像执行器
+pub struct ActivityGovernor {
+    config: RwLock<ActivityConfig>,
+    usage: Mutex<HashMap<String, DailyCounter>>,
+}
+
+impl Default for ActivityGovernor {
+    fn default() -> Self {
+        Self::new(ActivityConfig::default())
+    }
+}
+
+impl ActivityGovernor {
+    pub fn new(config: ActivityConfig) -> Self {
+        Self { config: RwLock::new(config), usage: Mutex::new(HashMap::new()) }
+    }
+
+    /// 从 XHS_ACTIVITY_PROFILES 指定的文件加载（文件不存在时不做限制）
+    pub fn from_env() -> Result<Self> {
+        let config = ActivityConfig::from_env()?;
+        if !config.profiles.is_empty() {
+            tracing::info!("[Activity] Loaded {} profile(s) from {}", config.profiles.len(), profiles_path());
+        }
+        Ok(Self::new(config))
+    }
+
+    /// 替换配置（用于热更新），当日用量保留
+    pub fn reload(&self, config: ActivityConfig) {
+        *self.config.write().unwrap() = config;
+    }
+
+    /// 重新读取画像文件，返回配置是否有变化；文件无效时保留当前配置
+    pub fn reload_from_env(&self) -> Result<bool> {
+        let config = ActivityConfig::from_env()?;
+        if *self.config.read().unwrap() == config {
+            return Ok(false);
+        }
+        self.reload(config);
+        Ok(true)
+    }
+
+    /// 账号绑定的画像（没有单独绑定时取 [`DEFAULT_ACCOUNT`] 的画像）
+    pub fn profile_for(&self, account: &str) -> Option<(String, ActivityProfile)> {
+        let config = self.config.read().unwrap();
+        let name = config.accounts.get(account).or_else(|| config.accounts.get(DEFAULT_ACCOUNT))?;
+        config.profiles.get(name).map(|p| (name.clone(), p.clone()))
+    }
+
+    /// 当前是否处于活跃时段
+    pub fn is_active_now(&self, account: &str) -> bool {
+        self.profile_for(account)
+            .is_none_or(|(_, p)| p.is_active_at(Local::now().hour()))
+    }
+
+    /// 检查并记录一次请求，超出画像约束时返回错误
+    pub fn admit(&self, account: &str, category: RequestCategory) -> Result<()> {
+        let Some((name, profile)) = self.profile_for(account) else {
+            return Ok(());
+        };
+
+        if !profile.is_active_at(Local::now().hour()) {
+            return Err(XhsError::Throttled(format!("Account '{}' is outside active hours of profile '{}'", account, name)).into());
+        }
+
+        let mut usage = self.usage.lock().unwrap();
+        let counter = usage.entry(account.to_string()).or_insert_with(DailyCounter::today);
+        counter.rollover();
+
+        if profile.daily_cap.is_some_and(|cap| counter.total >= cap) {
+            return Err(XhsError::Throttled(format!("Account '{}' reached daily cap of profile '{}'", account, name)).into());
+        }
+        let used = counter.by_category.get(&category).copied().unwrap_or(0);
+        if profile.category_caps.get(&category).is_some_and(|cap| used >= *cap) {
+            return Err(XhsError::Throttled(format!("Account '{}' reached daily {:?} cap of profile '{}'", account, category, name)).into());
+        }
+        if let Some(ratio) = profile.request_mix.get(&category) {
+            let share = (used + 1) as f64 / (counter.total + 1) as f64;
+            if counter.total >= MIX_MIN_SAMPLES && share > ratio + profile.mix_tolerance {
+                return Err(XhsError::Throttled(format!(
+                    "Account '{}' {:?} share {:.2} exceeds mix {:.2} of profile '{}'",
+                    account, category, share, ratio, name
+                )).into());
+            }
+        }
+
+        counter.total += 1;
+        *counter.by_category.entry(category).or_insert(0) += 1;
+        Ok(())
+    }
+
+    /// 距目标配比最欠缺的请求类型（在 `/api/activity/status` 中给出，供调用方挑选下一步动作）
+    pub fn next_category(&self, account: &str) -> Option<RequestCategory> {
+        let (_, profile) = self.profile_for(account)?;
+        let mut usage = self.usage.lock().unwrap();
+        let counter = usage.entry(account.to_string()).or_insert_with(DailyCounter::today);
+        counter.rollover();
+
+        let total = counter.total.max(1) as f64;
+        profile.request_mix.iter()
+            .filter(|(category, _)| {
+                let used = counter.by_category.get(category).copied().unwrap_or(0);
+                profile.category_caps.get(category).is_none_or(|cap| used < *cap)
+            })
+            .map(|(category, ratio)| {
+                let share = counter.by_category.get(category).copied().unwrap_or(0) as f64 / total;
+                (*category, ratio - share)
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(category, _)| category)
+    }
+
+    /// 当日用量快照
+    pub fn usage(&self, account: &str) -> ActivityUsage {
+        let profile = self.profile_for(account);
+        let active_now = self.is_active_now(account);
+        let mut usage = self.usage.lock().unwrap();
+        let counter = usage.entry(account.to_string()).or_insert_with(DailyCounter::today);
+        counter.rollover();
+
+        ActivityUsage {
+            account: account.to_string(),
+            daily_cap: profile.as_ref().and_then(|(_, p)| p.daily_cap),
+            profile: profile.map(|(name, _)| name),
+            active_now,
+            total: counter.total,
+            by_category: counter.by_category.clone(),
+        }
+    }
+}
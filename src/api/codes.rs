@@ -0,0 +1,59 @@
+//! 业务码解析 (Business Codes)
+//!
+//! 小红书很多失败以 HTTP 200 + `{"success": false, "code": ..., "msg": ...}` 返回。
+//! `XhsApiClient::handle_response` 对所有接口统一调用 `decode`，把已知业务码映射为
+//! 对应的 `XhsError`，各接口无需再自行检查 `success` / `code`。
+//!
+//! | code | 含义 | 映射 |
+//! | :--- | :--- | :--- |
+//! | -100 / -101 | 登录已过期 / 无登录信息 | `LoginExpired`（使用存储凭据时自动失效） |
+//! | 300011 | 账号异常 | `AccountRestricted` |
+//! | 300012 / 300013 | IP 存在风险 / 访问频次异常 | `RiskControl461` |
+//! | 300015 | 浏览器环境异常（签名被识别） | `InvalidSignature406` |
+//! | -510000 / -510001 | 笔记状态异常 / 笔记不存在或不可见 | `ContentUnavailable` |
+//! | 其他 | | `Business { code, msg }` |
+
+use serde::Deserialize;
+
+use crate::error::XhsError;
+
+/// 成功码（部分接口 0，部分接口 1000）
+const SUCCESS_CODES: [i64; 2] = [0, 1000];
+
+#[derive(Deserialize)]
+struct Envelope {
+    #[serde(default)]
+    success: Option<bool>,
+    #[serde(default)]
+    code: Option<serde_json::Value>,
+    #[serde(default)]
+    msg: Option<String>,
+}
+
+/// 解析响应信封，业务失败时返回对应错误；成功或非 JSON 响应返回 None
+pub fn decode(text: &str) -> Option<XhsError> {
+    if !text.trim_start().starts_with('{') {
+        return None;
+    }
+    let envelope: Envelope = serde_json::from_str(text).ok()?;
+    let code = envelope.code.as_ref().and_then(|c| c.as_i64().or_else(|| c.as_str()?.parse().ok()));
+    let failed = match (envelope.success, code) {
+        (Some(success), _) => !success,
+        (None, Some(code)) => !SUCCESS_CODES.contains(&code),
+        (None, None) => false,
+    };
+    if !failed {
+        return None;
+    }
+
+    let msg = envelope.msg.unwrap_or_default();
+    let code = code.unwrap_or(-1);
+    Some(match code {
+        -100 | -101 => XhsError::LoginExpired(format!("{} ({})", msg, code)),
+        300011 => XhsError::AccountRestricted(format!("{} ({})", msg, code)),
+        300012 | 300013 => XhsError::RiskControl461 { body: text.to_string() },
+        300015 => XhsError::InvalidSignature406 { body: text.to_string() },
+        -510000 | -510001 => XhsError::ContentUnavailable { code, msg },
+        _ => XhsError::Business { code, msg },
+    })
+}
@@ -9,17 +9,40 @@
 //! ## 签名策略 (Signature Strategy)
 //! 1. **纯算法优先**: 调用 Python Agent 生成签名 (xhshow)
 //! 2. **浏览器兜底**: 若 Agent 不可用，回退到存储的签名
+//!
+//! 顺序可按接口配置（`signature::policy`），所有请求方法统一经 `send_signed` 应用。
 
-use crate::auth::AuthService;
+use crate::activity::{ActivityGovernor, RequestCategory};
+use crate::api::codes;
+use crate::api::dedup::PostDedup;
+use crate::api::dry_run::{self, PreparedRequest};
+use crate::api::guest::GuestBrowsing;
+use crate::api::maintenance::{self, MaintenanceGate};
+use crate::api::pacing::{Decoy, StealthPacer};
+use crate::api::rate_limit::RateLimiter;
+use crate::api::raw;
+use crate::api::retry::RetryPolicy;
+use crate::api::risk::RiskBreaker;
+use crate::api::rotation::AccountPool;
+use crate::api::vcr;
+use crate::api::verification::{VerificationChallenge, VerificationGate};
+use crate::archive::ResponseArchive;
+use crate::audit::{AuditLog, AuditRecord, SignatureMode};
+use crate::auth::{AuthService, BrowserFingerprint, UserCredentials};
 use crate::auth::credentials::ApiSignature;
 use crate::client::XhsClient;
+use crate::models::feed::HomefeedRequest;
+use crate::models::feed::tuning::REFRESH_TYPE_INITIAL;
 use crate::signature::{SignatureService, Signature, parse_cookie_string};
-use anyhow::{Result, anyhow};
-use std::sync::Arc;
+use crate::signature::metrics::SIGNATURE_METRICS;
+use crate::signature::policy::{SignaturePolicies, SignaturePolicy};
+use crate::signature::prewarm::SignatureCache;
+use crate::telemetry;
+use crate::error::{Result, XhsError};
+use std::sync::{Arc, RwLock};
 
 const ORIGIN: &str = "https://www.xiaohongshu.com";
 const REFERER: &str = "https://www.xiaohongshu.com/";
-const USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/143.0.0.0 Safari/537.36";
 
 /// Endpoint Key 到 API URI 的映射
 /// 用于纯算法签名生成
@@ -69,29 +92,326 @@ fn parse_uri_with_params(uri: &str) -> (&str, Vec<(&str, &str)>) {
     }
 }
 
+/// 签名 API 的域名
+const API_BASE: &str = "https://edith.xiaohongshu.com";
+
+/// URL 中 edith.xiaohongshu.com 之后的 URI（path + query），其他域名为 None
+fn api_uri(url: &str) -> Option<&str> {
+    let idx = url.find("edith.xiaohongshu.com")?;
+    let start = url[idx..].find('/').map(|i| idx + i).unwrap_or(url.len());
+    Some(&url[start..])
+}
+
+/// 以 URI 调用的接口对应的存储签名 key（HAR 导入时的命名，没有对应 key 时为 path）
+fn stored_key(uri: &str, body: Option<&str>) -> String {
+    let path = uri.split('?').next().unwrap_or(uri);
+    crate::auth::har::endpoint_key(path, body).unwrap_or_else(|| path.to_string())
+}
+
+/// 纯算法签名的目标
+struct AlgoTarget {
+    /// 参与签名的 URI（path + query）
+    uri: String,
+    url: String,
+    /// 以 `.query()` 传递的参数（此时 url 不含查询串）
+    query: Vec<(String, String)>,
+}
+
+impl AlgoTarget {
+    /// 查询参数直接包含在 URL 中
+    fn whole(uri: &str) -> Self {
+        Self { uri: uri.to_string(), url: format!("{}{}", API_BASE, uri), query: Vec::new() }
+    }
+
+    /// 查询参数从 URI 中分离，以 `.query()` 传递
+    fn split(uri: &str) -> Self {
+        let (path, params) = parse_uri_with_params(uri);
+        Self {
+            uri: uri.to_string(),
+            url: format!("{}{}", API_BASE, path),
+            query: params.into_iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+        }
+    }
+}
+
+/// 一次签名请求：各签名方式按需取用
+struct SignTarget {
+    method: &'static str,
+    /// 日志、签名策略与熔断使用的接口标识（endpoint key 或 URI）
+    endpoint: String,
+    /// 存储签名的 key
+    stored_key: String,
+    /// None 时不能使用纯算法签名（没有 URI 映射）
+    algo: Option<AlgoTarget>,
+    /// 存储签名使用的 URL；None 时使用签名录制时的 URL
+    url: Option<String>,
+    /// POST 的 JSON 请求体（纯算法签名需要）
+    payload: Option<serde_json::Value>,
+    /// POST 请求体；None 时纯算法使用 payload、存储签名使用录制的请求体
+    body: Option<String>,
+}
+
+impl SignTarget {
+    fn get(endpoint: &str, stored_key: &str, algo: Option<AlgoTarget>, url: Option<String>) -> Self {
+        Self {
+            method: "GET",
+            endpoint: endpoint.to_string(),
+            stored_key: stored_key.to_string(),
+            algo,
+            url,
+            payload: None,
+            body: None,
+        }
+    }
+
+    fn post(
+        endpoint: &str,
+        stored_key: &str,
+        algo: Option<AlgoTarget>,
+        url: Option<String>,
+        payload: Option<serde_json::Value>,
+        body: Option<String>,
+    ) -> Self {
+        Self { method: "POST", payload, body, ..Self::get(endpoint, stored_key, algo, url) }
+    }
+}
+
 /// XHS API 公共客户端
 /// 
 /// 封装了所有 API 请求的公共逻辑：
-/// - 从 AuthService 获取 Cookie
-/// - 优先使用 SignatureService (纯算法) 生成签名
-/// - 回退到存储的签名 (浏览器捕获)
+/// - 从 AuthService 获取 Cookie（配置了 `AccountPool` 时只读接口在多个账号间轮换；
+///   没有凭据时，只读接口可经 `GuestBrowsing` 以访客身份请求）
+/// - 按 `SignaturePolicies` 使用 SignatureService (纯算法) 或存储的签名 (浏览器捕获)，默认纯算法优先
 /// - 构建标准浏览器 Headers
+/// - 发出请求前经 `RiskBreaker` 熔断检查与 `RateLimiter` 令牌桶限流（拟人模式下再经 `StealthPacer` 停顿）
+/// - 瞬时故障按 `RetryPolicy` 自动退避重试
+/// - 收到验证码挑战后经 `VerificationGate` 暂停该账号的请求，直到 Cookie 更新
 pub struct XhsApiClient {
-    http_client: XhsClient,
+    /// 代理配置热加载时整体替换
+    http_client: RwLock<XhsClient>,
     auth: Arc<AuthService>,
     signature_service: SignatureService,
+    activity: Arc<ActivityGovernor>,
+    dedup: PostDedup,
+    archive: Option<Arc<ResponseArchive>>,
+    audit: Option<Arc<AuditLog>>,
+    maintenance: Arc<MaintenanceGate>,
+    retry: RetryPolicy,
+    rate_limiter: RateLimiter,
+    risk: RiskBreaker,
+    pacer: StealthPacer,
+    guest: GuestBrowsing,
+    pool: Option<Arc<AccountPool>>,
+    verification: VerificationGate,
+    signature_policy: Arc<SignaturePolicies>,
+    signature_cache: SignatureCache,
 }
 
 impl XhsApiClient {
     /// 创建新的 API 客户端
     pub fn new(http_client: XhsClient, auth: Arc<AuthService>) -> Self {
         Self { 
-            http_client, 
+            http_client: RwLock::new(http_client), 
             auth,
             signature_service: SignatureService::new(),
+            activity: Arc::new(ActivityGovernor::default()),
+            dedup: PostDedup::default(),
+            archive: None,
+            audit: None,
+            maintenance: Arc::new(MaintenanceGate::default()),
+            retry: RetryPolicy::default(),
+            rate_limiter: RateLimiter::default(),
+            risk: RiskBreaker::default(),
+            pacer: StealthPacer::default(),
+            guest: GuestBrowsing::default(),
+            pool: None,
+            verification: VerificationGate::new(),
+            signature_policy: Arc::new(SignaturePolicies::default()),
+            signature_cache: SignatureCache::default(),
         }
     }
 
+    /// 当前的 HTTP 客户端
+    fn http(&self) -> reqwest::Client {
+        self.http_client.read().unwrap_or_else(|e| e.into_inner()).get_client().clone()
+    }
+
+    /// 是否在回放录制的响应（见 `api::vcr`）
+    fn is_replaying(&self) -> bool {
+        self.http_client.read().unwrap_or_else(|e| e.into_inner()).cassette().is_some_and(|c| c.is_replaying())
+    }
+
+    /// 按 XHS_PROXY / XHS_NO_PROXY 重建 HTTP 客户端（配置热加载），Cookie 保留
+    pub fn reload_proxy(&self) -> Result<()> {
+        let client = self.http_client.read().unwrap_or_else(|e| e.into_inner()).reconnect()?;
+        *self.http_client.write().unwrap_or_else(|e| e.into_inner()) = client;
+        Ok(())
+    }
+
+    /// 绑定账号行为画像（活跃时段 / 每日上限 / 请求配比）
+    pub fn with_activity(mut self, activity: Arc<ActivityGovernor>) -> Self {
+        self.activity = activity;
+        self
+    }
+
+    /// 设置重试策略（默认取自 XHS_RETRY_* 环境变量）
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// 获取重试策略
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry
+    }
+
+    /// 设置出站限流器（默认取自 XHS_RATE_LIMIT_* 环境变量）
+    pub fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
+    /// 获取出站限流器
+    pub fn rate_limiter(&self) -> &RateLimiter {
+        &self.rate_limiter
+    }
+
+    /// 设置拟人节奏模式（默认取自 XHS_STEALTH_* 环境变量）
+    pub fn with_pacer(mut self, pacer: StealthPacer) -> Self {
+        self.pacer = pacer;
+        self
+    }
+
+    /// 获取拟人节奏控制器
+    pub fn pacer(&self) -> &StealthPacer {
+        &self.pacer
+    }
+
+    /// 设置访客浏览模式（未登录时只读接口使用访客凭据，默认取自 XHS_GUEST_BROWSING）
+    pub fn with_guest_browsing(mut self, guest: GuestBrowsing) -> Self {
+        self.guest = guest;
+        self
+    }
+
+    /// 获取访客浏览模式
+    pub fn guest_browsing(&self) -> &GuestBrowsing {
+        &self.guest
+    }
+
+    /// 启用多账号轮换（只读接口在账号池中按策略选择账号）
+    pub fn with_account_pool(mut self, pool: Arc<AccountPool>) -> Self {
+        self.pool = Some(pool);
+        self
+    }
+
+    /// 获取账号池（未启用时为 None）
+    pub fn account_pool(&self) -> Option<&Arc<AccountPool>> {
+        self.pool.as_ref()
+    }
+
+    /// 试运行一次调用：照常签名并构建请求，但不发送，返回本应发出的请求（见 `api::dry_run`）
+    pub async fn dry_run<T>(&self, call: impl std::future::Future<Output = Result<T>>) -> Result<PreparedRequest> {
+        dry_run::capture(call).await
+    }
+
+    /// 设置签名来源策略（默认纯算法优先、存储签名兜底）
+    pub fn with_signature_policy(mut self, policy: Arc<SignaturePolicies>) -> Self {
+        self.signature_policy = policy;
+        self
+    }
+
+    /// 获取签名来源策略
+    pub fn signature_policy(&self) -> &Arc<SignaturePolicies> {
+        &self.signature_policy
+    }
+
+    /// 获取验证码闸门
+    pub fn verification(&self) -> &VerificationGate {
+        &self.verification
+    }
+
+    /// 获取 461 风控熔断器
+    pub fn risk(&self) -> &RiskBreaker {
+        &self.risk
+    }
+
+    /// 获取行为画像执行器
+    pub fn activity(&self) -> &Arc<ActivityGovernor> {
+        &self.activity
+    }
+
+    /// 主账号当前是否处于画像的活跃时段（未登录时按默认画像判断），后台调度器据此跳过本轮
+    pub async fn is_active_now(&self) -> bool {
+        let account = match self.auth.try_get_credentials().await {
+            Ok(Some(credentials)) => credentials.user_id,
+            _ => crate::activity::DEFAULT_ACCOUNT.to_string(),
+        };
+        self.activity.is_active_now(&account)
+    }
+
+    /// 启用原始响应归档（成功响应压缩保存）
+    pub fn with_archive(mut self, archive: Arc<ResponseArchive>) -> Self {
+        self.archive = Some(archive);
+        self
+    }
+
+    /// 获取原始响应归档（未启用时为 None）
+    pub fn archive(&self) -> Option<&Arc<ResponseArchive>> {
+        self.archive.as_ref()
+    }
+
+    /// 启用上游请求审计日志
+    pub fn with_audit(mut self, audit: Arc<AuditLog>) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
+    /// 获取审计日志（未启用时为 None）
+    pub fn audit(&self) -> Option<&Arc<AuditLog>> {
+        self.audit.as_ref()
+    }
+
+    /// 共享上游维护闸门（多个客户端共用同一个暂停状态与探针）
+    pub fn with_maintenance(mut self, maintenance: Arc<MaintenanceGate>) -> Self {
+        self.maintenance = maintenance;
+        self
+    }
+
+    /// 获取上游维护闸门
+    pub fn maintenance(&self) -> &Arc<MaintenanceGate> {
+        &self.maintenance
+    }
+
+    /// 维护探针：绕过维护闸门发送一次真实请求，确认上游已恢复
+    ///
+    /// 已登录时请求 user/me；未登录时请求主页
+    pub async fn probe_upstream(&self) -> Result<()> {
+        if let Some(credentials) = self.auth.try_get_credentials().await? {
+            self.get_signed("user_me", &credentials, true).await?;
+            return Ok(());
+        }
+
+        let response = self.http()
+            .get(REFERER)
+            .header("user-agent", BrowserFingerprint::default().user_agent)
+            .send()
+            .await?;
+        let status = response.status().as_u16();
+        let text = response.text().await?;
+        if let Some(reason) = maintenance::detect(status, &text) {
+            return Err(XhsError::Maintenance(reason));
+        }
+        if status >= 400 {
+            return Err(XhsError::Upstream { status, body: text });
+        }
+        Ok(())
+    }
+
+    /// 获取 POST 去重缓存
+    pub fn dedup(&self) -> &PostDedup {
+        &self.dedup
+    }
+
     /// 获取认证服务引用
     pub fn auth(&self) -> &Arc<AuthService> {
         &self.auth
@@ -107,50 +427,39 @@ impl XhsApiClient {
     /// # Returns
     /// 响应文本内容
     pub async fn get(&self, endpoint_key: &str) -> Result<String> {
-        let credentials = self.auth.try_get_credentials().await?
-            .ok_or_else(|| anyhow!("Not logged in. Please call /api/auth/login-session first."))?;
-        
-        let cookie_str = credentials.cookie_string();
-        
-        // 优先尝试纯算法签名
-        if let Some(uri) = endpoint_to_uri(endpoint_key) {
-            // 解析 URI，分离 path 和 query params
-            let (path, params) = parse_uri_with_params(uri);
-            let base_url = format!("https://edith.xiaohongshu.com{}", path);
-            
-            match self.get_algo_signature("GET", uri, &cookie_str, None).await {
-                Ok(signature) => {
-                    tracing::info!("[XhsApiClient] GET {} using ALGO (path: {}, params: {:?})", endpoint_key, path, params);
-                    // 使用 .query() 传递参数，而不是直接拼在 URL 中
-                    let response = self.build_get_request_algo(&base_url, &signature, &cookie_str)
-                        .query(&params)
-                        .send()
-                        .await?;
-                    return self.handle_response(response, endpoint_key).await;
-                }
-                Err(algo_err) => {
-                    tracing::warn!("[XhsApiClient] Algo failed for {}: {}, trying stored signature", endpoint_key, algo_err);
-                }
-            }
-        }
-        
-        // 回退到存储的签名
-        let signature = self.get_signature(endpoint_key).await?;
-        let url = signature.request_url.clone()
-            .ok_or_else(|| anyhow!("No request_url found for endpoint: {}", endpoint_key))?;
-        
-        tracing::info!("[XhsApiClient] GET {} using STORED signature", endpoint_key);
-        
-        let response = self.build_get_request(&url, &signature, &cookie_str)
-            .send()
-            .await?;
+        let span = telemetry::request_span("GET", endpoint_key);
+        telemetry::traced(span, self.retry.run(endpoint_key, || self.get_once(endpoint_key))).await
+    }
+
+    async fn get_once(&self, endpoint_key: &str) -> Result<String> {
+        let credentials = self.admit_credentials(endpoint_key).await?;
         
-        self.handle_response(response, endpoint_key).await
+        self.get_signed(endpoint_key, &credentials, true).await
     }
 
-    /// 执行 GET 请求（纯算法签名优先）
+    /// 使用指定凭据执行 GET 请求（纯算法优先 + 存储回退）
     /// 
-    /// 优先使用 Python Agent 生成签名，失败时回退到存储的签名
+    /// 与 `get` 相同，但不读取 AuthService 中的当前凭据，
+    /// 用于在保存之前校验外部导入的 Cookie
+    /// 
+    /// # Arguments
+    /// * `endpoint_key` - 签名存储的 key（如 "user_me"）
+    /// * `credentials` - 用于本次请求的凭据
+    pub async fn get_with_credentials(&self, endpoint_key: &str, credentials: &UserCredentials) -> Result<String> {
+        let span = telemetry::request_span("GET", endpoint_key);
+        telemetry::traced(span, self.retry.run(endpoint_key, || async {
+            self.rate_limiter.acquire(endpoint_key).await;
+            self.get_signed(endpoint_key, credentials, false).await
+        })).await
+    }
+
+    /// GET 请求实现；`stored` 表示凭据来自 AuthService（登录过期时自动失效）
+    async fn get_signed(&self, endpoint_key: &str, credentials: &UserCredentials, stored: bool) -> Result<String> {
+        let target = SignTarget::get(endpoint_key, endpoint_key, endpoint_to_uri(endpoint_key).map(AlgoTarget::split), None);
+        self.send_signed(&target, credentials, stored).await
+    }
+
+    /// 执行 GET 请求（按签名来源策略签名）
     /// 
     /// # Arguments
     /// * `uri` - API 路径（如 "/api/sns/web/v1/user/selfinfo"）
@@ -158,32 +467,18 @@ impl XhsApiClient {
     /// # Returns
     /// 响应文本内容
     pub async fn get_algo(&self, uri: &str) -> Result<String> {
-        let credentials = self.auth.try_get_credentials().await?
-            .ok_or_else(|| anyhow!("Not logged in. Please call /api/auth/login-session first."))?;
-        
-        let cookie_str = credentials.cookie_string();
-        let url = format!("https://edith.xiaohongshu.com{}", uri);
-        
-        // 尝试纯算法签名
-        match self.get_algo_signature("GET", uri, &cookie_str, None).await {
-            Ok(signature) => {
-                tracing::info!("[XhsApiClient] GET {} using ALGO signature", uri);
-                let response = self.build_get_request_algo(&url, &signature, &cookie_str)
-                    .send()
-                    .await?;
-                self.handle_response(response, uri).await
-            }
-            Err(algo_err) => {
-                // 算法失败，记录警告并回退
-                tracing::warn!("[XhsApiClient] Algo failed for {}: {}, falling back to stored signature", uri, algo_err);
-                // 这里需要 endpoint_key 来查找存储的签名，但我们没有
-                // 对于纯算法路径，失败即失败
-                Err(algo_err)
-            }
-        }
+        let span = telemetry::request_span("GET", uri);
+        telemetry::traced(span, self.retry.run(uri, || self.get_algo_once(uri))).await
     }
 
-    /// 执行带动态查询参数的 GET 请求（纯算法签名）
+    async fn get_algo_once(&self, uri: &str) -> Result<String> {
+        let credentials = self.admit_credentials(uri).await?;
+        let url = format!("{}{}", API_BASE, uri);
+        let target = SignTarget::get(uri, &stored_key(uri, None), Some(AlgoTarget::whole(uri)), Some(url));
+        self.send_signed(&target, &credentials, true).await
+    }
+
+    /// 执行带动态查询参数的 GET 请求（按签名来源策略签名）
     /// 
     /// 用于需要动态构造查询参数的接口（如 notification）
     /// 使用与 get 方法相同的 path/params 分离逻辑
@@ -194,133 +489,56 @@ impl XhsApiClient {
     /// # Returns
     /// 响应文本内容
     pub async fn get_with_query(&self, uri: &str) -> Result<String> {
-        let credentials = self.auth.try_get_credentials().await?
-            .ok_or_else(|| anyhow!("Not logged in. Please call /api/auth/login-session first."))?;
-        
-        let cookie_str = credentials.cookie_string();
-        
-        // 解析 URI，分离 path 和 query params（与 get 方法相同逻辑）
-        let (path, params) = parse_uri_with_params(uri);
-        let base_url = format!("https://edith.xiaohongshu.com{}", path);
-        
-        // 尝试纯算法签名
-        match self.get_algo_signature("GET", uri, &cookie_str, None).await {
-            Ok(signature) => {
-                tracing::info!("[XhsApiClient] GET {} using ALGO (path: {}, params: {:?})", uri, path, params);
-                // 使用 .query() 传递参数，保持与 get 方法一致
-                let response = self.build_get_request_algo(&base_url, &signature, &cookie_str)
-                    .query(&params)
-                    .send()
-                    .await?;
-                self.handle_response(response, uri).await
-            }
-            Err(algo_err) => {
-                tracing::warn!("[XhsApiClient] Algo failed for {}: {}", uri, algo_err);
-                Err(algo_err)
-            }
-        }
+        let span = telemetry::request_span("GET", uri);
+        telemetry::traced(span, self.retry.run(uri, || self.get_with_query_once(uri))).await
     }
 
-    /// 执行带自定义 URL 的 GET 请求（纯算法优先）
+    async fn get_with_query_once(&self, uri: &str) -> Result<String> {
+        let credentials = self.admit_credentials(uri).await?;
+        let url = format!("{}{}", API_BASE, uri);
+        let target = SignTarget::get(uri, &stored_key(uri, None), Some(AlgoTarget::split(uri)), Some(url));
+        self.send_signed(&target, &credentials, true).await
+    }
+
+    /// 执行带自定义 URL 的 GET 请求（按签名来源策略签名）
     /// 
     /// 用于需要动态构造 URL 参数的接口（如 note_page）
-    /// 优先使用 Python Agent 纯算法签名
     /// 
     /// # Arguments
-    /// * `endpoint_key` - 端点标识（用于日志和回退）
+    /// * `endpoint_key` - 端点标识（用于日志、签名策略与存储签名）
     /// * `url` - 完整的请求 URL（含查询参数）
     pub async fn get_with_url(&self, endpoint_key: &str, url: &str) -> Result<String> {
-        let credentials = self.auth.try_get_credentials().await?
-            .ok_or_else(|| anyhow!("Not logged in. Please call /api/auth/login-session first."))?;
-        
-        let cookie_str = credentials.cookie_string();
-        
-        // 从 URL 中解析 path 和 params
-        if let Some(idx) = url.find("edith.xiaohongshu.com") {
-            let uri_start = url[idx..].find('/').map(|i| idx + i).unwrap_or(url.len());
-            let uri = &url[uri_start..];
-            
-            // 解析 path 和 params
-            // let (path, params) = parse_uri_with_params(uri);
-            
-            // 尝试纯算法签名
-            match self.get_algo_signature("GET", uri, &cookie_str, None).await {
-                Ok(signature) => {
-                    // Use URL directly to avoid double encoding of query params by reqwest
-                    tracing::info!("[XhsApiClient] GET {} using ALGO (url: {})", endpoint_key, url);
-                    let response = self.build_get_request_algo(url, &signature, &cookie_str)
-                        .send()
-                        .await?;
-                    return self.handle_response(response, endpoint_key).await;
-                }
-                Err(algo_err) => {
-                    tracing::warn!("[XhsApiClient] Algo failed for {}: {}, trying stored signature", endpoint_key, algo_err);
-                }
-            }
-        }
-        
-        // 回退到存储的签名
-        let signature = self.get_signature(endpoint_key).await?;
-        
-        tracing::info!("[XhsApiClient] GET {} with custom URL using STORED signature", endpoint_key);
-        
-        let response = self.build_get_request(url, &signature, &cookie_str)
-            .send()
-            .await?;
-        
-        self.handle_response(response, endpoint_key).await
+        let span = telemetry::request_span("GET", endpoint_key);
+        telemetry::traced(span, self.retry.run(endpoint_key, || self.get_with_url_once(endpoint_key, url))).await
+    }
+
+    async fn get_with_url_once(&self, endpoint_key: &str, url: &str) -> Result<String> {
+        let credentials = self.admit_credentials(endpoint_key).await?;
+        // 直接使用 URL，避免 reqwest 对查询参数二次编码
+        let algo = api_uri(url).map(|uri| AlgoTarget { uri: uri.to_string(), url: url.to_string(), query: Vec::new() });
+        let target = SignTarget::get(endpoint_key, endpoint_key, algo, Some(url.to_string()));
+        self.send_signed(&target, &credentials, true).await
     }
 
-    /// 执行 POST 请求（纯算法优先 + 存储回退）
+    /// 执行 POST 请求（按签名来源策略签名）
     /// 
-    /// 优先使用 Python Agent 生成签名
+    /// 纯算法签名使用默认 payload，存储签名使用录制的请求体
     /// 
     /// # Arguments
     /// * `endpoint_key` - 签名存储的 key（如 "home_feed_recommend"）
     pub async fn post(&self, endpoint_key: &str) -> Result<String> {
-        let credentials = self.auth.try_get_credentials().await?
-            .ok_or_else(|| anyhow!("Not logged in. Please call /api/auth/login-session first."))?;
-        
-        let cookie_str = credentials.cookie_string();
-        
-        // 优先尝试纯算法签名
-        if let Some(uri) = endpoint_to_uri(endpoint_key) {
-            let url = format!("https://edith.xiaohongshu.com{}", uri);
-            
-            // 构建 Home Feed 的默认 payload
-            let payload = self.build_default_payload(endpoint_key);
-            let body = serde_json::to_string(&payload)?;
-            
-            match self.get_algo_signature("POST", uri, &cookie_str, Some(payload)).await {
-                Ok(signature) => {
-                    tracing::info!("[XhsApiClient] POST {} using ALGO", endpoint_key);
-                    let response = self.build_post_request_algo(&url, &signature, &cookie_str, body)
-                        .send()
-                        .await?;
-                    return self.handle_response(response, endpoint_key).await;
-                }
-                Err(algo_err) => {
-                    tracing::warn!("[XhsApiClient] Algo failed for {}: {}, trying stored signature", endpoint_key, algo_err);
-                }
-            }
-        }
-        
-        // 回退到存储的签名
-        let signature = self.get_signature(endpoint_key).await?;
-        let url = signature.request_url.clone()
-            .unwrap_or_else(|| format!("https://edith.xiaohongshu.com/api/sns/web/v1/{}", endpoint_key));
-        let body = signature.post_body.clone().unwrap_or_default();
-        
-        tracing::info!("[XhsApiClient] POST {} using STORED signature", endpoint_key);
-        
-        let response = self.build_post_request(&url, &signature, &cookie_str, body)
-            .send()
-            .await?;
-        
-        self.handle_response(response, endpoint_key).await
+        let span = telemetry::request_span("POST", endpoint_key);
+        telemetry::traced(span, self.retry.run(endpoint_key, || self.post_once(endpoint_key))).await
+    }
+
+    async fn post_once(&self, endpoint_key: &str) -> Result<String> {
+        let credentials = self.admit_credentials(endpoint_key).await?;
+        self.post_default_signed(endpoint_key, &credentials).await
     }
 
     /// 构建 Home Feed 请求的默认 Payload
+    /// 
+    /// 数量参数统一取自 `FeedTuning` 首次加载预设
     fn build_default_payload(&self, endpoint_key: &str) -> serde_json::Value {
         // 从 endpoint_key 提取 category
         let category = if endpoint_key == "home_feed_recommend" {
@@ -332,20 +550,8 @@ impl XhsApiClient {
             "homefeed_recommend".to_string()
         };
         
-        serde_json::json!({
-            "cursor_score": "",
-            "num": 20,
-            "refresh_type": 1,
-            "note_index": 0,
-            "unread_begin_note_id": "",
-            "unread_end_note_id": "",
-            "unread_note_count": 0,
-            "category": category,
-            "search_key": "",
-            "need_num": 18,
-            "image_formats": ["jpg", "webp", "avif"],
-            "need_filter_image": false
-        })
+        let request = HomefeedRequest::with_tuning(category, REFRESH_TYPE_INITIAL);
+        serde_json::to_value(&request).unwrap_or_default()
     }
 
     /// 执行 POST 请求（使用用户提供的 payload）
@@ -356,40 +562,22 @@ impl XhsApiClient {
     /// * `endpoint_key` - 签名存储的 key（如 "home_feed_fashion"）
     /// * `payload` - 用户提供的完整请求体
     pub async fn post_with_payload(&self, endpoint_key: &str, payload: serde_json::Value) -> Result<String> {
-        let credentials = self.auth.try_get_credentials().await?
-            .ok_or_else(|| anyhow!("Not logged in. Please call /api/auth/login-session first."))?;
-        
-        let cookie_str = credentials.cookie_string();
-        
-        // 优先尝试纯算法签名
-        if let Some(uri) = endpoint_to_uri(endpoint_key) {
-            let url = format!("https://edith.xiaohongshu.com{}", uri);
-            let body = serde_json::to_string(&payload)?;
-            
-            // DEBUG: 输出实际发送的 body
-            tracing::info!("[XhsApiClient] POST {} body: {}", endpoint_key, body);
-            
-            match self.get_algo_signature("POST", uri, &cookie_str, Some(payload)).await {
-                Ok(signature) => {
-                    tracing::info!("[XhsApiClient] POST {} with custom payload using ALGO", endpoint_key);
-                    let response = self.build_post_request_algo(&url, &signature, &cookie_str, body)
-                        .send()
-                        .await?;
-                    return self.handle_response(response, endpoint_key).await;
-                }
-                Err(algo_err) => {
-                    tracing::warn!("[XhsApiClient] Algo failed for {}: {}", endpoint_key, algo_err);
-                    return Err(algo_err);
-                }
-            }
-        }
+        let span = telemetry::request_span("POST", endpoint_key);
+        telemetry::traced(span, self.retry.run(endpoint_key, || self.post_with_payload_once(endpoint_key, payload.clone()))).await
+    }
+
+    async fn post_with_payload_once(&self, endpoint_key: &str, payload: serde_json::Value) -> Result<String> {
+        let credentials = self.admit_credentials(endpoint_key).await?;
+        let uri = endpoint_to_uri(endpoint_key)
+            .ok_or_else(|| XhsError::Other(anyhow::anyhow!("No URI mapping for endpoint: {}", endpoint_key)))?;
+        let body = serde_json::to_string(&payload)?;
+        tracing::info!("[XhsApiClient] POST {} body: {}", endpoint_key, body);
         
-        Err(anyhow!("No URI mapping for endpoint: {}", endpoint_key))
+        let target = SignTarget::post(endpoint_key, endpoint_key, Some(AlgoTarget::whole(uri)), Some(format!("{}{}", API_BASE, uri)), Some(payload), Some(body));
+        self.send_signed(&target, &credentials, true).await
     }
 
-    /// 执行 POST 请求（纯算法签名优先）
-    /// 
-    /// 优先使用 Python Agent 生成签名
+    /// 执行 POST 请求（按签名来源策略签名）
     /// 
     /// # Arguments
     /// * `uri` - API 路径（如 "/api/sns/web/v1/homefeed"）
@@ -398,59 +586,184 @@ impl XhsApiClient {
     /// # Returns
     /// 响应文本内容
     pub async fn post_algo(&self, uri: &str, payload: serde_json::Value) -> Result<String> {
-        let credentials = self.auth.try_get_credentials().await?
-            .ok_or_else(|| anyhow!("Not logged in. Please call /api/auth/login-session first."))?;
-        
-        let cookie_str = credentials.cookie_string();
-        let url = format!("https://edith.xiaohongshu.com{}", uri);
+        let span = telemetry::request_span("POST", uri);
+        telemetry::traced(span, self.retry.run(uri, || self.post_algo_once(uri, payload.clone()))).await
+    }
+
+    /// 执行非幂等的 POST 写操作（创建 / 删除等），与 [`post_algo`](Self::post_algo) 相同但不自动重试
+    ///
+    /// 超时或 5xx 时请求可能已被上游执行，重试会导致重复写入
+    pub async fn post_algo_mutation(&self, uri: &str, payload: serde_json::Value) -> Result<String> {
+        let span = telemetry::request_span("POST", uri);
+        telemetry::traced(span, self.post_algo_once(uri, payload)).await
+    }
+
+    async fn post_algo_once(&self, uri: &str, payload: serde_json::Value) -> Result<String> {
+        let credentials = self.admit_credentials(uri).await?;
         let body = serde_json::to_string(&payload)?;
-        
-        // DEBUG: 输出实际发送的 payload
         tracing::info!("[XhsApiClient] POST {} payload: {}", uri, body);
         
-        // 尝试纯算法签名
-        match self.get_algo_signature("POST", uri, &cookie_str, Some(payload)).await {
-            Ok(signature) => {
-                tracing::info!("[XhsApiClient] POST {} using ALGO signature", uri);
-                let response = self.build_post_request_algo(&url, &signature, &cookie_str, body)
-                    .send()
-                    .await?;
-                self.handle_response(response, uri).await
-            }
-            Err(algo_err) => {
-                tracing::warn!("[XhsApiClient] Algo failed for {}: {}", uri, algo_err);
-                Err(algo_err)
-            }
-        }
+        let target = SignTarget::post(uri, &stored_key(uri, Some(&body)), Some(AlgoTarget::whole(uri)), Some(format!("{}{}", API_BASE, uri)), Some(payload), Some(body));
+        self.send_signed(&target, &credentials, true).await
     }
 
-    /// 执行带自定义 body 的 POST 请求
+    /// 执行带自定义 body 的 POST 请求（按签名来源策略签名）
     /// 
-    /// 用于需要动态构造请求体的接口
+    /// 用于需要动态构造请求体的接口；body 不是 JSON 时只能使用存储签名。
+    /// 请求体可能是写操作，因此不自动重试
     pub async fn post_with_body(&self, endpoint_key: &str, url: &str, body: String) -> Result<String> {
-        let credentials = self.auth.try_get_credentials().await?
-            .ok_or_else(|| anyhow!("Not logged in. Please call /api/auth/login-session first."))?;
-        let signature = self.get_signature(endpoint_key).await?;
-        
+        let span = telemetry::request_span("POST", endpoint_key);
+        telemetry::traced(span, self.post_with_body_once(endpoint_key, url, body)).await
+    }
+
+    async fn post_with_body_once(&self, endpoint_key: &str, url: &str, body: String) -> Result<String> {
+        let credentials = self.admit_credentials(endpoint_key).await?;
         tracing::info!("[XhsApiClient] POST {} with custom body_len: {}", endpoint_key, body.len());
         
-        let response = self.build_post_request(url, &signature, &credentials.cookie_string(), body)
-            .send()
-            .await?;
-        
-        self.handle_response(response, endpoint_key).await
+        let payload = serde_json::from_str(&body).ok();
+        let algo = api_uri(url).filter(|_| payload.is_some())
+            .map(|uri| AlgoTarget { uri: uri.to_string(), url: url.to_string(), query: Vec::new() });
+        let target = SignTarget::post(endpoint_key, endpoint_key, algo, Some(url.to_string()), payload, Some(body));
+        self.send_signed(&target, &credentials, true).await
+    }
+
+    /// 按该接口的签名来源策略依次尝试纯算法 / 存储签名，用第一个成功的签名发出请求
+    ///
+    /// 所有方式都失败时返回最后一个签名错误
+    async fn send_signed(&self, target: &SignTarget, credentials: &UserCredentials, stored: bool) -> Result<String> {
+        let policy = self.signature_policy.for_endpoint(&target.endpoint);
+        let mut last_error = None;
+        for (attempt, mode) in policy.order().iter().enumerate() {
+            let fallback = policy.order().len() > attempt + 1;
+            let request = match mode {
+                SignatureMode::Algo => self.algo_request(target, credentials).await,
+                SignatureMode::Stored => self.stored_request(target, credentials).await,
+            };
+            match request {
+                Ok(request) => {
+                    tracing::info!("[XhsApiClient] {} {} using {} signature", target.method, target.endpoint, mode.as_str().to_uppercase());
+                    return self.send(request, &target.endpoint, credentials, *mode, stored).await;
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "[XhsApiClient] {} signature unavailable for {}: {}{}",
+                        mode.as_str(), target.endpoint, e, if fallback { ", trying next source" } else { "" }
+                    );
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| XhsError::SignatureFailed(format!("No signature source for {}", target.endpoint))))
+    }
+
+    /// 纯算法签名的请求
+    async fn algo_request(&self, target: &SignTarget, credentials: &UserCredentials) -> Result<reqwest::RequestBuilder> {
+        let algo = target.algo.as_ref()
+            .ok_or_else(|| XhsError::SignatureFailed(format!("No URI mapping for endpoint: {}", target.endpoint)))?;
+        let signature = self.get_algo_signature(target.method, &algo.uri, &credentials.cookie_string(), target.payload.clone())
+            .await
+            .map_err(|e| XhsError::SignatureFailed(e.to_string()))?;
+        Ok(match target.method {
+            "POST" => {
+                let body = match &target.body {
+                    Some(body) => body.clone(),
+                    None => serde_json::to_string(&target.payload)?,
+                };
+                self.build_post_request_algo(&algo.url, &signature, credentials, body)
+            }
+            // 使用 .query() 传递参数，而不是直接拼在 URL 中
+            _ if !algo.query.is_empty() => self.build_get_request_algo(&algo.url, &signature, credentials).query(&algo.query),
+            _ => self.build_get_request_algo(&algo.url, &signature, credentials),
+        })
+    }
+
+    /// 存储签名的请求：URL / 请求体优先使用调用方提供的，否则使用签名录制时的
+    async fn stored_request(&self, target: &SignTarget, credentials: &UserCredentials) -> Result<reqwest::RequestBuilder> {
+        let signature = self.get_signature(&target.stored_key).await?;
+        let url = target.url.clone()
+            .or_else(|| signature.request_url.clone())
+            .ok_or_else(|| XhsError::SignatureFailed(format!("No request_url found for endpoint: {}", target.stored_key)))?;
+        Ok(match target.method {
+            "POST" => {
+                let body = target.body.clone().or_else(|| signature.post_body.clone()).unwrap_or_default();
+                self.build_post_request(&url, &signature, credentials, body)
+            }
+            _ => self.build_get_request(&url, &signature, credentials),
+        })
     }
 
     // ==================== 私有辅助方法 ====================
 
+    /// 读取当前凭据，按行为画像与风控熔断检查本次请求是否允许发出，并等待限流令牌
+    ///
+    /// 没有用户凭据时，访客浏览模式允许的只读接口使用访客凭据
+    #[tracing::instrument(name = "xhs.admit", skip_all, fields(endpoint = %endpoint.split('?').next().unwrap_or(endpoint)))]
+    async fn admit_credentials(&self, endpoint: &str) -> Result<UserCredentials> {
+        let pooled = match &self.pool {
+            Some(pool) if pool.allows(endpoint) => pool.pick(&self.verification).await?,
+            _ => None,
+        };
+        let credentials = match pooled {
+            Some(credentials) => credentials,
+            None => match self.auth.try_get_credentials().await? {
+                Some(credentials) => credentials,
+                None if self.guest.allows(endpoint) => self.guest.credentials().await
+                    .map_err(|e| XhsError::NotLoggedIn(format!("Guest session unavailable: {}", e)))?,
+                None => return Err(XhsError::NotLoggedIn("Please call /api/auth/login-session first.".to_string())),
+            },
+        };
+        if !GuestBrowsing::is_guest(&credentials) {
+            self.verification.admit(&credentials)?;
+        }
+        if dry_run::is_active() {
+            return Ok(credentials);
+        }
+        self.maintenance.admit()?;
+        self.activity.admit(&credentials.user_id, RequestCategory::classify(endpoint))?;
+        self.risk.admit(endpoint).await?;
+        self.rate_limiter.acquire(endpoint).await;
+        if let Some(decoy) = self.pacer.pick_decoy(endpoint) {
+            self.send_decoy(decoy, &credentials).await;
+        }
+        self.pacer.pace(endpoint).await;
+        Ok(credentials)
+    }
+
+    /// 拟人模式的浏览动作：刷新热搜 / 首页推荐，结果丢弃
+    async fn send_decoy(&self, decoy: Decoy, credentials: &UserCredentials) {
+        let endpoint_key = decoy.endpoint_key();
+        if self.risk.admit(endpoint_key).await.is_err() {
+            return;
+        }
+        self.rate_limiter.acquire(endpoint_key).await;
+        self.pacer.pace(endpoint_key).await;
+        tracing::info!("[Stealth] Browsing {} before next request", endpoint_key);
+        let result = match decoy {
+            Decoy::Trending => self.get_signed(endpoint_key, credentials, true).await,
+            Decoy::Homefeed => self.post_default_signed(endpoint_key, credentials).await,
+        };
+        if let Err(e) = result {
+            tracing::warn!("[Stealth] Decoy {} failed: {}", endpoint_key, e);
+        }
+    }
+
+    /// 使用默认 payload 发送 POST（不经过准入检查）
+    async fn post_default_signed(&self, endpoint_key: &str, credentials: &UserCredentials) -> Result<String> {
+        let algo = endpoint_to_uri(endpoint_key).map(AlgoTarget::whole);
+        let payload = algo.as_ref().map(|_| self.build_default_payload(endpoint_key));
+        let target = SignTarget::post(endpoint_key, endpoint_key, algo, None, payload, None);
+        self.send_signed(&target, credentials, true).await
+    }
+
     /// 获取指定接口的签名（从存储）
     /// 兜底方法，当纯算法失败时使用
     async fn get_signature(&self, endpoint_key: &str) -> Result<ApiSignature> {
+        SIGNATURE_METRICS.record_stored(endpoint_to_uri(endpoint_key).unwrap_or(endpoint_key));
         self.auth.get_endpoint_signature(endpoint_key).await?
-            .ok_or_else(|| anyhow!(
+            .ok_or_else(|| XhsError::SignatureFailed(format!(
                 "No signature found for endpoint: {}. Please login again to capture signatures.", 
                 endpoint_key
-            ))
+            )))
     }
 
     /// 获取纯算法签名
@@ -461,29 +774,106 @@ impl XhsApiClient {
         uri: &str, 
         cookie_str: &str,
         payload: Option<serde_json::Value>,
-    ) -> Result<Signature> {
+    ) -> anyhow::Result<Signature> {
+        // 回放录制的响应时不需要真实签名
+        if self.is_replaying() {
+            return Ok(vcr::placeholder_signature());
+        }
         let cookies = parse_cookie_string(cookie_str);
+        // 试运行不消耗预生成的签名
+        if !dry_run::is_active() {
+            let ttl = crate::config::signature_prewarm_interval();
+            if let Some(signature) = self.signature_cache.take(method, uri, &cookies, payload.as_ref(), ttl) {
+                tracing::debug!("[XhsApiClient] Using pre-warmed signature for {} {}", method, uri);
+                SIGNATURE_METRICS.record_algo(uri, true);
+                return Ok(signature);
+            }
+        }
+        let result = self.signature_service
+            .get_signature_from_agent(method, uri, cookies, payload)
+            .await;
+        SIGNATURE_METRICS.record_algo(uri, result.is_ok());
+        result
+    }
+
+    /// 用主账号 Cookie 为固定 URI 的 GET 接口预生成纯算法签名（见 `signature::prewarm`）
+    ///
+    /// 未登录时只以空 Cookie 调用一次 Agent 使其保持预热；返回本轮生成的签名数，不计入签名指标
+    pub async fn prewarm_signatures(&self, endpoint_keys: &[String]) -> usize {
+        let credentials = match self.auth.try_get_credentials().await {
+            Ok(Some(credentials)) => credentials,
+            _ => {
+                if let Err(e) = self.signature_service.get_signature_from_agent("GET", "/api/sns/web/v2/user/me", Default::default(), None).await {
+                    tracing::debug!("[Prewarm] Agent warm-up failed: {}", e);
+                }
+                return 0;
+            }
+        };
+        let cookies = parse_cookie_string(&credentials.cookie_string());
+        let mut warmed = 0;
+        for endpoint_key in endpoint_keys {
+            // 请求体每次不同的 POST 接口无法复用预生成的签名
+            let uri = match endpoint_to_uri(endpoint_key) {
+                Some(uri) if !endpoint_key.starts_with("home_feed_") && endpoint_key != "search_notes" => uri,
+                _ => {
+                    tracing::warn!("[Prewarm] {} has no fixed GET URI, skipped", endpoint_key);
+                    continue;
+                }
+            };
+            if self.signature_policy.for_endpoint(endpoint_key) == SignaturePolicy::StoredOnly {
+                continue;
+            }
+            match self.signature_service.get_signature_from_agent("GET", uri, cookies.clone(), None).await {
+                Ok(signature) => {
+                    self.signature_cache.insert("GET", uri, &cookies, None, signature);
+                    warmed += 1;
+                }
+                Err(e) => tracing::warn!("[Prewarm] Signing {} failed: {}", endpoint_key, e),
+            }
+        }
+        warmed
+    }
+
+    /// 只生成签名不发送请求（`/api/debug/sign`）
+    ///
+    /// `cookies` 为空时使用当前账号的 Cookie（未登录时为空）；不计入签名指标
+    pub async fn sign_only(
+        &self,
+        method: &str,
+        uri: &str,
+        payload: Option<serde_json::Value>,
+        cookies: Option<std::collections::HashMap<String, String>>,
+    ) -> Result<Signature> {
+        let cookies = match cookies {
+            Some(cookies) => cookies,
+            None => match self.auth.try_get_credentials().await? {
+                Some(credentials) => parse_cookie_string(&credentials.cookie_string()),
+                None => Default::default(),
+            },
+        };
         self.signature_service
             .get_signature_from_agent(method, uri, cookies, payload)
             .await
+            .map_err(|e| XhsError::SignatureFailed(e.to_string()))
     }
 
     /// 构建 GET 请求（使用纯算法签名）
-    fn build_get_request_algo(&self, url: &str, signature: &Signature, cookie: &str) -> reqwest::RequestBuilder {
-        self.http_client.get_client()
+    fn build_get_request_algo(&self, url: &str, signature: &Signature, credentials: &UserCredentials) -> reqwest::RequestBuilder {
+        let fingerprint = credentials.fingerprint();
+        self.http()
             .get(url)
             .header("accept", "application/json, text/plain, */*")
             .header("accept-language", "zh-CN,zh;q=0.9")
             .header("cache-control", "no-cache")
             .header("pragma", "no-cache")
             .header("priority", "u=1, i")
-            .header("sec-ch-ua", r#""Google Chrome";v="143", "Chromium";v="143", "Not A(Brand";v="24""#)
+            .header("sec-ch-ua", &fingerprint.sec_ch_ua)
             .header("sec-ch-ua-mobile", "?0")
-            .header("sec-ch-ua-platform", r#""Windows""#)
+            .header("sec-ch-ua-platform", &fingerprint.platform)
             .header("sec-fetch-dest", "empty")
             .header("sec-fetch-mode", "cors")
             .header("sec-fetch-site", "same-site")
-            .header("user-agent", USER_AGENT)
+            .header("user-agent", &fingerprint.user_agent)
             .header("origin", ORIGIN)
             .header("referer", REFERER)
             .header("x-s", &signature.x_s)
@@ -491,12 +881,13 @@ impl XhsApiClient {
             .header("x-s-common", &signature.x_s_common)
             .header("x-b3-traceid", &signature.x_b3_traceid)
             .header("x-xray-traceid", &signature.x_xray_traceid)
-            .header("cookie", cookie)
+            .header("cookie", credentials.cookie_string())
     }
 
     /// 构建 POST 请求（使用纯算法签名）
-    fn build_post_request_algo(&self, url: &str, signature: &Signature, cookie: &str, body: String) -> reqwest::RequestBuilder {
-        self.http_client.get_client()
+    fn build_post_request_algo(&self, url: &str, signature: &Signature, credentials: &UserCredentials, body: String) -> reqwest::RequestBuilder {
+        let fingerprint = credentials.fingerprint();
+        self.http()
             .post(url)
             .header("accept", "application/json, text/plain, */*")
             .header("accept-language", "zh-CN,zh;q=0.9")
@@ -504,13 +895,13 @@ impl XhsApiClient {
             .header("content-type", "application/json;charset=UTF-8")
             .header("pragma", "no-cache")  // 修复：添加缺失的 header
             .header("priority", "u=1, i")
-            .header("sec-ch-ua", r#""Google Chrome";v="143", "Chromium";v="143", "Not A(Brand";v="24""#)
+            .header("sec-ch-ua", &fingerprint.sec_ch_ua)
             .header("sec-ch-ua-mobile", "?0")
-            .header("sec-ch-ua-platform", r#""Windows""#)
+            .header("sec-ch-ua-platform", &fingerprint.platform)
             .header("sec-fetch-dest", "empty")
             .header("sec-fetch-mode", "cors")
             .header("sec-fetch-site", "same-site")
-            .header("user-agent", USER_AGENT)
+            .header("user-agent", &fingerprint.user_agent)
             .header("origin", ORIGIN)
             .header("referer", REFERER)
             .header("x-s", &signature.x_s)
@@ -518,13 +909,14 @@ impl XhsApiClient {
             .header("x-s-common", &signature.x_s_common)
             .header("x-b3-traceid", &signature.x_b3_traceid)
             .header("x-xray-traceid", &signature.x_xray_traceid)
-            .header("cookie", cookie)
+            .header("cookie", credentials.cookie_string())
             .body(body)
     }
 
     /// 构建 GET 请求（含所有 headers）
-    fn build_get_request(&self, url: &str, signature: &ApiSignature, cookie: &str) -> reqwest::RequestBuilder {
-        self.http_client.get_client()
+    fn build_get_request(&self, url: &str, signature: &ApiSignature, credentials: &UserCredentials) -> reqwest::RequestBuilder {
+        let fingerprint = credentials.fingerprint();
+        self.http()
             .get(url)
             // Standard browser headers
             .header("accept", "application/json, text/plain, */*")
@@ -533,13 +925,13 @@ impl XhsApiClient {
             .header("pragma", "no-cache")
             .header("priority", "u=1, i")
             // Security headers
-            .header("sec-ch-ua", r#""Google Chrome";v="143", "Chromium";v="143", "Not A(Brand";v="24""#)
+            .header("sec-ch-ua", &fingerprint.sec_ch_ua)
             .header("sec-ch-ua-mobile", "?0")
-            .header("sec-ch-ua-platform", r#""Windows""#)
+            .header("sec-ch-ua-platform", &fingerprint.platform)
             .header("sec-fetch-dest", "empty")
             .header("sec-fetch-mode", "cors")
             .header("sec-fetch-site", "same-site")
-            .header("user-agent", USER_AGENT)
+            .header("user-agent", &fingerprint.user_agent)
             // XHS specific headers
             .header("origin", ORIGIN)
             .header("referer", REFERER)
@@ -548,12 +940,13 @@ impl XhsApiClient {
             .header("x-s-common", &signature.x_s_common)
             .header("x-b3-traceid", &signature.x_b3_traceid)
             .header("x-xray-traceid", &signature.x_xray_traceid)
-            .header("cookie", cookie)
+            .header("cookie", credentials.cookie_string())
     }
 
     /// 构建 POST 请求（含所有 headers）
-    fn build_post_request(&self, url: &str, signature: &ApiSignature, cookie: &str, body: String) -> reqwest::RequestBuilder {
-        self.http_client.get_client()
+    fn build_post_request(&self, url: &str, signature: &ApiSignature, credentials: &UserCredentials, body: String) -> reqwest::RequestBuilder {
+        let fingerprint = credentials.fingerprint();
+        self.http()
             .post(url)
             // Standard browser headers
             .header("accept", "application/json, text/plain, */*")
@@ -561,13 +954,13 @@ impl XhsApiClient {
             .header("content-type", "application/json;charset=UTF-8")
             .header("priority", "u=1, i")
             // Security headers
-            .header("sec-ch-ua", r#""Google Chrome";v="143", "Chromium";v="143", "Not A(Brand";v="24""#)
+            .header("sec-ch-ua", &fingerprint.sec_ch_ua)
             .header("sec-ch-ua-mobile", "?0")
-            .header("sec-ch-ua-platform", r#""Windows""#)
+            .header("sec-ch-ua-platform", &fingerprint.platform)
             .header("sec-fetch-dest", "empty")
             .header("sec-fetch-mode", "cors")
             .header("sec-fetch-site", "same-site")
-            .header("user-agent", USER_AGENT)
+            .header("user-agent", &fingerprint.user_agent)
             // XHS specific headers
             .header("origin", ORIGIN)
             .header("referer", REFERER)
@@ -577,48 +970,188 @@ impl XhsApiClient {
             .header("x-b3-traceid", &signature.x_b3_traceid)
             .header("x-xray-traceid", &signature.x_xray_traceid)
             .header("xy-direction", "98")
-            .header("cookie", cookie)
+            .header("cookie", credentials.cookie_string())
             .body(body)
     }
 
-    /// 处理响应（日志 + 错误状态码处理）
-    async fn handle_response(&self, response: reqwest::Response, endpoint_key: &str) -> Result<String> {
-        let status = response.status();
-        let text = response.text().await?;
+    /// 发出请求并处理响应；启用审计日志时记录本次请求
+    ///
+    /// 访客请求不影响存储的凭据与风控熔断，登录过期时只丢弃访客会话
+    async fn send(
+        &self,
+        request: reqwest::RequestBuilder,
+        endpoint_key: &str,
+        credentials: &UserCredentials,
+        signature: SignatureMode,
+        stored: bool,
+    ) -> Result<String> {
+        let request = request.build()?;
+        if let Some(dry_run) = dry_run::intercept(&request, endpoint_key, signature) {
+            return Err(dry_run);
+        }
+        let mut record = self.audit.as_ref()
+            .map(|_| AuditRecord::start(request.method().as_str(), request.url().path(), &credentials.user_id, signature));
+        let started = std::time::Instant::now();
+        let guest = GuestBrowsing::is_guest(credentials);
+
+        let http = self.http_client.read().unwrap_or_else(|e| e.into_inner()).clone();
+        let response = http.execute(request).await;
+        let result = match response {
+            Ok((status, headers, text)) => {
+                raw::record(&text);
+                if !guest {
+                    self.detect_verification(&headers, &text, endpoint_key, credentials);
+                }
+                if let (Some(record), Some(audit)) = (record.as_mut(), &self.audit) {
+                    record.respond(status.as_u16(), &text, audit.body_limit());
+                }
+                let owner = self.pool.as_ref().and_then(|pool| pool.owner(credentials));
+                let auth = owner.as_deref().unwrap_or(&self.auth);
+                self.handle_response(status, text, endpoint_key, auth, stored && !guest, owner.is_some()).await
+            }
+            Err(e) => Err(e),
+        };
+
+        if let Some(pool) = &self.pool {
+            pool.record(credentials, &result);
+        }
+        if guest && matches!(result, Err(XhsError::LoginExpired(_))) {
+            self.guest.reset().await;
+        }
+        if let (Some(record), Some(audit)) = (record, &self.audit) {
+            audit.record(record.finish(started.elapsed(), result.as_ref().err()));
+        }
+        result
+    }
+
+    /// 识别验证码挑战：暂停该账号的请求并推送 webhook
+    fn detect_verification(&self, headers: &reqwest::header::HeaderMap, text: &str, endpoint_key: &str, credentials: &UserCredentials) {
+        let Some((verify_type, verify_uuid)) = VerificationChallenge::detect(headers, text) else {
+            return;
+        };
+        let Some(challenge) = self.verification.challenge(credentials, endpoint_key, verify_type, verify_uuid) else {
+            return;
+        };
+        tokio::spawn(async move {
+            match crate::api::notification::webhook::WebhookNotifier::from_env() {
+                Ok(Some(notifier)) => {
+                    notifier.deliver("verification_required", &challenge).await;
+                }
+                Ok(None) => {}
+                Err(e) => tracing::warn!("[Verification] Failed to build webhook notifier: {}", e),
+            }
+        });
+    }
+
+    /// 处理响应（日志 + 错误状态码处理 + 业务码解析）
+    ///
+    /// `auth` 为凭据所属账号（多账号轮换时不一定是主账号）。
+    /// `stored` 为 true 时，登录过期（401 / 业务码 -100）会使该账号存储的凭据失效，
+    /// 风控（461 / 业务码 300012）会打开对应接口的熔断。
+    /// `pooled` 为 true（轮换账号）时不打开客户端级熔断，由账号池隔离该账号，其余账号继续服务
+    async fn handle_response(
+        &self,
+        status: reqwest::StatusCode,
+        text: String,
+        endpoint_key: &str,
+        auth: &AuthService,
+        stored: bool,
+        pooled: bool,
+    ) -> Result<String> {
+        telemetry::record_status(status.as_u16());
         
         tracing::info!("[XhsApiClient] {} Response [{}]: {} chars", endpoint_key, status, text.len());
+        auth.health().record_status(status.as_u16());
+        
+        if let Some(reason) = maintenance::detect(status.as_u16(), &text) {
+            self.maintenance.pause(endpoint_key, &reason);
+            return Err(XhsError::Maintenance(format!("XHS 上游维护中 ({}): {}", endpoint_key, reason)));
+        }
         
-        // 处理常见错误状态码
-        match status.as_u16() {
+        // 处理常见错误状态码，其次解析 HTTP 200 下的业务码
+        let error = match status.as_u16() {
+            401 => {
+                tracing::warn!("[XhsApiClient] {} received 401 - login expired", endpoint_key);
+                Some(XhsError::LoginExpired(text.clone()))
+            }
             406 => {
                 tracing::warn!(
                     "[XhsApiClient] {} received 406 - signature may be invalid (cookies are still valid)",
                     endpoint_key
                 );
+                Some(XhsError::InvalidSignature406 { body: text.clone() })
             }
             461 => {
                 tracing::warn!(
                     "[XhsApiClient] {} received 461 - XHS rate limit or risk control triggered",
                     endpoint_key
                 );
-                return Err(anyhow!(
-                    "XHS 风控触发 (461): 请稍后重试或更换关键词。Response: {}",
-                    text
-                ));
+                Some(XhsError::RiskControl461 { body: text.clone() })
             }
             status_code if status_code >= 400 => {
                 tracing::warn!(
                     "[XhsApiClient] {} received {} - request failed",
                     endpoint_key, status_code
                 );
-                return Err(anyhow!(
-                    "XHS API 错误 ({}): {}",
-                    status_code, text
-                ));
+                Some(XhsError::Upstream { status: status_code, body: text.clone() })
+            }
+            _ => codes::decode(&text).inspect(|e| {
+                tracing::warn!("[XhsApiClient] {} business failure ({}): {}", endpoint_key, e.kind(), e);
+            }),
+        };
+        
+        if let Some(error) = error {
+            if stored {
+                match &error {
+                    XhsError::LoginExpired(_) => {
+                        if let Err(e) = auth.invalidate_credentials().await {
+                            tracing::warn!("[XhsApiClient] Failed to invalidate credentials: {}", e);
+                        }
+                    }
+                    XhsError::RiskControl461 { body } if !pooled => self.risk.trip(endpoint_key, body),
+                    _ => {}
+                }
             }
-            _ => {}
+            return Err(error);
+        }
+        if stored {
+            self.risk.record_success(endpoint_key);
+        }
+        
+        if let Some(archive) = self.archive.clone() {
+            let endpoint = endpoint_to_uri(endpoint_key).unwrap_or(endpoint_key).to_string();
+            let raw = text.clone();
+            tokio::spawn(async move {
+                if let Err(e) = archive.store(&endpoint, &raw).await {
+                    tracing::warn!("[XhsApiClient] Failed to archive {} response: {}", endpoint, e);
+                }
+            });
         }
         
         Ok(text)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_uri_without_query() {
+        assert_eq!(parse_uri_with_params("/api/sns/web/v1/feed"), ("/api/sns/web/v1/feed", vec![]));
+    }
+
+    #[test]
+    fn parse_uri_drops_empty_values_like_parse_qs() {
+        let (path, params) = parse_uri_with_params("/api/foo?num=20&cursor=&flag&&user_id=abc");
+        assert_eq!(path, "/api/foo");
+        assert_eq!(params, vec![("num", "20"), ("user_id", "abc")]);
+    }
+
+    #[test]
+    fn parse_uri_keeps_encoded_values_and_extra_equals() {
+        let (path, params) = parse_uri_with_params("/api/foo?keyword=%E5%92%96%E5%95%A1&token=ab==");
+        assert_eq!(path, "/api/foo");
+        assert_eq!(params, vec![("keyword", "%E5%92%96%E5%95%A1"), ("token", "ab==")]);
+    }
+}
@@ -0,0 +1,112 @@
+//! JSON file job backend
+//!
+//! All jobs live in one JSON array, rewritten atomically (temp file + rename)
+//! on every change. Suitable for a single instance; use the Mongo backend when
+//! several instances share the jobs.
+use anyhow::Result;
+use std::path::PathBuf;
+
+use super::CrawlJob;
+use crate::utils::storage::JsonFileStore;
+
+pub struct FileBackend {
+    store: JsonFileStore<CrawlJob>,
+}
+
+impl FileBackend {
+    pub fn new(path: PathBuf) -> Self {
+        Self { store: JsonFileStore::new(path) }
+    }
+
+    /// Check that the file is readable and its directory writable
+    pub async fn ping(&self) -> Result<()> {
+        self.store.ping().await
+    }
+
+    pub async fn insert(&self, job: &CrawlJob) -> Result<()> {
+        self.store.modify(|jobs| {
+            jobs.push(job.clone());
+            (true, ())
+        }).await
+    }
+
+    pub async fn list(&self) -> Result<Vec<CrawlJob>> {
+        let mut jobs = self.store.load().await?;
+        jobs.sort_by_key(|j| j.created_at);
+        Ok(jobs)
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Option<CrawlJob>> {
+        Ok(self.store.load().await?.into_iter().find(|j| j.id == id))
+    }
+
+    /// Replace the stored job's settings; run state is left to the scheduler
+    pub async fn update(&self, job: &CrawlJob) -> Result<Option<CrawlJob>> {
+        self.store.modify(|jobs| match jobs.iter_mut().find(|j| j.id == job.id) {
+            Some(stored) => {
+                stored.name = job.name.clone();
+                stored.task = job.task.clone();
+                stored.interval_secs = job.interval_secs;
+                stored.enabled = job.enabled;
+                stored.next_run_at = job.next_run_at;
+                stored.updated_at = job.updated_at;
+                (true, Some(stored.clone()))
+            }
+            None => (false, None),
+        }).await
+    }
+
+    pub async fn delete(&self, id: &str) -> Result<bool> {
+        self.store.modify(|jobs| {
+            let before = jobs.len();
+            jobs.retain(|j| j.id != id);
+            let removed = jobs.len() < before;
+            (removed, removed)
+        }).await
+    }
+
+    pub async fn claim_due(&self, now: i64, stale_before: i64) -> Result<Vec<CrawlJob>> {
+        self.store.modify(|jobs| {
+            let mut due = Vec::new();
+            for job in jobs.iter_mut() {
+                if job.enabled && job.next_run_at <= now && (!job.running || job.updated_at < stale_before) {
+                    job.running = true;
+                    job.updated_at = now;
+                    due.push(job.clone());
+                }
+            }
+            due.sort_by_key(|j| j.next_run_at);
+            (!due.is_empty(), due)
+        }).await
+    }
+
+    pub async fn finish(
+        &self,
+        id: &str,
+        result: &std::result::Result<usize, String>,
+        now: i64,
+        next_run_at: i64,
+    ) -> Result<()> {
+        self.store.modify(|jobs| match jobs.iter_mut().find(|j| j.id == id) {
+            Some(job) => {
+                match result {
+                    Ok(items) => {
+                        job.last_items = Some(*items);
+                        job.last_error = None;
+                    }
+                    Err(e) => {
+                        job.last_error = Some(e.clone());
+                        job.failure_count += 1;
+                    }
+                }
+                job.running = false;
+                job.run_count += 1;
+                job.last_run_at = Some(now);
+                job.next_run_at = next_run_at;
+                job.updated_at = now;
+                (true, ())
+            }
+            None => (false, ()),
+        }).await
+    }
+}
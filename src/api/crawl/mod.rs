@@ -0,0 +1,515 @@
+//! Scheduled Crawl Jobs
+//!
+//! Recurring crawl tasks ("search keyword X every 30 min", "snapshot the
+//! fashion channel hourly") registered through `/api/jobs`. A background
+//! scheduler claims due jobs and runs them one after another through
+//! `XhsApiClient`, so the usual rate limiting, pacing, risk breaker and
+//! maintenance gate apply. Upstream responses land in the response archive
+//! (`XHS_ARCHIVE_BACKEND`); note jobs also store a structured note archive.
+//!
+//! Jobs are persisted so they survive restarts. Configuration (env):
+//! - `XHS_JOBS_BACKEND`: `file` (default) / `mongo`
+//! - `XHS_JOBS_FILE`: file backend path, default ./crawl_jobs.json
+//! - `XHS_JOBS_MONGO_URI`: Mongo backend URI, defaults to XHS_COORD_MONGO_URI
+//! - `XHS_JOBS_DB`: Mongo database, default xhs_jobs
+//!
+//! A job claimed by an instance that then crashed is picked up again once it
+//! has been `running` for longer than `STALE_RUNNING`.
+pub mod file;
+#[cfg(feature = "mongodb")]
+pub mod mongo;
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use utoipa::ToSchema;
+
+use crate::models::search::{SearchNoteType, SearchSort};
+use file::FileBackend;
+#[cfg(feature = "mongodb")]
+use mongo::MongoBackend;
+
+/// Running jobs older than this are considered interrupted
+pub const STALE_RUNNING: std::time::Duration = std::time::Duration::from_secs(3600);
+
+/// Shortest allowed interval between two runs of a job (seconds)
+pub const MIN_INTERVAL_SECS: u64 = 60;
+
+/// Most result pages a search job may fetch per run
+pub const MAX_SEARCH_PAGES: u32 = 10;
+
+// ============================================================================
+// Models
+// ============================================================================
+
+/// What a job crawls
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum JobTask {
+    /// Search notes by keyword
+    Search {
+        keyword: String,
+        /// Result pages per run, default 1
+        #[serde(default = "default_pages")]
+        pages: u32,
+        #[serde(default)]
+        sort: SearchSort,
+        /// all / image / video (legacy 0 / 1 / 2 accepted)
+        #[serde(default)]
+        note_type: SearchNoteType,
+    },
+    /// Snapshot the first page of a homefeed channel
+    Feed {
+        /// recommend / fashion / food / ... (see /api/feed/homefeed/{category})
+        #[serde(default = "default_category")]
+        category: String,
+    },
+    /// Fetch a note's detail and store it in the note archive
+    Note { note_id: String, xsec_token: String },
+}
+
+fn default_pages() -> u32 { 1 }
+fn default_category() -> String { "recommend".to_string() }
+
+impl JobTask {
+    /// Short description used as the default job name
+    pub fn describe(&self) -> String {
+        match self {
+            JobTask::Search { keyword, .. } => format!("search: {}", keyword),
+            JobTask::Feed { category } => format!("feed: {}", category),
+            JobTask::Note { note_id, .. } => format!("note: {}", note_id),
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        match self {
+            JobTask::Search { keyword, pages, .. } => {
+                if keyword.trim().is_empty() {
+                    return Err(anyhow!("keyword must not be empty"));
+                }
+                if !(1..=MAX_SEARCH_PAGES).contains(pages) {
+                    return Err(anyhow!("pages must be between 1 and {}", MAX_SEARCH_PAGES));
+                }
+            }
+            JobTask::Feed { category } => {
+                if category.is_empty() || !category.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                    return Err(anyhow!("Invalid feed category: {}", category));
+                }
+            }
+            JobTask::Note { note_id, xsec_token } => {
+                if note_id.is_empty() || xsec_token.is_empty() {
+                    return Err(anyhow!("note_id and xsec_token are required"));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A registered crawl job
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CrawlJob {
+    pub id: String,
+    pub name: String,
+    pub task: JobTask,
+    /// Seconds between runs
+    pub interval_secs: u64,
+    /// Disabled jobs stay registered but are not run
+    pub enabled: bool,
+    /// Next run time (ms)
+    pub next_run_at: i64,
+    /// Currently being run by the scheduler
+    #[serde(default)]
+    pub running: bool,
+    /// Last run time (ms)
+    #[serde(default)]
+    pub last_run_at: Option<i64>,
+    /// Items fetched by the last successful run (notes for search / feed)
+    #[serde(default)]
+    pub last_items: Option<usize>,
+    /// Error of the last run, cleared by the next successful one
+    #[serde(default)]
+    pub last_error: Option<String>,
+    #[serde(default)]
+    pub run_count: u64,
+    #[serde(default)]
+    pub failure_count: u64,
+    /// Creation time (ms)
+    pub created_at: i64,
+    /// Last change (ms)
+    pub updated_at: i64,
+}
+
+/// Request body for creating / replacing a job
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "name": "露营关键词",
+    "task": {"type": "search", "keyword": "露营", "pages": 2, "sort": "time_descending"},
+    "interval_secs": 1800
+}))]
+pub struct CrawlJobRequest {
+    /// Defaults to a description of the task, e.g. "search: 露营"
+    #[serde(default)]
+    pub name: Option<String>,
+    pub task: JobTask,
+    /// Seconds between runs, at least 60
+    pub interval_secs: u64,
+    /// Default true
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool { true }
+
+impl CrawlJobRequest {
+    fn validate(&self) -> Result<()> {
+        if self.interval_secs < MIN_INTERVAL_SECS {
+            return Err(anyhow!("interval_secs must be at least {}", MIN_INTERVAL_SECS));
+        }
+        self.task.validate()
+    }
+
+    fn name(&self) -> String {
+        self.name.clone().filter(|n| !n.trim().is_empty()).unwrap_or_else(|| self.task.describe())
+    }
+}
+
+// ============================================================================
+// Storage
+// ============================================================================
+
+/// Job storage configuration
+#[derive(Debug, Clone)]
+pub enum CrawlJobsConfig {
+    File { path: PathBuf },
+    #[cfg(feature = "mongodb")]
+    Mongo { uri: String, database: String },
+}
+
+impl CrawlJobsConfig {
+    pub fn from_env() -> Result<Self> {
+        let backend = std::env::var("XHS_JOBS_BACKEND").unwrap_or_else(|_| "file".to_string());
+        match backend.as_str() {
+            "" | "file" => Ok(Self::File {
+                path: std::env::var("XHS_JOBS_FILE")
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|_| PathBuf::from("./crawl_jobs.json")),
+            }),
+            #[cfg(feature = "mongodb")]
+            "mongo" => {
+                let uri = std::env::var("XHS_JOBS_MONGO_URI")
+                    .or_else(|_| std::env::var("XHS_COORD_MONGO_URI"))
+                    .map_err(|_| anyhow!("XHS_JOBS_BACKEND=mongo requires XHS_JOBS_MONGO_URI"))?;
+                let database = std::env::var("XHS_JOBS_DB").unwrap_or_else(|_| "xhs_jobs".to_string());
+                Ok(Self::Mongo { uri, database })
+            }
+            #[cfg(not(feature = "mongodb"))]
+            "mongo" => Err(anyhow!("XHS_JOBS_BACKEND=mongo requires the `mongodb` cargo feature")),
+            other => Err(anyhow!("Unknown XHS_JOBS_BACKEND: {}", other)),
+        }
+    }
+}
+
+enum JobsBackend {
+    File(FileBackend),
+    #[cfg(feature = "mongodb")]
+    Mongo(MongoBackend),
+}
+
+/// Persistent registry of crawl jobs
+pub struct CrawlJobs {
+    backend: JobsBackend,
+    description: String,
+}
+
+impl CrawlJobs {
+    pub async fn open(config: &CrawlJobsConfig) -> Result<Self> {
+        let (backend, description) = match config {
+            CrawlJobsConfig::File { path } => {
+                (JobsBackend::File(FileBackend::new(path.clone())), format!("file: {}", path.display()))
+            }
+            #[cfg(feature = "mongodb")]
+            CrawlJobsConfig::Mongo { uri, database } => {
+                (JobsBackend::Mongo(MongoBackend::connect(uri, database).await?), format!("mongo: {}", database))
+            }
+        };
+        tracing::info!("[CrawlJobs] Jobs stored in {}", description);
+        Ok(Self { backend, description })
+    }
+
+    pub async fn from_env() -> Result<Self> {
+        Self::open(&CrawlJobsConfig::from_env()?).await
+    }
+
+    /// Backend description, e.g. "file: ./crawl_jobs.json"
+    pub fn describe(&self) -> &str {
+        &self.description
+    }
+
+    /// Whether every instance sees the same jobs (MongoDB backend)
+    pub fn is_shared(&self) -> bool {
+        !matches!(self.backend, JobsBackend::File(_))
+    }
+
+    /// Check that the backend is reachable (readiness probe)
+    pub async fn ping(&self) -> Result<()> {
+        match &self.backend {
+            JobsBackend::File(b) => b.ping().await,
+            #[cfg(feature = "mongodb")]
+            JobsBackend::Mongo(b) => b.ping().await,
+        }
+    }
+
+    /// Register a job; its first run is due immediately
+    pub async fn create(&self, req: CrawlJobRequest) -> Result<CrawlJob> {
+        req.validate()?;
+        let now = Utc::now().timestamp_millis();
+        let job = CrawlJob {
+            id: uuid::Uuid::new_v4().simple().to_string(),
+            name: req.name(),
+            task: req.task,
+            interval_secs: req.interval_secs,
+            enabled: req.enabled,
+            next_run_at: now,
+            running: false,
+            last_run_at: None,
+            last_items: None,
+            last_error: None,
+            run_count: 0,
+            failure_count: 0,
+            created_at: now,
+            updated_at: now,
+        };
+        match &self.backend {
+            JobsBackend::File(b) => b.insert(&job).await?,
+            #[cfg(feature = "mongodb")]
+            JobsBackend::Mongo(b) => b.insert(&job).await?,
+        }
+        tracing::info!("[CrawlJobs] Registered {} \"{}\" every {}s", job.id, job.name, job.interval_secs);
+        Ok(job)
+    }
+
+    /// Jobs ordered by creation time
+    pub async fn list(&self) -> Result<Vec<CrawlJob>> {
+        match &self.backend {
+            JobsBackend::File(b) => b.list().await,
+            #[cfg(feature = "mongodb")]
+            JobsBackend::Mongo(b) => b.list().await,
+        }
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Option<CrawlJob>> {
+        match &self.backend {
+            JobsBackend::File(b) => b.get(id).await,
+            #[cfg(feature = "mongodb")]
+            JobsBackend::Mongo(b) => b.get(id).await,
+        }
+    }
+
+    /// Replace a job's name, task, interval and enabled flag, keeping its run history.
+    /// A changed task runs again immediately.
+    pub async fn update(&self, id: &str, req: CrawlJobRequest) -> Result<CrawlJob> {
+        req.validate()?;
+        let existing = self.get(id).await?.ok_or_else(|| anyhow!("Job {} not found", id))?;
+        let now = Utc::now().timestamp_millis();
+        let next_run_at = if existing.task != req.task || (req.enabled && !existing.enabled) {
+            now
+        } else {
+            existing.next_run_at
+        };
+        let job = CrawlJob {
+            name: req.name(),
+            task: req.task,
+            interval_secs: req.interval_secs,
+            enabled: req.enabled,
+            next_run_at,
+            updated_at: now,
+            ..existing
+        };
+        let updated = match &self.backend {
+            JobsBackend::File(b) => b.update(&job).await?,
+            #[cfg(feature = "mongodb")]
+            JobsBackend::Mongo(b) => b.update(&job).await?,
+        };
+        updated.ok_or_else(|| anyhow!("Job {} not found", id))
+    }
+
+    /// Remove a job, returning whether it existed
+    pub async fn delete(&self, id: &str) -> Result<bool> {
+        match &self.backend {
+            JobsBackend::File(b) => b.delete(id).await,
+            #[cfg(feature = "mongodb")]
+            JobsBackend::Mongo(b) => b.delete(id).await,
+        }
+    }
+
+    /// Atomically mark due jobs as running and return them
+    pub async fn claim_due(&self) -> Result<Vec<CrawlJob>> {
+        let now = Utc::now().timestamp_millis();
+        let stale_before = now - STALE_RUNNING.as_millis() as i64;
+        match &self.backend {
+            JobsBackend::File(b) => b.claim_due(now, stale_before).await,
+            #[cfg(feature = "mongodb")]
+            JobsBackend::Mongo(b) => b.claim_due(now, stale_before).await,
+        }
+    }
+
+    /// Record the outcome of a run (item count or error) and schedule the next one
+    pub async fn finish(&self, job: &CrawlJob, result: std::result::Result<usize, String>) -> Result<()> {
+        let now = Utc::now().timestamp_millis();
+        let next_run_at = now + job.interval_secs as i64 * 1000;
+        match &self.backend {
+            JobsBackend::File(b) => b.finish(&job.id, &result, now, next_run_at).await,
+            #[cfg(feature = "mongodb")]
+            JobsBackend::Mongo(b) => b.finish(&job.id, &result, now, next_run_at).await,
+        }
+    }
+}
+
+// ============================================================================
+// Scheduler
+// ============================================================================
+
+#[cfg(feature = "server")]
+use crate::server::AppState;
+#[cfg(feature = "server")]
+use std::sync::Arc;
+
+/// Work queue task kind for a claimed job run (payload: the job)
+#[cfg(feature = "server")]
+const CRAWL_JOB_RUN_TASK: &str = "crawl_job_run";
+
+/// Background scheduler: claims due jobs every `XHS_JOBS_TICK_SECS` and runs
+/// them one at a time, so a burst of due jobs does not become a burst of
+/// upstream requests. Skips ticks while upstream maintenance is in effect or
+/// the primary account is outside the active hours of its activity profile.
+///
+/// In cluster mode with a shared (MongoDB) job store, claimed runs are handed
+/// to the coordination queue instead and executed by whichever instance's
+/// worker leases them.
+#[cfg(feature = "server")]
+pub fn spawn_scheduler(state: Arc<AppState>) {
+    let interval = crate::config::crawl_jobs_tick_interval();
+    let queue = state.coordinator.clone().filter(|_| state.crawl_jobs.is_shared());
+    if let Some(queue) = queue.clone() {
+        let state = state.clone();
+        tokio::spawn(async move {
+            queue.run_worker(&[CRAWL_JOB_RUN_TASK], |task| run_task(&state, task)).await;
+        });
+    }
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(interval).await;
+            if state.api.maintenance().is_paused() || !state.api.is_active_now().await {
+                continue;
+            }
+            let due = match state.crawl_jobs.claim_due().await {
+                Ok(due) => due,
+                Err(e) => {
+                    tracing::error!("[CrawlJobs] Failed to claim due jobs: {}", e);
+                    continue;
+                }
+            };
+            for job in due {
+                match &queue {
+                    Some(queue) => {
+                        let key = format!("crawl_job:{}", job.id);
+                        let enqueued = match serde_json::to_value(&job) {
+                            Ok(payload) => queue.enqueue(&key, CRAWL_JOB_RUN_TASK, payload, 0).await,
+                            Err(e) => Err(e.into()),
+                        };
+                        if let Err(e) = enqueued {
+                            tracing::error!("[CrawlJobs] Failed to enqueue {}: {}", job.id, e);
+                            if let Err(e) = state.crawl_jobs.finish(&job, Err(e.to_string())).await {
+                                tracing::error!("[CrawlJobs] Failed to record outcome of {}: {}", job.id, e);
+                            }
+                        }
+                    }
+                    None => execute(&state, &job).await,
+                }
+            }
+        }
+    });
+}
+
+/// Run a claimed job and record the outcome
+#[cfg(feature = "server")]
+async fn execute(state: &AppState, job: &CrawlJob) {
+    tracing::info!("[CrawlJobs] Running {} \"{}\"", job.id, job.name);
+    let result = run(state, &job.task).await.map_err(|e| e.to_string());
+    match &result {
+        Ok(items) => tracing::info!("[CrawlJobs] {} fetched {} item(s)", job.id, items),
+        Err(e) => tracing::warn!("[CrawlJobs] {} failed: {}", job.id, e),
+    }
+    if let Err(e) = state.crawl_jobs.finish(job, result).await {
+        tracing::error!("[CrawlJobs] Failed to record outcome of {}: {}", job.id, e);
+    }
+}
+
+/// Queue worker: the run outcome is recorded on the job, which schedules its
+/// own retries, so the one-shot task always completes
+#[cfg(feature = "server")]
+async fn run_task(state: &AppState, task: crate::coordination::CrawlTask) -> std::result::Result<(), String> {
+    let job: CrawlJob = serde_json::from_value(task.payload).map_err(|e| e.to_string())?;
+    execute(state, &job).await;
+    Ok(())
+}
+
+/// Run one task, returning the number of items fetched
+#[cfg(feature = "server")]
+async fn run(state: &AppState, task: &JobTask) -> Result<usize> {
+    use crate::api::note::detail::{fetch_note_detail, NoteDetailRequest};
+    use crate::archive::notes::ArchivedNote;
+    use crate::models::search::SearchNotesRequest;
+
+    match task {
+        JobTask::Search { keyword, pages, sort, note_type } => {
+            let mut items = 0;
+            let mut search_id: Option<String> = None;
+            for page in 1..=*pages {
+                let req = SearchNotesRequest {
+                    page: page as i32,
+                    search_id: search_id.take(),
+                    sort: *sort,
+                    note_type: *note_type,
+                    ..SearchNotesRequest::new(keyword.clone())
+                };
+                let result = crate::api::search::search_notes(&state.api, req).await?;
+                if !result.success {
+                    return Err(anyhow!("Search failed: {}", result.msg.unwrap_or_default()));
+                }
+                let Some(data) = result.data else { break };
+                state.note_cache.observe_items(&data.items).await;
+                items += data.items.len();
+                if !data.has_more {
+                    break;
+                }
+                search_id = data.search_id;
+            }
+            Ok(items)
+        }
+        JobTask::Feed { category } => {
+            let result = crate::api::feed::category::fetch_category_feed(
+                &state.api,
+                category,
+                crate::models::feed::HomefeedRequest::default(),
+            ).await?;
+            if !result.success {
+                return Err(anyhow!("Feed failed: {}", result.msg.unwrap_or_default()));
+            }
+            let items = result.data.map(|d| d.items).unwrap_or_default();
+            state.note_cache.observe_items(&items).await;
+            Ok(items.len())
+        }
+        JobTask::Note { note_id, xsec_token } => {
+            let archive = state.api.archive()
+                .ok_or_else(|| anyhow!("Note jobs need the archive (set XHS_ARCHIVE_BACKEND=file|mongo)"))?;
+            let detail = fetch_note_detail(&state.api, &state.note_cache, NoteDetailRequest::new(note_id, xsec_token)).await?;
+            let note_card = detail.note_card()
+                .ok_or_else(|| anyhow!("Note {} not found: {}", note_id, detail.msg.clone().unwrap_or_default()))?;
+            archive.store_note(ArchivedNote::from_note_card(note_id, xsec_token, note_card)?).await?;
+            Ok(1)
+        }
+    }
+}
@@ -0,0 +1,138 @@
+//! MongoDB job backend
+//!
+//! One document per job in `crawl_jobs`; due jobs are claimed with
+//! `findOneAndUpdate` so several instances can share the jobs without
+//! running one twice.
+use anyhow::{anyhow, Result};
+use mongodb::bson::{doc, Document};
+use mongodb::options::{IndexOptions, ReturnDocument};
+use mongodb::{Collection, IndexModel};
+
+use super::CrawlJob;
+
+pub struct MongoBackend {
+    jobs: Collection<CrawlJob>,
+}
+
+impl MongoBackend {
+    pub async fn connect(uri: &str, database: &str) -> Result<Self> {
+        let client = crate::utils::storage::mongo_client(uri)
+            .await
+            .map_err(|e| anyhow!("Failed to connect to jobs MongoDB: {}", e))?;
+        let jobs: Collection<CrawlJob> = client.database(database).collection("crawl_jobs");
+        jobs
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "id": 1 })
+                    .options(IndexOptions::builder().name("id".to_string()).unique(true).build())
+                    .build(),
+            )
+            .await?;
+        jobs
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "enabled": 1, "next_run_at": 1 })
+                    .options(IndexOptions::builder().name("enabled_next_run_at".to_string()).build())
+                    .build(),
+            )
+            .await?;
+        Ok(Self { jobs })
+    }
+
+    /// Check that the deployment is reachable
+    pub async fn ping(&self) -> Result<()> {
+        crate::utils::storage::ping_mongo(&self.jobs).await
+    }
+
+    pub async fn insert(&self, job: &CrawlJob) -> Result<()> {
+        self.jobs.insert_one(job).await?;
+        Ok(())
+    }
+
+    pub async fn list(&self) -> Result<Vec<CrawlJob>> {
+        let mut cursor = self.jobs.find(doc! {}).sort(doc! { "created_at": 1 }).await?;
+        let mut jobs = Vec::new();
+        while cursor.advance().await? {
+            jobs.push(cursor.deserialize_current()?);
+        }
+        Ok(jobs)
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Option<CrawlJob>> {
+        Ok(self.jobs.find_one(doc! { "id": id }).await?)
+    }
+
+    pub async fn update(&self, job: &CrawlJob) -> Result<Option<CrawlJob>> {
+        let task = mongodb::bson::to_bson(&job.task)?;
+        Ok(self.jobs
+            .find_one_and_update(
+                doc! { "id": &job.id },
+                doc! { "$set": {
+                    "name": &job.name,
+                    "task": task,
+                    "interval_secs": job.interval_secs as i64,
+                    "enabled": job.enabled,
+                    "next_run_at": job.next_run_at,
+                    "updated_at": job.updated_at,
+                } },
+            )
+            .return_document(ReturnDocument::After)
+            .await?)
+    }
+
+    pub async fn delete(&self, id: &str) -> Result<bool> {
+        let result = self.jobs.delete_one(doc! { "id": id }).await?;
+        Ok(result.deleted_count > 0)
+    }
+
+    pub async fn claim_due(&self, now: i64, stale_before: i64) -> Result<Vec<CrawlJob>> {
+        let mut due = Vec::new();
+        while let Some(job) = self.jobs
+            .find_one_and_update(
+                doc! {
+                    "enabled": true,
+                    "next_run_at": { "$lte": now },
+                    "$or": [
+                        { "running": false },
+                        { "updated_at": { "$lt": stale_before } },
+                    ],
+                },
+                doc! { "$set": { "running": true, "updated_at": now } },
+            )
+            .sort(doc! { "next_run_at": 1 })
+            .return_document(ReturnDocument::After)
+            .await?
+        {
+            due.push(job);
+        }
+        Ok(due)
+    }
+
+    pub async fn finish(
+        &self,
+        id: &str,
+        result: &std::result::Result<usize, String>,
+        now: i64,
+        next_run_at: i64,
+    ) -> Result<()> {
+        let mut set: Document = doc! {
+            "running": false,
+            "last_run_at": now,
+            "next_run_at": next_run_at,
+            "updated_at": now,
+        };
+        let mut inc: Document = doc! { "run_count": 1_i64 };
+        match result {
+            Ok(items) => {
+                set.insert("last_items", *items as i64);
+                set.insert("last_error", mongodb::bson::Bson::Null);
+            }
+            Err(e) => {
+                set.insert("last_error", e);
+                inc.insert("failure_count", 1_i64);
+            }
+        }
+        self.jobs.update_one(doc! { "id": id }, doc! { "$set": set, "$inc": inc }).await?;
+        Ok(())
+    }
+}
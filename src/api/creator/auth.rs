@@ -21,7 +21,7 @@ use crate::api::login::{
 // Creator QR Code API is actually the same endpoint on customer.xiaohongshu.com
 const QRCODE_CREATE_URL: &str = "https://customer.xiaohongshu.com/api/cas/customer/web/qr-code";
 
-use crate::api::creator::utils::{sign_request, build_creator_headers, cookies_to_string};
+use crate::api::creator::utils::{sign_request, build_creator_headers, cookies_to_string, creator_client};
 
 // ============================================================================
 // Core Functions
@@ -73,14 +73,13 @@ pub async fn create_creator_qrcode(cookies: &HashMap<String, String>) -> Result<
     headers.insert("x-s-common", HeaderValue::from_str(&x_s_common)?);
     headers.insert("cookie", HeaderValue::from_str(&cookies_to_string(cookies))?);
     
-    let client = reqwest::Client::builder()
-        .default_headers(headers)
-        .build()?;
+    let client = creator_client()?;
     
     tracing::info!("Creating Creator QR code...");
     
     let response = client
         .post(QRCODE_CREATE_URL)
+        .headers(headers)
         .json(&payload)
         .send()
         .await?;
@@ -149,9 +148,7 @@ pub async fn check_creator_qrcode_status(
     headers.insert("x-s-common", HeaderValue::from_str(&x_s_common)?);
     headers.insert("cookie", HeaderValue::from_str(&cookies_to_string(cookies))?);
     
-    let client = reqwest::Client::builder()
-        .default_headers(headers)
-        .build()?;
+    let client = creator_client()?;
         
     let url = format!("{}?{}", QRCODE_CREATE_URL, query);
     
@@ -159,6 +156,7 @@ pub async fn check_creator_qrcode_status(
     
     let response = client
         .get(&url)
+        .headers(headers)
         .send()
         .await?;
         
@@ -0,0 +1,196 @@
+//! Creator Center Comment Management
+//!
+//! Comments across the creator's notes (评论管理): list, reply, delete and hide.
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+use crate::api::creator::utils::creator_request;
+use crate::models::partial::{parse_items, ItemParseError};
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+const COMMENT_LIST_URL: &str = "https://creator.xiaohongshu.com/api/galaxy/creator/comment/list";
+const COMMENT_REPLY_URL: &str = "https://creator.xiaohongshu.com/api/galaxy/creator/comment/reply";
+const COMMENT_DELETE_URL: &str = "https://creator.xiaohongshu.com/api/galaxy/creator/comment/delete";
+const COMMENT_HIDE_URL: &str = "https://creator.xiaohongshu.com/api/galaxy/creator/comment/hide";
+
+/// Upstream comment length limit
+pub const MAX_COMMENT_CHARS: usize = 280;
+
+// ============================================================================
+// Models
+// ============================================================================
+
+/// Comment list query parameters
+#[derive(Debug, Clone, Deserialize, utoipa::IntoParams)]
+pub struct CreatorCommentsParams {
+    /// Only comments of this note (default: all notes)
+    #[serde(default)]
+    pub note_id: Option<String>,
+    /// Pagination cursor from the previous response
+    #[serde(default)]
+    pub cursor: Option<String>,
+    /// Page size (default 20)
+    #[serde(default = "default_num")]
+    pub num: u32,
+}
+
+fn default_num() -> u32 { 20 }
+
+/// Comment author
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CommentUser {
+    #[serde(alias = "userid", alias = "id")]
+    pub user_id: String,
+    #[serde(alias = "nick_name", default)]
+    pub nickname: Option<String>,
+    #[serde(alias = "image", default)]
+    pub avatar: Option<String>,
+}
+
+/// Comment on one of the creator's notes
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreatorComment {
+    #[serde(alias = "id")]
+    pub comment_id: String,
+    pub note_id: String,
+    #[serde(default)]
+    pub note_title: Option<String>,
+    #[serde(default)]
+    pub content: String,
+    #[serde(alias = "user_info", default)]
+    pub user: Option<CommentUser>,
+    /// Creation time (ms)
+    #[serde(default)]
+    pub create_time: Option<i64>,
+    #[serde(alias = "like_count", default)]
+    pub liked_count: Option<i64>,
+    #[serde(default)]
+    pub sub_comment_count: Option<i64>,
+    /// Hidden from other viewers by the creator
+    #[serde(default)]
+    pub hidden: Option<bool>,
+}
+
+/// Comment list page
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(from = "RawCreatorCommentsData")]
+pub struct CreatorCommentsData {
+    pub comments: Vec<CreatorComment>,
+    pub cursor: Option<String>,
+    pub has_more: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parse_errors: Vec<ItemParseError>,
+}
+
+#[derive(Deserialize)]
+struct RawCreatorCommentsData {
+    #[serde(alias = "list", default)]
+    comments: Vec<serde_json::Value>,
+    #[serde(default)]
+    cursor: Option<String>,
+    #[serde(default)]
+    has_more: bool,
+}
+
+impl From<RawCreatorCommentsData> for CreatorCommentsData {
+    fn from(raw: RawCreatorCommentsData) -> Self {
+        let (comments, parse_errors) = parse_items(raw.comments);
+        Self { comments, cursor: raw.cursor.filter(|c| !c.is_empty()), has_more: raw.has_more, parse_errors }
+    }
+}
+
+/// Reply request
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[schema(example = json!({"note_id": "69539b19000000002202c106", "content": "谢谢喜欢～"}))]
+pub struct ReplyCommentRequest {
+    pub note_id: String,
+    pub content: String,
+}
+
+/// Hide / unhide request
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[schema(example = json!({"note_id": "69539b19000000002202c106", "hidden": true}))]
+pub struct HideCommentRequest {
+    pub note_id: String,
+    /// true = hide, false = show again
+    #[serde(default = "default_hidden")]
+    pub hidden: bool,
+}
+
+fn default_hidden() -> bool { true }
+
+/// Delete query parameters
+#[derive(Debug, Clone, Deserialize, utoipa::IntoParams)]
+pub struct DeleteCommentParams {
+    pub note_id: String,
+}
+
+/// Reply / delete / hide result
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CommentMutation {
+    /// Id of the created reply
+    pub comment_id: Option<String>,
+}
+
+// ============================================================================
+// API Functions
+// ============================================================================
+
+/// List comments across the creator's notes (or one note)
+pub async fn list_comments(cookies: &HashMap<String, String>, params: &CreatorCommentsParams) -> Result<CreatorCommentsData> {
+    let mut url = format!("{}?num={}", COMMENT_LIST_URL, params.num.clamp(1, 50));
+    if let Some(note_id) = &params.note_id {
+        url.push_str(&format!("&note_id={}", urlencoding::encode(note_id)));
+    }
+    if let Some(cursor) = &params.cursor {
+        url.push_str(&format!("&cursor={}", urlencoding::encode(cursor)));
+    }
+    let data = creator_request(cookies, "GET", &url, None).await?;
+    Ok(serde_json::from_value(data)?)
+}
+
+/// Reply to a comment, returning the new comment id
+pub async fn reply_comment(cookies: &HashMap<String, String>, comment_id: &str, req: &ReplyCommentRequest) -> Result<Option<String>> {
+    let content = req.content.trim();
+    if content.is_empty() {
+        return Err(anyhow!("Reply content is empty"));
+    }
+    if content.chars().count() > MAX_COMMENT_CHARS {
+        return Err(anyhow!("Reply exceeds {} characters", MAX_COMMENT_CHARS));
+    }
+    let payload = serde_json::json!({
+        "note_id": req.note_id,
+        "target_comment_id": comment_id,
+        "content": content,
+        "at_users": [],
+    });
+    let data = creator_request(cookies, "POST", COMMENT_REPLY_URL, Some(payload)).await?;
+    Ok(data
+        .pointer("/comment/id")
+        .or_else(|| data.get("comment_id"))
+        .and_then(|id| id.as_str())
+        .map(str::to_string))
+}
+
+/// Delete a comment
+pub async fn delete_comment(cookies: &HashMap<String, String>, note_id: &str, comment_id: &str) -> Result<()> {
+    let payload = serde_json::json!({ "note_id": note_id, "comment_id": comment_id });
+    creator_request(cookies, "POST", COMMENT_DELETE_URL, Some(payload)).await?;
+    Ok(())
+}
+
+/// Hide (or show again) a comment
+pub async fn hide_comment(cookies: &HashMap<String, String>, comment_id: &str, req: &HideCommentRequest) -> Result<()> {
+    let payload = serde_json::json!({
+        "note_id": req.note_id,
+        "comment_id": comment_id,
+        "hide": req.hidden,
+    });
+    creator_request(cookies, "POST", COMMENT_HIDE_URL, Some(payload)).await?;
+    Ok(())
+}
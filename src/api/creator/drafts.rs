@@ -0,0 +1,142 @@
+//! Creator Center Draft Box
+//!
+//! Drafts saved in the Creator Center (草稿箱). Notes can be prepared here
+//! programmatically and finished / published manually in the web UI.
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+use crate::api::creator::utils::creator_request;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+const DRAFT_LIST_URL: &str = "https://creator.xiaohongshu.com/api/galaxy/creator/draft/list";
+const DRAFT_DETAIL_URL: &str = "https://creator.xiaohongshu.com/api/galaxy/creator/draft/detail";
+const DRAFT_UPDATE_URL: &str = "https://creator.xiaohongshu.com/api/galaxy/creator/draft/update";
+const DRAFT_DELETE_URL: &str = "https://creator.xiaohongshu.com/api/galaxy/creator/draft/delete";
+
+// ============================================================================
+// Models
+// ============================================================================
+
+/// Draft list query parameters
+#[derive(Debug, Clone, Deserialize, utoipa::IntoParams)]
+pub struct DraftListParams {
+    /// Page number, starting at 1
+    #[serde(default = "default_page")]
+    pub page: u32,
+    /// Page size (default 20)
+    #[serde(default = "default_page_size")]
+    pub page_size: u32,
+    /// Filter by type: "normal" (image) / "video"
+    #[serde(default)]
+    pub note_type: Option<String>,
+}
+
+fn default_page() -> u32 { 1 }
+fn default_page_size() -> u32 { 20 }
+
+/// Draft
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Draft {
+    #[serde(alias = "id")]
+    pub draft_id: String,
+    /// "normal" (image) / "video"
+    #[serde(rename = "type", alias = "note_type", default)]
+    pub note_type: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub desc: Option<String>,
+    /// Cover image URL
+    #[serde(alias = "cover_url", default)]
+    pub cover: Option<String>,
+    /// Uploaded image file ids (image drafts)
+    #[serde(default)]
+    pub image_file_ids: Vec<String>,
+    /// Uploaded video file id (video drafts)
+    #[serde(default)]
+    pub video_file_id: Option<String>,
+    /// Last update time (ms)
+    #[serde(alias = "update_time", default)]
+    pub updated_at: Option<i64>,
+}
+
+/// Editable draft fields; omitted fields are left unchanged
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({"title": "周末露营清单", "desc": "第一次露营必带的 10 样东西"}))]
+pub struct DraftUpdate {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub desc: Option<String>,
+    /// Reorder / replace images with already uploaded file ids
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_file_ids: Option<Vec<String>>,
+}
+
+/// One page of drafts
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DraftList {
+    pub drafts: Vec<Draft>,
+    pub has_more: bool,
+}
+
+// ============================================================================
+// API Functions
+// ============================================================================
+
+/// List drafts, newest first; returns (drafts, has_more)
+pub async fn list_drafts(cookies: &HashMap<String, String>, params: &DraftListParams) -> Result<(Vec<Draft>, bool)> {
+    let mut url = format!("{}?page={}&page_size={}", DRAFT_LIST_URL, params.page.max(1), params.page_size.clamp(1, 100));
+    if let Some(note_type) = &params.note_type {
+        url.push_str(&format!("&type={}", urlencoding::encode(note_type)));
+    }
+    let data = creator_request(cookies, "GET", &url, None).await?;
+    let drafts: Vec<Draft> = serde_json::from_value(
+        data.get("drafts").or_else(|| data.get("list")).cloned().unwrap_or_default(),
+    ).unwrap_or_default();
+    let has_more = data.get("has_more").and_then(|v| v.as_bool())
+        .unwrap_or(drafts.len() as u32 >= params.page_size);
+    Ok((drafts, has_more))
+}
+
+/// Fetch one draft
+pub async fn get_draft(cookies: &HashMap<String, String>, draft_id: &str) -> Result<Draft> {
+    let url = format!("{}?draft_id={}", DRAFT_DETAIL_URL, urlencoding::encode(draft_id));
+    let data = creator_request(cookies, "GET", &url, None).await?;
+    let draft = data.get("draft").cloned().unwrap_or(data);
+    if draft.is_null() {
+        return Err(anyhow!("Draft {} not found", draft_id));
+    }
+    Ok(serde_json::from_value(draft)?)
+}
+
+/// Update a draft's title / desc / images
+pub async fn update_draft(cookies: &HashMap<String, String>, draft_id: &str, update: &DraftUpdate) -> Result<()> {
+    if let Some(title) = &update.title {
+        if title.chars().count() > super::publish::MAX_TITLE_CHARS {
+            return Err(anyhow!("Title exceeds {} characters", super::publish::MAX_TITLE_CHARS));
+        }
+    }
+    if let Some(desc) = &update.desc {
+        if desc.chars().count() > super::publish::MAX_DESC_CHARS {
+            return Err(anyhow!("Description exceeds {} characters", super::publish::MAX_DESC_CHARS));
+        }
+    }
+
+    let mut payload = serde_json::to_value(update)?;
+    payload["draft_id"] = serde_json::Value::String(draft_id.to_string());
+    creator_request(cookies, "POST", DRAFT_UPDATE_URL, Some(payload)).await?;
+    Ok(())
+}
+
+/// Delete a draft
+pub async fn delete_draft(cookies: &HashMap<String, String>, draft_id: &str) -> Result<()> {
+    let payload = serde_json::json!({ "draft_id": draft_id });
+    creator_request(cookies, "POST", DRAFT_DELETE_URL, Some(payload)).await?;
+    Ok(())
+}
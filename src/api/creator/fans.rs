@@ -0,0 +1,146 @@
+//! Creator Center Fans Analytics
+//!
+//! Audience portrait from the Creator Center fans data page (粉丝画像):
+//! gender, age, region and active hours distributions.
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+use crate::api::creator::utils::creator_request;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+const FANS_PORTRAIT_URL: &str = "https://creator.xiaohongshu.com/api/galaxy/creator/data/fans/portrait";
+const FANS_OVERALL_URL: &str = "https://creator.xiaohongshu.com/api/galaxy/creator/data/fans/overall_new";
+
+// ============================================================================
+// Models
+// ============================================================================
+
+/// One bucket of a distribution
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DistributionItem {
+    /// Bucket label, e.g. "female", "18-24", "上海", "21"
+    pub label: String,
+    /// Share of fans in this bucket (0.0 - 1.0)
+    pub ratio: f64,
+}
+
+/// Fans portrait
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct FansPortrait {
+    pub gender: Vec<DistributionItem>,
+    pub age: Vec<DistributionItem>,
+    /// Province level distribution
+    pub province: Vec<DistributionItem>,
+    /// City level distribution
+    pub city: Vec<DistributionItem>,
+    /// Active hours (label = hour of day 0-23)
+    pub active_hours: Vec<DistributionItem>,
+    /// Interest tags
+    pub interests: Vec<DistributionItem>,
+}
+
+/// Fans overview (counts over the selected period)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct FansOverview {
+    #[serde(alias = "fans_count", default)]
+    pub total_fans: Option<i64>,
+    #[serde(alias = "new_fans_count", alias = "rise_fans_count", default)]
+    pub new_fans: Option<i64>,
+    #[serde(alias = "lost_fans_count", alias = "leave_fans_count", default)]
+    pub lost_fans: Option<i64>,
+}
+
+/// Fans analytics query parameters
+#[derive(Debug, Clone, Deserialize, utoipa::IntoParams)]
+pub struct FansPortraitParams {
+    /// Statistics period in days: 7 or 30 (default 30)
+    #[serde(default = "default_days")]
+    pub days: u32,
+}
+
+fn default_days() -> u32 { 30 }
+
+/// Fans analytics (either part may be missing when its request failed)
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FansAnalytics {
+    pub overview: Option<FansOverview>,
+    pub portrait: Option<FansPortrait>,
+}
+
+// ============================================================================
+// API Functions
+// ============================================================================
+
+/// Get the fans portrait
+pub async fn get_fans_portrait(cookies: &HashMap<String, String>) -> Result<FansPortrait> {
+    let data = creator_request(cookies, "GET", FANS_PORTRAIT_URL, None).await?;
+    let field = |keys: &[&str]| {
+        keys.iter()
+            .find_map(|key| data.get(*key))
+            .map(parse_distribution)
+            .unwrap_or_default()
+    };
+    Ok(FansPortrait {
+        gender: field(&["gender", "genders"]),
+        age: field(&["age", "ages"]),
+        province: field(&["province", "provinces"]),
+        city: field(&["city", "cities"]),
+        active_hours: field(&["active_time", "active_hours"]),
+        interests: field(&["interest", "interests"]),
+    })
+}
+
+/// Get fans counts for the last `days` days (7 or 30)
+pub async fn get_fans_overview(cookies: &HashMap<String, String>, days: u32) -> Result<FansOverview> {
+    let period = if days <= 7 { 7 } else { 30 };
+    let url = format!("{}?day={}", FANS_OVERALL_URL, period);
+    let data = creator_request(cookies, "GET", &url, None).await?;
+    // Some responses nest both periods as {"seven": {..}, "thirty": {..}}
+    let key = if period == 7 { "seven" } else { "thirty" };
+    let data = data.get(key).cloned().unwrap_or(data);
+    Ok(serde_json::from_value(data).unwrap_or_default())
+}
+
+/// Normalize a distribution
+///
+/// Accepts `{"male": 0.3, "female": 0.7}` maps as well as arrays of
+/// `{"name"|"label"|"group"|"hour": .., "ratio"|"percent"|"value": ..}`; percentages
+/// over 1 are scaled down to ratios.
+fn parse_distribution(value: &serde_json::Value) -> Vec<DistributionItem> {
+    let label_of = |item: &serde_json::Value| {
+        ["label", "name", "group", "title", "hour"]
+            .iter()
+            .find_map(|key| item.get(*key))
+            .map(|v| v.as_str().map(str::to_string).unwrap_or_else(|| v.to_string()))
+    };
+    let ratio_of = |item: &serde_json::Value| {
+        ["ratio", "percent", "value", "rate"]
+            .iter()
+            .find_map(|key| item.get(*key))
+            .and_then(|v| v.as_f64().or_else(|| v.as_str().and_then(|s| s.trim_end_matches('%').parse().ok())))
+    };
+
+    let mut items: Vec<DistributionItem> = match value {
+        serde_json::Value::Object(map) => map
+            .iter()
+            .filter_map(|(label, v)| v.as_f64().map(|ratio| DistributionItem { label: label.clone(), ratio }))
+            .collect(),
+        serde_json::Value::Array(list) => list
+            .iter()
+            .filter_map(|item| Some(DistributionItem { label: label_of(item)?, ratio: ratio_of(item)? }))
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    if items.iter().any(|item| item.ratio > 1.0) {
+        for item in &mut items {
+            item.ratio /= 100.0;
+        }
+    }
+    items
+}
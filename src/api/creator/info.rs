@@ -3,7 +3,7 @@ use anyhow::{anyhow, Result};
 use std::collections::HashMap;
 use reqwest::header::HeaderValue;
 
-use crate::api::creator::utils::{sign_request, build_creator_headers, cookies_to_string};
+use crate::api::creator::utils::{sign_request, build_creator_headers, cookies_to_string, creator_client};
 use crate::api::creator::models::{CreatorUserInfo, CreatorHomeInfo};
 
 // ============================================================================
@@ -33,14 +33,13 @@ pub async fn get_creator_user_info(cookies: &HashMap<String, String>) -> Result<
     headers.insert("x-s-common", HeaderValue::from_str(&x_s_common)?);
     headers.insert("cookie", HeaderValue::from_str(&cookies_to_string(cookies))?);
     
-    let client = reqwest::Client::builder()
-        .default_headers(headers)
-        .build()?;
+    let client = creator_client()?;
         
     tracing::info!("Fetching Creator User Info...");
     
     let response = client
         .get(CREATOR_USER_INFO_URL)
+        .headers(headers)
         .send()
         .await?;
         
@@ -85,14 +84,13 @@ pub async fn get_creator_home_info(cookies: &HashMap<String, String>) -> Result<
     headers.insert("x-s-common", HeaderValue::from_str(&x_s_common)?);
     headers.insert("cookie", HeaderValue::from_str(&cookies_to_string(cookies))?);
     
-    let client = reqwest::Client::builder()
-        .default_headers(headers)
-        .build()?;
+    let client = creator_client()?;
         
     tracing::info!("Fetching Creator Home Info...");
     
     let response = client
         .get(CREATOR_HOME_INFO_URL)
+        .headers(headers)
         .send()
         .await?;
         
@@ -2,3 +2,8 @@ pub mod auth;
 pub mod models;
 pub mod info;
 pub mod utils;
+pub mod publish;
+pub mod drafts;
+pub mod fans;
+pub mod notes;
+pub mod comments;
@@ -0,0 +1,133 @@
+//! Creator Center Content Management
+//!
+//! The creator's own notes (笔记管理) with review status, visibility and stats.
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+use crate::api::creator::utils::creator_request;
+use crate::models::partial::{parse_items, ItemParseError};
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+const CREATOR_NOTES_URL: &str = "https://edith.xiaohongshu.com/web_api/sns/v5/creator/note/user/posted";
+
+// ============================================================================
+// Models
+// ============================================================================
+
+/// Status filter (tabs of the content management page)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CreatorNoteStatus {
+    #[default]
+    All,
+    /// Published and visible
+    Published,
+    /// Under review
+    Reviewing,
+    /// Rejected by review
+    Rejected,
+}
+
+impl CreatorNoteStatus {
+    fn tab(self) -> u8 {
+        match self {
+            CreatorNoteStatus::All => 0,
+            CreatorNoteStatus::Published => 1,
+            CreatorNoteStatus::Reviewing => 2,
+            CreatorNoteStatus::Rejected => 3,
+        }
+    }
+}
+
+/// Creator notes query parameters
+#[derive(Debug, Clone, Deserialize, utoipa::IntoParams)]
+pub struct CreatorNotesParams {
+    /// Page number starting at 0; use `next_page` from the previous response
+    #[serde(default)]
+    pub page: i32,
+    /// Status filter (default: all)
+    #[serde(default)]
+    pub status: CreatorNoteStatus,
+}
+
+/// A note in content management
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreatorNote {
+    #[serde(alias = "id")]
+    pub note_id: String,
+    #[serde(alias = "display_title", default)]
+    pub title: Option<String>,
+    /// "normal" (image) / "video"
+    #[serde(rename = "type", default)]
+    pub note_type: Option<String>,
+    /// Publish time as shown in the Creator Center
+    #[serde(default)]
+    pub time: Option<String>,
+    /// Review / publish status text (e.g. 已发布, 审核中, 未通过)
+    #[serde(alias = "tab_status", alias = "audit_status", default)]
+    pub status: Option<serde_json::Value>,
+    /// Visibility text (e.g. 公开可见, 仅自己可见)
+    #[serde(alias = "permission_msg", default)]
+    pub visibility: Option<String>,
+    /// Visibility code (0 = public)
+    #[serde(default)]
+    pub permission_code: Option<i32>,
+    #[serde(alias = "view_count", default)]
+    pub views: Option<i64>,
+    #[serde(alias = "likes", alias = "like_count", default)]
+    pub liked_count: Option<i64>,
+    #[serde(alias = "collect_count", default)]
+    pub collected_count: Option<i64>,
+    #[serde(alias = "comments_count", default)]
+    pub comment_count: Option<i64>,
+    #[serde(alias = "shared_count", default)]
+    pub share_count: Option<i64>,
+    /// Security token for opening the note page
+    #[serde(default)]
+    pub xsec_token: Option<String>,
+}
+
+/// Creator notes page
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(from = "RawCreatorNotesData")]
+pub struct CreatorNotesData {
+    pub notes: Vec<CreatorNote>,
+    /// Next page number, None when there are no more pages
+    pub next_page: Option<i32>,
+    /// Items that failed to parse
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parse_errors: Vec<ItemParseError>,
+}
+
+#[derive(Deserialize)]
+struct RawCreatorNotesData {
+    #[serde(default)]
+    notes: Vec<serde_json::Value>,
+    #[serde(default = "no_more_pages")]
+    page: i32,
+}
+
+fn no_more_pages() -> i32 { -1 }
+
+impl From<RawCreatorNotesData> for CreatorNotesData {
+    fn from(raw: RawCreatorNotesData) -> Self {
+        let (notes, parse_errors) = parse_items(raw.notes);
+        Self { notes, next_page: (raw.page >= 0).then_some(raw.page), parse_errors }
+    }
+}
+
+// ============================================================================
+// API Functions
+// ============================================================================
+
+/// List the creator's notes
+pub async fn get_creator_notes(cookies: &HashMap<String, String>, params: &CreatorNotesParams) -> Result<CreatorNotesData> {
+    let url = format!("{}?tab={}&page={}", CREATOR_NOTES_URL, params.status.tab(), params.page.max(0));
+    let data = creator_request(cookies, "GET", &url, None).await?;
+    Ok(serde_json::from_value(data)?)
+}
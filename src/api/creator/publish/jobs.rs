@@ -0,0 +1,132 @@
+//! Publish Job Progress
+//!
+//! Video uploads can take minutes, so publishing runs in the background and
+//! callers poll the job for upload progress and the final note id.
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use utoipa::ToSchema;
+
+/// Finished jobs kept for polling before the oldest are dropped
+const MAX_FINISHED_JOBS: usize = 100;
+
+/// Publish stage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PublishStage {
+    Pending,
+    Uploading,
+    UploadingCover,
+    Publishing,
+    Done,
+    Failed,
+}
+
+impl PublishStage {
+    pub fn is_finished(self) -> bool {
+        matches!(self, PublishStage::Done | PublishStage::Failed)
+    }
+}
+
+/// Progress snapshot of one publish job
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PublishProgress {
+    pub job_id: String,
+    pub stage: PublishStage,
+    pub uploaded_bytes: u64,
+    pub total_bytes: u64,
+    pub parts_done: usize,
+    pub parts_total: usize,
+    /// Published note id (stage = done)
+    pub note_id: Option<String>,
+    /// Failure reason (stage = failed)
+    pub error: Option<String>,
+    #[schema(value_type = String)]
+    pub started_at: DateTime<Utc>,
+    #[schema(value_type = String)]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Handle used by the running job to report progress
+#[derive(Clone)]
+pub struct JobHandle(Arc<Mutex<PublishProgress>>);
+
+impl JobHandle {
+    pub fn update(&self, f: impl FnOnce(&mut PublishProgress)) {
+        let mut progress = self.0.lock().unwrap();
+        f(&mut progress);
+        progress.updated_at = Utc::now();
+    }
+
+    pub fn stage(&self, stage: PublishStage) {
+        self.update(|p| p.stage = stage);
+    }
+
+    pub fn finish<E: std::fmt::Display>(&self, result: Result<String, E>) {
+        self.update(|p| match result {
+            Ok(note_id) => {
+                p.stage = PublishStage::Done;
+                p.note_id = Some(note_id);
+            }
+            Err(e) => {
+                p.stage = PublishStage::Failed;
+                p.error = Some(e.to_string());
+            }
+        });
+    }
+
+    pub fn snapshot(&self) -> PublishProgress {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+/// Registry of publish jobs
+#[derive(Default)]
+pub struct PublishJobs {
+    jobs: Mutex<HashMap<String, JobHandle>>,
+}
+
+impl PublishJobs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a new pending job
+    pub fn create(&self, total_bytes: u64) -> JobHandle {
+        let now = Utc::now();
+        let job_id = uuid::Uuid::new_v4().simple().to_string();
+        let handle = JobHandle(Arc::new(Mutex::new(PublishProgress {
+            job_id: job_id.clone(),
+            stage: PublishStage::Pending,
+            uploaded_bytes: 0,
+            total_bytes,
+            parts_done: 0,
+            parts_total: 0,
+            note_id: None,
+            error: None,
+            started_at: now,
+            updated_at: now,
+        })));
+
+        let mut jobs = self.jobs.lock().unwrap();
+        let mut finished: Vec<(DateTime<Utc>, String)> = jobs
+            .iter()
+            .map(|(id, job)| (job.snapshot(), id))
+            .filter(|(p, _)| p.stage.is_finished())
+            .map(|(p, id)| (p.updated_at, id.clone()))
+            .collect();
+        if finished.len() >= MAX_FINISHED_JOBS {
+            finished.sort();
+            for (_, id) in finished.iter().take(finished.len() + 1 - MAX_FINISHED_JOBS) {
+                jobs.remove(id);
+            }
+        }
+        jobs.insert(job_id, handle.clone());
+        handle
+    }
+
+    pub fn get(&self, job_id: &str) -> Option<PublishProgress> {
+        self.jobs.lock().unwrap().get(job_id).map(JobHandle::snapshot)
+    }
+}
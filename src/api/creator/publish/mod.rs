@@ -0,0 +1,373 @@
+//! Creator Center Note Publishing
+//!
+//! Image note flow:
+//! 1. Obtain an upload permit (file ids + temporary token) from the Creator Center
+//! 2. Upload each image to the ROS CDN with the permit token
+//! 3. Resolve topic names to topic ids
+//! 4. Create the note via web_api with title / desc / topics / uploaded images
+//!
+//! Video notes (`video`) follow the same flow with a chunked upload and progress
+//! tracked in `jobs`. Either kind can be queued for later in `schedule`.
+pub mod jobs;
+pub mod schedule;
+pub mod upload;
+pub mod video;
+
+use anyhow::{anyhow, Result};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+use crate::api::creator::utils::creator_request;
+use upload::upload_file;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+const UPLOAD_PERMIT_URL: &str = "https://creator.xiaohongshu.com/api/media/v1/upload/web/permit";
+const TOPIC_SEARCH_URL: &str = "https://edith.xiaohongshu.com/web_api/sns/v1/search/topic";
+const CREATE_NOTE_URL: &str = "https://edith.xiaohongshu.com/web_api/sns/v2/note";
+
+/// Upstream limits enforced by the publish page
+pub const MAX_TITLE_CHARS: usize = 20;
+pub const MAX_DESC_CHARS: usize = 1000;
+pub const MAX_IMAGES: usize = 18;
+
+// ============================================================================
+// Models
+// ============================================================================
+
+/// Image source for publishing
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(untagged)]
+pub enum ImageSource {
+    /// File under the download root (XHS_DOWNLOAD_ROOT)
+    Path { path: String },
+    /// Base64-encoded image bytes
+    Base64 { base64: String },
+}
+
+/// Request body for publishing an image note
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "title": "周末露营清单",
+    "desc": "第一次露营必带的 10 样东西",
+    "topics": ["露营", "户外"],
+    "images": [{"path": "camping/1.jpg"}, {"path": "camping/2.jpg"}],
+    "is_private": false
+}))]
+pub struct PublishImageRequest {
+    /// Note title (max 20 chars)
+    pub title: String,
+    /// Note body (max 1000 chars)
+    #[serde(default)]
+    pub desc: String,
+    /// Topic names; resolved to topic ids and appended to the body as `#topic[话题]#`
+    #[serde(default)]
+    pub topics: Vec<String>,
+    /// Images in display order (1-18)
+    pub images: Vec<ImageSource>,
+    /// Publish as private (only visible to self)
+    #[serde(default)]
+    pub is_private: bool,
+}
+
+/// Published image note
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PublishedNote {
+    /// Published note id
+    pub note_id: String,
+    /// Uploaded CDN file ids
+    #[serde(default)]
+    pub file_ids: Vec<String>,
+    /// Topics that could not be resolved (published as plain text)
+    #[serde(default)]
+    pub unresolved_topics: Vec<String>,
+}
+
+/// Temporary upload permit
+#[derive(Debug, Clone, Deserialize)]
+pub struct UploadPermit {
+    #[serde(rename = "fileIds")]
+    pub file_ids: Vec<String>,
+    pub token: String,
+    #[serde(rename = "uploadAddr")]
+    pub upload_addr: String,
+}
+
+/// Topic (hash tag)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Topic {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub link: Option<String>,
+}
+
+/// Image ready for upload
+pub struct ImagePayload {
+    pub bytes: Vec<u8>,
+    pub mime: &'static str,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ImagePayload {
+    pub fn new(bytes: Vec<u8>) -> Result<Self> {
+        let (mime, width, height) = sniff_image(&bytes)
+            .ok_or_else(|| anyhow!("Unsupported image format (expected JPEG, PNG or WebP)"))?;
+        Ok(Self { bytes, mime, width, height })
+    }
+}
+
+// ============================================================================
+// API Functions
+// ============================================================================
+
+/// Obtain upload permits for `count` files (`scene`: "image" / "video")
+pub async fn get_upload_permit(cookies: &HashMap<String, String>, scene: &str, count: usize) -> Result<UploadPermit> {
+    let url = format!(
+        "{}?biz_name=spectrum&scene={}&file_count={}&version=1&source=web",
+        UPLOAD_PERMIT_URL, scene, count
+    );
+    let data = creator_request(cookies, "GET", &url, None).await?;
+    let permit = data
+        .get("uploadTempPermits")
+        .and_then(|p| p.get(0))
+        .cloned()
+        .ok_or_else(|| anyhow!("No upload permit returned"))?;
+    let permit: UploadPermit = serde_json::from_value(permit)?;
+    if permit.file_ids.len() < count {
+        return Err(anyhow!("Upload permit covers {} of {} files", permit.file_ids.len(), count));
+    }
+    Ok(permit)
+}
+
+/// Search topics by keyword
+pub async fn search_topics(cookies: &HashMap<String, String>, keyword: &str) -> Result<Vec<Topic>> {
+    let payload = serde_json::json!({
+        "keyword": keyword,
+        "suggest_topic_request": {"title": "", "desc": ""},
+        "page": {"page_size": 20, "page": 1},
+    });
+    let data = creator_request(cookies, "POST", TOPIC_SEARCH_URL, Some(payload)).await?;
+    let topics = data.get("topic_info_dtos").cloned().unwrap_or_default();
+    Ok(serde_json::from_value(topics).unwrap_or_default())
+}
+
+/// Resolve topic names to topics (exact name match), returning unresolved names separately
+pub async fn resolve_topics(cookies: &HashMap<String, String>, names: &[String]) -> Result<(Vec<Topic>, Vec<String>)> {
+    let mut topics = Vec::new();
+    let mut unresolved = Vec::new();
+    for name in names {
+        let name = name.trim().trim_start_matches('#');
+        if name.is_empty() {
+            continue;
+        }
+        match search_topics(cookies, name).await?.into_iter().find(|t| t.name == name) {
+            Some(topic) => topics.push(topic),
+            None => unresolved.push(name.to_string()),
+        }
+    }
+    Ok((topics, unresolved))
+}
+
+/// Create a note (`note_type`: "normal" for images, "video" for video)
+///
+/// `media_info` is inserted as `image_info` / `video_info` accordingly.
+pub async fn create_note(
+    cookies: &HashMap<String, String>,
+    note_type: &str,
+    title: &str,
+    desc: &str,
+    topics: &[Topic],
+    is_private: bool,
+    media_info: serde_json::Value,
+) -> Result<String> {
+    let hash_tag: Vec<serde_json::Value> = topics
+        .iter()
+        .map(|t| serde_json::json!({
+            "id": t.id,
+            "name": t.name,
+            "link": t.link.clone().unwrap_or_default(),
+            "type": "topic",
+        }))
+        .collect();
+
+    let (image_info, video_info) = if note_type == "video" {
+        (serde_json::Value::Null, media_info)
+    } else {
+        (media_info, serde_json::Value::Null)
+    };
+
+    let payload = serde_json::json!({
+        "common": {
+            "type": note_type,
+            "title": title,
+            "note_id": "",
+            "desc": desc,
+            "source": r#"{"type":"web","ids":"","extraInfo":"{\"subType\":\"official\"}"}"#,
+            "business_binds": r#"{"version":1,"noteId":0,"noteOrderBind":{},"notePostTiming":{},"noteCollectionBind":{"id":""}}"#,
+            "ats": [],
+            "hash_tag": hash_tag,
+            "post_loc": {},
+            "privacy_info": {"op_type": 1, "type": if is_private { 1 } else { 0 }},
+        },
+        "image_info": image_info,
+        "video_info": video_info,
+    });
+
+    let data = creator_request(cookies, "POST", CREATE_NOTE_URL, Some(payload)).await?;
+    data.get("id")
+        .or_else(|| data.get("note_id"))
+        .and_then(|id| id.as_str())
+        .map(str::to_string)
+        .ok_or_else(|| anyhow!("Note created but no note id returned: {}", data))
+}
+
+/// Validate title / desc / topics and build the final body with topic markers
+pub fn compose_desc(title: &str, desc: &str, topics: &[Topic], unresolved: &[String]) -> Result<String> {
+    if title.trim().is_empty() {
+        return Err(anyhow!("Title is required"));
+    }
+    if title.chars().count() > MAX_TITLE_CHARS {
+        return Err(anyhow!("Title exceeds {} characters", MAX_TITLE_CHARS));
+    }
+
+    let mut body = desc.trim_end().to_string();
+    for topic in topics {
+        body.push_str(&format!(" #{}[话题]#", topic.name));
+    }
+    for name in unresolved {
+        body.push_str(&format!(" #{}", name));
+    }
+    let body = body.trim().to_string();
+    if body.chars().count() > MAX_DESC_CHARS {
+        return Err(anyhow!("Description exceeds {} characters (including topics)", MAX_DESC_CHARS));
+    }
+    Ok(body)
+}
+
+/// Publish an image note
+///
+/// Images are uploaded in order; the first image becomes the cover.
+pub async fn publish_image_note(
+    cookies: &HashMap<String, String>,
+    req: &PublishImageRequest,
+    images: Vec<ImagePayload>,
+) -> Result<PublishedNote> {
+    if images.is_empty() || images.len() > MAX_IMAGES {
+        return Err(anyhow!("Image notes need 1-{} images, got {}", MAX_IMAGES, images.len()));
+    }
+
+    let (topics, unresolved) = resolve_topics(cookies, &req.topics).await?;
+    let desc = compose_desc(&req.title, &req.desc, &topics, &unresolved)?;
+
+    let permit = get_upload_permit(cookies, "image", images.len()).await?;
+    let mut uploaded = Vec::with_capacity(images.len());
+    for (index, image) in images.into_iter().enumerate() {
+        let file_id = permit.file_ids[index].clone();
+        tracing::info!("[Publish] Uploading image {} as {}", index + 1, file_id);
+        upload_file(&permit, &file_id, image.bytes, image.mime).await?;
+        uploaded.push(serde_json::json!({
+            "file_id": file_id,
+            "width": image.width,
+            "height": image.height,
+            "metadata": {"source": -1},
+            "stickers": {"version": 2, "floating": []},
+            "extra_info_json": format!(r#"{{"mimeType":"{}"}}"#, image.mime),
+        }));
+    }
+
+    let media_info = serde_json::json!({ "images": uploaded });
+    let note_id = create_note(cookies, "normal", &req.title, &desc, &topics, req.is_private, media_info).await?;
+    tracing::info!("[Publish] Image note published: {}", note_id);
+
+    Ok(PublishedNote {
+        note_id,
+        file_ids: permit.file_ids.into_iter().take(uploaded.len()).collect(),
+        unresolved_topics: unresolved,
+    })
+}
+
+/// Load image bytes from a source; paths are resolved by the caller-supplied resolver
+/// (download-root sandbox)
+pub async fn load_image<F>(source: &ImageSource, resolve_path: F) -> Result<ImagePayload>
+where
+    F: Fn(&str) -> Result<std::path::PathBuf>,
+{
+    let bytes = match source {
+        ImageSource::Path { path } => tokio::fs::read(resolve_path(path)?).await?,
+        ImageSource::Base64 { base64 } => {
+            // Accept data URLs ("data:image/png;base64,....") as well as bare base64
+            let data = base64.split_once(',').map(|(_, d)| d).unwrap_or(base64);
+            base64::engine::general_purpose::STANDARD
+                .decode(data.trim())
+                .map_err(|e| anyhow!("Invalid base64 image: {}", e))?
+        }
+    };
+    ImagePayload::new(bytes)
+}
+
+// ============================================================================
+// Image header parsing
+// ============================================================================
+
+/// Detect mime type and dimensions from the image header
+fn sniff_image(bytes: &[u8]) -> Option<(&'static str, u32, u32)> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") && bytes.len() >= 24 {
+        let width = u32::from_be_bytes(bytes[16..20].try_into().ok()?);
+        let height = u32::from_be_bytes(bytes[20..24].try_into().ok()?);
+        return Some(("image/png", width, height));
+    }
+    if bytes.starts_with(b"\xff\xd8") {
+        return jpeg_size(bytes).map(|(w, h)| ("image/jpeg", w, h));
+    }
+    if bytes.len() >= 30 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        return webp_size(bytes).map(|(w, h)| ("image/webp", w, h));
+    }
+    None
+}
+
+/// Scan JPEG segments for a SOFn marker
+fn jpeg_size(bytes: &[u8]) -> Option<(u32, u32)> {
+    let mut i = 2;
+    while i + 9 < bytes.len() {
+        if bytes[i] != 0xff {
+            i += 1;
+            continue;
+        }
+        let marker = bytes[i + 1];
+        let length = u16::from_be_bytes([bytes[i + 2], bytes[i + 3]]) as usize;
+        let is_sof = (0xc0..=0xcf).contains(&marker) && !matches!(marker, 0xc4 | 0xc8 | 0xcc);
+        if is_sof {
+            let height = u16::from_be_bytes([bytes[i + 5], bytes[i + 6]]) as u32;
+            let width = u16::from_be_bytes([bytes[i + 7], bytes[i + 8]]) as u32;
+            return Some((width, height));
+        }
+        i += 2 + length;
+    }
+    None
+}
+
+fn webp_size(bytes: &[u8]) -> Option<(u32, u32)> {
+    match &bytes[12..16] {
+        b"VP8X" => {
+            let width = 1 + u32::from_le_bytes([bytes[24], bytes[25], bytes[26], 0]);
+            let height = 1 + u32::from_le_bytes([bytes[27], bytes[28], bytes[29], 0]);
+            Some((width, height))
+        }
+        b"VP8 " => {
+            let width = u16::from_le_bytes([bytes[26], bytes[27]]) as u32 & 0x3fff;
+            let height = u16::from_le_bytes([bytes[28], bytes[29]]) as u32 & 0x3fff;
+            Some((width, height))
+        }
+        b"VP8L" => {
+            let bits = u32::from_le_bytes([bytes[21], bytes[22], bytes[23], bytes[24]]);
+            Some(((bits & 0x3fff) + 1, ((bits >> 14) & 0x3fff) + 1))
+        }
+        _ => None,
+    }
+}
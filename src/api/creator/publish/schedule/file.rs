@@ -0,0 +1,118 @@
+//! JSON file schedule backend
+//!
+//! All posts live in one JSON array, rewritten atomically (temp file + rename)
+//! on every change. Suitable for a single instance; use the Mongo backend when
+//! several instances share the queue.
+use anyhow::Result;
+use std::path::PathBuf;
+
+use super::{ScheduleStatus, ScheduledPost, INTERRUPTED};
+use crate::utils::storage::JsonFileStore;
+
+pub struct FileBackend {
+    store: JsonFileStore<ScheduledPost>,
+}
+
+impl FileBackend {
+    pub fn new(path: PathBuf) -> Self {
+        Self { store: JsonFileStore::new(path) }
+    }
+
+    /// Check that the file is readable and its directory writable
+    pub async fn ping(&self) -> Result<()> {
+        self.store.ping().await
+    }
+
+    pub async fn insert(&self, post: &ScheduledPost) -> Result<()> {
+        self.store.modify(|posts| {
+            posts.push(post.clone());
+            (true, ())
+        }).await
+    }
+
+    pub async fn list(&self, status: Option<ScheduleStatus>) -> Result<Vec<ScheduledPost>> {
+        let mut posts: Vec<ScheduledPost> = self.store.load().await?
+            .into_iter()
+            .filter(|p| status.is_none_or(|s| p.status == s))
+            .collect();
+        posts.sort_by_key(|p| p.publish_at);
+        Ok(posts)
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Option<ScheduledPost>> {
+        Ok(self.store.load().await?.into_iter().find(|p| p.id == id))
+    }
+
+    pub async fn cancel(&self, id: &str, now: i64) -> Result<Option<ScheduledPost>> {
+        self.store.modify(|posts| {
+            match posts.iter_mut().find(|p| p.id == id && p.status == ScheduleStatus::Pending) {
+                Some(post) => {
+                    post.status = ScheduleStatus::Cancelled;
+                    post.updated_at = now;
+                    (true, Some(post.clone()))
+                }
+                None => (false, None),
+            }
+        }).await
+    }
+
+    pub async fn claim_due(&self, now: i64) -> Result<Vec<ScheduledPost>> {
+        self.store.modify(|posts| {
+            let mut due = Vec::new();
+            for post in posts.iter_mut() {
+                if post.status == ScheduleStatus::Pending && post.publish_at <= now {
+                    post.status = ScheduleStatus::Running;
+                    post.updated_at = now;
+                    due.push(post.clone());
+                }
+            }
+            (!due.is_empty(), due)
+        }).await
+    }
+
+    pub async fn set_job(&self, id: &str, job_id: &str, now: i64) -> Result<()> {
+        self.store.modify(|posts| match posts.iter_mut().find(|p| p.id == id) {
+            Some(post) => {
+                post.job_id = Some(job_id.to_string());
+                post.updated_at = now;
+                (true, ())
+            }
+            None => (false, ()),
+        }).await
+    }
+
+    pub async fn finish(&self, id: &str, result: &std::result::Result<String, String>, now: i64) -> Result<()> {
+        self.store.modify(|posts| match posts.iter_mut().find(|p| p.id == id) {
+            Some(post) => {
+                match result {
+                    Ok(note_id) => {
+                        post.status = ScheduleStatus::Done;
+                        post.note_id = Some(note_id.clone());
+                    }
+                    Err(e) => {
+                        post.status = ScheduleStatus::Failed;
+                        post.error = Some(e.clone());
+                    }
+                }
+                post.updated_at = now;
+                (true, ())
+            }
+            None => (false, ()),
+        }).await
+    }
+
+    pub async fn fail_stale(&self, before: i64, now: i64) -> Result<u64> {
+        self.store.modify(|posts| {
+            let mut count = 0;
+            for post in posts.iter_mut() {
+                if post.status == ScheduleStatus::Running && post.updated_at < before {
+                    post.status = ScheduleStatus::Failed;
+                    post.error = Some(INTERRUPTED.to_string());
+                    post.updated_at = now;
+                    count += 1;
+                }
+            }
+            (count > 0, count)
+        }).await
+    }
+}
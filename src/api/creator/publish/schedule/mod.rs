@@ -0,0 +1,446 @@
+//! Scheduled Publishing
+//!
+//! Image / video publish requests queued with a `publish_at` time. A background
+//! scheduler claims due posts, runs the regular publish flow and records the
+//! outcome (note id or error) on the post.
+//!
+//! Posts are persisted so the queue survives restarts. Configuration (env):
+//! - `XHS_SCHEDULE_BACKEND`: `file` (default) / `mongo`
+//! - `XHS_SCHEDULE_FILE`: file backend path, default ./scheduled_posts.json
+//! - `XHS_SCHEDULE_MONGO_URI`: Mongo backend URI, defaults to XHS_COORD_MONGO_URI
+//! - `XHS_SCHEDULE_DB`: Mongo database, default xhs_publish
+//!
+//! A post is published at most once: posts still `running` after
+//! `STALE_RUNNING` (crash or restart mid-publish) are marked failed instead of
+//! being retried, since the note may already be live.
+pub mod file;
+#[cfg(feature = "mongodb")]
+pub mod mongo;
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use utoipa::ToSchema;
+
+use super::video::PublishVideoRequest;
+use super::{compose_desc, ImageSource, PublishImageRequest, MAX_IMAGES};
+use file::FileBackend;
+#[cfg(feature = "mongodb")]
+use mongo::MongoBackend;
+
+/// Running posts older than this are considered interrupted
+pub const STALE_RUNNING: std::time::Duration = std::time::Duration::from_secs(3600);
+
+// ============================================================================
+// Models
+// ============================================================================
+
+/// What to publish
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ScheduledContent {
+    Image(PublishImageRequest),
+    Video(PublishVideoRequest),
+}
+
+impl ScheduledContent {
+    fn title(&self) -> &str {
+        match self {
+            ScheduledContent::Image(req) => &req.title,
+            ScheduledContent::Video(req) => &req.title,
+        }
+    }
+}
+
+/// Scheduled post status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleStatus {
+    Pending,
+    Running,
+    Done,
+    Failed,
+    Cancelled,
+}
+
+impl ScheduleStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ScheduleStatus::Pending => "pending",
+            ScheduleStatus::Running => "running",
+            ScheduleStatus::Done => "done",
+            ScheduleStatus::Failed => "failed",
+            ScheduleStatus::Cancelled => "cancelled",
+        }
+    }
+}
+
+/// A scheduled post
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ScheduledPost {
+    pub id: String,
+    pub content: ScheduledContent,
+    /// Publish time (ms)
+    pub publish_at: i64,
+    pub status: ScheduleStatus,
+    /// Published note id (status = done)
+    #[serde(default)]
+    pub note_id: Option<String>,
+    /// Publish job for video posts, see `/api/creator/publish/jobs/{job_id}`
+    #[serde(default)]
+    pub job_id: Option<String>,
+    /// Failure reason (status = failed)
+    #[serde(default)]
+    pub error: Option<String>,
+    /// Creation time (ms)
+    pub created_at: i64,
+    /// Last status change (ms)
+    pub updated_at: i64,
+}
+
+/// Request body for scheduling a post
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "publish_at": "2026-10-20T12:00:00+08:00",
+    "content": {
+        "type": "image",
+        "title": "周末露营清单",
+        "desc": "第一次露营必带的 10 样东西",
+        "topics": ["露营"],
+        "images": [{"path": "camping/1.jpg"}]
+    }
+}))]
+pub struct SchedulePostRequest {
+    /// RFC 3339 publish time, must be in the future
+    #[schema(value_type = String)]
+    pub publish_at: DateTime<Utc>,
+    pub content: ScheduledContent,
+}
+
+/// Schedule list query parameters
+#[derive(Debug, Clone, Deserialize, utoipa::IntoParams)]
+pub struct ScheduleListParams {
+    /// Only posts with this status
+    #[serde(default)]
+    #[param(value_type = Option<String>)]
+    pub status: Option<ScheduleStatus>,
+}
+
+// ============================================================================
+// Storage
+// ============================================================================
+
+/// Schedule storage configuration
+#[derive(Debug, Clone)]
+pub enum ScheduleConfig {
+    File { path: PathBuf },
+    #[cfg(feature = "mongodb")]
+    Mongo { uri: String, database: String },
+}
+
+impl ScheduleConfig {
+    pub fn from_env() -> Result<Self> {
+        let backend = std::env::var("XHS_SCHEDULE_BACKEND").unwrap_or_else(|_| "file".to_string());
+        match backend.as_str() {
+            "" | "file" => Ok(Self::File {
+                path: std::env::var("XHS_SCHEDULE_FILE")
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|_| PathBuf::from("./scheduled_posts.json")),
+            }),
+            #[cfg(feature = "mongodb")]
+            "mongo" => {
+                let uri = std::env::var("XHS_SCHEDULE_MONGO_URI")
+                    .or_else(|_| std::env::var("XHS_COORD_MONGO_URI"))
+                    .map_err(|_| anyhow!("XHS_SCHEDULE_BACKEND=mongo requires XHS_SCHEDULE_MONGO_URI"))?;
+                let database = std::env::var("XHS_SCHEDULE_DB").unwrap_or_else(|_| "xhs_publish".to_string());
+                Ok(Self::Mongo { uri, database })
+            }
+            #[cfg(not(feature = "mongodb"))]
+            "mongo" => Err(anyhow!("XHS_SCHEDULE_BACKEND=mongo requires the `mongodb` cargo feature")),
+            other => Err(anyhow!("Unknown XHS_SCHEDULE_BACKEND: {}", other)),
+        }
+    }
+}
+
+enum ScheduleBackend {
+    File(FileBackend),
+    #[cfg(feature = "mongodb")]
+    Mongo(MongoBackend),
+}
+
+/// Persistent queue of scheduled posts
+pub struct PublishSchedule {
+    backend: ScheduleBackend,
+    description: String,
+}
+
+impl PublishSchedule {
+    pub async fn open(config: &ScheduleConfig) -> Result<Self> {
+        let (backend, description) = match config {
+            ScheduleConfig::File { path } => {
+                (ScheduleBackend::File(FileBackend::new(path.clone())), format!("file: {}", path.display()))
+            }
+            #[cfg(feature = "mongodb")]
+            ScheduleConfig::Mongo { uri, database } => {
+                (ScheduleBackend::Mongo(MongoBackend::connect(uri, database).await?), format!("mongo: {}", database))
+            }
+        };
+        tracing::info!("[Schedule] Publish queue stored in {}", description);
+        Ok(Self { backend, description })
+    }
+
+    pub async fn from_env() -> Result<Self> {
+        Self::open(&ScheduleConfig::from_env()?).await
+    }
+
+    /// Backend description, e.g. "file: ./scheduled_posts.json"
+    pub fn describe(&self) -> &str {
+        &self.description
+    }
+
+    /// Check that the backend is reachable (readiness probe)
+    pub async fn ping(&self) -> Result<()> {
+        match &self.backend {
+            ScheduleBackend::File(b) => b.ping().await,
+            #[cfg(feature = "mongodb")]
+            ScheduleBackend::Mongo(b) => b.ping().await,
+        }
+    }
+
+    /// Queue a validated request
+    pub async fn schedule(&self, req: SchedulePostRequest) -> Result<ScheduledPost> {
+        let now = Utc::now().timestamp_millis();
+        let publish_at = req.publish_at.timestamp_millis();
+        if publish_at <= now {
+            return Err(anyhow!("publish_at must be in the future"));
+        }
+        let post = ScheduledPost {
+            id: uuid::Uuid::new_v4().simple().to_string(),
+            content: req.content,
+            publish_at,
+            status: ScheduleStatus::Pending,
+            note_id: None,
+            job_id: None,
+            error: None,
+            created_at: now,
+            updated_at: now,
+        };
+        match &self.backend {
+            ScheduleBackend::File(b) => b.insert(&post).await?,
+            #[cfg(feature = "mongodb")]
+            ScheduleBackend::Mongo(b) => b.insert(&post).await?,
+        }
+        tracing::info!("[Schedule] Scheduled {} \"{}\" at {}", post.id, post.content.title(), req.publish_at);
+        Ok(post)
+    }
+
+    /// Posts ordered by publish time
+    pub async fn list(&self, status: Option<ScheduleStatus>) -> Result<Vec<ScheduledPost>> {
+        match &self.backend {
+            ScheduleBackend::File(b) => b.list(status).await,
+            #[cfg(feature = "mongodb")]
+            ScheduleBackend::Mongo(b) => b.list(status).await,
+        }
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Option<ScheduledPost>> {
+        match &self.backend {
+            ScheduleBackend::File(b) => b.get(id).await,
+            #[cfg(feature = "mongodb")]
+            ScheduleBackend::Mongo(b) => b.get(id).await,
+        }
+    }
+
+    /// Cancel a pending post; errors if it already started or finished
+    pub async fn cancel(&self, id: &str) -> Result<ScheduledPost> {
+        let now = Utc::now().timestamp_millis();
+        let cancelled = match &self.backend {
+            ScheduleBackend::File(b) => b.cancel(id, now).await?,
+            #[cfg(feature = "mongodb")]
+            ScheduleBackend::Mongo(b) => b.cancel(id, now).await?,
+        };
+        match cancelled {
+            Some(post) => Ok(post),
+            None => match self.get(id).await? {
+                Some(post) => Err(anyhow!("Post {} is already {}", id, post.status.as_str())),
+                None => Err(anyhow!("Scheduled post {} not found", id)),
+            },
+        }
+    }
+
+    /// Atomically mark due pending posts as running and return them
+    pub async fn claim_due(&self) -> Result<Vec<ScheduledPost>> {
+        let now = Utc::now().timestamp_millis();
+        match &self.backend {
+            ScheduleBackend::File(b) => b.claim_due(now).await,
+            #[cfg(feature = "mongodb")]
+            ScheduleBackend::Mongo(b) => b.claim_due(now).await,
+        }
+    }
+
+    /// Record the job id of a running video post
+    pub async fn set_job(&self, id: &str, job_id: &str) -> Result<()> {
+        let now = Utc::now().timestamp_millis();
+        match &self.backend {
+            ScheduleBackend::File(b) => b.set_job(id, job_id, now).await,
+            #[cfg(feature = "mongodb")]
+            ScheduleBackend::Mongo(b) => b.set_job(id, job_id, now).await,
+        }
+    }
+
+    /// Record the outcome of a running post
+    pub async fn finish(&self, id: &str, result: std::result::Result<String, String>) -> Result<()> {
+        let now = Utc::now().timestamp_millis();
+        match &self.backend {
+            ScheduleBackend::File(b) => b.finish(id, &result, now).await,
+            #[cfg(feature = "mongodb")]
+            ScheduleBackend::Mongo(b) => b.finish(id, &result, now).await,
+        }
+    }
+
+    /// Fail posts stuck in `running` since before `STALE_RUNNING`
+    pub async fn fail_stale(&self) -> Result<u64> {
+        let now = Utc::now().timestamp_millis();
+        let before = now - STALE_RUNNING.as_millis() as i64;
+        match &self.backend {
+            ScheduleBackend::File(b) => b.fail_stale(before, now).await,
+            #[cfg(feature = "mongodb")]
+            ScheduleBackend::Mongo(b) => b.fail_stale(before, now).await,
+        }
+    }
+}
+
+/// Error recorded on posts interrupted mid-publish
+const INTERRUPTED: &str = "Interrupted while publishing; not retried because the note may already be published";
+
+/// Check a request before queueing it, so obvious mistakes surface now rather
+/// than at publish time. Paths are resolved by the caller-supplied resolver
+/// (download-root sandbox).
+pub fn validate<F>(content: &ScheduledContent, resolve_path: F) -> Result<()>
+where
+    F: Fn(&str) -> Result<PathBuf>,
+{
+    let (title, desc, topics) = match content {
+        ScheduledContent::Image(req) => (&req.title, &req.desc, &req.topics),
+        ScheduledContent::Video(req) => (&req.title, &req.desc, &req.topics),
+    };
+    compose_desc(title, desc, &[], topics)?;
+
+    let check_file = |path: &str| -> Result<()> {
+        let resolved = resolve_path(path)?;
+        if !resolved.is_file() {
+            return Err(anyhow!("File not found: {}", path));
+        }
+        Ok(())
+    };
+    match content {
+        ScheduledContent::Image(req) => {
+            if req.images.is_empty() || req.images.len() > MAX_IMAGES {
+                return Err(anyhow!("Image notes need 1-{} images, got {}", MAX_IMAGES, req.images.len()));
+            }
+            for source in &req.images {
+                if let ImageSource::Path { path } = source {
+                    check_file(path)?;
+                }
+            }
+        }
+        ScheduledContent::Video(req) => {
+            check_file(&req.video_path)?;
+            if let Some(ImageSource::Path { path }) = &req.cover {
+                check_file(path)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Scheduler
+// ============================================================================
+
+#[cfg(feature = "server")]
+use crate::server::AppState;
+#[cfg(feature = "server")]
+use std::sync::Arc;
+
+/// Background scheduler: claims due posts every `XHS_SCHEDULE_TICK_SECS` and
+/// publishes each in its own task. Skips ticks while upstream maintenance is
+/// in effect or the primary account is outside the active hours of its
+/// activity profile; posts then go out late rather than fail.
+#[cfg(feature = "server")]
+pub fn spawn_scheduler(state: Arc<AppState>) {
+    let interval = crate::config::schedule_tick_interval();
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(interval).await;
+            let schedule = &state.publish_schedule;
+            match schedule.fail_stale().await {
+                Ok(0) => {}
+                Ok(n) => tracing::warn!("[Schedule] Marked {} interrupted post(s) as failed", n),
+                Err(e) => tracing::error!("[Schedule] Stale check failed: {}", e),
+            }
+            if state.api.maintenance().is_paused() || !state.api.is_active_now().await {
+                continue;
+            }
+            let due = match schedule.claim_due().await {
+                Ok(due) => due,
+                Err(e) => {
+                    tracing::error!("[Schedule] Failed to claim due posts: {}", e);
+                    continue;
+                }
+            };
+            for post in due {
+                let state = state.clone();
+                tokio::spawn(async move {
+                    tracing::info!("[Schedule] Publishing {} \"{}\"", post.id, post.content.title());
+                    let result = publish(&state, &post).await.map_err(|e| e.to_string());
+                    match &result {
+                        Ok(note_id) => tracing::info!("[Schedule] {} published as {}", post.id, note_id),
+                        Err(e) => tracing::error!("[Schedule] {} failed: {}", post.id, e),
+                    }
+                    if let Err(e) = state.publish_schedule.finish(&post.id, result).await {
+                        tracing::error!("[Schedule] Failed to record outcome of {}: {}", post.id, e);
+                    }
+                });
+            }
+        }
+    });
+}
+
+/// Run the publish flow for a claimed post, returning the note id
+#[cfg(feature = "server")]
+async fn publish(state: &AppState, post: &ScheduledPost) -> Result<String> {
+    let cookies = state
+        .creator_auth
+        .try_get_credentials()
+        .await?
+        .ok_or_else(|| anyhow!("Not logged in (Creator)"))?
+        .cookies;
+    let resolve = |path: &str| state.file_signer.resolve(path);
+
+    match &post.content {
+        ScheduledContent::Image(req) => {
+            let mut images = Vec::with_capacity(req.images.len());
+            for (index, source) in req.images.iter().enumerate() {
+                images.push(super::load_image(source, resolve).await.map_err(|e| anyhow!("Image {}: {}", index + 1, e))?);
+            }
+            Ok(super::publish_image_note(&cookies, req, images).await?.note_id)
+        }
+        ScheduledContent::Video(req) => {
+            let video_path = resolve(&req.video_path)?;
+            let cover = match &req.cover {
+                Some(source) => Some(super::load_image(source, resolve).await.map_err(|e| anyhow!("Cover: {}", e))?),
+                None => None,
+            };
+            let total_bytes = tokio::fs::metadata(&video_path).await?.len();
+
+            let job = state.publish_jobs.create(total_bytes);
+            state.publish_schedule.set_job(&post.id, &job.snapshot().job_id).await?;
+            let result = super::video::publish_video_note(&cookies, req, video_path, cover, &job).await;
+            let outcome = result.as_ref().map(String::clone).map_err(|e| anyhow!("{}", e));
+            job.finish(result);
+            outcome
+        }
+    }
+}
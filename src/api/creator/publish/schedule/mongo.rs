@@ -0,0 +1,124 @@
+//! MongoDB schedule backend
+//!
+//! One document per post in `scheduled_posts`; posts are claimed with
+//! `findOneAndUpdate` so several instances can share the queue without
+//! publishing a post twice.
+use anyhow::{anyhow, Result};
+use mongodb::bson::{doc, Document};
+use mongodb::options::{IndexOptions, ReturnDocument};
+use mongodb::{Collection, IndexModel};
+
+use super::{ScheduleStatus, ScheduledPost, INTERRUPTED};
+
+pub struct MongoBackend {
+    posts: Collection<ScheduledPost>,
+}
+
+impl MongoBackend {
+    pub async fn connect(uri: &str, database: &str) -> Result<Self> {
+        let client = crate::utils::storage::mongo_client(uri)
+            .await
+            .map_err(|e| anyhow!("Failed to connect to schedule MongoDB: {}", e))?;
+        let posts: Collection<ScheduledPost> = client.database(database).collection("scheduled_posts");
+        posts
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "id": 1 })
+                    .options(IndexOptions::builder().name("id".to_string()).unique(true).build())
+                    .build(),
+            )
+            .await?;
+        posts
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "status": 1, "publish_at": 1 })
+                    .options(IndexOptions::builder().name("status_publish_at".to_string()).build())
+                    .build(),
+            )
+            .await?;
+        Ok(Self { posts })
+    }
+
+    /// Check that the deployment is reachable
+    pub async fn ping(&self) -> Result<()> {
+        crate::utils::storage::ping_mongo(&self.posts).await
+    }
+
+    pub async fn insert(&self, post: &ScheduledPost) -> Result<()> {
+        self.posts.insert_one(post).await?;
+        Ok(())
+    }
+
+    pub async fn list(&self, status: Option<ScheduleStatus>) -> Result<Vec<ScheduledPost>> {
+        let filter = match status {
+            Some(status) => doc! { "status": status.as_str() },
+            None => doc! {},
+        };
+        let mut cursor = self.posts.find(filter).sort(doc! { "publish_at": 1 }).await?;
+        let mut posts = Vec::new();
+        while cursor.advance().await? {
+            posts.push(cursor.deserialize_current()?);
+        }
+        Ok(posts)
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Option<ScheduledPost>> {
+        Ok(self.posts.find_one(doc! { "id": id }).await?)
+    }
+
+    pub async fn cancel(&self, id: &str, now: i64) -> Result<Option<ScheduledPost>> {
+        Ok(self.posts
+            .find_one_and_update(
+                doc! { "id": id, "status": ScheduleStatus::Pending.as_str() },
+                doc! { "$set": { "status": ScheduleStatus::Cancelled.as_str(), "updated_at": now } },
+            )
+            .return_document(ReturnDocument::After)
+            .await?)
+    }
+
+    pub async fn claim_due(&self, now: i64) -> Result<Vec<ScheduledPost>> {
+        let mut due = Vec::new();
+        while let Some(post) = self.posts
+            .find_one_and_update(
+                doc! { "status": ScheduleStatus::Pending.as_str(), "publish_at": { "$lte": now } },
+                doc! { "$set": { "status": ScheduleStatus::Running.as_str(), "updated_at": now } },
+            )
+            .sort(doc! { "publish_at": 1 })
+            .return_document(ReturnDocument::After)
+            .await?
+        {
+            due.push(post);
+        }
+        Ok(due)
+    }
+
+    pub async fn set_job(&self, id: &str, job_id: &str, now: i64) -> Result<()> {
+        self.posts
+            .update_one(doc! { "id": id }, doc! { "$set": { "job_id": job_id, "updated_at": now } })
+            .await?;
+        Ok(())
+    }
+
+    pub async fn finish(&self, id: &str, result: &std::result::Result<String, String>, now: i64) -> Result<()> {
+        let set: Document = match result {
+            Ok(note_id) => doc! { "status": ScheduleStatus::Done.as_str(), "note_id": note_id, "updated_at": now },
+            Err(e) => doc! { "status": ScheduleStatus::Failed.as_str(), "error": e, "updated_at": now },
+        };
+        self.posts.update_one(doc! { "id": id }, doc! { "$set": set }).await?;
+        Ok(())
+    }
+
+    pub async fn fail_stale(&self, before: i64, now: i64) -> Result<u64> {
+        let result = self.posts
+            .update_many(
+                doc! { "status": ScheduleStatus::Running.as_str(), "updated_at": { "$lt": before } },
+                doc! { "$set": {
+                    "status": ScheduleStatus::Failed.as_str(),
+                    "error": INTERRUPTED,
+                    "updated_at": now,
+                } },
+            )
+            .await?;
+        Ok(result.modified_count)
+    }
+}
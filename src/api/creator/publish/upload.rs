@@ -0,0 +1,125 @@
+//! CDN Upload (ROS)
+//!
+//! Small files are sent with a single PUT. Large files (videos) use the
+//! S3-compatible multipart protocol exposed by the upload address:
+//! `POST ?uploads` → `PUT ?partNumber&uploadId` per chunk → `POST ?uploadId` with the part list.
+use anyhow::{anyhow, Result};
+use std::path::Path;
+use tokio::io::AsyncReadExt;
+
+use super::UploadPermit;
+use crate::api::creator::utils::{creator_client, XHS_USER_AGENT, CREATOR_ORIGIN, CREATOR_REFERER};
+
+/// Chunk size for multipart uploads
+pub const CHUNK_SIZE: usize = 5 * 1024 * 1024;
+
+fn request(client: &reqwest::Client, method: reqwest::Method, url: &str, permit: &UploadPermit) -> reqwest::RequestBuilder {
+    client
+        .request(method, url)
+        .header("x-cos-security-token", &permit.token)
+        .header("origin", CREATOR_ORIGIN)
+        .header("referer", CREATOR_REFERER)
+        .header("user-agent", XHS_USER_AGENT)
+}
+
+async fn check(response: reqwest::Response, what: &str) -> Result<reqwest::Response> {
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        return Err(anyhow!("{} failed ({}): {}", what, status, text));
+    }
+    Ok(response)
+}
+
+/// Upload one file to the CDN under a permit file id
+pub async fn upload_file(permit: &UploadPermit, file_id: &str, bytes: Vec<u8>, mime: &str) -> Result<()> {
+    let url = format!("https://{}/{}", permit.upload_addr, file_id);
+    let response = request(&creator_client()?, reqwest::Method::PUT, &url, permit)
+        .header("content-type", mime)
+        .body(bytes)
+        .send()
+        .await?;
+    check(response, &format!("Upload of {}", file_id)).await?;
+    Ok(())
+}
+
+/// Upload a local file in `CHUNK_SIZE` parts, reporting uploaded bytes after each part
+///
+/// Files no larger than one chunk fall back to a single PUT.
+pub async fn upload_file_chunked<F>(
+    permit: &UploadPermit,
+    file_id: &str,
+    path: &Path,
+    mime: &str,
+    on_progress: F,
+) -> Result<()>
+where
+    F: Fn(u64, usize, usize),
+{
+    let total = tokio::fs::metadata(path).await?.len();
+    let parts_total = (total as usize).div_ceil(CHUNK_SIZE).max(1);
+    if parts_total == 1 {
+        upload_file(permit, file_id, tokio::fs::read(path).await?, mime).await?;
+        on_progress(total, 1, 1);
+        return Ok(());
+    }
+
+    let client = creator_client()?;
+    let url = format!("https://{}/{}", permit.upload_addr, file_id);
+
+    let response = request(&client, reqwest::Method::POST, &format!("{}?uploads", url), permit)
+        .header("content-type", mime)
+        .send()
+        .await?;
+    let body = check(response, "Initiate multipart upload").await?.text().await?;
+    let upload_id = xml_value(&body, "UploadId")
+        .ok_or_else(|| anyhow!("No UploadId in multipart response: {}", body))?;
+
+    let mut file = tokio::fs::File::open(path).await?;
+    let mut parts = Vec::with_capacity(parts_total);
+    let mut uploaded = 0u64;
+    for part_number in 1..=parts_total {
+        let mut chunk = Vec::with_capacity(CHUNK_SIZE);
+        (&mut file).take(CHUNK_SIZE as u64).read_to_end(&mut chunk).await?;
+        let size = chunk.len() as u64;
+
+        let part_url = format!("{}?partNumber={}&uploadId={}", url, part_number, urlencoding::encode(&upload_id));
+        let response = request(&client, reqwest::Method::PUT, &part_url, permit)
+            .body(chunk)
+            .send()
+            .await?;
+        let response = check(response, &format!("Upload of part {}/{}", part_number, parts_total)).await?;
+        let etag = response
+            .headers()
+            .get("etag")
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| anyhow!("Part {} returned no ETag", part_number))?
+            .to_string();
+        parts.push((part_number, etag));
+
+        uploaded += size;
+        on_progress(uploaded, part_number, parts_total);
+    }
+
+    let mut complete = String::from("<CompleteMultipartUpload>");
+    for (number, etag) in &parts {
+        complete.push_str(&format!("<Part><PartNumber>{}</PartNumber><ETag>{}</ETag></Part>", number, etag));
+    }
+    complete.push_str("</CompleteMultipartUpload>");
+
+    let response = request(&client, reqwest::Method::POST, &format!("{}?uploadId={}", url, urlencoding::encode(&upload_id)), permit)
+        .header("content-type", "application/xml")
+        .body(complete)
+        .send()
+        .await?;
+    check(response, "Complete multipart upload").await?;
+    Ok(())
+}
+
+/// Extract `<tag>value</tag>` from a small XML response
+fn xml_value(xml: &str, tag: &str) -> Option<String> {
+    let open = format!("<{}>", tag);
+    let start = xml.find(&open)? + open.len();
+    let end = start + xml[start..].find(&format!("</{}>", tag))?;
+    Some(xml[start..end].to_string())
+}
@@ -0,0 +1,266 @@
+//! Video Note Publishing
+//!
+//! 1. Probe the MP4 for dimensions and duration (`moov/mvhd`, `trak/tkhd`)
+//! 2. Upload the video in chunks under a video permit
+//! 3. Upload a custom cover image, or select a frame by timestamp
+//! 4. Create the note with `video_info`
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use utoipa::ToSchema;
+
+use super::jobs::{JobHandle, PublishStage};
+use super::upload::{upload_file, upload_file_chunked};
+use super::{compose_desc, create_note, get_upload_permit, resolve_topics, ImagePayload, ImageSource};
+
+/// Request body for publishing a video note
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "title": "露营 vlog",
+    "desc": "山里的一晚",
+    "topics": ["露营"],
+    "video_path": "camping/vlog.mp4",
+    "cover_time_ms": 3000,
+    "is_private": false
+}))]
+pub struct PublishVideoRequest {
+    /// Note title (max 20 chars)
+    pub title: String,
+    /// Note body (max 1000 chars)
+    #[serde(default)]
+    pub desc: String,
+    /// Topic names; resolved to topic ids
+    #[serde(default)]
+    pub topics: Vec<String>,
+    /// MP4 file under the download root (XHS_DOWNLOAD_ROOT)
+    pub video_path: String,
+    /// Custom cover image (takes precedence over `cover_time_ms`)
+    #[serde(default)]
+    pub cover: Option<ImageSource>,
+    /// Use the video frame at this offset as the cover (default: first frame)
+    #[serde(default)]
+    pub cover_time_ms: Option<u64>,
+    /// Publish as private (only visible to self)
+    #[serde(default)]
+    pub is_private: bool,
+}
+
+/// Started video publish job
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PublishVideoJob {
+    /// Poll `/api/creator/publish/jobs/{job_id}` for progress
+    pub job_id: String,
+}
+
+/// Basic MP4 metadata
+#[derive(Debug, Clone, Copy)]
+pub struct VideoMeta {
+    pub width: u32,
+    pub height: u32,
+    pub duration_ms: u64,
+}
+
+/// Publish a video note, reporting progress on `job`; returns the note id
+pub async fn publish_video_note(
+    cookies: &HashMap<String, String>,
+    req: &PublishVideoRequest,
+    video_path: PathBuf,
+    cover: Option<ImagePayload>,
+    job: &JobHandle,
+) -> Result<String> {
+    let probe_path = video_path.clone();
+    let meta = tokio::task::spawn_blocking(move || probe_mp4(&probe_path)).await??;
+    if let Some(ts) = req.cover_time_ms {
+        if ts > meta.duration_ms {
+            return Err(anyhow!("cover_time_ms {} is beyond the video duration {}ms", ts, meta.duration_ms));
+        }
+    }
+
+    let (topics, unresolved) = resolve_topics(cookies, &req.topics).await?;
+    let desc = compose_desc(&req.title, &req.desc, &topics, &unresolved)?;
+
+    // Video upload
+    job.stage(PublishStage::Uploading);
+    let permit = get_upload_permit(cookies, "video", 1).await?;
+    let video_id = permit.file_ids[0].clone();
+    tracing::info!("[Publish] Uploading video {} as {}", video_path.display(), video_id);
+    upload_file_chunked(&permit, &video_id, &video_path, "video/mp4", |uploaded, done, total| {
+        job.update(|p| {
+            p.uploaded_bytes = uploaded;
+            p.parts_done = done;
+            p.parts_total = total;
+        });
+    }).await?;
+
+    // Cover
+    let cover_info = match cover {
+        Some(image) => {
+            job.stage(PublishStage::UploadingCover);
+            let permit = get_upload_permit(cookies, "image", 1).await?;
+            let cover_id = permit.file_ids[0].clone();
+            let (width, height) = (image.width, image.height);
+            upload_file(&permit, &cover_id, image.bytes, image.mime).await?;
+            serde_json::json!({
+                "fileid": cover_id,
+                "file_id": cover_id,
+                "width": width,
+                "height": height,
+                "frame": {"ts": 0, "is_user_select": false, "is_upload": true},
+            })
+        }
+        None => serde_json::json!({
+            "fileid": "",
+            "file_id": "",
+            "width": meta.width,
+            "height": meta.height,
+            "frame": {
+                "ts": req.cover_time_ms.unwrap_or(0) as f64 / 1000.0,
+                "is_user_select": req.cover_time_ms.is_some(),
+                "is_upload": false,
+            },
+        }),
+    };
+
+    job.stage(PublishStage::Publishing);
+    let metadata = serde_json::json!({
+        "video": {
+            "width": meta.width,
+            "height": meta.height,
+            "duration": meta.duration_ms,
+            "format": "MPEG-4",
+            "rotation": 0,
+        },
+        "audio": {},
+    });
+    let video_info = serde_json::json!({
+        "fileid": video_id,
+        "file_id": video_id,
+        "format_width": meta.width,
+        "format_height": meta.height,
+        "video_preview_type": if meta.height > meta.width { "full_vertical_screen" } else { "" },
+        "composite_metadata": metadata,
+        "timelines": [],
+        "cover": cover_info,
+        "chapters": [],
+        "chapter_sync_text": false,
+        "segments": {
+            "count": 1,
+            "need_slice": false,
+            "items": [{
+                "mute": 0,
+                "speed": 1,
+                "start": 0,
+                "duration": meta.duration_ms as f64 / 1000.0,
+                "transcoded": 0,
+                "media_source": 1,
+                "original_metadata": metadata,
+            }],
+        },
+        "entrance": "web",
+    });
+
+    let note_id = create_note(cookies, "video", &req.title, &desc, &topics, req.is_private, video_info).await?;
+    tracing::info!("[Publish] Video note published: {}", note_id);
+    Ok(note_id)
+}
+
+// ============================================================================
+// MP4 probing
+// ============================================================================
+
+/// Read width / height (first video track) and duration from an MP4 file
+pub fn probe_mp4(path: &Path) -> Result<VideoMeta> {
+    let mut file = std::fs::File::open(path)?;
+    let len = file.metadata()?.len();
+
+    // Find the top-level moov box (may be at the start or the end of the file)
+    let mut offset = 0u64;
+    let moov = loop {
+        if offset + 8 > len {
+            return Err(anyhow!("No moov box found, not an MP4 file?"));
+        }
+        file.seek(SeekFrom::Start(offset))?;
+        let mut header = [0u8; 8];
+        file.read_exact(&mut header)?;
+        let mut size = u32::from_be_bytes(header[0..4].try_into()?) as u64;
+        let mut header_len = 8;
+        if size == 1 {
+            let mut large = [0u8; 8];
+            file.read_exact(&mut large)?;
+            size = u64::from_be_bytes(large);
+            header_len = 16;
+        } else if size == 0 {
+            size = len - offset;
+        }
+        if size < header_len {
+            return Err(anyhow!("Corrupt MP4 box at offset {}", offset));
+        }
+        if &header[4..8] == b"moov" {
+            let mut body = vec![0u8; (size - header_len) as usize];
+            file.read_exact(&mut body)?;
+            break body;
+        }
+        offset += size;
+    };
+
+    let mut duration_ms = None;
+    let mut dimensions = None;
+    for (kind, body) in boxes(&moov) {
+        match kind {
+            b"mvhd" => duration_ms = mvhd_duration_ms(body),
+            b"trak" if dimensions.is_none() => {
+                dimensions = boxes(body)
+                    .find(|(kind, _)| kind == b"tkhd")
+                    .and_then(|(_, tkhd)| tkhd_dimensions(tkhd))
+                    .filter(|(w, h)| *w > 0 && *h > 0);
+            }
+            _ => {}
+        }
+    }
+
+    let (width, height) = dimensions.ok_or_else(|| anyhow!("No video track found"))?;
+    Ok(VideoMeta {
+        width,
+        height,
+        duration_ms: duration_ms.ok_or_else(|| anyhow!("No mvhd box found"))?,
+    })
+}
+
+/// Iterate child boxes of a container body
+fn boxes(data: &[u8]) -> impl Iterator<Item = (&[u8], &[u8])> {
+    let mut offset = 0usize;
+    std::iter::from_fn(move || {
+        if offset + 8 > data.len() {
+            return None;
+        }
+        let size = u32::from_be_bytes(data[offset..offset + 4].try_into().ok()?) as usize;
+        if size < 8 || offset + size > data.len() {
+            return None;
+        }
+        let item = (&data[offset + 4..offset + 8], &data[offset + 8..offset + size]);
+        offset += size;
+        Some(item)
+    })
+}
+
+fn mvhd_duration_ms(body: &[u8]) -> Option<u64> {
+    let read_u32 = |at: usize| body.get(at..at + 4).map(|b| u32::from_be_bytes(b.try_into().unwrap()) as u64);
+    let read_u64 = |at: usize| body.get(at..at + 8).map(|b| u64::from_be_bytes(b.try_into().unwrap()));
+    let (timescale, duration) = match body.first()? {
+        1 => (read_u32(20)?, read_u64(24)?),
+        _ => (read_u32(12)?, read_u32(16)?),
+    };
+    (timescale > 0).then(|| duration * 1000 / timescale)
+}
+
+fn tkhd_dimensions(body: &[u8]) -> Option<(u32, u32)> {
+    let offset = match body.first()? {
+        1 => 88,
+        _ => 76,
+    };
+    let width = u32::from_be_bytes(body.get(offset..offset + 4)?.try_into().ok()?) >> 16;
+    let height = u32::from_be_bytes(body.get(offset + 4..offset + 8)?.try_into().ok()?) >> 16;
+    Some((width, height))
+}
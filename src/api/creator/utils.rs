@@ -57,6 +57,13 @@ pub async fn sign_request(
     ))
 }
 
+/// Shared HTTP client for Creator Center and CDN upload requests
+///
+/// Built once from the installed `XhsClientBuilder`; cookies and signatures go on each request.
+pub fn creator_client() -> Result<reqwest::Client> {
+    crate::client::shared_client("creator", None, |builder| builder)
+}
+
 /// Build common headers for Creator API
 pub fn build_creator_headers() -> HeaderMap {
     let mut headers = HeaderMap::new();
@@ -76,3 +83,73 @@ pub fn cookies_to_string(cookies: &HashMap<String, String>) -> String {
         .collect::<Vec<_>>()
         .join("; ")
 }
+
+/// Send a signed Creator Center / web_api request and unwrap `data`
+///
+/// Both `creator.xiaohongshu.com` (`{code, data}`) and `edith.xiaohongshu.com/web_api`
+/// (`{success, code, data}`) wrappers are accepted; a missing `data` yields `null`.
+pub async fn creator_request(
+    cookies: &HashMap<String, String>,
+    method: &str,
+    url: &str,
+    payload: Option<serde_json::Value>,
+) -> Result<serde_json::Value> {
+    let parsed = url::Url::parse(url)?;
+    let uri = match parsed.query() {
+        Some(query) => format!("{}?{}", parsed.path(), query),
+        None => parsed.path().to_string(),
+    };
+
+    let (x_s, x_t, x_s_common) = sign_request(cookies, method, &uri, payload.clone()).await?;
+
+    let mut headers = build_creator_headers();
+    headers.insert("x-s", HeaderValue::from_str(&x_s)?);
+    headers.insert("x-t", HeaderValue::from_str(&x_t)?);
+    headers.insert("x-s-common", HeaderValue::from_str(&x_s_common)?);
+    headers.insert("cookie", HeaderValue::from_str(&cookies_to_string(cookies))?);
+
+    let client = creator_client()?;
+
+    let request = match method {
+        "GET" => client.get(url),
+        "DELETE" => client.delete(url),
+        _ => client.post(url),
+    }
+    .headers(headers);
+    let request = match payload {
+        Some(payload) => request.json(&payload),
+        None => request,
+    };
+
+    let response = request.send().await?;
+    let status = response.status();
+    let text = response.text().await?;
+
+    if status.as_u16() >= 400 {
+        return Err(anyhow!("API Error ({}): {}", status, text));
+    }
+
+    #[derive(serde::Deserialize)]
+    struct ResponseWrapper {
+        #[serde(default)]
+        code: i64,
+        #[serde(default)]
+        success: Option<bool>,
+        #[serde(default)]
+        msg: Option<String>,
+        #[serde(default)]
+        data: serde_json::Value,
+    }
+
+    let wrapper: ResponseWrapper = serde_json::from_str(&text)
+        .map_err(|e| anyhow!("Parse error: {} - Body: {}", e, text))?;
+
+    if wrapper.code != 0 || wrapper.success == Some(false) {
+        return Err(anyhow!("API Failed (code {}): {}",
+            wrapper.code,
+            wrapper.msg.unwrap_or_default()
+        ));
+    }
+
+    Ok(wrapper.data)
+}
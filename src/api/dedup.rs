@@ -0,0 +1,88 @@
+//! POST 请求去重 (Request Deduplication)
+//!
+//! 带重试逻辑的客户端经常在几秒内重复发送完全相同的 homefeed / search 请求。
+//! 在短时间窗口内，以"接口 + 请求体"的哈希为 key 直接返回上一次的结果，
+//! 避免不必要的上游调用（也避免消耗行为画像的每日配额）。
+//!
+//! 仅缓存 `success: true` 的响应，上游失败时重试仍会真正发出请求。
+//!
+//! 配置: `XHS_POST_DEDUP_SECS`，默认 5，0 表示禁用
+
+use crate::error::Result;
+use serde::{de::DeserializeOwned, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 短窗口 POST 去重缓存
+pub struct PostDedup {
+    window: Duration,
+    entries: Mutex<HashMap<u64, (Instant, serde_json::Value)>>,
+}
+
+impl Default for PostDedup {
+    fn default() -> Self {
+        Self::new(crate::config::post_dedup_window())
+    }
+}
+
+impl PostDedup {
+    pub fn new(window: Duration) -> Self {
+        Self { window, entries: Mutex::new(HashMap::new()) }
+    }
+
+    /// 窗口内存在相同请求的成功结果时直接返回 `(结果, true)`，否则执行 `fetch` 并缓存
+    ///
+    /// `payload` 应为客户端原始请求（在补全 search_id 等随机字段之前），
+    /// 否则每次重试的哈希都不同。
+    pub async fn run<P, T, F, Fut>(&self, scope: &str, payload: &P, fetch: F) -> Result<(T, bool)>
+    where
+        P: Serialize,
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        // 透传原始响应时必须真正请求上游
+        if self.window.is_zero() || crate::api::raw::is_active() {
+            return Ok((fetch().await?, false));
+        }
+
+        let key = Self::key(scope, payload)?;
+        if let Some(cached) = self.lookup(key) {
+            if let Ok(value) = serde_json::from_value(cached) {
+                tracing::info!("[Dedup] {} served from previous identical request", scope);
+                return Ok((value, true));
+            }
+        }
+
+        let result = fetch().await?;
+        let value = serde_json::to_value(&result)?;
+        if value.get("success") == Some(&serde_json::Value::Bool(true)) {
+            self.store(key, value);
+        }
+        Ok((result, false))
+    }
+
+    fn key<P: Serialize>(scope: &str, payload: &P) -> Result<u64> {
+        let mut hasher = DefaultHasher::new();
+        scope.hash(&mut hasher);
+        serde_json::to_string(payload)?.hash(&mut hasher);
+        Ok(hasher.finish())
+    }
+
+    fn lookup(&self, key: u64) -> Option<serde_json::Value> {
+        let entries = self.entries.lock().unwrap();
+        entries.get(&key)
+            .filter(|(at, _)| at.elapsed() < self.window)
+            .map(|(_, value)| value.clone())
+    }
+
+    fn store(&self, key: u64, value: serde_json::Value) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, (at, _)| at.elapsed() < self.window);
+        entries.insert(key, (Instant::now(), value));
+    }
+}
@@ -0,0 +1,183 @@
+//! 试运行 (Dry Run)
+//!
+//! 试运行时 `XhsApiClient` 照常完成准入、签名与请求构建，但在发送前停下：
+//! 第一个构建好的上游请求（URL / 请求头 / 签名 / 请求体）连同等价的 curl 命令被记录下来，
+//! 调用返回 `XhsError::DryRun`。准入阶段不消耗限流令牌、不计入行为画像、不做拟人停顿，
+//! 也不读取响应缓存 / 笔记详情缓存。
+//!
+//! - 库调用：`api.dry_run(fetch_note_detail(&api, &cache, req)).await` 返回 [`PreparedRequest`]
+//! - HTTP：在支持的路由（[`DRY_RUN_ROUTES`]）上加 `?dry_run=1`，响应体为 `ApiResponse<PreparedRequest>`
+//!
+//! 请求头包含账号 Cookie，导出的 curl 命令请按凭据对待。
+
+use std::cell::RefCell;
+use std::future::Future;
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::audit::SignatureMode;
+use crate::error::{Result, XhsError};
+
+/// 支持 `?dry_run=1` 的路由
+///
+/// 只包含所有上游请求都在 handler 任务内经 `XhsApiClient` 发出的路由；
+/// 后台任务（全量搜索 / 全量评论 / 媒体下载 / 通知轮询）与 Creator 接口不在其中
+pub const DRY_RUN_ROUTES: &[&str] = &[
+    "/api/search/trending",
+    "/api/search/recommend",
+    "/api/search/notes",
+    "/api/search/onebox",
+    "/api/search/filter",
+    "/api/search/usersearch",
+    "/api/user/me",
+    "/api/user/:user_id/notes",
+    "/api/user/boards",
+    "/api/user/boards/:board_id",
+    "/api/user/boards/:board_id/notes",
+    "/api/user/followings",
+    "/api/user/followers",
+    "/api/feed/homefeed/recommend",
+    "/api/feed/homefeed/:category",
+    "/api/note/page",
+    "/api/note/detail",
+    "/api/note/:note_id/stats",
+    "/api/note/video",
+    "/api/note/images",
+    "/api/notification/mentions",
+    "/api/notification/connections",
+    "/api/notification/likes",
+    "/api/tag/:tag_id/notes",
+];
+
+/// 构建完成但未发送的上游请求
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PreparedRequest {
+    /// 接口标识（endpoint key 或 URI）
+    pub endpoint: String,
+    pub signature: SignatureMode,
+    pub method: String,
+    pub url: String,
+    /// 请求头（按发送顺序）
+    pub headers: Vec<PreparedHeader>,
+    /// 请求体（GET 为空）
+    pub body: Option<String>,
+    /// 等价的 curl 命令
+    pub curl: String,
+}
+
+/// 请求头
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PreparedHeader {
+    pub name: String,
+    pub value: String,
+}
+
+impl PreparedRequest {
+    pub fn from_request(request: &reqwest::Request, endpoint: &str, signature: SignatureMode) -> Self {
+        let headers: Vec<PreparedHeader> = request.headers().iter()
+            .map(|(name, value)| PreparedHeader {
+                name: name.to_string(),
+                value: String::from_utf8_lossy(value.as_bytes()).into_owned(),
+            })
+            .collect();
+        let body = request.body()
+            .and_then(|body| body.as_bytes())
+            .map(|bytes| String::from_utf8_lossy(bytes).into_owned());
+        let method = request.method().to_string();
+        let url = request.url().to_string();
+        let curl = to_curl(&method, &url, &headers, body.as_deref());
+        Self { endpoint: endpoint.to_string(), signature, method, url, headers, body, curl }
+    }
+}
+
+fn to_curl(method: &str, url: &str, headers: &[PreparedHeader], body: Option<&str>) -> String {
+    let mut parts = vec!["curl".to_string()];
+    if method != "GET" {
+        parts.push(format!("-X {}", method));
+    }
+    parts.push(shell_quote(url));
+    for header in headers {
+        parts.push(format!("-H {}", shell_quote(&format!("{}: {}", header.name, header.value))));
+    }
+    if let Some(body) = body {
+        parts.push(format!("--data-raw {}", shell_quote(body)));
+    }
+    parts.push("--compressed".to_string());
+    parts.join(" \\\n  ")
+}
+
+/// POSIX shell 单引号转义
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+tokio::task_local! {
+    /// 存在即处于试运行；记录第一个构建好的请求
+    static DRY_RUN: RefCell<Option<PreparedRequest>>;
+}
+
+/// 当前任务是否处于试运行
+pub fn is_active() -> bool {
+    DRY_RUN.try_with(|_| ()).is_ok()
+}
+
+/// 试运行时记录请求（只保留第一个）并返回 `XhsError::DryRun`；否则返回 None
+pub(crate) fn intercept(request: &reqwest::Request, endpoint: &str, signature: SignatureMode) -> Option<XhsError> {
+    DRY_RUN.try_with(|slot| {
+        let mut slot = slot.borrow_mut();
+        if slot.is_none() {
+            tracing::info!("[DryRun] {} {} built, not sending", request.method(), request.url().path());
+            *slot = Some(PreparedRequest::from_request(request, endpoint, signature));
+        }
+        XhsError::DryRun
+    }).ok()
+}
+
+/// 以试运行方式执行 `future`，返回其结果与记录到的第一个请求
+pub async fn scope<F: Future>(future: F) -> (F::Output, Option<PreparedRequest>) {
+    DRY_RUN.scope(RefCell::new(None), async {
+        let output = future.await;
+        let prepared = DRY_RUN.with(|slot| slot.borrow_mut().take());
+        (output, prepared)
+    }).await
+}
+
+/// 以试运行方式执行一次 `XhsApiClient` 调用，返回本应发出的请求
+///
+/// 调用在构建请求前就失败（未登录、参数错误等）时返回该错误
+pub async fn capture<T, F: Future<Output = Result<T>>>(future: F) -> Result<PreparedRequest> {
+    match scope(future).await {
+        (_, Some(prepared)) => Ok(prepared),
+        (Err(e), None) => Err(e),
+        (Ok(_), None) => Err(XhsError::Other(anyhow::anyhow!("Dry run finished without building an upstream request"))),
+    }
+}
+
+/// 路由中间件 (`route_layer`)：`?dry_run=1` 时以试运行方式执行 handler，
+/// 返回本应发出的请求；不支持试运行的路由返回 400
+#[cfg(feature = "server")]
+pub async fn middleware(request: axum::extract::Request, next: axum::middleware::Next) -> axum::response::Response {
+    use axum::{extract::MatchedPath, http::StatusCode, response::IntoResponse};
+    use crate::response::ApiResponse;
+
+    let requested = request.uri().query().is_some_and(|query| {
+        url::form_urlencoded::parse(query.as_bytes())
+            .any(|(key, value)| key == "dry_run" && matches!(value.as_ref(), "1" | "true"))
+    });
+    if !requested {
+        return next.run(request).await;
+    }
+
+    let route = request.extensions().get::<MatchedPath>().map(|route| route.as_str().to_string());
+    if !route.as_deref().is_some_and(|route| DRY_RUN_ROUTES.contains(&route)) {
+        let msg = format!("dry_run is not supported on {}", route.as_deref().unwrap_or(request.uri().path()));
+        return (StatusCode::BAD_REQUEST, ApiResponse::<PreparedRequest>::error(msg)).into_response();
+    }
+
+    match scope(next.run(request)).await {
+        (_, Some(prepared)) => ApiResponse::ok(prepared).into_response(),
+        // 构建请求前就失败（参数错误 / 未登录等），原样返回
+        (response, None) => response,
+    }
+}
@@ -1,13 +1,17 @@
+#[cfg(feature = "server")]
 use axum::{
     extract::{Path, State},
     response::IntoResponse,
     Json,
 };
+#[cfg(feature = "server")]
 use std::sync::Arc;
+#[cfg(feature = "server")]
+use crate::server::AppState;
 use crate::{
     api::XhsApiClient,
     models::feed::{HomefeedRequest, HomefeedResponse},
-    server::AppState,
+    models::parse,
 };
 
 /// Get feed for specific category (页面-主页发现-频道)
@@ -15,6 +19,7 @@ use crate::{
 /// 
 /// 用户可自定义分页参数 (cursor_score, note_index 等)
 /// 完整分页规则请参阅 doc/homefeed_pagination.md
+#[cfg(feature = "server")]
 #[utoipa::path(
     post,
     path = "/api/feed/homefeed/{category}",
@@ -33,19 +38,16 @@ use crate::{
 pub async fn get_category_feed(
     State(state): State<Arc<AppState>>,
     Path(category): Path<String>,
-    Json(mut req): Json<HomefeedRequest>,
+    Json(req): Json<HomefeedRequest>,
 ) -> impl axum::response::IntoResponse {
-    // Map category to correct format
-    req.category = map_category(&category);
-    
-    match get_feed_internal(&state.api, &category, req).await {
-        Ok(data) => Json(data).into_response(),
-        Err(e) => Json(serde_json::json!({
-            "code": -1,
-            "success": false,
-            "msg": e.to_string(),
-            "data": null
-        })).into_response(),
+    match fetch_category_feed(&state.api, &category, req).await {
+        Ok(data) => {
+            if let Some(ref feed) = data.data {
+                state.note_cache.observe_items(&feed.items).await;
+            }
+            Json(data).into_response()
+        }
+        Err(e) => e.into_response(),
     }
 }
 
@@ -58,11 +60,15 @@ fn map_category(category: &str) -> String {
     }
 }
 
-async fn get_feed_internal(
+/// Fetch one page of a homefeed channel (library entry point behind the handler)
+pub async fn fetch_category_feed(
     api: &XhsApiClient,
     category: &str,
-    req: HomefeedRequest,
-) -> anyhow::Result<HomefeedResponse> {
+    mut req: HomefeedRequest,
+) -> crate::error::Result<HomefeedResponse> {
+    // Map category to correct format
+    req.category = map_category(category);
+    
     // Construct signature key: home_feed_fashion, home_feed_food, etc.
     let signature_key = if category == "recommend" {
         "home_feed_recommend".to_string()
@@ -73,8 +79,14 @@ async fn get_feed_internal(
     // Serialize user request to payload
     let payload = serde_json::to_value(&req)?;
     
-    // Use post_with_payload to sign and send with user-provided payload
-    let text = api.post_with_payload(&signature_key, payload).await?;
-    let feed_resp: HomefeedResponse = serde_json::from_str(&text)?;
+    // Identical retries within the dedup window reuse the previous result
+    let (mut feed_resp, deduplicated) = api.dedup()
+        .run(&signature_key, &payload, || async {
+            // Use post_with_payload to sign and send with user-provided payload
+            let text = api.post_with_payload(&signature_key, payload.clone()).await?;
+            parse::from_str::<HomefeedResponse>(&text)
+        })
+        .await?;
+    feed_resp.deduplicated = deduplicated;
     Ok(feed_resp)
 }
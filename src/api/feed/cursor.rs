@@ -0,0 +1,59 @@
+//! 首页频道翻页状态
+//!
+//! 按 doc/homefeed_pagination.md 的规则维护 cursor_score / note_index，并过滤跨页重复的笔记。
+//! `/ws/feed` 推送与 [`XhsApiClient::feed_stream`](crate::api::XhsApiClient::feed_stream) 共用。
+
+use std::collections::HashSet;
+
+use crate::models::feed::tuning::{REFRESH_TYPE_INITIAL, REFRESH_TYPE_SCROLL};
+use crate::models::feed::{FeedTuning, HomefeedItem, HomefeedRequest};
+
+/// 去重集合上限，超过后清空（长时间翻页时避免无限增长）
+const MAX_SEEN_IDS: usize = 5000;
+
+/// 单个频道的翻页状态
+pub struct FeedCursor {
+    /// 频道: recommend/fashion/food/...
+    pub category: String,
+    /// 已获取的页数
+    pub page: u32,
+    cursor_score: String,
+    note_index: i32,
+    seen: HashSet<String>,
+}
+
+impl FeedCursor {
+    pub fn new(category: String) -> Self {
+        Self {
+            category,
+            page: 0,
+            cursor_score: String::new(),
+            note_index: 0,
+            seen: HashSet::new(),
+        }
+    }
+
+    /// 构造下一页请求
+    pub fn next_request(&self) -> HomefeedRequest {
+        if self.page == 0 {
+            return HomefeedRequest::with_tuning(String::new(), REFRESH_TYPE_INITIAL);
+        }
+        let mut req = HomefeedRequest::with_tuning(String::new(), REFRESH_TYPE_SCROLL);
+        req.cursor_score = self.cursor_score.clone();
+        req.note_index = self.note_index;
+        req
+    }
+
+    /// 记录一页结果，返回之前未出现过的笔记
+    pub fn advance(&mut self, cursor_score: Option<String>, items: Vec<HomefeedItem>) -> Vec<HomefeedItem> {
+        self.note_index = FeedTuning::next_note_index(self.note_index, items.len(), self.page == 0);
+        self.page += 1;
+        if let Some(cursor) = cursor_score {
+            self.cursor_score = cursor;
+        }
+        if self.seen.len() > MAX_SEEN_IDS {
+            self.seen.clear();
+        }
+        items.into_iter().filter(|item| self.seen.insert(item.id.clone())).collect()
+    }
+}
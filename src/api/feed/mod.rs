@@ -1,2 +1,3 @@
 pub mod recommend;
 pub mod category;
+pub mod cursor;
@@ -0,0 +1,169 @@
+//! Guest Cookie Bootstrap - 纯 Rust 访客 Cookie 生成
+//!
+//! 替代 Agent 的 Playwright 访客流程：
+//! 1. 本地生成 a1 / webId（[`crate::utils::device`]，与网页端 JS 同算法）
+//! 2. 调用 login/activate 激活访客会话，获取访客 web_session
+//!
+//! 签名仍由 Agent 的 /sign 接口提供（纯算法，不启动浏览器）。
+//! gid 由设备指纹接口 (webprofile) 下发，QR 登录流程不依赖它，因此不生成。
+//!
+//! [`GuestBrowsing`] 复用同一流程：未保存用户凭据时，只读接口（笔记详情、用户主页、搜索）
+//! 以访客身份请求。尽力而为，上游可能对访客限流或要求登录。
+
+use anyhow::{anyhow, Result};
+use reqwest::header::{HeaderValue, CONTENT_TYPE};
+use std::collections::HashMap;
+use tokio::sync::Mutex;
+
+use super::login::{build_common_headers, cookies_to_string, sign_request};
+use crate::auth::UserCredentials;
+use crate::utils::device::{DeviceIdentity, DevicePlatform};
+
+const ACTIVATE_URI: &str = "/api/sns/web/v1/login/activate";
+const ACTIVATE_URL: &str = "https://edith.xiaohongshu.com/api/sns/web/v1/login/activate";
+
+/// 访客凭据的 user_id
+pub const GUEST_USER_ID: &str = "guest";
+
+/// 访客可访问的只读接口（endpoint key 或不含查询参数的 URI）
+const GUEST_ENDPOINTS: &[&str] = &[
+    "/api/sns/web/v1/feed",
+    "/api/sns/web/v1/user_posted",
+    "/api/sns/web/v1/search/notes",
+    "/api/sns/web/v1/search/onebox",
+    "/api/sns/web/v1/search/usersearch",
+    "search_trending",
+    "search_recommend",
+];
+
+/// 访客浏览模式：没有用户凭据时为只读接口提供访客凭据
+///
+/// 访客会话在首次使用时激活并缓存，登录过期 (401 / -100) 后重新激活
+pub struct GuestBrowsing {
+    enabled: bool,
+    session: Mutex<Option<UserCredentials>>,
+}
+
+impl Default for GuestBrowsing {
+    fn default() -> Self {
+        Self::new(crate::config::guest_browsing_enabled())
+    }
+}
+
+impl GuestBrowsing {
+    pub fn new(enabled: bool) -> Self {
+        Self { enabled, session: Mutex::new(None) }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// 该接口是否可以用访客身份请求
+    pub fn allows(&self, endpoint: &str) -> bool {
+        let path = endpoint.split('?').next().unwrap_or(endpoint);
+        self.enabled && GUEST_ENDPOINTS.contains(&path)
+    }
+
+    /// 是否为访客凭据
+    pub fn is_guest(credentials: &UserCredentials) -> bool {
+        credentials.user_id == GUEST_USER_ID
+    }
+
+    /// 当前访客凭据，尚未激活时激活一次（并发请求共享同一次激活）
+    pub async fn credentials(&self) -> Result<UserCredentials> {
+        let mut session = self.session.lock().await;
+        if let Some(credentials) = session.as_ref() {
+            return Ok(credentials.clone());
+        }
+        let credentials = UserCredentials::new(GUEST_USER_ID.to_string(), bootstrap_guest_cookies().await?, None);
+        *session = Some(credentials.clone());
+        Ok(credentials)
+    }
+
+    /// 丢弃访客会话，下次请求时重新激活
+    pub async fn reset(&self) {
+        if self.session.lock().await.take().is_some() {
+            tracing::info!("Guest session expired, will re-activate on next request");
+        }
+    }
+}
+
+/// 本地生成的初始访客 Cookie（激活前）
+///
+/// 访客请求使用 Windows Chrome 的 User-Agent，设备平台与之保持一致
+pub fn generate_guest_cookies() -> HashMap<String, String> {
+    let mut cookies = HashMap::new();
+    DeviceIdentity::new(DevicePlatform::Windows).apply_to(&mut cookies);
+    cookies.insert("xsecappid".to_string(), "xhs-pc-web".to_string());
+    cookies.insert("abRequestId".to_string(), uuid::Uuid::new_v4().to_string());
+    cookies
+}
+
+/// 生成并激活访客 Cookie
+///
+/// 返回包含 a1 / webId / 访客 web_session 的 Cookie 集合，可直接用于创建登录二维码
+pub async fn bootstrap_guest_cookies() -> Result<HashMap<String, String>> {
+    let mut cookies = generate_guest_cookies();
+    let payload = serde_json::json!({});
+
+    let (x_s, x_t, x_s_common, x_b3_traceid) =
+        sign_request(&cookies, "POST", ACTIVATE_URI, Some(payload.clone())).await?;
+
+    let mut headers = build_common_headers();
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json;charset=UTF-8"));
+    headers.insert("x-s", HeaderValue::from_str(&x_s)?);
+    headers.insert("x-t", HeaderValue::from_str(&x_t)?);
+    headers.insert("x-s-common", HeaderValue::from_str(&x_s_common)?);
+    headers.insert("x-b3-traceid", HeaderValue::from_str(&x_b3_traceid)?);
+    headers.insert("cookie", HeaderValue::from_str(&cookies_to_string(&cookies))?);
+
+    let client = crate::client::client_builder(None)?
+        .default_headers(headers)
+        .build()?;
+
+    tracing::info!("Activating guest session natively...");
+
+    let response = client
+        .post(ACTIVATE_URL)
+        .json(&payload)
+        .timeout(std::time::Duration::from_secs(10))
+        .send()
+        .await?;
+
+    let status = response.status();
+    for value in response.headers().get_all("set-cookie") {
+        if let Some((k, v)) = value.to_str().ok().and_then(parse_set_cookie) {
+            cookies.insert(k, v);
+        }
+    }
+    let text = response.text().await?;
+
+    if status.as_u16() == 406 || status.as_u16() == 461 {
+        return Err(anyhow!("Guest activation rejected ({})", status));
+    }
+
+    let json: serde_json::Value = serde_json::from_str(&text)
+        .map_err(|e| anyhow!("Parse error: {} - Body: {}", e, text))?;
+
+    // 部分响应只在 data.session 中返回会话而不下发 Set-Cookie
+    if !cookies.contains_key("web_session") {
+        if let Some(session) = json.pointer("/data/session").and_then(|s| s.as_str()) {
+            cookies.insert("web_session".to_string(), session.to_string());
+        }
+    }
+
+    if !cookies.contains_key("web_session") {
+        return Err(anyhow!("Guest activation returned no web_session: {}", text));
+    }
+
+    tracing::info!("Guest session activated ({} cookies)", cookies.len());
+    Ok(cookies)
+}
+
+/// 解析 Set-Cookie 头的 "name=value" 部分
+fn parse_set_cookie(header: &str) -> Option<(String, String)> {
+    let main = header.split(';').next()?;
+    let (k, v) = main.split_once('=')?;
+    Some((k.trim().to_string(), v.trim().to_string()))
+}
@@ -1,7 +1,7 @@
 //! Login API - QR Code Login Flow
 //!
 //! This module handles the XHS QR code login process:
-//! 1. Fetch guest cookies from Python Agent (Playwright)
+//! 1. Bootstrap guest cookies natively (falls back to Python Agent / Playwright)
 //! 2. Create QR code using official API
 //! 3. Poll QR code status until login success
 //! 4. Store user credentials in MongoDB
@@ -10,11 +10,13 @@
 //! - Single Responsibility: Each function does one thing
 //! - KISS: Simple, straightforward implementation
 
-use anyhow::{anyhow, Result};
+use anyhow::anyhow;
+use crate::error::{Result, XhsError};
 use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, CONTENT_TYPE, ORIGIN, REFERER, USER_AGENT};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use crate::config::get_agent_url;
+use crate::response::ApiResponse;
 
 // ============================================================================
 // Constants
@@ -97,6 +99,111 @@ pub struct QrCodeStatusData {
     pub login_info: Option<LoginInfo>,
 }
 
+impl QrCodeStatusResponse {
+    /// Map the raw response into a typed state
+    ///
+    /// Terminal failures are reported either through `code_status` or, for
+    /// some cases, a non-success business response with a message only.
+    pub fn state(&self) -> QrCodeState {
+        if let Some(code_status) = self.data.as_ref().and_then(|d| d.code_status) {
+            return QrCodeState::from_code_status(code_status);
+        }
+        if self.success {
+            return QrCodeState::Waiting;
+        }
+        let msg = self.msg.as_deref().unwrap_or_default();
+        if msg.contains("过期") || msg.contains("失效") {
+            QrCodeState::Expired
+        } else if msg.contains("取消") {
+            QrCodeState::Cancelled
+        } else if msg.contains("其他设备") || msg.contains("已被使用") {
+            QrCodeState::Conflict
+        } else {
+            QrCodeState::Error
+        }
+    }
+}
+
+/// QR code validity window; the official web page regenerates after this
+pub const QR_CODE_TTL_SECS: i64 = 120;
+
+/// Typed QR login state
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QrCodeState {
+    /// Waiting for scan (code_status=0)
+    Waiting,
+    /// Scanned, waiting for confirmation on the phone (code_status=1)
+    Scanned,
+    /// Login confirmed (code_status=2)
+    Confirmed,
+    /// QR code expired (code_status=3), a new one must be created
+    Expired,
+    /// Scan cancelled on the phone (code_status=4)
+    Cancelled,
+    /// QR code already confirmed by another device/session (code_status=5)
+    Conflict,
+    /// Request failed or status unknown
+    Error,
+}
+
+impl QrCodeState {
+    pub fn from_code_status(code_status: i32) -> Self {
+        match code_status {
+            0 => QrCodeState::Waiting,
+            1 => QrCodeState::Scanned,
+            2 => QrCodeState::Confirmed,
+            3 => QrCodeState::Expired,
+            4 => QrCodeState::Cancelled,
+            5 => QrCodeState::Conflict,
+            _ => QrCodeState::Error,
+        }
+    }
+
+    /// Legacy numeric code (-1 for errors)
+    pub fn code_status(self) -> i32 {
+        match self {
+            QrCodeState::Waiting => 0,
+            QrCodeState::Scanned => 1,
+            QrCodeState::Confirmed => 2,
+            QrCodeState::Expired => 3,
+            QrCodeState::Cancelled => 4,
+            QrCodeState::Conflict => 5,
+            QrCodeState::Error => -1,
+        }
+    }
+}
+
+/// Active QR code (persisted in the login session between create and poll)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QrCodeSession {
+    pub qr_id: String,
+    pub code: String,
+    pub url: String,
+    /// Creation time (Unix seconds)
+    pub created_at: i64,
+}
+
+impl QrCodeSession {
+    pub fn new(data: &QrCodeCreateData) -> Self {
+        Self {
+            qr_id: data.qr_id.clone(),
+            code: data.code.clone(),
+            url: data.url.clone(),
+            created_at: chrono::Utc::now().timestamp(),
+        }
+    }
+
+    /// Seconds left before the QR code expires (0 when expired)
+    pub fn remaining_secs(&self) -> i64 {
+        (QR_CODE_TTL_SECS - (chrono::Utc::now().timestamp() - self.created_at)).clamp(0, QR_CODE_TTL_SECS)
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.remaining_secs() == 0
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone, utoipa::ToSchema)]
 pub struct LoginInfo {
     pub user_id: Option<String>,
@@ -107,42 +214,234 @@ pub struct LoginInfo {
 // Public API Response Models (for Rust Server endpoints)
 // ============================================================================
 
-/// Response for guest-init endpoint
+/// Guest session returned by the guest-init endpoints
 #[derive(Debug, Serialize, utoipa::ToSchema)]
-pub struct GuestInitResponse {
-    pub success: bool,
-    pub cookies: Option<HashMap<String, String>>,
-    pub error: Option<String>,
+pub struct GuestSession {
+    /// Login session token; pass it as `session_token` to the qrcode endpoints
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_token: Option<String>,
+    pub cookies: HashMap<String, String>,
 }
 
-/// Response for qrcode/create endpoint
-#[derive(Debug, Serialize, utoipa::ToSchema)]
-pub struct CreateQrCodeResponse {
-    pub success: bool,
-    pub qr_url: Option<String>,
-    pub qr_id: Option<String>,
-    pub code: Option<String>,
-    pub error: Option<String>,
+/// Login session selector for the qrcode endpoints
+#[derive(Debug, Clone, Default, Deserialize, utoipa::IntoParams)]
+pub struct LoginSessionParams {
+    /// Token returned by guest-init (default: the most recently used login session)
+    #[serde(default)]
+    pub session_token: Option<String>,
 }
 
-/// Response for qrcode/status endpoint
+/// QR code returned by the qrcode/create endpoints
 #[derive(Debug, Serialize, utoipa::ToSchema)]
-pub struct PollStatusResponse {
-    pub success: bool,
-    pub code_status: i32,  // 0=waiting, 1=scanned, 2=confirmed
+pub struct QrCodeInfo {
+    pub qr_url: String,
+    pub qr_id: String,
+    pub code: String,
+    /// Seconds until the QR code expires
+    pub expires_in: Option<i64>,
+}
+
+/// QR login status (`data` of the qrcode/status responses and qrcode/events events)
+///
+/// Failures and notices (cancelled, conflict, credential save errors) are reported in the envelope `msg`.
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct QrStatus {
+    pub code_status: i32,  // 0=waiting, 1=scanned, 2=confirmed, 3=expired, 4=cancelled, 5=conflict, -1=error
+    pub state: QrCodeState,
+    /// Seconds until the current QR code expires
+    pub remaining_secs: Option<i64>,
+    /// Set when the expired QR code was replaced; clients must re-render it
+    pub regenerated: bool,
+    pub qr_url: Option<String>,
     pub login_info: Option<LoginInfo>,
     pub new_cookies: Option<HashMap<String, String>>,
-    pub error: Option<String>,
+}
+
+impl QrStatus {
+    pub fn new(state: QrCodeState) -> Self {
+        Self {
+            code_status: state.code_status(),
+            state,
+            remaining_secs: None,
+            regenerated: false,
+            qr_url: None,
+            login_info: None,
+            new_cookies: None,
+        }
+    }
+
+    /// Failed status (`state: error`) with the error in `msg`
+    pub fn error(msg: impl Into<String>) -> ApiResponse<Self> {
+        ApiResponse::error(msg).with_data(Self::new(QrCodeState::Error))
+    }
+}
+
+/// Query parameters for qrcode/status/wait
+#[derive(Debug, Clone, Deserialize, utoipa::IntoParams)]
+pub struct QrStatusWaitParams {
+    /// Token returned by guest-init (default: the most recently used login session)
+    #[serde(default)]
+    pub session_token: Option<String>,
+    /// Last state seen by the client; returns as soon as the state differs (default: the current state)
+    #[serde(default)]
+    pub state: Option<QrCodeState>,
+    /// Max time to hold the request, e.g. "30s", "1500ms", "1m" or plain seconds; at most 60s (default 30s)
+    #[serde(default)]
+    pub wait: Option<String>,
+}
+
+/// Result of qrcode/status/wait
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct QrStatusWait {
+    /// Whether the status changed (or reached a final state) before the wait elapsed
+    pub changed: bool,
+    /// Time the request was held (milliseconds)
+    pub waited_ms: u64,
+    #[serde(flatten)]
+    pub status: QrStatus,
+}
+
+/// Shared upstream QR status watcher behind qrcode/status/wait
+///
+/// Requests waiting on the same login session share one cached status; upstream is polled
+/// at most once per `interval` per session no matter how many clients are waiting.
+pub struct QrStatusWatcher {
+    interval: std::time::Duration,
+    latest: tokio::sync::Mutex<HashMap<String, (std::time::Instant, ApiResponse<QrStatus>)>>,
+}
+
+impl Default for QrStatusWatcher {
+    fn default() -> Self {
+        Self::new(std::time::Duration::from_secs(2))
+    }
+}
+
+impl QrStatusWatcher {
+    pub fn new(interval: std::time::Duration) -> Self {
+        Self { interval, latest: tokio::sync::Mutex::new(HashMap::new()) }
+    }
+
+    /// Current status of login session `key`; `poll` runs only when the cached status is older than `interval`
+    ///
+    /// The lock is held while polling so only one upstream request is in flight.
+    pub async fn latest<F, Fut>(&self, key: &str, poll: F) -> ApiResponse<QrStatus>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = ApiResponse<QrStatus>>,
+    {
+        let mut latest = self.latest.lock().await;
+        if let Some((at, status)) = latest.get(key) {
+            if at.elapsed() < self.interval {
+                return status.clone();
+            }
+        }
+        let status = poll().await;
+        latest.retain(|_, (at, _)| at.elapsed() < self.interval);
+        latest.insert(key.to_string(), (std::time::Instant::now(), status.clone()));
+        status
+    }
+
+    /// Time until the cached status of `key` goes stale
+    pub async fn until_next_poll(&self, key: &str) -> std::time::Duration {
+        match self.latest.lock().await.get(key) {
+            Some((at, _)) => self.interval.saturating_sub(at.elapsed()),
+            None => std::time::Duration::ZERO,
+        }
+    }
+
+    /// Drop all cached statuses (e.g. after a new QR code is created)
+    pub async fn reset(&self) {
+        self.latest.lock().await.clear();
+    }
+}
+
+/// Cookie input for import-cookies endpoint
+///
+/// Accepts either a raw `Cookie` header string copied from the browser,
+/// or a JSON object of cookie name/value pairs.
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+#[serde(untagged)]
+pub enum CookieInput {
+    /// Raw cookie string, e.g. "a1=xxx; web_session=xxx; webId=xxx"
+    Raw(String),
+    /// Cookie key-value map
+    Map(HashMap<String, String>),
+}
+
+impl CookieInput {
+    /// Normalize the input into a cookie map (empty names are dropped)
+    pub fn into_cookies(self) -> HashMap<String, String> {
+        let cookies = match self {
+            CookieInput::Raw(raw) => crate::signature::parse_cookie_string(&raw),
+            CookieInput::Map(map) => map,
+        };
+        cookies
+            .into_iter()
+            .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+            .filter(|(k, _)| !k.is_empty())
+            .collect()
+    }
+}
+
+/// Request body for import-cookies endpoint
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+#[schema(example = json!({"cookies": "a1=xxx; web_session=xxx; webId=xxx"}))]
+pub struct ImportCookiesRequest {
+    /// Raw cookie string or JSON cookie map
+    pub cookies: CookieInput,
+}
+
+/// Account saved by the import-cookies endpoint
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct ImportedAccount {
+    pub user_id: String,
+    pub nickname: Option<String>,
+}
+
+/// Query parameters for export endpoint
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct ExportCredentialsParams {
+    /// 导出格式: header / netscape / playwright (默认 header)
+    #[serde(default)]
+    #[param(value_type = Option<crate::auth::export::ExportFormat>)]
+    pub format: crate::auth::export::ExportFormat,
+    /// 是否遮蔽 Cookie 值 (用于安全分享)
+    #[serde(default)]
+    pub masked: bool,
+}
+
+/// Credentials exported by the export endpoint
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct ExportedCredentials {
+    pub format: String,
+    pub masked: bool,
+    pub user_id: String,
+    /// Exported content (Playwright format is a JSON string)
+    pub content: String,
 }
 
 // ============================================================================
 // Core Functions
 // ============================================================================
 
+/// Fetch guest cookies for QR code login
+///
+/// Generates and activates guest cookies natively; the Playwright-based
+/// Agent endpoint is only used when native activation fails.
+pub async fn fetch_guest_cookies() -> Result<HashMap<String, String>> {
+    match super::guest::bootstrap_guest_cookies().await {
+        Ok(cookies) => Ok(cookies),
+        Err(e) => {
+            tracing::warn!("Native guest bootstrap failed: {}. Falling back to Agent...", e);
+            fetch_guest_cookies_from_agent().await
+        }
+    }
+}
+
 /// Fetch guest cookies from Python Agent (uses Playwright internally)
 ///
 /// Returns a HashMap of cookies needed for QR code login
-pub async fn fetch_guest_cookies() -> Result<HashMap<String, String>> {
+pub async fn fetch_guest_cookies_from_agent() -> Result<HashMap<String, String>> {
     let client = reqwest::Client::new();
     let url = format!("{}/guest-cookies", get_agent_url());
     
@@ -161,14 +460,14 @@ pub async fn fetch_guest_cookies() -> Result<HashMap<String, String>> {
         .map_err(|e| anyhow!("Failed to parse Agent response: {}", e))?;
     
     if !result.success {
-        return Err(anyhow!("Agent error: {}", result.error.unwrap_or_default()));
+        return Err(anyhow!("Agent error: {}", result.error.unwrap_or_default()).into());
     }
     
-    result.cookies.ok_or_else(|| anyhow!("No cookies returned"))
+    result.cookies.ok_or_else(|| anyhow!("No cookies returned").into())
 }
 
 /// Get signature from Python Agent
-async fn sign_request(
+pub(crate) async fn sign_request(
     cookies: &HashMap<String, String>,
     method: &str,
     uri: &str,
@@ -198,7 +497,7 @@ async fn sign_request(
         .map_err(|e| anyhow!("Failed to parse signature response: {}", e))?;
     
     if !result.success {
-        return Err(anyhow!("Sign error: {}", result.error.unwrap_or_default()));
+        return Err(anyhow!("Sign error: {}", result.error.unwrap_or_default()).into());
     }
     
     Ok((
@@ -210,7 +509,7 @@ async fn sign_request(
 }
 
 /// Build common headers for XHS API requests
-fn build_common_headers() -> HeaderMap {
+pub(crate) fn build_common_headers() -> HeaderMap {
     let mut headers = HeaderMap::new();
     headers.insert(ACCEPT, HeaderValue::from_static("application/json, text/plain, */*"));
     headers.insert(ORIGIN, HeaderValue::from_static(XHS_ORIGIN));
@@ -219,8 +518,22 @@ fn build_common_headers() -> HeaderMap {
     headers
 }
 
+/// Common headers plus the Agent signature and cookie
+fn signed_headers(
+    cookies: &HashMap<String, String>,
+    (x_s, x_t, x_s_common, x_b3_traceid): (String, String, String, String),
+) -> anyhow::Result<HeaderMap> {
+    let mut headers = build_common_headers();
+    headers.insert("x-s", HeaderValue::from_str(&x_s)?);
+    headers.insert("x-t", HeaderValue::from_str(&x_t)?);
+    headers.insert("x-s-common", HeaderValue::from_str(&x_s_common)?);
+    headers.insert("x-b3-traceid", HeaderValue::from_str(&x_b3_traceid)?);
+    headers.insert("cookie", HeaderValue::from_str(&cookies_to_string(cookies))?);
+    Ok(headers)
+}
+
 /// Convert cookies HashMap to cookie string
-fn cookies_to_string(cookies: &HashMap<String, String>) -> String {
+pub(crate) fn cookies_to_string(cookies: &HashMap<String, String>) -> String {
     cookies
         .iter()
         .map(|(k, v)| format!("{}={}", k, v))
@@ -234,19 +547,13 @@ pub async fn create_qrcode(cookies: &HashMap<String, String>) -> Result<QrCodeCr
     let payload = serde_json::json!({"qr_type": 1});
     
     // Get signature
-    let (x_s, x_t, x_s_common, x_b3_traceid) = 
-        sign_request(cookies, "POST", uri, Some(payload.clone())).await?;
+    let signature = sign_request(cookies, "POST", uri, Some(payload.clone())).await?;
     
     // Build request
-    let mut headers = build_common_headers();
+    let mut headers = signed_headers(cookies, signature)?;
     headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json;charset=UTF-8"));
-    headers.insert("x-s", HeaderValue::from_str(&x_s)?);
-    headers.insert("x-t", HeaderValue::from_str(&x_t)?);
-    headers.insert("x-s-common", HeaderValue::from_str(&x_s_common)?);
-    headers.insert("x-b3-traceid", HeaderValue::from_str(&x_b3_traceid)?);
-    headers.insert("cookie", HeaderValue::from_str(&cookies_to_string(cookies))?);
     
-    let client = reqwest::Client::builder()
+    let client = crate::client::client_builder(None)?
         .default_headers(headers)
         .build()?;
     
@@ -264,10 +571,10 @@ pub async fn create_qrcode(cookies: &HashMap<String, String>) -> Result<QrCodeCr
     tracing::debug!("QR Create Response [{}]: {}", status, text);
     
     if status.as_u16() == 406 {
-        return Err(anyhow!("Signature rejected (406): cookies may be invalid"));
+        return Err(anyhow!("Signature rejected (406): cookies may be invalid").into());
     }
     
-    serde_json::from_str(&text).map_err(|e| anyhow!("Parse error: {} - Body: {}", e, text))
+    serde_json::from_str(&text).map_err(|e| XhsError::Parse(format!("{} - Body: {}", e, text)))
 }
 
 /// Check QR code status using official API
@@ -282,18 +589,12 @@ pub async fn check_qrcode_status(
     let url = format!("{}?qr_id={}&code={}", QRCODE_STATUS_URL, qr_id, code);
     
     // Get signature
-    let (x_s, x_t, x_s_common, x_b3_traceid) = 
-        sign_request(cookies, "GET", &uri, None).await?;
+    let signature = sign_request(cookies, "GET", &uri, None).await?;
     
     // Build request
-    let mut headers = build_common_headers();
-    headers.insert("x-s", HeaderValue::from_str(&x_s)?);
-    headers.insert("x-t", HeaderValue::from_str(&x_t)?);
-    headers.insert("x-s-common", HeaderValue::from_str(&x_s_common)?);
-    headers.insert("x-b3-traceid", HeaderValue::from_str(&x_b3_traceid)?);
-    headers.insert("cookie", HeaderValue::from_str(&cookies_to_string(cookies))?);
+    let headers = signed_headers(cookies, signature)?;
     
-    let client = reqwest::Client::builder()
+    let client = crate::client::client_builder(None)?
         .default_headers(headers)
         .build()?;
     
@@ -396,15 +697,15 @@ pub async fn sync_login_cookies(cookies: &HashMap<String, String>, target: Optio
         
     if !status.is_success() {
         tracing::error!("Agent sync returned error status: {} - Body: {}", status, text);
-        return Err(anyhow!("Agent sync failed with status {}: {}", status, text));
+        return Err(anyhow!("Agent sync failed with status {}: {}", status, text).into());
     }
 
     let result: AgentGuestCookiesResponse = serde_json::from_str(&text)
         .map_err(|e| anyhow!("Failed to parse Agent sync response: {} - Body: {}", e, text))?;
         
     if !result.success {
-        return Err(anyhow!("Agent sync error: {}", result.error.unwrap_or_default()));
+        return Err(anyhow!("Agent sync error: {}", result.error.unwrap_or_default()).into());
     }
     
-    result.cookies.ok_or_else(|| anyhow!("No cookies returned from sync"))
+    result.cookies.ok_or_else(|| anyhow!("No cookies returned from sync").into())
 }
@@ -0,0 +1,255 @@
+//! 上游维护窗口检测 (Maintenance Gate)
+//!
+//! XHS 停机维护期间所有请求必然失败，继续发送只会累积 406/461 记录、消耗账号额度。
+//! `MaintenanceGate` 在响应中识别维护标记后暂停全部出站请求与队列任务：
+//!
+//! - **Normal**: 正常放行
+//! - **Paused**: 拒绝所有请求；后台探针 (canary) 定期发送一次真实请求
+//! - **Ramping**: 探针成功后按比例放行，放行比例在 `XHS_MAINTENANCE_RAMP_SECS` 内线性升至 100%
+//!
+//! 恢复期间再次检测到维护标记会立即回到 Paused。
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use utoipa::ToSchema;
+
+use crate::error::XhsError;
+
+#[cfg(feature = "server")]
+use crate::server::AppState;
+#[cfg(feature = "server")]
+use std::sync::Arc;
+
+/// 恢复开始时的最低放行比例
+const RAMP_FLOOR: f64 = 0.1;
+
+/// 失败响应 msg / 错误页中出现即视为维护的关键字
+const MAINTENANCE_MARKERS: &[&str] = &["系统维护", "维护中", "停机维护", "服务升级", "maintenance"];
+
+/// 从上游响应中识别维护标记，返回原因
+///
+/// 只检查 503、非 2xx 响应体以及 `success: false` 的 msg，避免笔记正文中的关键字误判
+pub fn detect(status: u16, text: &str) -> Option<String> {
+    if status == 503 {
+        return Some("HTTP 503 Service Unavailable".to_string());
+    }
+
+    let haystack = if (200..300).contains(&status) {
+        let value: serde_json::Value = serde_json::from_str(text).ok()?;
+        if value.get("success").and_then(|s| s.as_bool()) != Some(false) {
+            return None;
+        }
+        value.get("msg").and_then(|m| m.as_str())?.to_string()
+    } else {
+        text.to_string()
+    };
+
+    let lower = haystack.to_lowercase();
+    MAINTENANCE_MARKERS
+        .iter()
+        .find(|marker| lower.contains(*marker))
+        .map(|marker| format!("HTTP {} 响应包含维护标记 \"{}\"", status, marker))
+}
+
+/// 维护状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MaintenancePhase {
+    Normal,
+    Paused,
+    Ramping,
+}
+
+/// 最近一次探针结果
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CanaryResult {
+    #[schema(value_type = String)]
+    pub probed_at: DateTime<Utc>,
+    pub ok: bool,
+    pub error: Option<String>,
+}
+
+/// 维护状态快照
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MaintenanceStatus {
+    pub phase: MaintenancePhase,
+    /// 进入当前状态的时间
+    #[schema(value_type = Option<String>)]
+    pub since: Option<DateTime<Utc>>,
+    /// 触发暂停的接口与原因
+    pub reason: Option<String>,
+    /// 当前放行比例 (0.0 - 1.0)
+    pub admit_ratio: f64,
+    /// 本次暂停 / 恢复期间被拒绝的请求数
+    pub rejected: u64,
+    pub last_canary: Option<CanaryResult>,
+}
+
+struct GateState {
+    phase: MaintenancePhase,
+    since: Option<(DateTime<Utc>, Instant)>,
+    reason: Option<String>,
+    rejected: u64,
+    last_canary: Option<CanaryResult>,
+}
+
+/// 维护窗口闸门
+pub struct MaintenanceGate {
+    ramp: Duration,
+    state: Mutex<GateState>,
+}
+
+impl Default for MaintenanceGate {
+    fn default() -> Self {
+        Self::new(crate::config::maintenance_ramp())
+    }
+}
+
+impl MaintenanceGate {
+    pub fn new(ramp: Duration) -> Self {
+        Self {
+            ramp,
+            state: Mutex::new(GateState {
+                phase: MaintenancePhase::Normal,
+                since: None,
+                reason: None,
+                rejected: 0,
+                last_canary: None,
+            }),
+        }
+    }
+
+    /// 进入暂停状态（已暂停时只更新原因）
+    pub fn pause(&self, endpoint: &str, reason: &str) {
+        let mut state = self.state.lock().unwrap();
+        let reason = format!("{}: {}", endpoint, reason);
+        if state.phase != MaintenancePhase::Paused {
+            tracing::warn!("[Maintenance] Upstream maintenance detected ({}), pausing all requests", reason);
+            state.phase = MaintenancePhase::Paused;
+            state.since = Some((Utc::now(), Instant::now()));
+            state.rejected = 0;
+        }
+        state.reason = Some(reason);
+    }
+
+    /// 探针成功，开始逐步恢复
+    pub fn resume(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.last_canary = Some(CanaryResult { probed_at: Utc::now(), ok: true, error: None });
+        if state.phase != MaintenancePhase::Paused {
+            return;
+        }
+        if self.ramp.is_zero() {
+            tracing::info!("[Maintenance] Canary succeeded, resuming");
+            state.phase = MaintenancePhase::Normal;
+            state.since = None;
+            state.reason = None;
+        } else {
+            tracing::info!("[Maintenance] Canary succeeded, ramping up over {:?}", self.ramp);
+            state.phase = MaintenancePhase::Ramping;
+            state.since = Some((Utc::now(), Instant::now()));
+        }
+    }
+
+    /// 记录失败的探针
+    pub fn record_canary_failure(&self, error: String) {
+        let mut state = self.state.lock().unwrap();
+        state.last_canary = Some(CanaryResult { probed_at: Utc::now(), ok: false, error: Some(error) });
+    }
+
+    /// 是否处于暂停状态（恢复期不算）
+    pub fn is_paused(&self) -> bool {
+        self.state.lock().unwrap().phase == MaintenancePhase::Paused
+    }
+
+    /// 出站请求准入
+    pub fn admit(&self) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        match state.phase {
+            MaintenancePhase::Normal => Ok(()),
+            MaintenancePhase::Paused => {
+                state.rejected += 1;
+                Err(XhsError::Maintenance(format!(
+                    "XHS 上游维护中，请求已暂停 ({})",
+                    state.reason.as_deref().unwrap_or("unknown")
+                )).into())
+            }
+            MaintenancePhase::Ramping => {
+                let ratio = self.ratio(&mut state);
+                if ratio >= 1.0 || rand::random::<f64>() < ratio {
+                    Ok(())
+                } else {
+                    state.rejected += 1;
+                    Err(XhsError::Maintenance(format!("XHS 维护恢复中，当前放行比例 {:.0}%", ratio * 100.0)).into())
+                }
+            }
+        }
+    }
+
+    /// 队列 / 定时任务是否可以领取新任务
+    ///
+    /// 与 `admit` 使用相同的放行比例，但不计入拒绝数
+    pub fn accepts_jobs(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        match state.phase {
+            MaintenancePhase::Normal => true,
+            MaintenancePhase::Paused => false,
+            MaintenancePhase::Ramping => rand::random::<f64>() < self.ratio(&mut state),
+        }
+    }
+
+    pub fn status(&self) -> MaintenanceStatus {
+        let mut state = self.state.lock().unwrap();
+        let admit_ratio = match state.phase {
+            MaintenancePhase::Normal => 1.0,
+            MaintenancePhase::Paused => 0.0,
+            MaintenancePhase::Ramping => self.ratio(&mut state),
+        };
+        MaintenanceStatus {
+            phase: state.phase,
+            since: state.since.map(|(at, _)| at),
+            reason: state.reason.clone(),
+            admit_ratio,
+            rejected: state.rejected,
+            last_canary: state.last_canary.clone(),
+        }
+    }
+
+    /// 恢复期放行比例，爬坡结束时切回 Normal
+    fn ratio(&self, state: &mut GateState) -> f64 {
+        let elapsed = state.since.map(|(_, at)| at.elapsed()).unwrap_or_default();
+        if elapsed >= self.ramp {
+            tracing::info!("[Maintenance] Ramp complete, back to normal");
+            state.phase = MaintenancePhase::Normal;
+            state.since = None;
+            state.reason = None;
+            return 1.0;
+        }
+        RAMP_FLOOR + (1.0 - RAMP_FLOOR) * elapsed.as_secs_f64() / self.ramp.as_secs_f64()
+    }
+}
+
+#[cfg(feature = "server")]
+/// 启动维护探针：暂停期间按 XHS_MAINTENANCE_PROBE_SECS 间隔发送 canary 请求
+pub fn spawn_canary(state: Arc<AppState>) {
+    let interval = crate::config::maintenance_probe_interval();
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(interval).await;
+            let gate = state.api.maintenance();
+            if !gate.is_paused() {
+                continue;
+            }
+            match state.api.probe_upstream().await {
+                Ok(()) => gate.resume(),
+                Err(e) => {
+                    tracing::info!("[Maintenance] Canary failed, staying paused: {}", e);
+                    gate.record_canary_failure(e.to_string());
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,186 @@
+//! Note Media Bundle
+//!
+//! Packs every image / video of a note plus its metadata into one zip archive,
+//! streaming each file from the CDN straight into the archive
+
+use anyhow::anyhow;
+use crate::error::Result;
+use async_zip::base::write::ZipFileWriter;
+use async_zip::{Compression, ZipEntryBuilder};
+use futures_util::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio_util::compat::FuturesAsyncWriteCompatExt;
+use tokio_util::io::StreamReader;
+use utoipa::ToSchema;
+
+use super::download::{media_request, DownloadTarget};
+use super::guard;
+use super::images::images_from_note_card;
+use super::s3::S3Client;
+use super::sidecar::{NoteMetadata, SidecarFormat};
+use super::video::videos_from_note_card;
+
+/// 笔记打包请求参数
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub struct BundleRequest {
+    /// 笔记 ID (必填)
+    pub note_id: String,
+    /// xsec_token (必填，从 feed/search 结果获取)
+    pub xsec_token: String,
+    /// 本次下载使用的代理 (可选)
+    /// 覆盖 XHS_PROXY，例如 "socks5h://127.0.0.1:1080"；"direct" 表示直连
+    #[serde(default)]
+    pub proxy: Option<String>,
+    /// 是否附带笔记元数据 metadata.json / metadata.yaml (默认 true)
+    #[serde(default = "default_include_metadata")]
+    pub include_metadata: bool,
+    /// 元数据格式: json (默认) / yaml
+    #[serde(default)]
+    pub metadata_format: SidecarFormat,
+    /// 输出位置: local (默认，zip 流式返回给调用方) / s3 (上传到 XHS_S3_* 配置的对象存储)
+    #[serde(default)]
+    pub target: DownloadTarget,
+    /// 对象键 (target = s3 时使用，默认 "{note_id}.zip")
+    #[serde(default)]
+    pub key: Option<String>,
+}
+
+fn default_include_metadata() -> bool {
+    true
+}
+
+/// 上传到对象存储的打包结果
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BundleUploadData {
+    /// s3://bucket/key
+    pub location: String,
+    /// 压缩包大小 (bytes)
+    pub file_size: u64,
+    /// 包含的媒体文件数量
+    pub media_count: usize,
+}
+
+/// 压缩包中的一个媒体文件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BundleEntry {
+    /// 压缩包内文件名
+    pub name: String,
+    pub url: String,
+}
+
+/// 从笔记卡片 (note_card) 中列出要打包的媒体
+///
+/// 视频笔记取画质最高的视频和封面；图文笔记取全部无水印图片，实况图另附 `N.mp4` 动态视频
+pub fn bundle_entries(note_card: &serde_json::Value) -> Vec<BundleEntry> {
+    let mut entries = Vec::new();
+    if let Some(video) = videos_from_note_card(note_card).into_iter().next() {
+        entries.push(BundleEntry { name: "video.mp4".to_string(), url: video.url });
+        if let Some(cover) = images_from_note_card(note_card).into_iter().next() {
+            entries.push(BundleEntry { name: "cover.jpg".to_string(), url: cover.url_original });
+        }
+        return entries;
+    }
+    for image in images_from_note_card(note_card) {
+        entries.push(BundleEntry { name: format!("{}.jpg", image.index), url: image.url_original });
+        if let Some(motion) = image.motion {
+            entries.push(BundleEntry { name: format!("{}.mp4", image.index), url: motion.url });
+        }
+    }
+    entries
+}
+
+/// 把媒体文件写成 zip 写入 `writer`
+///
+/// `metadata_format` 不为空时附带 `metadata.<ext>`（标题、作者、标签、互动数据等）与原始 `note_card.json`。
+/// 媒体文件本身已压缩，以存储 (Stored) 方式写入；返回写入的媒体文件数量
+#[tracing::instrument(name = "media.bundle", skip_all, fields(note_id = %note_id))]
+pub async fn write_bundle<W: AsyncWrite + Unpin>(
+    note_id: &str,
+    note_card: &serde_json::Value,
+    metadata_format: Option<SidecarFormat>,
+    proxy: Option<&str>,
+    writer: W,
+) -> Result<usize> {
+    let entries = bundle_entries(note_card);
+    if entries.is_empty() {
+        return Err(anyhow!("No media found in this note").into());
+    }
+    for entry in &entries {
+        guard::check_url(&entry.url)?;
+    }
+    let client = guard::client(proxy).map_err(|e| anyhow!("Failed to create HTTP client: {}", e))?;
+    write_zip(note_id, note_card, &entries, metadata_format, &client, writer).await?;
+    Ok(entries.len())
+}
+
+/// 逐个下载媒体文件写入 zip，最后写入元数据
+async fn write_zip<W: AsyncWrite + Unpin>(
+    note_id: &str,
+    note_card: &serde_json::Value,
+    entries: &[BundleEntry],
+    metadata_format: Option<SidecarFormat>,
+    client: &reqwest::Client,
+    writer: W,
+) -> anyhow::Result<()> {
+    let mut zip = ZipFileWriter::with_tokio(writer);
+
+    for entry in entries {
+        let response = media_request(client, &entry.url)
+            .send()
+            .await
+            .map_err(|e| anyhow!("Failed to download {}: {:#}", entry.name, anyhow::Error::from(e)))?;
+        if !response.status().is_success() {
+            return Err(anyhow!("Failed to download {}: HTTP {}", entry.name, response.status()));
+        }
+        let body = response
+            .bytes_stream()
+            .map_err(std::io::Error::other);
+        let mut reader = StreamReader::new(body);
+
+        let builder = ZipEntryBuilder::new(entry.name.clone().into(), Compression::Stored);
+        let mut entry_writer = zip.write_entry_stream(builder).await?.compat_write();
+        tokio::io::copy(&mut reader, &mut entry_writer).await
+            .map_err(|e| anyhow!("Failed to download {}: {}", entry.name, e))?;
+        entry_writer.into_inner().close().await?;
+    }
+
+    if let Some(format) = metadata_format {
+        let metadata = NoteMetadata::from_note_card(note_id, note_card)?.render(format)?;
+        let builder = ZipEntryBuilder::new(format!("metadata.{}", format.extension()).into(), Compression::Stored);
+        zip.write_entry_whole(builder, &metadata).await?;
+        let raw = serde_json::to_vec_pretty(note_card)?;
+        let builder = ZipEntryBuilder::new("note_card.json".to_string().into(), Compression::Stored);
+        zip.write_entry_whole(builder, &raw).await?;
+    }
+    zip.close().await?.into_inner().shutdown().await?;
+    Ok(())
+}
+
+/// 打包并上传到对象存储，打包与上传边写边传
+pub async fn upload_bundle(
+    s3: &S3Client,
+    key: &str,
+    note_id: &str,
+    note_card: &serde_json::Value,
+    metadata_format: Option<SidecarFormat>,
+    proxy: Option<&str>,
+) -> Result<BundleUploadData> {
+    let key = s3.object_key(key)?;
+    let (reader, writer) = tokio::io::duplex(64 * 1024);
+    let (written, uploaded) = tokio::join!(
+        write_bundle(note_id, note_card, metadata_format, proxy, writer),
+        s3.upload(&key, "application/zip", reader),
+    );
+    match (written, uploaded) {
+        (Ok(media_count), Ok(file_size)) => Ok(BundleUploadData { location: s3.location(&key), file_size, media_count }),
+        // 打包中途失败时上传端读到的是截断的压缩包，删除已上传的对象
+        (Err(e), Ok(_)) => {
+            if let Err(delete) = s3.delete_object(&key).await {
+                tracing::warn!("[MediaBundle] Failed to delete incomplete {}: {}", key, delete);
+            }
+            Err(e)
+        }
+        (_, Err(e)) => Err(e),
+    }
+}
@@ -2,15 +2,23 @@
 //!
 //! Downloads media files (video/image) to local storage
 
-use anyhow::{Result, anyhow};
+use anyhow::anyhow;
+use crate::error::Result;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use std::path::Path;
+use futures_util::TryStreamExt;
 use tokio::fs;
-use tokio::io::AsyncWriteExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt, BufWriter};
+use tokio_util::io::{InspectReader, StreamReader};
+
+use super::s3::S3Client;
+use super::sandbox::{self, DiskQuota};
+use super::{guard, segmented};
+use super::sidecar::SidecarFormat;
 
 /// 媒体下载请求参数
-#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, ToSchema)]
 pub struct DownloadRequest {
     /// 媒体文件 URL (必填)
     /// 支持 xhscdn.com 域名的视频和图片
@@ -18,6 +26,40 @@ pub struct DownloadRequest {
     /// 保存路径 (必填)
     /// 例如: "./downloads/video.mp4"
     pub save_path: String,
+    /// 本次下载使用的代理 (可选)
+    /// 覆盖 XHS_PROXY，例如 "socks5h://127.0.0.1:1080"；"direct" 表示直连
+    #[serde(default)]
+    pub proxy: Option<String>,
+    /// 同时在媒体文件旁写入笔记元数据 sidecar (可选，默认 false)
+    /// 需同时提供 note_id 与 xsec_token，如 video.mp4 → video.meta.json
+    #[serde(default)]
+    pub include_metadata: bool,
+    /// sidecar 格式: json (默认) / yaml
+    #[serde(default)]
+    pub metadata_format: SidecarFormat,
+    /// 媒体所属笔记 ID (include_metadata 时必填)
+    #[serde(default)]
+    pub note_id: Option<String>,
+    /// 媒体所属笔记的 xsec_token (include_metadata 时必填)
+    #[serde(default)]
+    pub xsec_token: Option<String>,
+    /// 本次下载的文件大小上限 (bytes，可选)，不能超过 XHS_DOWNLOAD_MAX_FILE_MB
+    #[serde(default)]
+    pub max_bytes: Option<u64>,
+    /// 保存位置: local (默认，下载根目录) / s3 (XHS_S3_* 配置的对象存储，save_path 作为对象键)
+    #[serde(default)]
+    pub target: DownloadTarget,
+}
+
+/// 下载保存位置
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DownloadTarget {
+    /// 本地下载根目录 (XHS_DOWNLOAD_ROOT)
+    #[default]
+    Local,
+    /// S3 兼容对象存储 (XHS_S3_ENDPOINT / XHS_S3_BUCKET)
+    S3,
 }
 
 /// 媒体下载响应
@@ -33,69 +75,223 @@ pub struct DownloadResponse {
 /// 下载结果数据
 #[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
 pub struct DownloadData {
-    /// 保存的文件路径
+    /// 保存的文件路径（对象存储为 s3://bucket/key）
     pub saved_path: String,
     /// 文件大小 (bytes)
     pub file_size: u64,
     /// 内容类型 (如 video/mp4, image/jpeg)
     pub content_type: String,
+    /// 从已有 .part 文件续传的字节数 (0 表示完整下载)
+    #[serde(default)]
+    pub resumed_bytes: u64,
+    /// 元数据 sidecar 路径 (include_metadata 时)
+    #[serde(default)]
+    pub metadata_path: Option<String>,
 }
 
-/// 允许的 CDN 域名白名单
-const ALLOWED_DOMAINS: &[&str] = &[
-    "xhscdn.com",
-    "xiaohongshu.com",
-];
-
 /// 下载媒体文件到本地
 ///
-/// 支持视频和图片的下载
+/// 支持视频和图片的下载。`save_path` 相对下载根目录（XHS_DOWNLOAD_ROOT）解析，不能越出根目录。
+/// 下载过程中写入 `<save_path>.part`，中断后再次下载同一 URL
+/// 到同一路径时通过 Range 请求续传，完成后校验文件大小并改名为 `save_path`
 pub async fn download_media(req: DownloadRequest) -> Result<DownloadResponse> {
-    // 验证 URL 域名白名单
-    if !is_url_allowed(&req.url) {
+    download_media_with_progress(req, |_, _| {}).await
+}
+
+/// 下载媒体文件到本地，每写入一块数据回调一次 `(已下载字节, 总字节)`
+///
+/// 总字节取自 Content-Length，服务端未返回时为 None
+pub async fn download_media_with_progress(
+    req: DownloadRequest,
+    on_progress: impl Fn(u64, Option<u64>),
+) -> Result<DownloadResponse> {
+    download_media_in(&crate::config::download_root(), req, on_progress).await
+}
+
+/// 以 `root` 为下载根目录下载媒体文件（如 CLI 的 `--out`）
+#[tracing::instrument(name = "media.download", skip_all, fields(url.full = %req.url, target = ?req.target))]
+pub async fn download_media_in(
+    root: &Path,
+    req: DownloadRequest,
+    on_progress: impl Fn(u64, Option<u64>),
+) -> Result<DownloadResponse> {
+    // 验证 URL 域名白名单（解析后的主机名后缀匹配；内网地址在 DNS 解析时拦截）
+    if let Err(e) = guard::check_url(&req.url) {
         return Ok(DownloadResponse {
             success: false,
-            msg: Some("URL domain not in whitelist. Only xhscdn.com and xiaohongshu.com are allowed.".to_string()),
+            msg: Some(e.to_string()),
             data: None,
         });
     }
     
+    if req.target == DownloadTarget::S3 {
+        return download_to_s3(req, on_progress).await;
+    }
+    
+    // 保存路径限制在下载根目录内
+    let save_path = match sandbox::resolve_save_path(root, &req.save_path).await {
+        Ok(path) => path,
+        Err(e) => {
+            return Ok(DownloadResponse {
+                success: false,
+                msg: Some(e.to_string()),
+                data: None,
+            });
+        }
+    };
+    let quota = DiskQuota::for_request(root, req.max_bytes).await?;
+    
     // 确保保存目录存在
-    let save_path = Path::new(&req.save_path);
     if let Some(parent) = save_path.parent() {
         if !parent.exists() {
             fs::create_dir_all(parent).await
                 .map_err(|e| anyhow!("Failed to create directory: {}", e))?;
         }
     }
+    let save_path = save_path.to_string_lossy().into_owned();
     
-    // 创建 HTTP 客户端
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(300)) // 5分钟超时
-        .build()?;
+    // 复用进程级下载客户端（5分钟超时）
+    let client = guard::client(req.proxy.as_deref())?;
     
-    // 发送下载请求
-    let response = client
-        .get(&req.url)
-        .header("Accept", "*/*")
-        .header("Accept-Language", "zh-CN,zh;q=0.9")
-        .header("Origin", "https://www.xiaohongshu.com")
-        .header("Referer", "https://www.xiaohongshu.com/")
-        .header("User-Agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/143.0.0.0 Safari/537.36")
+    let part_path = format!("{}.part", save_path);
+    let meta_path = format!("{}.part.json", save_path);
+    let segments = crate::config::download_segments();
+    let segment_size = crate::config::download_segment_size();
+    
+    // 第一次尝试续传已有的 .part；服务端拒绝 Range（416）时丢弃 .part 从头下载
+    let mut resume = PartMeta::load(&req.url, &part_path, &meta_path).await;
+    loop {
+        let mut request = media_request(&client, &req.url);
+        if let Some((offset, ref meta)) = resume {
+            request = request.header("Range", format!("bytes={}-", offset));
+            // 远端文件已变化时 If-Range 让服务端返回完整的 200 响应
+            if let Some(validator) = meta.etag.as_ref().or(meta.last_modified.as_ref()) {
+                request = request.header("If-Range", validator);
+            }
+        } else if segments > 1 {
+            // 首个分段兼作探测：返回 206 说明支持 Range，可并发下载其余分段
+            request = request.header("Range", format!("bytes=0-{}", segment_size - 1));
+        }
+        
+        let response = request
+            .send()
+            .await
+            .map_err(|e| anyhow!("Failed to download: {:#}", anyhow::Error::from(e)))?;
+        let status = response.status();
+        
+        if status == reqwest::StatusCode::RANGE_NOT_SATISFIABLE && resume.is_some() {
+            tracing::info!("[MediaDownload] Range rejected for {}, restarting from scratch", save_path);
+            PartMeta::discard(&part_path, &meta_path).await;
+            resume = None;
+            continue;
+        }
+        
+        // 检查响应状态
+        if !status.is_success() {
+            return Ok(DownloadResponse {
+                success: false,
+                msg: Some(format!("Download failed with status: {}", status)),
+                data: None,
+            });
+        }
+        
+        // 获取内容类型
+        let content_type = response
+            .headers()
+            .get("content-type")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("application/octet-stream")
+            .to_string();
+        
+        // 206 续传追加写入；200 说明服务端忽略了 Range（或文件已变化），从头写入
+        let partial = status == reqwest::StatusCode::PARTIAL_CONTENT;
+        let resumed_bytes = match resume {
+            Some((offset, _)) if partial => offset,
+            _ => 0,
+        };
+        let meta = PartMeta::from_response(&req.url, &response, resumed_bytes);
+        // 磁盘配额：已知大小时先检查并预占，未知大小时限制写入量
+        let _reservation = match meta.total_bytes {
+            Some(total) => Some(quota.reserve(total, resumed_bytes)?),
+            None => None,
+        };
+        let stream_limit = match meta.total_bytes {
+            Some(total) => Some(total.saturating_sub(resumed_bytes)),
+            None => quota.stream_limit(resumed_bytes),
+        };
+        on_progress(resumed_bytes, meta.total_bytes);
+        
+        let file_size = match meta.total_bytes {
+            // 分段下载：各分段并发写入 .part 的对应位置（文件有空洞，不支持续传）
+            Some(total) if partial && resume.is_none() => {
+                let _ = fs::remove_file(&meta_path).await;
+                let result = segmented::download(&client, &req.url, response, &part_path, total, segment_size, segments, &on_progress).await;
+                if result.is_err() {
+                    PartMeta::discard(&part_path, &meta_path).await;
+                }
+                result?
+            }
+            // 流式写入 .part，完成后再改名，大视频不整体驻留内存；中断时保留 .part 供下次续传
+            _ => {
+                meta.save(&meta_path).await;
+                stream_to_file(response, &part_path, resumed_bytes, meta.total_bytes, stream_limit, &on_progress).await?
+            }
+        };
+        
+        // 完整性校验：实际大小必须等于服务端声明的总大小
+        if let Some(expected) = meta.total_bytes {
+            if file_size != expected {
+                PartMeta::discard(&part_path, &meta_path).await;
+                return Err(anyhow!(
+                    "Downloaded size mismatch: expected {} bytes, got {} (partial file discarded)",
+                    expected, file_size
+                ).into());
+            }
+        }
+        fs::rename(&part_path, &save_path).await
+            .map_err(|e| anyhow!("Failed to move file into place: {}", e))?;
+        let _ = fs::remove_file(&meta_path).await;
+        
+        tracing::info!(
+            "[MediaDownload] Downloaded {} -> {} ({} bytes, {} resumed)", 
+            req.url, save_path, file_size, resumed_bytes
+        );
+        
+        return Ok(DownloadResponse {
+            success: true,
+            msg: None,
+            data: Some(DownloadData {
+                saved_path: save_path,
+                file_size,
+                content_type,
+                resumed_bytes,
+                metadata_path: None,
+            }),
+        });
+    }
+}
+
+/// 下载到对象存储：边下载边上传，不落本地磁盘（不支持续传与分段并发）
+async fn download_to_s3(
+    req: DownloadRequest,
+    on_progress: impl Fn(u64, Option<u64>),
+) -> Result<DownloadResponse> {
+    let s3 = S3Client::from_env()?;
+    let key = s3.object_key(&req.save_path)?;
+    let client = guard::client(req.proxy.as_deref())?;
+    
+    let response = media_request(&client, &req.url)
         .send()
         .await
-        .map_err(|e| anyhow!("Failed to download: {}", e))?;
-    
-    // 检查响应状态
-    if !response.status().is_success() {
+        .map_err(|e| anyhow!("Failed to download: {:#}", anyhow::Error::from(e)))?;
+    let status = response.status();
+    if !status.is_success() {
         return Ok(DownloadResponse {
             success: false,
-            msg: Some(format!("Download failed with status: {}", response.status())),
+            msg: Some(format!("Download failed with status: {}", status)),
             data: None,
         });
     }
-    
-    // 获取内容类型
     let content_type = response
         .headers()
         .get("content-type")
@@ -103,44 +299,163 @@ pub async fn download_media(req: DownloadRequest) -> Result<DownloadResponse> {
         .unwrap_or("application/octet-stream")
         .to_string();
     
-    // 获取文件内容
-    let bytes = response.bytes().await
-        .map_err(|e| anyhow!("Failed to read response body: {}", e))?;
-    
-    let file_size = bytes.len() as u64;
-    
-    // 写入文件
-    let mut file = fs::File::create(&req.save_path).await
-        .map_err(|e| anyhow!("Failed to create file: {}", e))?;
-    
-    file.write_all(&bytes).await
-        .map_err(|e| anyhow!("Failed to write file: {}", e))?;
+    let total_bytes = response.content_length();
+    let limit = sandbox::file_limit(req.max_bytes);
+    if let (Some(total), Some(limit)) = (total_bytes, limit) {
+        if total > limit {
+            return Err(anyhow!("File size {} bytes exceeds the per-download limit of {} bytes", total, limit).into());
+        }
+    }
     
-    file.flush().await
-        .map_err(|e| anyhow!("Failed to flush file: {}", e))?;
+    let body = response
+        .bytes_stream()
+        .map_err(std::io::Error::other);
+    let mut downloaded = 0;
+    let reader = InspectReader::new(StreamReader::new(body), |chunk: &[u8]| {
+        downloaded += chunk.len() as u64;
+        on_progress(downloaded, total_bytes);
+    });
+    // 多读 1 字节用于判断是否超出上限
+    let reader = reader.take(limit.map_or(u64::MAX, |limit| limit.saturating_add(1)));
+    let file_size = s3.upload(&key, &content_type, reader).await?;
     
-    tracing::info!(
-        "[MediaDownload] Downloaded {} -> {} ({} bytes)", 
-        req.url, req.save_path, file_size
-    );
+    let invalid = match (limit, total_bytes) {
+        (Some(limit), _) if file_size > limit => Some(format!("Download exceeds the size limit of {} bytes", limit)),
+        (_, Some(expected)) if file_size != expected => Some(format!(
+            "Downloaded size mismatch: expected {} bytes, got {}", expected, file_size
+        )),
+        _ => None,
+    };
+    if let Some(msg) = invalid {
+        if let Err(e) = s3.delete_object(&key).await {
+            tracing::warn!("[MediaDownload] Failed to delete invalid object {}: {}", key, e);
+        }
+        return Err(anyhow!("{} (object discarded)", msg).into());
+    }
     
+    let location = s3.location(&key);
+    tracing::info!("[MediaDownload] Uploaded {} -> {} ({} bytes)", req.url, location, file_size);
     Ok(DownloadResponse {
         success: true,
         msg: None,
         data: Some(DownloadData {
-            saved_path: req.save_path,
+            saved_path: location,
             file_size,
             content_type,
+            resumed_bytes: 0,
+            metadata_path: None,
         }),
     })
 }
 
-/// 检查 URL 是否在白名单中
-fn is_url_allowed(url: &str) -> bool {
-    for domain in ALLOWED_DOMAINS {
-        if url.contains(domain) {
-            return true;
+/// 带 CDN 所需请求头的 GET 请求
+pub(super) fn media_request(client: &reqwest::Client, url: &str) -> reqwest::RequestBuilder {
+    client
+        .get(url)
+        .header("Accept", "*/*")
+        .header("Accept-Language", "zh-CN,zh;q=0.9")
+        .header("Origin", "https://www.xiaohongshu.com")
+        .header("Referer", "https://www.xiaohongshu.com/")
+        .header("User-Agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/143.0.0.0 Safari/537.36")
+}
+
+/// `.part` 文件旁的续传元数据（`<save_path>.part.json`）
+#[derive(Debug, Serialize, Deserialize)]
+struct PartMeta {
+    url: String,
+    #[serde(default)]
+    etag: Option<String>,
+    #[serde(default)]
+    last_modified: Option<String>,
+    /// 完整文件大小
+    #[serde(default)]
+    total_bytes: Option<u64>,
+}
+
+impl PartMeta {
+    /// 同一 URL 留下的非空 .part 文件，返回 (已下载字节, 元数据)
+    async fn load(url: &str, part_path: &str, meta_path: &str) -> Option<(u64, PartMeta)> {
+        let offset = fs::metadata(part_path).await.ok()?.len();
+        let meta: PartMeta = serde_json::from_slice(&fs::read(meta_path).await.ok()?).ok()?;
+        if offset == 0 || meta.url != url || meta.total_bytes.is_some_and(|total| offset >= total) {
+            return None;
+        }
+        tracing::info!("[MediaDownload] Resuming {} from {} bytes", part_path, offset);
+        Some((offset, meta))
+    }
+
+    fn from_response(url: &str, response: &reqwest::Response, offset: u64) -> Self {
+        let header = |name: &str| response.headers().get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+        // 206: Content-Range "bytes start-end/total"；200: Content-Length 即总大小
+        let total_bytes = if response.status() == reqwest::StatusCode::PARTIAL_CONTENT {
+            header("content-range")
+                .and_then(|range| range.rsplit('/').next().and_then(|total| total.parse().ok()))
+                .or_else(|| response.content_length().map(|len| len + offset))
+        } else {
+            response.content_length()
+        };
+        Self {
+            url: url.to_string(),
+            etag: header("etag"),
+            last_modified: header("last-modified"),
+            total_bytes,
         }
     }
-    false
+
+    async fn save(&self, meta_path: &str) {
+        if let Ok(json) = serde_json::to_vec(self) {
+            if let Err(e) = fs::write(meta_path, json).await {
+                tracing::warn!("[MediaDownload] Failed to write {}: {}", meta_path, e);
+            }
+        }
+    }
+
+    async fn discard(part_path: &str, meta_path: &str) {
+        let _ = fs::remove_file(part_path).await;
+        let _ = fs::remove_file(meta_path).await;
+    }
 }
+
+/// 把响应体写入 `path`（`offset` > 0 时追加），返回文件的总字节数
+async fn stream_to_file(
+    response: reqwest::Response,
+    path: &str,
+    offset: u64,
+    total_bytes: Option<u64>,
+    limit: Option<u64>,
+    on_progress: &impl Fn(u64, Option<u64>),
+) -> Result<u64> {
+    let body = response
+        .bytes_stream()
+        .map_err(std::io::Error::other);
+    let mut downloaded = offset;
+    let reader = InspectReader::new(StreamReader::new(body), |chunk: &[u8]| {
+        downloaded += chunk.len() as u64;
+        on_progress(downloaded, total_bytes);
+    });
+    tokio::pin!(reader);
+    // 多读 1 字节用于判断是否超出上限
+    let mut reader = reader.take(limit.map_or(u64::MAX, |limit| limit.saturating_add(1)));
+    
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(offset > 0)
+        .truncate(offset == 0)
+        .open(path)
+        .await
+        .map_err(|e| anyhow!("Failed to create file: {}", e))?;
+    let mut writer = BufWriter::new(file);
+    let copied = tokio::io::copy(&mut reader, &mut writer).await;
+    // 已写入的部分也要落盘，供下次续传
+    writer.flush().await
+        .map_err(|e| anyhow!("Failed to flush file: {}", e))?;
+    let copied = copied.map_err(|e| anyhow!("Download interrupted, partial file kept for resume: {}", e))?;
+    if let Some(limit) = limit.filter(|limit| copied > *limit) {
+        // 超出上限的 .part 无法续传
+        let _ = fs::remove_file(path).await;
+        return Err(anyhow!("Download exceeds the size limit of {} bytes (partial file discarded)", offset + limit).into());
+    }
+    Ok(offset + copied)
+}
+
@@ -0,0 +1,194 @@
+//! Download URL Guard
+//!
+//! SSRF protection for media downloads: only allow-listed CDN hosts (parsed, suffix
+//! matched), only public IPs after DNS resolution (checked on every connection, so
+//! redirects and DNS rebinding are covered too) and a bounded redirect chain
+
+use anyhow::{Result, anyhow};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+use url::{Host, Url};
+
+/// 允许的 CDN 域名白名单（域名本身及其子域名）
+const ALLOWED_DOMAINS: &[&str] = &[
+    "xhscdn.com",
+    "xiaohongshu.com",
+];
+
+/// 最多跟随的重定向次数
+const MAX_REDIRECTS: usize = 5;
+/// 单次下载请求超时（安装的 XhsClientBuilder 设置了 timeout 时以其为准）
+const DOWNLOAD_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(300);
+
+/// 校验下载 URL：http(s) 协议、主机为白名单域名（不接受 IP 地址）
+pub fn check_url(url: &str) -> Result<Url> {
+    let parsed = Url::parse(url).map_err(|e| anyhow!("Invalid URL {}: {}", url, e))?;
+    check_parsed(&parsed)?;
+    Ok(parsed)
+}
+
+fn check_parsed(url: &Url) -> Result<()> {
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(anyhow!("Unsupported URL scheme: {}", url.scheme()));
+    }
+    if !url.username().is_empty() || url.password().is_some() {
+        return Err(anyhow!("URLs with credentials are not allowed"));
+    }
+    match url.host() {
+        Some(Host::Domain(host)) if is_allowed_host(host) => Ok(()),
+        _ => Err(anyhow!(
+            "URL host not in whitelist. Only {} (and subdomains) are allowed.",
+            ALLOWED_DOMAINS.join(", ")
+        )),
+    }
+}
+
+fn is_allowed_host(host: &str) -> bool {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    ALLOWED_DOMAINS.iter().any(|domain| {
+        host == *domain || host.strip_suffix(domain).is_some_and(|prefix| prefix.ends_with('.'))
+    })
+}
+
+/// 是否为公网地址（拒绝回环、私有、链路本地、CGNAT、组播、文档保留等地址）
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(v4) => is_public_ipv4(v4),
+            None => is_public_ipv6(ip),
+        },
+    }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        || a == 0
+        // 100.64.0.0/10 运营商级 NAT
+        || (a == 100 && (64..128).contains(&b))
+        // 198.18.0.0/15 基准测试
+        || (a == 198 && (18..20).contains(&b))
+        // 240.0.0.0/4 保留
+        || a >= 240)
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        // fc00::/7 唯一本地地址
+        || (first & 0xfe00) == 0xfc00
+        // fe80::/10 链路本地地址
+        || (first & 0xffc0) == 0xfe80
+        // 2001:db8::/32 文档保留
+        || (first == 0x2001 && ip.segments()[1] == 0x0db8))
+}
+
+/// 只返回公网地址的 DNS 解析器
+///
+/// 每次建立连接都会经过这里，重定向目标与 DNS 重绑定同样受限。
+/// 代理服务器本身（常见为本机地址）不受限制；经代理下载时目标域名由代理解析。
+struct PublicResolver {
+    exempt: Vec<String>,
+}
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = name.as_str().to_string();
+        let exempt = self.exempt.iter().any(|h| h.eq_ignore_ascii_case(&host));
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0)).await?.collect();
+            let allowed: Vec<SocketAddr> = addrs
+                .iter()
+                .copied()
+                .filter(|addr| exempt || is_public_ip(addr.ip()))
+                .collect();
+            if allowed.is_empty() {
+                tracing::warn!("[MediaDownload] Blocked {}: resolves to non-public addresses {:?}", host, addrs);
+                return Err(format!("{} does not resolve to a public address", host).into());
+            }
+            Ok(Box::new(allowed.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// 重定向策略：最多 MAX_REDIRECTS 次，每一跳都必须满足 [`check_url`]
+fn redirect_policy() -> reqwest::redirect::Policy {
+    reqwest::redirect::Policy::custom(|attempt| {
+        if attempt.previous().len() > MAX_REDIRECTS {
+            attempt.error(format!("Too many redirects (max {})", MAX_REDIRECTS))
+        } else if let Err(e) = check_parsed(attempt.url()) {
+            let message = format!("Redirect to {} blocked: {}", attempt.url(), e);
+            attempt.error(message)
+        } else {
+            attempt.follow()
+        }
+    })
+}
+
+/// 带 SSRF 防护的下载客户端（按代理复用进程级共享客户端，见 [`crate::client::shared_client`]）
+pub fn client(proxy: Option<&str>) -> Result<reqwest::Client> {
+    let exempt = [proxy.map(str::to_string), crate::config::proxy_url()]
+        .into_iter()
+        .flatten()
+        .filter_map(|p| Url::parse(&p).ok()?.host_str().map(str::to_string))
+        .collect();
+    crate::client::shared_client("media", proxy, |builder| {
+        builder
+            .timeout(DOWNLOAD_TIMEOUT)
+            .dns_resolver(Arc::new(PublicResolver { exempt }))
+            .redirect(redirect_policy())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allows_whitelisted_domains_and_subdomains() {
+        assert!(is_allowed_host("xhscdn.com"));
+        assert!(is_allowed_host("sns-video-bd.xhscdn.com"));
+        assert!(is_allowed_host("SNS-IMG-QC.XHSCDN.COM."));
+        assert!(is_allowed_host("www.xiaohongshu.com"));
+    }
+
+    #[test]
+    fn rejects_lookalike_hosts() {
+        assert!(!is_allowed_host("evilxhscdn.com"));
+        assert!(!is_allowed_host("xhscdn.com.evil.com"));
+        assert!(!is_allowed_host("xhscdn.co"));
+        assert!(!is_allowed_host(""));
+    }
+
+    #[test]
+    fn public_ipv4() {
+        for ip in ["1.1.1.1", "8.8.8.8", "100.63.255.255", "100.128.0.1", "198.20.0.1"] {
+            assert!(is_public_ip(ip.parse().unwrap()), "{}", ip);
+        }
+        for ip in [
+            "0.0.0.0", "0.1.2.3", "127.0.0.1", "10.0.0.1", "172.16.0.1", "192.168.1.1", "169.254.169.254",
+            "255.255.255.255", "192.0.2.1", "224.0.0.1", "100.64.0.1", "100.127.255.255", "198.18.0.1", "240.0.0.1",
+        ] {
+            assert!(!is_public_ip(ip.parse().unwrap()), "{}", ip);
+        }
+    }
+
+    #[test]
+    fn public_ipv6() {
+        assert!(is_public_ip("2606:4700:4700::1111".parse().unwrap()));
+        assert!(is_public_ip("::ffff:8.8.8.8".parse().unwrap()));
+        for ip in ["::", "::1", "ff02::1", "fc00::1", "fd12:3456::1", "fe80::1", "2001:db8::1", "::ffff:127.0.0.1", "::ffff:10.0.0.1"] {
+            assert!(!is_public_ip(ip.parse().unwrap()), "{}", ip);
+        }
+    }
+}
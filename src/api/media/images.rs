@@ -1,9 +1,11 @@
 //! Image URL Extraction API
 //!
-//! Extracts image download URLs from note details
+//! Extracts image download URLs from note details, including the motion
+//! clips attached to live photos
 
+use super::video::{live_photo_videos, VideoItem};
 use crate::api::XhsApiClient;
-use anyhow::{Result, anyhow};
+use crate::error::{Result, XhsError};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
@@ -40,6 +42,9 @@ pub struct ImagesData {
     pub desc: Option<String>,
     /// 图片数量
     pub image_count: usize,
+    /// 其中实况图 (Live Photo) 数量
+    #[serde(default)]
+    pub live_photo_count: usize,
     /// 图片列表
     pub images: Vec<ImageItem>,
 }
@@ -57,6 +62,12 @@ pub struct ImageItem {
     pub url_watermark: String,
     /// 无水印图片 URL (url_pre / WB_PRV)
     pub url_original: String,
+    /// 是否为实况图 (Live Photo)
+    #[serde(default)]
+    pub live_photo: bool,
+    /// 实况图的动态视频 (画质最高的一路)，普通图片为空
+    #[serde(default)]
+    pub motion: Option<VideoItem>,
 }
 
 /// 获取图片下载地址
@@ -91,7 +102,7 @@ pub async fn get_image_urls(api: &XhsApiClient, req: ImagesRequest) -> Result<Im
     // 提取笔记卡片
     let note_card = raw
         .pointer("/data/items/0/note_card")
-        .ok_or_else(|| anyhow!("No note_card found in response"))?;
+        .ok_or_else(|| XhsError::Parse("No note_card found in response".to_string()))?;
     
     // 检查笔记类型 (normal = 图文笔记)
     let note_type = note_card.get("type").and_then(|v| v.as_str()).unwrap_or("");
@@ -113,15 +124,7 @@ pub async fn get_image_urls(api: &XhsApiClient, req: ImagesRequest) -> Result<Im
     let desc = note_card.get("desc").and_then(|v| v.as_str()).map(|s| s.to_string());
     
     // 提取图片列表
-    let mut images = Vec::new();
-    
-    if let Some(image_list) = note_card.get("image_list").and_then(|v| v.as_array()) {
-        for (idx, img) in image_list.iter().enumerate() {
-            if let Some(item) = parse_image_item(img, idx + 1) {
-                images.push(item);
-            }
-        }
-    }
+    let images = images_from_note_card(note_card);
     
     if images.is_empty() {
         return Ok(ImagesResponse {
@@ -140,11 +143,26 @@ pub async fn get_image_urls(api: &XhsApiClient, req: ImagesRequest) -> Result<Im
             author,
             desc,
             image_count: images.len(),
+            live_photo_count: images.iter().filter(|image| image.live_photo).count(),
             images,
         }),
     })
 }
 
+/// 从笔记卡片 (note_card) 中提取图片列表
+pub fn images_from_note_card(note_card: &serde_json::Value) -> Vec<ImageItem> {
+    note_card
+        .get("image_list")
+        .and_then(|v| v.as_array())
+        .map(|list| {
+            list.iter()
+                .enumerate()
+                .filter_map(|(idx, img)| parse_image_item(img, idx + 1))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 /// 解析单张图片
 fn parse_image_item(img: &serde_json::Value, index: usize) -> Option<ImageItem> {
     let width = img.get("width")?.as_i64()? as i32;
@@ -179,11 +197,18 @@ fn parse_image_item(img: &serde_json::Value, index: usize) -> Option<ImageItem>
         })?
         .to_string();
     
+    // 实况图：静态图之外附带一段短视频
+    let motion = live_photo_videos(img).into_iter().next();
+    let live_photo = motion.is_some()
+        || img.get("live_photo").and_then(|v| v.as_bool()).unwrap_or(false);
+    
     Some(ImageItem {
         index,
         width,
         height,
         url_watermark,
         url_original,
+        live_photo,
+        motion,
     })
 }
@@ -0,0 +1,202 @@
+//! Download Jobs
+//!
+//! Large videos take minutes to download, so `/api/media/download` queues a
+//! job and returns immediately. Jobs run in a bounded worker pool
+//! (`XHS_DOWNLOAD_WORKERS`) and callers poll `/api/media/jobs/{id}` for
+//! progress.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Semaphore;
+use utoipa::ToSchema;
+
+use super::download::{download_media_with_progress, DownloadRequest};
+use super::sidecar::{attach_sidecar, NoteMetadata};
+
+/// 完成的任务最多保留多少个，超出后丢弃最早完成的
+const MAX_FINISHED_JOBS: usize = 500;
+
+/// 下载任务状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadStatus {
+    /// 等待空闲 worker
+    Queued,
+    Running,
+    Done,
+    Failed,
+}
+
+impl DownloadStatus {
+    pub fn is_finished(self) -> bool {
+        matches!(self, DownloadStatus::Done | DownloadStatus::Failed)
+    }
+}
+
+/// 下载任务进度
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DownloadProgress {
+    pub job_id: String,
+    pub status: DownloadStatus,
+    pub url: String,
+    pub save_path: String,
+    /// 已下载字节
+    pub downloaded_bytes: u64,
+    /// 总字节 (Content-Length，未知时为 null)
+    pub total_bytes: Option<u64>,
+    /// 内容类型 (status = done)
+    pub content_type: Option<String>,
+    /// 元数据 sidecar 路径 (status = done 且请求 include_metadata)
+    pub metadata_path: Option<String>,
+    /// 失败原因 (status = failed)
+    pub error: Option<String>,
+    #[schema(value_type = String)]
+    pub created_at: DateTime<Utc>,
+    #[schema(value_type = Option<String>)]
+    pub started_at: Option<DateTime<Utc>>,
+    #[schema(value_type = String)]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 运行中的任务用来上报进度
+#[derive(Clone)]
+struct JobHandle(Arc<Mutex<DownloadProgress>>);
+
+impl JobHandle {
+    fn update(&self, f: impl FnOnce(&mut DownloadProgress)) {
+        let mut progress = self.0.lock().unwrap();
+        f(&mut progress);
+        progress.updated_at = Utc::now();
+    }
+
+    fn snapshot(&self) -> DownloadProgress {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+/// 下载任务队列
+pub struct DownloadJobs {
+    jobs: Mutex<HashMap<String, JobHandle>>,
+    workers: Arc<Semaphore>,
+}
+
+impl Default for DownloadJobs {
+    fn default() -> Self {
+        Self::new(crate::config::download_workers())
+    }
+}
+
+impl DownloadJobs {
+    pub fn new(workers: usize) -> Self {
+        Self {
+            jobs: Mutex::new(HashMap::new()),
+            workers: Arc::new(Semaphore::new(workers.max(1))),
+        }
+    }
+
+    /// 提交下载任务，立即返回排队中的任务
+    ///
+    /// 传入 `metadata` 时下载完成后在文件旁写入 sidecar（格式取 `req.metadata_format`）
+    pub fn submit(&self, req: DownloadRequest, metadata: Option<NoteMetadata>) -> DownloadProgress {
+        let now = Utc::now();
+        let handle = JobHandle(Arc::new(Mutex::new(DownloadProgress {
+            job_id: uuid::Uuid::new_v4().simple().to_string(),
+            status: DownloadStatus::Queued,
+            url: req.url.clone(),
+            save_path: req.save_path.clone(),
+            downloaded_bytes: 0,
+            total_bytes: None,
+            content_type: None,
+            metadata_path: None,
+            error: None,
+            created_at: now,
+            started_at: None,
+            updated_at: now,
+        })));
+        let snapshot = handle.snapshot();
+        self.register(handle.clone());
+
+        let workers = self.workers.clone();
+        tokio::spawn(async move {
+            let Ok(_permit) = workers.acquire_owned().await else {
+                return;
+            };
+            handle.update(|p| {
+                p.status = DownloadStatus::Running;
+                p.started_at = Some(Utc::now());
+            });
+
+            let (format, target) = (req.metadata_format, req.target);
+            let progress = handle.clone();
+            let mut result = download_media_with_progress(req, move |downloaded, total| {
+                progress.update(|p| {
+                    p.downloaded_bytes = downloaded;
+                    p.total_bytes = total;
+                });
+            }).await;
+            if let (Ok(resp), Some(metadata)) = (result.as_mut(), metadata.as_ref()) {
+                if let Err(e) = attach_sidecar(resp, metadata, format, target).await {
+                    result = Err(e.into());
+                }
+            }
+
+            handle.update(|p| match result {
+                Ok(resp) => match resp.data {
+                    Some(data) if resp.success => {
+                        p.status = DownloadStatus::Done;
+                        p.downloaded_bytes = data.file_size;
+                        p.content_type = Some(data.content_type);
+                        p.metadata_path = data.metadata_path;
+                    }
+                    _ => {
+                        p.status = DownloadStatus::Failed;
+                        p.error = Some(resp.msg.unwrap_or_else(|| "Download failed".to_string()));
+                    }
+                },
+                Err(e) => {
+                    p.status = DownloadStatus::Failed;
+                    p.error = Some(e.to_string());
+                }
+            });
+            let finished = handle.snapshot();
+            match finished.error {
+                Some(ref error) => tracing::warn!("[DownloadJob] {} failed: {}", finished.job_id, error),
+                None => tracing::info!("[DownloadJob] {} done ({} bytes)", finished.job_id, finished.downloaded_bytes),
+            }
+        });
+
+        snapshot
+    }
+
+    fn register(&self, handle: JobHandle) {
+        let mut jobs = self.jobs.lock().unwrap();
+        let mut finished: Vec<(DateTime<Utc>, String)> = jobs
+            .iter()
+            .map(|(id, job)| (job.snapshot(), id))
+            .filter(|(p, _)| p.status.is_finished())
+            .map(|(p, id)| (p.updated_at, id.clone()))
+            .collect();
+        if finished.len() >= MAX_FINISHED_JOBS {
+            finished.sort();
+            for (_, id) in finished.iter().take(finished.len() + 1 - MAX_FINISHED_JOBS) {
+                jobs.remove(id);
+            }
+        }
+        let job_id = handle.snapshot().job_id;
+        jobs.insert(job_id, handle);
+    }
+
+    pub fn get(&self, job_id: &str) -> Option<DownloadProgress> {
+        self.jobs.lock().unwrap().get(job_id).map(JobHandle::snapshot)
+    }
+}
+
+/// 提交下载的查询参数
+#[derive(Debug, Default, serde::Deserialize, utoipa::IntoParams)]
+pub struct DownloadParams {
+    /// true 时同步等待下载完成并返回 DownloadResponse（旧行为）
+    #[serde(default)]
+    pub wait: bool,
+}
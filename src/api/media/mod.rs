@@ -1,10 +1,20 @@
 //! Media API Module
 //!
 //! Handles media file operations: video URL extraction, image URL extraction, file download
+//! (segmented for large files, SSRF-guarded, sandboxed to the download root), note zip bundles,
+//! metadata sidecars, download jobs, signed file URLs
 
 pub mod video;
 pub mod images;
 pub mod download;
+pub mod bundle;
+pub mod sidecar;
+pub mod guard;
+pub mod sandbox;
+pub mod s3;
+mod segmented;
+pub mod jobs;
+pub mod signed_url;
 
 pub use video::*;
 pub use images::*;
@@ -0,0 +1,289 @@
+//! S3-Compatible Object Storage
+//!
+//! Streams downloads straight into an S3-compatible bucket (AWS S3, MinIO, R2, ...)
+//! instead of local disk. Requests are SigV4-signed with path-style addressing;
+//! anything larger than one part goes through a multipart upload, so memory use
+//! is bounded by `XHS_S3_PART_MB`.
+
+use anyhow::anyhow;
+use crate::error::Result;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use reqwest::Method;
+use sha2::{Digest, Sha256};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use url::Url;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Bucket settings, from `XHS_S3_*`
+#[derive(Debug, Clone)]
+pub struct S3Config {
+    pub endpoint: String,
+    pub bucket: String,
+    pub region: String,
+    pub access_key: String,
+    pub secret_key: String,
+    /// Prepended to every object key
+    pub prefix: String,
+    /// Multipart upload part size (bytes)
+    pub part_size: usize,
+}
+
+impl S3Config {
+    /// Read the configuration, failing when endpoint / bucket / credentials are missing
+    pub fn from_env() -> Result<Self> {
+        let (Some(endpoint), Some(bucket), Some((access_key, secret_key))) = (
+            crate::config::s3_endpoint(),
+            crate::config::s3_bucket(),
+            crate::config::s3_credentials(),
+        ) else {
+            return Err(anyhow!(
+                "Object storage is not configured (set XHS_S3_ENDPOINT, XHS_S3_BUCKET, XHS_S3_ACCESS_KEY and XHS_S3_SECRET_KEY)"
+            ).into());
+        };
+        Ok(Self {
+            endpoint,
+            bucket,
+            region: crate::config::s3_region(),
+            access_key,
+            secret_key,
+            prefix: crate::config::s3_prefix(),
+            part_size: crate::config::s3_part_size(),
+        })
+    }
+}
+
+/// Minimal S3 client: put / multipart upload / delete
+pub struct S3Client {
+    config: S3Config,
+    http: reqwest::Client,
+}
+
+impl S3Client {
+    pub fn new(config: S3Config) -> Result<Self> {
+        Url::parse(&config.endpoint).map_err(|e| anyhow!("Invalid XHS_S3_ENDPOINT {}: {}", config.endpoint, e))?;
+        Ok(Self { config, http: reqwest::Client::builder().build()? })
+    }
+
+    pub fn from_env() -> Result<Self> {
+        Self::new(S3Config::from_env()?)
+    }
+
+    /// Full object key for a relative path: prefix + path, rejecting `.` / `..` segments
+    pub fn object_key(&self, path: &str) -> Result<String> {
+        let path = path.trim_start_matches("./").trim_start_matches('/');
+        if path.is_empty() || path.split('/').any(|s| s.is_empty() || s == "." || s == "..") {
+            return Err(anyhow!("Invalid object key: {}", path).into());
+        }
+        Ok(format!("{}{}", self.config.prefix, path))
+    }
+
+    /// `s3://bucket/key`
+    pub fn location(&self, key: &str) -> String {
+        format!("s3://{}/{}", self.config.bucket, key)
+    }
+
+    /// Inverse of [`location`](Self::location): the full object key, if the location is in this bucket
+    pub fn key_from_location<'a>(&self, location: &'a str) -> Option<&'a str> {
+        location.strip_prefix("s3://")?.strip_prefix(self.config.bucket.as_str())?.strip_prefix('/')
+    }
+
+    /// Upload a small object in one request
+    pub async fn put_object(&self, key: &str, content_type: &str, body: Vec<u8>) -> Result<()> {
+        self.send(Method::PUT, key, &[], body, Some(content_type)).await?;
+        Ok(())
+    }
+
+    pub async fn delete_object(&self, key: &str) -> Result<()> {
+        self.send(Method::DELETE, key, &[], Vec::new(), None).await?;
+        Ok(())
+    }
+
+    /// Stream `reader` into `key`, returning the number of bytes uploaded
+    ///
+    /// Bodies that fit into one part are sent with a single PUT; larger ones use a
+    /// multipart upload, aborted if any part fails.
+    pub async fn upload<R: AsyncRead + Unpin>(&self, key: &str, content_type: &str, mut reader: R) -> Result<u64> {
+        let first = read_part(&mut reader, self.config.part_size).await?;
+        if first.len() < self.config.part_size {
+            let size = first.len() as u64;
+            self.put_object(key, content_type, first).await?;
+            return Ok(size);
+        }
+
+        let created = self.send(Method::POST, key, &[("uploads", "")], Vec::new(), Some(content_type)).await?;
+        let body = created.text().await?;
+        let upload_id = xml_value(&body, "UploadId")
+            .ok_or_else(|| anyhow!("CreateMultipartUpload returned no UploadId"))?
+            .to_string();
+
+        match self.upload_parts(key, &upload_id, first, &mut reader).await {
+            Ok(size) => Ok(size),
+            Err(e) => {
+                if let Err(abort) = self.send(Method::DELETE, key, &[("uploadId", &upload_id)], Vec::new(), None).await {
+                    tracing::warn!("[S3] Failed to abort multipart upload {}: {}", upload_id, abort);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    async fn upload_parts<R: AsyncRead + Unpin>(
+        &self,
+        key: &str,
+        upload_id: &str,
+        first: Vec<u8>,
+        reader: &mut R,
+    ) -> Result<u64> {
+        let mut etags = Vec::new();
+        let mut size = 0u64;
+        let mut part = first;
+        while !part.is_empty() {
+            let number = (etags.len() + 1).to_string();
+            size += part.len() as u64;
+            let response = self
+                .send(Method::PUT, key, &[("partNumber", &number), ("uploadId", upload_id)], part, None)
+                .await?;
+            let etag = response
+                .headers()
+                .get("etag")
+                .and_then(|v| v.to_str().ok())
+                .ok_or_else(|| anyhow!("UploadPart {} returned no ETag", number))?
+                .to_string();
+            etags.push(etag);
+            part = read_part(reader, self.config.part_size).await?;
+        }
+
+        let parts: String = etags
+            .iter()
+            .enumerate()
+            .map(|(i, etag)| format!("<Part><PartNumber>{}</PartNumber><ETag>{}</ETag></Part>", i + 1, etag))
+            .collect();
+        let body = format!("<CompleteMultipartUpload>{}</CompleteMultipartUpload>", parts);
+        let response = self
+            .send(Method::POST, key, &[("uploadId", upload_id)], body.into_bytes(), Some("application/xml"))
+            .await?;
+        // CompleteMultipartUpload can fail with 200 + <Error> in the body
+        let text = response.text().await?;
+        if text.contains("<Error>") {
+            return Err(anyhow!("CompleteMultipartUpload failed: {}", xml_value(&text, "Message").unwrap_or(&text)).into());
+        }
+        Ok(size)
+    }
+
+    /// Sign and send one request, turning non-2xx responses into errors
+    async fn send(
+        &self,
+        method: Method,
+        key: &str,
+        query: &[(&str, &str)],
+        body: Vec<u8>,
+        content_type: Option<&str>,
+    ) -> Result<reqwest::Response> {
+        let mut url = Url::parse(&self.config.endpoint)
+            .map_err(|e| anyhow!("Invalid XHS_S3_ENDPOINT {}: {}", self.config.endpoint, e))?;
+        let path = format!("{}/{}/{}", url.path().trim_end_matches('/'), self.config.bucket, key);
+        url.set_path(&path.split('/').map(uri_encode).collect::<Vec<_>>().join("/"));
+        let mut pairs: Vec<(String, String)> = query.iter().map(|(k, v)| (uri_encode(k), uri_encode(v))).collect();
+        pairs.sort();
+        let canonical_query = pairs.iter().map(|(k, v)| format!("{}={}", k, v)).collect::<Vec<_>>().join("&");
+        url.set_query(Some(&canonical_query).filter(|q| !q.is_empty()).map(String::as_str));
+
+        let payload_hash = hex(&Sha256::digest(&body));
+        let mut request = self.http.request(method.clone(), url.clone());
+        for (name, value) in self.sign(&method, &url, &canonical_query, &payload_hash, Utc::now()) {
+            request = request.header(name, value);
+        }
+        if let Some(content_type) = content_type {
+            request = request.header("Content-Type", content_type);
+        }
+
+        let response = request.body(body).send().await
+            .map_err(|e| anyhow!("Object storage request failed: {:#}", anyhow::Error::from(e)))?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            let detail = xml_value(&text, "Message").or_else(|| xml_value(&text, "Code")).unwrap_or(&text);
+            return Err(anyhow!("Object storage {} {} returned {}: {}", method, key, status, detail).into());
+        }
+        Ok(response)
+    }
+
+    /// AWS Signature Version 4 headers (`host`, `x-amz-content-sha256`, `x-amz-date`, `authorization`)
+    fn sign(
+        &self,
+        method: &Method,
+        url: &Url,
+        canonical_query: &str,
+        payload_hash: &str,
+        now: DateTime<Utc>,
+    ) -> Vec<(&'static str, String)> {
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let host = match url.port() {
+            Some(port) => format!("{}:{}", url.host_str().unwrap_or_default(), port),
+            None => url.host_str().unwrap_or_default().to_string(),
+        };
+
+        let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+        let canonical_request = format!(
+            "{}\n{}\n{}\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+            method, url.path(), canonical_query, host, payload_hash, amz_date, signed_headers, payload_hash
+        );
+        let scope = format!("{}/{}/s3/aws4_request", date, self.config.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date, scope, hex(&Sha256::digest(canonical_request.as_bytes()))
+        );
+
+        let key = [date.as_str(), self.config.region.as_str(), "s3", "aws4_request"]
+            .iter()
+            .fold(format!("AWS4{}", self.config.secret_key).into_bytes(), |key, part| hmac(&key, part.as_bytes()));
+        let signature = hex(&hmac(&key, string_to_sign.as_bytes()));
+
+        vec![
+            ("x-amz-content-sha256", payload_hash.to_string()),
+            ("x-amz-date", amz_date),
+            ("authorization", format!(
+                "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+                self.config.access_key, scope, signed_headers, signature
+            )),
+        ]
+    }
+}
+
+/// Read up to `size` bytes (less only at end of stream)
+async fn read_part<R: AsyncRead + Unpin>(reader: &mut R, size: usize) -> Result<Vec<u8>> {
+    let mut buf = Vec::with_capacity(size);
+    reader.take(size as u64).read_to_end(&mut buf).await
+        .map_err(|e| anyhow!("Failed to read upload body: {}", e))?;
+    Ok(buf)
+}
+
+fn hmac(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// SigV4 URI encoding: everything except unreserved characters
+fn uri_encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Text of the first `<tag>` element
+fn xml_value<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let start = xml.find(&format!("<{}>", tag))? + tag.len() + 2;
+    let end = xml[start..].find(&format!("</{}>", tag))? + start;
+    Some(&xml[start..end])
+}
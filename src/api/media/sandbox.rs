@@ -0,0 +1,139 @@
+//! Download Root Sandbox
+//!
+//! Every download is written inside the download root (`XHS_DOWNLOAD_ROOT`):
+//! save paths are resolved against it, `..` / foreign absolute paths and
+//! symlinks leading out of it are rejected. Disk usage is bounded by an
+//! optional per-download limit and a global quota for the whole root.
+
+use anyhow::{anyhow, Result};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// 进行中下载已预占的字节数（尚未写入磁盘的部分）
+static RESERVED_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// 把路径规范为下载根目录内的相对路径，拒绝越界
+///
+/// 接受 `a/1.jpg`、`./downloads/a/1.jpg`（带根目录前缀）以及位于根目录下的绝对路径
+pub fn relative_to_root(root: &Path, path: &str) -> Result<PathBuf> {
+    let path = Path::new(path);
+    let relative = if path.is_absolute() {
+        let root = root.canonicalize()
+            .map_err(|e| anyhow!("Download root unavailable: {}", e))?;
+        path.strip_prefix(&root)
+            .map_err(|_| anyhow!("Path is outside the download root"))?
+            .to_path_buf()
+    } else {
+        let path = without_cur_dir(path);
+        let root = without_cur_dir(root);
+        path.strip_prefix(&root).map(Path::to_path_buf).unwrap_or(path)
+    };
+
+    if relative.as_os_str().is_empty()
+        || !relative.components().all(|c| matches!(c, Component::Normal(_)))
+    {
+        return Err(anyhow!("Invalid file path"));
+    }
+    Ok(relative)
+}
+
+fn without_cur_dir(path: &Path) -> PathBuf {
+    path.components().filter(|c| !matches!(c, Component::CurDir)).collect()
+}
+
+/// 解析保存路径为根目录内的绝对路径（必要时创建根目录）
+///
+/// 已存在的上级目录经符号链接指向根目录之外时拒绝
+pub async fn resolve_save_path(root: &Path, save_path: &str) -> Result<PathBuf> {
+    tokio::fs::create_dir_all(root).await
+        .map_err(|e| anyhow!("Failed to create download root: {}", e))?;
+    let canonical_root = tokio::fs::canonicalize(root).await
+        .map_err(|e| anyhow!("Download root unavailable: {}", e))?;
+    let full = canonical_root.join(relative_to_root(root, save_path)?);
+
+    let mut existing = full.as_path();
+    while !tokio::fs::try_exists(existing).await.unwrap_or(false) {
+        existing = existing.parent().unwrap_or(&canonical_root);
+    }
+    let resolved = tokio::fs::canonicalize(existing).await
+        .map_err(|e| anyhow!("Failed to resolve save path: {}", e))?;
+    if !resolved.starts_with(&canonical_root) {
+        return Err(anyhow!("Path is outside the download root"));
+    }
+    Ok(full)
+}
+
+/// 单个文件的大小上限：XHS_DOWNLOAD_MAX_FILE_MB 与请求 `max_bytes` 中较小者
+pub fn file_limit(max_bytes: Option<u64>) -> Option<u64> {
+    match (crate::config::download_max_file_bytes(), max_bytes) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// 目录占用的字节数（不跟随符号链接）
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| Some((entry.path(), entry.metadata().ok()?)))
+        .map(|(path, meta)| if meta.is_dir() { dir_size(&path) } else { meta.len() })
+        .sum()
+}
+
+/// 本次下载的磁盘配额
+pub struct DiskQuota {
+    /// 单个文件的大小上限
+    file_limit: Option<u64>,
+    /// 根目录还能写入的字节数
+    remaining: Option<u64>,
+}
+
+impl DiskQuota {
+    /// 按 XHS_DOWNLOAD_MAX_FILE_MB / XHS_DOWNLOAD_QUOTA_MB 与请求的 `max_bytes` 计算
+    pub async fn for_request(root: &Path, max_bytes: Option<u64>) -> Result<Self> {
+        let file_limit = file_limit(max_bytes);
+        let remaining = match crate::config::download_quota_bytes() {
+            Some(quota) => {
+                let root = root.to_path_buf();
+                let used = tokio::task::spawn_blocking(move || dir_size(&root)).await?;
+                Some(quota.saturating_sub(used + RESERVED_BYTES.load(Ordering::Relaxed)))
+            }
+            None => None,
+        };
+        Ok(Self { file_limit, remaining })
+    }
+
+    /// 已知总大小时检查配额并预占，返回的 guard 释放时归还预占
+    pub fn reserve(&self, total_bytes: u64, resumed_bytes: u64) -> Result<Reservation> {
+        if let Some(limit) = self.file_limit.filter(|limit| total_bytes > *limit) {
+            return Err(anyhow!("File size {} bytes exceeds the per-download limit of {} bytes", total_bytes, limit));
+        }
+        let needed = total_bytes.saturating_sub(resumed_bytes);
+        if let Some(remaining) = self.remaining.filter(|remaining| needed > *remaining) {
+            return Err(anyhow!("Download quota exceeded: needs {} bytes, {} bytes left", needed, remaining));
+        }
+        RESERVED_BYTES.fetch_add(needed, Ordering::Relaxed);
+        Ok(Reservation(needed))
+    }
+
+    /// 总大小未知时本次最多写入的字节数
+    pub fn stream_limit(&self, resumed_bytes: u64) -> Option<u64> {
+        let file = self.file_limit.map(|limit| limit.saturating_sub(resumed_bytes));
+        match (file, self.remaining) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+/// 预占的配额
+pub struct Reservation(u64);
+
+impl Drop for Reservation {
+    fn drop(&mut self) {
+        RESERVED_BYTES.fetch_sub(self.0, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,135 @@
+//! Segmented Media Download
+//!
+//! Splits large CDN files into byte ranges fetched over parallel connections and
+//! written straight into their offsets of the `.part` file
+
+use anyhow::{Result, anyhow};
+use futures_util::{StreamExt, TryStreamExt};
+use std::io::SeekFrom;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::fs;
+use tokio::io::{AsyncSeekExt, AsyncWriteExt, BufWriter};
+use tokio_util::io::{InspectReader, StreamReader};
+
+use super::download::media_request;
+
+/// 单个分段的请求尝试次数
+const SEGMENT_ATTEMPTS: u32 = 2;
+
+/// 分段并发下载
+///
+/// `first` 是 `bytes=0-{segment_size-1}` 的 206 响应，其余分段以 `connections` 个连接并发拉取，
+/// 返回写入的总字节数。
+#[allow(clippy::too_many_arguments)]
+pub(super) async fn download(
+    client: &reqwest::Client,
+    url: &str,
+    first: reqwest::Response,
+    path: &str,
+    total: u64,
+    segment_size: u64,
+    connections: usize,
+    on_progress: &impl Fn(u64, Option<u64>),
+) -> Result<u64> {
+    // 预分配完整大小，各分段写入各自偏移
+    let file = fs::File::create(path).await
+        .map_err(|e| anyhow!("Failed to create file: {}", e))?;
+    file.set_len(total).await
+        .map_err(|e| anyhow!("Failed to allocate file: {}", e))?;
+    drop(file);
+
+    let downloaded = AtomicU64::new(0);
+    let report = |len: usize| {
+        let done = downloaded.fetch_add(len as u64, Ordering::Relaxed) + len as u64;
+        on_progress(done, Some(total));
+    };
+
+    let first_end = segment_size.min(total);
+    let ranges: Vec<(u64, u64)> = (first_end..total)
+        .step_by(segment_size as usize)
+        .map(|start| (start, (start + segment_size).min(total)))
+        .collect();
+    tracing::debug!("[MediaDownload] {} bytes in {} segment(s) over {} connection(s)", total, ranges.len() + 1, connections);
+
+    let first_segment = async {
+        write_segment(first, path, 0, first_end, &report).await
+    };
+    let rest = futures_util::stream::iter(ranges)
+        .map(|(start, end)| fetch_segment(client, url, path, start, end, &report))
+        .buffer_unordered(connections.saturating_sub(1).max(1))
+        .try_collect::<Vec<()>>();
+    tokio::try_join!(first_segment, rest)?;
+
+    // 每个分段都已校验长度
+    Ok(total)
+}
+
+/// 拉取 `[start, end)` 分段，失败时重试
+#[tracing::instrument(name = "media.segment", skip_all, fields(range = %format!("{}-{}", start, end - 1)))]
+async fn fetch_segment(
+    client: &reqwest::Client,
+    url: &str,
+    path: &str,
+    start: u64,
+    end: u64,
+    report: &impl Fn(usize),
+) -> Result<()> {
+    let mut last_error = None;
+    for attempt in 1..=SEGMENT_ATTEMPTS {
+        let result = async {
+            let response = media_request(client, url)
+                .header("Range", format!("bytes={}-{}", start, end - 1))
+                .send()
+                .await
+                .map_err(|e| anyhow!("Segment request failed: {}", e))?;
+            if response.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+                return Err(anyhow!("Segment {}-{} returned {}", start, end - 1, response.status()));
+            }
+            write_segment(response, path, start, end, report).await
+        }.await;
+        match result {
+            Ok(()) => return Ok(()),
+            Err(e) => {
+                tracing::warn!("[MediaDownload] Segment {}-{} failed (attempt {}/{}): {}", start, end - 1, attempt, SEGMENT_ATTEMPTS, e);
+                last_error = Some(e);
+            }
+        }
+    }
+    Err(last_error.unwrap_or_else(|| anyhow!("Segment {}-{} failed", start, end - 1)))
+}
+
+/// 把分段响应写入文件的 `[start, end)` 区间
+///
+/// 重试时进度会重复计入失败前已写的部分，仅影响进度显示。
+async fn write_segment(
+    response: reqwest::Response,
+    path: &str,
+    start: u64,
+    end: u64,
+    report: &impl Fn(usize),
+) -> Result<()> {
+    let body = response
+        .bytes_stream()
+        .map_err(std::io::Error::other);
+    let reader = InspectReader::new(StreamReader::new(body), |chunk: &[u8]| report(chunk.len()));
+    tokio::pin!(reader);
+    // 多读的数据不能越界覆盖下一分段
+    let mut reader = tokio::io::AsyncReadExt::take(reader, end - start);
+
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .open(path)
+        .await
+        .map_err(|e| anyhow!("Failed to open file: {}", e))?;
+    file.seek(SeekFrom::Start(start)).await
+        .map_err(|e| anyhow!("Failed to seek file: {}", e))?;
+    let mut writer = BufWriter::new(file);
+    let copied = tokio::io::copy(&mut reader, &mut writer).await
+        .map_err(|e| anyhow!("Segment download interrupted: {}", e))?;
+    writer.flush().await
+        .map_err(|e| anyhow!("Failed to flush file: {}", e))?;
+    if copied != end - start {
+        return Err(anyhow!("Segment {}-{} incomplete: {} of {} bytes", start, end - 1, copied, end - start));
+    }
+    Ok(())
+}
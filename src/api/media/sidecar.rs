@@ -0,0 +1,155 @@
+//! Metadata Sidecar
+//!
+//! Writes a note's title, description, author, tags, publish time and
+//! interaction counts next to downloaded media (`video.mp4` → `video.meta.json`)
+
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use utoipa::ToSchema;
+
+use super::download::{DownloadResponse, DownloadTarget};
+use super::s3::S3Client;
+use crate::api::note::stats::NoteStats;
+
+/// sidecar 文件格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SidecarFormat {
+    #[default]
+    Json,
+    Yaml,
+}
+
+impl SidecarFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            SidecarFormat::Json => "json",
+            SidecarFormat::Yaml => "yaml",
+        }
+    }
+}
+
+/// 笔记作者
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub struct NoteAuthor {
+    pub user_id: String,
+    pub nickname: String,
+}
+
+/// 写入 sidecar 的笔记元数据
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub struct NoteMetadata {
+    pub note_id: String,
+    /// 笔记类型 (normal = 图文, video = 视频)
+    pub note_type: String,
+    pub title: String,
+    pub desc: String,
+    pub author: NoteAuthor,
+    /// 话题标签
+    pub tags: Vec<String>,
+    /// 发布时间 (毫秒)
+    pub publish_time: Option<i64>,
+    /// 最后更新时间 (毫秒)
+    pub last_update_time: Option<i64>,
+    /// IP 属地
+    pub ip_location: Option<String>,
+    /// 点赞数
+    pub liked_count: Option<i64>,
+    /// 收藏数
+    pub collected_count: Option<i64>,
+    /// 评论数
+    pub comment_count: Option<i64>,
+    /// 分享数
+    pub share_count: Option<i64>,
+}
+
+impl NoteMetadata {
+    /// 从详情接口的 note_card 提取
+    pub fn from_note_card(note_id: &str, note_card: &serde_json::Value) -> crate::error::Result<Self> {
+        let stats = NoteStats::from_note_card(note_id, note_card)?;
+        let text = |pointer: &str| {
+            note_card.pointer(pointer).and_then(|v| v.as_str()).unwrap_or("").to_string()
+        };
+        let tags = note_card
+            .get("tag_list")
+            .and_then(|v| v.as_array())
+            .map(|list| {
+                list.iter()
+                    .filter_map(|tag| tag.get("name").and_then(|v| v.as_str()))
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(Self {
+            note_id: note_id.to_string(),
+            note_type: text("/type"),
+            title: text("/title"),
+            desc: text("/desc"),
+            author: NoteAuthor {
+                user_id: text("/user/user_id"),
+                nickname: text("/user/nickname"),
+            },
+            tags,
+            publish_time: note_card.get("time").and_then(|v| v.as_i64()),
+            last_update_time: stats.last_update_time,
+            ip_location: note_card.get("ip_location").and_then(|v| v.as_str()).map(str::to_string),
+            liked_count: stats.liked_count,
+            collected_count: stats.collected_count,
+            comment_count: stats.comment_count,
+            share_count: stats.share_count,
+        })
+    }
+
+    /// 按格式序列化
+    pub fn render(&self, format: SidecarFormat) -> Result<Vec<u8>> {
+        Ok(match format {
+            SidecarFormat::Json => serde_json::to_vec_pretty(self)?,
+            SidecarFormat::Yaml => serde_yaml::to_string(self)?.into_bytes(),
+        })
+    }
+}
+
+/// 媒体文件对应的 sidecar 路径：`dir/video.mp4` → `dir/video.meta.json`
+pub fn sidecar_path(save_path: &str, format: SidecarFormat) -> String {
+    Path::new(save_path)
+        .with_extension(format!("meta.{}", format.extension()))
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// 在媒体文件旁写入 sidecar，返回 sidecar 路径
+pub async fn write_sidecar(save_path: &str, metadata: &NoteMetadata, format: SidecarFormat) -> Result<String> {
+    let path = sidecar_path(save_path, format);
+    tokio::fs::write(&path, metadata.render(format)?).await
+        .map_err(|e| anyhow!("Failed to write metadata sidecar {}: {}", path, e))?;
+    Ok(path)
+}
+
+/// 下载成功后写入 sidecar（对象存储目标时上传到同一桶），并把路径记录到 `data.metadata_path`
+pub async fn attach_sidecar(
+    resp: &mut DownloadResponse,
+    metadata: &NoteMetadata,
+    format: SidecarFormat,
+    target: DownloadTarget,
+) -> Result<()> {
+    let Some(data) = resp.data.as_mut().filter(|_| resp.success) else {
+        return Ok(());
+    };
+    data.metadata_path = Some(match target {
+        DownloadTarget::Local => write_sidecar(&data.saved_path, metadata, format).await?,
+        DownloadTarget::S3 => {
+            let s3 = S3Client::from_env()?;
+            let key = s3.key_from_location(&data.saved_path)
+                .ok_or_else(|| anyhow!("Unexpected object location {}", data.saved_path))?;
+            let key = sidecar_path(key, format);
+            let content_type = match format {
+                SidecarFormat::Json => "application/json",
+                SidecarFormat::Yaml => "application/yaml",
+            };
+            s3.put_object(&key, content_type, metadata.render(format)?).await?;
+            s3.location(&key)
+        }
+    });
+    Ok(())
+}
@@ -0,0 +1,162 @@
+//! Signed File URLs
+//!
+//! Time-limited links (`/files/{token}`) for files under the download root,
+//! so dashboards and webhook payloads can reference fetched media without
+//! exposing filesystem paths or a browsable directory.
+//!
+//! Token format: `base64url(json{p, e}) . base64url(hmac_sha256(secret, payload))`
+
+use anyhow::{anyhow, Result};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::path::{Path, PathBuf};
+use utoipa::ToSchema;
+
+use super::sandbox::relative_to_root;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Longest link lifetime (7 days); longer requested / configured TTLs are clamped
+pub const MAX_TTL_SECS: u64 = 7 * 24 * 3600;
+
+/// 签名链接请求参数
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[schema(example = json!({"path": "6789abcd/1.jpg", "ttl_secs": 3600}))]
+pub struct SignFileRequest {
+    /// 下载根目录内的相对路径（或位于根目录下的完整路径）
+    pub path: String,
+    /// 有效期（秒），默认 XHS_FILE_URL_TTL_SECS，最长 7 天
+    #[serde(default)]
+    pub ttl_secs: Option<u64>,
+}
+
+/// 签名链接
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SignedFile {
+    /// 相对链接，例如 /files/{token}
+    pub url: String,
+    /// 过期时间 (Unix 秒)
+    pub expires_at: i64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TokenPayload {
+    /// Path relative to the download root
+    p: String,
+    /// Expiry (unix seconds)
+    e: i64,
+}
+
+/// Issues and verifies signed file tokens
+pub struct FileSigner {
+    secret: Vec<u8>,
+    root: PathBuf,
+    default_ttl: u64,
+}
+
+impl FileSigner {
+    pub fn new(secret: Vec<u8>, root: PathBuf, default_ttl: u64) -> Self {
+        Self { secret, root, default_ttl }
+    }
+
+    /// Build from XHS_FILE_URL_SECRET / XHS_DOWNLOAD_ROOT / XHS_FILE_URL_TTL_SECS
+    ///
+    /// Without a configured secret a random one is used, so links do not
+    /// survive a restart.
+    pub fn from_env() -> Self {
+        let secret = match std::env::var("XHS_FILE_URL_SECRET") {
+            Ok(s) if !s.is_empty() => s.into_bytes(),
+            _ => {
+                tracing::info!("XHS_FILE_URL_SECRET not set, signed file URLs are valid until restart");
+                uuid::Uuid::new_v4().as_bytes().to_vec()
+            }
+        };
+        Self::new(secret, crate::config::download_root(), crate::config::file_url_ttl())
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Resolve a path (relative to the root, or absolute inside it) to an absolute file path
+    pub fn resolve(&self, path: &str) -> Result<PathBuf> {
+        let full = self.root.join(self.relative_path(path)?);
+        if !full.is_file() {
+            return Err(anyhow!("File not found: {}", path));
+        }
+        Ok(full)
+    }
+
+    /// Sign a file path (relative to the root, or absolute inside it)
+    pub fn sign(&self, path: &str, ttl_secs: Option<u64>) -> Result<(String, i64)> {
+        let relative = self.relative_path(path)?;
+        let full = self.root.join(&relative);
+        if !full.is_file() {
+            return Err(anyhow!("File not found: {}", relative.display()));
+        }
+
+        let ttl = ttl_secs.unwrap_or(self.default_ttl).min(MAX_TTL_SECS) as i64;
+        let expires_at = chrono::Utc::now()
+            .timestamp()
+            .checked_add(ttl)
+            .ok_or_else(|| anyhow!("Invalid TTL: {}", ttl))?;
+        let payload = serde_json::to_vec(&TokenPayload {
+            p: relative.to_string_lossy().replace('\\', "/"),
+            e: expires_at,
+        })?;
+
+        let encoded = URL_SAFE_NO_PAD.encode(&payload);
+        let signature = URL_SAFE_NO_PAD.encode(self.mac(encoded.as_bytes()));
+        Ok((format!("{}.{}", encoded, signature), expires_at))
+    }
+
+    /// Verify a token and return the absolute file path
+    pub fn verify(&self, token: &str) -> Result<PathBuf> {
+        let (encoded, signature) = token.split_once('.').ok_or_else(|| anyhow!("Malformed token"))?;
+        let signature = URL_SAFE_NO_PAD.decode(signature).map_err(|_| anyhow!("Malformed token"))?;
+
+        let mut mac = HmacSha256::new_from_slice(&self.secret).expect("HMAC accepts any key length");
+        mac.update(encoded.as_bytes());
+        mac.verify_slice(&signature).map_err(|_| anyhow!("Invalid signature"))?;
+
+        let payload: TokenPayload = serde_json::from_slice(
+            &URL_SAFE_NO_PAD.decode(encoded).map_err(|_| anyhow!("Malformed token"))?,
+        )?;
+        if payload.e < chrono::Utc::now().timestamp() {
+            return Err(anyhow!("Link expired"));
+        }
+
+        let relative = self.relative_path(&payload.p)?;
+        Ok(self.root.join(relative))
+    }
+
+    fn mac(&self, data: &[u8]) -> Vec<u8> {
+        let mut mac = HmacSha256::new_from_slice(&self.secret).expect("HMAC accepts any key length");
+        mac.update(data);
+        mac.finalize().into_bytes().to_vec()
+    }
+
+    /// Normalize to a path relative to the root, rejecting traversal
+    fn relative_path(&self, path: &str) -> Result<PathBuf> {
+        relative_to_root(&self.root, path)
+    }
+}
+
+/// Content type by file extension
+pub fn content_type_for(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase()).as_deref() {
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("png") => "image/png",
+        Some("webp") => "image/webp",
+        Some("avif") => "image/avif",
+        Some("gif") => "image/gif",
+        Some("heic") => "image/heic",
+        Some("mp4") => "video/mp4",
+        Some("mov") => "video/quicktime",
+        Some("json") => "application/json",
+        _ => "application/octet-stream",
+    }
+}
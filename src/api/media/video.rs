@@ -3,7 +3,7 @@
 //! Extracts video download URLs from note details
 
 use crate::api::XhsApiClient;
-use anyhow::{Result, anyhow};
+use crate::error::{Result, XhsError};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
@@ -95,7 +95,7 @@ pub async fn get_video_urls(api: &XhsApiClient, req: VideoRequest) -> Result<Vid
     // 提取笔记卡片
     let note_card = raw
         .pointer("/data/items/0/note_card")
-        .ok_or_else(|| anyhow!("No note_card found in response"))?;
+        .ok_or_else(|| XhsError::Parse("No note_card found in response".to_string()))?;
     
     // 检查是否为视频类型
     let note_type = note_card.get("type").and_then(|v| v.as_str()).unwrap_or("");
@@ -127,11 +127,48 @@ pub async fn get_video_urls(api: &XhsApiClient, req: VideoRequest) -> Result<Vid
         .and_then(|v| v.as_str())
         .map(|s| s.to_string());
     
-    // 提取视频流
+    // 提取视频流 (按文件大小降序，最高画质在前)
+    let videos = videos_from_note_card(note_card);
+    
+    Ok(VideoResponse {
+        success: true,
+        msg: None,
+        data: Some(VideoData {
+            note_id: req.note_id,
+            title,
+            author,
+            duration,
+            videos,
+            cover,
+        }),
+    })
+}
+
+/// 从笔记卡片 (note_card) 中提取所有画质的视频流，按文件大小降序排列
+pub fn videos_from_note_card(note_card: &serde_json::Value) -> Vec<VideoItem> {
+    note_card
+        .pointer("/video/media/stream")
+        .map(videos_from_stream)
+        .unwrap_or_default()
+}
+
+/// 实况图 (Live Photo) 的动态视频，按文件大小降序排列
+///
+/// 图文笔记 image_list 中 `live_photo = true` 的图片在 `stream` 下附带一段短视频，
+/// 结构与视频笔记的 `video.media.stream` 相同；普通图片返回空列表
+pub fn live_photo_videos(image: &serde_json::Value) -> Vec<VideoItem> {
+    image
+        .get("stream")
+        .map(videos_from_stream)
+        .unwrap_or_default()
+}
+
+/// 解析 stream 对象中 h265 / h264 的各画质视频流
+fn videos_from_stream(stream: &serde_json::Value) -> Vec<VideoItem> {
     let mut videos = Vec::new();
     
     // 解析 h265 流
-    if let Some(h265_streams) = note_card.pointer("/video/media/stream/h265").and_then(|v| v.as_array()) {
+    if let Some(h265_streams) = stream.get("h265").and_then(|v| v.as_array()) {
         for stream in h265_streams {
             if let Some(item) = parse_video_stream(stream, "hevc") {
                 videos.push(item);
@@ -140,7 +177,7 @@ pub async fn get_video_urls(api: &XhsApiClient, req: VideoRequest) -> Result<Vid
     }
     
     // 解析 h264 流
-    if let Some(h264_streams) = note_card.pointer("/video/media/stream/h264").and_then(|v| v.as_array()) {
+    if let Some(h264_streams) = stream.get("h264").and_then(|v| v.as_array()) {
         for stream in h264_streams {
             if let Some(item) = parse_video_stream(stream, "h264") {
                 videos.push(item);
@@ -149,28 +186,17 @@ pub async fn get_video_urls(api: &XhsApiClient, req: VideoRequest) -> Result<Vid
     }
     
     // 按文件大小降序排列 (最高画质在前)
-    videos.sort_by(|a, b| b.size.cmp(&a.size));
-    
-    Ok(VideoResponse {
-        success: true,
-        msg: None,
-        data: Some(VideoData {
-            note_id: req.note_id,
-            title,
-            author,
-            duration,
-            videos,
-            cover,
-        }),
-    })
+    videos.sort_by_key(|v| std::cmp::Reverse(v.size));
+    videos
 }
 
 /// 解析单个视频流
 fn parse_video_stream(stream: &serde_json::Value, codec: &str) -> Option<VideoItem> {
     let master_url = stream.get("master_url")?.as_str()?;
-    let width = stream.get("width")?.as_i64()? as i32;
-    let height = stream.get("height")?.as_i64()? as i32;
-    let size = stream.get("size")?.as_i64()?;
+    // 实况图的短视频流可能缺少尺寸与大小字段
+    let width = stream.get("width").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
+    let height = stream.get("height").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
+    let size = stream.get("size").and_then(|v| v.as_i64()).unwrap_or(0);
     
     // 构建画质标识
     let quality = format!("{}_{}", codec, 
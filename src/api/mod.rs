@@ -1,11 +1,29 @@
+pub mod codes;
 pub mod common;
+pub mod crawl;
+pub mod dedup;
+pub mod dry_run;
 pub mod feed;
+pub mod guest;
 pub mod login;
+pub mod maintenance;
 pub mod media;
+pub mod monitor;
 pub mod note;
 pub mod notification;
+pub mod pacing;
+pub mod paginate;
+pub mod rate_limit;
+pub mod raw;
+pub mod retry;
+pub mod risk;
+pub mod rotation;
 pub mod search;
+pub mod tag;
 pub mod user;
+pub mod vcr;
+pub mod verification;
+pub mod watch;
 pub mod creator;
 
 pub use common::XhsApiClient;
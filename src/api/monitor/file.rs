@@ -0,0 +1,123 @@
+//! JSON file monitor backend
+//!
+//! All monitors live in one JSON array, rewritten atomically (temp file +
+//! rename) on every change. Suitable for a single instance; use the Mongo
+//! backend when several instances share the monitors.
+use anyhow::Result;
+use std::path::PathBuf;
+
+use super::{merge_recent, KeywordMonitor, MonitorRun};
+use crate::utils::storage::JsonFileStore;
+
+pub struct FileBackend {
+    store: JsonFileStore<KeywordMonitor>,
+}
+
+impl FileBackend {
+    pub fn new(path: PathBuf) -> Self {
+        Self { store: JsonFileStore::new(path) }
+    }
+
+    /// Check that the file is readable and its directory writable
+    pub async fn ping(&self) -> Result<()> {
+        self.store.ping().await
+    }
+
+    pub async fn insert(&self, monitor: &KeywordMonitor) -> Result<()> {
+        self.store.modify(|monitors| {
+            monitors.push(monitor.clone());
+            (true, ())
+        }).await
+    }
+
+    pub async fn list(&self) -> Result<Vec<KeywordMonitor>> {
+        let mut monitors = self.store.load().await?;
+        monitors.sort_by_key(|m| m.created_at);
+        Ok(monitors)
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Option<KeywordMonitor>> {
+        Ok(self.store.load().await?.into_iter().find(|m| m.id == id))
+    }
+
+    /// Replace the stored monitor's settings; run state is left to the scheduler
+    pub async fn update(&self, monitor: &KeywordMonitor) -> Result<Option<KeywordMonitor>> {
+        self.store.modify(|monitors| match monitors.iter_mut().find(|m| m.id == monitor.id) {
+            Some(stored) => {
+                stored.name = monitor.name.clone();
+                stored.keyword = monitor.keyword.clone();
+                stored.sort = monitor.sort;
+                stored.note_type = monitor.note_type;
+                stored.pages = monitor.pages;
+                stored.filters = monitor.filters.clone();
+                stored.webhook_urls = monitor.webhook_urls.clone();
+                stored.interval_secs = monitor.interval_secs;
+                stored.enabled = monitor.enabled;
+                stored.next_run_at = monitor.next_run_at;
+                stored.baselined = monitor.baselined;
+                stored.seen_note_ids = monitor.seen_note_ids.clone();
+                stored.updated_at = monitor.updated_at;
+                (true, Some(stored.clone()))
+            }
+            None => (false, None),
+        }).await
+    }
+
+    pub async fn delete(&self, id: &str) -> Result<bool> {
+        self.store.modify(|monitors| {
+            let before = monitors.len();
+            monitors.retain(|m| m.id != id);
+            let removed = monitors.len() < before;
+            (removed, removed)
+        }).await
+    }
+
+    pub async fn claim_due(&self, now: i64, stale_before: i64) -> Result<Vec<KeywordMonitor>> {
+        self.store.modify(|monitors| {
+            let mut due = Vec::new();
+            for monitor in monitors.iter_mut() {
+                if monitor.enabled && monitor.next_run_at <= now && (!monitor.running || monitor.updated_at < stale_before) {
+                    monitor.running = true;
+                    monitor.updated_at = now;
+                    due.push(monitor.clone());
+                }
+            }
+            due.sort_by_key(|m| m.next_run_at);
+            (!due.is_empty(), due)
+        }).await
+    }
+
+    pub async fn finish(
+        &self,
+        monitor: &KeywordMonitor,
+        result: &std::result::Result<MonitorRun, String>,
+        now: i64,
+        next_run_at: i64,
+    ) -> Result<()> {
+        self.store.modify(|monitors| match monitors.iter_mut().find(|m| m.id == monitor.id) {
+            Some(stored) => {
+                match result {
+                    Ok(run) => {
+                        stored.baselined = true;
+                        stored.last_matches = Some(run.matches.len());
+                        stored.last_error = None;
+                        stored.total_matches += run.matches.len() as u64;
+                        stored.recent_matches = merge_recent(&run.matches, &stored.recent_matches);
+                        stored.seen_note_ids = run.seen_note_ids.clone();
+                    }
+                    Err(e) => {
+                        stored.last_error = Some(e.clone());
+                        stored.failure_count += 1;
+                    }
+                }
+                stored.running = false;
+                stored.run_count += 1;
+                stored.last_run_at = Some(now);
+                stored.next_run_at = next_run_at;
+                stored.updated_at = now;
+                (true, ())
+            }
+            None => (false, ()),
+        }).await
+    }
+}
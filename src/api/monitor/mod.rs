@@ -0,0 +1,658 @@
+//! Keyword Monitors
+//!
+//! Monitors registered through `/api/monitors` periodically search a keyword,
+//! keep the IDs of notes they have already reported, and raise an alert for
+//! every new note that passes the monitor's filters: a log event plus a
+//! webhook POST (`X-Xhs-Event: keyword_monitor`) to the monitor's own
+//! `webhook_urls`, or to `XHS_WEBHOOK_URLS` when it has none. Deliveries are
+//! signed with `XHS_WEBHOOK_SECRET` like notification webhooks.
+//!
+//! The first run of a monitor only records a baseline, so registering a
+//! monitor for a popular keyword does not flood the webhook with old notes.
+//! Changing the keyword, sort or note type starts a new baseline.
+//!
+//! Monitors are persisted so they survive restarts. Configuration (env):
+//! - `XHS_MONITORS_BACKEND`: `file` (default) / `mongo`
+//! - `XHS_MONITORS_FILE`: file backend path, default ./monitors.json
+//! - `XHS_MONITORS_MONGO_URI`: Mongo backend URI, defaults to XHS_COORD_MONGO_URI
+//! - `XHS_MONITORS_DB`: Mongo database, default xhs_monitors
+pub mod file;
+#[cfg(feature = "mongodb")]
+pub mod mongo;
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use utoipa::ToSchema;
+
+use crate::models::feed::{HomefeedItem, InteractInfo};
+use crate::models::search::{SearchNoteType, SearchSort};
+use file::FileBackend;
+#[cfg(feature = "mongodb")]
+use mongo::MongoBackend;
+
+/// Running monitors older than this are considered interrupted
+pub const STALE_RUNNING: std::time::Duration = std::time::Duration::from_secs(3600);
+
+/// Shortest allowed interval between two runs of a monitor (seconds)
+pub const MIN_INTERVAL_SECS: u64 = 60;
+
+/// Most result pages a monitor may search per run
+pub const MAX_PAGES: u32 = 5;
+
+/// Reported note IDs kept per monitor (oldest are forgotten first)
+pub const SEEN_CAPACITY: usize = 1000;
+
+/// Matches kept in `recent_matches`
+pub const RECENT_MATCHES: usize = 50;
+
+// ============================================================================
+// Models
+// ============================================================================
+
+/// Conditions a new note must meet to be reported
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct MonitorFilters {
+    /// Minimum like count
+    #[serde(default)]
+    pub min_liked_count: Option<i64>,
+    /// Title must contain at least one of these words (empty = any title)
+    #[serde(default)]
+    pub include_words: Vec<String>,
+    /// Title must contain none of these words
+    #[serde(default)]
+    pub exclude_words: Vec<String>,
+    /// Ignore notes by these authors
+    #[serde(default)]
+    pub exclude_user_ids: Vec<String>,
+}
+
+impl MonitorFilters {
+    /// Whether a candidate note passes all conditions
+    pub fn matches(&self, note: &MonitorMatch) -> bool {
+        self.min_liked_count.is_none_or(|min| note.liked_count.is_some_and(|n| n >= min))
+            && (self.include_words.is_empty() || self.include_words.iter().any(|w| note.title.contains(w.as_str())))
+            && !self.exclude_words.iter().any(|w| note.title.contains(w.as_str()))
+            && !self.exclude_user_ids.contains(&note.user_id)
+    }
+}
+
+/// A note reported by a monitor
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct MonitorMatch {
+    pub note_id: String,
+    pub xsec_token: String,
+    /// normal / video
+    pub note_type: String,
+    pub title: String,
+    pub user_id: String,
+    pub nickname: String,
+    pub liked_count: Option<i64>,
+    /// Detection time (ms)
+    pub detected_at: i64,
+}
+
+impl MonitorMatch {
+    /// Build from a search result item; `None` for items without a note card
+    pub fn from_item(item: &HomefeedItem, detected_at: i64) -> Option<Self> {
+        let card = item.note_card.as_ref()?;
+        let user = card.user.as_ref();
+        Some(Self {
+            note_id: item.id.clone(),
+            xsec_token: item.xsec_token.clone().unwrap_or_default(),
+            note_type: card.note_type.clone().unwrap_or_default(),
+            title: card.display_title.clone().unwrap_or_default(),
+            user_id: user.and_then(|u| u.user_id.clone()).unwrap_or_default(),
+            nickname: user.and_then(|u| u.nickname.clone().or_else(|| u.nick_name.clone())).unwrap_or_default(),
+            liked_count: card.interact_info.as_ref()
+                .and_then(|i| i.liked_count.as_deref())
+                .and_then(InteractInfo::parse_count),
+            detected_at,
+        })
+    }
+}
+
+/// A registered keyword monitor
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct KeywordMonitor {
+    pub id: String,
+    pub name: String,
+    pub keyword: String,
+    pub sort: SearchSort,
+    /// all / image / video (legacy 0 / 1 / 2 accepted)
+    pub note_type: SearchNoteType,
+    /// Result pages searched per run
+    pub pages: u32,
+    pub filters: MonitorFilters,
+    /// Alert targets; empty = XHS_WEBHOOK_URLS
+    pub webhook_urls: Vec<String>,
+    /// Seconds between runs
+    pub interval_secs: u64,
+    /// Disabled monitors stay registered but are not run
+    pub enabled: bool,
+    /// Next run time (ms)
+    pub next_run_at: i64,
+    /// Currently being run by the scheduler
+    #[serde(default)]
+    pub running: bool,
+    /// Whether the baseline run has happened
+    #[serde(default)]
+    pub baselined: bool,
+    /// Last run time (ms)
+    #[serde(default)]
+    pub last_run_at: Option<i64>,
+    /// New matches found by the last successful run
+    #[serde(default)]
+    pub last_matches: Option<usize>,
+    /// Error of the last run, cleared by the next successful one
+    #[serde(default)]
+    pub last_error: Option<String>,
+    #[serde(default)]
+    pub run_count: u64,
+    #[serde(default)]
+    pub failure_count: u64,
+    /// Matches reported since the monitor was created
+    #[serde(default)]
+    pub total_matches: u64,
+    /// Latest matches, newest first
+    #[serde(default)]
+    pub recent_matches: Vec<MonitorMatch>,
+    /// Note IDs already reported (or part of the baseline), newest first.
+    /// Stored with the monitor but left out of API responses.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[schema(ignore)]
+    pub seen_note_ids: Vec<String>,
+    /// Creation time (ms)
+    pub created_at: i64,
+    /// Last change (ms)
+    pub updated_at: i64,
+}
+
+impl KeywordMonitor {
+    /// Drop the seen-ID list for API responses
+    pub fn without_seen(mut self) -> Self {
+        self.seen_note_ids = Vec::new();
+        self
+    }
+
+    /// Whether `req` searches for something else, which needs a new baseline
+    fn query_differs(&self, req: &MonitorRequest) -> bool {
+        self.keyword != req.keyword.trim() || self.sort != req.sort || self.note_type != req.note_type
+    }
+}
+
+/// Request body for creating / replacing a monitor
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "keyword": "露营装备",
+    "sort": "time_descending",
+    "filters": {"min_liked_count": 100, "exclude_words": ["广告"]},
+    "webhook_urls": ["https://example.com/hooks/xhs"],
+    "interval_secs": 600
+}))]
+pub struct MonitorRequest {
+    /// Defaults to "monitor: {keyword}"
+    #[serde(default)]
+    pub name: Option<String>,
+    pub keyword: String,
+    /// Default time_descending, so new notes show up on the first page
+    #[serde(default = "default_sort")]
+    pub sort: SearchSort,
+    /// all (default) / image / video
+    #[serde(default)]
+    pub note_type: SearchNoteType,
+    /// Result pages searched per run, default 1, at most 5
+    #[serde(default = "default_pages")]
+    pub pages: u32,
+    #[serde(default)]
+    pub filters: MonitorFilters,
+    /// Alert targets; empty = XHS_WEBHOOK_URLS (log only when neither is set)
+    #[serde(default)]
+    pub webhook_urls: Vec<String>,
+    /// Seconds between runs, at least 60
+    pub interval_secs: u64,
+    /// Default true
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_sort() -> SearchSort { SearchSort::TimeDescending }
+fn default_pages() -> u32 { 1 }
+fn default_enabled() -> bool { true }
+
+impl MonitorRequest {
+    fn validate(&self) -> Result<()> {
+        if self.keyword.trim().is_empty() {
+            return Err(anyhow!("keyword must not be empty"));
+        }
+        if self.interval_secs < MIN_INTERVAL_SECS {
+            return Err(anyhow!("interval_secs must be at least {}", MIN_INTERVAL_SECS));
+        }
+        if !(1..=MAX_PAGES).contains(&self.pages) {
+            return Err(anyhow!("pages must be between 1 and {}", MAX_PAGES));
+        }
+        if let Some(url) = self.webhook_urls.iter().find(|u| !u.starts_with("http://") && !u.starts_with("https://")) {
+            return Err(anyhow!("Invalid webhook URL: {}", url));
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+            .filter(|n| !n.trim().is_empty())
+            .unwrap_or_else(|| format!("monitor: {}", self.keyword.trim()))
+    }
+}
+
+/// Outcome of a successful run, applied to the stored monitor by `finish`
+#[derive(Debug, Clone)]
+pub struct MonitorRun {
+    /// New matches, newest first (empty for the baseline run)
+    pub matches: Vec<MonitorMatch>,
+    /// Updated seen-ID list
+    pub seen_note_ids: Vec<String>,
+}
+
+/// webhook body for `X-Xhs-Event: keyword_monitor`
+#[derive(Debug, Serialize)]
+pub struct MonitorAlert<'a> {
+    pub monitor_id: &'a str,
+    pub name: &'a str,
+    pub keyword: &'a str,
+    pub detected_at: chrono::DateTime<Utc>,
+    pub matches: &'a [MonitorMatch],
+}
+
+// ============================================================================
+// Storage
+// ============================================================================
+
+/// Monitor storage configuration
+#[derive(Debug, Clone)]
+pub enum MonitorsConfig {
+    File { path: PathBuf },
+    #[cfg(feature = "mongodb")]
+    Mongo { uri: String, database: String },
+}
+
+impl MonitorsConfig {
+    pub fn from_env() -> Result<Self> {
+        let backend = std::env::var("XHS_MONITORS_BACKEND").unwrap_or_else(|_| "file".to_string());
+        match backend.as_str() {
+            "" | "file" => Ok(Self::File {
+                path: std::env::var("XHS_MONITORS_FILE")
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|_| PathBuf::from("./monitors.json")),
+            }),
+            #[cfg(feature = "mongodb")]
+            "mongo" => {
+                let uri = std::env::var("XHS_MONITORS_MONGO_URI")
+                    .or_else(|_| std::env::var("XHS_COORD_MONGO_URI"))
+                    .map_err(|_| anyhow!("XHS_MONITORS_BACKEND=mongo requires XHS_MONITORS_MONGO_URI"))?;
+                let database = std::env::var("XHS_MONITORS_DB").unwrap_or_else(|_| "xhs_monitors".to_string());
+                Ok(Self::Mongo { uri, database })
+            }
+            #[cfg(not(feature = "mongodb"))]
+            "mongo" => Err(anyhow!("XHS_MONITORS_BACKEND=mongo requires the `mongodb` cargo feature")),
+            other => Err(anyhow!("Unknown XHS_MONITORS_BACKEND: {}", other)),
+        }
+    }
+}
+
+enum MonitorsBackend {
+    File(FileBackend),
+    #[cfg(feature = "mongodb")]
+    Mongo(MongoBackend),
+}
+
+/// Persistent registry of keyword monitors
+pub struct KeywordMonitors {
+    backend: MonitorsBackend,
+    description: String,
+}
+
+impl KeywordMonitors {
+    pub async fn open(config: &MonitorsConfig) -> Result<Self> {
+        let (backend, description) = match config {
+            MonitorsConfig::File { path } => {
+                (MonitorsBackend::File(FileBackend::new(path.clone())), format!("file: {}", path.display()))
+            }
+            #[cfg(feature = "mongodb")]
+            MonitorsConfig::Mongo { uri, database } => {
+                (MonitorsBackend::Mongo(MongoBackend::connect(uri, database).await?), format!("mongo: {}", database))
+            }
+        };
+        tracing::info!("[Monitors] Monitors stored in {}", description);
+        Ok(Self { backend, description })
+    }
+
+    pub async fn from_env() -> Result<Self> {
+        Self::open(&MonitorsConfig::from_env()?).await
+    }
+
+    /// Backend description, e.g. "file: ./monitors.json"
+    pub fn describe(&self) -> &str {
+        &self.description
+    }
+
+    /// Whether every instance sees the same monitors (MongoDB backend)
+    pub fn is_shared(&self) -> bool {
+        !matches!(self.backend, MonitorsBackend::File(_))
+    }
+
+    /// Check that the backend is reachable (readiness probe)
+    pub async fn ping(&self) -> Result<()> {
+        match &self.backend {
+            MonitorsBackend::File(b) => b.ping().await,
+            #[cfg(feature = "mongodb")]
+            MonitorsBackend::Mongo(b) => b.ping().await,
+        }
+    }
+
+    /// Register a monitor; its baseline run is due immediately
+    pub async fn create(&self, req: MonitorRequest) -> Result<KeywordMonitor> {
+        req.validate()?;
+        let now = Utc::now().timestamp_millis();
+        let monitor = KeywordMonitor {
+            id: uuid::Uuid::new_v4().simple().to_string(),
+            name: req.name(),
+            keyword: req.keyword.trim().to_string(),
+            sort: req.sort,
+            note_type: req.note_type,
+            pages: req.pages,
+            filters: req.filters,
+            webhook_urls: req.webhook_urls,
+            interval_secs: req.interval_secs,
+            enabled: req.enabled,
+            next_run_at: now,
+            running: false,
+            baselined: false,
+            last_run_at: None,
+            last_matches: None,
+            last_error: None,
+            run_count: 0,
+            failure_count: 0,
+            total_matches: 0,
+            recent_matches: Vec::new(),
+            seen_note_ids: Vec::new(),
+            created_at: now,
+            updated_at: now,
+        };
+        match &self.backend {
+            MonitorsBackend::File(b) => b.insert(&monitor).await?,
+            #[cfg(feature = "mongodb")]
+            MonitorsBackend::Mongo(b) => b.insert(&monitor).await?,
+        }
+        tracing::info!("[Monitors] Registered {} \"{}\" every {}s", monitor.id, monitor.name, monitor.interval_secs);
+        Ok(monitor)
+    }
+
+    /// Monitors ordered by creation time
+    pub async fn list(&self) -> Result<Vec<KeywordMonitor>> {
+        match &self.backend {
+            MonitorsBackend::File(b) => b.list().await,
+            #[cfg(feature = "mongodb")]
+            MonitorsBackend::Mongo(b) => b.list().await,
+        }
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Option<KeywordMonitor>> {
+        match &self.backend {
+            MonitorsBackend::File(b) => b.get(id).await,
+            #[cfg(feature = "mongodb")]
+            MonitorsBackend::Mongo(b) => b.get(id).await,
+        }
+    }
+
+    /// Replace a monitor's settings, keeping its run history. A changed
+    /// search (keyword / sort / note type) drops the seen IDs and runs a new
+    /// baseline immediately; re-enabling runs immediately as well.
+    pub async fn update(&self, id: &str, req: MonitorRequest) -> Result<KeywordMonitor> {
+        req.validate()?;
+        let existing = self.get(id).await?.ok_or_else(|| anyhow!("Monitor {} not found", id))?;
+        let now = Utc::now().timestamp_millis();
+        let rebaseline = existing.query_differs(&req);
+        let next_run_at = if rebaseline || (req.enabled && !existing.enabled) {
+            now
+        } else {
+            existing.next_run_at
+        };
+        let monitor = KeywordMonitor {
+            name: req.name(),
+            keyword: req.keyword.trim().to_string(),
+            sort: req.sort,
+            note_type: req.note_type,
+            pages: req.pages,
+            filters: req.filters,
+            webhook_urls: req.webhook_urls,
+            interval_secs: req.interval_secs,
+            enabled: req.enabled,
+            next_run_at,
+            baselined: existing.baselined && !rebaseline,
+            seen_note_ids: if rebaseline { Vec::new() } else { existing.seen_note_ids.clone() },
+            updated_at: now,
+            ..existing
+        };
+        let updated = match &self.backend {
+            MonitorsBackend::File(b) => b.update(&monitor).await?,
+            #[cfg(feature = "mongodb")]
+            MonitorsBackend::Mongo(b) => b.update(&monitor).await?,
+        };
+        updated.ok_or_else(|| anyhow!("Monitor {} not found", id))
+    }
+
+    /// Remove a monitor, returning whether it existed
+    pub async fn delete(&self, id: &str) -> Result<bool> {
+        match &self.backend {
+            MonitorsBackend::File(b) => b.delete(id).await,
+            #[cfg(feature = "mongodb")]
+            MonitorsBackend::Mongo(b) => b.delete(id).await,
+        }
+    }
+
+    /// Atomically mark due monitors as running and return them
+    pub async fn claim_due(&self) -> Result<Vec<KeywordMonitor>> {
+        let now = Utc::now().timestamp_millis();
+        let stale_before = now - STALE_RUNNING.as_millis() as i64;
+        match &self.backend {
+            MonitorsBackend::File(b) => b.claim_due(now, stale_before).await,
+            #[cfg(feature = "mongodb")]
+            MonitorsBackend::Mongo(b) => b.claim_due(now, stale_before).await,
+        }
+    }
+
+    /// Record the outcome of a run and schedule the next one
+    pub async fn finish(&self, monitor: &KeywordMonitor, result: std::result::Result<MonitorRun, String>) -> Result<()> {
+        let now = Utc::now().timestamp_millis();
+        let next_run_at = now + monitor.interval_secs as i64 * 1000;
+        match &self.backend {
+            MonitorsBackend::File(b) => b.finish(monitor, &result, now, next_run_at).await,
+            #[cfg(feature = "mongodb")]
+            MonitorsBackend::Mongo(b) => b.finish(monitor, &result, now, next_run_at).await,
+        }
+    }
+}
+
+/// Newest-first matches merged into the stored list, capped at `RECENT_MATCHES`
+fn merge_recent(matches: &[MonitorMatch], recent: &[MonitorMatch]) -> Vec<MonitorMatch> {
+    matches.iter().chain(recent).take(RECENT_MATCHES).cloned().collect()
+}
+
+// ============================================================================
+// Scheduler
+// ============================================================================
+
+#[cfg(feature = "server")]
+use crate::server::AppState;
+#[cfg(feature = "server")]
+use std::sync::Arc;
+
+/// Work queue task kind for a claimed monitor run (payload: the monitor)
+#[cfg(feature = "server")]
+const MONITOR_RUN_TASK: &str = "monitor_run";
+
+/// Background scheduler: claims due monitors every `XHS_MONITORS_TICK_SECS`
+/// and runs them one at a time. Skips ticks while upstream maintenance is in
+/// effect or the primary account is outside the active hours of its activity
+/// profile.
+///
+/// In cluster mode with a shared (MongoDB) monitor store, claimed runs are
+/// handed to the coordination queue instead and executed by whichever
+/// instance's worker leases them.
+#[cfg(feature = "server")]
+pub fn spawn_scheduler(state: Arc<AppState>) {
+    let interval = crate::config::monitors_tick_interval();
+    let queue = state.coordinator.clone().filter(|_| state.monitors.is_shared());
+    if let Some(queue) = queue.clone() {
+        let state = state.clone();
+        tokio::spawn(async move {
+            queue.run_worker(&[MONITOR_RUN_TASK], |task| run_task(&state, task)).await;
+        });
+    }
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(interval).await;
+            if state.api.maintenance().is_paused() || !state.api.is_active_now().await {
+                continue;
+            }
+            let due = match state.monitors.claim_due().await {
+                Ok(due) => due,
+                Err(e) => {
+                    tracing::error!("[Monitors] Failed to claim due monitors: {}", e);
+                    continue;
+                }
+            };
+            for monitor in due {
+                match &queue {
+                    Some(queue) => {
+                        let key = format!("monitor:{}", monitor.id);
+                        let enqueued = match serde_json::to_value(&monitor) {
+                            Ok(payload) => queue.enqueue(&key, MONITOR_RUN_TASK, payload, 0).await,
+                            Err(e) => Err(e.into()),
+                        };
+                        if let Err(e) = enqueued {
+                            tracing::error!("[Monitors] Failed to enqueue {}: {}", monitor.id, e);
+                            if let Err(e) = state.monitors.finish(&monitor, Err(e.to_string())).await {
+                                tracing::error!("[Monitors] Failed to record outcome of {}: {}", monitor.id, e);
+                            }
+                        }
+                    }
+                    None => execute(&state, &monitor).await,
+                }
+            }
+        }
+    });
+}
+
+/// Run a claimed monitor and record the outcome
+#[cfg(feature = "server")]
+async fn execute(state: &AppState, monitor: &KeywordMonitor) {
+    let result = run(state, monitor).await.map_err(|e| e.to_string());
+    if let Err(e) = &result {
+        tracing::warn!("[Monitors] {} \"{}\" failed: {}", monitor.id, monitor.name, e);
+    }
+    if let Err(e) = state.monitors.finish(monitor, result).await {
+        tracing::error!("[Monitors] Failed to record outcome of {}: {}", monitor.id, e);
+    }
+}
+
+/// Queue worker: the run outcome is recorded on the monitor, which schedules
+/// its own retries, so the one-shot task always completes
+#[cfg(feature = "server")]
+async fn run_task(state: &AppState, task: crate::coordination::CrawlTask) -> std::result::Result<(), String> {
+    let monitor: KeywordMonitor = serde_json::from_value(task.payload).map_err(|e| e.to_string())?;
+    execute(state, &monitor).await;
+    Ok(())
+}
+
+/// Search the monitor's keyword, report new matching notes and return the
+/// updated seen list
+#[cfg(feature = "server")]
+async fn run(state: &AppState, monitor: &KeywordMonitor) -> Result<MonitorRun> {
+    use crate::models::search::SearchNotesRequest;
+    use std::collections::HashSet;
+
+    let now = Utc::now().timestamp_millis();
+    let mut candidates = Vec::new();
+    let mut search_id: Option<String> = None;
+    for page in 1..=monitor.pages {
+        let req = SearchNotesRequest {
+            page: page as i32,
+            search_id: search_id.take(),
+            sort: monitor.sort,
+            note_type: monitor.note_type,
+            ..SearchNotesRequest::new(monitor.keyword.clone())
+        };
+        let result = crate::api::search::search_notes(&state.api, req).await?;
+        if !result.success {
+            return Err(anyhow!("Search failed: {}", result.msg.unwrap_or_default()));
+        }
+        let Some(data) = result.data else { break };
+        state.note_cache.observe_items(&data.items).await;
+        candidates.extend(data.items.iter().filter_map(|item| MonitorMatch::from_item(item, now)));
+        if !data.has_more {
+            break;
+        }
+        search_id = data.search_id;
+    }
+
+    // Only reported notes are remembered, so a note that meets the filters
+    // later (e.g. crosses min_liked_count) is still reported once
+    let seen: HashSet<&str> = monitor.seen_note_ids.iter().map(String::as_str).collect();
+    let mut matches: Vec<MonitorMatch> = Vec::new();
+    for candidate in candidates {
+        if !seen.contains(candidate.note_id.as_str())
+            && !matches.iter().any(|m| m.note_id == candidate.note_id)
+            && monitor.filters.matches(&candidate)
+        {
+            matches.push(candidate);
+        }
+    }
+    let seen_note_ids = matches.iter()
+        .map(|m| m.note_id.clone())
+        .chain(monitor.seen_note_ids.iter().cloned())
+        .take(SEEN_CAPACITY)
+        .collect();
+
+    if !monitor.baselined {
+        tracing::info!("[Monitors] {} \"{}\" baseline: {} note(s)", monitor.id, monitor.name, matches.len());
+        return Ok(MonitorRun { matches: Vec::new(), seen_note_ids });
+    }
+    for m in &matches {
+        tracing::info!("[Monitors] {} \"{}\" new note {} \"{}\" by {}", monitor.id, monitor.name, m.note_id, m.title, m.nickname);
+    }
+    if !matches.is_empty() {
+        alert(monitor, &matches).await;
+    }
+    Ok(MonitorRun { matches, seen_note_ids })
+}
+
+/// Deliver the alert to the monitor's webhooks (or XHS_WEBHOOK_URLS)
+#[cfg(feature = "server")]
+async fn alert(monitor: &KeywordMonitor, matches: &[MonitorMatch]) {
+    use crate::api::notification::webhook::WebhookNotifier;
+
+    let notifier = if monitor.webhook_urls.is_empty() {
+        WebhookNotifier::from_env()
+    } else {
+        WebhookNotifier::new(monitor.webhook_urls.clone(), crate::config::webhook_secret()).map(Some)
+    };
+    let notifier = match notifier {
+        Ok(Some(notifier)) => notifier,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::error!("[Monitors] Failed to build webhook client: {}", e);
+            return;
+        }
+    };
+    let payload = MonitorAlert {
+        monitor_id: &monitor.id,
+        name: &monitor.name,
+        keyword: &monitor.keyword,
+        detected_at: Utc::now(),
+        matches,
+    };
+    let delivered = notifier.deliver("keyword_monitor", &payload).await;
+    tracing::info!("[Monitors] {} match(es) of {} delivered to {} webhook(s)", matches.len(), monitor.id, delivered);
+}
@@ -0,0 +1,150 @@
+//! MongoDB monitor backend
+//!
+//! One document per monitor in `keyword_monitors`; due monitors are claimed
+//! with `findOneAndUpdate` so several instances can share the monitors
+//! without running one twice.
+use anyhow::{anyhow, Result};
+use mongodb::bson::{doc, to_bson, Bson, Document};
+use mongodb::options::{IndexOptions, ReturnDocument};
+use mongodb::{Collection, IndexModel};
+
+use super::{merge_recent, KeywordMonitor, MonitorRun};
+
+pub struct MongoBackend {
+    monitors: Collection<KeywordMonitor>,
+}
+
+impl MongoBackend {
+    pub async fn connect(uri: &str, database: &str) -> Result<Self> {
+        let client = crate::utils::storage::mongo_client(uri)
+            .await
+            .map_err(|e| anyhow!("Failed to connect to monitors MongoDB: {}", e))?;
+        let monitors: Collection<KeywordMonitor> = client.database(database).collection("keyword_monitors");
+        monitors
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "id": 1 })
+                    .options(IndexOptions::builder().name("id".to_string()).unique(true).build())
+                    .build(),
+            )
+            .await?;
+        monitors
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "enabled": 1, "next_run_at": 1 })
+                    .options(IndexOptions::builder().name("enabled_next_run_at".to_string()).build())
+                    .build(),
+            )
+            .await?;
+        Ok(Self { monitors })
+    }
+
+    /// Check that the deployment is reachable
+    pub async fn ping(&self) -> Result<()> {
+        crate::utils::storage::ping_mongo(&self.monitors).await
+    }
+
+    pub async fn insert(&self, monitor: &KeywordMonitor) -> Result<()> {
+        self.monitors.insert_one(monitor).await?;
+        Ok(())
+    }
+
+    pub async fn list(&self) -> Result<Vec<KeywordMonitor>> {
+        let mut cursor = self.monitors.find(doc! {}).sort(doc! { "created_at": 1 }).await?;
+        let mut monitors = Vec::new();
+        while cursor.advance().await? {
+            monitors.push(cursor.deserialize_current()?);
+        }
+        Ok(monitors)
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Option<KeywordMonitor>> {
+        Ok(self.monitors.find_one(doc! { "id": id }).await?)
+    }
+
+    pub async fn update(&self, monitor: &KeywordMonitor) -> Result<Option<KeywordMonitor>> {
+        Ok(self.monitors
+            .find_one_and_update(
+                doc! { "id": &monitor.id },
+                doc! { "$set": {
+                    "name": &monitor.name,
+                    "keyword": &monitor.keyword,
+                    "sort": to_bson(&monitor.sort)?,
+                    "note_type": to_bson(&monitor.note_type)?,
+                    "pages": monitor.pages as i64,
+                    "filters": to_bson(&monitor.filters)?,
+                    "webhook_urls": &monitor.webhook_urls,
+                    "interval_secs": monitor.interval_secs as i64,
+                    "enabled": monitor.enabled,
+                    "next_run_at": monitor.next_run_at,
+                    "baselined": monitor.baselined,
+                    "seen_note_ids": &monitor.seen_note_ids,
+                    "updated_at": monitor.updated_at,
+                } },
+            )
+            .return_document(ReturnDocument::After)
+            .await?)
+    }
+
+    pub async fn delete(&self, id: &str) -> Result<bool> {
+        let result = self.monitors.delete_one(doc! { "id": id }).await?;
+        Ok(result.deleted_count > 0)
+    }
+
+    pub async fn claim_due(&self, now: i64, stale_before: i64) -> Result<Vec<KeywordMonitor>> {
+        let mut due = Vec::new();
+        while let Some(monitor) = self.monitors
+            .find_one_and_update(
+                doc! {
+                    "enabled": true,
+                    "next_run_at": { "$lte": now },
+                    "$or": [
+                        { "running": false },
+                        { "updated_at": { "$lt": stale_before } },
+                    ],
+                },
+                doc! { "$set": { "running": true, "updated_at": now } },
+            )
+            .sort(doc! { "next_run_at": 1 })
+            .return_document(ReturnDocument::After)
+            .await?
+        {
+            due.push(monitor);
+        }
+        Ok(due)
+    }
+
+    /// `monitor` is the claimed copy; the run owns the monitor's match state
+    /// until it finishes, so the merged lists are written as a whole
+    pub async fn finish(
+        &self,
+        monitor: &KeywordMonitor,
+        result: &std::result::Result<MonitorRun, String>,
+        now: i64,
+        next_run_at: i64,
+    ) -> Result<()> {
+        let mut set: Document = doc! {
+            "running": false,
+            "last_run_at": now,
+            "next_run_at": next_run_at,
+            "updated_at": now,
+        };
+        let mut inc: Document = doc! { "run_count": 1_i64 };
+        match result {
+            Ok(run) => {
+                set.insert("baselined", true);
+                set.insert("last_matches", run.matches.len() as i64);
+                set.insert("last_error", Bson::Null);
+                set.insert("recent_matches", to_bson(&merge_recent(&run.matches, &monitor.recent_matches))?);
+                set.insert("seen_note_ids", &run.seen_note_ids);
+                inc.insert("total_matches", run.matches.len() as i64);
+            }
+            Err(e) => {
+                set.insert("last_error", e);
+                inc.insert("failure_count", 1_i64);
+            }
+        }
+        self.monitors.update_one(doc! { "id": &monitor.id }, doc! { "$set": set, "$inc": inc }).await?;
+        Ok(())
+    }
+}
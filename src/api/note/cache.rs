@@ -0,0 +1,179 @@
+//! Note Detail Cache
+//!
+//! 按 note_id 缓存笔记详情，热门笔记在有效期内直接从本地返回。
+//!
+//! 失效策略：
+//! - **有效期**: 超过 TTL 的条目视为过期，下次请求重新拉取（保证最大陈旧时间）
+//! - **版本比对**: Feed / 搜索结果中携带了笔记的互动数据，每次经过时与缓存中的
+//!   版本（last_update_time + 互动计数）比对，不一致则立即失效，无需额外请求
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+use super::detail::NoteDetailResponse;
+use crate::models::feed::HomefeedItem;
+
+/// 默认最多缓存的笔记数量
+const DEFAULT_MAX_ENTRIES: usize = 1000;
+
+/// 笔记内容版本
+///
+/// 字段均为可选：Feed 卡片通常只包含 liked_count，详情接口包含全部字段，
+/// 比对时只比较双方都存在的字段
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NoteVersion {
+    pub last_update_time: Option<i64>,
+    pub liked_count: Option<String>,
+    pub collected_count: Option<String>,
+    pub comment_count: Option<String>,
+    pub share_count: Option<String>,
+}
+
+impl NoteVersion {
+    /// 从详情接口的 note_card 提取版本
+    pub fn from_note_card(note_card: &serde_json::Value) -> Self {
+        let count = |key: &str| {
+            note_card
+                .get("interact_info")
+                .and_then(|info| info.get(key))
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string())
+        };
+        Self {
+            last_update_time: note_card.get("last_update_time").and_then(|v| v.as_i64()),
+            liked_count: count("liked_count"),
+            collected_count: count("collected_count"),
+            comment_count: count("comment_count"),
+            share_count: count("share_count"),
+        }
+    }
+
+    /// 从 Feed / 搜索结果中的笔记卡片提取版本
+    pub fn from_feed_item(item: &HomefeedItem) -> Self {
+        let Some(info) = item.note_card.as_ref().and_then(|card| card.interact_info.as_ref()) else {
+            return Self::default();
+        };
+        Self {
+            last_update_time: None,
+            liked_count: info.liked_count.clone(),
+            collected_count: info.collected_count.clone(),
+            comment_count: info.comment_count.clone(),
+            share_count: info.share_count.clone(),
+        }
+    }
+
+    /// 双方都存在的字段中是否有任一不同
+    pub fn conflicts_with(&self, other: &NoteVersion) -> bool {
+        fn differs<T: PartialEq>(a: &Option<T>, b: &Option<T>) -> bool {
+            matches!((a, b), (Some(a), Some(b)) if a != b)
+        }
+        differs(&self.last_update_time, &other.last_update_time)
+            || differs(&self.liked_count, &other.liked_count)
+            || differs(&self.collected_count, &other.collected_count)
+            || differs(&self.comment_count, &other.comment_count)
+            || differs(&self.share_count, &other.share_count)
+    }
+}
+
+struct CachedNote {
+    response: NoteDetailResponse,
+    version: NoteVersion,
+    fetched_at: Instant,
+}
+
+/// 笔记详情缓存
+pub struct NoteDetailCache {
+    entries: RwLock<HashMap<String, CachedNote>>,
+    ttl: Duration,
+    max_entries: usize,
+}
+
+impl NoteDetailCache {
+    /// 创建缓存，`ttl` 为零时禁用缓存
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            entries: RwLock::new(HashMap::new()),
+            ttl,
+            max_entries: DEFAULT_MAX_ENTRIES,
+        }
+    }
+
+    /// 是否启用
+    pub fn is_enabled(&self) -> bool {
+        !self.ttl.is_zero()
+    }
+
+    /// 读取未过期的缓存
+    pub async fn get(&self, note_id: &str) -> Option<NoteDetailResponse> {
+        if !self.is_enabled() {
+            return None;
+        }
+        let entries = self.entries.read().await;
+        entries
+            .get(note_id)
+            .filter(|entry| entry.fetched_at.elapsed() < self.ttl)
+            .map(|entry| entry.response.clone())
+    }
+
+    /// 写入缓存（仅缓存成功的响应）
+    pub async fn insert(&self, note_id: &str, response: &NoteDetailResponse) {
+        if !self.is_enabled() || !response.success {
+            return;
+        }
+        let version = response
+            .note_card()
+            .map(NoteVersion::from_note_card)
+            .unwrap_or_default();
+
+        let mut entries = self.entries.write().await;
+        if entries.len() >= self.max_entries && !entries.contains_key(note_id) {
+            // 淘汰最早拉取的条目
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.fetched_at)
+                .map(|(id, _)| id.clone());
+            if let Some(id) = oldest {
+                entries.remove(&id);
+            }
+        }
+        entries.insert(
+            note_id.to_string(),
+            CachedNote {
+                response: response.clone(),
+                version,
+                fetched_at: Instant::now(),
+            },
+        );
+    }
+
+    /// 观察到笔记的新版本，与缓存版本不一致时使缓存失效
+    pub async fn observe(&self, note_id: &str, version: &NoteVersion) {
+        if !self.is_enabled() {
+            return;
+        }
+        let mut entries = self.entries.write().await;
+        let stale = entries
+            .get(note_id)
+            .is_some_and(|entry| entry.version.conflicts_with(version));
+        if stale {
+            tracing::info!("[NoteDetailCache] {} changed, invalidating cached detail", note_id);
+            entries.remove(note_id);
+        }
+    }
+
+    /// 批量观察 Feed / 搜索结果中的笔记
+    pub async fn observe_items(&self, items: &[HomefeedItem]) {
+        if !self.is_enabled() {
+            return;
+        }
+        for item in items {
+            self.observe(&item.id, &NoteVersion::from_feed_item(item)).await;
+        }
+    }
+
+    /// 手动使某篇笔记失效
+    pub async fn invalidate(&self, note_id: &str) {
+        self.entries.write().await.remove(note_id);
+    }
+}
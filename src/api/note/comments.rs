@@ -0,0 +1,409 @@
+//! Note Comment Crawler
+//!
+//! Pages through every top-level comment of a note (`comment/page`) and, for each
+//! comment whose replies were truncated, through its sub-comments (`comment/sub/page`).
+//! Sub-comment threads of one page are fetched with bounded concurrency; every request
+//! still goes through XhsApiClient's rate limiter, risk breaker and pacing.
+
+#[cfg(feature = "server")]
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "server")]
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use utoipa::ToSchema;
+
+use crate::api::XhsApiClient;
+use crate::error::{Result, XhsError};
+use crate::models::feed::recommend::InteractInfo;
+#[cfg(feature = "server")]
+use crate::server::AppState;
+
+/// 子评论每页条数（与网页端一致）
+const SUB_PAGE_SIZE: u32 = 10;
+/// 默认并发抓取的子评论线程数
+const DEFAULT_CONCURRENCY: usize = 2;
+/// 最大并发
+const MAX_CONCURRENCY: usize = 5;
+
+/// 整篇评论抓取请求
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub struct AllCommentsRequest {
+    /// 笔记 ID (必填)
+    pub note_id: String,
+    /// xsec_token (必填)
+    pub xsec_token: String,
+    /// 是否展开全部子评论（默认 true；false 时只保留一级评论自带的前几条回复）
+    #[serde(default = "default_true")]
+    pub sub_comments: bool,
+    /// 最多抓取的评论数（含子评论），默认不限
+    #[serde(default)]
+    pub max_comments: Option<usize>,
+    /// 并发抓取的子评论线程数，默认 2，最大 5
+    #[serde(default = "default_concurrency")]
+    pub concurrency: usize,
+    /// 写入笔记归档而不是流式返回（需启用 XHS_ARCHIVE_BACKEND）
+    #[serde(default)]
+    pub archive: bool,
+}
+
+fn default_true() -> bool { true }
+
+fn default_concurrency() -> usize { DEFAULT_CONCURRENCY }
+
+/// 单条评论（一级评论与子评论共用）
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub struct NoteComment {
+    pub comment_id: String,
+    pub note_id: String,
+    /// 所属一级评论 ID（一级评论为空）
+    #[serde(default)]
+    pub root_comment_id: Option<String>,
+    /// 回复的评论 ID（直接回复一级评论时与 root_comment_id 相同）
+    #[serde(default)]
+    pub target_comment_id: Option<String>,
+    pub user_id: String,
+    pub nickname: String,
+    pub content: String,
+    /// 点赞数
+    pub like_count: Option<i64>,
+    /// 子评论数（仅一级评论）
+    #[serde(default)]
+    pub sub_comment_count: Option<i64>,
+    /// 评论时间 (ms)
+    pub create_time: Option<i64>,
+    /// IP 属地
+    #[serde(default)]
+    pub ip_location: Option<String>,
+    /// 评论图片地址
+    #[serde(default)]
+    pub pictures: Vec<String>,
+}
+
+impl NoteComment {
+    /// 从 comment/page 或 comment/sub/page 的评论对象提取
+    pub fn from_value(note_id: &str, root_comment_id: Option<&str>, value: &serde_json::Value) -> Option<Self> {
+        let text = |pointer: &str| value.pointer(pointer).and_then(|v| v.as_str()).map(str::to_string);
+        let count = |key: &str| match value.get(key)? {
+            serde_json::Value::String(s) => InteractInfo::parse_count(s),
+            v => v.as_i64(),
+        };
+        let pictures = value
+            .get("pictures")
+            .and_then(|v| v.as_array())
+            .map(|pictures| {
+                pictures
+                    .iter()
+                    .filter_map(|p| p.get("url_default").and_then(|u| u.as_str()).map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Some(Self {
+            comment_id: text("/id")?,
+            note_id: note_id.to_string(),
+            root_comment_id: root_comment_id.map(str::to_string),
+            target_comment_id: text("/target_comment/id").or_else(|| root_comment_id.map(str::to_string)),
+            user_id: text("/user_info/user_id").unwrap_or_default(),
+            nickname: text("/user_info/nickname").unwrap_or_default(),
+            content: text("/content").unwrap_or_default(),
+            like_count: count("like_count"),
+            sub_comment_count: root_comment_id.is_none().then(|| count("sub_comment_count")).flatten(),
+            create_time: value.get("create_time").and_then(|v| v.as_i64()),
+            ip_location: text("/ip_location"),
+            pictures,
+        })
+    }
+}
+
+/// 抓取统计
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct CommentCrawlSummary {
+    pub note_id: String,
+    /// 一级评论数
+    pub comments: usize,
+    /// 子评论数
+    pub sub_comments: usize,
+    /// 请求的页数
+    pub pages: usize,
+    /// 是否因 max_comments 提前停止
+    pub truncated: bool,
+    /// 中途失败的原因（已抓取的评论仍然有效）
+    pub error: Option<String>,
+}
+
+impl CommentCrawlSummary {
+    fn total(&self) -> usize {
+        self.comments + self.sub_comments
+    }
+}
+
+/// 流式响应的每一行：评论逐条输出，最后一行为统计
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CommentStreamLine {
+    Comment(NoteComment),
+    Summary(CommentCrawlSummary),
+}
+
+/// 一页评论
+pub(crate) struct CommentPage {
+    pub(crate) comments: Vec<serde_json::Value>,
+    pub(crate) cursor: String,
+    pub(crate) has_more: bool,
+}
+
+impl CommentPage {
+    pub(crate) fn parse(endpoint: &str, mut response: serde_json::Value) -> Result<Self> {
+        if response.get("success").and_then(|v| v.as_bool()) == Some(false) {
+            let msg = response.get("msg").and_then(|v| v.as_str()).unwrap_or("unknown error");
+            return Err(XhsError::Other(anyhow::anyhow!("{} failed: {}", endpoint, msg)));
+        }
+        let mut data = response.get_mut("data").map(serde_json::Value::take).unwrap_or_default();
+        Ok(Self {
+            comments: match data.get_mut("comments").map(serde_json::Value::take) {
+                Some(serde_json::Value::Array(comments)) => comments,
+                _ => Vec::new(),
+            },
+            cursor: data.get("cursor").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+            has_more: data.get("has_more").and_then(|v| v.as_bool()).unwrap_or(false),
+        })
+    }
+}
+
+/// 获取一页子评论
+pub async fn fetch_sub_comment_page(
+    api: &XhsApiClient,
+    note_id: &str,
+    root_comment_id: &str,
+    cursor: &str,
+    xsec_token: &str,
+) -> Result<serde_json::Value> {
+    let url = format!(
+        "https://edith.xiaohongshu.com/api/sns/web/v2/comment/sub/page?note_id={}&root_comment_id={}&num={}&cursor={}&image_formats=jpg,webp,avif&top_comment_id=&xsec_token={}",
+        note_id,
+        root_comment_id,
+        SUB_PAGE_SIZE,
+        cursor,
+        urlencoding::encode(xsec_token)
+    );
+    let text = api.get_with_url("note_sub_comments", &url).await?;
+    Ok(serde_json::from_str(&text)?)
+}
+
+/// 抓取一条一级评论剩余的全部子评论
+async fn crawl_thread(
+    api: &XhsApiClient,
+    req: &AllCommentsRequest,
+    root_comment_id: String,
+    mut cursor: String,
+) -> (Vec<NoteComment>, usize, Option<XhsError>) {
+    let mut replies = Vec::new();
+    let mut pages = 0;
+    loop {
+        let page = match fetch_sub_comment_page(api, &req.note_id, &root_comment_id, &cursor, &req.xsec_token)
+            .await
+            .and_then(|v| CommentPage::parse("comment/sub/page", v))
+        {
+            Ok(page) => page,
+            Err(e) => return (replies, pages, Some(e)),
+        };
+        pages += 1;
+        replies.extend(
+            page.comments
+                .iter()
+                .filter_map(|c| NoteComment::from_value(&req.note_id, Some(&root_comment_id), c)),
+        );
+        if !page.has_more || page.cursor.is_empty() || page.cursor == cursor {
+            return (replies, pages, None);
+        }
+        cursor = page.cursor;
+    }
+}
+
+/// 抓取笔记的全部评论，逐条发送到 `tx`（接收端关闭时停止），返回统计
+///
+/// 中途失败时停止抓取，错误记录在统计的 `error` 中
+pub async fn crawl_comments(
+    api: &XhsApiClient,
+    req: &AllCommentsRequest,
+    tx: mpsc::Sender<NoteComment>,
+) -> CommentCrawlSummary {
+    let mut summary = CommentCrawlSummary { note_id: req.note_id.clone(), ..Default::default() };
+    let limit = req.max_comments.unwrap_or(usize::MAX);
+    let concurrency = req.concurrency.clamp(1, MAX_CONCURRENCY);
+    let mut cursor = String::new();
+
+    'pages: loop {
+        let params = super::page::NotePageParams {
+            note_id: req.note_id.clone(),
+            cursor: cursor.clone(),
+            top_comment_id: String::new(),
+            image_formats: "jpg,webp,avif".to_string(),
+            xsec_token: req.xsec_token.clone(),
+        };
+        let page = match super::page::fetch_note_page(api, params).await
+            .and_then(|v| CommentPage::parse("comment/page", v))
+        {
+            Ok(page) => page,
+            Err(e) => {
+                summary.error = Some(e.to_string());
+                break;
+            }
+        };
+        summary.pages += 1;
+
+        // 一级评论连同自带的前几条回复按顺序输出，回复未展示完的线程稍后并发补齐
+        let mut threads = Vec::new();
+        for value in &page.comments {
+            let Some(comment) = NoteComment::from_value(&req.note_id, None, value) else {
+                continue;
+            };
+            let root_id = comment.comment_id.clone();
+            let mut batch = vec![comment];
+            batch.extend(
+                value.get("sub_comments")
+                    .and_then(|v| v.as_array())
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|c| NoteComment::from_value(&req.note_id, Some(&root_id), c)),
+            );
+            let has_more = value.get("sub_comment_has_more").and_then(|v| v.as_bool()).unwrap_or(false);
+            let sub_cursor = value.get("sub_comment_cursor").and_then(|v| v.as_str()).unwrap_or_default();
+            if req.sub_comments && has_more && !sub_cursor.is_empty() {
+                threads.push((root_id, sub_cursor.to_string()));
+            }
+            if !emit(&tx, &mut summary, batch, limit).await {
+                break 'pages;
+            }
+        }
+
+        let mut fetches = futures_util::stream::iter(threads)
+            .map(|(root_id, sub_cursor)| crawl_thread(api, req, root_id, sub_cursor))
+            .buffer_unordered(concurrency);
+        while let Some((replies, pages, error)) = fetches.next().await {
+            summary.pages += pages;
+            if !emit(&tx, &mut summary, replies, limit).await {
+                break 'pages;
+            }
+            if let Some(e) = error {
+                summary.error = Some(e.to_string());
+                break 'pages;
+            }
+        }
+
+        if !page.has_more || page.cursor.is_empty() || page.cursor == cursor {
+            break;
+        }
+        cursor = page.cursor;
+    }
+
+    tracing::info!(
+        "[Comments] Note {}: {} comment(s), {} sub-comment(s) in {} page(s){}",
+        summary.note_id, summary.comments, summary.sub_comments, summary.pages,
+        summary.error.as_deref().map(|e| format!(", stopped: {}", e)).unwrap_or_default()
+    );
+    summary
+}
+
+/// 发送一批评论并计数；达到上限或接收端关闭时返回 false
+async fn emit(
+    tx: &mpsc::Sender<NoteComment>,
+    summary: &mut CommentCrawlSummary,
+    comments: Vec<NoteComment>,
+    limit: usize,
+) -> bool {
+    for comment in comments {
+        if summary.total() >= limit {
+            summary.truncated = true;
+            return false;
+        }
+        if comment.root_comment_id.is_some() {
+            summary.sub_comments += 1;
+        } else {
+            summary.comments += 1;
+        }
+        if tx.send(comment).await.is_err() {
+            return false;
+        }
+    }
+    true
+}
+
+/// 抓取笔记全部评论
+///
+/// 自动翻页抓取全部一级评论与子评论，默认以 NDJSON 流式返回；`archive=true` 时写入归档并返回统计
+#[cfg(feature = "server")]
+#[utoipa::path(
+    post,
+    path = "/api/note/comments/all",
+    tag = "Note",
+    summary = "抓取笔记全部评论",
+    description = "自动追踪游标翻完全部一级评论，并展开每条评论被折叠的子评论（`sub_comments=false` 时跳过）。子评论线程按 `concurrency` 并发抓取（默认 2，最大 5），所有请求仍经过限流 / 拟人节奏 / 风控熔断。\n\n- 默认：`application/x-ndjson` 流式返回，每行一个 `{\"type\":\"comment\", ...}`，最后一行为 `{\"type\":\"summary\", ...}`（中途失败时 `error` 非空，已输出的评论仍然有效）\n- `archive=true`：写入笔记归档（需启用 XHS_ARCHIVE_BACKEND，按 comment_id 覆盖），返回 JSON 统计，可通过 `/api/archive/notes/{note_id}/comments` 读取",
+    request_body = AllCommentsRequest,
+    responses(
+        (status = 200, description = "NDJSON 评论流，或 archive=true 时的抓取统计", body = CommentCrawlSummary),
+        (status = 503, description = "archive=true 但未启用归档", body = CommentCrawlSummary)
+    )
+)]
+pub async fn crawl_all_comments(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<AllCommentsRequest>,
+) -> impl IntoResponse {
+    let (tx, rx) = mpsc::channel::<NoteComment>(256);
+
+    if req.archive {
+        let Some(archive) = state.api.archive().cloned() else {
+            let summary = CommentCrawlSummary {
+                note_id: req.note_id,
+                error: Some("Response archive is disabled (set XHS_ARCHIVE_BACKEND=file|mongo)".to_string()),
+                ..Default::default()
+            };
+            return (StatusCode::SERVICE_UNAVAILABLE, Json(summary)).into_response();
+        };
+        let collector = tokio::spawn(async move {
+            let mut rx = rx;
+            let mut comments = Vec::new();
+            while let Some(comment) = rx.recv().await {
+                comments.push(comment);
+            }
+            comments
+        });
+        let mut summary = crawl_comments(&state.api, &req, tx).await;
+        let comments = collector.await.unwrap_or_default();
+        // 中途失败时也保存已抓取的部分
+        if let Err(e) = archive.store_comments(&req.note_id, &comments).await {
+            summary.error = Some(format!("Failed to archive comments: {}", e));
+        }
+        return Json(summary).into_response();
+    }
+
+    let crawler = tokio::spawn(async move { crawl_comments(&state.api, &req, tx).await });
+    let lines = futures_util::stream::unfold((rx, Some(crawler)), |(mut rx, crawler)| async move {
+        let line = match rx.recv().await {
+            Some(comment) => CommentStreamLine::Comment(comment),
+            None => {
+                // 发送端已关闭，抓取结束，最后输出统计
+                let summary = crawler?.await.unwrap_or_else(|e| CommentCrawlSummary {
+                    error: Some(format!("Crawler task failed: {}", e)),
+                    ..Default::default()
+                });
+                let mut line = serde_json::to_vec(&CommentStreamLine::Summary(summary)).unwrap_or_default();
+                line.push(b'\n');
+                return Some((Ok::<_, std::io::Error>(line), (rx, None)));
+            }
+        };
+        let mut bytes = serde_json::to_vec(&line).unwrap_or_default();
+        bytes.push(b'\n');
+        Some((Ok(bytes), (rx, crawler)))
+    });
+
+    ([(header::CONTENT_TYPE, "application/x-ndjson")], Body::from_stream(lines)).into_response()
+}
@@ -2,15 +2,20 @@
 //! 
 //! Fetches the actual content of a note (title, description, images, etc.)
 
+#[cfg(feature = "server")]
 use axum::{
     extract::State,
     response::IntoResponse,
     Json,
 };
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "server")]
 use std::sync::Arc;
 use utoipa::ToSchema;
+#[cfg(feature = "server")]
 use crate::server::AppState;
+use super::cache::NoteDetailCache;
+use crate::models::parse;
 
 /// 笔记详情请求参数
 #[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
@@ -30,6 +35,19 @@ pub struct NoteDetailRequest {
     pub xsec_token: String,
 }
 
+impl NoteDetailRequest {
+    /// 默认参数的详情请求（与媒体地址解析接口一致，附带 need_body_topic）
+    pub fn new(source_note_id: impl Into<String>, xsec_token: impl Into<String>) -> Self {
+        Self {
+            source_note_id: source_note_id.into(),
+            image_formats: default_image_formats(),
+            extra: Some(serde_json::json!({"need_body_topic": "1"})),
+            xsec_source: default_xsec_source(),
+            xsec_token: xsec_token.into(),
+        }
+    }
+}
+
 fn default_image_formats() -> Vec<String> {
     vec!["jpg".to_string(), "webp".to_string(), "avif".to_string()]
 }
@@ -40,6 +58,8 @@ fn default_xsec_source() -> String {
 
 /// 笔记详情响应 (简化)
 #[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
+#[schema(example = crate::models::examples::note_detail_response)]
 pub struct NoteDetailResponse {
     pub code: i32,
     pub success: bool,
@@ -49,14 +69,25 @@ pub struct NoteDetailResponse {
     pub data: Option<serde_json::Value>,
 }
 
+impl NoteDetailResponse {
+    /// 详情中的笔记卡片 (data.items[0].note_card)
+    pub fn note_card(&self) -> Option<&serde_json::Value> {
+        self.data.as_ref().and_then(|data| data.pointer("/items/0/note_card"))
+    }
+}
+
 /// 获取笔记详情
 /// 
 /// 获取指定笔记的完整内容，包括标题、正文、图片、标签、互动数据等。
 /// 这是点击 Feed 中某篇笔记后弹出的详情页内容。
 /// 
+/// 详情按 note_id 缓存（XHS_NOTE_CACHE_TTL_SECS，默认 60 秒），
+/// Feed / 搜索结果中观察到互动数据变化时缓存自动失效。
+/// 
 /// 参数说明：
 /// - `source_note_id`: 笔记ID，从 Feed 或搜索结果中获取
 /// - `xsec_token`: 安全令牌，从 Feed 返回的笔记信息中获取
+#[cfg(feature = "server")]
 #[utoipa::path(
     post,
     path = "/api/note/detail",
@@ -73,22 +104,25 @@ pub async fn get_note_detail(
     State(state): State<Arc<AppState>>,
     Json(req): Json<NoteDetailRequest>,
 ) -> impl IntoResponse {
-    match get_note_detail_internal(&state.api, req).await {
+    match fetch_note_detail(&state.api, &state.note_cache, req).await {
         Ok(data) => Json(data).into_response(),
-        Err(e) => Json(serde_json::json!({
-            "code": -1,
-            "success": false,
-            "msg": e.to_string(),
-            "data": null
-        })).into_response(),
+        Err(e) => e.into_response(),
     }
 }
 
-async fn get_note_detail_internal(
+/// 获取笔记详情（handler 背后的库函数，命中缓存时不请求上游）
+pub async fn fetch_note_detail(
     api: &crate::api::XhsApiClient,
+    cache: &NoteDetailCache,
     req: NoteDetailRequest,
-) -> anyhow::Result<NoteDetailResponse> {
+) -> crate::error::Result<NoteDetailResponse> {
+    if let Some(cached) = cache.get(&req.source_note_id).await.filter(|_| !crate::api::dry_run::is_active() && !crate::api::raw::is_active()) {
+        tracing::info!("[NoteDetail] {} served from cache", req.source_note_id);
+        return Ok(cached);
+    }
+    
     let path = "/api/sns/web/v1/feed";
+    let note_id = req.source_note_id.clone();
     
     // 构造请求体
     let mut payload = serde_json::json!({
@@ -104,6 +138,7 @@ async fn get_note_detail_internal(
     }
     
     let text = api.post_algo(path, payload).await?;
-    let response: NoteDetailResponse = serde_json::from_str(&text)?;
+    let response: NoteDetailResponse = parse::from_str(&text)?;
+    cache.insert(&note_id, &response).await;
     Ok(response)
 }
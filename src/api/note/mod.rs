@@ -1,2 +1,6 @@
 pub mod page;
 pub mod detail;
+pub mod cache;
+pub mod stats;
+pub mod comments;
+pub mod resolve;
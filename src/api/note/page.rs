@@ -1,10 +1,13 @@
+#[cfg(feature = "server")]
 use axum::{
     extract::{Query, State},
     response::IntoResponse,
     Json,
 };
 use serde::Deserialize;
+#[cfg(feature = "server")]
 use std::sync::Arc;
+#[cfg(feature = "server")]
 use crate::server::AppState;
 
 /// 笔记评论页请求参数
@@ -38,6 +41,7 @@ fn default_image_formats() -> String {
 /// - `note_id`: 笔记ID，从笔记URL或Feed中获取
 /// - `cursor`: 分页游标，首次请求为空，后续请求使用上次返回的cursor
 /// - `xsec_token`: 安全令牌，从笔记详情页获取
+#[cfg(feature = "server")]
 #[utoipa::path(
     get,
     path = "/api/note/page",
@@ -54,21 +58,17 @@ pub async fn get_note_page(
     State(state): State<Arc<AppState>>,
     Query(params): Query<NotePageParams>,
 ) -> impl IntoResponse {
-    match get_note_page_internal(&state.api, params).await {
+    match fetch_note_page(&state.api, params).await {
         Ok(data) => Json(data).into_response(),
-        Err(e) => Json(serde_json::json!({
-            "code": -1,
-            "success": false,
-            "msg": e.to_string(),
-            "data": null
-        })).into_response(),
+        Err(e) => e.into_response(),
     }
 }
 
-async fn get_note_page_internal(
+/// 获取笔记评论页（handler 背后的库函数）
+pub async fn fetch_note_page(
     api: &crate::api::XhsApiClient,
     params: NotePageParams,
-) -> anyhow::Result<serde_json::Value> {
+) -> crate::error::Result<serde_json::Value> {
     // 构造完整 URL（note_page 是 GET 请求，参数在 URL 中）
     let url = format!(
         "https://edith.xiaohongshu.com/api/sns/web/v2/comment/page?note_id={}&cursor={}&top_comment_id={}&image_formats={}&xsec_token={}",
@@ -0,0 +1,121 @@
+//! Note Link Resolver
+//!
+//! 从笔记链接解析 note_id 与 xsec_token，结果可直接用于 `/api/note/detail` 与媒体接口。
+//! 支持：
+//! - `https://www.xiaohongshu.com/explore/<id>?xsec_token=...`
+//! - `https://www.xiaohongshu.com/discovery/item/<id>?xsec_token=...`
+//! - `http://xhslink.com/...` 短链
+//! - App 分享文案（取其中第一个小红书链接）
+//!
+//! 链接解析与短链展开见 [`crate::utils::url`]。
+
+#[cfg(feature = "server")]
+use axum::{
+    extract::Query,
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::error::{Result, XhsError};
+#[cfg(feature = "server")]
+use crate::response::ApiResponse;
+use crate::utils::url::{self, XhsLink};
+
+/// 笔记链接解析参数
+#[derive(Debug, Clone, Deserialize, utoipa::IntoParams)]
+pub struct ResolveNoteParams {
+    /// 笔记链接、xhslink.com 短链或包含链接的分享文案
+    pub url: String,
+}
+
+/// 解析结果
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "note_id": "69539b19000000002202c106",
+    "xsec_token": "ABx8fQ2y1example=",
+    "xsec_source": "app_share",
+    "url": "https://www.xiaohongshu.com/explore/69539b19000000002202c106?xsec_token=ABx8fQ2y1example%3D&xsec_source=app_share",
+    "resolved_url": "https://www.xiaohongshu.com/discovery/item/69539b19000000002202c106?app_platform=ios&xsec_token=ABx8fQ2y1example=&xsec_source=app_share"
+}))]
+pub struct ResolvedNote {
+    pub note_id: String,
+    /// 链接中没有 xsec_token 时为空（此时无法请求详情，需从 feed / 搜索结果获取）
+    pub xsec_token: Option<String>,
+    /// 链接中的 xsec_source（如 app_share / pc_search）
+    pub xsec_source: Option<String>,
+    /// 规范化的笔记链接
+    pub url: String,
+    /// 短链展开后的原始链接
+    pub resolved_url: String,
+}
+
+/// 解析笔记链接
+///
+/// 从笔记链接、xhslink.com 短链或 App 分享文案中解析 note_id 与 xsec_token。
+#[cfg(feature = "server")]
+#[utoipa::path(
+    get,
+    path = "/api/note/resolve",
+    tag = "Note",
+    summary = "解析笔记链接",
+    description = "接受 `xiaohongshu.com/explore/<id>` / `discovery/item/<id>` 链接、`xhslink.com` 短链或包含链接的分享文案，跟随短链重定向，返回 `note_id` 与 `xsec_token`，可直接用于 `/api/note/detail`、`/api/note/images`、`/api/note/video` 等接口。",
+    params(ResolveNoteParams),
+    responses(
+        (status = 200, description = "解析结果", body = ApiResponse<ResolvedNote>),
+        (status = 400, description = "无法识别的链接"),
+        (status = 502, description = "短链请求失败")
+    )
+)]
+pub async fn resolve_note(Query(params): Query<ResolveNoteParams>) -> impl IntoResponse {
+    match resolve_note_url(&params.url).await {
+        Ok(data) => ApiResponse::ok(data).into_response(),
+        Err(XhsError::Other(e)) => (StatusCode::BAD_REQUEST, ApiResponse::<ResolvedNote>::error(e.to_string())).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// 解析笔记链接（handler 背后的库函数）
+///
+/// 无法识别的输入或非笔记链接返回 `XhsError::Other`，短链请求失败返回 `XhsError::Network`
+pub async fn resolve_note_url(input: &str) -> Result<ResolvedNote> {
+    let (link, resolved) = url::resolve(input).await?;
+    let url = link.share_url();
+    match link {
+        XhsLink::Note { note_id, xsec_token, xsec_source } => Ok(ResolvedNote {
+            note_id,
+            xsec_token,
+            xsec_source,
+            url,
+            resolved_url: resolved.to_string(),
+        }),
+        _ => Err(XhsError::Other(anyhow::anyhow!("Not a note link: {}", resolved))),
+    }
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request, routing::get, Router};
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn error_response_carries_request_id() {
+        let app = Router::new()
+            .route("/api/note/resolve", get(resolve_note))
+            .layer(axum::middleware::from_fn(crate::response::request_id));
+        let request = Request::get("/api/note/resolve?url=https%3A%2F%2Fexample.com%2Fpost")
+            .header("x-request-id", "resolve-test-1")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["code"], -1);
+        assert_eq!(json["success"], false);
+        assert_eq!(json["request_id"], "resolve-test-1");
+    }
+}
@@ -0,0 +1,148 @@
+//! Note Stats API
+//!
+//! 笔记互动数据快照（点赞 / 收藏 / 评论 / 分享），基于详情接口提取，
+//! 计数同时返回上游展示文本与解析后的数值
+
+#[cfg(feature = "server")]
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "server")]
+use std::sync::Arc;
+use utoipa::ToSchema;
+#[cfg(feature = "server")]
+use crate::response::ApiResponse;
+#[cfg(feature = "server")]
+use crate::server::AppState;
+use crate::models::feed::InteractInfo;
+use super::cache::NoteDetailCache;
+use super::detail::{fetch_note_detail, NoteDetailRequest};
+
+/// 笔记互动数据查询参数
+#[derive(Debug, Clone, Deserialize, utoipa::IntoParams)]
+pub struct NoteStatsParams {
+    /// xsec_token (必填，从 feed / 搜索结果中获取)
+    pub xsec_token: String,
+    /// xsec_source (默认: pc_feed)
+    #[serde(default = "default_xsec_source")]
+    pub xsec_source: String,
+}
+
+fn default_xsec_source() -> String {
+    "pc_feed".to_string()
+}
+
+/// 笔记互动数据快照
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "note_id": "69539b19000000002202c106",
+    "liked_count": 12000,
+    "collected_count": 652,
+    "comment_count": 87,
+    "share_count": 143,
+    "interact_info": {
+        "liked": false,
+        "liked_count": "1.2万",
+        "collected": false,
+        "collected_count": "652",
+        "comment_count": "87",
+        "share_count": "143",
+        "followed": false
+    },
+    "last_update_time": 1767085200000_i64
+}))]
+pub struct NoteStats {
+    pub note_id: String,
+    /// 点赞数
+    pub liked_count: Option<i64>,
+    /// 收藏数
+    pub collected_count: Option<i64>,
+    /// 评论数
+    pub comment_count: Option<i64>,
+    /// 分享数
+    pub share_count: Option<i64>,
+    /// 上游原始互动信息（展示文本，如 "1.2万"，及当前账号的点赞/收藏/关注状态）
+    pub interact_info: InteractInfo,
+    /// 笔记最后更新时间 (毫秒)
+    pub last_update_time: Option<i64>,
+}
+
+impl NoteStats {
+    /// 从详情接口的 note_card 提取
+    pub fn from_note_card(note_id: &str, note_card: &serde_json::Value) -> crate::error::Result<Self> {
+        let interact_info: InteractInfo = serde_json::from_value(
+            note_card.get("interact_info").cloned().unwrap_or_default(),
+        )?;
+        let count = |text: &Option<String>| text.as_deref().and_then(InteractInfo::parse_count);
+        Ok(Self {
+            note_id: note_id.to_string(),
+            liked_count: count(&interact_info.liked_count),
+            collected_count: count(&interact_info.collected_count),
+            comment_count: count(&interact_info.comment_count),
+            share_count: count(&interact_info.share_count),
+            last_update_time: note_card.get("last_update_time").and_then(|v| v.as_i64()),
+            interact_info,
+        })
+    }
+}
+
+/// 笔记互动数据
+///
+/// 返回点赞、收藏、评论、分享的完整快照。
+/// 数据来自笔记详情接口（与 `/api/note/detail` 共用缓存，互动数据变化时缓存自动失效）。
+#[cfg(feature = "server")]
+#[utoipa::path(
+    get,
+    path = "/api/note/{note_id}/stats",
+    tag = "Note",
+    summary = "笔记互动数据",
+    description = "点赞 / 收藏 / 评论 / 分享计数（数值 + 上游展示文本）",
+    params(
+        ("note_id" = String, Path, description = "笔记 ID"),
+        NoteStatsParams
+    ),
+    responses(
+        (status = 200, description = "互动数据快照", body = ApiResponse<NoteStats>),
+        (status = 500, description = "请求失败")
+    )
+)]
+pub async fn get_note_stats(
+    State(state): State<Arc<AppState>>,
+    Path(note_id): Path<String>,
+    Query(params): Query<NoteStatsParams>,
+) -> impl IntoResponse {
+    match fetch_note_stats(&state.api, &state.note_cache, &note_id, params).await {
+        Ok(stats) => ApiResponse::ok(stats).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// 获取笔记互动数据（handler 背后的库函数）
+pub async fn fetch_note_stats(
+    api: &crate::api::XhsApiClient,
+    cache: &NoteDetailCache,
+    note_id: &str,
+    params: NoteStatsParams,
+) -> crate::error::Result<NoteStats> {
+    let detail = fetch_note_detail(api, cache, NoteDetailRequest {
+        source_note_id: note_id.to_string(),
+        image_formats: vec!["jpg".to_string(), "webp".to_string(), "avif".to_string()],
+        extra: None,
+        xsec_source: params.xsec_source,
+        xsec_token: params.xsec_token,
+    }).await?;
+
+    if !detail.success {
+        return Err(crate::error::XhsError::Business {
+            code: detail.code as i64,
+            msg: detail.msg.unwrap_or_default(),
+        });
+    }
+    let note_card = detail
+        .note_card()
+        .ok_or_else(|| crate::error::XhsError::Parse(format!("Note {} not found in detail response", note_id)))?;
+
+    NoteStats::from_note_card(note_id, note_card)
+}
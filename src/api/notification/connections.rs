@@ -1,6 +1,7 @@
 use crate::api::XhsApiClient;
-use anyhow::Result;
+use crate::error::Result;
 use serde::{Deserialize, Serialize};
+use crate::models::parse;
 
 /// Connections request parameters (新增关注 请求参数)
 #[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema, utoipa::IntoParams)]
@@ -31,6 +32,7 @@ impl Default for ConnectionsParams {
 
 /// Connections response (新增关注 通知)
 #[derive(Debug, Serialize, Deserialize, utoipa::ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
 pub struct ConnectionsResponse {
     pub success: bool,
     pub msg: String,
@@ -38,6 +40,7 @@ pub struct ConnectionsResponse {
 }
 
 #[derive(Debug, Serialize, Deserialize, utoipa::ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
 pub struct ConnectionsData {
     /// 通知消息列表
     pub message_list: Vec<serde_json::Value>,
@@ -72,7 +75,7 @@ pub async fn get_connections_with_params(api: &XhsApiClient, params: Connections
     let uri = format!("/api/sns/web/v1/you/connections?num={}&cursor={}", params.num, cursor);
     
     let text = api.get_with_query(&uri).await?;
-    let result = serde_json::from_str::<ConnectionsResponse>(&text)?;
+    let result = parse::from_str::<ConnectionsResponse>(&text)?;
     Ok(result)
 }
 
@@ -1,6 +1,7 @@
 use crate::api::XhsApiClient;
-use anyhow::Result;
+use crate::error::Result;
 use serde::{Deserialize, Serialize};
+use crate::models::parse;
 
 /// Likes request parameters (赞和收藏 请求参数)
 #[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema, utoipa::IntoParams)]
@@ -31,6 +32,7 @@ impl Default for LikesParams {
 
 /// Likes response (赞和收藏 通知)
 #[derive(Debug, Serialize, Deserialize, utoipa::ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
 pub struct LikesResponse {
     pub success: bool,
     pub msg: String,
@@ -38,6 +40,7 @@ pub struct LikesResponse {
 }
 
 #[derive(Debug, Serialize, Deserialize, utoipa::ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
 pub struct LikesData {
     /// 通知消息列表
     pub message_list: Vec<serde_json::Value>,
@@ -72,6 +75,6 @@ pub async fn get_likes_with_params(api: &XhsApiClient, params: LikesParams) -> R
     let uri = format!("/api/sns/web/v1/you/likes?num={}&cursor={}", params.num, cursor);
     
     let text = api.get_with_query(&uri).await?;
-    let result = serde_json::from_str::<LikesResponse>(&text)?;
+    let result = parse::from_str::<LikesResponse>(&text)?;
     Ok(result)
 }
@@ -1,6 +1,7 @@
 use crate::api::XhsApiClient;
-use anyhow::Result;
+use crate::error::Result;
 use serde::{Deserialize, Serialize};
+use crate::models::parse;
 
 /// Mentions request parameters (评论和@ 请求参数)
 #[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema, utoipa::IntoParams)]
@@ -31,6 +32,7 @@ impl Default for MentionsParams {
 
 /// Mentions response (评论和@ 通知)
 #[derive(Debug, Serialize, Deserialize, utoipa::ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
 pub struct MentionsResponse {
     pub code: Option<i32>,
     pub success: bool,
@@ -39,6 +41,7 @@ pub struct MentionsResponse {
 }
 
 #[derive(Debug, Serialize, Deserialize, utoipa::ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
 pub struct MentionsData {
     /// 下一页游标 (数值型)
     pub cursor: Option<i64>,
@@ -72,7 +75,7 @@ pub async fn get_mentions_with_params(api: &XhsApiClient, params: MentionsParams
     let uri = format!("/api/sns/web/v1/you/mentions?num={}&cursor={}", params.num, cursor);
     
     let text = api.get_with_query(&uri).await?;
-    let result = serde_json::from_str::<MentionsResponse>(&text)?;
+    let result = parse::from_str::<MentionsResponse>(&text)?;
     Ok(result)
 }
 
@@ -1,9 +1,12 @@
 // Notification API module
-// Handles mentions, connections and likes endpoints
+// Handles mentions, connections and likes endpoints, plus long-poll on unread counts
+// and webhook delivery of new notifications
 
 pub mod mentions;
 pub mod connections;
 pub mod likes;
+pub mod poll;
+pub mod webhook;
 
 pub use mentions::get_mentions;
 pub use connections::get_connections;
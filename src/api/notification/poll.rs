@@ -0,0 +1,180 @@
+use crate::api::XhsApiClient;
+use crate::error::Result;
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use crate::models::parse;
+
+/// 长轮询最长等待时间
+pub const MAX_WAIT: Duration = Duration::from_secs(60);
+
+/// Unread count (未读通知数)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
+pub struct UnreadCount {
+    /// 未读总数
+    #[serde(default)]
+    pub unread_count: i64,
+    /// 赞和收藏
+    #[serde(default)]
+    pub likes: i64,
+    /// 新增关注
+    #[serde(default)]
+    pub connections: i64,
+    /// 评论和@
+    #[serde(default)]
+    pub mentions: i64,
+}
+
+impl UnreadCount {
+    /// 相比 baseline 有新增的类别
+    pub fn increased_since(&self, baseline: &UnreadCount) -> bool {
+        self.likes > baseline.likes
+            || self.connections > baseline.connections
+            || self.mentions > baseline.mentions
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
+struct UnreadCountResponse {
+    data: Option<UnreadCount>,
+}
+
+/// Long-poll query parameters (长轮询参数)
+#[derive(Debug, Clone, Deserialize, utoipa::IntoParams)]
+pub struct PollParams {
+    /// 最长等待时间，如 "30s"、"1500ms"、"1m" 或纯数字秒，最大 60s
+    #[serde(default)]
+    pub wait: Option<String>,
+}
+
+/// Long-poll result (长轮询结果)
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct NotificationPoll {
+    /// 等待期间是否出现新通知
+    pub changed: bool,
+    /// 实际等待时间 (毫秒)
+    pub waited_ms: u64,
+    /// 开始等待时的未读数
+    pub baseline: UnreadCount,
+    /// 最新未读数
+    pub unread: UnreadCount,
+    /// 新增的评论和@ (仅在 mentions 增加时返回第一页)
+    pub mentions: Option<Vec<serde_json::Value>>,
+    /// 新增的赞和收藏 (仅在 likes 增加时返回第一页)
+    pub likes: Option<Vec<serde_json::Value>>,
+    /// 新增的关注 (仅在 connections 增加时返回第一页)
+    pub connections: Option<Vec<serde_json::Value>>,
+}
+
+/// 解析等待时长 ("30s" / "1500ms" / "1m" / "30")
+pub fn parse_wait(wait: Option<&str>) -> anyhow::Result<Duration> {
+    let Some(wait) = wait.map(str::trim).filter(|w| !w.is_empty()) else {
+        return Ok(Duration::from_secs(30));
+    };
+    let (number, unit) = match wait.find(|c: char| !c.is_ascii_digit()) {
+        Some(idx) => wait.split_at(idx),
+        None => (wait, "s"),
+    };
+    let value: u64 = number.parse().map_err(|_| anyhow!("Invalid wait: {}", wait))?;
+    let duration = match unit {
+        "ms" => Duration::from_millis(value),
+        "s" => Duration::from_secs(value),
+        "m" => Duration::from_secs(value.saturating_mul(60)),
+        _ => return Err(anyhow!("Invalid wait unit: {}", wait)),
+    };
+    Ok(duration.min(MAX_WAIT))
+}
+
+/// 查询未读通知数
+pub async fn get_unread_count(api: &XhsApiClient) -> Result<UnreadCount> {
+    let text = api.get_with_query("/api/sns/web/unread_count").await?;
+    let result = parse::from_str::<UnreadCountResponse>(&text)?;
+    Ok(result.data.unwrap_or_default())
+}
+
+/// 共享的上游轮询器
+///
+/// 所有长轮询请求共用一份未读数缓存，等待期间上游请求间隔不低于 `interval`，
+/// 无论有多少下游连接在等待；每次等待开始时的基准未读数另行请求上游获取
+pub struct NotificationPoller {
+    interval: Duration,
+    latest: Mutex<Option<(Instant, UnreadCount)>>,
+}
+
+impl NotificationPoller {
+    pub fn new(interval: Duration) -> Self {
+        Self { interval, latest: Mutex::new(None) }
+    }
+
+    /// 获取未读数，缓存未过期时不请求上游
+    pub async fn latest(&self, api: &XhsApiClient) -> Result<UnreadCount> {
+        // 持锁期间请求上游，保证同一时刻只有一个上游请求
+        let mut latest = self.latest.lock().await;
+        if let Some((at, count)) = latest.as_ref() {
+            if at.elapsed() < self.interval {
+                return Ok(count.clone());
+            }
+        }
+        let started = Instant::now();
+        let count = get_unread_count(api).await?;
+        *latest = Some((started, count.clone()));
+        Ok(count)
+    }
+
+    /// 请求上游获取未读数并更新缓存；排队期间已有其他请求在本次调用之后发出时直接复用其结果
+    pub async fn refresh(&self, api: &XhsApiClient) -> Result<UnreadCount> {
+        let requested = Instant::now();
+        let mut latest = self.latest.lock().await;
+        if let Some((at, count)) = latest.as_ref() {
+            if *at >= requested {
+                return Ok(count.clone());
+            }
+        }
+        let started = Instant::now();
+        let count = get_unread_count(api).await?;
+        *latest = Some((started, count.clone()));
+        Ok(count)
+    }
+
+    /// 下一次上游轮询前的剩余时间
+    async fn until_next_poll(&self) -> Duration {
+        match self.latest.lock().await.as_ref() {
+            Some((at, _)) => self.interval.saturating_sub(at.elapsed()),
+            None => Duration::ZERO,
+        }
+    }
+
+    /// 等待未读数增加，或直到超时
+    ///
+    /// 基准未读数在等待开始时请求上游获取，不取缓存，避免以过期的缓存值为基准漏报或误报变化。
+    /// 返回 (baseline, latest, changed)
+    pub async fn wait_for_change(
+        &self,
+        api: &XhsApiClient,
+        wait: Duration,
+    ) -> Result<(UnreadCount, UnreadCount, bool)> {
+        let deadline = Instant::now() + wait;
+        let baseline = self.refresh(api).await?;
+
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Ok((baseline.clone(), baseline, false));
+            }
+            let next_poll = self.until_next_poll().await;
+            if next_poll >= remaining {
+                tokio::time::sleep(remaining).await;
+                return Ok((baseline.clone(), baseline, false));
+            }
+            tokio::time::sleep(next_poll.max(Duration::from_millis(50))).await;
+
+            let current = self.latest(api).await?;
+            if current.increased_since(&baseline) {
+                return Ok((baseline, current, true));
+            }
+        }
+    }
+}
@@ -0,0 +1,300 @@
+//! 通知 Webhook
+//!
+//! 后台定期拉取评论和@、赞和收藏、新增关注的第一页，与上次结果对比，
+//! 把新出现的通知 POST 到 `XHS_WEBHOOK_URLS`。
+//!
+//! - 每个账号第一次检查只记录基线，不推送历史通知
+//! - 配置 `XHS_WEBHOOK_SECRET` 后，请求头 `X-Xhs-Signature: sha256=<hex>` 为
+//!   `HMAC-SHA256(secret, "{X-Xhs-Timestamp}.{body}")`，接收方可据此校验来源与防重放
+//! - 投递失败重试 3 次，仍失败则丢弃（不阻塞后续检查）
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+use std::collections::{HashSet, VecDeque};
+use std::time::Duration;
+
+use crate::api::XhsApiClient;
+use crate::error::Result;
+
+#[cfg(feature = "server")]
+use crate::error::XhsError;
+#[cfg(feature = "server")]
+use crate::server::AppState;
+#[cfg(feature = "server")]
+use std::sync::Arc;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// 每类通知保留的已见 ID 数量
+const SEEN_CAPACITY: usize = 500;
+/// 单个 webhook 的投递尝试次数
+const DELIVERY_ATTEMPTS: u32 = 3;
+/// webhook 请求超时
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 通知类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    /// 评论和@
+    Mention,
+    /// 赞和收藏
+    Like,
+    /// 新增关注
+    Connection,
+}
+
+impl NotificationKind {
+    const ALL: [NotificationKind; 3] = [NotificationKind::Mention, NotificationKind::Like, NotificationKind::Connection];
+
+    /// 拉取该类别的第一页通知
+    async fn fetch(self, api: &XhsApiClient) -> Result<Vec<serde_json::Value>> {
+        let list = match self {
+            NotificationKind::Mention => super::get_mentions(api).await?.data.map(|d| d.message_list),
+            NotificationKind::Like => super::get_likes(api).await?.data.map(|d| d.message_list),
+            NotificationKind::Connection => super::get_connections(api).await?.data.map(|d| d.message_list),
+        };
+        Ok(list.unwrap_or_default())
+    }
+}
+
+/// 单条新通知
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookEvent {
+    pub kind: NotificationKind,
+    /// 通知 ID（上游无 id 时为消息内容）
+    pub id: String,
+    /// 上游原始通知
+    pub message: serde_json::Value,
+}
+
+/// webhook 请求体
+#[derive(Debug, Serialize)]
+pub struct WebhookPayload<'a> {
+    /// 收到通知的账号
+    pub user_id: &'a str,
+    pub detected_at: DateTime<Utc>,
+    pub events: &'a [WebhookEvent],
+}
+
+/// 通知 ID，缺少 id 字段时退化为整条消息
+fn message_id(message: &serde_json::Value) -> String {
+    match message.get("id") {
+        Some(serde_json::Value::String(id)) => id.clone(),
+        Some(id) if !id.is_null() => id.to_string(),
+        _ => message.to_string(),
+    }
+}
+
+/// 有界的已见 ID 集合
+#[derive(Default)]
+struct SeenIds {
+    ids: HashSet<String>,
+    order: VecDeque<String>,
+}
+
+impl SeenIds {
+    /// 记录 ID，返回是否为新出现
+    fn insert(&mut self, id: &str) -> bool {
+        if self.ids.contains(id) {
+            return false;
+        }
+        self.ids.insert(id.to_string());
+        self.order.push_back(id.to_string());
+        if self.order.len() > SEEN_CAPACITY {
+            if let Some(oldest) = self.order.pop_front() {
+                self.ids.remove(&oldest);
+            }
+        }
+        true
+    }
+}
+
+/// webhook 投递器
+pub struct WebhookNotifier {
+    urls: Vec<String>,
+    secret: Option<Vec<u8>>,
+    client: reqwest::Client,
+}
+
+impl WebhookNotifier {
+    pub fn new(urls: Vec<String>, secret: Option<String>) -> anyhow::Result<Self> {
+        Ok(Self {
+            urls,
+            secret: secret.map(String::into_bytes),
+            client: reqwest::Client::builder().timeout(DELIVERY_TIMEOUT).build()?,
+        })
+    }
+
+    /// 从 XHS_WEBHOOK_URLS / XHS_WEBHOOK_SECRET 构造，未配置地址时返回 None
+    pub fn from_env() -> anyhow::Result<Option<Self>> {
+        let urls = crate::config::webhook_urls();
+        if urls.is_empty() {
+            return Ok(None);
+        }
+        Self::new(urls, crate::config::webhook_secret()).map(Some)
+    }
+
+    /// 是否与给定的地址和密钥一致
+    #[cfg(feature = "server")]
+    fn configured_with(&self, urls: &[String], secret: Option<&str>) -> bool {
+        self.urls == urls && self.secret.as_deref() == secret.map(str::as_bytes)
+    }
+
+    /// `sha256=<hex>` 签名
+    fn sign(&self, timestamp: i64, body: &str) -> Option<String> {
+        let secret = self.secret.as_ref()?;
+        let mut mac = HmacSha256::new_from_slice(secret).expect("HMAC accepts any key length");
+        mac.update(timestamp.to_string().as_bytes());
+        mac.update(b".");
+        mac.update(body.as_bytes());
+        let digest = mac.finalize().into_bytes();
+        Some(format!("sha256={}", digest.iter().map(|b| format!("{:02x}", b)).collect::<String>()))
+    }
+
+    /// 投递到所有 webhook（`event` 写入 X-Xhs-Event 请求头），返回成功的数量
+    pub async fn deliver<T: Serialize>(&self, event: &str, payload: &T) -> usize {
+        let body = match serde_json::to_string(payload) {
+            Ok(body) => body,
+            Err(e) => {
+                tracing::error!("[Webhook] Failed to serialize payload: {}", e);
+                return 0;
+            }
+        };
+        let mut delivered = 0;
+        for url in &self.urls {
+            if self.deliver_one(url, event, &body).await {
+                delivered += 1;
+            }
+        }
+        delivered
+    }
+
+    async fn deliver_one(&self, url: &str, event: &str, body: &str) -> bool {
+        for attempt in 1..=DELIVERY_ATTEMPTS {
+            let timestamp = Utc::now().timestamp();
+            let mut request = self.client
+                .post(url)
+                .header("Content-Type", "application/json")
+                .header("X-Xhs-Event", event)
+                .header("X-Xhs-Timestamp", timestamp.to_string())
+                .body(body.to_string());
+            if let Some(signature) = self.sign(timestamp, body) {
+                request = request.header("X-Xhs-Signature", signature);
+            }
+            match request.send().await {
+                Ok(resp) if resp.status().is_success() => return true,
+                Ok(resp) => tracing::warn!("[Webhook] {} returned {} (attempt {}/{})", url, resp.status(), attempt, DELIVERY_ATTEMPTS),
+                Err(e) => tracing::warn!("[Webhook] {} failed: {} (attempt {}/{})", url, e, attempt, DELIVERY_ATTEMPTS),
+            }
+            if attempt < DELIVERY_ATTEMPTS {
+                tokio::time::sleep(Duration::from_secs(1 << attempt)).await;
+            }
+        }
+        tracing::error!("[Webhook] Giving up on {} after {} attempts", url, DELIVERY_ATTEMPTS);
+        false
+    }
+}
+
+/// 通知差异检测
+pub struct NotificationWatcher {
+    notifier: WebhookNotifier,
+    /// 当前基线所属账号，账号切换后重新建立基线
+    user_id: Option<String>,
+    seen: [SeenIds; 3],
+}
+
+impl NotificationWatcher {
+    pub fn new(notifier: WebhookNotifier) -> Self {
+        Self { notifier, user_id: None, seen: Default::default() }
+    }
+
+    /// 检查一次，返回新通知数量
+    pub async fn tick(&mut self, api: &XhsApiClient, user_id: &str) -> Result<usize> {
+        let baseline = self.user_id.as_deref() != Some(user_id);
+        if baseline {
+            self.seen = Default::default();
+        }
+
+        // 三类都拉取成功后再更新已见集合，避免部分失败时漏推
+        let mut lists = Vec::with_capacity(NotificationKind::ALL.len());
+        for kind in NotificationKind::ALL {
+            lists.push((kind, kind.fetch(api).await?));
+        }
+
+        let mut events = Vec::new();
+        for (seen, (kind, messages)) in self.seen.iter_mut().zip(lists) {
+            for message in messages {
+                let id = message_id(&message);
+                if seen.insert(&id) && !baseline {
+                    events.push(WebhookEvent { kind, id, message });
+                }
+            }
+        }
+
+        if baseline {
+            tracing::info!("[Webhook] Baseline recorded for user {}", user_id);
+            self.user_id = Some(user_id.to_string());
+            return Ok(0);
+        }
+        if !events.is_empty() {
+            // 上游按时间倒序返回，投递时改为正序
+            events.reverse();
+            let payload = WebhookPayload { user_id, detected_at: Utc::now(), events: &events };
+            let delivered = self.notifier.deliver("notification", &payload).await;
+            tracing::info!("[Webhook] {} new notification(s) delivered to {} webhook(s)", events.len(), delivered);
+        }
+        Ok(events.len())
+    }
+}
+
+#[cfg(feature = "server")]
+/// 启动通知监听
+///
+/// 每轮检查前按当前的 XHS_WEBHOOK_URLS / XHS_WEBHOOK_SECRET 更新投递器（配置热加载），
+/// 已见集合保留；未配置地址时跳过检查
+pub fn spawn_watcher(state: Arc<AppState>) {
+    let interval = crate::config::webhook_poll_interval();
+
+    tokio::spawn(async move {
+        let mut watcher: Option<NotificationWatcher> = None;
+        loop {
+            let urls = crate::config::webhook_urls();
+            let secret = crate::config::webhook_secret();
+            if urls.is_empty() {
+                if watcher.take().is_some() {
+                    tracing::info!("[Webhook] XHS_WEBHOOK_URLS cleared, stopped watching notifications");
+                }
+            } else if watcher.as_ref().is_none_or(|w| !w.notifier.configured_with(&urls, secret.as_deref())) {
+                match WebhookNotifier::new(urls, secret) {
+                    Ok(notifier) => {
+                        tracing::info!("[Webhook] Watching notifications every {:?} for {} webhook(s)", interval, notifier.urls.len());
+                        match &mut watcher {
+                            Some(watcher) => watcher.notifier = notifier,
+                            None => watcher = Some(NotificationWatcher::new(notifier)),
+                        }
+                    }
+                    Err(e) => tracing::error!("[Webhook] Failed to create notifier: {}", e),
+                }
+            }
+
+            if let Some(watcher) = &mut watcher {
+                if !state.api.maintenance().is_paused() {
+                    if let Ok(Some(creds)) = state.auth.try_get_credentials().await {
+                        match watcher.tick(&state.api, &creds.user_id).await {
+                            Ok(_) => {}
+                            // 风控 / 限流期间等待下一轮即可
+                            Err(e @ (XhsError::RiskCircuitOpen { .. } | XhsError::RiskControl461 { .. } | XhsError::Throttled(_))) => {
+                                tracing::info!("[Webhook] Skipping check: {}", e);
+                            }
+                            Err(e) => tracing::warn!("[Webhook] Notification check failed: {}", e),
+                        }
+                    }
+                }
+            }
+            tokio::time::sleep(interval).await;
+        }
+    });
+}
@@ -0,0 +1,126 @@
+//! 拟人节奏模式 (Stealth Pacing)
+//!
+//! 长时间抓取时，固定间隔、只访问目标接口的流量特征很明显。启用 `XHS_STEALTH` 后，
+//! `XhsApiClient` 在令牌桶限流之外额外:
+//!
+//! - 每次请求前随机停顿 `XHS_STEALTH_DELAY_MS`（默认 800-3500ms）
+//! - 以 `XHS_STEALTH_MAX_RPM`（默认 20）限制持续请求速率（无突发）
+//! - 以 `XHS_STEALTH_DECOY_RATE`（默认 8%）的概率先刷新一次热搜或首页推荐，模拟真人浏览
+//!
+//! 默认关闭；关闭时不引入任何延迟。
+
+use rand::Rng;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use utoipa::ToSchema;
+
+use super::rate_limit::RateLimiter;
+
+/// 浏览动作（插入的"闲逛"请求）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decoy {
+    /// 刷新热搜
+    Trending,
+    /// 刷新首页推荐
+    Homefeed,
+}
+
+impl Decoy {
+    pub fn endpoint_key(&self) -> &'static str {
+        match self {
+            Decoy::Trending => "search_trending",
+            Decoy::Homefeed => "home_feed_recommend",
+        }
+    }
+}
+
+/// 拟人模式配置与计数
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StealthStatus {
+    pub enabled: bool,
+    /// 请求前随机停顿下限（毫秒）
+    pub delay_min_ms: u64,
+    /// 请求前随机停顿上限（毫秒）
+    pub delay_max_ms: u64,
+    /// 持续请求速率上限（每分钟）
+    pub max_rpm: u32,
+    /// 插入浏览动作的概率
+    pub decoy_rate: f64,
+    /// 已插入的浏览动作次数
+    pub decoys_sent: u64,
+}
+
+/// 拟人节奏控制器
+pub struct StealthPacer {
+    enabled: bool,
+    delay_ms: (u64, u64),
+    max_rpm: u32,
+    decoy_rate: f64,
+    limiter: RateLimiter,
+    decoys_sent: AtomicU64,
+}
+
+impl Default for StealthPacer {
+    fn default() -> Self {
+        Self::new(
+            crate::config::stealth_enabled(),
+            crate::config::stealth_delay_range(),
+            crate::config::stealth_max_rpm(),
+            crate::config::stealth_decoy_rate(),
+        )
+    }
+}
+
+impl StealthPacer {
+    pub fn new(enabled: bool, delay_ms: (u64, u64), max_rpm: u32, decoy_rate: f64) -> Self {
+        Self {
+            enabled,
+            delay_ms,
+            max_rpm,
+            decoy_rate,
+            limiter: if enabled { RateLimiter::new(max_rpm, 1, Vec::new()) } else { RateLimiter::unlimited() },
+            decoys_sent: AtomicU64::new(0),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// 等待持续速率上限与随机停顿
+    pub async fn pace(&self, endpoint: &str) {
+        if !self.enabled {
+            return;
+        }
+        self.limiter.acquire("*").await;
+        let (min, max) = self.delay_ms;
+        let delay = Duration::from_millis(rand::thread_rng().gen_range(min..=max));
+        tracing::debug!("[Stealth] {} pausing {:?}", endpoint, delay);
+        tokio::time::sleep(delay).await;
+    }
+
+    /// 按概率决定本次请求前是否插入浏览动作（目标本身是热搜 / 首页时不插入）
+    pub fn pick_decoy(&self, endpoint: &str) -> Option<Decoy> {
+        if !self.enabled || endpoint.contains("trending") || endpoint.contains("homefeed") || endpoint.starts_with("home_feed") {
+            return None;
+        }
+        let mut rng = rand::thread_rng();
+        if !rng.gen_bool(self.decoy_rate) {
+            return None;
+        }
+        self.decoys_sent.fetch_add(1, Ordering::Relaxed);
+        Some(if rng.gen_bool(0.5) { Decoy::Trending } else { Decoy::Homefeed })
+    }
+
+    pub fn status(&self) -> StealthStatus {
+        StealthStatus {
+            enabled: self.enabled,
+            delay_min_ms: self.delay_ms.0,
+            delay_max_ms: self.delay_ms.1,
+            max_rpm: self.max_rpm,
+            decoy_rate: self.decoy_rate,
+            decoys_sent: self.decoys_sent.load(Ordering::Relaxed),
+        }
+    }
+}
@@ -0,0 +1,184 @@
+//! 自动翻页流 (库接口)
+//!
+//! 把搜索 / 首页频道 / 评论的游标翻页封装为 `impl Stream<Item = Result<T>>`，逐条产出结果，
+//! 消费端停止 poll 即停止翻页，无需手写循环:
+//!
+//! ```no_run
+//! # use futures_util::{pin_mut, StreamExt};
+//! # async fn demo(api: &xhs_rs::api::XhsApiClient) -> xhs_rs::error::Result<()> {
+//! let stream = api.search_notes_stream(serde_json::from_value(serde_json::json!({ "keyword": "咖啡" }))?);
+//! pin_mut!(stream);
+//! while let Some(item) = stream.next().await {
+//!     println!("{}", item?.id);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! 每页请求照常经过 `XhsApiClient` 的限流、拟人节奏与风控熔断；请求失败时产出一个 `Err` 后结束。
+
+use futures_util::stream::{self, Stream};
+use std::collections::{HashSet, VecDeque};
+use std::future::Future;
+
+use crate::api::feed::category::fetch_category_feed;
+use crate::api::feed::cursor::FeedCursor;
+use crate::api::note::comments::{fetch_sub_comment_page, CommentPage, NoteComment};
+use crate::api::note::page::{fetch_note_page, NotePageParams};
+use crate::api::search::{generate_search_id, search_notes};
+use crate::api::XhsApiClient;
+use crate::error::Result;
+use crate::models::feed::HomefeedItem;
+use crate::models::search::SearchNotesRequest;
+
+/// 连续多少页没有新条目时结束（防止上游一直返回空页 / 重复内容时无限请求）
+const MAX_EMPTY_PAGES: usize = 3;
+
+impl XhsApiClient {
+    /// 搜索笔记并自动翻页，直到 `has_more=false`，跨页重复的笔记只产出一次
+    ///
+    /// 从 `req.page` 开始；`search_id` 为空时生成一次并在各页间复用
+    pub fn search_notes_stream(&self, mut req: SearchNotesRequest) -> impl Stream<Item = Result<HomefeedItem>> + '_ {
+        if req.search_id.as_deref().is_none_or(str::is_empty) {
+            req.search_id = Some(generate_search_id());
+        }
+        paginate((req, HashSet::new()), move |(mut req, mut seen)| async move {
+            let (items, has_more) = search_notes(self, req.clone())
+                .await?
+                .data
+                .map(|d| (d.items, d.has_more))
+                .unwrap_or_default();
+            let items: Vec<HomefeedItem> = items.into_iter().filter(|item| seen.insert(item.id.clone())).collect();
+            req.page += 1;
+            Ok((items, has_more.then_some((req, seen))))
+        })
+    }
+
+    /// 首页频道无限流（recommend/fashion/food/...），跨页去重
+    ///
+    /// 频道没有终点，由消费端决定何时停止（如 `.take(100)`）
+    pub fn feed_stream(&self, category: &str) -> impl Stream<Item = Result<HomefeedItem>> + '_ {
+        paginate(FeedCursor::new(category.to_string()), move |mut cursor| async move {
+            let resp = fetch_category_feed(self, &cursor.category, cursor.next_request()).await?;
+            let (cursor_score, items) = resp.data.map(|d| (d.cursor_score, d.items)).unwrap_or_default();
+            let items = cursor.advance(cursor_score, items);
+            Ok((items, Some(cursor)))
+        })
+    }
+
+    /// 笔记的全部评论：一级评论与其子评论按顺序产出，被折叠的子评论逐页展开
+    ///
+    /// 与 `/api/note/comments/all` 不同，子评论线程顺序抓取，消费端停止 poll 时不会多发请求
+    pub fn comments_stream(&self, note_id: &str, xsec_token: &str) -> impl Stream<Item = Result<NoteComment>> + '_ {
+        let cursor = CommentCursor {
+            note_id: note_id.to_string(),
+            xsec_token: xsec_token.to_string(),
+            page: Some(String::new()),
+            threads: VecDeque::new(),
+        };
+        paginate(cursor, move |mut cursor| async move {
+            let comments = match cursor.threads.pop_front() {
+                Some((root_id, sub_cursor)) => {
+                    let value = fetch_sub_comment_page(self, &cursor.note_id, &root_id, &sub_cursor, &cursor.xsec_token).await?;
+                    let page = CommentPage::parse("comment/sub/page", value)?;
+                    let comments = page.comments
+                        .iter()
+                        .filter_map(|c| NoteComment::from_value(&cursor.note_id, Some(&root_id), c))
+                        .collect();
+                    if page.has_more && !page.cursor.is_empty() && page.cursor != sub_cursor {
+                        cursor.threads.push_front((root_id, page.cursor));
+                    }
+                    comments
+                }
+                None => {
+                    let Some(page_cursor) = cursor.page.take() else {
+                        return Ok((Vec::new(), None));
+                    };
+                    let params = NotePageParams {
+                        note_id: cursor.note_id.clone(),
+                        cursor: page_cursor.clone(),
+                        top_comment_id: String::new(),
+                        image_formats: "jpg,webp,avif".to_string(),
+                        xsec_token: cursor.xsec_token.clone(),
+                    };
+                    let page = CommentPage::parse("comment/page", fetch_note_page(self, params).await?)?;
+                    let comments = cursor.expand_page(&page);
+                    if page.has_more && !page.cursor.is_empty() && page.cursor != page_cursor {
+                        cursor.page = Some(page.cursor);
+                    }
+                    comments
+                }
+            };
+            let done = cursor.page.is_none() && cursor.threads.is_empty();
+            Ok((comments, (!done).then_some(cursor)))
+        })
+    }
+}
+
+/// 评论翻页状态：先展开当前页被折叠的子评论线程，再翻下一页一级评论
+struct CommentCursor {
+    note_id: String,
+    xsec_token: String,
+    /// 下一页一级评论的游标，None 表示已是最后一页
+    page: Option<String>,
+    /// 待展开的子评论线程 (root_comment_id, sub_comment_cursor)
+    threads: VecDeque<(String, String)>,
+}
+
+impl CommentCursor {
+    /// 一级评论连同自带的前几条回复，并记录回复未展示完的线程
+    fn expand_page(&mut self, page: &CommentPage) -> Vec<NoteComment> {
+        let mut comments = Vec::new();
+        for value in &page.comments {
+            let Some(comment) = NoteComment::from_value(&self.note_id, None, value) else {
+                continue;
+            };
+            let root_id = comment.comment_id.clone();
+            comments.push(comment);
+            comments.extend(
+                value.get("sub_comments")
+                    .and_then(|v| v.as_array())
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|c| NoteComment::from_value(&self.note_id, Some(&root_id), c)),
+            );
+            let has_more = value.get("sub_comment_has_more").and_then(|v| v.as_bool()).unwrap_or(false);
+            let sub_cursor = value.get("sub_comment_cursor").and_then(|v| v.as_str()).unwrap_or_default();
+            if has_more && !sub_cursor.is_empty() {
+                self.threads.push_back((root_id, sub_cursor.to_string()));
+            }
+        }
+        comments
+    }
+}
+
+/// 通用翻页流：`fetch` 返回一页条目与下一页游标（None 表示结束），条目逐个产出
+fn paginate<'a, C, T, F, Fut>(cursor: C, fetch: F) -> impl Stream<Item = Result<T>> + 'a
+where
+    C: 'a,
+    T: 'a,
+    F: FnMut(C) -> Fut + 'a,
+    Fut: Future<Output = Result<(Vec<T>, Option<C>)>> + 'a,
+{
+    let state = (Some(cursor), VecDeque::new(), fetch, 0usize);
+    stream::unfold(state, |(mut cursor, mut pending, mut fetch, mut empty_pages)| async move {
+        loop {
+            if let Some(item) = pending.pop_front() {
+                return Some((Ok(item), (cursor, pending, fetch, empty_pages)));
+            }
+            let current = cursor.take()?;
+            match fetch(current).await {
+                Ok((items, next)) => {
+                    empty_pages = if items.is_empty() { empty_pages + 1 } else { 0 };
+                    if empty_pages >= MAX_EMPTY_PAGES {
+                        tracing::warn!("[Paginate] {} consecutive empty pages, stopping", empty_pages);
+                        return None;
+                    }
+                    pending.extend(items);
+                    cursor = next;
+                }
+                Err(e) => return Some((Err(e), (None, pending, fetch, empty_pages))),
+            }
+        }
+    })
+}
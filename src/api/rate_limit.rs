@@ -0,0 +1,190 @@
+//! 出站限流 (Token Bucket Rate Limiter)
+//!
+//! 批量操作时请求过于密集是触发 461 风控的主要原因。`XhsApiClient` 在每次发出请求前
+//! 先从令牌桶取令牌，桶空时等待而不是报错，从而把突发流量摊平。
+//!
+//! - 全局桶: 所有接口共享，`XHS_RATE_LIMIT_RPM` / `XHS_RATE_LIMIT_BURST`
+//! - 接口桶: `XHS_RATE_LIMIT_ENDPOINTS`，按 endpoint key 或 URI 前缀匹配，同时受全局桶约束
+//!
+//! 两者均可通过配置热加载在运行时调整（[`RateLimiter::reconfigure`]）。
+//!
+//! 取令牌采用预约方式：令牌不足时先记账（余额可为负）再在锁外等待，
+//! 并发请求按到达顺序依次放行。
+
+use serde::Serialize;
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+use utoipa::ToSchema;
+
+/// 令牌桶
+struct TokenBucket {
+    /// 每秒补充的令牌数
+    rate: f64,
+    capacity: f64,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn new(rpm: u32, burst: u32) -> Self {
+        Self {
+            rate: rpm as f64 / 60.0,
+            capacity: burst as f64,
+            tokens: burst as f64,
+            updated: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.updated = now;
+    }
+
+    /// 预约一个令牌，返回需要等待的时间
+    fn reserve(&mut self, now: Instant) -> Duration {
+        self.refill(now);
+        self.tokens -= 1.0;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.rate)
+        }
+    }
+}
+
+/// 单个桶的配置与当前状态
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BucketStatus {
+    /// 匹配范围（"*" 表示全局）
+    pub scope: String,
+    pub requests_per_minute: u32,
+    pub burst: u32,
+    /// 当前可用令牌（负数表示已有请求在排队）
+    pub available: f64,
+}
+
+struct Bucket {
+    scope: String,
+    rpm: u32,
+    burst: u32,
+    bucket: Mutex<TokenBucket>,
+}
+
+impl Bucket {
+    fn new(scope: String, rpm: u32, burst: u32) -> Self {
+        Self { scope, rpm, burst, bucket: Mutex::new(TokenBucket::new(rpm, burst)) }
+    }
+
+    fn matches(&self, endpoint: &str) -> bool {
+        endpoint == self.scope || endpoint.starts_with(&self.scope)
+    }
+
+    fn status(&self) -> BucketStatus {
+        let mut bucket = self.bucket.lock().unwrap();
+        bucket.refill(Instant::now());
+        BucketStatus {
+            scope: self.scope.clone(),
+            requests_per_minute: self.rpm,
+            burst: self.burst,
+            available: (bucket.tokens * 100.0).round() / 100.0,
+        }
+    }
+}
+
+/// 全局桶与接口桶
+struct Buckets {
+    global: Option<Bucket>,
+    endpoints: Vec<Bucket>,
+}
+
+impl Buckets {
+    fn new(rpm: u32, burst: u32, endpoints: Vec<(String, u32, u32)>) -> Self {
+        Self {
+            global: (rpm > 0).then(|| Bucket::new("*".to_string(), rpm, burst.max(1))),
+            endpoints: endpoints
+                .into_iter()
+                .map(|(scope, rpm, burst)| Bucket::new(scope, rpm, burst))
+                .collect(),
+        }
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &Bucket> {
+        self.global.iter().chain(self.endpoints.iter())
+    }
+}
+
+/// 全局 + 按接口的出站限流器
+pub struct RateLimiter {
+    buckets: RwLock<Buckets>,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        let (rpm, burst) = crate::config::rate_limit_global();
+        Self::new(rpm, burst, crate::config::rate_limit_endpoints())
+    }
+}
+
+impl RateLimiter {
+    /// `rpm` 为 0 时不启用全局桶；`endpoints` 为 (接口, 每分钟请求数, 突发容量)
+    pub fn new(rpm: u32, burst: u32, endpoints: Vec<(String, u32, u32)>) -> Self {
+        Self { buckets: RwLock::new(Buckets::new(rpm, burst, endpoints)) }
+    }
+
+    /// 不限流
+    pub fn unlimited() -> Self {
+        Self::new(0, 0, Vec::new())
+    }
+
+    /// 运行时替换限流配置，配置未变化时返回 false
+    ///
+    /// 同一范围的桶保留剩余令牌（上限为新的突发容量），避免重新加载时放行一次突发
+    pub fn reconfigure(&self, rpm: u32, burst: u32, endpoints: Vec<(String, u32, u32)>) -> bool {
+        let next = Buckets::new(rpm, burst, endpoints);
+        let mut buckets = self.buckets.write().unwrap();
+        let config = |b: &Bucket| (b.scope.clone(), b.rpm, b.burst);
+        if buckets.iter().map(config).eq(next.iter().map(config)) {
+            return false;
+        }
+        for bucket in next.iter() {
+            if let Some(previous) = buckets.iter().find(|b| b.scope == bucket.scope) {
+                let previous = previous.bucket.lock().unwrap();
+                let mut current = bucket.bucket.lock().unwrap();
+                current.tokens = previous.tokens.min(current.capacity);
+                current.updated = previous.updated;
+            }
+        }
+        *buckets = next;
+        true
+    }
+
+    /// 按 XHS_RATE_LIMIT_* 重新配置（配置热加载）
+    pub fn reload_from_env(&self) -> bool {
+        let (rpm, burst) = crate::config::rate_limit_global();
+        self.reconfigure(rpm, burst, crate::config::rate_limit_endpoints())
+    }
+
+    /// 等待直到 `endpoint` 可以发出一次请求
+    pub async fn acquire(&self, endpoint: &str) {
+        let endpoint = endpoint.split('?').next().unwrap_or(endpoint);
+        let now = Instant::now();
+        let wait = {
+            let buckets = self.buckets.read().unwrap();
+            buckets.global.iter()
+                .chain(buckets.endpoints.iter().filter(|b| b.matches(endpoint)))
+                .map(|b| b.bucket.lock().unwrap().reserve(now))
+                .max()
+                .unwrap_or_default()
+        };
+        if !wait.is_zero() {
+            tracing::debug!("[RateLimit] {} waiting {:?}", endpoint, wait);
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// 各个桶的状态（全局桶在前）
+    pub fn status(&self) -> Vec<BucketStatus> {
+        self.buckets.read().unwrap().iter().map(Bucket::status).collect()
+    }
+}
@@ -0,0 +1,111 @@
+//! 原始响应透传 (Raw Passthrough)
+//!
+//! 类型化模型只覆盖部分字段；需要模型尚未覆盖的字段时，可直接取得小红书返回的原始 JSON。
+//! 作用域内 `XhsApiClient` 收到的最后一个上游响应体被记录下来（拟人模式的浏览动作在真实请求之前，
+//! 重试时以最后一次为准），作用域内不读取响应缓存、笔记详情缓存与 POST 去重结果。
+//!
+//! - 库调用：`raw::scope(search_notes(&api, req)).await` 返回 `(结果, 原始 JSON)`
+//! - HTTP：在支持的路由（[`RAW_ROUTES`]）上加 `?raw=true` 返回 `ApiResponse`（`data` 为原始 JSON），
+//!   `?raw=both` 在类型化响应中附加 `raw` 字段
+
+use std::cell::RefCell;
+use std::future::Future;
+
+tokio::task_local! {
+    /// 存在即处于透传作用域；记录最后一个上游响应体
+    static RAW: RefCell<Option<String>>;
+}
+
+/// 支持 `?raw=` 的路由（只发出一个上游请求的笔记 / 首页推荐 / 搜索接口）
+pub const RAW_ROUTES: &[&str] = &[
+    "/api/search/trending",
+    "/api/search/recommend",
+    "/api/search/notes",
+    "/api/search/onebox",
+    "/api/search/filter",
+    "/api/search/usersearch",
+    "/api/feed/homefeed/recommend",
+    "/api/feed/homefeed/:category",
+    "/api/note/page",
+    "/api/note/detail",
+];
+
+/// 当前任务是否在透传作用域内
+pub fn is_active() -> bool {
+    RAW.try_with(|_| ()).is_ok()
+}
+
+/// 透传作用域内记录上游响应体（覆盖之前的记录）
+pub(crate) fn record(text: &str) {
+    let _ = RAW.try_with(|slot| *slot.borrow_mut() = Some(text.to_string()));
+}
+
+/// 在透传作用域内执行 `future`，返回其结果与最后一个上游响应（不是 JSON 时为字符串）
+pub async fn scope<F: Future>(future: F) -> (F::Output, Option<serde_json::Value>) {
+    RAW.scope(RefCell::new(None), async {
+        let output = future.await;
+        let raw = RAW.with(|slot| slot.borrow_mut().take())
+            .map(|text| serde_json::from_str(&text).unwrap_or(serde_json::Value::String(text)));
+        (output, raw)
+    }).await
+}
+
+/// `?raw=` 的取值
+#[cfg(feature = "server")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RawMode {
+    /// 只返回原始 JSON
+    Only,
+    /// 类型化响应 + `raw` 字段
+    Both,
+}
+
+/// 路由中间件 (`route_layer`)：`?raw=true` / `?raw=both` 时透传原始响应；不支持的路由返回 400
+///
+/// handler 失败或没有发出上游请求时原样返回 handler 的响应
+#[cfg(feature = "server")]
+pub async fn middleware(request: axum::extract::Request, next: axum::middleware::Next) -> axum::response::Response {
+    use axum::{body::Body, extract::MatchedPath, http::StatusCode, response::{IntoResponse, Response}};
+    use crate::response::ApiResponse;
+
+    let mode = request.uri().query().and_then(|query| {
+        url::form_urlencoded::parse(query.as_bytes())
+            .find(|(key, _)| key == "raw")
+            .and_then(|(_, value)| match value.as_ref() {
+                "1" | "true" => Some(RawMode::Only),
+                "both" => Some(RawMode::Both),
+                _ => None,
+            })
+    });
+    let Some(mode) = mode else {
+        return next.run(request).await;
+    };
+
+    let route = request.extensions().get::<MatchedPath>().map(|route| route.as_str().to_string());
+    if !route.as_deref().is_some_and(|route| RAW_ROUTES.contains(&route)) {
+        let msg = format!("raw is not supported on {}", route.as_deref().unwrap_or(request.uri().path()));
+        return (StatusCode::BAD_REQUEST, ApiResponse::<()>::error(msg)).into_response();
+    }
+
+    let (response, raw) = scope(next.run(request)).await;
+    let Some(raw) = raw.filter(|_| response.status().is_success()) else {
+        return response;
+    };
+    match mode {
+        RawMode::Only => ApiResponse::ok(raw).into_response(),
+        RawMode::Both => {
+            let (mut parts, body) = response.into_parts();
+            let body = match axum::body::to_bytes(body, usize::MAX).await {
+                Ok(body) => body,
+                Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+            };
+            let Ok(serde_json::Value::Object(mut typed)) = serde_json::from_slice(&body) else {
+                return Response::from_parts(parts, Body::from(body));
+            };
+            typed.insert("raw".to_string(), raw);
+            let body = serde_json::to_vec(&typed).unwrap_or_default();
+            parts.headers.remove(axum::http::header::CONTENT_LENGTH);
+            Response::from_parts(parts, Body::from(body))
+        }
+    }
+}
@@ -0,0 +1,136 @@
+//! 自动重试 (Retry Policy)
+//!
+//! Agent 偶发超时、网络抖动、上游 5xx 等瞬时故障不应直接返回给调用方。
+//! `XhsApiClient` 的 get / post 系列方法按本策略自动重试，每次重试都会重新生成签名。
+//!
+//! 退避: `base * 2^(n-1)`，上限 `max`，采用 full jitter（在 `[0, 退避]` 内随机）避免多个请求同步重试。
+//!
+//! 会重试的错误:
+//! - 上游 5xx
+//! - 网络超时 / 连接失败
+//! - 签名失败（Agent 不可用）/ 签名被拒 (406)
+//!
+//! 风控 (461)、维护、行为画像限制、登录失效等不重试：立即重试只会加重问题。
+//! 非幂等的写操作（`post_algo_mutation` / `post_with_body`）一律不重试，避免重复写入。
+//!
+//! 配置: `XHS_RETRY_MAX_ATTEMPTS`（含首次请求，默认 3，1 表示禁用）、
+//! `XHS_RETRY_BASE_MS`（默认 300）、`XHS_RETRY_MAX_MS`（默认 5000）
+
+use crate::error::{Result, XhsError};
+use rand::Rng;
+use std::future::Future;
+use std::time::Duration;
+
+/// 重试策略
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// 最大尝试次数（含首次请求）
+    pub max_attempts: u32,
+    /// 首次重试的基础退避
+    pub base_delay: Duration,
+    /// 单次退避上限
+    pub max_delay: Duration,
+    /// 是否在退避区间内随机抖动
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: crate::config::retry_max_attempts(),
+            base_delay: crate::config::retry_base_delay(),
+            max_delay: crate::config::retry_max_delay(),
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// 不重试
+    pub fn disabled() -> Self {
+        Self { max_attempts: 1, ..Self::default() }
+    }
+
+    /// 错误是否属于可重试的瞬时故障
+    pub fn should_retry(error: &XhsError) -> bool {
+        match error {
+            XhsError::Upstream { status, .. } => *status >= 500,
+            XhsError::Network(e) => e.is_timeout() || e.is_connect() || e.is_request(),
+            XhsError::SignatureFailed(_) | XhsError::InvalidSignature406 { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// 第 `retry` 次重试（从 1 开始）前的等待时间
+    pub fn delay(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        let backoff = self.base_delay.saturating_mul(factor).min(self.max_delay);
+        if !self.jitter || backoff.is_zero() {
+            return backoff;
+        }
+        let millis = backoff.as_millis() as u64;
+        Duration::from_millis(rand::thread_rng().gen_range(0..=millis))
+    }
+
+    /// 执行 `op`，遇到可重试错误时按退避策略重试
+    pub async fn run<T, F, Fut>(&self, label: &str, mut op: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut attempt = 1;
+        loop {
+            match op().await {
+                Err(e) if attempt < self.max_attempts && Self::should_retry(&e) => {
+                    let delay = self.delay(attempt);
+                    tracing::warn!(
+                        "[Retry] {} attempt {}/{} failed ({}): {}, retrying in {:?}",
+                        label, attempt, self.max_attempts, e.kind(), e, delay
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(jitter: bool) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 5,
+            base_delay: Duration::from_millis(300),
+            max_delay: Duration::from_millis(2000),
+            jitter,
+        }
+    }
+
+    #[test]
+    fn delay_doubles_up_to_max() {
+        let policy = policy(false);
+        assert_eq!(policy.delay(1), Duration::from_millis(300));
+        assert_eq!(policy.delay(2), Duration::from_millis(600));
+        assert_eq!(policy.delay(3), Duration::from_millis(1200));
+        assert_eq!(policy.delay(4), Duration::from_millis(2000));
+        assert_eq!(policy.delay(100), Duration::from_millis(2000));
+        assert_eq!(policy.delay(0), Duration::from_millis(300));
+    }
+
+    #[test]
+    fn jittered_delay_stays_within_backoff() {
+        let (jittered, fixed) = (policy(true), policy(false));
+        for retry in 1..8 {
+            assert!(jittered.delay(retry) <= fixed.delay(retry));
+        }
+    }
+
+    #[test]
+    fn zero_base_delay_never_waits() {
+        let policy = RetryPolicy { base_delay: Duration::ZERO, ..policy(true) };
+        assert_eq!(policy.delay(3), Duration::ZERO);
+    }
+}
@@ -0,0 +1,331 @@
+//! 461 风控熔断 (Risk-Control Circuit Breaker)
+//!
+//! 收到 461（或等价业务码）后继续请求同一接口只会加重风控。`RiskBreaker` 为该接口打开熔断：
+//!
+//! - **Closed**: 正常放行
+//! - **Open**: 冷却期内拒绝请求（`risk_circuit_open`，429）；冷却期不超过 `XHS_RISK_MAX_WAIT_SECS` 时改为排队等待
+//! - **HalfOpen**: 冷却结束后放行一个探测请求，成功则关闭，再次 461 则以加倍的冷却期重新打开
+//!
+//! 冷却期从 `XHS_RISK_COOLDOWN_SECS` 开始按连续触发次数翻倍，上限 `XHS_RISK_COOLDOWN_MAX_SECS`。
+//! 同时处于熔断的接口数达到 `XHS_RISK_ACCOUNT_THRESHOLD` 时整个账号熔断；
+//! 账号熔断冷却结束后由后台探针请求 user/me 确认恢复。
+//!
+//! 启用账号池轮换时，轮换账号收到的 461 不打开熔断，只由账号池隔离该账号（见 `rotation`），
+//! 其余账号继续服务。
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use utoipa::ToSchema;
+
+use crate::error::{Result, XhsError};
+
+#[cfg(feature = "server")]
+use crate::server::AppState;
+#[cfg(feature = "server")]
+use std::sync::Arc;
+
+/// 账号级熔断的 scope 名称
+const ACCOUNT_SCOPE: &str = "account";
+
+/// 探测请求超时：超过该时间仍无结果（如网络错误）时允许下一个请求重新探测
+const PROBE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 账号熔断探针检查间隔
+#[cfg(feature = "server")]
+const PROBE_TICK: Duration = Duration::from_secs(15);
+
+/// 状态中保留的响应体长度
+const BODY_PREVIEW_CHARS: usize = 200;
+
+/// 熔断状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+/// 单个熔断器快照
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CircuitStatus {
+    /// 接口（endpoint key 或 URI）或 "account"
+    pub scope: String,
+    pub state: CircuitState,
+    /// 连续触发次数（成功后清零）
+    pub trips: u32,
+    #[schema(value_type = Option<String>)]
+    pub opened_at: Option<DateTime<Utc>>,
+    /// 距冷却结束的秒数
+    pub retry_after_secs: u64,
+    /// 熔断期间被拒绝的请求数
+    pub rejected: u64,
+    /// 最近一次 461 响应（截断）
+    pub last_response: Option<String>,
+}
+
+/// 熔断总览
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RiskStatus {
+    /// 账号级熔断
+    pub account: CircuitStatus,
+    /// 非 Closed 或曾触发过的接口
+    pub endpoints: Vec<CircuitStatus>,
+    /// 累计 461 次数
+    pub total_trips: u64,
+}
+
+#[derive(Default)]
+struct Circuit {
+    trips: u32,
+    opened: Option<(DateTime<Utc>, Instant)>,
+    open_until: Option<Instant>,
+    probing: Option<Instant>,
+    rejected: u64,
+    last_response: Option<String>,
+}
+
+/// 准入判定
+enum Verdict {
+    Admit,
+    Wait(Duration),
+    Reject(Duration),
+}
+
+impl Circuit {
+    fn state(&self, now: Instant) -> CircuitState {
+        match self.open_until {
+            None => CircuitState::Closed,
+            Some(until) if now < until => CircuitState::Open,
+            Some(_) => CircuitState::HalfOpen,
+        }
+    }
+
+    fn trip(&mut self, cooldown: Duration, body: &str) {
+        let now = Instant::now();
+        self.trips += 1;
+        self.opened = Some((Utc::now(), now));
+        self.open_until = Some(now + cooldown);
+        self.probing = None;
+        self.last_response = Some(body.chars().take(BODY_PREVIEW_CHARS).collect());
+    }
+
+    fn close(&mut self) {
+        self.trips = 0;
+        self.opened = None;
+        self.open_until = None;
+        self.probing = None;
+        self.rejected = 0;
+    }
+
+    fn verdict(&mut self, now: Instant, max_wait: Duration) -> Verdict {
+        match self.state(now) {
+            CircuitState::Closed => Verdict::Admit,
+            CircuitState::HalfOpen if self.probing.is_none_or(|at| now.duration_since(at) >= PROBE_TIMEOUT) => {
+                self.probing = Some(now);
+                Verdict::Admit
+            }
+            // 探测请求进行中：其余请求短暂等待探测结果
+            CircuitState::HalfOpen => Verdict::Reject(Duration::from_secs(1)),
+            CircuitState::Open => {
+                let remaining = self.open_until.unwrap_or(now).saturating_duration_since(now);
+                if remaining <= max_wait {
+                    Verdict::Wait(remaining)
+                } else {
+                    self.rejected += 1;
+                    Verdict::Reject(remaining)
+                }
+            }
+        }
+    }
+
+    fn status(&self, scope: &str, now: Instant) -> CircuitStatus {
+        CircuitStatus {
+            scope: scope.to_string(),
+            state: self.state(now),
+            trips: self.trips,
+            opened_at: self.opened.map(|(at, _)| at),
+            retry_after_secs: self.open_until
+                .map(|until| until.saturating_duration_since(now).as_secs())
+                .unwrap_or(0),
+            rejected: self.rejected,
+            last_response: self.last_response.clone(),
+        }
+    }
+}
+
+struct BreakerState {
+    account: Circuit,
+    endpoints: HashMap<String, Circuit>,
+    total_trips: u64,
+}
+
+/// 461 风控熔断器
+pub struct RiskBreaker {
+    cooldown: Duration,
+    max_cooldown: Duration,
+    max_wait: Duration,
+    /// 同时熔断的接口数达到该值时熔断整个账号，0 表示不启用
+    account_threshold: usize,
+    state: Mutex<BreakerState>,
+}
+
+impl Default for RiskBreaker {
+    fn default() -> Self {
+        Self::new(
+            crate::config::risk_cooldown(),
+            crate::config::risk_max_cooldown(),
+            crate::config::risk_max_wait(),
+            crate::config::risk_account_threshold(),
+        )
+    }
+}
+
+impl RiskBreaker {
+    pub fn new(cooldown: Duration, max_cooldown: Duration, max_wait: Duration, account_threshold: usize) -> Self {
+        Self {
+            cooldown,
+            max_cooldown: max_cooldown.max(cooldown),
+            max_wait,
+            account_threshold,
+            state: Mutex::new(BreakerState {
+                account: Circuit::default(),
+                endpoints: HashMap::new(),
+                total_trips: 0,
+            }),
+        }
+    }
+
+    fn scope(endpoint: &str) -> &str {
+        endpoint.split('?').next().unwrap_or(endpoint)
+    }
+
+    fn cooldown_for(&self, trips: u32) -> Duration {
+        let factor = 2u32.saturating_pow(trips.min(16));
+        self.cooldown.saturating_mul(factor).min(self.max_cooldown)
+    }
+
+    /// 出站请求准入：账号或接口熔断时拒绝，剩余冷却不超过排队上限时等待
+    pub async fn admit(&self, endpoint: &str) -> Result<()> {
+        let scope = Self::scope(endpoint);
+        loop {
+            let verdict = {
+                let mut state = self.state.lock().unwrap();
+                let now = Instant::now();
+                match state.account.verdict(now, self.max_wait) {
+                    Verdict::Admit => {
+                        let circuit = state.endpoints.entry(scope.to_string()).or_default();
+                        match circuit.verdict(now, self.max_wait) {
+                            Verdict::Admit => return Ok(()),
+                            other => other.with_scope(scope),
+                        }
+                    }
+                    other => other.with_scope(ACCOUNT_SCOPE),
+                }
+            };
+            match verdict {
+                (Verdict::Wait(wait), scope) => {
+                    tracing::info!("[Risk] {} circuit open, queueing for {:?}", scope, wait);
+                    tokio::time::sleep(wait).await;
+                }
+                (Verdict::Reject(remaining), scope) => {
+                    return Err(XhsError::RiskCircuitOpen {
+                        scope: scope.to_string(),
+                        retry_after: remaining.as_secs().max(1),
+                    });
+                }
+                (Verdict::Admit, _) => return Ok(()),
+            }
+        }
+    }
+
+    /// 记录一次 461，打开（或以更长冷却期重新打开）接口熔断
+    pub fn trip(&self, endpoint: &str, body: &str) {
+        let scope = Self::scope(endpoint);
+        let mut state = self.state.lock().unwrap();
+        state.total_trips += 1;
+
+        let circuit = state.endpoints.entry(scope.to_string()).or_default();
+        let cooldown = self.cooldown_for(circuit.trips);
+        circuit.trip(cooldown, body);
+        tracing::warn!("[Risk] 461 on {}, circuit open for {:?} (trip #{})", scope, cooldown, circuit.trips);
+
+        let now = Instant::now();
+        let open = state.endpoints.values().filter(|c| c.state(now) != CircuitState::Closed).count();
+        let account_tripped = state.account.state(now) == CircuitState::HalfOpen
+            || (self.account_threshold > 0 && open >= self.account_threshold && state.account.state(now) == CircuitState::Closed);
+        if account_tripped {
+            let cooldown = self.cooldown_for(state.account.trips);
+            state.account.trip(cooldown, body);
+            tracing::warn!(
+                "[Risk] {} endpoint circuit(s) open, opening account circuit for {:?}",
+                open, cooldown
+            );
+        }
+    }
+
+    /// 记录一次成功响应，关闭接口熔断（账号熔断处于探测阶段时一并关闭）
+    pub fn record_success(&self, endpoint: &str) {
+        let scope = Self::scope(endpoint);
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        if let Some(circuit) = state.endpoints.get_mut(scope) {
+            if circuit.trips > 0 {
+                tracing::info!("[Risk] {} recovered, closing circuit", scope);
+                circuit.close();
+            }
+        }
+        if state.account.state(now) == CircuitState::HalfOpen {
+            tracing::info!("[Risk] Account recovered via {}, closing account circuit", scope);
+            state.account.close();
+        }
+    }
+
+    /// 账号熔断冷却已结束、等待探测
+    pub fn account_needs_probe(&self) -> bool {
+        let state = self.state.lock().unwrap();
+        state.account.state(Instant::now()) == CircuitState::HalfOpen
+    }
+
+    pub fn status(&self) -> RiskStatus {
+        let state = self.state.lock().unwrap();
+        let now = Instant::now();
+        let mut endpoints: Vec<CircuitStatus> = state.endpoints
+            .iter()
+            .filter(|(_, c)| c.trips > 0)
+            .map(|(scope, c)| c.status(scope, now))
+            .collect();
+        endpoints.sort_by(|a, b| b.retry_after_secs.cmp(&a.retry_after_secs).then(a.scope.cmp(&b.scope)));
+        RiskStatus {
+            account: state.account.status(ACCOUNT_SCOPE, now),
+            endpoints,
+            total_trips: state.total_trips,
+        }
+    }
+}
+
+impl Verdict {
+    fn with_scope(self, scope: &str) -> (Verdict, String) {
+        (self, scope.to_string())
+    }
+}
+
+#[cfg(feature = "server")]
+/// 启动账号熔断探针：冷却结束后发送一次 user/me 确认风控已解除
+pub fn spawn_probe(state: Arc<AppState>) {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(PROBE_TICK).await;
+            if !state.api.risk().account_needs_probe() {
+                continue;
+            }
+            match state.api.probe_upstream().await {
+                Ok(()) => state.api.risk().record_success("user_me"),
+                Err(e) => tracing::info!("[Risk] Account probe failed: {}", e),
+            }
+        }
+    });
+}
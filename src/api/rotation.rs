@@ -0,0 +1,399 @@
+//! 多账号轮换 (Account Rotation)
+//!
+//! 配置了多个凭据文件时，只读接口（笔记详情、评论、搜索、用户主页、话题页、首页推荐）
+//! 按轮换策略分摊到各账号，避免单账号触发限流；写接口、通知、专辑与 user/me 等
+//! 与账号身份相关的接口始终使用主账号 (cookie.json)。
+//!
+//! 配置 (环境变量):
+//! - `XHS_ACCOUNT_POOL`: 额外的凭据文件，逗号分隔（格式同 cookie.json）；主账号始终在池中
+//! - `XHS_ACCOUNT_ROTATION`: 轮换策略
+//!   - `round_robin`（默认）: 依次使用
+//!   - `lru`: 使用最久未用的账号
+//!   - `health`: 优先近一小时 406/461 最少、且不需要重新登录的账号，相同时取最久未用
+//!
+//! 只有凭据有效的账号参与轮换；某账号登录过期时只失效该账号的凭据。
+//!
+//! ## 隔离 (Quarantine)
+//! 账号在 `XHS_QUARANTINE_WINDOW_SECS`（默认 600）内连续收到 `XHS_QUARANTINE_THRESHOLD`（默认 3）次
+//! 风控信号（461 / 业务码 300012、300013、HTTP 419、验证码）时被隔离 `XHS_QUARANTINE_SECS`
+//! （默认 3600）秒，期间不参与轮换；账号异常（业务码 300011）立即隔离。成功响应清零计数。
+//! 所有已登录账号都在隔离中时，只读接口返回 `RiskCircuitOpen`。
+//! 隔离状态见 `/api/admin/accounts`，可提前解除。
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use utoipa::ToSchema;
+
+use crate::api::verification::VerificationGate;
+use crate::auth::{AuthService, UserCredentials};
+use crate::error::XhsError;
+
+/// 参与轮换的只读接口（endpoint key、endpoint key 前缀或不含查询参数的 URI）
+const ROTATED_ENDPOINTS: &[&str] = &[
+    "/api/sns/web/v1/feed",
+    "/api/sns/web/v1/user_posted",
+    "/api/sns/web/v1/page/notes",
+    "/api/sns/web/v1/search/notes",
+    "/api/sns/web/v1/search/onebox",
+    "/api/sns/web/v1/search/usersearch",
+    "search_trending",
+    "search_recommend",
+    "search_filter",
+    "note_page",
+    "note_sub_comments",
+    "home_feed_",
+];
+
+/// 轮换策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RotationStrategy {
+    #[default]
+    RoundRobin,
+    #[serde(rename = "lru")]
+    LeastRecentlyUsed,
+    #[serde(rename = "health")]
+    HealthWeighted,
+}
+
+impl RotationStrategy {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "round_robin" | "round-robin" | "rr" => Some(Self::RoundRobin),
+            "lru" | "least_recently_used" => Some(Self::LeastRecentlyUsed),
+            "health" | "health_weighted" => Some(Self::HealthWeighted),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::RoundRobin => "round_robin",
+            Self::LeastRecentlyUsed => "lru",
+            Self::HealthWeighted => "health",
+        }
+    }
+}
+
+/// 隔离策略
+#[derive(Debug, Clone)]
+pub struct QuarantinePolicy {
+    /// 窗口内触发隔离的风控信号次数
+    pub threshold: usize,
+    pub window: Duration,
+    /// 隔离时长
+    pub cooldown: Duration,
+}
+
+impl Default for QuarantinePolicy {
+    fn default() -> Self {
+        let secs = |key: &str, default: u64| {
+            std::env::var(key).ok().and_then(|v| v.parse::<u64>().ok()).unwrap_or(default)
+        };
+        Self {
+            threshold: secs("XHS_QUARANTINE_THRESHOLD", 3).max(1) as usize,
+            window: Duration::from_secs(secs("XHS_QUARANTINE_WINDOW_SECS", 600)),
+            cooldown: Duration::from_secs(secs("XHS_QUARANTINE_SECS", 3600)),
+        }
+    }
+}
+
+/// 隔离中的账号
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Quarantine {
+    #[schema(value_type = String)]
+    pub since: DateTime<Utc>,
+    #[schema(value_type = String)]
+    pub until: DateTime<Utc>,
+    /// 触发隔离的信号（461 / 419 / captcha / account_restricted）
+    pub reason: String,
+}
+
+/// 账号池中一个账号的状态
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PooledAccount {
+    /// 账号下标（0 为主账号），用于解除隔离
+    pub index: usize,
+    /// 凭据文件
+    pub source: String,
+    pub logged_in: bool,
+    pub user_id: Option<String>,
+    /// 距上次被选中的秒数
+    pub last_used_secs: Option<u64>,
+    /// 窗口内的风控信号次数
+    pub recent_signals: usize,
+    /// 隔离中时不为空
+    pub quarantine: Option<Quarantine>,
+}
+
+/// 账号池状态
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AccountPoolStatus {
+    pub strategy: RotationStrategy,
+    pub accounts: Vec<PooledAccount>,
+}
+
+#[derive(Default)]
+struct AccountState {
+    last_used: Option<Instant>,
+    /// 窗口内风控信号的时间
+    signals: VecDeque<Instant>,
+    quarantine: Option<Quarantine>,
+}
+
+impl AccountState {
+    /// 是否隔离中（到期的隔离在此解除）
+    fn quarantined(&mut self, index: usize) -> bool {
+        match &self.quarantine {
+            Some(q) if q.until > Utc::now() => true,
+            Some(_) => {
+                tracing::info!("[Rotation] Account #{} quarantine expired", index);
+                self.quarantine = None;
+                false
+            }
+            None => false,
+        }
+    }
+}
+
+/// 账号池：主账号 + `XHS_ACCOUNT_POOL` 中的账号
+pub struct AccountPool {
+    strategy: RotationStrategy,
+    policy: QuarantinePolicy,
+    /// (凭据文件, 账号)
+    accounts: Vec<(String, Arc<AuthService>)>,
+    cursor: AtomicUsize,
+    /// 各账号的轮换与隔离状态（与 accounts 下标对应）
+    states: Mutex<Vec<AccountState>>,
+    /// user_id -> 账号下标，用于把响应（登录过期、406/461）记到对应账号
+    owners: Mutex<HashMap<String, usize>>,
+}
+
+impl AccountPool {
+    /// `accounts[0]` 为主账号
+    pub fn new(strategy: RotationStrategy, policy: QuarantinePolicy, accounts: Vec<(String, Arc<AuthService>)>) -> Self {
+        let states = Mutex::new(accounts.iter().map(|_| AccountState::default()).collect());
+        Self {
+            strategy,
+            policy,
+            accounts,
+            cursor: AtomicUsize::new(0),
+            states,
+            owners: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 按 XHS_ACCOUNT_POOL / XHS_ACCOUNT_ROTATION 构建；未配置额外账号时为 None
+    pub async fn from_env(primary: Arc<AuthService>) -> Result<Option<Self>> {
+        let files: Vec<PathBuf> = std::env::var("XHS_ACCOUNT_POOL")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(PathBuf::from)
+            .collect();
+        if files.is_empty() {
+            return Ok(None);
+        }
+        let strategy = match std::env::var("XHS_ACCOUNT_ROTATION") {
+            Ok(value) if !value.trim().is_empty() => RotationStrategy::parse(&value)
+                .ok_or_else(|| anyhow!("Invalid XHS_ACCOUNT_ROTATION '{}' (expected round_robin / lru / health)", value))?,
+            _ => RotationStrategy::default(),
+        };
+        let mut accounts = vec![("cookie.json".to_string(), primary)];
+        for file in files {
+            let source = file.display().to_string();
+            accounts.push((source, Arc::new(AuthService::new(file).await?)));
+        }
+        tracing::info!("[Rotation] {} accounts, strategy {}", accounts.len(), strategy.as_str());
+        Ok(Some(Self::new(strategy, QuarantinePolicy::default(), accounts)))
+    }
+
+    /// (凭据文件, 账号)，下标与 `status()` 一致
+    pub fn accounts(&self) -> &[(String, Arc<AuthService>)] {
+        &self.accounts
+    }
+
+    pub fn strategy(&self) -> RotationStrategy {
+        self.strategy
+    }
+
+    /// 账号数（含主账号）
+    pub fn len(&self) -> usize {
+        self.accounts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+
+    /// 该接口是否参与轮换
+    pub fn allows(&self, endpoint: &str) -> bool {
+        let path = endpoint.split('?').next().unwrap_or(endpoint);
+        ROTATED_ENDPOINTS
+            .iter()
+            .any(|e| path == *e || (e.ends_with('_') && path.starts_with(e)))
+    }
+
+    /// 按策略选出一个已登录、未隔离且未被验证码暂停的账号的凭据
+    ///
+    /// 没有已登录账号时为 None（回退到主账号 / 访客）；已登录账号全部隔离时返回 `RiskCircuitOpen`；
+    /// 未隔离的账号全部等待验证时仍返回其中一个，由 `VerificationGate` 拒绝并给出验证链接
+    pub async fn pick(&self, verification: &VerificationGate) -> Result<Option<UserCredentials>, XhsError> {
+        let mut logged_in = Vec::new();
+        for (index, (_, auth)) in self.accounts.iter().enumerate() {
+            if let Ok(Some(credentials)) = auth.try_get_credentials().await {
+                logged_in.push((index, credentials));
+            }
+        }
+        if logged_in.is_empty() {
+            return Ok(None);
+        }
+
+        let mut states = self.states.lock().unwrap();
+        let mut live: Vec<_> = logged_in
+            .into_iter()
+            .filter(|(index, _)| !states[*index].quarantined(*index))
+            .collect();
+        if live.is_empty() {
+            let until = states.iter().filter_map(|s| s.quarantine.as_ref().map(|q| q.until)).min();
+            let retry_after = until.map(|u| (u - Utc::now()).num_seconds().max(1) as u64).unwrap_or(1);
+            return Err(XhsError::RiskCircuitOpen { scope: "account_pool".to_string(), retry_after });
+        }
+        if live.iter().any(|(_, credentials)| !verification.is_paused(credentials)) {
+            live.retain(|(_, credentials)| !verification.is_paused(credentials));
+        }
+
+        let position = match self.strategy {
+            RotationStrategy::RoundRobin => self.cursor.fetch_add(1, Ordering::Relaxed) % live.len(),
+            RotationStrategy::LeastRecentlyUsed => (0..live.len())
+                .min_by_key(|&i| states[live[i].0].last_used)
+                .unwrap_or_default(),
+            RotationStrategy::HealthWeighted => (0..live.len())
+                .min_by_key(|&i| {
+                    let (index, credentials) = &live[i];
+                    let health = self.accounts[*index].1.health().snapshot("", Some(credentials));
+                    (health.needs_relogin, health.recent_406 + health.recent_461, states[*index].last_used)
+                })
+                .unwrap_or_default(),
+        };
+        let (index, credentials) = live.swap_remove(position);
+        states[index].last_used = Some(Instant::now());
+        drop(states);
+
+        self.owners.lock().unwrap().insert(credentials.user_id.clone(), index);
+        tracing::debug!("[Rotation] Using account #{} ({})", index, credentials.user_id);
+        Ok(Some(credentials))
+    }
+
+    fn owner_index(&self, credentials: &UserCredentials) -> Option<usize> {
+        self.owners.lock().unwrap().get(&credentials.user_id).copied()
+    }
+
+    /// 凭据所属账号的 AuthService（不是轮换选出的凭据时为 None）
+    pub fn owner(&self, credentials: &UserCredentials) -> Option<Arc<AuthService>> {
+        self.accounts.get(self.owner_index(credentials)?).map(|(_, auth)| auth.clone())
+    }
+
+    /// 记录一次请求结果：风控信号累计到阈值时隔离该账号，成功响应清零计数
+    pub fn record<T>(&self, credentials: &UserCredentials, result: &Result<T, XhsError>) {
+        let Some(index) = self.owner_index(credentials) else {
+            return;
+        };
+        let mut states = self.states.lock().unwrap();
+        let state = &mut states[index];
+        let (signal, immediate) = match result {
+            Ok(_) => {
+                state.signals.clear();
+                return;
+            }
+            Err(error) => match risk_signal(error) {
+                Some(signal) => signal,
+                None => return,
+            },
+        };
+
+        let now = Instant::now();
+        state.signals.push_back(now);
+        while state.signals.front().is_some_and(|at| now.duration_since(*at) > self.policy.window) {
+            state.signals.pop_front();
+        }
+        if state.quarantine.is_some() || !(immediate || state.signals.len() >= self.policy.threshold) {
+            return;
+        }
+
+        let since = Utc::now();
+        let until = since + chrono::Duration::from_std(self.policy.cooldown).unwrap_or_default();
+        tracing::warn!(
+            "[Rotation] Quarantining account #{} ({}) until {} after {} ({} signals)",
+            index, credentials.user_id, until, signal, state.signals.len()
+        );
+        state.signals.clear();
+        state.quarantine = Some(Quarantine { since, until, reason: signal.to_string() });
+    }
+
+    /// 提前解除隔离；账号不存在或未隔离时返回 false
+    pub fn release(&self, index: usize) -> bool {
+        let mut states = self.states.lock().unwrap();
+        let released = states.get_mut(index).and_then(|s| s.quarantine.take()).is_some();
+        if released {
+            states[index].signals.clear();
+            tracing::info!("[Rotation] Account #{} released from quarantine", index);
+        }
+        released
+    }
+
+    /// 各账号的登录、轮换与隔离状态
+    pub async fn status(&self) -> AccountPoolStatus {
+        let mut credentials = Vec::with_capacity(self.accounts.len());
+        for (_, auth) in &self.accounts {
+            credentials.push(auth.try_get_credentials().await.ok().flatten());
+        }
+        let now = Instant::now();
+        let window = self.policy.window;
+        let mut states = self.states.lock().unwrap();
+        let accounts = self.accounts
+            .iter()
+            .zip(credentials)
+            .enumerate()
+            .map(|(index, ((source, _), creds))| {
+                let state = &mut states[index];
+                state.quarantined(index);
+                PooledAccount {
+                    index,
+                    source: source.clone(),
+                    logged_in: creds.is_some(),
+                    user_id: creds.map(|c| c.user_id),
+                    last_used_secs: state.last_used.map(|at| now.duration_since(at).as_secs()),
+                    recent_signals: state.signals.iter().filter(|at| now.duration_since(**at) <= window).count(),
+                    quarantine: state.quarantine.clone(),
+                }
+            })
+            .collect();
+        AccountPoolStatus { strategy: self.strategy, accounts }
+    }
+}
+
+/// 响应是否为账号级风控信号，返回 (信号名, 是否立即隔离)
+fn risk_signal(error: &XhsError) -> Option<(&'static str, bool)> {
+    match error {
+        XhsError::AccountRestricted(_) => Some(("account_restricted", true)),
+        XhsError::RiskControl461 { body } if is_captcha(body) => Some(("captcha", false)),
+        XhsError::RiskControl461 { .. } => Some(("461", false)),
+        XhsError::Upstream { status: 419, .. } => Some(("419", false)),
+        XhsError::Upstream { body, .. } if is_captcha(body) => Some(("captcha", false)),
+        _ => None,
+    }
+}
+
+/// 响应体是否要求验证码（滑块 / 图形验证）
+fn is_captcha(body: &str) -> bool {
+    let body = body.to_lowercase();
+    ["captcha", "verifyuuid", "verify_uuid", "verifytype", "verify_type"].iter().any(|k| body.contains(k))
+}
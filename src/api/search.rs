@@ -1,6 +1,7 @@
-use anyhow::Result;
+use crate::error::Result;
 use crate::api::XhsApiClient;
 use crate::models::search::*;
+use crate::models::parse;
 use rand::{Rng, distributions::Alphanumeric};
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -9,7 +10,7 @@ use std::time::{SystemTime, UNIX_EPOCH};
 /// 获取小红书首页搜索框的热门搜索推荐词
 pub async fn query_trending(api: &XhsApiClient) -> Result<QueryTrendingResponse> {
     let text = api.get("search_trending").await?;
-    let result = serde_json::from_str::<QueryTrendingResponse>(&text)?;
+    let result = parse::from_str::<QueryTrendingResponse>(&text)?;
     Ok(result)
 }
 
@@ -22,7 +23,7 @@ pub async fn recommend_search(api: &XhsApiClient, keyword: &str) -> Result<Searc
     
     // 使用 get_with_url 处理动态参数并进行纯算法签名
     let text = api.get_with_url("search_recommend", &url).await?;
-    let result = serde_json::from_str::<SearchRecommendResponse>(&text)?;
+    let result = parse::from_str::<SearchRecommendResponse>(&text)?;
     Ok(result)
 }
 
@@ -64,7 +65,15 @@ fn generate_request_id() -> String {
 }
 
 /// 搜索笔记列表
-pub async fn search_notes(api: &XhsApiClient, mut req: SearchNotesRequest) -> Result<SearchNotesResponse> {
+pub async fn search_notes(api: &XhsApiClient, req: SearchNotesRequest) -> Result<SearchNotesResponse> {
+    let (mut result, deduplicated) = api.dedup()
+        .run("search_notes", &req, || search_notes_internal(api, req.clone()))
+        .await?;
+    result.deduplicated = deduplicated;
+    Ok(result)
+}
+
+async fn search_notes_internal(api: &XhsApiClient, mut req: SearchNotesRequest) -> Result<SearchNotesResponse> {
     // 自动补全 search_id (格式: xxx@xxx)
     if req.search_id.is_none() || req.search_id.as_ref().is_some_and(|s| s.is_empty()) {
         req.search_id = Some(generate_search_id());
@@ -83,16 +92,16 @@ pub async fn search_notes(api: &XhsApiClient, mut req: SearchNotesRequest) -> Re
         "page_size": req.page_size,
         "search_id": req.search_id,
         "sort": req.sort,
-        "note_type": req.note_type,
+        "note_type": req.note_type.code(),
         "ext_flags": req.ext_flags,
-        "filters": req.filters,
+        "filters": req.filters(),
         "geo": req.geo,
         "image_formats": req.image_formats
     });
     
     // 使用 post_algo 进行签名和发送
     let text = api.post_algo(path, payload).await?;
-    let mut result = serde_json::from_str::<SearchNotesResponse>(&text)?;
+    let mut result = parse::from_str::<SearchNotesResponse>(&text)?;
     
     // 注入 search_id 到响应中，供客户端用于后续请求 (如 onebox)
     if let Some(ref mut data) = result.data {
@@ -105,7 +114,15 @@ pub async fn search_notes(api: &XhsApiClient, mut req: SearchNotesRequest) -> Re
 /// 搜索 OneBox (聚合结果)
 /// 
 /// 注意：onebox 应使用与 search/notes 相同的 search_id 来关联搜索会话
-pub async fn search_onebox(api: &XhsApiClient, mut req: SearchOneboxRequest) -> Result<SearchOneboxResponse> {
+pub async fn search_onebox(api: &XhsApiClient, req: SearchOneboxRequest) -> Result<SearchOneboxResponse> {
+    let (mut result, deduplicated) = api.dedup()
+        .run("search_onebox", &req, || search_onebox_internal(api, req.clone()))
+        .await?;
+    result.deduplicated = deduplicated;
+    Ok(result)
+}
+
+async fn search_onebox_internal(api: &XhsApiClient, mut req: SearchOneboxRequest) -> Result<SearchOneboxResponse> {
     // 只在 search_id 为空时才自动生成，保持与 notes 的会话关联
     if req.search_id.is_empty() {
         req.search_id = generate_simple_search_id();
@@ -119,7 +136,7 @@ pub async fn search_onebox(api: &XhsApiClient, mut req: SearchOneboxRequest) ->
     let payload = serde_json::to_value(&req)?;
     
     let text = api.post_algo(path, payload).await?;
-    let result = serde_json::from_str::<SearchOneboxResponse>(&text)?;
+    let result = parse::from_str::<SearchOneboxResponse>(&text)?;
     Ok(result)
 }
 
@@ -131,12 +148,20 @@ pub async fn search_filter(api: &XhsApiClient, keyword: &str, search_id: &str) -
     
     // get_with_url 适用于任何 edith URL，只要路径正确即可
     let text = api.get_with_url("search_filter", &url).await?;
-    let result = serde_json::from_str::<SearchFilterResponse>(&text)?;
+    let result = parse::from_str::<SearchFilterResponse>(&text)?;
     Ok(result)
 }
 
 /// 搜索用户列表
-pub async fn search_user(api: &XhsApiClient, mut req: SearchUserRequest) -> Result<SearchUserResponse> {
+pub async fn search_user(api: &XhsApiClient, req: SearchUserRequest) -> Result<SearchUserResponse> {
+    let (mut result, deduplicated) = api.dedup()
+        .run("search_user", &req, || search_user_internal(api, req.clone()))
+        .await?;
+    result.deduplicated = deduplicated;
+    Ok(result)
+}
+
+async fn search_user_internal(api: &XhsApiClient, mut req: SearchUserRequest) -> Result<SearchUserResponse> {
     // 补全 search_id (使用简单格式)
     if req.search_id.is_none() || req.search_id.as_ref().map(|s| s.starts_with("demo")).unwrap_or(false) {
         req.search_id = Some(generate_simple_search_id());
@@ -154,6 +179,6 @@ pub async fn search_user(api: &XhsApiClient, mut req: SearchUserRequest) -> Resu
     
     let payload = serde_json::to_value(&request_wrapper)?;
     let text = api.post_algo(path, payload).await?;
-    let result = serde_json::from_str::<SearchUserResponse>(&text)?;
+    let result = parse::from_str::<SearchUserResponse>(&text)?;
     Ok(result)
 }
@@ -0,0 +1,167 @@
+//! Tag Page API - 话题页笔记流
+//!
+//! 话题落地页 (https://www.xiaohongshu.com/page/topics/{page_id}) 的笔记列表，
+//! 与关键词搜索不同：结果限定在话题内，支持 最热/最新 两种排序，用于话题级监控
+
+use crate::api::XhsApiClient;
+use crate::models::partial::{parse_items, ItemParseError};
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use crate::models::parse;
+
+/// 话题页排序方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TagSort {
+    /// 最热
+    #[default]
+    Hot,
+    /// 最新
+    Time,
+}
+
+impl TagSort {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TagSort::Hot => "hot",
+            TagSort::Time => "time",
+        }
+    }
+}
+
+/// 话题页笔记流请求参数
+#[derive(Debug, Clone, Deserialize, utoipa::IntoParams)]
+pub struct TagNotesParams {
+    /// 排序: hot (最热，默认) / time (最新)
+    #[serde(default)]
+    #[param(value_type = Option<TagSort>)]
+    pub sort: TagSort,
+    /// 分页游标，首次请求为空，后续使用响应中的 cursor
+    #[serde(default)]
+    pub cursor: Option<String>,
+    /// 每页数量
+    #[serde(default = "default_page_size")]
+    #[param(default = 20, minimum = 1, maximum = 50)]
+    pub page_size: i32,
+}
+
+fn default_page_size() -> i32 { 20 }
+
+impl Default for TagNotesParams {
+    fn default() -> Self {
+        Self {
+            sort: TagSort::Hot,
+            cursor: None,
+            page_size: default_page_size(),
+        }
+    }
+}
+
+/// 话题页笔记流响应
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
+#[schema(example = crate::models::examples::tag_notes_response)]
+pub struct TagNotesResponse {
+    #[serde(default)]
+    pub code: i32,
+    pub success: bool,
+    #[serde(default)]
+    pub msg: Option<String>,
+    #[serde(default)]
+    pub data: Option<TagNotesData>,
+}
+
+/// 话题页笔记流数据
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(from = "RawTagNotesData")]
+pub struct TagNotesData {
+    /// 下一页游标
+    pub cursor: Option<String>,
+    pub has_more: bool,
+    /// 笔记列表（仅包含解析成功的条目）
+    pub notes: Vec<TagNote>,
+    /// 解析失败的条目
+    pub parse_errors: Vec<ItemParseError>,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
+struct RawTagNotesData {
+    #[serde(default)]
+    cursor: Option<String>,
+    #[serde(default)]
+    has_more: bool,
+    #[serde(default)]
+    notes: Vec<serde_json::Value>,
+}
+
+impl From<RawTagNotesData> for TagNotesData {
+    fn from(raw: RawTagNotesData) -> Self {
+        let (notes, parse_errors) = parse_items(raw.notes);
+        Self { cursor: raw.cursor, has_more: raw.has_more, notes, parse_errors }
+    }
+}
+
+/// 话题页笔记
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
+pub struct TagNote {
+    /// 笔记ID
+    #[serde(alias = "note_id")]
+    pub id: String,
+    /// 笔记类型 (normal, video)
+    #[serde(rename = "type", default)]
+    pub note_type: Option<String>,
+    /// 标题
+    #[serde(alias = "display_title", default)]
+    pub title: Option<String>,
+    /// 安全Token（用于获取详情）
+    #[serde(default)]
+    pub xsec_token: Option<String>,
+    /// 发布时间 (毫秒)
+    #[serde(default)]
+    pub time: Option<i64>,
+    /// 点赞数
+    #[serde(alias = "liked_count", default)]
+    pub likes: Option<i64>,
+    /// 收藏数
+    #[serde(alias = "collected_count", default)]
+    pub collects: Option<i64>,
+    /// 评论数
+    #[serde(alias = "comment_count", default)]
+    pub comments: Option<i64>,
+    /// 分享数
+    #[serde(alias = "share_count", default)]
+    pub shares: Option<i64>,
+    /// 作者信息
+    #[serde(default)]
+    pub user: Option<serde_json::Value>,
+    /// 封面/图片
+    #[serde(alias = "cover", default)]
+    pub images_list: Option<serde_json::Value>,
+}
+
+/// 获取话题页笔记流
+///
+/// # Arguments
+/// * `api` - API 客户端
+/// * `tag_id` - 话题 ID (page_id)
+/// * `params` - 排序与分页参数
+pub async fn get_tag_notes(api: &XhsApiClient, tag_id: &str, params: TagNotesParams) -> Result<TagNotesResponse> {
+    let cursor = params.cursor.unwrap_or_default();
+    let path = "/api/sns/web/v1/page/notes";
+    // 参数已编码，直接使用完整 URL（get_with_query 会再编码一次）
+    let url = format!(
+        "https://edith.xiaohongshu.com{}?page_id={}&cursor={}&page_size={}&sort={}",
+        path,
+        urlencoding::encode(tag_id),
+        urlencoding::encode(&cursor),
+        params.page_size,
+        params.sort.as_str(),
+    );
+
+    let text = api.get_with_url(path, &url).await?;
+    let result = parse::from_str::<TagNotesResponse>(&text)?;
+    Ok(result)
+}
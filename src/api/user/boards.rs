@@ -0,0 +1,242 @@
+//! Boards API - 收藏专辑
+//!
+//! 当前用户的专辑列表、专辑内笔记，以及创建/删除专辑。
+//! 专辑列表需要 user_id，未指定时先通过 user/me 获取当前用户。
+
+use crate::api::XhsApiClient;
+use crate::models::partial::{parse_items, ItemParseError};
+use crate::models::user::PostedNote;
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use crate::models::parse;
+
+/// 专辑列表请求参数
+#[derive(Debug, Clone, Deserialize, utoipa::IntoParams)]
+pub struct BoardListParams {
+    /// 用户ID，默认当前登录用户
+    #[serde(default)]
+    pub user_id: Option<String>,
+    /// 页码，从 1 开始
+    #[serde(default = "default_page")]
+    #[param(default = 1, minimum = 1)]
+    pub page: i32,
+    /// 每页数量
+    #[serde(default = "default_board_num")]
+    #[param(default = 15, minimum = 1, maximum = 30)]
+    pub num: i32,
+}
+
+fn default_page() -> i32 { 1 }
+fn default_board_num() -> i32 { 15 }
+
+/// 专辑内笔记请求参数
+#[derive(Debug, Clone, Deserialize, utoipa::IntoParams)]
+pub struct BoardNotesParams {
+    /// 分页游标，首次请求为空，后续使用响应中的 cursor
+    #[serde(default)]
+    pub cursor: Option<String>,
+    /// 每页数量
+    #[serde(default = "default_note_num")]
+    #[param(default = 10, minimum = 1, maximum = 30)]
+    pub num: i32,
+}
+
+fn default_note_num() -> i32 { 10 }
+
+/// 创建专辑请求
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({"name": "露营装备", "desc": "", "privacy": 0}))]
+pub struct CreateBoardRequest {
+    /// 专辑名称
+    pub name: String,
+    /// 专辑描述
+    #[serde(default)]
+    pub desc: String,
+    /// 0 公开 / 1 私密
+    #[serde(default)]
+    pub privacy: i32,
+}
+
+/// 专辑信息
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
+#[schema(example = json!({
+    "id": "64f1c2d3000000001e03a4b5",
+    "name": "露营装备",
+    "desc": "",
+    "privacy": 0,
+    "total": 42,
+    "fans": 3,
+    "images": ["https://sns-webpic-qc.xhscdn.com/..."]
+}))]
+pub struct Board {
+    /// 专辑ID
+    #[serde(alias = "board_id")]
+    pub id: String,
+    /// 名称
+    #[serde(default)]
+    pub name: Option<String>,
+    /// 描述
+    #[serde(default)]
+    pub desc: Option<String>,
+    /// 0 公开 / 1 私密
+    #[serde(default)]
+    pub privacy: Option<i32>,
+    /// 笔记数
+    #[serde(alias = "note_count", default)]
+    pub total: Option<i64>,
+    /// 关注数
+    #[serde(alias = "fans_count", default)]
+    pub fans: Option<i64>,
+    /// 封面图
+    #[serde(default)]
+    pub images: Vec<String>,
+}
+
+/// 专辑列表响应
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
+pub struct BoardListResponse {
+    #[serde(default)]
+    pub code: i32,
+    pub success: bool,
+    #[serde(default)]
+    pub msg: Option<String>,
+    #[serde(default)]
+    pub data: Option<BoardListData>,
+}
+
+/// 专辑列表数据
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(from = "RawBoardListData")]
+pub struct BoardListData {
+    pub has_more: bool,
+    /// 专辑列表（仅包含解析成功的条目）
+    pub boards: Vec<Board>,
+    /// 解析失败的条目
+    pub parse_errors: Vec<ItemParseError>,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
+struct RawBoardListData {
+    #[serde(default)]
+    has_more: bool,
+    #[serde(default)]
+    boards: Vec<serde_json::Value>,
+}
+
+impl From<RawBoardListData> for BoardListData {
+    fn from(raw: RawBoardListData) -> Self {
+        let (boards, parse_errors) = parse_items(raw.boards);
+        Self { has_more: raw.has_more, boards, parse_errors }
+    }
+}
+
+/// 专辑内笔记响应
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
+pub struct BoardNotesResponse {
+    #[serde(default)]
+    pub code: i32,
+    pub success: bool,
+    #[serde(default)]
+    pub msg: Option<String>,
+    #[serde(default)]
+    pub data: Option<BoardNotesData>,
+}
+
+/// 专辑内笔记数据
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(from = "RawBoardNotesData")]
+pub struct BoardNotesData {
+    /// 下一页游标
+    pub cursor: Option<String>,
+    pub has_more: bool,
+    /// 笔记列表（仅包含解析成功的条目）
+    pub notes: Vec<PostedNote>,
+    /// 解析失败的条目
+    pub parse_errors: Vec<ItemParseError>,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
+struct RawBoardNotesData {
+    #[serde(default)]
+    cursor: Option<String>,
+    #[serde(default)]
+    has_more: bool,
+    #[serde(default)]
+    notes: Vec<serde_json::Value>,
+}
+
+impl From<RawBoardNotesData> for BoardNotesData {
+    fn from(raw: RawBoardNotesData) -> Self {
+        let (notes, parse_errors) = parse_items(raw.notes);
+        Self { cursor: raw.cursor, has_more: raw.has_more, notes, parse_errors }
+    }
+}
+
+/// 创建/删除专辑响应
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
+pub struct BoardMutationResponse {
+    #[serde(default)]
+    pub code: i32,
+    pub success: bool,
+    #[serde(default)]
+    pub msg: Option<String>,
+    /// 创建时返回新专辑信息
+    #[serde(default)]
+    pub data: Option<serde_json::Value>,
+}
+
+/// 获取专辑列表
+pub async fn get_boards(api: &XhsApiClient, params: BoardListParams) -> Result<BoardListResponse> {
+    let user_id = super::resolve_user_id(api, params.user_id).await?;
+
+    let path = "/api/sns/web/v1/board/user";
+    // 参数已编码，直接使用完整 URL（get_with_query 会再编码一次）
+    let url = format!(
+        "https://edith.xiaohongshu.com{}?user_id={}&num={}&page={}",
+        path,
+        urlencoding::encode(&user_id),
+        params.num,
+        params.page,
+    );
+    let text = api.get_with_url(path, &url).await?;
+    let result = parse::from_str::<BoardListResponse>(&text)?;
+    Ok(result)
+}
+
+/// 获取专辑内笔记，使用 cursor 分页
+pub async fn get_board_notes(api: &XhsApiClient, board_id: &str, params: BoardNotesParams) -> Result<BoardNotesResponse> {
+    let path = "/api/sns/web/v1/board/note";
+    let url = format!(
+        "https://edith.xiaohongshu.com{}?board_id={}&num={}&cursor={}&image_formats=jpg,webp,avif",
+        path,
+        urlencoding::encode(board_id),
+        params.num,
+        urlencoding::encode(&params.cursor.unwrap_or_default()),
+    );
+    let text = api.get_with_url(path, &url).await?;
+    let result = parse::from_str::<BoardNotesResponse>(&text)?;
+    Ok(result)
+}
+
+/// 创建专辑
+pub async fn create_board(api: &XhsApiClient, req: CreateBoardRequest) -> Result<BoardMutationResponse> {
+    let payload = serde_json::to_value(&req)?;
+    let text = api.post_algo_mutation("/api/sns/web/v1/board", payload).await?;
+    let result = parse::from_str::<BoardMutationResponse>(&text)?;
+    Ok(result)
+}
+
+/// 删除专辑
+pub async fn delete_board(api: &XhsApiClient, board_id: &str) -> Result<BoardMutationResponse> {
+    let payload = serde_json::json!({ "board_id": board_id });
+    let text = api.post_algo_mutation("/api/sns/web/v1/board/delete", payload).await?;
+    let result = parse::from_str::<BoardMutationResponse>(&text)?;
+    Ok(result)
+}
@@ -0,0 +1,139 @@
+//! Follows API - 关注 / 粉丝列表
+//!
+//! 按 cursor 分页遍历用户的关注列表与粉丝列表，用于关系图谱分析。
+//! 未指定 user_id 时使用当前登录用户。
+
+use crate::api::XhsApiClient;
+use crate::models::partial::{parse_items, ItemParseError};
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use crate::models::parse;
+
+/// 关系列表类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FollowKind {
+    /// 关注
+    Followings,
+    /// 粉丝
+    Followers,
+}
+
+impl FollowKind {
+    fn path(&self) -> &'static str {
+        match self {
+            FollowKind::Followings => "/api/sns/web/v1/user/followings",
+            FollowKind::Followers => "/api/sns/web/v1/user/followers",
+        }
+    }
+}
+
+/// 关注/粉丝列表请求参数
+#[derive(Debug, Clone, Deserialize, utoipa::IntoParams)]
+pub struct FollowListParams {
+    /// 用户ID，默认当前登录用户
+    #[serde(default)]
+    pub user_id: Option<String>,
+    /// 分页游标，首次请求为空，后续使用响应中的 cursor
+    #[serde(default)]
+    pub cursor: Option<String>,
+    /// 每页数量
+    #[serde(default = "default_num")]
+    #[param(default = 20, minimum = 1, maximum = 50)]
+    pub num: i32,
+}
+
+fn default_num() -> i32 { 20 }
+
+/// 关系列表中的用户
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
+#[schema(example = json!({
+    "user_id": "5c9a8b7d000000001203f4e5",
+    "nickname": "城市漫游指南",
+    "avatar": "https://sns-avatar-qc.xhscdn.com/avatar/...",
+    "desc": "周末探店 / 城市散步",
+    "red_id": "95270001",
+    "fstatus": "follows"
+}))]
+pub struct FollowUser {
+    /// 用户ID
+    #[serde(alias = "userid", alias = "id")]
+    pub user_id: String,
+    /// 昵称
+    #[serde(alias = "nick_name", default)]
+    pub nickname: Option<String>,
+    /// 头像
+    #[serde(alias = "images", alias = "image", default)]
+    pub avatar: Option<String>,
+    /// 简介
+    #[serde(default)]
+    pub desc: Option<String>,
+    /// 小红书号
+    #[serde(default)]
+    pub red_id: Option<String>,
+    /// 与当前用户的关系 (follows / fans / both / none)
+    #[serde(default)]
+    pub fstatus: Option<String>,
+}
+
+/// 关注/粉丝列表响应
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
+pub struct FollowListResponse {
+    #[serde(default)]
+    pub code: i32,
+    pub success: bool,
+    #[serde(default)]
+    pub msg: Option<String>,
+    #[serde(default)]
+    pub data: Option<FollowListData>,
+}
+
+/// 关注/粉丝列表数据
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(from = "RawFollowListData")]
+pub struct FollowListData {
+    /// 下一页游标
+    pub cursor: Option<String>,
+    pub has_more: bool,
+    /// 用户列表（仅包含解析成功的条目）
+    pub users: Vec<FollowUser>,
+    /// 解析失败的条目
+    pub parse_errors: Vec<ItemParseError>,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
+struct RawFollowListData {
+    #[serde(default)]
+    cursor: Option<String>,
+    #[serde(default)]
+    has_more: bool,
+    #[serde(alias = "followings", alias = "followers", default)]
+    users: Vec<serde_json::Value>,
+}
+
+impl From<RawFollowListData> for FollowListData {
+    fn from(raw: RawFollowListData) -> Self {
+        let (users, parse_errors) = parse_items(raw.users);
+        Self { cursor: raw.cursor, has_more: raw.has_more, users, parse_errors }
+    }
+}
+
+/// 获取关注或粉丝列表
+pub async fn get_follow_list(api: &XhsApiClient, kind: FollowKind, params: FollowListParams) -> Result<FollowListResponse> {
+    let user_id = super::resolve_user_id(api, params.user_id).await?;
+    // 参数已编码，直接使用完整 URL（get_with_query 会再编码一次）
+    let url = format!(
+        "https://edith.xiaohongshu.com{}?user_id={}&cursor={}&num={}",
+        kind.path(),
+        urlencoding::encode(&user_id),
+        urlencoding::encode(&params.cursor.unwrap_or_default()),
+        params.num,
+    );
+
+    let text = api.get_with_url(kind.path(), &url).await?;
+    let result = parse::from_str::<FollowListResponse>(&text)?;
+    Ok(result)
+}
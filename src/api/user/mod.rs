@@ -0,0 +1,66 @@
+pub mod boards;
+pub mod follows;
+
+use crate::api::XhsApiClient;
+use crate::auth::UserCredentials;
+use crate::models::user::{UserMeResponse, UserPostedParams, UserPostedResponse};
+use crate::models::parse;
+use crate::error::{Result, XhsError};
+
+/// 页面-我
+/// 
+/// 获取当前登录用户的个人信息
+/// 
+/// 使用 Python 端捕获的 user_me 签名发送请求
+pub async fn get_current_user(api: &XhsApiClient) -> Result<UserMeResponse> {
+    // 使用公共模块的 get 方法，自动处理签名和 headers
+    let text = api.get("user_me").await?;
+    
+    let result = parse::from_str::<UserMeResponse>(&text)?;
+    Ok(result)
+}
+
+/// 解析目标用户ID：未指定时使用当前登录用户
+pub(crate) async fn resolve_user_id(api: &XhsApiClient, user_id: Option<String>) -> Result<String> {
+    match user_id {
+        Some(user_id) if !user_id.is_empty() => Ok(user_id),
+        _ => {
+            let me = get_current_user(api).await?;
+            if me.data.guest {
+                return Err(XhsError::NotLoggedIn("guest session".to_string()));
+            }
+            Ok(me.data.user_id)
+        }
+    }
+}
+
+/// 使用指定凭据获取用户信息
+/// 
+/// 用于校验外部导入的 Cookie 是否为有效的登录态（不读取当前存储的凭据）
+pub async fn get_user_with_credentials(api: &XhsApiClient, credentials: &UserCredentials) -> Result<UserMeResponse> {
+    let text = api.get_with_credentials("user_me", credentials).await?;
+    
+    let result = parse::from_str::<UserMeResponse>(&text)?;
+    Ok(result)
+}
+
+/// 用户主页-笔记
+/// 
+/// 获取指定用户发布的笔记列表，使用 cursor 分页
+pub async fn get_user_posted(api: &XhsApiClient, user_id: &str, params: UserPostedParams) -> Result<UserPostedResponse> {
+    let path = "/api/sns/web/v1/user_posted";
+    // 参数已编码，直接使用完整 URL（get_with_query 会再编码一次）
+    let url = format!(
+        "https://edith.xiaohongshu.com{}?num={}&cursor={}&user_id={}&image_formats=jpg,webp,avif&xsec_token={}&xsec_source={}",
+        path,
+        params.num,
+        urlencoding::encode(&params.cursor.unwrap_or_default()),
+        urlencoding::encode(user_id),
+        urlencoding::encode(&params.xsec_token.unwrap_or_default()),
+        urlencoding::encode(&params.xsec_source),
+    );
+    
+    let text = api.get_with_url(path, &url).await?;
+    let result = parse::from_str::<UserPostedResponse>(&text)?;
+    Ok(result)
+}
@@ -0,0 +1,251 @@
+//! 录制 / 回放 (VCR)
+//!
+//! `XhsClient` 绑定 [`Cassette`] 后，`XhsApiClient` 发出的签名请求都经它转发：
+//! - 录制 (`record`)：照常请求小红书，并把响应（状态码 / 响应头 / 响应体）追加写入 cassette 文件
+//! - 回放 (`replay`)：不联网，按方法 + 域名 + 路径依次返回录制的响应（优先查询串与请求体完全相同的记录）；
+//!   纯算法签名以占位签名代替，不需要 Agent
+//!
+//! 配合 `XHS_STORAGE=memory` 与任意占位凭据，解析器与 handler 的集成测试无需真实账号即可确定性地运行。
+//!
+//! - 环境变量：`XHS_VCR_MODE=record|replay`，cassette 路径 `XHS_VCR_CASSETTE`（默认 `fixtures/vcr/cassette.json`）
+//! - 库调用：`XhsClient::builder().cassette(Arc::new(Cassette::replay("fixtures/vcr/search.json")?)).build()`
+//!
+//! 只保存请求的方法、URL 与请求体，不保存请求头（Cookie / 签名）与响应的 `set-cookie`；
+//! 响应体中可能含 xsec_token 等，提交 cassette 前请检查。
+//! 主页探针、访客会话、Creator 与媒体下载等不经 `XhsApiClient` 签名发送的请求不在录制范围内。
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use anyhow::{anyhow, Context};
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Result, XhsError};
+use crate::signature::Signature;
+
+/// 录制时不保存的响应头
+const SKIPPED_HEADERS: &[&str] = &["set-cookie"];
+
+/// 录制或回放
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VcrMode {
+    /// 请求小红书并保存响应
+    Record,
+    /// 只返回录制的响应
+    Replay,
+}
+
+impl VcrMode {
+    /// XHS_VCR_MODE: `off`（默认）/ `record` / `replay`
+    pub fn from_env() -> anyhow::Result<Option<Self>> {
+        match std::env::var("XHS_VCR_MODE").unwrap_or_default().trim().to_lowercase().as_str() {
+            "" | "off" => Ok(None),
+            "record" => Ok(Some(Self::Record)),
+            "replay" => Ok(Some(Self::Replay)),
+            other => Err(anyhow!("Invalid XHS_VCR_MODE '{}' (expected off, record or replay)", other)),
+        }
+    }
+}
+
+/// 一次录制的请求与响应
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+    pub method: String,
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    pub status: u16,
+    /// 响应头（按接收顺序）
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+    /// 响应体
+    pub response: String,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// cassette 文件内容
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CassetteFile {
+    interactions: Vec<Interaction>,
+}
+
+#[derive(Debug, Default)]
+struct CassetteState {
+    interactions: Vec<Interaction>,
+    /// 回放时已返回过的记录
+    played: Vec<bool>,
+}
+
+/// 一个 cassette 文件的录制 / 回放状态
+#[derive(Debug)]
+pub struct Cassette {
+    mode: VcrMode,
+    path: PathBuf,
+    state: Mutex<CassetteState>,
+    /// 录制时串行写文件，保证文件内容是最新的
+    write_lock: tokio::sync::Mutex<()>,
+}
+
+impl Cassette {
+    /// 录制到 `path`，已有的文件在第一次请求后被覆盖
+    pub fn record(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        tracing::info!("[VCR] Recording XHS responses to {}", path.display());
+        Self::with_state(VcrMode::Record, path, CassetteState::default())
+    }
+
+    /// 从 `path` 回放
+    pub fn replay(path: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        let path = path.into();
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read VCR cassette {}", path.display()))?;
+        let file: CassetteFile = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid VCR cassette {}", path.display()))?;
+        tracing::info!("[VCR] Replaying {} interaction(s) from {}", file.interactions.len(), path.display());
+        let played = vec![false; file.interactions.len()];
+        Ok(Self::with_state(VcrMode::Replay, path, CassetteState { interactions: file.interactions, played }))
+    }
+
+    /// 按 XHS_VCR_MODE / XHS_VCR_CASSETTE 创建；未启用时为 None
+    pub fn from_env() -> anyhow::Result<Option<Self>> {
+        let path = crate::config::vcr_cassette_path();
+        match VcrMode::from_env()? {
+            None => Ok(None),
+            Some(VcrMode::Record) => Ok(Some(Self::record(path))),
+            Some(VcrMode::Replay) => Self::replay(path).map(Some),
+        }
+    }
+
+    fn with_state(mode: VcrMode, path: PathBuf, state: CassetteState) -> Self {
+        Self { mode, path, state: Mutex::new(state), write_lock: tokio::sync::Mutex::new(()) }
+    }
+
+    pub fn mode(&self) -> VcrMode {
+        self.mode
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 是否处于回放（不联网、不需要签名）
+    pub fn is_replaying(&self) -> bool {
+        self.mode == VcrMode::Replay
+    }
+
+    /// 已录制 / 可回放的记录
+    pub fn interactions(&self) -> Vec<Interaction> {
+        self.lock().interactions.clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CassetteState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 录制：发出请求并保存响应；回放：返回匹配的录制响应
+    pub async fn execute(
+        &self,
+        client: &reqwest::Client,
+        request: reqwest::Request,
+    ) -> Result<(StatusCode, HeaderMap, String)> {
+        if self.is_replaying() {
+            return self.play(&request);
+        }
+
+        let method = request.method().to_string();
+        let url = request.url().to_string();
+        let body = request_body(&request);
+        let response = client.execute(request).await?;
+        let status = response.status();
+        let headers = response.headers().clone();
+        let text = response.text().await?;
+        self.append(Interaction {
+            method,
+            url,
+            body,
+            status: status.as_u16(),
+            headers: headers.iter()
+                .filter(|(name, _)| !SKIPPED_HEADERS.contains(&name.as_str()))
+                .map(|(name, value)| (name.to_string(), String::from_utf8_lossy(value.as_bytes()).into_owned()))
+                .collect(),
+            response: text.clone(),
+            recorded_at: Utc::now(),
+        }).await?;
+        Ok((status, headers, text))
+    }
+
+    /// 返回方法、域名与路径相同的第一条未回放记录，查询串与请求体完全相同的优先
+    fn play(&self, request: &reqwest::Request) -> Result<(StatusCode, HeaderMap, String)> {
+        let method = request.method().as_str();
+        let url = request.url();
+        let body = request_body(request);
+
+        let mut state = self.lock();
+        let candidates: Vec<usize> = (0..state.interactions.len())
+            .filter(|&i| !state.played[i])
+            .filter(|&i| {
+                let interaction = &state.interactions[i];
+                interaction.method == method
+                    && url::Url::parse(&interaction.url)
+                        .is_ok_and(|recorded| recorded.host_str() == url.host_str() && recorded.path() == url.path())
+            })
+            .collect();
+        let index = candidates.iter()
+            .copied()
+            .find(|&i| state.interactions[i].url == url.as_str() && state.interactions[i].body == body)
+            .or_else(|| candidates.first().copied())
+            .ok_or_else(|| XhsError::Other(anyhow!(
+                "No recorded interaction left for {} {} in {}", method, url, self.path.display()
+            )))?;
+        state.played[index] = true;
+
+        let interaction = &state.interactions[index];
+        tracing::debug!("[VCR] Replaying {} {} [{}]", method, url.path(), interaction.status);
+        let status = StatusCode::from_u16(interaction.status)
+            .map_err(|e| XhsError::Other(anyhow!("Invalid recorded status {}: {}", interaction.status, e)))?;
+        let mut headers = HeaderMap::new();
+        for (name, value) in &interaction.headers {
+            if let (Ok(name), Ok(value)) = (HeaderName::from_bytes(name.as_bytes()), HeaderValue::from_str(value)) {
+                headers.append(name, value);
+            }
+        }
+        Ok((status, headers, interaction.response.clone()))
+    }
+
+    /// 追加一条记录并重写 cassette 文件
+    async fn append(&self, interaction: Interaction) -> anyhow::Result<()> {
+        let _write = self.write_lock.lock().await;
+        let content = {
+            let mut state = self.lock();
+            state.interactions.push(interaction);
+            state.played.push(false);
+            serde_json::to_string_pretty(&CassetteFile { interactions: state.interactions.clone() })?
+        };
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(&self.path, content).await
+            .with_context(|| format!("Failed to write VCR cassette {}", self.path.display()))
+    }
+}
+
+/// 请求体（非 UTF-8 时按有损转换）
+fn request_body(request: &reqwest::Request) -> Option<String> {
+    request.body()
+        .and_then(|body| body.as_bytes())
+        .map(|bytes| String::from_utf8_lossy(bytes).into_owned())
+}
+
+/// 回放时代替纯算法签名的占位签名
+pub fn placeholder_signature() -> Signature {
+    Signature {
+        x_s: "vcr-replay".to_string(),
+        x_t: "0".to_string(),
+        x_s_common: "vcr-replay".to_string(),
+        x_b3_traceid: "0".repeat(16),
+        x_xray_traceid: "0".repeat(32),
+    }
+}
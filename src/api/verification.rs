@@ -0,0 +1,179 @@
+//! 验证码挑战 (Verification Challenge)
+//!
+//! 风控升级时 XHS 对请求返回 461 并在响应头 / 响应体中附带 `verifyType` 与 `verifyUuid`，
+//! 要求在浏览器中完成滑块 / 图形验证。继续发送请求只会让账号风险升级，因此
+//! `VerificationGate` 记录挑战后暂停该账号的全部请求（返回 `VerificationRequired`）：
+//!
+//! 1. 挑战链接见 `/api/auth/verification`，并推送 webhook（`X-Xhs-Event: verification_required`，
+//!    地址为 `XHS_WEBHOOK_URLS`）
+//! 2. 人工或 Playwright Agent 打开链接完成验证，再更新 Cookie（扫码登录 / `/api/auth/import-cookies`）
+//! 3. 闸门发现账号 Cookie 已变化后自动恢复；也可调用 `/api/auth/verification/resolve` 手动恢复
+//!
+//! 访客凭据不受影响（访客会话会在下次请求时重新激活）。
+
+use chrono::{DateTime, Utc};
+use reqwest::header::HeaderMap;
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+use utoipa::ToSchema;
+
+use crate::auth::UserCredentials;
+use crate::error::XhsError;
+
+/// 网页端验证页
+const CAPTCHA_PAGE: &str = "https://www.xiaohongshu.com/website-login/captcha";
+
+/// 待完成的验证码挑战
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct VerificationChallenge {
+    /// 被要求验证的账号
+    pub user_id: String,
+    /// 触发挑战的接口
+    pub endpoint: String,
+    /// 验证类型（如 124 滑块）
+    pub verify_type: String,
+    pub verify_uuid: String,
+    /// 在已登录该账号的浏览器中打开以完成验证
+    pub url: String,
+    #[schema(value_type = String)]
+    pub detected_at: DateTime<Utc>,
+    /// 挑战出现后被暂停的请求数
+    pub paused_requests: u64,
+}
+
+impl VerificationChallenge {
+    /// 从 461 响应中识别挑战（响应头 verifytype / verifyuuid，或响应体中的同名字段）
+    pub fn detect(headers: &HeaderMap, body: &str) -> Option<(String, String)> {
+        let header = |name: &str| {
+            headers.get(name).and_then(|v| v.to_str().ok()).filter(|v| !v.is_empty()).map(str::to_string)
+        };
+        if let (Some(verify_type), Some(verify_uuid)) = (header("verifytype"), header("verifyuuid")) {
+            return Some((verify_type, verify_uuid));
+        }
+
+        if !body.contains("verifyUuid") && !body.contains("verify_uuid") {
+            return None;
+        }
+        let value: serde_json::Value = serde_json::from_str(body).ok()?;
+        let field = |keys: &[&str]| {
+            let scopes = [Some(&value), value.get("data")];
+            scopes.into_iter().flatten().find_map(|scope| {
+                keys.iter().find_map(|key| match scope.get(*key)? {
+                    serde_json::Value::String(s) if !s.is_empty() => Some(s.clone()),
+                    serde_json::Value::Number(n) => Some(n.to_string()),
+                    _ => None,
+                })
+            })
+        };
+        Some((field(&["verifyType", "verify_type"])?, field(&["verifyUuid", "verify_uuid"])?))
+    }
+
+    /// 网页端验证页链接
+    pub fn challenge_url(verify_type: &str, verify_uuid: &str) -> String {
+        format!(
+            "{}?redirectPath={}&verifyUuid={}&verifyType={}&verifyBiz=461",
+            CAPTCHA_PAGE,
+            urlencoding::encode("https://www.xiaohongshu.com/explore"),
+            urlencoding::encode(verify_uuid),
+            urlencoding::encode(verify_type),
+        )
+    }
+}
+
+struct Pending {
+    challenge: VerificationChallenge,
+    /// 挑战出现时的 Cookie 摘要，变化即视为已完成验证
+    cookies: u64,
+}
+
+/// 验证码闸门：有待完成挑战的账号暂停全部请求
+#[derive(Default)]
+pub struct VerificationGate {
+    pending: Mutex<HashMap<String, Pending>>,
+}
+
+impl VerificationGate {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 请求前检查：该账号有待完成的挑战时拒绝；Cookie 已更新时解除挑战并放行
+    pub fn admit(&self, credentials: &UserCredentials) -> Result<(), XhsError> {
+        let mut pending = self.pending.lock().unwrap();
+        let Some(entry) = pending.get_mut(&credentials.user_id) else {
+            return Ok(());
+        };
+        if entry.cookies != cookie_digest(credentials) {
+            tracing::info!("[Verification] Cookies of {} updated, resuming requests", credentials.user_id);
+            pending.remove(&credentials.user_id);
+            return Ok(());
+        }
+        entry.challenge.paused_requests += 1;
+        Err(XhsError::VerificationRequired { url: entry.challenge.url.clone() })
+    }
+
+    /// 该账号是否因待完成的挑战暂停（不计入被拦截的请求数）
+    pub fn is_paused(&self, credentials: &UserCredentials) -> bool {
+        let pending = self.pending.lock().unwrap();
+        pending
+            .get(&credentials.user_id)
+            .is_some_and(|entry| entry.cookies == cookie_digest(credentials))
+    }
+
+    /// 记录挑战；新挑战时返回它，该账号已有挑战时只更新链接并返回 None
+    pub fn challenge(&self, credentials: &UserCredentials, endpoint: &str, verify_type: String, verify_uuid: String) -> Option<VerificationChallenge> {
+        let url = VerificationChallenge::challenge_url(&verify_type, &verify_uuid);
+        let mut pending = self.pending.lock().unwrap();
+        if let Some(entry) = pending.get_mut(&credentials.user_id) {
+            entry.challenge.url = url;
+            entry.challenge.verify_type = verify_type;
+            entry.challenge.verify_uuid = verify_uuid;
+            return None;
+        }
+        tracing::warn!("[Verification] {} requires verification ({}), pausing its requests: {}", credentials.user_id, endpoint, url);
+        let challenge = VerificationChallenge {
+            user_id: credentials.user_id.clone(),
+            endpoint: endpoint.to_string(),
+            verify_type,
+            verify_uuid,
+            url,
+            detected_at: Utc::now(),
+            paused_requests: 0,
+        };
+        pending.insert(credentials.user_id.clone(), Pending { challenge: challenge.clone(), cookies: cookie_digest(credentials) });
+        Some(challenge)
+    }
+
+    /// 待完成的挑战
+    pub fn pending(&self) -> Vec<VerificationChallenge> {
+        let mut challenges: Vec<_> = self.pending.lock().unwrap().values().map(|p| p.challenge.clone()).collect();
+        challenges.sort_by_key(|c| c.detected_at);
+        challenges
+    }
+
+    /// 手动解除挑战（`user_id` 为空时解除全部），返回解除的数量
+    pub fn resolve(&self, user_id: Option<&str>) -> usize {
+        let mut pending = self.pending.lock().unwrap();
+        let before = pending.len();
+        match user_id {
+            Some(user_id) => {
+                pending.remove(user_id);
+            }
+            None => pending.clear(),
+        }
+        let resolved = before - pending.len();
+        if resolved > 0 {
+            tracing::info!("[Verification] {} challenge(s) resolved manually", resolved);
+        }
+        resolved
+    }
+}
+
+fn cookie_digest(credentials: &UserCredentials) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    credentials.cookie_string().hash(&mut hasher);
+    hasher.finish()
+}
@@ -0,0 +1,120 @@
+//! JSON file watch list backend
+//!
+//! All watched users live in one JSON array, rewritten atomically (temp file +
+//! rename) on every change. Suitable for a single instance; use the Mongo
+//! backend when several instances share the watch list.
+use anyhow::Result;
+use std::path::PathBuf;
+
+use super::{merge_recent, WatchRun, WatchedUser};
+use crate::archive::events::NoteEventKind;
+use crate::utils::storage::JsonFileStore;
+
+pub struct FileBackend {
+    store: JsonFileStore<WatchedUser>,
+}
+
+impl FileBackend {
+    pub fn new(path: PathBuf) -> Self {
+        Self { store: JsonFileStore::new(path) }
+    }
+
+    /// Check that the file is readable and its directory writable
+    pub async fn ping(&self) -> Result<()> {
+        self.store.ping().await
+    }
+
+    pub async fn insert(&self, user: &WatchedUser) -> Result<()> {
+        self.store.modify(|users| {
+            users.push(user.clone());
+            (true, ())
+        }).await
+    }
+
+    pub async fn list(&self) -> Result<Vec<WatchedUser>> {
+        let mut users = self.store.load().await?;
+        users.sort_by_key(|u| u.created_at);
+        Ok(users)
+    }
+
+    pub async fn get(&self, user_id: &str) -> Result<Option<WatchedUser>> {
+        Ok(self.store.load().await?.into_iter().find(|u| u.user_id == user_id))
+    }
+
+    /// Replace the stored user's settings; poll state is left to the scheduler
+    pub async fn update(&self, user: &WatchedUser) -> Result<Option<WatchedUser>> {
+        self.store.modify(|users| match users.iter_mut().find(|u| u.user_id == user.user_id) {
+            Some(stored) => {
+                stored.xsec_token = user.xsec_token.clone();
+                stored.pages = user.pages;
+                stored.webhook_urls = user.webhook_urls.clone();
+                stored.interval_secs = user.interval_secs;
+                stored.enabled = user.enabled;
+                stored.next_run_at = user.next_run_at;
+                stored.updated_at = user.updated_at;
+                (true, Some(stored.clone()))
+            }
+            None => (false, None),
+        }).await
+    }
+
+    pub async fn delete(&self, user_id: &str) -> Result<bool> {
+        self.store.modify(|users| {
+            let before = users.len();
+            users.retain(|u| u.user_id != user_id);
+            let removed = users.len() < before;
+            (removed, removed)
+        }).await
+    }
+
+    pub async fn claim_due(&self, now: i64, stale_before: i64) -> Result<Vec<WatchedUser>> {
+        self.store.modify(|users| {
+            let mut due = Vec::new();
+            for user in users.iter_mut() {
+                if user.enabled && user.next_run_at <= now && (!user.running || user.updated_at < stale_before) {
+                    user.running = true;
+                    user.updated_at = now;
+                    due.push(user.clone());
+                }
+            }
+            due.sort_by_key(|u| u.next_run_at);
+            (!due.is_empty(), due)
+        }).await
+    }
+
+    pub async fn finish(
+        &self,
+        user: &WatchedUser,
+        result: &std::result::Result<WatchRun, String>,
+        now: i64,
+        next_run_at: i64,
+    ) -> Result<()> {
+        self.store.modify(|users| match users.iter_mut().find(|u| u.user_id == user.user_id) {
+            Some(stored) => {
+                match result {
+                    Ok(run) => {
+                        stored.baselined = true;
+                        stored.nickname = run.nickname.clone();
+                        stored.last_events = Some(run.events.len());
+                        stored.last_error = None;
+                        stored.total_published += run.count(NoteEventKind::Published);
+                        stored.total_deleted += run.count(NoteEventKind::Deleted);
+                        stored.recent_events = merge_recent(&run.events, &stored.recent_events);
+                        stored.known_notes = run.known_notes.clone();
+                    }
+                    Err(e) => {
+                        stored.last_error = Some(e.clone());
+                        stored.failure_count += 1;
+                    }
+                }
+                stored.running = false;
+                stored.run_count += 1;
+                stored.last_run_at = Some(now);
+                stored.next_run_at = next_run_at;
+                stored.updated_at = now;
+                (true, ())
+            }
+            None => (false, ()),
+        }).await
+    }
+}
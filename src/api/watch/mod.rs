@@ -0,0 +1,597 @@
+//! User Watch List
+//!
+//! Users registered through `/api/watch/users` have their posted-notes list
+//! polled periodically. Each poll is compared with the previous snapshot to
+//! detect newly published notes and notes that disappeared from the profile
+//! (deleted or made private). Events are kept on the watched user
+//! (`recent_events`), appended to the archive's note events when
+//! `XHS_ARCHIVE_BACKEND` is set, and POSTed (`X-Xhs-Event: user_watch`) to
+//! the user's `webhook_urls` or, when it has none, to `XHS_WEBHOOK_URLS`.
+//!
+//! Only the first `pages` pages of the profile are fetched, so a note that
+//! slides out of that window because newer ones were published is not a
+//! deletion; only known notes newer than the oldest note still visible are
+//! checked. The first poll only records a baseline.
+//!
+//! Watched users are persisted so they survive restarts. Configuration (env):
+//! - `XHS_WATCH_BACKEND`: `file` (default) / `mongo`
+//! - `XHS_WATCH_FILE`: file backend path, default ./watch_users.json
+//! - `XHS_WATCH_MONGO_URI`: Mongo backend URI, defaults to XHS_COORD_MONGO_URI
+//! - `XHS_WATCH_DB`: Mongo database, default xhs_watch
+pub mod file;
+#[cfg(feature = "mongodb")]
+pub mod mongo;
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use utoipa::ToSchema;
+
+use crate::archive::events::{NoteEvent, NoteEventKind};
+use crate::models::user::PostedNote;
+use file::FileBackend;
+#[cfg(feature = "mongodb")]
+use mongo::MongoBackend;
+
+/// Running watches older than this are considered interrupted
+pub const STALE_RUNNING: std::time::Duration = std::time::Duration::from_secs(3600);
+
+/// Shortest allowed interval between two polls of a user (seconds)
+pub const MIN_INTERVAL_SECS: u64 = 60;
+
+/// Most profile pages (30 notes each) fetched per poll
+pub const MAX_PAGES: u32 = 5;
+
+/// Events kept in `recent_events`
+pub const RECENT_EVENTS: usize = 50;
+
+// ============================================================================
+// Models
+// ============================================================================
+
+/// A note seen on the profile by the last poll
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct KnownNote {
+    pub note_id: String,
+    #[serde(default)]
+    pub xsec_token: String,
+    #[serde(default)]
+    pub note_type: String,
+    #[serde(default)]
+    pub title: String,
+}
+
+impl KnownNote {
+    pub fn from_posted(note: &PostedNote) -> Self {
+        Self {
+            note_id: note.note_id.clone(),
+            xsec_token: note.xsec_token.clone().unwrap_or_default(),
+            note_type: note.note_type.clone().unwrap_or_default(),
+            title: note.display_title.clone().unwrap_or_default(),
+        }
+    }
+
+    /// Event about this note, attributed to `user`
+    pub fn event(&self, kind: NoteEventKind, user: &WatchedUser, nickname: &str, detected_at: i64) -> NoteEvent {
+        NoteEvent {
+            id: uuid::Uuid::new_v4().simple().to_string(),
+            kind,
+            user_id: user.user_id.clone(),
+            nickname: nickname.to_string(),
+            note_id: self.note_id.clone(),
+            xsec_token: self.xsec_token.clone(),
+            note_type: self.note_type.clone(),
+            title: self.title.clone(),
+            detected_at,
+        }
+    }
+}
+
+/// A watched user
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct WatchedUser {
+    pub user_id: String,
+    /// Nickname seen on the last poll
+    #[serde(default)]
+    pub nickname: String,
+    /// Profile xsec_token passed to user_posted, if any
+    #[serde(default)]
+    pub xsec_token: Option<String>,
+    /// Profile pages fetched per poll
+    pub pages: u32,
+    /// Alert targets; empty = XHS_WEBHOOK_URLS
+    pub webhook_urls: Vec<String>,
+    /// Seconds between polls
+    pub interval_secs: u64,
+    /// Disabled users stay registered but are not polled
+    pub enabled: bool,
+    /// Next poll time (ms)
+    pub next_run_at: i64,
+    /// Currently being polled
+    #[serde(default)]
+    pub running: bool,
+    /// Whether the baseline poll has happened
+    #[serde(default)]
+    pub baselined: bool,
+    /// Last poll time (ms)
+    #[serde(default)]
+    pub last_run_at: Option<i64>,
+    /// Events found by the last successful poll
+    #[serde(default)]
+    pub last_events: Option<usize>,
+    /// Error of the last poll, cleared by the next successful one
+    #[serde(default)]
+    pub last_error: Option<String>,
+    #[serde(default)]
+    pub run_count: u64,
+    #[serde(default)]
+    pub failure_count: u64,
+    #[serde(default)]
+    pub total_published: u64,
+    #[serde(default)]
+    pub total_deleted: u64,
+    /// Latest events, newest first
+    #[serde(default)]
+    pub recent_events: Vec<NoteEvent>,
+    /// Notes visible on the last poll, in profile order.
+    /// Stored with the user but left out of API responses.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[schema(ignore)]
+    pub known_notes: Vec<KnownNote>,
+    /// Registration time (ms)
+    pub created_at: i64,
+    /// Last change (ms)
+    pub updated_at: i64,
+}
+
+impl WatchedUser {
+    /// Drop the profile snapshot for API responses
+    pub fn without_known(mut self) -> Self {
+        self.known_notes = Vec::new();
+        self
+    }
+}
+
+/// Request body for adding / replacing a watched user
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "user_id": "5ff0e6410000000001008400",
+    "webhook_urls": ["https://example.com/hooks/xhs"],
+    "interval_secs": 900
+}))]
+pub struct WatchUserRequest {
+    pub user_id: String,
+    /// Profile xsec_token (from a profile link or a note's author), optional
+    #[serde(default)]
+    pub xsec_token: Option<String>,
+    /// Profile pages fetched per poll, default 1, at most 5
+    #[serde(default = "default_pages")]
+    pub pages: u32,
+    /// Alert targets; empty = XHS_WEBHOOK_URLS (no webhook when neither is set)
+    #[serde(default)]
+    pub webhook_urls: Vec<String>,
+    /// Seconds between polls, at least 60
+    pub interval_secs: u64,
+    /// Default true
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_pages() -> u32 { 1 }
+fn default_enabled() -> bool { true }
+
+impl WatchUserRequest {
+    fn validate(&self) -> Result<()> {
+        if self.user_id.is_empty() || !self.user_id.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(anyhow!("Invalid user_id: {}", self.user_id));
+        }
+        if self.interval_secs < MIN_INTERVAL_SECS {
+            return Err(anyhow!("interval_secs must be at least {}", MIN_INTERVAL_SECS));
+        }
+        if !(1..=MAX_PAGES).contains(&self.pages) {
+            return Err(anyhow!("pages must be between 1 and {}", MAX_PAGES));
+        }
+        if let Some(url) = self.webhook_urls.iter().find(|u| !u.starts_with("http://") && !u.starts_with("https://")) {
+            return Err(anyhow!("Invalid webhook URL: {}", url));
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of a successful poll, applied to the stored user by `finish`
+#[derive(Debug, Clone)]
+pub struct WatchRun {
+    pub nickname: String,
+    /// New events, newest first (empty for the baseline poll)
+    pub events: Vec<NoteEvent>,
+    /// Profile snapshot for the next comparison
+    pub known_notes: Vec<KnownNote>,
+}
+
+impl WatchRun {
+    fn count(&self, kind: NoteEventKind) -> u64 {
+        self.events.iter().filter(|e| e.kind == kind).count() as u64
+    }
+}
+
+/// webhook body for `X-Xhs-Event: user_watch`
+#[derive(Debug, Serialize)]
+pub struct WatchAlert<'a> {
+    pub user_id: &'a str,
+    pub nickname: &'a str,
+    pub detected_at: chrono::DateTime<Utc>,
+    pub events: &'a [NoteEvent],
+}
+
+/// Compare a fresh profile listing with the previous snapshot.
+///
+/// `complete` is true when the listing reached the end of the profile.
+/// Returns (published, deleted) notes.
+pub fn diff_notes(known: &[KnownNote], current: &[KnownNote], complete: bool) -> (Vec<KnownNote>, Vec<KnownNote>) {
+    let known_ids: HashSet<&str> = known.iter().map(|n| n.note_id.as_str()).collect();
+    let current_ids: HashSet<&str> = current.iter().map(|n| n.note_id.as_str()).collect();
+
+    // Unknown notes after the last known one were pushed into the window by
+    // deletions above them, not published
+    let last_known = current.iter().rposition(|n| known_ids.contains(n.note_id.as_str()));
+    let in_window = |i: usize| complete || known.is_empty() || last_known.is_some_and(|last| i < last);
+    let published = current.iter()
+        .enumerate()
+        .filter(|(i, n)| !known_ids.contains(n.note_id.as_str()) && in_window(*i))
+        .map(|(_, n)| n.clone())
+        .collect();
+
+    // Known notes below the oldest still-visible one may just have slid out
+    // of the window
+    let boundary = if complete {
+        Some(known.len())
+    } else {
+        known.iter().rposition(|n| current_ids.contains(n.note_id.as_str())).map(|i| i + 1)
+    };
+    let deleted = match boundary {
+        Some(boundary) => known[..boundary].iter()
+            .filter(|n| !current_ids.contains(n.note_id.as_str()))
+            .cloned()
+            .collect(),
+        None => Vec::new(),
+    };
+    (published, deleted)
+}
+
+// ============================================================================
+// Storage
+// ============================================================================
+
+/// Watch list storage configuration
+#[derive(Debug, Clone)]
+pub enum WatchListConfig {
+    File { path: PathBuf },
+    #[cfg(feature = "mongodb")]
+    Mongo { uri: String, database: String },
+}
+
+impl WatchListConfig {
+    pub fn from_env() -> Result<Self> {
+        let backend = std::env::var("XHS_WATCH_BACKEND").unwrap_or_else(|_| "file".to_string());
+        match backend.as_str() {
+            "" | "file" => Ok(Self::File {
+                path: std::env::var("XHS_WATCH_FILE")
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|_| PathBuf::from("./watch_users.json")),
+            }),
+            #[cfg(feature = "mongodb")]
+            "mongo" => {
+                let uri = std::env::var("XHS_WATCH_MONGO_URI")
+                    .or_else(|_| std::env::var("XHS_COORD_MONGO_URI"))
+                    .map_err(|_| anyhow!("XHS_WATCH_BACKEND=mongo requires XHS_WATCH_MONGO_URI"))?;
+                let database = std::env::var("XHS_WATCH_DB").unwrap_or_else(|_| "xhs_watch".to_string());
+                Ok(Self::Mongo { uri, database })
+            }
+            #[cfg(not(feature = "mongodb"))]
+            "mongo" => Err(anyhow!("XHS_WATCH_BACKEND=mongo requires the `mongodb` cargo feature")),
+            other => Err(anyhow!("Unknown XHS_WATCH_BACKEND: {}", other)),
+        }
+    }
+}
+
+enum WatchBackend {
+    File(FileBackend),
+    #[cfg(feature = "mongodb")]
+    Mongo(MongoBackend),
+}
+
+/// Persistent list of watched users
+pub struct WatchList {
+    backend: WatchBackend,
+    description: String,
+}
+
+impl WatchList {
+    pub async fn open(config: &WatchListConfig) -> Result<Self> {
+        let (backend, description) = match config {
+            WatchListConfig::File { path } => {
+                (WatchBackend::File(FileBackend::new(path.clone())), format!("file: {}", path.display()))
+            }
+            #[cfg(feature = "mongodb")]
+            WatchListConfig::Mongo { uri, database } => {
+                (WatchBackend::Mongo(MongoBackend::connect(uri, database).await?), format!("mongo: {}", database))
+            }
+        };
+        tracing::info!("[Watch] Watch list stored in {}", description);
+        Ok(Self { backend, description })
+    }
+
+    pub async fn from_env() -> Result<Self> {
+        Self::open(&WatchListConfig::from_env()?).await
+    }
+
+    /// Backend description, e.g. "file: ./watch_users.json"
+    pub fn describe(&self) -> &str {
+        &self.description
+    }
+
+    /// Check that the backend is reachable (readiness probe)
+    pub async fn ping(&self) -> Result<()> {
+        match &self.backend {
+            WatchBackend::File(b) => b.ping().await,
+            #[cfg(feature = "mongodb")]
+            WatchBackend::Mongo(b) => b.ping().await,
+        }
+    }
+
+    /// Start watching a user; the baseline poll is due immediately
+    pub async fn add(&self, req: WatchUserRequest) -> Result<WatchedUser> {
+        req.validate()?;
+        if self.get(&req.user_id).await?.is_some() {
+            return Err(anyhow!("User {} is already watched", req.user_id));
+        }
+        let now = Utc::now().timestamp_millis();
+        let user = WatchedUser {
+            user_id: req.user_id,
+            nickname: String::new(),
+            xsec_token: req.xsec_token.filter(|t| !t.is_empty()),
+            pages: req.pages,
+            webhook_urls: req.webhook_urls,
+            interval_secs: req.interval_secs,
+            enabled: req.enabled,
+            next_run_at: now,
+            running: false,
+            baselined: false,
+            last_run_at: None,
+            last_events: None,
+            last_error: None,
+            run_count: 0,
+            failure_count: 0,
+            total_published: 0,
+            total_deleted: 0,
+            recent_events: Vec::new(),
+            known_notes: Vec::new(),
+            created_at: now,
+            updated_at: now,
+        };
+        match &self.backend {
+            WatchBackend::File(b) => b.insert(&user).await?,
+            #[cfg(feature = "mongodb")]
+            WatchBackend::Mongo(b) => b.insert(&user).await?,
+        }
+        tracing::info!("[Watch] Watching {} every {}s", user.user_id, user.interval_secs);
+        Ok(user)
+    }
+
+    /// Watched users ordered by registration time
+    pub async fn list(&self) -> Result<Vec<WatchedUser>> {
+        match &self.backend {
+            WatchBackend::File(b) => b.list().await,
+            #[cfg(feature = "mongodb")]
+            WatchBackend::Mongo(b) => b.list().await,
+        }
+    }
+
+    pub async fn get(&self, user_id: &str) -> Result<Option<WatchedUser>> {
+        match &self.backend {
+            WatchBackend::File(b) => b.get(user_id).await,
+            #[cfg(feature = "mongodb")]
+            WatchBackend::Mongo(b) => b.get(user_id).await,
+        }
+    }
+
+    /// Replace a watched user's settings, keeping the snapshot and history.
+    /// Re-enabling polls immediately.
+    pub async fn update(&self, user_id: &str, req: WatchUserRequest) -> Result<WatchedUser> {
+        req.validate()?;
+        if req.user_id != user_id {
+            return Err(anyhow!("user_id in the body does not match the path"));
+        }
+        let existing = self.get(user_id).await?.ok_or_else(|| anyhow!("User {} is not watched", user_id))?;
+        let now = Utc::now().timestamp_millis();
+        let next_run_at = if req.enabled && !existing.enabled { now } else { existing.next_run_at };
+        let user = WatchedUser {
+            xsec_token: req.xsec_token.filter(|t| !t.is_empty()),
+            pages: req.pages,
+            webhook_urls: req.webhook_urls,
+            interval_secs: req.interval_secs,
+            enabled: req.enabled,
+            next_run_at,
+            updated_at: now,
+            ..existing
+        };
+        let updated = match &self.backend {
+            WatchBackend::File(b) => b.update(&user).await?,
+            #[cfg(feature = "mongodb")]
+            WatchBackend::Mongo(b) => b.update(&user).await?,
+        };
+        updated.ok_or_else(|| anyhow!("User {} is not watched", user_id))
+    }
+
+    /// Stop watching a user, returning whether it was watched
+    pub async fn remove(&self, user_id: &str) -> Result<bool> {
+        match &self.backend {
+            WatchBackend::File(b) => b.delete(user_id).await,
+            #[cfg(feature = "mongodb")]
+            WatchBackend::Mongo(b) => b.delete(user_id).await,
+        }
+    }
+
+    /// Atomically mark due users as running and return them
+    pub async fn claim_due(&self) -> Result<Vec<WatchedUser>> {
+        let now = Utc::now().timestamp_millis();
+        let stale_before = now - STALE_RUNNING.as_millis() as i64;
+        match &self.backend {
+            WatchBackend::File(b) => b.claim_due(now, stale_before).await,
+            #[cfg(feature = "mongodb")]
+            WatchBackend::Mongo(b) => b.claim_due(now, stale_before).await,
+        }
+    }
+
+    /// Record the outcome of a poll and schedule the next one
+    pub async fn finish(&self, user: &WatchedUser, result: std::result::Result<WatchRun, String>) -> Result<()> {
+        let now = Utc::now().timestamp_millis();
+        let next_run_at = now + user.interval_secs as i64 * 1000;
+        match &self.backend {
+            WatchBackend::File(b) => b.finish(user, &result, now, next_run_at).await,
+            #[cfg(feature = "mongodb")]
+            WatchBackend::Mongo(b) => b.finish(user, &result, now, next_run_at).await,
+        }
+    }
+}
+
+/// Newest-first events merged into the stored list, capped at `RECENT_EVENTS`
+fn merge_recent(events: &[NoteEvent], recent: &[NoteEvent]) -> Vec<NoteEvent> {
+    events.iter().chain(recent).take(RECENT_EVENTS).cloned().collect()
+}
+
+// ============================================================================
+// Scheduler
+// ============================================================================
+
+#[cfg(feature = "server")]
+use crate::server::AppState;
+#[cfg(feature = "server")]
+use std::sync::Arc;
+
+/// Background scheduler: claims due users every `XHS_WATCH_TICK_SECS` and
+/// polls them one at a time. Skips ticks while upstream maintenance is in
+/// effect or the primary account is outside the active hours of its activity
+/// profile.
+#[cfg(feature = "server")]
+pub fn spawn_scheduler(state: Arc<AppState>) {
+    let interval = crate::config::watch_tick_interval();
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(interval).await;
+            if state.api.maintenance().is_paused() || !state.api.is_active_now().await {
+                continue;
+            }
+            let due = match state.watch_list.claim_due().await {
+                Ok(due) => due,
+                Err(e) => {
+                    tracing::error!("[Watch] Failed to claim due users: {}", e);
+                    continue;
+                }
+            };
+            for user in due {
+                let result = poll(&state, &user).await.map_err(|e| e.to_string());
+                if let Err(e) = &result {
+                    tracing::warn!("[Watch] Polling {} failed: {}", user.user_id, e);
+                }
+                if let Err(e) = state.watch_list.finish(&user, result).await {
+                    tracing::error!("[Watch] Failed to record outcome of {}: {}", user.user_id, e);
+                }
+            }
+        }
+    });
+}
+
+/// Fetch the user's profile listing, diff it against the last snapshot and
+/// report the events
+#[cfg(feature = "server")]
+async fn poll(state: &AppState, user: &WatchedUser) -> Result<WatchRun> {
+    use crate::models::user::UserPostedParams;
+
+    let mut current: Vec<KnownNote> = Vec::new();
+    let mut nickname = user.nickname.clone();
+    let mut cursor: Option<String> = None;
+    let mut complete = false;
+    for _ in 0..user.pages {
+        let params = UserPostedParams {
+            cursor: cursor.clone(),
+            xsec_token: user.xsec_token.clone(),
+            ..UserPostedParams::default()
+        };
+        let result = crate::api::user::get_user_posted(&state.api, &user.user_id, params).await?;
+        if !result.success {
+            return Err(anyhow!("user_posted failed: {}", result.msg.unwrap_or_default()));
+        }
+        let Some(data) = result.data else {
+            complete = true;
+            break;
+        };
+        if let Some(name) = data.notes.iter().find_map(|n| n.user.as_ref().and_then(|u| u.nickname.clone())) {
+            nickname = name;
+        }
+        for note in &data.notes {
+            if !current.iter().any(|n| n.note_id == note.note_id) {
+                current.push(KnownNote::from_posted(note));
+            }
+        }
+        if !data.has_more || data.cursor.as_deref().unwrap_or_default().is_empty() {
+            complete = true;
+            break;
+        }
+        cursor = data.cursor;
+    }
+
+    if !user.baselined {
+        tracing::info!("[Watch] Baseline for {} ({}): {} note(s)", user.user_id, nickname, current.len());
+        return Ok(WatchRun { nickname, events: Vec::new(), known_notes: current });
+    }
+    // An empty listing for a user who had notes is more likely a blocked or
+    // failed request than a mass deletion
+    if current.is_empty() && !user.known_notes.is_empty() {
+        return Err(anyhow!("Empty note list for {} (previously {} notes), skipped", user.user_id, user.known_notes.len()));
+    }
+
+    let now = Utc::now().timestamp_millis();
+    let (published, deleted) = diff_notes(&user.known_notes, &current, complete);
+    let events: Vec<NoteEvent> = published.iter()
+        .map(|n| n.event(NoteEventKind::Published, user, &nickname, now))
+        .chain(deleted.iter().map(|n| n.event(NoteEventKind::Deleted, user, &nickname, now)))
+        .collect();
+    for event in &events {
+        tracing::info!("[Watch] {} ({}) {:?} note {} \"{}\"", user.user_id, nickname, event.kind, event.note_id, event.title);
+    }
+    if !events.is_empty() {
+        if let Some(archive) = state.api.archive() {
+            if let Err(e) = archive.store_events(&events).await {
+                tracing::warn!("[Watch] Failed to archive events of {}: {}", user.user_id, e);
+            }
+        }
+        alert(user, &nickname, &events).await;
+    }
+    Ok(WatchRun { nickname, events, known_notes: current })
+}
+
+/// Deliver the events to the user's webhooks (or XHS_WEBHOOK_URLS)
+#[cfg(feature = "server")]
+async fn alert(user: &WatchedUser, nickname: &str, events: &[NoteEvent]) {
+    use crate::api::notification::webhook::WebhookNotifier;
+
+    let notifier = if user.webhook_urls.is_empty() {
+        WebhookNotifier::from_env()
+    } else {
+        WebhookNotifier::new(user.webhook_urls.clone(), crate::config::webhook_secret()).map(Some)
+    };
+    let notifier = match notifier {
+        Ok(Some(notifier)) => notifier,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::error!("[Watch] Failed to build webhook client: {}", e);
+            return;
+        }
+    };
+    let payload = WatchAlert { user_id: &user.user_id, nickname, detected_at: Utc::now(), events };
+    let delivered = notifier.deliver("user_watch", &payload).await;
+    tracing::info!("[Watch] {} event(s) of {} delivered to {} webhook(s)", events.len(), user.user_id, delivered);
+}
@@ -0,0 +1,147 @@
+//! MongoDB watch list backend
+//!
+//! One document per watched user in `watched_users`; due users are claimed
+//! with `findOneAndUpdate` so several instances can share the watch list
+//! without polling a user twice.
+use anyhow::{anyhow, Result};
+use mongodb::bson::{doc, to_bson, Bson, Document};
+use mongodb::options::{IndexOptions, ReturnDocument};
+use mongodb::{Collection, IndexModel};
+
+use super::{merge_recent, WatchRun, WatchedUser};
+use crate::archive::events::NoteEventKind;
+
+pub struct MongoBackend {
+    users: Collection<WatchedUser>,
+}
+
+impl MongoBackend {
+    pub async fn connect(uri: &str, database: &str) -> Result<Self> {
+        let client = crate::utils::storage::mongo_client(uri)
+            .await
+            .map_err(|e| anyhow!("Failed to connect to watch list MongoDB: {}", e))?;
+        let users: Collection<WatchedUser> = client.database(database).collection("watched_users");
+        users
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "user_id": 1 })
+                    .options(IndexOptions::builder().name("user_id".to_string()).unique(true).build())
+                    .build(),
+            )
+            .await?;
+        users
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "enabled": 1, "next_run_at": 1 })
+                    .options(IndexOptions::builder().name("enabled_next_run_at".to_string()).build())
+                    .build(),
+            )
+            .await?;
+        Ok(Self { users })
+    }
+
+    /// Check that the deployment is reachable
+    pub async fn ping(&self) -> Result<()> {
+        crate::utils::storage::ping_mongo(&self.users).await
+    }
+
+    pub async fn insert(&self, user: &WatchedUser) -> Result<()> {
+        self.users.insert_one(user).await?;
+        Ok(())
+    }
+
+    pub async fn list(&self) -> Result<Vec<WatchedUser>> {
+        let mut cursor = self.users.find(doc! {}).sort(doc! { "created_at": 1 }).await?;
+        let mut users = Vec::new();
+        while cursor.advance().await? {
+            users.push(cursor.deserialize_current()?);
+        }
+        Ok(users)
+    }
+
+    pub async fn get(&self, user_id: &str) -> Result<Option<WatchedUser>> {
+        Ok(self.users.find_one(doc! { "user_id": user_id }).await?)
+    }
+
+    pub async fn update(&self, user: &WatchedUser) -> Result<Option<WatchedUser>> {
+        Ok(self.users
+            .find_one_and_update(
+                doc! { "user_id": &user.user_id },
+                doc! { "$set": {
+                    "xsec_token": &user.xsec_token,
+                    "pages": user.pages as i64,
+                    "webhook_urls": &user.webhook_urls,
+                    "interval_secs": user.interval_secs as i64,
+                    "enabled": user.enabled,
+                    "next_run_at": user.next_run_at,
+                    "updated_at": user.updated_at,
+                } },
+            )
+            .return_document(ReturnDocument::After)
+            .await?)
+    }
+
+    pub async fn delete(&self, user_id: &str) -> Result<bool> {
+        let result = self.users.delete_one(doc! { "user_id": user_id }).await?;
+        Ok(result.deleted_count > 0)
+    }
+
+    pub async fn claim_due(&self, now: i64, stale_before: i64) -> Result<Vec<WatchedUser>> {
+        let mut due = Vec::new();
+        while let Some(user) = self.users
+            .find_one_and_update(
+                doc! {
+                    "enabled": true,
+                    "next_run_at": { "$lte": now },
+                    "$or": [
+                        { "running": false },
+                        { "updated_at": { "$lt": stale_before } },
+                    ],
+                },
+                doc! { "$set": { "running": true, "updated_at": now } },
+            )
+            .sort(doc! { "next_run_at": 1 })
+            .return_document(ReturnDocument::After)
+            .await?
+        {
+            due.push(user);
+        }
+        Ok(due)
+    }
+
+    /// `user` is the claimed copy; the poll owns the user's snapshot and
+    /// event list until it finishes, so they are written as a whole
+    pub async fn finish(
+        &self,
+        user: &WatchedUser,
+        result: &std::result::Result<WatchRun, String>,
+        now: i64,
+        next_run_at: i64,
+    ) -> Result<()> {
+        let mut set: Document = doc! {
+            "running": false,
+            "last_run_at": now,
+            "next_run_at": next_run_at,
+            "updated_at": now,
+        };
+        let mut inc: Document = doc! { "run_count": 1_i64 };
+        match result {
+            Ok(run) => {
+                set.insert("baselined", true);
+                set.insert("nickname", &run.nickname);
+                set.insert("last_events", run.events.len() as i64);
+                set.insert("last_error", Bson::Null);
+                set.insert("recent_events", to_bson(&merge_recent(&run.events, &user.recent_events))?);
+                set.insert("known_notes", to_bson(&run.known_notes)?);
+                inc.insert("total_published", run.count(NoteEventKind::Published) as i64);
+                inc.insert("total_deleted", run.count(NoteEventKind::Deleted) as i64);
+            }
+            Err(e) => {
+                set.insert("last_error", e);
+                inc.insert("failure_count", 1_i64);
+            }
+        }
+        self.users.update_one(doc! { "user_id": &user.user_id }, doc! { "$set": set, "$inc": inc }).await?;
+        Ok(())
+    }
+}
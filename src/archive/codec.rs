@@ -0,0 +1,84 @@
+//! zstd 压缩编解码（支持字典）
+//!
+//! XHS 的 JSON 响应字段名、URL 前缀高度重复，用历史响应训练的字典压缩
+//! 单条响应，比无字典压缩再小数倍。每个 zstd 帧头都记录了字典 ID，
+//! 因此切换新字典后，旧数据仍可用对应的旧字典解压。
+
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::RwLock;
+
+#[derive(Default)]
+struct DictionarySet {
+    /// 新数据使用的字典
+    active: Option<u32>,
+    by_id: HashMap<u32, Vec<u8>>,
+}
+
+/// 归档压缩编解码器
+pub struct ArchiveCodec {
+    level: i32,
+    dictionaries: RwLock<DictionarySet>,
+}
+
+impl ArchiveCodec {
+    pub fn new(level: i32) -> Self {
+        Self { level, dictionaries: RwLock::new(DictionarySet::default()) }
+    }
+
+    /// 注册字典，`activate` 为 true 时后续压缩使用该字典
+    ///
+    /// 返回字典 ID
+    pub fn add_dictionary(&self, dictionary: Vec<u8>, activate: bool) -> Result<u32> {
+        let id = zstd::zstd_safe::get_dict_id_from_dict(&dictionary)
+            .ok_or_else(|| anyhow!("Not a zstd dictionary"))?
+            .get();
+        let mut set = self.dictionaries.write().unwrap();
+        set.by_id.insert(id, dictionary);
+        if activate {
+            set.active = Some(id);
+        }
+        Ok(id)
+    }
+
+    /// 当前使用的字典 ID
+    pub fn active_dictionary(&self) -> Option<u32> {
+        self.dictionaries.read().unwrap().active
+    }
+
+    /// 压缩，返回 (压缩数据, 使用的字典 ID)
+    pub fn compress(&self, data: &[u8]) -> Result<(Vec<u8>, Option<u32>)> {
+        let set = self.dictionaries.read().unwrap();
+        match set.active.and_then(|id| set.by_id.get(&id).map(|dict| (id, dict))) {
+            Some((id, dict)) => {
+                let mut compressor = zstd::bulk::Compressor::with_dictionary(self.level, dict)?;
+                Ok((compressor.compress(data)?, Some(id)))
+            }
+            None => Ok((zstd::bulk::compress(data, self.level)?, None)),
+        }
+    }
+
+    /// 解压（根据帧头中的字典 ID 自动选择字典）
+    pub fn decompress(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let mut output = Vec::new();
+        match zstd::zstd_safe::get_dict_id_from_frame(data) {
+            Some(id) => {
+                let set = self.dictionaries.read().unwrap();
+                let dict = set.by_id.get(&id.get())
+                    .ok_or_else(|| anyhow!("Missing archive dictionary {}", id))?;
+                zstd::stream::read::Decoder::with_dictionary(data, dict)?.read_to_end(&mut output)?;
+            }
+            None => {
+                zstd::stream::read::Decoder::new(data)?.read_to_end(&mut output)?;
+            }
+        }
+        Ok(output)
+    }
+}
+
+/// 从样本训练字典
+pub fn train_dictionary(samples: &[Vec<u8>], max_size: usize) -> Result<Vec<u8>> {
+    zstd::dict::from_samples(samples, max_size)
+        .map_err(|e| anyhow!("Dictionary training failed ({} samples): {}", samples.len(), e))
+}
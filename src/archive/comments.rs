@@ -0,0 +1,28 @@
+//! 评论归档 (Comment Archive)
+//!
+//! `/api/note/comments/all` 以 `archive=true` 抓取的评论按 comment_id 保存，
+//! 重复抓取时覆盖为最新内容（点赞数、子评论数会更新），已删除的评论保留。
+
+use crate::api::note::comments::NoteComment;
+
+/// 查询默认返回条数
+pub const DEFAULT_COMMENT_LIMIT: usize = 100;
+
+/// 查询最大返回条数
+pub const MAX_COMMENT_LIMIT: usize = 1000;
+
+/// 评论归档查询条件
+#[derive(Debug, Clone, Default)]
+pub struct CommentFilter {
+    /// 只返回该一级评论下的子评论
+    pub root_comment_id: Option<String>,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+impl CommentFilter {
+    /// 文件后端逐条匹配使用
+    pub fn matches(&self, comment: &NoteComment) -> bool {
+        self.root_comment_id.as_ref().is_none_or(|id| comment.root_comment_id.as_ref() == Some(id))
+    }
+}
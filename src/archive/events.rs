@@ -0,0 +1,65 @@
+//! 笔记事件归档 (Note Events)
+//!
+//! 用户关注列表（见 `api::watch`）检测到的发布 / 删除事件，追加写入归档后端，
+//! 可按用户 / 类型 / 时间查询，用于回溯账号的发文与删文记录。
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// 查询默认返回条数
+pub const DEFAULT_EVENT_LIMIT: usize = 50;
+
+/// 查询最大返回条数
+pub const MAX_EVENT_LIMIT: usize = 500;
+
+/// 事件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NoteEventKind {
+    /// 新发布的笔记
+    Published,
+    /// 从作者主页消失的笔记（删除或设为仅自己可见）
+    Deleted,
+}
+
+/// 笔记事件
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NoteEvent {
+    /// 事件 ID
+    pub id: String,
+    pub kind: NoteEventKind,
+    /// 作者 user_id
+    pub user_id: String,
+    /// 作者昵称
+    pub nickname: String,
+    pub note_id: String,
+    /// 发现事件时笔记的 xsec_token（删除事件为最后一次见到时的值）
+    pub xsec_token: String,
+    /// 笔记类型 (normal / video)
+    pub note_type: String,
+    pub title: String,
+    /// 检测时间 (ms)
+    pub detected_at: i64,
+}
+
+/// 事件查询条件（均为可选，同时给出时取交集）
+#[derive(Debug, Clone, Default)]
+pub struct EventFilter {
+    pub user_id: Option<String>,
+    pub kind: Option<NoteEventKind>,
+    /// 检测时间下限 (ms, 含)
+    pub since: Option<i64>,
+    /// 检测时间上限 (ms, 不含)
+    pub until: Option<i64>,
+    pub limit: usize,
+}
+
+impl EventFilter {
+    /// 文件后端逐条匹配使用
+    pub fn matches(&self, event: &NoteEvent) -> bool {
+        self.user_id.as_ref().is_none_or(|id| event.user_id == *id)
+            && self.kind.is_none_or(|kind| event.kind == kind)
+            && self.since.is_none_or(|since| event.detected_at >= since)
+            && self.until.is_none_or(|until| event.detected_at < until)
+    }
+}
@@ -0,0 +1,285 @@
+//! 笔记归档导出 (Archive Export)
+//!
+//! 把笔记归档展平为一行一条笔记的表格，按 CSV / JSONL / Parquet 输出，
+//! 列可选，便于直接用 pandas / DuckDB 读取。
+//! CSV 与 JSONL 逐页编码、边查边发；Parquet 的元数据在文件尾部，
+//! 每页写成一个 row group，全部写完后一次性返回。
+
+use std::str::FromStr;
+use std::sync::Arc;
+
+use arrow_array::{ArrayRef, Int64Array, RecordBatch, StringArray};
+use arrow_schema::{DataType, Field, Schema};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use serde::Deserialize;
+
+use super::notes::ArchivedNote;
+
+/// 导出格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ArchiveExportFormat {
+    /// 带表头的 CSV (UTF-8)
+    Csv,
+    /// 每行一个 JSON 对象
+    #[default]
+    Jsonl,
+    /// Apache Parquet (snappy 压缩)
+    Parquet,
+}
+
+impl ArchiveExportFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv; charset=utf-8",
+            Self::Jsonl => "application/x-ndjson",
+            Self::Parquet => "application/vnd.apache.parquet",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Jsonl => "jsonl",
+            Self::Parquet => "parquet",
+        }
+    }
+}
+
+/// 可导出的列
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportColumn {
+    NoteId,
+    XsecToken,
+    NoteType,
+    Title,
+    Desc,
+    UserId,
+    Nickname,
+    /// 话题标签，以逗号连接
+    Tags,
+    PublishTime,
+    LastUpdateTime,
+    IpLocation,
+    LikedCount,
+    CollectedCount,
+    CommentCount,
+    ShareCount,
+    ImageCount,
+    /// 最高画质视频地址
+    VideoUrl,
+    FirstArchivedAt,
+    ArchivedAt,
+    ArchiveCount,
+}
+
+/// 单元格取值
+enum Cell {
+    Text(String),
+    Int(Option<i64>),
+}
+
+impl ExportColumn {
+    /// 全部列（未指定 columns 时的导出顺序）
+    pub const ALL: [ExportColumn; 20] = [
+        Self::NoteId,
+        Self::XsecToken,
+        Self::NoteType,
+        Self::Title,
+        Self::Desc,
+        Self::UserId,
+        Self::Nickname,
+        Self::Tags,
+        Self::PublishTime,
+        Self::LastUpdateTime,
+        Self::IpLocation,
+        Self::LikedCount,
+        Self::CollectedCount,
+        Self::CommentCount,
+        Self::ShareCount,
+        Self::ImageCount,
+        Self::VideoUrl,
+        Self::FirstArchivedAt,
+        Self::ArchivedAt,
+        Self::ArchiveCount,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::NoteId => "note_id",
+            Self::XsecToken => "xsec_token",
+            Self::NoteType => "note_type",
+            Self::Title => "title",
+            Self::Desc => "desc",
+            Self::UserId => "user_id",
+            Self::Nickname => "nickname",
+            Self::Tags => "tags",
+            Self::PublishTime => "publish_time",
+            Self::LastUpdateTime => "last_update_time",
+            Self::IpLocation => "ip_location",
+            Self::LikedCount => "liked_count",
+            Self::CollectedCount => "collected_count",
+            Self::CommentCount => "comment_count",
+            Self::ShareCount => "share_count",
+            Self::ImageCount => "image_count",
+            Self::VideoUrl => "video_url",
+            Self::FirstArchivedAt => "first_archived_at",
+            Self::ArchivedAt => "archived_at",
+            Self::ArchiveCount => "archive_count",
+        }
+    }
+
+    /// 解析逗号分隔的列名；为空时返回全部列
+    pub fn parse_list(columns: Option<&str>) -> Result<Vec<ExportColumn>, String> {
+        let Some(columns) = columns.map(str::trim).filter(|c| !c.is_empty()) else {
+            return Ok(Self::ALL.to_vec());
+        };
+        let mut parsed = Vec::new();
+        for name in columns.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            let column = name.parse()?;
+            if !parsed.contains(&column) {
+                parsed.push(column);
+            }
+        }
+        Ok(parsed)
+    }
+
+    fn is_text(self) -> bool {
+        matches!(
+            self,
+            Self::NoteId | Self::XsecToken | Self::NoteType | Self::Title | Self::Desc
+                | Self::UserId | Self::Nickname | Self::Tags | Self::IpLocation | Self::VideoUrl
+        )
+    }
+
+    fn cell(self, note: &ArchivedNote) -> Cell {
+        let detail = &note.detail;
+        match self {
+            Self::NoteId => Cell::Text(note.note_id.clone()),
+            Self::XsecToken => Cell::Text(note.xsec_token.clone()),
+            Self::NoteType => Cell::Text(detail.note_type.clone()),
+            Self::Title => Cell::Text(detail.title.clone()),
+            Self::Desc => Cell::Text(detail.desc.clone()),
+            Self::UserId => Cell::Text(detail.author.user_id.clone()),
+            Self::Nickname => Cell::Text(detail.author.nickname.clone()),
+            Self::Tags => Cell::Text(detail.tags.join(",")),
+            Self::IpLocation => Cell::Text(detail.ip_location.clone().unwrap_or_default()),
+            Self::VideoUrl => Cell::Text(note.videos.first().map(|v| v.url.clone()).unwrap_or_default()),
+            Self::PublishTime => Cell::Int(detail.publish_time),
+            Self::LastUpdateTime => Cell::Int(detail.last_update_time),
+            Self::LikedCount => Cell::Int(detail.liked_count),
+            Self::CollectedCount => Cell::Int(detail.collected_count),
+            Self::CommentCount => Cell::Int(detail.comment_count),
+            Self::ShareCount => Cell::Int(detail.share_count),
+            Self::ImageCount => Cell::Int(Some(note.images.len() as i64)),
+            Self::FirstArchivedAt => Cell::Int(Some(note.first_archived_at)),
+            Self::ArchivedAt => Cell::Int(Some(note.archived_at)),
+            Self::ArchiveCount => Cell::Int(Some(note.archive_count as i64)),
+        }
+    }
+}
+
+impl FromStr for ExportColumn {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|c| c.name() == name)
+            .ok_or_else(|| format!("Unknown export column: {}", name))
+    }
+}
+
+/// 编码为 CSV 行；`header` 为 true 时先写表头
+pub fn csv_rows(notes: &[ArchivedNote], columns: &[ExportColumn], header: bool) -> anyhow::Result<Vec<u8>> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    if header {
+        writer.write_record(columns.iter().map(|c| c.name()))?;
+    }
+    for note in notes {
+        writer.write_record(columns.iter().map(|c| match c.cell(note) {
+            Cell::Text(s) => s,
+            Cell::Int(n) => n.map(|n| n.to_string()).unwrap_or_default(),
+        }))?;
+    }
+    writer.into_inner().map_err(|e| anyhow::anyhow!(e.to_string()))
+}
+
+/// 编码为 JSONL 行（数值列缺失时为 null）
+pub fn jsonl_rows(notes: &[ArchivedNote], columns: &[ExportColumn]) -> anyhow::Result<Vec<u8>> {
+    let mut out = Vec::new();
+    for note in notes {
+        let row: serde_json::Map<String, serde_json::Value> = columns
+            .iter()
+            .map(|c| {
+                let value = match c.cell(note) {
+                    Cell::Text(s) => serde_json::Value::String(s),
+                    Cell::Int(n) => n.map_or(serde_json::Value::Null, serde_json::Value::from),
+                };
+                (c.name().to_string(), value)
+            })
+            .collect();
+        serde_json::to_writer(&mut out, &row)?;
+        out.push(b'\n');
+    }
+    Ok(out)
+}
+
+/// Parquet 写入器：每次 `write` 写一个 row group
+pub struct ParquetExport {
+    columns: Vec<ExportColumn>,
+    schema: Arc<Schema>,
+    writer: ArrowWriter<Vec<u8>>,
+}
+
+impl ParquetExport {
+    pub fn new(columns: Vec<ExportColumn>) -> anyhow::Result<Self> {
+        let fields: Vec<Field> = columns
+            .iter()
+            .map(|c| {
+                if c.is_text() {
+                    Field::new(c.name(), DataType::Utf8, false)
+                } else {
+                    Field::new(c.name(), DataType::Int64, true)
+                }
+            })
+            .collect();
+        let schema = Arc::new(Schema::new(fields));
+        let props = WriterProperties::builder().set_compression(Compression::SNAPPY).build();
+        let writer = ArrowWriter::try_new(Vec::new(), schema.clone(), Some(props))?;
+        Ok(Self { columns, schema, writer })
+    }
+
+    pub fn write(&mut self, notes: &[ArchivedNote]) -> anyhow::Result<()> {
+        if notes.is_empty() {
+            return Ok(());
+        }
+        let arrays: Vec<ArrayRef> = self.columns
+            .iter()
+            .map(|c| -> ArrayRef {
+                let cells = notes.iter().map(|note| c.cell(note));
+                if c.is_text() {
+                    Arc::new(StringArray::from_iter_values(cells.map(|cell| match cell {
+                        Cell::Text(s) => s,
+                        Cell::Int(_) => String::new(),
+                    })))
+                } else {
+                    Arc::new(cells.map(|cell| match cell {
+                        Cell::Int(n) => n,
+                        Cell::Text(_) => None,
+                    }).collect::<Int64Array>())
+                }
+            })
+            .collect();
+        let batch = RecordBatch::try_new(self.schema.clone(), arrays)?;
+        self.writer.write(&batch)?;
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    pub fn finish(self) -> anyhow::Result<Vec<u8>> {
+        Ok(self.writer.into_inner()?)
+    }
+}
@@ -0,0 +1,348 @@
+//! 文件归档后端
+//!
+//! 目录结构:
+//! ```text
+//! {XHS_ARCHIVE_DIR}/
+//!   dictionaries/{created_ms}-{dict_id}.zdict
+//!   20260101/index.jsonl        每行一条 ArchiveEntry
+//!   20260101/{uuid}.zst         压缩后的原始响应
+//!   notes/{note_id}.json        笔记归档（最新快照）
+//!   history/{note_id}.jsonl     笔记变更记录，每行一条 NoteRevision
+//!   comments/{note_id}.jsonl    笔记评论，每行一条 NoteComment（按 comment_id 覆盖）
+//!   events.jsonl                笔记事件，每行一条 NoteEvent
+//! ```
+//! 归档 ID 为 `{yyyymmdd}-{uuid}`，读取时可直接定位文件。
+
+use anyhow::{anyhow, Result};
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+
+use super::comments::CommentFilter;
+use super::events::{EventFilter, NoteEvent};
+use super::history::{HistoryFilter, NoteRevision};
+use super::notes::{ArchivedNote, NoteFilter};
+use super::ArchiveEntry;
+use crate::api::note::comments::NoteComment;
+
+pub struct FileBackend {
+    root: PathBuf,
+}
+
+impl FileBackend {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    /// 检查目录可写
+    pub async fn ping(&self) -> Result<()> {
+        crate::utils::storage::check_dir(&self.root).await
+    }
+
+    /// 新归档 ID
+    pub fn new_id(fetched_at: i64) -> String {
+        let date = chrono::DateTime::from_timestamp_millis(fetched_at)
+            .unwrap_or_default()
+            .format("%Y%m%d");
+        format!("{}-{}", date, uuid::Uuid::new_v4().simple())
+    }
+
+    pub async fn put(&self, entry: &ArchiveEntry, body: &[u8]) -> Result<()> {
+        let (dir, file) = self.locate(&entry.id)?;
+        tokio::fs::create_dir_all(&dir).await?;
+        tokio::fs::write(dir.join(file), body).await?;
+
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+        let mut index = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join("index.jsonl"))
+            .await?;
+        index.write_all(line.as_bytes()).await?;
+        Ok(())
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Option<(ArchiveEntry, Vec<u8>)>> {
+        let (dir, file) = self.locate(id)?;
+        let body = match tokio::fs::read(dir.join(&file)).await {
+            Ok(body) => body,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let entry = self.read_index(&dir).await?
+            .into_iter()
+            .find(|e| e.id == id)
+            .ok_or_else(|| anyhow!("Archive {} missing from index", id))?;
+        Ok(Some((entry, body)))
+    }
+
+    /// 最近的归档（按日期目录倒序扫描索引）
+    pub async fn recent(&self, endpoint: Option<&str>, limit: usize) -> Result<Vec<ArchiveEntry>> {
+        let mut dates = Vec::new();
+        if let Ok(mut dir) = tokio::fs::read_dir(&self.root).await {
+            while let Some(item) = dir.next_entry().await? {
+                let name = item.file_name().to_string_lossy().to_string();
+                if name.len() == 8 && name.chars().all(|c| c.is_ascii_digit()) {
+                    dates.push(name);
+                }
+            }
+        }
+        dates.sort_unstable_by(|a, b| b.cmp(a));
+
+        let mut entries = Vec::new();
+        for date in dates {
+            let mut day = self.read_index(&self.root.join(&date)).await?;
+            day.retain(|e| endpoint.is_none_or(|ep| e.endpoint == ep));
+            day.reverse();
+            entries.extend(day);
+            if entries.len() >= limit {
+                break;
+            }
+        }
+        entries.truncate(limit);
+        Ok(entries)
+    }
+
+    /// 已保存的字典（按创建时间升序）
+    pub async fn load_dictionaries(&self) -> Result<Vec<Vec<u8>>> {
+        let dir = self.root.join("dictionaries");
+        let mut paths = Vec::new();
+        if let Ok(mut entries) = tokio::fs::read_dir(&dir).await {
+            while let Some(item) = entries.next_entry().await? {
+                let path = item.path();
+                if path.extension().is_some_and(|e| e == "zdict") {
+                    paths.push(path);
+                }
+            }
+        }
+        paths.sort();
+
+        let mut dictionaries = Vec::with_capacity(paths.len());
+        for path in paths {
+            dictionaries.push(tokio::fs::read(path).await?);
+        }
+        Ok(dictionaries)
+    }
+
+    pub async fn save_dictionary(&self, dict_id: u32, dictionary: &[u8]) -> Result<()> {
+        let dir = self.root.join("dictionaries");
+        tokio::fs::create_dir_all(&dir).await?;
+        let name = format!("{}-{}.zdict", chrono::Utc::now().timestamp_millis(), dict_id);
+        tokio::fs::write(dir.join(name), dictionary).await?;
+        Ok(())
+    }
+
+    pub async fn put_note(&self, note: &ArchivedNote) -> Result<()> {
+        let path = self.note_path(&note.note_id)?;
+        tokio::fs::create_dir_all(self.root.join("notes")).await?;
+        // 先写临时文件再改名，避免并发读到半截 JSON
+        let tmp = path.with_extension("json.tmp");
+        tokio::fs::write(&tmp, serde_json::to_vec_pretty(note)?).await?;
+        tokio::fs::rename(&tmp, &path).await?;
+        Ok(())
+    }
+
+    pub async fn get_note(&self, note_id: &str) -> Result<Option<ArchivedNote>> {
+        match tokio::fs::read(self.note_path(note_id)?).await {
+            Ok(body) => Ok(Some(serde_json::from_slice(&body)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// 扫描全部笔记归档，按最近归档时间倒序分页
+    pub async fn query_notes(&self, filter: &NoteFilter) -> Result<Vec<ArchivedNote>> {
+        let mut notes = Vec::new();
+        if let Ok(mut dir) = tokio::fs::read_dir(self.root.join("notes")).await {
+            while let Some(item) = dir.next_entry().await? {
+                let path = item.path();
+                if path.extension().is_none_or(|e| e != "json") {
+                    continue;
+                }
+                let Ok(body) = tokio::fs::read(&path).await else {
+                    continue;
+                };
+                match serde_json::from_slice::<ArchivedNote>(&body) {
+                    Ok(note) if filter.matches(&note) => notes.push(note),
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("[Archive] Skipping unreadable {}: {}", path.display(), e),
+                }
+            }
+        }
+        notes.sort_unstable_by_key(|note| std::cmp::Reverse(note.archived_at));
+        Ok(notes.into_iter().skip(filter.offset).take(filter.limit).collect())
+    }
+
+    pub async fn put_revision(&self, revision: &NoteRevision) -> Result<()> {
+        valid_note_id(&revision.note_id)?;
+        let dir = self.root.join("history");
+        tokio::fs::create_dir_all(&dir).await?;
+        let mut line = serde_json::to_string(revision)?;
+        line.push('\n');
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(format!("{}.jsonl", revision.note_id)))
+            .await?;
+        file.write_all(line.as_bytes()).await?;
+        Ok(())
+    }
+
+    /// 读取笔记的变更记录，取最近的 `limit` 条后按归档时间正序返回
+    pub async fn query_revisions(&self, note_id: &str, filter: &HistoryFilter) -> Result<Vec<NoteRevision>> {
+        valid_note_id(note_id)?;
+        let path = self.root.join("history").join(format!("{}.jsonl", note_id));
+        let content = match tokio::fs::read_to_string(path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut revisions: Vec<NoteRevision> = content.lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .filter(|revision| filter.matches(revision))
+            .collect();
+        revisions.sort_by_key(|revision| revision.archived_at);
+        let skip = revisions.len().saturating_sub(filter.limit);
+        Ok(revisions.split_off(skip))
+    }
+
+    /// 与已保存的评论按 comment_id 合并后整体重写
+    pub async fn put_comments(&self, note_id: &str, comments: &[NoteComment]) -> Result<()> {
+        let path = self.comments_path(note_id)?;
+        let mut merged = self.read_comments(&path).await?;
+        let mut positions: std::collections::HashMap<String, usize> = merged.iter()
+            .enumerate()
+            .map(|(i, c)| (c.comment_id.clone(), i))
+            .collect();
+        for comment in comments {
+            match positions.get(&comment.comment_id) {
+                Some(&i) => merged[i] = comment.clone(),
+                None => {
+                    positions.insert(comment.comment_id.clone(), merged.len());
+                    merged.push(comment.clone());
+                }
+            }
+        }
+
+        let mut content = String::new();
+        for comment in &merged {
+            content.push_str(&serde_json::to_string(comment)?);
+            content.push('\n');
+        }
+        tokio::fs::create_dir_all(self.root.join("comments")).await?;
+        let tmp = path.with_extension("jsonl.tmp");
+        tokio::fs::write(&tmp, content).await?;
+        tokio::fs::rename(&tmp, &path).await?;
+        Ok(())
+    }
+
+    /// 读取笔记的评论，按评论时间正序分页
+    pub async fn query_comments(&self, note_id: &str, filter: &CommentFilter) -> Result<Vec<NoteComment>> {
+        let mut comments = self.read_comments(&self.comments_path(note_id)?).await?;
+        comments.retain(|c| filter.matches(c));
+        comments.sort_by_key(|c| c.create_time);
+        Ok(comments.into_iter().skip(filter.offset).take(filter.limit).collect())
+    }
+
+    /// 有归档评论的笔记
+    pub async fn comment_note_ids(&self) -> Result<Vec<String>> {
+        let mut dir = match tokio::fs::read_dir(self.root.join("comments")).await {
+            Ok(dir) => dir,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut note_ids = Vec::new();
+        while let Some(entry) = dir.next_entry().await? {
+            if let Some(note_id) = entry.file_name().to_str().and_then(|name| name.strip_suffix(".jsonl")) {
+                note_ids.push(note_id.to_string());
+            }
+        }
+        note_ids.sort();
+        Ok(note_ids)
+    }
+
+    async fn read_comments(&self, path: &Path) -> Result<Vec<NoteComment>> {
+        let content = match tokio::fs::read_to_string(path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(content.lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+
+    fn comments_path(&self, note_id: &str) -> Result<PathBuf> {
+        valid_note_id(note_id)?;
+        Ok(self.root.join("comments").join(format!("{}.jsonl", note_id)))
+    }
+
+    pub async fn put_events(&self, events: &[NoteEvent]) -> Result<()> {
+        tokio::fs::create_dir_all(&self.root).await?;
+        let mut lines = String::new();
+        for event in events {
+            lines.push_str(&serde_json::to_string(event)?);
+            lines.push('\n');
+        }
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.root.join("events.jsonl"))
+            .await?;
+        file.write_all(lines.as_bytes()).await?;
+        Ok(())
+    }
+
+    /// 扫描事件文件，按检测时间倒序
+    pub async fn query_events(&self, filter: &EventFilter) -> Result<Vec<NoteEvent>> {
+        let content = match tokio::fs::read_to_string(self.root.join("events.jsonl")).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut events: Vec<NoteEvent> = content.lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .filter(|event| filter.matches(event))
+            .collect();
+        events.sort_by_key(|event| std::cmp::Reverse(event.detected_at));
+        events.truncate(filter.limit);
+        Ok(events)
+    }
+
+    fn note_path(&self, note_id: &str) -> Result<PathBuf> {
+        valid_note_id(note_id)?;
+        Ok(self.root.join("notes").join(format!("{}.json", note_id)))
+    }
+
+    async fn read_index(&self, dir: &Path) -> Result<Vec<ArchiveEntry>> {
+        let content = match tokio::fs::read_to_string(dir.join("index.jsonl")).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(content.lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+
+    /// 归档 ID -> (日期目录, 文件名)
+    fn locate(&self, id: &str) -> Result<(PathBuf, String)> {
+        let (date, name) = id.split_once('-').ok_or_else(|| anyhow!("Invalid archive id: {}", id))?;
+        let valid = date.len() == 8
+            && date.chars().all(|c| c.is_ascii_digit())
+            && !name.is_empty()
+            && name.chars().all(|c| c.is_ascii_alphanumeric());
+        if !valid {
+            return Err(anyhow!("Invalid archive id: {}", id));
+        }
+        Ok((self.root.join(date), format!("{}.zst", name)))
+    }
+}
+
+/// note_id 直接用作文件名，只允许字母数字
+fn valid_note_id(note_id: &str) -> Result<()> {
+    if note_id.is_empty() || !note_id.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(anyhow!("Invalid note id: {}", note_id));
+    }
+    Ok(())
+}
@@ -0,0 +1,143 @@
+//! 笔记变更记录 (Note History)
+//!
+//! 每次归档笔记时与上一次快照比较，记录标题 / 正文 / 标签的修改与互动数据的增减，
+//! 同时保存本次的互动数据，按时间排列即为该笔记的互动趋势。
+//! 首次归档的记录没有变更项，作为趋势的起点。
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::notes::ArchivedNote;
+
+/// 查询默认返回条数
+pub const DEFAULT_HISTORY_LIMIT: usize = 100;
+
+/// 查询最大返回条数
+pub const MAX_HISTORY_LIMIT: usize = 1000;
+
+/// 跟踪的字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NoteField {
+    Title,
+    Desc,
+    Tags,
+    LikedCount,
+    CollectedCount,
+    CommentCount,
+    ShareCount,
+}
+
+/// 单个字段的变化
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FieldChange {
+    pub field: NoteField,
+    /// 上一次快照的值
+    pub old: serde_json::Value,
+    /// 本次快照的值
+    pub new: serde_json::Value,
+    /// 互动数据的增量（新值 - 旧值，两者都有值时给出）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delta: Option<i64>,
+}
+
+/// 一次归档的变更记录
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NoteRevision {
+    pub note_id: String,
+    /// 归档时间 (ms)
+    pub archived_at: i64,
+    /// 第几次归档
+    pub archive_count: u32,
+    /// 本次快照的点赞数
+    pub liked_count: Option<i64>,
+    /// 本次快照的收藏数
+    pub collected_count: Option<i64>,
+    /// 本次快照的评论数
+    pub comment_count: Option<i64>,
+    /// 本次快照的分享数
+    pub share_count: Option<i64>,
+    /// 相对上一次快照变化的字段（首次归档为空）
+    #[serde(default)]
+    pub changes: Vec<FieldChange>,
+}
+
+impl NoteRevision {
+    /// 比较上一次快照与本次快照（本次的归档时间与次数已填写）
+    pub fn between(previous: Option<&ArchivedNote>, note: &ArchivedNote) -> Self {
+        let current = &note.detail;
+        let mut changes = Vec::new();
+        if let Some(previous) = previous.map(|p| &p.detail) {
+            let mut text = |field, old: &str, new: &str| {
+                if old != new {
+                    changes.push(FieldChange { field, old: old.into(), new: new.into(), delta: None });
+                }
+            };
+            text(NoteField::Title, &previous.title, &current.title);
+            text(NoteField::Desc, &previous.desc, &current.desc);
+            if previous.tags != current.tags {
+                changes.push(FieldChange {
+                    field: NoteField::Tags,
+                    old: previous.tags.clone().into(),
+                    new: current.tags.clone().into(),
+                    delta: None,
+                });
+            }
+
+            let counts = [
+                (NoteField::LikedCount, previous.liked_count, current.liked_count),
+                (NoteField::CollectedCount, previous.collected_count, current.collected_count),
+                (NoteField::CommentCount, previous.comment_count, current.comment_count),
+                (NoteField::ShareCount, previous.share_count, current.share_count),
+            ];
+            for (field, old, new) in counts {
+                if old != new {
+                    changes.push(FieldChange {
+                        field,
+                        old: old.into(),
+                        new: new.into(),
+                        delta: old.zip(new).map(|(old, new)| new - old),
+                    });
+                }
+            }
+        }
+
+        Self {
+            note_id: note.note_id.clone(),
+            archived_at: note.archived_at,
+            archive_count: note.archive_count,
+            liked_count: current.liked_count,
+            collected_count: current.collected_count,
+            comment_count: current.comment_count,
+            share_count: current.share_count,
+            changes,
+        }
+    }
+
+    /// 是否修改了标题 / 正文 / 标签
+    pub fn is_edit(&self) -> bool {
+        self.changes.iter().any(|c| matches!(c.field, NoteField::Title | NoteField::Desc | NoteField::Tags))
+    }
+}
+
+/// 变更记录查询条件
+#[derive(Debug, Clone)]
+pub struct HistoryFilter {
+    /// 归档时间下限 (ms, 含)
+    pub since: Option<i64>,
+    /// 归档时间上限 (ms, 不含)
+    pub until: Option<i64>,
+    /// 只返回修改了标题 / 正文 / 标签的记录
+    pub edits_only: bool,
+    /// 返回最近的条数
+    pub limit: usize,
+}
+
+impl HistoryFilter {
+    /// 文件后端逐条匹配使用
+    pub fn matches(&self, revision: &NoteRevision) -> bool {
+        self.since.is_none_or(|since| revision.archived_at >= since)
+            && self.until.is_none_or(|until| revision.archived_at < until)
+            && (!self.edits_only || revision.is_edit())
+    }
+}
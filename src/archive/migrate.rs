@@ -0,0 +1,158 @@
+//! 归档迁移 (Archive Migration)
+//!
+//! 把一个归档后端的全部数据复制到另一个后端（如 file → mongo），供 `xhs-rs migrate` 使用：
+//! 压缩字典、原始响应（保持压缩后的内容与归档 ID）、笔记归档、变更记录、评论与笔记事件。
+//!
+//! 可重复执行：目标中已存在的字典 / 原始响应 / 事件跳过，笔记与评论按 ID 覆盖，
+//! 已有变更记录的笔记不再复制变更记录。
+
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::HashSet;
+
+use super::comments::CommentFilter;
+use super::events::EventFilter;
+use super::history::HistoryFilter;
+use super::notes::NoteFilter;
+use super::{ArchiveBackend, ResponseArchive};
+
+/// 查询“全部”时使用的上限（Mongo 的 limit 为 i64）
+const ALL: usize = i64::MAX as usize;
+
+/// 迁移结果（均为本次写入目标的数量）
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MigrationReport {
+    pub dictionaries: usize,
+    pub responses: usize,
+    /// 目标中已存在而跳过的原始响应
+    pub responses_skipped: usize,
+    pub notes: usize,
+    pub revisions: usize,
+    pub comments: usize,
+    pub events: usize,
+}
+
+impl ResponseArchive {
+    /// 把本归档的全部数据复制到 `target`
+    pub async fn migrate_to(&self, target: &ResponseArchive) -> Result<MigrationReport> {
+        let mut report = MigrationReport::default();
+
+        // 字典先于原始响应复制，否则目标无法解压
+        let existing: HashSet<u32> = target.backend.load_dictionaries().await?.iter().filter_map(|d| dict_id(d)).collect();
+        for dictionary in self.backend.load_dictionaries().await? {
+            let Some(id) = dict_id(&dictionary) else { continue };
+            if existing.contains(&id) {
+                continue;
+            }
+            target.backend.save_dictionary(id, &dictionary).await?;
+            target.codec.add_dictionary(dictionary, false)?;
+            report.dictionaries += 1;
+        }
+
+        let mut entries = self.recent(None, ALL).await?;
+        entries.reverse();
+        for entry in entries {
+            if target.backend.get(&entry.id).await?.is_some() {
+                report.responses_skipped += 1;
+                continue;
+            }
+            if let Some((entry, body)) = self.backend.get(&entry.id).await? {
+                target.backend.put(&entry, &body).await?;
+                report.responses += 1;
+            }
+        }
+
+        let history = HistoryFilter { since: None, until: None, edits_only: false, limit: ALL };
+        for note in self.query_notes(&NoteFilter { limit: ALL, ..Default::default() }).await? {
+            if target.note_history(&note.note_id, &history).await?.is_empty() {
+                for revision in self.note_history(&note.note_id, &history).await? {
+                    target.backend.put_revision(&revision).await?;
+                    report.revisions += 1;
+                }
+            }
+            target.backend.put_note(&note).await?;
+            report.notes += 1;
+        }
+
+        for note_id in self.backend.comment_note_ids().await? {
+            let comments = self.query_comments(&note_id, &CommentFilter { limit: ALL, ..Default::default() }).await?;
+            target.store_comments(&note_id, &comments).await?;
+            report.comments += comments.len();
+        }
+
+        let all_events = EventFilter { limit: ALL, ..Default::default() };
+        let existing: HashSet<String> = target.query_events(&all_events).await?.into_iter().map(|e| e.id).collect();
+        let mut events: Vec<_> = self.query_events(&all_events).await?
+            .into_iter()
+            .filter(|e| !existing.contains(&e.id))
+            .collect();
+        events.reverse();
+        target.store_events(&events).await?;
+        report.events = events.len();
+
+        Ok(report)
+    }
+}
+
+fn dict_id(dictionary: &[u8]) -> Option<u32> {
+    zstd::zstd_safe::get_dict_id_from_dict(dictionary).map(|id| id.get())
+}
+
+/// 迁移用到的后端原语（与 `ResponseArchive` 中的分派方式一致）
+impl ArchiveBackend {
+    async fn load_dictionaries(&self) -> Result<Vec<Vec<u8>>> {
+        match self {
+            ArchiveBackend::File(b) => b.load_dictionaries().await,
+            #[cfg(feature = "mongodb")]
+            ArchiveBackend::Mongo(b) => b.load_dictionaries().await,
+        }
+    }
+
+    async fn save_dictionary(&self, dict_id: u32, dictionary: &[u8]) -> Result<()> {
+        match self {
+            ArchiveBackend::File(b) => b.save_dictionary(dict_id, dictionary).await,
+            #[cfg(feature = "mongodb")]
+            ArchiveBackend::Mongo(b) => b.save_dictionary(dict_id, dictionary).await,
+        }
+    }
+
+    async fn get(&self, id: &str) -> Result<Option<(super::ArchiveEntry, Vec<u8>)>> {
+        match self {
+            ArchiveBackend::File(b) => b.get(id).await,
+            #[cfg(feature = "mongodb")]
+            ArchiveBackend::Mongo(b) => b.get(id).await,
+        }
+    }
+
+    async fn put(&self, entry: &super::ArchiveEntry, body: &[u8]) -> Result<()> {
+        match self {
+            ArchiveBackend::File(b) => b.put(entry, body).await,
+            #[cfg(feature = "mongodb")]
+            ArchiveBackend::Mongo(b) => b.put(entry, body).await,
+        }
+    }
+
+    async fn put_note(&self, note: &super::notes::ArchivedNote) -> Result<()> {
+        match self {
+            ArchiveBackend::File(b) => b.put_note(note).await,
+            #[cfg(feature = "mongodb")]
+            ArchiveBackend::Mongo(b) => b.put_note(note).await,
+        }
+    }
+
+    async fn put_revision(&self, revision: &super::history::NoteRevision) -> Result<()> {
+        match self {
+            ArchiveBackend::File(b) => b.put_revision(revision).await,
+            #[cfg(feature = "mongodb")]
+            ArchiveBackend::Mongo(b) => b.put_revision(revision).await,
+        }
+    }
+
+    async fn comment_note_ids(&self) -> Result<Vec<String>> {
+        match self {
+            ArchiveBackend::File(b) => b.comment_note_ids().await,
+            #[cfg(feature = "mongodb")]
+            ArchiveBackend::Mongo(b) => b.comment_note_ids().await,
+        }
+    }
+}
@@ -0,0 +1,365 @@
+//! 原始响应归档 (Raw Response Archive)
+//!
+//! 长期抓取时保留上游原始 JSON 便于回溯与重新解析，但原始响应体积大且高度重复。
+//! 归档时使用 zstd 压缩（可用历史响应训练字典），读取时透明解压。
+//! 同一后端还保存结构化的笔记归档（见 [`notes`]），可按作者 / 类型 / 标签 / 时间查询，
+//! 并可导出为 CSV / JSONL / Parquet（见 `export`，需 `export` feature）；重复归档时记录标题 / 正文与互动数据的变化（见 [`history`]）；
+//! 整篇抓取的评论见 [`comments`]；用户关注列表的发布 / 删除事件见 [`events`]。
+//! 更换后端时可用 `xhs-rs migrate` 复制全部归档数据（见 [`migrate`]）。
+//!
+//! 配置 (环境变量):
+//! - `XHS_ARCHIVE_BACKEND`: `off` (默认) / `file` / `mongo`
+//! - `XHS_ARCHIVE_DIR`: 文件后端目录，默认 ./archive
+//! - `XHS_ARCHIVE_MONGO_URI`: Mongo 后端连接串，默认复用 XHS_COORD_MONGO_URI
+//! - `XHS_ARCHIVE_DB`: Mongo 数据库名，默认 xhs_archive
+//! - `XHS_ARCHIVE_LEVEL`: zstd 压缩级别，默认 9
+
+pub mod codec;
+pub mod comments;
+pub mod events;
+#[cfg(feature = "export")]
+pub mod export;
+pub mod file;
+pub mod history;
+pub mod migrate;
+#[cfg(feature = "mongodb")]
+pub mod mongo;
+pub mod notes;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use utoipa::ToSchema;
+
+use codec::ArchiveCodec;
+use file::FileBackend;
+#[cfg(feature = "mongodb")]
+use mongo::MongoBackend;
+use comments::CommentFilter;
+use events::{EventFilter, NoteEvent};
+use history::{HistoryFilter, NoteRevision};
+use notes::{ArchivedNote, NoteFilter};
+use crate::api::note::comments::NoteComment;
+
+/// 默认字典大小上限 (112KB，zstd 推荐值)
+pub const DEFAULT_DICT_SIZE: usize = 112 * 1024;
+
+/// 训练字典所需的最少样本数
+const MIN_TRAINING_SAMPLES: usize = 20;
+
+/// 归档配置
+#[derive(Debug, Clone)]
+pub enum ArchiveConfig {
+    File { dir: PathBuf, level: i32 },
+    #[cfg(feature = "mongodb")]
+    Mongo { uri: String, database: String, level: i32 },
+}
+
+impl ArchiveConfig {
+    /// 从环境变量读取，未启用时返回 None
+    pub fn from_env() -> Result<Option<Self>> {
+        let level = std::env::var("XHS_ARCHIVE_LEVEL")
+            .ok()
+            .and_then(|v| v.parse::<i32>().ok())
+            .unwrap_or(9);
+        let backend = std::env::var("XHS_ARCHIVE_BACKEND").unwrap_or_else(|_| "off".to_string());
+
+        match backend.as_str() {
+            "" | "off" => Ok(None),
+            "file" => Ok(Some(Self::File {
+                dir: std::env::var("XHS_ARCHIVE_DIR")
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|_| PathBuf::from("./archive")),
+                level,
+            })),
+            #[cfg(feature = "mongodb")]
+            "mongo" => {
+                let uri = std::env::var("XHS_ARCHIVE_MONGO_URI")
+                    .or_else(|_| std::env::var("XHS_COORD_MONGO_URI"))
+                    .map_err(|_| anyhow!("XHS_ARCHIVE_BACKEND=mongo requires XHS_ARCHIVE_MONGO_URI"))?;
+                let database = std::env::var("XHS_ARCHIVE_DB").unwrap_or_else(|_| "xhs_archive".to_string());
+                Ok(Some(Self::Mongo { uri, database, level }))
+            }
+            #[cfg(not(feature = "mongodb"))]
+            "mongo" => Err(anyhow!("XHS_ARCHIVE_BACKEND=mongo requires the `mongodb` cargo feature")),
+            other => Err(anyhow!("Unknown XHS_ARCHIVE_BACKEND: {}", other)),
+        }
+    }
+}
+
+impl ArchiveConfig {
+    /// 命令行指定的后端：`mongodb://` / `mongodb+srv://` 连接串为 Mongo（数据库名 `database`），其他视为文件目录
+    pub fn from_location(location: &str, database: &str, level: i32) -> Result<Self> {
+        if location.starts_with("mongodb://") || location.starts_with("mongodb+srv://") {
+            #[cfg(feature = "mongodb")]
+            return Ok(Self::Mongo { uri: location.to_string(), database: database.to_string(), level });
+            #[cfg(not(feature = "mongodb"))]
+            return Err(anyhow!("MongoDB archives require the `mongodb` cargo feature (database {})", database));
+        }
+        Ok(Self::File { dir: PathBuf::from(location), level })
+    }
+}
+
+/// 归档条目元数据
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ArchiveEntry {
+    /// 归档 ID
+    pub id: String,
+    /// 接口路径（不含查询参数）
+    pub endpoint: String,
+    /// 抓取时间 (ms)
+    pub fetched_at: i64,
+    /// 原始大小 (字节)
+    pub raw_size: u64,
+    /// 压缩后大小 (字节)
+    pub stored_size: u64,
+    /// 压缩使用的字典 ID
+    #[serde(default)]
+    pub dict_id: Option<u32>,
+}
+
+/// 字典训练结果
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DictionaryInfo {
+    pub dict_id: u32,
+    pub size: usize,
+    pub samples: usize,
+}
+
+enum ArchiveBackend {
+    File(FileBackend),
+    #[cfg(feature = "mongodb")]
+    Mongo(MongoBackend),
+}
+
+/// 原始响应归档
+pub struct ResponseArchive {
+    backend: ArchiveBackend,
+    codec: ArchiveCodec,
+    description: String,
+}
+
+impl ResponseArchive {
+    /// 连接后端并加载已有字典（最后保存的字典用于新数据）
+    pub async fn open(config: &ArchiveConfig) -> Result<Self> {
+        let (backend, level) = match config {
+            ArchiveConfig::File { dir, level } => (ArchiveBackend::File(FileBackend::new(dir.clone())), *level),
+            #[cfg(feature = "mongodb")]
+            ArchiveConfig::Mongo { uri, database, level } => {
+                (ArchiveBackend::Mongo(MongoBackend::connect(uri, database).await?), *level)
+            }
+        };
+        let description = match config {
+            ArchiveConfig::File { dir, .. } => format!("file: {}", dir.display()),
+            #[cfg(feature = "mongodb")]
+            ArchiveConfig::Mongo { database, .. } => format!("mongo: {}", database),
+        };
+        let archive = Self { backend, codec: ArchiveCodec::new(level), description };
+
+        let dictionaries = match &archive.backend {
+            ArchiveBackend::File(b) => b.load_dictionaries().await?,
+            #[cfg(feature = "mongodb")]
+            ArchiveBackend::Mongo(b) => b.load_dictionaries().await?,
+        };
+        for dictionary in dictionaries {
+            archive.codec.add_dictionary(dictionary, true)?;
+        }
+
+        tracing::info!(
+            "[Archive] Enabled ({}, level {}, dictionary: {:?})",
+            archive.description,
+            level,
+            archive.codec.active_dictionary()
+        );
+        Ok(archive)
+    }
+
+    /// 后端描述，如 "file: ./archive"
+    pub fn describe(&self) -> &str {
+        &self.description
+    }
+
+    /// 检查后端可用（就绪探针）
+    pub async fn ping(&self) -> Result<()> {
+        match &self.backend {
+            ArchiveBackend::File(b) => b.ping().await,
+            #[cfg(feature = "mongodb")]
+            ArchiveBackend::Mongo(b) => b.ping().await,
+        }
+    }
+
+    /// 按环境变量打开，未启用时返回 None
+    pub async fn from_env() -> Result<Option<Self>> {
+        match ArchiveConfig::from_env()? {
+            Some(config) => Ok(Some(Self::open(&config).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// 压缩并保存一条原始响应
+    pub async fn store(&self, endpoint: &str, raw: &str) -> Result<ArchiveEntry> {
+        let fetched_at = chrono::Utc::now().timestamp_millis();
+        let (body, dict_id) = self.codec.compress(raw.as_bytes())?;
+        let entry = ArchiveEntry {
+            id: FileBackend::new_id(fetched_at),
+            endpoint: endpoint.split('?').next().unwrap_or(endpoint).to_string(),
+            fetched_at,
+            raw_size: raw.len() as u64,
+            stored_size: body.len() as u64,
+            dict_id,
+        };
+
+        match &self.backend {
+            ArchiveBackend::File(b) => b.put(&entry, &body).await?,
+            #[cfg(feature = "mongodb")]
+            ArchiveBackend::Mongo(b) => b.put(&entry, &body).await?,
+        }
+        Ok(entry)
+    }
+
+    /// 读取并解压
+    pub async fn read(&self, id: &str) -> Result<Option<(ArchiveEntry, String)>> {
+        let stored = match &self.backend {
+            ArchiveBackend::File(b) => b.get(id).await?,
+            #[cfg(feature = "mongodb")]
+            ArchiveBackend::Mongo(b) => b.get(id).await?,
+        };
+        match stored {
+            Some((entry, body)) => {
+                let raw = String::from_utf8(self.codec.decompress(&body)?)?;
+                Ok(Some((entry, raw)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// 最近的归档条目
+    pub async fn recent(&self, endpoint: Option<&str>, limit: usize) -> Result<Vec<ArchiveEntry>> {
+        match &self.backend {
+            ArchiveBackend::File(b) => b.recent(endpoint, limit).await,
+            #[cfg(feature = "mongodb")]
+            ArchiveBackend::Mongo(b) => b.recent(endpoint, limit).await,
+        }
+    }
+
+    /// 保存笔记归档：已归档过的笔记覆盖为最新快照，保留首次归档时间并累加归档次数，
+    /// 同时记录相对上一次快照的变化
+    pub async fn store_note(&self, mut note: ArchivedNote) -> Result<(ArchivedNote, NoteRevision)> {
+        let now = chrono::Utc::now().timestamp_millis();
+        let previous = match &self.backend {
+            ArchiveBackend::File(b) => b.get_note(&note.note_id).await?,
+            #[cfg(feature = "mongodb")]
+            ArchiveBackend::Mongo(b) => b.get_note(&note.note_id).await?,
+        };
+        note.first_archived_at = previous.as_ref().map_or(now, |p| p.first_archived_at);
+        note.archive_count = previous.as_ref().map_or(0, |p| p.archive_count) + 1;
+        note.archived_at = now;
+        let revision = NoteRevision::between(previous.as_ref(), &note);
+
+        match &self.backend {
+            ArchiveBackend::File(b) => {
+                b.put_note(&note).await?;
+                b.put_revision(&revision).await?;
+            }
+            #[cfg(feature = "mongodb")]
+            ArchiveBackend::Mongo(b) => {
+                b.put_note(&note).await?;
+                b.put_revision(&revision).await?;
+            }
+        }
+        Ok((note, revision))
+    }
+
+    /// 笔记的变更记录（按归档时间正序，取最近的 `filter.limit` 条）
+    pub async fn note_history(&self, note_id: &str, filter: &HistoryFilter) -> Result<Vec<NoteRevision>> {
+        match &self.backend {
+            ArchiveBackend::File(b) => b.query_revisions(note_id, filter).await,
+            #[cfg(feature = "mongodb")]
+            ArchiveBackend::Mongo(b) => b.query_revisions(note_id, filter).await,
+        }
+    }
+
+    /// 按条件查询笔记归档（最近归档的在前）
+    pub async fn query_notes(&self, filter: &NoteFilter) -> Result<Vec<ArchivedNote>> {
+        match &self.backend {
+            ArchiveBackend::File(b) => b.query_notes(filter).await,
+            #[cfg(feature = "mongodb")]
+            ArchiveBackend::Mongo(b) => b.query_notes(filter).await,
+        }
+    }
+
+    /// 保存笔记评论（按 comment_id 覆盖）
+    pub async fn store_comments(&self, note_id: &str, comments: &[NoteComment]) -> Result<()> {
+        if comments.is_empty() {
+            return Ok(());
+        }
+        match &self.backend {
+            ArchiveBackend::File(b) => b.put_comments(note_id, comments).await,
+            #[cfg(feature = "mongodb")]
+            ArchiveBackend::Mongo(b) => b.put_comments(comments).await,
+        }
+    }
+
+    /// 笔记的归档评论（按评论时间正序）
+    pub async fn query_comments(&self, note_id: &str, filter: &CommentFilter) -> Result<Vec<NoteComment>> {
+        match &self.backend {
+            ArchiveBackend::File(b) => b.query_comments(note_id, filter).await,
+            #[cfg(feature = "mongodb")]
+            ArchiveBackend::Mongo(b) => b.query_comments(note_id, filter).await,
+        }
+    }
+
+    /// 追加笔记事件
+    pub async fn store_events(&self, events: &[NoteEvent]) -> Result<()> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        match &self.backend {
+            ArchiveBackend::File(b) => b.put_events(events).await,
+            #[cfg(feature = "mongodb")]
+            ArchiveBackend::Mongo(b) => b.put_events(events).await,
+        }
+    }
+
+    /// 按条件查询笔记事件（最近的在前）
+    pub async fn query_events(&self, filter: &EventFilter) -> Result<Vec<NoteEvent>> {
+        match &self.backend {
+            ArchiveBackend::File(b) => b.query_events(filter).await,
+            #[cfg(feature = "mongodb")]
+            ArchiveBackend::Mongo(b) => b.query_events(filter).await,
+        }
+    }
+
+    /// 用最近的 `samples` 条归档训练新字典，保存并用于后续压缩
+    pub async fn train_dictionary(&self, samples: usize, max_size: usize) -> Result<DictionaryInfo> {
+        let mut raws = Vec::with_capacity(samples);
+        for entry in self.recent(None, samples).await? {
+            if let Some((_, raw)) = self.read(&entry.id).await? {
+                raws.push(raw.into_bytes());
+            }
+        }
+        if raws.len() < MIN_TRAINING_SAMPLES {
+            return Err(anyhow!(
+                "Need at least {} archived responses to train a dictionary, found {}",
+                MIN_TRAINING_SAMPLES, raws.len()
+            ));
+        }
+
+        let count = raws.len();
+        let dictionary = tokio::task::spawn_blocking(move || codec::train_dictionary(&raws, max_size)).await??;
+        let size = dictionary.len();
+        let dict_id = zstd::zstd_safe::get_dict_id_from_dict(&dictionary)
+            .ok_or_else(|| anyhow!("Trained dictionary has no id"))?
+            .get();
+
+        match &self.backend {
+            ArchiveBackend::File(b) => b.save_dictionary(dict_id, &dictionary).await?,
+            #[cfg(feature = "mongodb")]
+            ArchiveBackend::Mongo(b) => b.save_dictionary(dict_id, &dictionary).await?,
+        }
+        self.codec.add_dictionary(dictionary, true)?;
+
+        tracing::info!("[Archive] Trained dictionary {} ({} bytes from {} samples)", dict_id, size, count);
+        Ok(DictionaryInfo { dict_id, size, samples: count })
+    }
+}
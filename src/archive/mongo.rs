@@ -0,0 +1,395 @@
+//! MongoDB 归档后端
+//!
+//! - `raw_responses`: 每条归档一个文档，压缩数据以 Binary 存放
+//!   （字典压缩后的单条响应通常只有几 KB，远低于 16MB 文档上限，无需 GridFS）
+//! - `archive_dictionaries`: 训练得到的字典
+//! - `archived_notes`: 笔记归档，每篇笔记一个文档（按 note_id 覆盖）
+//! - `note_history`: 笔记变更记录，每次归档一个文档，只追加
+//! - `archived_comments`: 笔记评论，每条评论一个文档（按 comment_id 覆盖）
+//! - `note_events`: 笔记发布 / 删除事件，只追加
+
+use anyhow::{anyhow, Result};
+use mongodb::bson::spec::BinarySubtype;
+use mongodb::bson::{doc, Binary, Document};
+use mongodb::options::IndexOptions;
+use mongodb::{Collection, IndexModel};
+use serde::{Deserialize, Serialize};
+
+use super::comments::CommentFilter;
+use super::events::{EventFilter, NoteEvent};
+use super::history::{HistoryFilter, NoteRevision};
+use super::notes::{ArchivedNote, NoteFilter};
+use super::ArchiveEntry;
+use crate::api::note::comments::NoteComment;
+
+#[derive(Serialize, Deserialize)]
+struct ArchiveDoc {
+    #[serde(rename = "_id")]
+    id: String,
+    endpoint: String,
+    fetched_at: i64,
+    raw_size: i64,
+    stored_size: i64,
+    #[serde(default)]
+    dict_id: Option<i64>,
+    body: Binary,
+}
+
+impl ArchiveDoc {
+    fn entry(&self) -> ArchiveEntry {
+        ArchiveEntry {
+            id: self.id.clone(),
+            endpoint: self.endpoint.clone(),
+            fetched_at: self.fetched_at,
+            raw_size: self.raw_size as u64,
+            stored_size: self.stored_size as u64,
+            dict_id: self.dict_id.map(|id| id as u32),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct DictionaryDoc {
+    #[serde(rename = "_id")]
+    id: i64,
+    created_at: i64,
+    data: Binary,
+}
+
+pub struct MongoBackend {
+    responses: Collection<ArchiveDoc>,
+    dictionaries: Collection<DictionaryDoc>,
+    notes: Collection<ArchivedNote>,
+    history: Collection<NoteRevision>,
+    comments: Collection<NoteComment>,
+    events: Collection<NoteEvent>,
+}
+
+impl MongoBackend {
+    pub async fn connect(uri: &str, database: &str) -> Result<Self> {
+        let client = crate::utils::storage::mongo_client(uri)
+            .await
+            .map_err(|e| anyhow!("Failed to connect to archive MongoDB: {}", e))?;
+        let db = client.database(database);
+        let responses: Collection<ArchiveDoc> = db.collection("raw_responses");
+        responses
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "endpoint": 1, "fetched_at": -1 })
+                    .options(IndexOptions::builder().name("endpoint_fetched_at".to_string()).build())
+                    .build(),
+            )
+            .await?;
+
+        let notes: Collection<ArchivedNote> = db.collection("archived_notes");
+        notes
+            .create_indexes([
+                IndexModel::builder()
+                    .keys(doc! { "note_id": 1 })
+                    .options(IndexOptions::builder().name("note_id".to_string()).unique(true).build())
+                    .build(),
+                IndexModel::builder()
+                    .keys(doc! { "archived_at": -1 })
+                    .options(IndexOptions::builder().name("archived_at".to_string()).build())
+                    .build(),
+                IndexModel::builder()
+                    .keys(doc! { "detail.author.user_id": 1, "archived_at": -1 })
+                    .options(IndexOptions::builder().name("author_archived_at".to_string()).build())
+                    .build(),
+            ])
+            .await?;
+
+        let history: Collection<NoteRevision> = db.collection("note_history");
+        history
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "note_id": 1, "archived_at": -1 })
+                    .options(IndexOptions::builder().name("note_archived_at".to_string()).build())
+                    .build(),
+            )
+            .await?;
+
+        let comments: Collection<NoteComment> = db.collection("archived_comments");
+        comments
+            .create_indexes([
+                IndexModel::builder()
+                    .keys(doc! { "comment_id": 1 })
+                    .options(IndexOptions::builder().name("comment_id".to_string()).unique(true).build())
+                    .build(),
+                IndexModel::builder()
+                    .keys(doc! { "note_id": 1, "create_time": 1 })
+                    .options(IndexOptions::builder().name("note_create_time".to_string()).build())
+                    .build(),
+            ])
+            .await?;
+
+        let events: Collection<NoteEvent> = db.collection("note_events");
+        events
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "user_id": 1, "detected_at": -1 })
+                    .options(IndexOptions::builder().name("user_detected_at".to_string()).build())
+                    .build(),
+            )
+            .await?;
+
+        Ok(Self {
+            responses,
+            dictionaries: db.collection("archive_dictionaries"),
+            notes,
+            history,
+            comments,
+            events,
+        })
+    }
+
+    /// 检查数据库可连接
+    pub async fn ping(&self) -> Result<()> {
+        crate::utils::storage::ping_mongo(&self.responses).await
+    }
+
+    pub async fn put(&self, entry: &ArchiveEntry, body: &[u8]) -> Result<()> {
+        self.responses
+            .insert_one(ArchiveDoc {
+                id: entry.id.clone(),
+                endpoint: entry.endpoint.clone(),
+                fetched_at: entry.fetched_at,
+                raw_size: entry.raw_size as i64,
+                stored_size: entry.stored_size as i64,
+                dict_id: entry.dict_id.map(i64::from),
+                body: binary(body),
+            })
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Option<(ArchiveEntry, Vec<u8>)>> {
+        Ok(self.responses
+            .find_one(doc! { "_id": id })
+            .await?
+            .map(|doc| (doc.entry(), doc.body.bytes)))
+    }
+
+    pub async fn recent(&self, endpoint: Option<&str>, limit: usize) -> Result<Vec<ArchiveEntry>> {
+        let filter = match endpoint {
+            Some(endpoint) => doc! { "endpoint": endpoint },
+            None => doc! {},
+        };
+        let mut cursor = self.responses
+            .find(filter)
+            .sort(doc! { "fetched_at": -1 })
+            .limit(limit as i64)
+            .await?;
+
+        let mut entries = Vec::new();
+        while cursor.advance().await? {
+            entries.push(cursor.deserialize_current()?.entry());
+        }
+        Ok(entries)
+    }
+
+    pub async fn load_dictionaries(&self) -> Result<Vec<Vec<u8>>> {
+        let mut cursor = self.dictionaries.find(doc! {}).sort(doc! { "created_at": 1 }).await?;
+        let mut dictionaries = Vec::new();
+        while cursor.advance().await? {
+            dictionaries.push(cursor.deserialize_current()?.data.bytes);
+        }
+        Ok(dictionaries)
+    }
+
+    pub async fn save_dictionary(&self, dict_id: u32, dictionary: &[u8]) -> Result<()> {
+        self.dictionaries
+            .insert_one(DictionaryDoc {
+                id: dict_id as i64,
+                created_at: chrono::Utc::now().timestamp_millis(),
+                data: binary(dictionary),
+            })
+            .await?;
+        Ok(())
+    }
+
+    pub async fn put_note(&self, note: &ArchivedNote) -> Result<()> {
+        self.notes
+            .replace_one(doc! { "note_id": &note.note_id }, note)
+            .upsert(true)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_note(&self, note_id: &str) -> Result<Option<ArchivedNote>> {
+        Ok(self.notes.find_one(doc! { "note_id": note_id }).await?)
+    }
+
+    pub async fn query_notes(&self, filter: &NoteFilter) -> Result<Vec<ArchivedNote>> {
+        let mut cursor = self.notes
+            .find(note_query(filter))
+            .sort(doc! { "archived_at": -1 })
+            .skip(filter.offset as u64)
+            .limit(filter.limit as i64)
+            .await?;
+
+        let mut notes = Vec::new();
+        while cursor.advance().await? {
+            notes.push(cursor.deserialize_current()?);
+        }
+        Ok(notes)
+    }
+
+    pub async fn put_revision(&self, revision: &NoteRevision) -> Result<()> {
+        self.history.insert_one(revision).await?;
+        Ok(())
+    }
+
+    pub async fn query_revisions(&self, note_id: &str, filter: &HistoryFilter) -> Result<Vec<NoteRevision>> {
+        let mut query = doc! { "note_id": note_id };
+        let mut archived_at = Document::new();
+        if let Some(since) = filter.since {
+            archived_at.insert("$gte", since);
+        }
+        if let Some(until) = filter.until {
+            archived_at.insert("$lt", until);
+        }
+        if !archived_at.is_empty() {
+            query.insert("archived_at", archived_at);
+        }
+        if filter.edits_only {
+            query.insert("changes.field", doc! { "$in": ["title", "desc", "tags"] });
+        }
+
+        let mut cursor = self.history
+            .find(query)
+            .sort(doc! { "archived_at": -1 })
+            .limit(filter.limit as i64)
+            .await?;
+        let mut revisions = Vec::new();
+        while cursor.advance().await? {
+            revisions.push(cursor.deserialize_current()?);
+        }
+        revisions.reverse();
+        Ok(revisions)
+    }
+
+    pub async fn put_comments(&self, comments: &[NoteComment]) -> Result<()> {
+        for comment in comments {
+            self.comments
+                .replace_one(doc! { "comment_id": &comment.comment_id }, comment)
+                .upsert(true)
+                .await?;
+        }
+        Ok(())
+    }
+
+    pub async fn query_comments(&self, note_id: &str, filter: &CommentFilter) -> Result<Vec<NoteComment>> {
+        let mut query = doc! { "note_id": note_id };
+        if let Some(root_comment_id) = &filter.root_comment_id {
+            query.insert("root_comment_id", root_comment_id);
+        }
+        let mut cursor = self.comments
+            .find(query)
+            .sort(doc! { "create_time": 1 })
+            .skip(filter.offset as u64)
+            .limit(filter.limit as i64)
+            .await?;
+        let mut comments = Vec::new();
+        while cursor.advance().await? {
+            comments.push(cursor.deserialize_current()?);
+        }
+        Ok(comments)
+    }
+
+    /// 有归档评论的笔记
+    pub async fn comment_note_ids(&self) -> Result<Vec<String>> {
+        let mut note_ids: Vec<String> = self.comments
+            .distinct("note_id", doc! {})
+            .await?
+            .into_iter()
+            .filter_map(|id| id.as_str().map(str::to_string))
+            .collect();
+        note_ids.sort();
+        Ok(note_ids)
+    }
+
+    pub async fn put_events(&self, events: &[NoteEvent]) -> Result<()> {
+        self.events.insert_many(events).await?;
+        Ok(())
+    }
+
+    pub async fn query_events(&self, filter: &EventFilter) -> Result<Vec<NoteEvent>> {
+        let mut query = Document::new();
+        if let Some(user_id) = &filter.user_id {
+            query.insert("user_id", user_id);
+        }
+        if let Some(kind) = filter.kind {
+            query.insert("kind", mongodb::bson::to_bson(&kind)?);
+        }
+        let mut detected_at = Document::new();
+        if let Some(since) = filter.since {
+            detected_at.insert("$gte", since);
+        }
+        if let Some(until) = filter.until {
+            detected_at.insert("$lt", until);
+        }
+        if !detected_at.is_empty() {
+            query.insert("detected_at", detected_at);
+        }
+
+        let mut cursor = self.events
+            .find(query)
+            .sort(doc! { "detected_at": -1 })
+            .limit(filter.limit as i64)
+            .await?;
+        let mut events = Vec::new();
+        while cursor.advance().await? {
+            events.push(cursor.deserialize_current()?);
+        }
+        Ok(events)
+    }
+}
+
+/// NoteFilter -> Mongo 查询条件（与 [`NoteFilter::matches`] 语义一致）
+fn note_query(filter: &NoteFilter) -> Document {
+    let mut query = Document::new();
+    if let Some(user_id) = &filter.user_id {
+        query.insert("detail.author.user_id", user_id);
+    }
+    if let Some(note_type) = &filter.note_type {
+        query.insert("detail.note_type", note_type);
+    }
+    if let Some(tag) = &filter.tag {
+        query.insert("detail.tags", tag);
+    }
+    if let Some(keyword) = &filter.keyword {
+        let pattern = regex_escape(keyword);
+        query.insert("$or", vec![
+            doc! { "detail.title": { "$regex": &pattern } },
+            doc! { "detail.desc": { "$regex": &pattern } },
+        ]);
+    }
+    let mut archived_at = Document::new();
+    if let Some(since) = filter.since {
+        archived_at.insert("$gte", since);
+    }
+    if let Some(until) = filter.until {
+        archived_at.insert("$lt", until);
+    }
+    if !archived_at.is_empty() {
+        query.insert("archived_at", archived_at);
+    }
+    query
+}
+
+/// 关键词按字面匹配
+fn regex_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if "\\.^$|?*+()[]{}".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn binary(bytes: &[u8]) -> Binary {
+    Binary { subtype: BinarySubtype::Generic, bytes: bytes.to_vec() }
+}
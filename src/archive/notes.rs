@@ -0,0 +1,94 @@
+//! 笔记归档 (Note Archive)
+//!
+//! 与原始响应归档共用后端，按 note_id 保存笔记的结构化详情与媒体地址：
+//! 同一笔记重复归档时覆盖为最新快照，保留首次归档时间与归档次数。
+//! 笔记记录体积小且需要按条件查询，不做压缩。
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::api::media::images::{images_from_note_card, ImageItem};
+use crate::api::media::sidecar::NoteMetadata;
+use crate::api::media::video::{videos_from_note_card, VideoItem};
+
+/// 查询默认返回条数
+pub const DEFAULT_NOTE_LIMIT: usize = 50;
+
+/// 查询最大返回条数
+pub const MAX_NOTE_LIMIT: usize = 500;
+
+/// 归档的笔记
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ArchivedNote {
+    pub note_id: String,
+    /// 归档时使用的 xsec_token（便于之后重新抓取）
+    pub xsec_token: String,
+    /// 结构化详情（标题、正文、作者、标签、发布时间、互动数据）
+    pub detail: NoteMetadata,
+    /// 图片地址（含实况图动态视频）
+    #[serde(default)]
+    pub images: Vec<ImageItem>,
+    /// 视频地址（按画质降序）
+    #[serde(default)]
+    pub videos: Vec<VideoItem>,
+    /// 首次归档时间 (ms)
+    pub first_archived_at: i64,
+    /// 最近一次归档时间 (ms)
+    pub archived_at: i64,
+    /// 归档次数
+    #[serde(default)]
+    pub archive_count: u32,
+}
+
+impl ArchivedNote {
+    /// 从详情接口的 note_card 构建（时间戳由调用方填写）
+    pub fn from_note_card(
+        note_id: &str,
+        xsec_token: &str,
+        note_card: &serde_json::Value,
+    ) -> crate::error::Result<Self> {
+        Ok(Self {
+            note_id: note_id.to_string(),
+            xsec_token: xsec_token.to_string(),
+            detail: NoteMetadata::from_note_card(note_id, note_card)?,
+            images: images_from_note_card(note_card),
+            videos: videos_from_note_card(note_card),
+            first_archived_at: 0,
+            archived_at: 0,
+            archive_count: 0,
+        })
+    }
+}
+
+/// 笔记归档查询条件（均为可选，同时给出时取交集）
+#[derive(Debug, Clone, Default)]
+pub struct NoteFilter {
+    /// 作者 user_id
+    pub user_id: Option<String>,
+    /// 笔记类型 (normal / video)
+    pub note_type: Option<String>,
+    /// 包含该话题标签
+    pub tag: Option<String>,
+    /// 标题或正文包含该关键词
+    pub keyword: Option<String>,
+    /// 最近归档时间下限 (ms, 含)
+    pub since: Option<i64>,
+    /// 最近归档时间上限 (ms, 不含)
+    pub until: Option<i64>,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+impl NoteFilter {
+    /// 文件后端逐条匹配使用
+    pub fn matches(&self, note: &ArchivedNote) -> bool {
+        self.user_id.as_ref().is_none_or(|id| note.detail.author.user_id == *id)
+            && self.note_type.as_ref().is_none_or(|t| note.detail.note_type == *t)
+            && self.tag.as_ref().is_none_or(|tag| note.detail.tags.contains(tag))
+            && self.keyword.as_ref().is_none_or(|kw| {
+                note.detail.title.contains(kw.as_str()) || note.detail.desc.contains(kw.as_str())
+            })
+            && self.since.is_none_or(|since| note.archived_at >= since)
+            && self.until.is_none_or(|until| note.archived_at < until)
+    }
+}
@@ -0,0 +1,134 @@
+//! 文件审计日志后端
+//!
+//! 按日期分文件追加写入:
+//! ```text
+//! {XHS_AUDIT_DIR}/
+//!   20260101.jsonl    每行一条 AuditRecord
+//! ```
+//! 过期清理按整天删除文件。
+
+use anyhow::Result;
+use chrono::NaiveDate;
+use std::path::PathBuf;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+use super::{AuditFilter, AuditRecord};
+
+pub struct FileBackend {
+    root: PathBuf,
+    /// 串行追加，避免并发写入的行交错
+    lock: Mutex<()>,
+}
+
+impl FileBackend {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root, lock: Mutex::new(()) }
+    }
+
+    /// 检查目录可写
+    pub async fn ping(&self) -> Result<()> {
+        crate::utils::storage::check_dir(&self.root).await
+    }
+
+    /// 新记录 ID
+    pub fn new_id(sent_at: i64) -> String {
+        format!("{}-{}", day_of(sent_at), uuid::Uuid::new_v4().simple())
+    }
+
+    pub async fn append(&self, record: &AuditRecord) -> Result<()> {
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+        let _guard = self.lock.lock().await;
+        tokio::fs::create_dir_all(&self.root).await?;
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.root.join(format!("{}.jsonl", day_of(record.sent_at))))
+            .await?;
+        file.write_all(line.as_bytes()).await?;
+        Ok(())
+    }
+
+    /// 按日期倒序扫描，返回最近的匹配记录
+    pub async fn query(&self, filter: &AuditFilter) -> Result<Vec<AuditRecord>> {
+        let since = filter.since.map(day_of);
+        let until = filter.until.map(day_of);
+        let mut records = Vec::new();
+        for day in self.days().await?.into_iter().rev() {
+            if since.as_ref().is_some_and(|s| &day < s) {
+                break;
+            }
+            if until.as_ref().is_some_and(|u| &day > u) {
+                continue;
+            }
+            let mut matched: Vec<AuditRecord> = self.read_day(&day).await?
+                .into_iter()
+                .filter(|r| filter.matches(r))
+                .collect();
+            matched.reverse();
+            matched.sort_by_key(|r| std::cmp::Reverse(r.sent_at));
+            records.extend(matched);
+            if records.len() >= filter.limit {
+                break;
+            }
+        }
+        records.truncate(filter.limit);
+        Ok(records)
+    }
+
+    /// 删除 `cutoff` 所在日期之前的文件，返回删除的记录数
+    pub async fn prune(&self, cutoff: i64) -> Result<u64> {
+        let cutoff = day_of(cutoff);
+        let _guard = self.lock.lock().await;
+        let mut removed = 0;
+        for day in self.days().await? {
+            if day >= cutoff {
+                break;
+            }
+            let path = self.root.join(format!("{}.jsonl", day));
+            let content = tokio::fs::read_to_string(&path).await?;
+            removed += content.lines().filter(|l| !l.trim().is_empty()).count() as u64;
+            tokio::fs::remove_file(&path).await?;
+        }
+        Ok(removed)
+    }
+
+    /// 已有的日期 (yyyymmdd)，升序
+    async fn days(&self) -> Result<Vec<String>> {
+        let mut days = Vec::new();
+        if let Ok(mut dir) = tokio::fs::read_dir(&self.root).await {
+            while let Some(item) = dir.next_entry().await? {
+                let name = item.file_name().to_string_lossy().to_string();
+                if let Some(day) = name.strip_suffix(".jsonl") {
+                    if NaiveDate::parse_from_str(day, "%Y%m%d").is_ok() {
+                        days.push(day.to_string());
+                    }
+                }
+            }
+        }
+        days.sort_unstable();
+        Ok(days)
+    }
+
+    async fn read_day(&self, day: &str) -> Result<Vec<AuditRecord>> {
+        let content = match tokio::fs::read_to_string(self.root.join(format!("{}.jsonl", day))).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(content
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .filter_map(|l| serde_json::from_str(l).ok())
+            .collect())
+    }
+}
+
+/// 时间戳 (ms) 所在日期 (UTC, yyyymmdd)
+fn day_of(ms: i64) -> String {
+    chrono::DateTime::from_timestamp_millis(ms)
+        .unwrap_or_default()
+        .format("%Y%m%d")
+        .to_string()
+}
@@ -0,0 +1,357 @@
+//! 上游请求审计日志 (Request Audit Log)
+//!
+//! 记录 `XhsApiClient` 发出的每个签名请求（含重试与拟人模式的浏览动作）的元数据：
+//! 接口、状态码、耗时、账号、签名方式、错误类型与截断后的响应体，
+//! 用于排查风控 (461 / 300012) 出现前后的请求规律。写入在后台进行，不阻塞请求。
+//!
+//! 配置 (环境变量):
+//! - `XHS_AUDIT_BACKEND`: `off` (默认) / `file` / `mongo`
+//! - `XHS_AUDIT_DIR`: 文件后端目录，默认 ./audit
+//! - `XHS_AUDIT_MONGO_URI`: Mongo 后端连接串，默认复用 XHS_COORD_MONGO_URI
+//! - `XHS_AUDIT_DB`: Mongo 数据库名，默认 xhs_audit
+//! - `XHS_AUDIT_RETENTION_DAYS`: 保留天数，默认 7，0 为永久保留
+//! - `XHS_AUDIT_BODY_BYTES`: 响应体保留的最大字节数，默认 2048，0 为不保存响应体
+
+pub mod file;
+#[cfg(feature = "mongodb")]
+pub mod mongo;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use utoipa::ToSchema;
+
+use file::FileBackend;
+#[cfg(feature = "mongodb")]
+use mongo::MongoBackend;
+
+/// 查询默认返回条数
+pub const DEFAULT_AUDIT_LIMIT: usize = 100;
+
+/// 查询最大返回条数
+pub const MAX_AUDIT_LIMIT: usize = 1000;
+
+/// 过期记录的清理间隔
+const PRUNE_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// 审计日志存储
+#[derive(Debug, Clone)]
+pub enum AuditStorage {
+    File { dir: PathBuf },
+    #[cfg(feature = "mongodb")]
+    Mongo { uri: String, database: String },
+}
+
+/// 审计日志配置
+#[derive(Debug, Clone)]
+pub struct AuditConfig {
+    pub storage: AuditStorage,
+    /// 保留天数，0 为永久保留
+    pub retention_days: u32,
+    /// 响应体保留的最大字节数
+    pub body_limit: usize,
+}
+
+impl AuditConfig {
+    /// 从环境变量读取，未启用时返回 None
+    pub fn from_env() -> Result<Option<Self>> {
+        let backend = std::env::var("XHS_AUDIT_BACKEND").unwrap_or_else(|_| "off".to_string());
+        let storage = match backend.as_str() {
+            "" | "off" => return Ok(None),
+            "file" => AuditStorage::File {
+                dir: std::env::var("XHS_AUDIT_DIR")
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|_| PathBuf::from("./audit")),
+            },
+            #[cfg(feature = "mongodb")]
+            "mongo" => {
+                let uri = std::env::var("XHS_AUDIT_MONGO_URI")
+                    .or_else(|_| std::env::var("XHS_COORD_MONGO_URI"))
+                    .map_err(|_| anyhow!("XHS_AUDIT_BACKEND=mongo requires XHS_AUDIT_MONGO_URI"))?;
+                let database = std::env::var("XHS_AUDIT_DB").unwrap_or_else(|_| "xhs_audit".to_string());
+                AuditStorage::Mongo { uri, database }
+            }
+            #[cfg(not(feature = "mongodb"))]
+            "mongo" => return Err(anyhow!("XHS_AUDIT_BACKEND=mongo requires the `mongodb` cargo feature")),
+            other => return Err(anyhow!("Unknown XHS_AUDIT_BACKEND: {}", other)),
+        };
+        let retention_days = std::env::var("XHS_AUDIT_RETENTION_DAYS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(7);
+        let body_limit = std::env::var("XHS_AUDIT_BODY_BYTES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(2048);
+        Ok(Some(Self { storage, retention_days, body_limit }))
+    }
+}
+
+/// 请求使用的签名方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureMode {
+    /// Python Agent 纯算法签名
+    Algo,
+    /// 浏览器捕获的存储签名
+    Stored,
+}
+
+impl SignatureMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SignatureMode::Algo => "algo",
+            SignatureMode::Stored => "stored",
+        }
+    }
+}
+
+/// 一条审计记录
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AuditRecord {
+    pub id: String,
+    /// 发出时间 (ms)
+    pub sent_at: i64,
+    pub method: String,
+    /// 接口路径（不含查询参数）
+    pub endpoint: String,
+    /// HTTP 状态码（网络错误时为空）
+    #[serde(default)]
+    pub status: Option<u16>,
+    /// 响应 JSON 中的业务码
+    #[serde(default)]
+    pub code: Option<i64>,
+    /// 耗时 (ms)，含读取响应体
+    pub duration_ms: u64,
+    /// 发出请求的账号 user_id
+    pub account: String,
+    pub signature: SignatureMode,
+    /// 失败时的错误类型（同错误响应的 error_kind）
+    #[serde(default)]
+    pub error_kind: Option<String>,
+    /// 响应体大小 (字节)
+    #[serde(default)]
+    pub body_size: u64,
+    /// 截断后的响应体（失败且无响应时为错误信息）
+    #[serde(default)]
+    pub body: Option<String>,
+    /// 响应体是否被截断
+    #[serde(default)]
+    pub truncated: bool,
+    /// 触发该上游请求的 HTTP 请求 ID（x-request-id，后台任务发出时为空）
+    #[serde(default)]
+    pub request_id: Option<String>,
+}
+
+impl AuditRecord {
+    /// 请求发出前创建，响应后由 [`AuditRecord::respond`] / [`AuditRecord::finish`] 补全
+    pub fn start(method: &str, endpoint: &str, account: &str, signature: SignatureMode) -> Self {
+        let sent_at = chrono::Utc::now().timestamp_millis();
+        Self {
+            id: FileBackend::new_id(sent_at),
+            sent_at,
+            method: method.to_string(),
+            endpoint: endpoint.to_string(),
+            status: None,
+            code: None,
+            duration_ms: 0,
+            account: account.to_string(),
+            signature,
+            error_kind: None,
+            body_size: 0,
+            body: None,
+            truncated: false,
+            request_id: crate::response::current_request_id(),
+        }
+    }
+
+    /// 记录状态码与响应体（保留前 `body_limit` 字节）
+    pub fn respond(&mut self, status: u16, body: &str, body_limit: usize) {
+        self.status = Some(status);
+        self.code = serde_json::from_str::<serde_json::Value>(body)
+            .ok()
+            .and_then(|v| v.get("code").and_then(|c| c.as_i64()));
+        self.body_size = body.len() as u64;
+        if body_limit > 0 && !body.is_empty() {
+            let mut end = body.len().min(body_limit);
+            while !body.is_char_boundary(end) {
+                end -= 1;
+            }
+            self.body = Some(body[..end].to_string());
+            self.truncated = end < body.len();
+        }
+    }
+
+    /// 记录耗时与结果
+    pub fn finish(mut self, elapsed: Duration, error: Option<&crate::error::XhsError>) -> Self {
+        self.duration_ms = elapsed.as_millis() as u64;
+        if let Some(error) = error {
+            self.error_kind = Some(error.kind().to_string());
+            if self.status.is_none() {
+                self.body = Some(error.to_string());
+            }
+        }
+        self
+    }
+}
+
+/// 审计记录查询条件
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilter {
+    /// 接口路径
+    pub endpoint: Option<String>,
+    /// 账号 user_id
+    pub account: Option<String>,
+    /// 触发上游请求的 HTTP 请求 ID
+    pub request_id: Option<String>,
+    /// HTTP 状态码
+    pub status: Option<u16>,
+    /// 只返回失败的请求
+    pub errors_only: bool,
+    /// 发出时间下限 (ms, 含)
+    pub since: Option<i64>,
+    /// 发出时间上限 (ms, 不含)
+    pub until: Option<i64>,
+    /// 返回最近的条数
+    pub limit: usize,
+}
+
+impl AuditFilter {
+    /// 文件后端逐条匹配使用
+    pub fn matches(&self, record: &AuditRecord) -> bool {
+        self.endpoint.as_ref().is_none_or(|e| &record.endpoint == e)
+            && self.account.as_ref().is_none_or(|a| &record.account == a)
+            && self.request_id.as_ref().is_none_or(|id| record.request_id.as_ref() == Some(id))
+            && self.status.is_none_or(|s| record.status == Some(s))
+            && (!self.errors_only || record.error_kind.is_some())
+            && self.since.is_none_or(|since| record.sent_at >= since)
+            && self.until.is_none_or(|until| record.sent_at < until)
+    }
+}
+
+enum AuditBackend {
+    File(FileBackend),
+    #[cfg(feature = "mongodb")]
+    Mongo(MongoBackend),
+}
+
+/// 上游请求审计日志
+pub struct AuditLog {
+    backend: AuditBackend,
+    retention_days: u32,
+    body_limit: usize,
+    description: String,
+}
+
+impl AuditLog {
+    pub async fn open(config: &AuditConfig) -> Result<Self> {
+        let (backend, description) = match &config.storage {
+            AuditStorage::File { dir } => {
+                (AuditBackend::File(FileBackend::new(dir.clone())), format!("file: {}", dir.display()))
+            }
+            #[cfg(feature = "mongodb")]
+            AuditStorage::Mongo { uri, database } => {
+                (AuditBackend::Mongo(MongoBackend::connect(uri, database).await?), format!("mongo: {}", database))
+            }
+        };
+        tracing::info!(
+            "[Audit] Recording upstream requests ({}, retention {} day(s), body {} bytes)",
+            description,
+            config.retention_days,
+            config.body_limit
+        );
+        Ok(Self {
+            backend,
+            retention_days: config.retention_days,
+            body_limit: config.body_limit,
+            description,
+        })
+    }
+
+    /// 按环境变量打开，未启用时返回 None
+    pub async fn from_env() -> Result<Option<Self>> {
+        match AuditConfig::from_env()? {
+            Some(config) => Ok(Some(Self::open(&config).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// 后端描述，如 "file: ./audit"
+    pub fn describe(&self) -> &str {
+        &self.description
+    }
+
+    /// 检查后端可用（就绪探针）
+    pub async fn ping(&self) -> Result<()> {
+        match &self.backend {
+            AuditBackend::File(b) => b.ping().await,
+            #[cfg(feature = "mongodb")]
+            AuditBackend::Mongo(b) => b.ping().await,
+        }
+    }
+
+    /// 响应体保留的最大字节数
+    pub fn body_limit(&self) -> usize {
+        self.body_limit
+    }
+
+    /// 保存一条记录
+    pub async fn store(&self, record: &AuditRecord) -> Result<()> {
+        match &self.backend {
+            AuditBackend::File(b) => b.append(record).await,
+            #[cfg(feature = "mongodb")]
+            AuditBackend::Mongo(b) => b.insert(record).await,
+        }
+    }
+
+    /// 后台保存一条记录，失败只记日志
+    pub fn record(self: &Arc<Self>, record: AuditRecord) {
+        let log = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = log.store(&record).await {
+                tracing::warn!("[Audit] Failed to record {} {}: {}", record.method, record.endpoint, e);
+            }
+        });
+    }
+
+    /// 按条件查询，返回最近的 `limit` 条（新的在前）
+    pub async fn query(&self, filter: &AuditFilter) -> Result<Vec<AuditRecord>> {
+        match &self.backend {
+            AuditBackend::File(b) => b.query(filter).await,
+            #[cfg(feature = "mongodb")]
+            AuditBackend::Mongo(b) => b.query(filter).await,
+        }
+    }
+
+    /// 删除超过保留天数的记录，返回删除条数
+    pub async fn prune(&self) -> Result<u64> {
+        if self.retention_days == 0 {
+            return Ok(0);
+        }
+        let cutoff = chrono::Utc::now().timestamp_millis() - self.retention_days as i64 * 86_400_000;
+        match &self.backend {
+            AuditBackend::File(b) => b.prune(cutoff).await,
+            #[cfg(feature = "mongodb")]
+            AuditBackend::Mongo(b) => b.prune(cutoff).await,
+        }
+    }
+}
+
+/// 后台每小时清理一次过期记录（启动时先清理一次）
+pub fn spawn_pruner(log: Arc<AuditLog>) {
+    if log.retention_days == 0 {
+        return;
+    }
+    tokio::spawn(async move {
+        loop {
+            match log.prune().await {
+                Ok(0) => {}
+                Ok(removed) => tracing::info!("[Audit] Pruned {} expired record(s)", removed),
+                Err(e) => tracing::warn!("[Audit] Failed to prune expired records: {}", e),
+            }
+            tokio::time::sleep(PRUNE_INTERVAL).await;
+        }
+    });
+}
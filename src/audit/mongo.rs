@@ -0,0 +1,95 @@
+//! MongoDB 审计日志后端
+//!
+//! `audit_log` 集合每条请求一个文档，只追加；过期清理按 `sent_at` 删除。
+
+use anyhow::{anyhow, Result};
+use mongodb::bson::{doc, Document};
+use mongodb::options::IndexOptions;
+use mongodb::{Collection, IndexModel};
+
+use super::{AuditFilter, AuditRecord};
+
+pub struct MongoBackend {
+    records: Collection<AuditRecord>,
+}
+
+impl MongoBackend {
+    pub async fn connect(uri: &str, database: &str) -> Result<Self> {
+        let client = crate::utils::storage::mongo_client(uri)
+            .await
+            .map_err(|e| anyhow!("Failed to connect to audit MongoDB: {}", e))?;
+        let records: Collection<AuditRecord> = client.database(database).collection("audit_log");
+        records
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "sent_at": -1 })
+                    .options(IndexOptions::builder().name("sent_at".to_string()).build())
+                    .build(),
+            )
+            .await?;
+        records
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "endpoint": 1, "sent_at": -1 })
+                    .options(IndexOptions::builder().name("endpoint_sent_at".to_string()).build())
+                    .build(),
+            )
+            .await?;
+        Ok(Self { records })
+    }
+
+    /// 检查数据库可连接
+    pub async fn ping(&self) -> Result<()> {
+        crate::utils::storage::ping_mongo(&self.records).await
+    }
+
+    pub async fn insert(&self, record: &AuditRecord) -> Result<()> {
+        self.records.insert_one(record).await?;
+        Ok(())
+    }
+
+    pub async fn query(&self, filter: &AuditFilter) -> Result<Vec<AuditRecord>> {
+        let mut query = Document::new();
+        if let Some(endpoint) = &filter.endpoint {
+            query.insert("endpoint", endpoint);
+        }
+        if let Some(account) = &filter.account {
+            query.insert("account", account);
+        }
+        if let Some(request_id) = &filter.request_id {
+            query.insert("request_id", request_id);
+        }
+        if let Some(status) = filter.status {
+            query.insert("status", status as i32);
+        }
+        if filter.errors_only {
+            query.insert("error_kind", doc! { "$ne": null });
+        }
+        let mut sent_at = Document::new();
+        if let Some(since) = filter.since {
+            sent_at.insert("$gte", since);
+        }
+        if let Some(until) = filter.until {
+            sent_at.insert("$lt", until);
+        }
+        if !sent_at.is_empty() {
+            query.insert("sent_at", sent_at);
+        }
+
+        let mut cursor = self.records
+            .find(query)
+            .sort(doc! { "sent_at": -1 })
+            .limit(filter.limit as i64)
+            .await?;
+        let mut records = Vec::new();
+        while cursor.advance().await? {
+            records.push(cursor.deserialize_current()?);
+        }
+        Ok(records)
+    }
+
+    pub async fn prune(&self, cutoff: i64) -> Result<u64> {
+        let result = self.records.delete_many(doc! { "sent_at": { "$lt": cutoff } }).await?;
+        Ok(result.deleted_count)
+    }
+}
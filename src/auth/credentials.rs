@@ -2,6 +2,8 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use super::fingerprint::BrowserFingerprint;
+
 /// User credentials captured from browser login
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserCredentials {
@@ -24,12 +26,17 @@ pub struct UserCredentials {
     
     /// Whether these credentials are currently valid
     pub is_valid: bool,
+    
+    /// Browser fingerprint pinned to this account (assigned on first save)
+    #[serde(default)]
+    pub fingerprint: Option<BrowserFingerprint>,
 }
 
 impl UserCredentials {
     /// Create new credentials
     pub fn new(user_id: String, cookies: HashMap<String, String>, x_s_common: Option<String>) -> Self {
         let now = Utc::now();
+        let fingerprint = BrowserFingerprint::for_account(&user_id, &cookies);
         Self {
             user_id,
             cookies,
@@ -37,16 +44,34 @@ impl UserCredentials {
             created_at: now,
             updated_at: now,
             is_valid: true,
+            fingerprint: Some(fingerprint),
         }
     }
     
+    /// Browser fingerprint for requests (derived from the cookies for credentials saved before fingerprints existed)
+    pub fn fingerprint(&self) -> BrowserFingerprint {
+        self.fingerprint
+            .clone()
+            .unwrap_or_else(|| BrowserFingerprint::for_account(&self.user_id, &self.cookies))
+    }
+    
     /// Get cookies as a single string for HTTP headers
+    ///
+    /// `a1` / `webId` missing from the cookie jar are filled in from the fingerprint,
+    /// so the device id always matches the one the account logged in with.
     pub fn cookie_string(&self) -> String {
-        self.cookies
+        let mut pairs: Vec<String> = self.cookies
             .iter()
             .map(|(k, v)| format!("{}={}", k, v))
-            .collect::<Vec<_>>()
-            .join("; ")
+            .collect();
+        if let Some(fingerprint) = &self.fingerprint {
+            for (key, value) in [("a1", &fingerprint.a1), ("webId", &fingerprint.web_id)] {
+                if let (false, Some(value)) = (self.cookies.contains_key(key), value) {
+                    pairs.push(format!("{}={}", key, value));
+                }
+            }
+        }
+        pairs.join("; ")
     }
     
     /// Check if credentials might be expired (older than 7 days)
@@ -68,7 +93,7 @@ impl UserCredentials {
     }
 }
 
-/// API endpoint signature (legacy, kept for compatibility)
+/// Signature captured from a browser request (stored-signature fallback, imported from a HAR)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiSignature {
     /// Endpoint name (e.g., "user_me", "search_trending")
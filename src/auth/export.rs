@@ -0,0 +1,113 @@
+//! Credential export
+//!
+//! Renders stored credentials into formats understood by other tools:
+//! a `Cookie` header string, Netscape `cookies.txt`, or Playwright `storage_state` JSON.
+
+use chrono::Duration;
+use serde::Deserialize;
+
+use super::credentials::UserCredentials;
+
+/// Cookie domain used for exported cookies
+const COOKIE_DOMAIN: &str = ".xiaohongshu.com";
+
+/// Exported cookies expire this long after the credentials were last updated
+/// (matches `UserCredentials::is_potentially_expired`)
+const EXPORT_TTL_DAYS: i64 = 7;
+
+/// Supported export formats
+#[derive(Debug, Clone, Copy, Default, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    /// `a1=xxx; web_session=xxx` (for a `Cookie` request header)
+    #[default]
+    Header,
+    /// Netscape cookies.txt (curl, yt-dlp, browser extensions)
+    Netscape,
+    /// Playwright `storage_state` JSON
+    Playwright,
+}
+
+impl ExportFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExportFormat::Header => "header",
+            ExportFormat::Netscape => "netscape",
+            ExportFormat::Playwright => "playwright",
+        }
+    }
+}
+
+/// Render credentials in the requested format
+///
+/// When `masked` is set, every cookie value is partially hidden so the output
+/// can be shared (e.g. in bug reports) without leaking the session.
+pub fn export_credentials(creds: &UserCredentials, format: ExportFormat, masked: bool) -> String {
+    let cookies = sorted_cookies(creds, masked);
+    let expires = (creds.updated_at + Duration::days(EXPORT_TTL_DAYS)).timestamp();
+
+    match format {
+        ExportFormat::Header => cookies
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect::<Vec<_>>()
+            .join("; "),
+        ExportFormat::Netscape => {
+            let mut out = String::from("# Netscape HTTP Cookie File\n");
+            for (k, v) in &cookies {
+                out.push_str(&format!(
+                    "{}\tTRUE\t/\tTRUE\t{}\t{}\t{}\n",
+                    COOKIE_DOMAIN, expires, k, v
+                ));
+            }
+            out
+        }
+        ExportFormat::Playwright => {
+            let cookies: Vec<serde_json::Value> = cookies
+                .iter()
+                .map(|(k, v)| {
+                    serde_json::json!({
+                        "name": k,
+                        "value": v,
+                        "domain": COOKIE_DOMAIN,
+                        "path": "/",
+                        "expires": expires,
+                        "httpOnly": false,
+                        "secure": true,
+                        "sameSite": "Lax"
+                    })
+                })
+                .collect();
+            let state = serde_json::json!({
+                "cookies": cookies,
+                "origins": []
+            });
+            serde_json::to_string_pretty(&state).unwrap_or_default()
+        }
+    }
+}
+
+/// Cookies sorted by name (stable output), optionally masked
+fn sorted_cookies(creds: &UserCredentials, masked: bool) -> Vec<(String, String)> {
+    let mut cookies: Vec<(String, String)> = creds
+        .cookies
+        .iter()
+        .map(|(k, v)| {
+            let value = if masked { mask_value(v) } else { v.clone() };
+            (k.clone(), value)
+        })
+        .collect();
+    cookies.sort_by(|a, b| a.0.cmp(&b.0));
+    cookies
+}
+
+/// Keep the first and last 4 characters, hide the rest
+pub fn mask_value(value: &str) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    if chars.len() <= 8 {
+        return "*".repeat(chars.len());
+    }
+    let head: String = chars[..4].iter().collect();
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    format!("{}{}{}", head, "*".repeat(chars.len() - 8), tail)
+}
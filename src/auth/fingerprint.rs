@@ -0,0 +1,130 @@
+//! Per-account browser fingerprint
+//!
+//! XHS correlates the user-agent / client hints of every request with the
+//! session that logged in. Sending the same hard-coded Chrome fingerprint from
+//! every account (or switching fingerprints mid-session) is a risk-control
+//! signal, so each credential profile is pinned to one fingerprint: chosen
+//! from a small set of presets when the account is first saved and persisted
+//! alongside the cookies.
+
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use crate::utils::device::{DeviceIdentity, DevicePlatform};
+
+/// Browser identity sent with every request of one account
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BrowserFingerprint {
+    /// Preset the fingerprint was derived from
+    #[serde(default)]
+    pub preset: String,
+    pub user_agent: String,
+    /// `sec-ch-ua` header value
+    pub sec_ch_ua: String,
+    /// `sec-ch-ua-platform` header value (quoted, e.g. `"Windows"`)
+    pub platform: String,
+    /// `a1` device cookie the account logged in with
+    #[serde(default)]
+    pub a1: Option<String>,
+    /// `webId` cookie the account logged in with
+    #[serde(default, rename = "webId")]
+    pub web_id: Option<String>,
+}
+
+/// (name, user-agent, sec-ch-ua, platform)
+const PRESETS: &[(&str, &str, &str, &str)] = &[
+    (
+        "chrome-143-windows",
+        "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/143.0.0.0 Safari/537.36",
+        r#""Google Chrome";v="143", "Chromium";v="143", "Not A(Brand";v="24""#,
+        r#""Windows""#,
+    ),
+    (
+        "chrome-143-macos",
+        "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/143.0.0.0 Safari/537.36",
+        r#""Google Chrome";v="143", "Chromium";v="143", "Not A(Brand";v="24""#,
+        r#""macOS""#,
+    ),
+    (
+        "edge-143-windows",
+        "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/143.0.0.0 Safari/537.36 Edg/143.0.0.0",
+        r#""Microsoft Edge";v="143", "Chromium";v="143", "Not A(Brand";v="24""#,
+        r#""Windows""#,
+    ),
+    (
+        "chrome-142-windows",
+        "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/142.0.0.0 Safari/537.36",
+        r#""Chromium";v="142", "Google Chrome";v="142", "Not_A Brand";v="99""#,
+        r#""Windows""#,
+    ),
+];
+
+impl Default for BrowserFingerprint {
+    /// The Chrome 143 / Windows fingerprint used before per-account profiles existed
+    fn default() -> Self {
+        Self::from_preset(0)
+    }
+}
+
+impl BrowserFingerprint {
+    fn from_preset(index: usize) -> Self {
+        let (preset, user_agent, sec_ch_ua, platform) = PRESETS[index % PRESETS.len()];
+        Self {
+            preset: preset.to_string(),
+            user_agent: user_agent.to_string(),
+            sec_ch_ua: sec_ch_ua.to_string(),
+            platform: platform.to_string(),
+            a1: None,
+            web_id: None,
+        }
+    }
+
+    /// Names of the built-in presets
+    pub fn presets() -> Vec<&'static str> {
+        PRESETS.iter().map(|(name, ..)| *name).collect()
+    }
+
+    /// Look up a preset by name
+    pub fn preset(name: &str) -> Option<Self> {
+        PRESETS.iter().position(|(n, ..)| *n == name).map(Self::from_preset)
+    }
+
+    /// Pick a stable fingerprint for an account
+    ///
+    /// The preset is chosen from the device id (`a1`, falling back to the user id) so the
+    /// same device always maps to the same browser; `a1` / `webId` are copied from the cookies.
+    pub fn for_account(user_id: &str, cookies: &HashMap<String, String>) -> Self {
+        let seed = cookies.get("a1").map(String::as_str).unwrap_or(user_id);
+        let mut hasher = DefaultHasher::new();
+        seed.hash(&mut hasher);
+        let mut fingerprint = Self::from_preset(hasher.finish() as usize);
+        fingerprint.a1 = cookies.get("a1").cloned();
+        fingerprint.web_id = cookies.get("webId").cloned();
+        fingerprint
+    }
+
+    /// Device platform claimed by `sec-ch-ua-platform` (encoded into minted `a1` values)
+    pub fn device_platform(&self) -> DevicePlatform {
+        DevicePlatform::from_client_hint(&self.platform)
+    }
+
+    /// Pin a freshly minted `a1` / `webId` for this fingerprint's platform
+    pub fn with_new_device(self) -> Self {
+        let device = DeviceIdentity::new(self.device_platform());
+        self.with_device(device)
+    }
+
+    /// Pin a reproducible `a1` / `webId` derived from `seed` (e.g. a profile name) and creation time
+    pub fn with_seeded_device(self, seed: &str, created_ms: u64) -> Self {
+        let device = DeviceIdentity::from_seed(seed, created_ms, self.device_platform());
+        self.with_device(device)
+    }
+
+    fn with_device(mut self, device: DeviceIdentity) -> Self {
+        self.a1 = Some(device.a1);
+        self.web_id = Some(device.web_id);
+        self
+    }
+}
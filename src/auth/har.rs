@@ -0,0 +1,221 @@
+//! HAR import
+//!
+//! Parses a browser HAR export (DevTools → Network → "Save all as HAR") and extracts:
+//! - the cookies sent to `*.xiaohongshu.com` (later requests win)
+//! - per-endpoint signatures (`x-s` / `x-t` / `x-s-common` / trace ids) with the request URL
+//!   and body, keyed the same way as the stored-signature fallback in `XhsApiClient`
+//! - the logged-in user from a captured `/user/me` response, if any
+//!
+//! This seeds the stored-signature fallback without running the Playwright capture script.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use super::credentials::ApiSignature;
+
+/// Signed API host
+const API_HOST: &str = "edith.xiaohongshu.com";
+
+/// Fixed API paths and the endpoint keys the stored-signature fallback looks them up by
+const ENDPOINT_PATHS: &[(&str, &str)] = &[
+    ("/api/sns/web/v2/user/me", "user_me"),
+    ("/api/sns/web/v1/user/selfinfo", "user_selfinfo"),
+    ("/api/sns/web/v1/search/querytrending", "search_trending"),
+    ("/api/sns/web/v1/search/recommend", "search_recommend"),
+    ("/api/sns/web/v1/search/filter", "search_filter"),
+    ("/api/sns/web/v1/search/notes", "search_notes"),
+    ("/api/sns/web/v1/you/mentions", "notification_mentions"),
+    ("/api/sns/web/v1/you/connections", "notification_connections"),
+    ("/api/sns/web/v1/you/likes", "notification_likes"),
+    ("/api/sns/web/v2/comment/page", "note_page"),
+    ("/api/sns/web/v2/comment/sub/page", "note_sub_comments"),
+];
+
+#[derive(Debug, Deserialize)]
+pub struct Har {
+    pub log: HarLog,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HarLog {
+    #[serde(default)]
+    pub entries: Vec<HarEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarEntry {
+    #[serde(default)]
+    pub started_date_time: Option<DateTime<Utc>>,
+    pub request: HarRequest,
+    #[serde(default)]
+    pub response: Option<HarResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarRequest {
+    pub method: String,
+    pub url: String,
+    #[serde(default)]
+    pub headers: Vec<HarNameValue>,
+    #[serde(default)]
+    pub cookies: Vec<HarNameValue>,
+    #[serde(default)]
+    pub post_data: Option<HarPostData>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HarNameValue {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HarPostData {
+    #[serde(default)]
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HarResponse {
+    #[serde(default)]
+    pub content: Option<HarContent>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HarContent {
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub encoding: Option<String>,
+}
+
+/// What a HAR file yielded
+#[derive(Debug, Default)]
+pub struct HarImport {
+    /// Cookies sent to xiaohongshu.com
+    pub cookies: HashMap<String, String>,
+    /// Latest signature per endpoint key
+    pub signatures: Vec<ApiSignature>,
+    /// `user_id` from a logged-in `/user/me` response
+    pub user_id: Option<String>,
+    /// Number of HAR entries
+    pub entries: usize,
+    /// Signed API requests whose path has no endpoint key
+    pub unmapped: Vec<String>,
+}
+
+/// Extract cookies, signatures and the logged-in user from a HAR document
+pub fn parse_har(har: &Har) -> HarImport {
+    let mut import = HarImport { entries: har.log.entries.len(), ..Default::default() };
+    let mut signatures: HashMap<String, ApiSignature> = HashMap::new();
+
+    for entry in &har.log.entries {
+        let request = &entry.request;
+        let Ok(url) = url::Url::parse(&request.url) else {
+            continue;
+        };
+        let Some(host) = url.host_str() else {
+            continue;
+        };
+        if !host.ends_with("xiaohongshu.com") {
+            continue;
+        }
+
+        for cookie in &request.cookies {
+            import.cookies.insert(cookie.name.clone(), cookie.value.clone());
+        }
+        if let Some(header) = header(request, "cookie") {
+            import.cookies.extend(crate::signature::parse_cookie_string(header));
+        }
+
+        if host != API_HOST {
+            continue;
+        }
+        if url.path() == "/api/sns/web/v2/user/me" {
+            if let Some(user_id) = entry.response.as_ref().and_then(logged_in_user) {
+                import.user_id = Some(user_id);
+            }
+        }
+
+        let (Some(x_s), Some(x_t)) = (header(request, "x-s"), header(request, "x-t")) else {
+            continue;
+        };
+        let body = request.post_data.as_ref().and_then(|data| data.text.clone());
+        let Some(endpoint) = endpoint_key(url.path(), body.as_deref()) else {
+            if !import.unmapped.iter().any(|path| path == url.path()) {
+                import.unmapped.push(url.path().to_string());
+            }
+            continue;
+        };
+        let signature = ApiSignature {
+            endpoint: endpoint.clone(),
+            x_s: x_s.to_string(),
+            x_t: x_t.to_string(),
+            x_s_common: header(request, "x-s-common").unwrap_or_default().to_string(),
+            x_b3_traceid: header(request, "x-b3-traceid").unwrap_or_default().to_string(),
+            x_xray_traceid: header(request, "x-xray-traceid").unwrap_or_default().to_string(),
+            method: Some(request.method.to_uppercase()),
+            post_body: body,
+            request_url: Some(request.url.clone()),
+            captured_at: entry.started_date_time.unwrap_or_else(Utc::now),
+            is_valid: true,
+        };
+        match signatures.get(&endpoint) {
+            Some(existing) if existing.captured_at > signature.captured_at => {}
+            _ => {
+                signatures.insert(endpoint, signature);
+            }
+        }
+    }
+
+    import.cookies.retain(|name, _| !name.trim().is_empty());
+    import.signatures = signatures.into_values().collect();
+    import.signatures.sort_by(|a, b| a.endpoint.cmp(&b.endpoint));
+    import
+}
+
+/// Endpoint key for a signed request (home feed channels are told apart by the body's `category`)
+pub fn endpoint_key(path: &str, body: Option<&str>) -> Option<String> {
+    if path == "/api/sns/web/v1/homefeed" {
+        let body: serde_json::Value = serde_json::from_str(body?).ok()?;
+        let category = body.get("category")?.as_str()?;
+        return match category {
+            "homefeed_recommend" => Some("home_feed_recommend".to_string()),
+            other => other
+                .strip_prefix("homefeed.")
+                .and_then(|c| c.strip_suffix("_v3"))
+                .map(|c| format!("home_feed_{}", c)),
+        };
+    }
+    ENDPOINT_PATHS.iter().find(|(p, _)| *p == path).map(|(_, key)| key.to_string())
+}
+
+fn header<'a>(request: &'a HarRequest, name: &str) -> Option<&'a str> {
+    request.headers.iter()
+        .find(|h| h.name.eq_ignore_ascii_case(name))
+        .map(|h| h.value.as_str())
+        .filter(|v| !v.is_empty())
+}
+
+/// `user_id` from a `/user/me` response body, unless it is a guest session
+fn logged_in_user(response: &HarResponse) -> Option<String> {
+    let content = response.content.as_ref()?;
+    let text = content.text.as_deref()?;
+    let text = match content.encoding.as_deref() {
+        Some("base64") => {
+            use base64::Engine;
+            String::from_utf8(base64::engine::general_purpose::STANDARD.decode(text).ok()?).ok()?
+        }
+        _ => text.to_string(),
+    };
+    let value: serde_json::Value = serde_json::from_str(&text).ok()?;
+    let data = value.get("data")?;
+    if data.get("guest").and_then(|g| g.as_bool()).unwrap_or(false) {
+        return None;
+    }
+    data.get("user_id")?.as_str().filter(|id| !id.is_empty()).map(str::to_string)
+}
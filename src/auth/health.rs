@@ -0,0 +1,196 @@
+//! Credential health tracking
+//!
+//! Each `AuthService` owns a `HealthTracker` that records the outcome of
+//! real API requests (last success, recent 406/461 responses) and of the
+//! periodic background check, so operators can see when a re-login is
+//! needed before jobs start failing.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+#[cfg(feature = "server")]
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+use super::credentials::UserCredentials;
+#[cfg(feature = "server")]
+use crate::server::AppState;
+
+/// Window for counting recent 406/461 responses
+const RECENT_WINDOW_MINUTES: i64 = 60;
+
+/// Upper bound of remembered error events
+const MAX_RECENT_ERRORS: usize = 256;
+
+/// Result of the last background validity check
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct HealthCheck {
+    #[schema(value_type = String)]
+    pub checked_at: DateTime<Utc>,
+    pub ok: bool,
+    pub error: Option<String>,
+}
+
+/// Health snapshot of one account
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AccountHealth {
+    /// Account name ("user" / "creator")
+    pub account: String,
+    /// Whether credentials are stored and marked valid
+    pub logged_in: bool,
+    pub user_id: Option<String>,
+    /// Seconds since credentials were last updated
+    pub age_secs: Option<i64>,
+    /// Older than 7 days
+    pub potentially_expired: bool,
+    /// Last API request that returned 2xx
+    #[schema(value_type = Option<String>)]
+    pub last_success_at: Option<DateTime<Utc>>,
+    /// Last background check
+    pub last_check: Option<HealthCheck>,
+    /// 406 responses within the last hour
+    pub recent_406: usize,
+    /// 461 responses within the last hour
+    pub recent_461: usize,
+    /// Operator hint: re-login is recommended
+    pub needs_relogin: bool,
+}
+
+#[derive(Default)]
+struct HealthState {
+    last_success_at: Option<DateTime<Utc>>,
+    recent_errors: VecDeque<(DateTime<Utc>, u16)>,
+    last_check: Option<HealthCheck>,
+}
+
+/// Per-account request outcome tracker
+#[derive(Default)]
+pub struct HealthTracker {
+    state: Mutex<HealthState>,
+}
+
+impl HealthTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the HTTP status of an API response
+    pub fn record_status(&self, status: u16) {
+        let mut state = self.state.lock().unwrap();
+        let now = Utc::now();
+        match status {
+            200..=299 => state.last_success_at = Some(now),
+            406 | 461 => {
+                state.recent_errors.push_back((now, status));
+                while state.recent_errors.len() > MAX_RECENT_ERRORS {
+                    state.recent_errors.pop_front();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Record the outcome of a background validity check
+    pub fn record_check(&self, result: std::result::Result<(), String>) {
+        let mut state = self.state.lock().unwrap();
+        state.last_check = Some(HealthCheck {
+            checked_at: Utc::now(),
+            ok: result.is_ok(),
+            error: result.err(),
+        });
+    }
+
+    /// Build a snapshot for the given account
+    pub fn snapshot(&self, account: &str, creds: Option<&UserCredentials>) -> AccountHealth {
+        let mut state = self.state.lock().unwrap();
+        let since = Utc::now() - Duration::minutes(RECENT_WINDOW_MINUTES);
+        while state.recent_errors.front().is_some_and(|(at, _)| *at < since) {
+            state.recent_errors.pop_front();
+        }
+        let recent_406 = state.recent_errors.iter().filter(|(_, s)| *s == 406).count();
+        let recent_461 = state.recent_errors.iter().filter(|(_, s)| *s == 461).count();
+
+        let logged_in = creds.is_some_and(|c| c.is_valid);
+        let potentially_expired = creds.is_some_and(|c| c.is_potentially_expired());
+        let check_failed = state.last_check.as_ref().is_some_and(|c| !c.ok);
+
+        AccountHealth {
+            account: account.to_string(),
+            logged_in,
+            user_id: creds.map(|c| c.user_id.clone()),
+            age_secs: creds.map(|c| Utc::now().signed_duration_since(c.updated_at).num_seconds()),
+            potentially_expired,
+            last_success_at: state.last_success_at,
+            last_check: state.last_check.clone(),
+            recent_406,
+            recent_461,
+            needs_relogin: !logged_in || potentially_expired || check_failed,
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+/// Background checker interval (XHS_HEALTH_CHECK_INTERVAL_SECS, default 600, 0 disables)
+fn check_interval() -> Option<std::time::Duration> {
+    let secs = std::env::var("XHS_HEALTH_CHECK_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(600);
+    (secs > 0).then(|| std::time::Duration::from_secs(secs))
+}
+
+#[cfg(feature = "server")]
+/// Spawn the periodic validity checker for user and creator accounts
+pub fn spawn_health_checker(state: Arc<AppState>) {
+    let Some(interval) = check_interval() else {
+        tracing::info!("[Health] Background credential check disabled");
+        return;
+    };
+
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(interval).await;
+            // Upstream maintenance would only record false check failures
+            if state.api.maintenance().is_paused() {
+                continue;
+            }
+            check_user(&state).await;
+            check_creator(&state).await;
+        }
+    });
+}
+
+#[cfg(feature = "server")]
+/// Validate the user credentials via user/me and record the result
+pub async fn check_user(state: &AppState) {
+    // Skip silently when not logged in: the snapshot already reports it
+    if !matches!(state.auth.try_get_credentials().await, Ok(Some(_))) {
+        return;
+    }
+    let result = match crate::api::user::get_current_user(&state.api).await {
+        Ok(me) if !me.data.guest => Ok(()),
+        Ok(_) => Err("user/me returned a guest session".to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+    if let Err(ref e) = result {
+        tracing::warn!("[Health] User credential check failed: {}", e);
+    }
+    state.auth.health().record_check(result);
+}
+
+#[cfg(feature = "server")]
+/// Validate the creator credentials via the creator info endpoint and record the result
+pub async fn check_creator(state: &AppState) {
+    let Ok(Some(creds)) = state.creator_auth.try_get_credentials().await else {
+        return;
+    };
+    let result = crate::api::creator::info::get_creator_user_info(&creds.cookies)
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string());
+    if let Err(ref e) = result {
+        tracing::warn!("[Health] Creator credential check failed: {}", e);
+    }
+    state.creator_auth.health().record_check(result);
+}
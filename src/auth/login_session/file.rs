@@ -0,0 +1,77 @@
+//! JSON file login session backend
+//!
+//! All sessions live in one JSON array, rewritten atomically (temp file +
+//! rename) on every change; expired sessions are dropped on insert. Survives
+//! restarts of a single instance; use the Mongo backend when several instances
+//! serve the login flow.
+use anyhow::Result;
+use std::path::PathBuf;
+use tokio::sync::Mutex;
+
+use super::LoginSession;
+use crate::api::login::QrCodeSession;
+
+pub struct FileBackend {
+    path: PathBuf,
+    /// Serializes read-modify-write cycles
+    lock: Mutex<()>,
+}
+
+impl FileBackend {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path, lock: Mutex::new(()) }
+    }
+
+    /// Check that the file is readable and its directory writable
+    pub async fn ping(&self) -> Result<()> {
+        self.load().await.map_err(|e| anyhow::anyhow!("{}: {}", self.path.display(), e))?;
+        crate::utils::storage::check_dir(self.path.parent().unwrap_or(std::path::Path::new("."))).await
+    }
+
+    async fn load(&self) -> Result<Vec<LoginSession>> {
+        match tokio::fs::read_to_string(&self.path).await {
+            Ok(content) if content.trim().is_empty() => Ok(Vec::new()),
+            Ok(content) => Ok(serde_json::from_str(&content)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn save(&self, sessions: &[LoginSession]) -> Result<()> {
+        if let Some(dir) = self.path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        let tmp = self.path.with_extension("json.tmp");
+        tokio::fs::write(&tmp, serde_json::to_string_pretty(sessions)?).await?;
+        tokio::fs::rename(&tmp, &self.path).await?;
+        Ok(())
+    }
+
+    pub async fn insert(&self, session: &LoginSession, cutoff: i64) -> Result<()> {
+        let _guard = self.lock.lock().await;
+        let mut sessions = self.load().await?;
+        sessions.retain(|s| s.updated_at >= cutoff);
+        sessions.push(session.clone());
+        self.save(&sessions).await
+    }
+
+    pub async fn get(&self, scope: &str, token: Option<&str>, cutoff: i64) -> Result<Option<LoginSession>> {
+        let _guard = self.lock.lock().await;
+        Ok(self.load().await?
+            .into_iter()
+            .filter(|s| s.scope == scope && s.updated_at >= cutoff && token.is_none_or(|t| s.token == t))
+            .max_by_key(|s| s.updated_at))
+    }
+
+    pub async fn set_qrcode(&self, scope: &str, token: &str, qrcode: Option<&QrCodeSession>, now: i64) -> Result<bool> {
+        let _guard = self.lock.lock().await;
+        let mut sessions = self.load().await?;
+        let Some(session) = sessions.iter_mut().find(|s| s.scope == scope && s.token == token) else {
+            return Ok(false);
+        };
+        session.qrcode = qrcode.cloned();
+        session.updated_at = now;
+        self.save(&sessions).await?;
+        Ok(true)
+    }
+}
@@ -0,0 +1,177 @@
+//! Persisted QR login flow state
+//!
+//! `guest-init` creates a login session holding the activated guest cookies and
+//! returns its token; `qrcode/create`, `qrcode/status`, `qrcode/status/wait` and
+//! `qrcode/events` look the session up by that token (`session_token` query
+//! parameter; the most recently used session when omitted). Sessions live in the
+//! storage backend rather than in memory, so a login started before a restart
+//! can be finished after it, and instances behind a load balancer share the
+//! flow when they share the Mongo backend.
+//!
+//! Sessions unused for [`SESSION_TTL`] are dropped. Configuration (env):
+//! - `XHS_LOGIN_SESSIONS_BACKEND`: `file` (default) / `mongo`
+//! - `XHS_LOGIN_SESSIONS_FILE`: file backend path, default ./login_sessions.json
+//! - `XHS_LOGIN_SESSIONS_MONGO_URI`: Mongo backend URI, defaults to XHS_COORD_MONGO_URI
+//! - `XHS_LOGIN_SESSIONS_DB`: Mongo database, default xhs_login
+pub mod file;
+#[cfg(feature = "mongodb")]
+pub mod mongo;
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::api::login::QrCodeSession;
+use file::FileBackend;
+#[cfg(feature = "mongodb")]
+use mongo::MongoBackend;
+
+/// Sessions idle for longer than this are expired
+pub const SESSION_TTL: Duration = Duration::from_secs(30 * 60);
+
+/// One QR login attempt: activated guest cookies plus the current QR code
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoginSession {
+    pub token: String,
+    /// Tenant id; empty for the deployment's own account
+    #[serde(default)]
+    pub scope: String,
+    pub guest_cookies: HashMap<String, String>,
+    /// Current QR code; None before qrcode/create and after the login finished
+    #[serde(default)]
+    pub qrcode: Option<QrCodeSession>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// Oldest `updated_at` (ms) of a live session
+fn live_cutoff() -> i64 {
+    Utc::now().timestamp_millis() - SESSION_TTL.as_millis() as i64
+}
+
+/// Login session storage configuration
+#[derive(Debug, Clone)]
+pub enum LoginSessionsConfig {
+    File { path: PathBuf },
+    #[cfg(feature = "mongodb")]
+    Mongo { uri: String, database: String },
+}
+
+impl LoginSessionsConfig {
+    pub fn from_env() -> Result<Self> {
+        let backend = std::env::var("XHS_LOGIN_SESSIONS_BACKEND").unwrap_or_else(|_| "file".to_string());
+        match backend.as_str() {
+            "" | "file" => Ok(Self::File {
+                path: std::env::var("XHS_LOGIN_SESSIONS_FILE")
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|_| PathBuf::from("./login_sessions.json")),
+            }),
+            #[cfg(feature = "mongodb")]
+            "mongo" => {
+                let uri = std::env::var("XHS_LOGIN_SESSIONS_MONGO_URI")
+                    .or_else(|_| std::env::var("XHS_COORD_MONGO_URI"))
+                    .map_err(|_| anyhow!("XHS_LOGIN_SESSIONS_BACKEND=mongo requires XHS_LOGIN_SESSIONS_MONGO_URI"))?;
+                let database = std::env::var("XHS_LOGIN_SESSIONS_DB").unwrap_or_else(|_| "xhs_login".to_string());
+                Ok(Self::Mongo { uri, database })
+            }
+            #[cfg(not(feature = "mongodb"))]
+            "mongo" => Err(anyhow!("XHS_LOGIN_SESSIONS_BACKEND=mongo requires the `mongodb` cargo feature")),
+            other => Err(anyhow!("Unknown XHS_LOGIN_SESSIONS_BACKEND: {}", other)),
+        }
+    }
+}
+
+enum LoginSessionsBackend {
+    File(FileBackend),
+    #[cfg(feature = "mongodb")]
+    Mongo(MongoBackend),
+}
+
+/// Persistent store of QR login sessions, scoped to one account (tenant)
+#[derive(Clone)]
+pub struct LoginSessions {
+    backend: Arc<LoginSessionsBackend>,
+    description: String,
+    scope: String,
+}
+
+impl LoginSessions {
+    pub async fn open(config: &LoginSessionsConfig) -> Result<Self> {
+        let (backend, description) = match config {
+            LoginSessionsConfig::File { path } => {
+                (LoginSessionsBackend::File(FileBackend::new(path.clone())), format!("file: {}", path.display()))
+            }
+            #[cfg(feature = "mongodb")]
+            LoginSessionsConfig::Mongo { uri, database } => {
+                (LoginSessionsBackend::Mongo(MongoBackend::connect(uri, database).await?), format!("mongo: {}", database))
+            }
+        };
+        tracing::info!("[Login] Login sessions stored in {}", description);
+        Ok(Self { backend: Arc::new(backend), description, scope: String::new() })
+    }
+
+    pub async fn from_env() -> Result<Self> {
+        Self::open(&LoginSessionsConfig::from_env()?).await
+    }
+
+    /// Same storage, restricted to the sessions of tenant `scope`
+    pub fn scoped(&self, scope: &str) -> Self {
+        Self { scope: scope.to_string(), ..self.clone() }
+    }
+
+    /// Backend description, e.g. "file: ./login_sessions.json"
+    pub fn describe(&self) -> &str {
+        &self.description
+    }
+
+    /// Check that the backend is reachable (readiness probe)
+    pub async fn ping(&self) -> Result<()> {
+        match self.backend.as_ref() {
+            LoginSessionsBackend::File(b) => b.ping().await,
+            #[cfg(feature = "mongodb")]
+            LoginSessionsBackend::Mongo(b) => b.ping().await,
+        }
+    }
+
+    /// Start a login session with freshly activated guest cookies
+    pub async fn create(&self, guest_cookies: HashMap<String, String>) -> Result<LoginSession> {
+        let now = Utc::now().timestamp_millis();
+        let session = LoginSession {
+            token: uuid::Uuid::new_v4().simple().to_string(),
+            scope: self.scope.clone(),
+            guest_cookies,
+            qrcode: None,
+            created_at: now,
+            updated_at: now,
+        };
+        match self.backend.as_ref() {
+            LoginSessionsBackend::File(b) => b.insert(&session, live_cutoff()).await?,
+            #[cfg(feature = "mongodb")]
+            LoginSessionsBackend::Mongo(b) => b.insert(&session, live_cutoff()).await?,
+        }
+        Ok(session)
+    }
+
+    /// Live session by token, or the most recently used one when `token` is None
+    pub async fn get(&self, token: Option<&str>) -> Result<Option<LoginSession>> {
+        match self.backend.as_ref() {
+            LoginSessionsBackend::File(b) => b.get(&self.scope, token, live_cutoff()).await,
+            #[cfg(feature = "mongodb")]
+            LoginSessionsBackend::Mongo(b) => b.get(&self.scope, token, live_cutoff()).await,
+        }
+    }
+
+    /// Replace the session's QR code; false when the session no longer exists
+    pub async fn set_qrcode(&self, token: &str, qrcode: Option<&QrCodeSession>) -> Result<bool> {
+        let now = Utc::now().timestamp_millis();
+        match self.backend.as_ref() {
+            LoginSessionsBackend::File(b) => b.set_qrcode(&self.scope, token, qrcode, now).await,
+            #[cfg(feature = "mongodb")]
+            LoginSessionsBackend::Mongo(b) => b.set_qrcode(&self.scope, token, qrcode, now).await,
+        }
+    }
+}
@@ -0,0 +1,70 @@
+//! MongoDB login session backend
+//!
+//! One document per session in `login_sessions`; every instance pointing at
+//! the same database can continue a login started on another one.
+use anyhow::{anyhow, Result};
+use mongodb::bson::{doc, to_bson};
+use mongodb::options::IndexOptions;
+use mongodb::{Collection, IndexModel};
+
+use super::LoginSession;
+use crate::api::login::QrCodeSession;
+
+pub struct MongoBackend {
+    sessions: Collection<LoginSession>,
+}
+
+impl MongoBackend {
+    pub async fn connect(uri: &str, database: &str) -> Result<Self> {
+        let client = crate::utils::storage::mongo_client(uri)
+            .await
+            .map_err(|e| anyhow!("Failed to connect to login sessions MongoDB: {}", e))?;
+        let sessions: Collection<LoginSession> = client.database(database).collection("login_sessions");
+        sessions
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "token": 1 })
+                    .options(IndexOptions::builder().name("token".to_string()).unique(true).build())
+                    .build(),
+            )
+            .await?;
+        sessions
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "scope": 1, "updated_at": -1 })
+                    .options(IndexOptions::builder().name("scope_updated_at".to_string()).build())
+                    .build(),
+            )
+            .await?;
+        Ok(Self { sessions })
+    }
+
+    /// Check that the deployment is reachable
+    pub async fn ping(&self) -> Result<()> {
+        crate::utils::storage::ping_mongo(&self.sessions).await
+    }
+
+    pub async fn insert(&self, session: &LoginSession, cutoff: i64) -> Result<()> {
+        self.sessions.delete_many(doc! { "updated_at": { "$lt": cutoff } }).await?;
+        self.sessions.insert_one(session).await?;
+        Ok(())
+    }
+
+    pub async fn get(&self, scope: &str, token: Option<&str>, cutoff: i64) -> Result<Option<LoginSession>> {
+        let mut filter = doc! { "scope": scope, "updated_at": { "$gte": cutoff } };
+        if let Some(token) = token {
+            filter.insert("token", token);
+        }
+        Ok(self.sessions.find_one(filter).sort(doc! { "updated_at": -1 }).await?)
+    }
+
+    pub async fn set_qrcode(&self, scope: &str, token: &str, qrcode: Option<&QrCodeSession>, now: i64) -> Result<bool> {
+        let result = self.sessions
+            .update_one(
+                doc! { "scope": scope, "token": token },
+                doc! { "$set": { "qrcode": to_bson(&qrcode)?, "updated_at": now } },
+            )
+            .await?;
+        Ok(result.matched_count > 0)
+    }
+}
@@ -2,8 +2,15 @@ pub mod credentials;
 pub mod storage;
 pub mod browser;
 pub mod service;
+pub mod export;
+pub mod har;
+pub mod health;
+pub mod fingerprint;
+pub mod login_session;
 
 pub use credentials::UserCredentials;
 pub use storage::CredentialStorage;
 pub use service::AuthService;
+pub use fingerprint::BrowserFingerprint;
+pub use login_session::{LoginSession, LoginSessions};
 
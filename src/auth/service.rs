@@ -5,8 +5,9 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{info, warn};
 
-use crate::auth::{CredentialStorage, UserCredentials};
+use crate::auth::{BrowserFingerprint, CredentialStorage, UserCredentials};
 use crate::auth::browser::trigger_python_login;
+use crate::auth::health::HealthTracker;
 
 use std::path::PathBuf;
 
@@ -14,6 +15,7 @@ use std::path::PathBuf;
 pub struct AuthService {
     storage: CredentialStorage,
     cached_credentials: Arc<RwLock<Option<UserCredentials>>>,
+    health: HealthTracker,
 }
 
 impl AuthService {
@@ -31,9 +33,15 @@ impl AuthService {
         Ok(Self {
             storage,
             cached_credentials: Arc::new(RwLock::new(cached)),
+            health: HealthTracker::new(),
         })
     }
     
+    /// Request outcome tracker for these credentials
+    pub fn health(&self) -> &HealthTracker {
+        &self.health
+    }
+    
     /// Get current credentials passively (check cache and file only)
     /// Returns None if no valid credentials found, does NOT trigger login
     pub async fn try_get_credentials(&self) -> Result<Option<UserCredentials>> {
@@ -105,10 +113,45 @@ impl AuthService {
         Ok(())
     }
     
+    /// Stored credentials including invalidated ones (for the admin credentials API)
+    pub async fn stored_credentials(&self) -> Result<Option<UserCredentials>> {
+        self.storage.read_credentials().await
+    }
+    
+    /// Delete stored credentials; returns false if nothing was stored
+    pub async fn delete_credentials(&self) -> Result<bool> {
+        let deleted = self.storage.delete().await?;
+        
+        let mut cache = self.cached_credentials.write().await;
+        *cache = None;
+        
+        Ok(deleted)
+    }
+    
     /// Save new credentials (used after QR code login success)
+    ///
+    /// A returning account keeps its previous browser fingerprint (with `a1` / `webId`
+    /// refreshed from the new cookies) so re-logins do not switch browsers. Accounts whose
+    /// cookies never carried an `a1` (e.g. imported from a cookie string) get a freshly
+    /// minted device id matching the fingerprint's platform.
     pub async fn save_credentials(&self, creds: &UserCredentials) -> Result<()> {
+        let mut creds = creds.clone();
+        let fresh = creds.fingerprint();
+        let fingerprint = match self.storage.stored_fingerprint(&creds.user_id).await {
+            Ok(Some(previous)) => BrowserFingerprint {
+                a1: fresh.a1.or(previous.a1.clone()),
+                web_id: fresh.web_id.or(previous.web_id.clone()),
+                ..previous
+            },
+            _ => fresh,
+        };
+        creds.fingerprint = Some(match fingerprint.a1 {
+            Some(_) => fingerprint,
+            None => fingerprint.with_new_device(),
+        });
+        
         // Save to JSON file
-        self.storage.save_credentials(creds).await?;
+        self.storage.save_credentials(&creds).await?;
         
         // Update cache
         let mut cache = self.cached_credentials.write().await;
@@ -118,11 +161,16 @@ impl AuthService {
         Ok(())
     }
     
-    /// Get captured signature for a specific endpoint (legacy, returns None)
+    /// Get captured signature for a specific endpoint (None until signatures are imported from a HAR)
     pub async fn get_endpoint_signature(&self, endpoint: &str) -> Result<Option<super::credentials::ApiSignature>> {
         self.storage.get_api_signature(endpoint).await
     }
     
+    /// Save signatures captured in the browser for the stored-signature fallback
+    pub async fn save_endpoint_signatures(&self, signatures: &[super::credentials::ApiSignature]) -> Result<()> {
+        self.storage.save_api_signatures(signatures).await
+    }
+    
     /// Generate a dummy signature - in new architecture, we use x-s-common from stored credentials
     /// The actual signing happens in the browser during login
     pub async fn sign_request(&self, _url: &str, _method: &str, _body: Option<&str>) -> Result<(String, i64, String)> {
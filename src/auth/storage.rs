@@ -1,36 +1,105 @@
-//! JSON file-based credential storage
+//! Credential storage
 //!
-//! Stores credentials in `cookie.json` in the project root directory.
+//! By default credentials are stored as JSON in `cookie.json` (in the project root directory),
+//! and signatures captured from the browser (HAR import) in `cookie.signatures.json` next to it.
+//!
+//! With `XHS_STORAGE=memory` nothing touches the disk: credentials and signatures live in a
+//! process-wide map keyed by the same paths, so stores opened for the same file share state
+//! and everything is lost on exit. Meant for integration tests and quick experiments.
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::{Arc, LazyLock, Mutex};
 use tracing::{info, warn};
 
-use super::credentials::UserCredentials;
+use super::credentials::{ApiSignature, UserCredentials};
+
+/// In-memory stores by credentials path
+static MEMORY_STORES: LazyLock<Mutex<HashMap<PathBuf, Arc<Mutex<MemoryStore>>>>> = LazyLock::new(Default::default);
 
+/// Contents of one in-memory store
+#[derive(Default)]
+struct MemoryStore {
+    credentials: Option<UserCredentials>,
+    signatures: HashMap<String, ApiSignature>,
+}
 
+enum StorageBackend {
+    File,
+    Memory(Arc<Mutex<MemoryStore>>),
+}
 
-/// JSON file-based credential storage
+/// Credential storage (JSON file, or in memory with `XHS_STORAGE=memory`)
 pub struct CredentialStorage {
     file_path: PathBuf,
+    backend: StorageBackend,
 }
 
 impl CredentialStorage {
-    /// Create a new storage instance
+    /// Create a new storage instance; the backend is chosen by `XHS_STORAGE` (`file` by default, or `memory`)
     pub async fn new(file_path: PathBuf) -> Result<Self> {
-        info!("Using JSON credential storage: {}", file_path.display());
-        Ok(Self { file_path })
+        match std::env::var("XHS_STORAGE").unwrap_or_default().as_str() {
+            "" | "file" => {
+                info!("Using JSON credential storage: {}", file_path.display());
+                Ok(Self { file_path, backend: StorageBackend::File })
+            }
+            "memory" => Ok(Self::in_memory(file_path)),
+            other => Err(anyhow!("Unknown XHS_STORAGE: {} (expected file or memory)", other)),
+        }
+    }
+
+    /// In-memory storage regardless of `XHS_STORAGE`; `key` identifies the store like a file path would
+    pub fn in_memory(key: PathBuf) -> Self {
+        info!("Using in-memory credential storage: {}", key.display());
+        let store = MEMORY_STORES.lock().unwrap_or_else(|e| e.into_inner())
+            .entry(key.clone())
+            .or_default()
+            .clone();
+        Self { file_path: key, backend: StorageBackend::Memory(store) }
+    }
+
+    /// `json: cookie.json` or `memory: cookie.json`
+    pub fn describe(&self) -> String {
+        match self.backend {
+            StorageBackend::File => format!("json: {}", self.file_path.display()),
+            StorageBackend::Memory(_) => format!("memory: {}", self.file_path.display()),
+        }
+    }
+
+    /// Stored credentials, valid or not
+    async fn load(&self) -> Result<Option<UserCredentials>> {
+        match &self.backend {
+            StorageBackend::File => {
+                if !self.file_path.exists() {
+                    return Ok(None);
+                }
+                let content = tokio::fs::read_to_string(&self.file_path).await?;
+                Ok(Some(serde_json::from_str(&content)?))
+            }
+            StorageBackend::Memory(store) => Ok(store.lock().unwrap_or_else(|e| e.into_inner()).credentials.clone()),
+        }
+    }
+
+    async fn store(&self, creds: &UserCredentials) -> Result<()> {
+        match &self.backend {
+            StorageBackend::File => {
+                let content = serde_json::to_string_pretty(creds)?;
+                tokio::fs::write(&self.file_path, content).await?;
+            }
+            StorageBackend::Memory(store) => {
+                store.lock().unwrap_or_else(|e| e.into_inner()).credentials = Some(creds.clone());
+            }
+        }
+        Ok(())
     }
     
     /// Get the currently active (valid) credentials
     pub async fn get_active_credentials(&self) -> Result<Option<UserCredentials>> {
-        if !self.file_path.exists() {
-            info!("No cookie.json found");
+        let Some(creds) = self.load().await? else {
+            info!("No stored credentials ({})", self.describe());
             return Ok(None);
-        }
-        
-        let content = tokio::fs::read_to_string(&self.file_path).await?;
-        let creds: UserCredentials = serde_json::from_str(&content)?;
+        };
         
         if creds.is_valid {
             info!("Found active credentials for user: {}", creds.user_id);
@@ -43,53 +112,130 @@ impl CredentialStorage {
     
     /// Save or update credentials
     pub async fn save_credentials(&self, creds: &UserCredentials) -> Result<()> {
-        let content = serde_json::to_string_pretty(creds)?;
-        tokio::fs::write(&self.file_path, content).await?;
-        info!("Saved credentials for user: {} to {}", creds.user_id, self.file_path.display());
+        self.store(creds).await?;
+        info!("Saved credentials for user: {} to {}", creds.user_id, self.describe());
         Ok(())
     }
     
+    /// Stored credentials whether or not they are still valid
+    pub async fn read_credentials(&self) -> Result<Option<UserCredentials>> {
+        self.load().await
+    }
+    
+    /// Delete the stored credentials and captured signatures; returns false if nothing was stored
+    pub async fn delete(&self) -> Result<bool> {
+        match &self.backend {
+            StorageBackend::File => {
+                if !self.file_path.exists() {
+                    return Ok(false);
+                }
+                tokio::fs::remove_file(&self.file_path).await?;
+                let signatures = self.signatures_path();
+                if signatures.exists() {
+                    tokio::fs::remove_file(&signatures).await?;
+                }
+            }
+            StorageBackend::Memory(store) => {
+                let mut store = store.lock().unwrap_or_else(|e| e.into_inner());
+                if store.credentials.take().is_none() {
+                    return Ok(false);
+                }
+                store.signatures.clear();
+            }
+        }
+        warn!("Deleted credentials ({})", self.describe());
+        Ok(true)
+    }
+    
+    /// Fingerprint previously stored for `user_id`, whether or not those credentials are still valid
+    pub async fn stored_fingerprint(&self, user_id: &str) -> Result<Option<super::BrowserFingerprint>> {
+        Ok(self.load().await?.and_then(|creds| creds.fingerprint.filter(|_| creds.user_id == user_id)))
+    }
+    
     /// Mark all credentials as invalid
     pub async fn invalidate_all(&self) -> Result<()> {
-        if !self.file_path.exists() {
-            return Ok(());
+        if let Some(mut creds) = self.load().await? {
+            if creds.is_valid {
+                creds.invalidate();
+                self.store(&creds).await?;
+                warn!("Invalidated credentials for user: {}", creds.user_id);
+            }
         }
-        
-        let content = tokio::fs::read_to_string(&self.file_path).await?;
-        let mut creds: UserCredentials = serde_json::from_str(&content)?;
-        
-        if creds.is_valid {
-            creds.invalidate();
-            let content = serde_json::to_string_pretty(&creds)?;
-            tokio::fs::write(&self.file_path, content).await?;
-            warn!("Invalidated credentials for user: {}", creds.user_id);
-        }
-        
         Ok(())
     }
     
     /// Invalidate credentials for a specific user (same as invalidate_all for single-user storage)
     pub async fn invalidate_user(&self, user_id: &str) -> Result<()> {
-        if !self.file_path.exists() {
-            return Ok(());
-        }
-        
-        let content = tokio::fs::read_to_string(&self.file_path).await?;
-        let mut creds: UserCredentials = serde_json::from_str(&content)?;
-        
-        if creds.user_id == user_id && creds.is_valid {
-            creds.invalidate();
-            let content = serde_json::to_string_pretty(&creds)?;
-            tokio::fs::write(&self.file_path, content).await?;
-            warn!("Invalidated credentials for user: {}", user_id);
+        if let Some(mut creds) = self.load().await? {
+            if creds.user_id == user_id && creds.is_valid {
+                creds.invalidate();
+                self.store(&creds).await?;
+                warn!("Invalidated credentials for user: {}", user_id);
+            }
         }
-        
         Ok(())
     }
     
-    /// Get API signature for a specific endpoint (legacy, returns None for JSON storage)
-    pub async fn get_api_signature(&self, _endpoint: &str) -> Result<Option<super::credentials::ApiSignature>> {
-        // API signatures are not stored in JSON storage (they are generated on-demand via Agent)
-        Ok(None)
+    /// Captured signatures live next to the credentials file (`cookie.json` → `cookie.signatures.json`)
+    fn signatures_path(&self) -> PathBuf {
+        self.file_path.with_extension("signatures.json")
+    }
+    
+    async fn load_api_signatures(&self) -> Result<HashMap<String, ApiSignature>> {
+        match &self.backend {
+            StorageBackend::File => {
+                let path = self.signatures_path();
+                if !path.exists() {
+                    return Ok(HashMap::new());
+                }
+                let content = tokio::fs::read_to_string(&path).await?;
+                Ok(serde_json::from_str(&content)?)
+            }
+            StorageBackend::Memory(store) => Ok(store.lock().unwrap_or_else(|e| e.into_inner()).signatures.clone()),
+        }
+    }
+    
+    /// Get the captured signature for a specific endpoint (stored-signature fallback)
+    ///
+    /// Signatures older than `XHS_STORED_SIGNATURE_TTL_HOURS` are treated as missing
+    pub async fn get_api_signature(&self, endpoint: &str) -> Result<Option<ApiSignature>> {
+        let ttl = crate::config::stored_signature_ttl();
+        Ok(self.load_api_signatures().await?
+            .remove(endpoint)
+            .filter(|sig| sig.is_valid)
+            .filter(|sig| match ttl {
+                Some(ttl) if chrono::Utc::now() - sig.captured_at > ttl => {
+                    warn!("Stored signature for {} expired (captured at {})", endpoint, sig.captured_at);
+                    false
+                }
+                _ => true,
+            }))
+    }
+    
+    /// All captured signatures, including invalidated or expired ones (for migration)
+    pub async fn api_signatures(&self) -> Result<Vec<ApiSignature>> {
+        let mut signatures: Vec<ApiSignature> = self.load_api_signatures().await?.into_values().collect();
+        signatures.sort_by(|a, b| a.endpoint.cmp(&b.endpoint));
+        Ok(signatures)
+    }
+    
+    /// Save captured signatures, replacing earlier ones for the same endpoints
+    pub async fn save_api_signatures(&self, signatures: &[ApiSignature]) -> Result<()> {
+        if let StorageBackend::Memory(store) = &self.backend {
+            let mut store = store.lock().unwrap_or_else(|e| e.into_inner());
+            for signature in signatures {
+                store.signatures.insert(signature.endpoint.clone(), signature.clone());
+            }
+            info!("Saved {} captured signature(s) in memory", signatures.len());
+            return Ok(());
+        }
+        let mut stored = self.load_api_signatures().await?;
+        for signature in signatures {
+            stored.insert(signature.endpoint.clone(), signature.clone());
+        }
+        let path = self.signatures_path();
+        tokio::fs::write(&path, serde_json::to_string_pretty(&stored)?).await?;
+        info!("Saved {} captured signature(s) to {}", signatures.len(), path.display());
+        Ok(())
     }
 }
@@ -0,0 +1,52 @@
+//! 内存响应缓存后端
+//!
+//! 进程内 HashMap，重启后清空；达到上限时先清理过期条目，仍然满时淘汰最早写入的条目。
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+use super::CachedResponse;
+
+struct Entry {
+    response: CachedResponse,
+    stored: Instant,
+    expires: Instant,
+}
+
+pub struct MemoryBackend {
+    entries: RwLock<HashMap<String, Entry>>,
+    max_entries: usize,
+}
+
+impl MemoryBackend {
+    pub fn new(max_entries: usize) -> Self {
+        Self { entries: RwLock::new(HashMap::new()), max_entries: max_entries.max(1) }
+    }
+
+    pub async fn get(&self, key: &str) -> Option<CachedResponse> {
+        let entries = self.entries.read().await;
+        entries
+            .get(key)
+            .filter(|entry| entry.expires > Instant::now())
+            .map(|entry| entry.response.clone())
+    }
+
+    pub async fn put(&self, key: &str, response: &CachedResponse, ttl: Duration) {
+        let now = Instant::now();
+        let mut entries = self.entries.write().await;
+        if entries.len() >= self.max_entries && !entries.contains_key(key) {
+            entries.retain(|_, entry| entry.expires > now);
+            if entries.len() >= self.max_entries {
+                let oldest = entries
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.stored)
+                    .map(|(key, _)| key.clone());
+                if let Some(key) = oldest {
+                    entries.remove(&key);
+                }
+            }
+        }
+        entries.insert(key.to_string(), Entry { response: response.clone(), stored: now, expires: now + ttl });
+    }
+}
@@ -0,0 +1,344 @@
+//! 响应缓存 (Response Cache)
+//!
+//! 缓存幂等读接口（热搜、搜索、笔记详情、用户笔记等）的成功响应，有效期内直接返回，
+//! 减少发往 XHS 的请求量与风控暴露。默认关闭。
+//!
+//! - 缓存键: 登录账号 + 方法 + 路径与查询串 + 请求体，不同账号 / 租户之间互不可见
+//! - 只缓存状态码 200 且 `success` 不为 false 的 JSON 响应
+//! - 请求头 `Cache-Control`: `no-cache` 跳过缓存并刷新，`no-store` 完全绕过，
+//!   `max-age=N` 只接受 N 秒内写入的缓存
+//! - 响应头 `X-Cache: HIT / MISS / BYPASS`，命中时附带 `Age`
+//!
+//! 配置 (环境变量):
+//! - `XHS_CACHE_BACKEND`: `off` (默认) / `memory` / `redis`
+//! - `XHS_CACHE_MAX_ENTRIES`: 内存后端最多缓存的响应数，默认 10000
+//! - `XHS_CACHE_REDIS_URL`: Redis 连接串，默认 redis://127.0.0.1:6379（多实例共享缓存）
+//! - `XHS_CACHE_TTLS`: 按路由覆盖有效期（秒），如 `/api/search/trending=600,/api/note/detail=0`，
+//!   0 为不缓存；默认值见 [`DEFAULT_TTLS`]
+
+pub mod memory;
+#[cfg(feature = "redis")]
+pub mod redis;
+
+use anyhow::{anyhow, Result};
+use axum::{
+    body::Body,
+    extract::{MatchedPath, Request, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::server::AppState;
+use memory::MemoryBackend;
+#[cfg(feature = "redis")]
+use self::redis::RedisBackend;
+
+/// 默认缓存的路由与有效期（秒）
+pub const DEFAULT_TTLS: &[(&str, u64)] = &[
+    ("/api/search/trending", 300),
+    ("/api/search/recommend", 300),
+    ("/api/search/filter", 600),
+    ("/api/search/notes", 120),
+    ("/api/search/onebox", 120),
+    ("/api/search/usersearch", 120),
+    ("/api/note/detail", 60),
+    ("/api/user/me", 60),
+    ("/api/user/:user_id/notes", 120),
+    ("/api/tag/:tag_id/notes", 120),
+];
+
+/// 缓存键参与摘要的请求体上限，与 axum Json 提取器的默认上限一致
+const MAX_REQUEST_BYTES: usize = 2 * 1024 * 1024;
+
+/// 超过该大小的响应不缓存
+const MAX_RESPONSE_BYTES: usize = 2 * 1024 * 1024;
+
+/// 响应缓存存储
+#[derive(Debug, Clone)]
+pub enum CacheStorage {
+    Memory { max_entries: usize },
+    #[cfg(feature = "redis")]
+    Redis { url: String },
+}
+
+/// 响应缓存配置
+#[derive(Debug, Clone)]
+pub struct CacheConfig {
+    pub storage: CacheStorage,
+    /// 路由 -> 有效期
+    pub ttls: HashMap<String, Duration>,
+}
+
+impl CacheConfig {
+    /// 从环境变量读取，未启用时返回 None
+    pub fn from_env() -> Result<Option<Self>> {
+        let backend = std::env::var("XHS_CACHE_BACKEND").unwrap_or_else(|_| "off".to_string());
+        let storage = match backend.as_str() {
+            "" | "off" => return Ok(None),
+            "memory" => CacheStorage::Memory {
+                max_entries: std::env::var("XHS_CACHE_MAX_ENTRIES")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(10_000),
+            },
+            #[cfg(feature = "redis")]
+            "redis" => CacheStorage::Redis {
+                url: std::env::var("XHS_CACHE_REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string()),
+            },
+            #[cfg(not(feature = "redis"))]
+            "redis" => return Err(anyhow!("XHS_CACHE_BACKEND=redis requires the `redis` cargo feature")),
+            other => return Err(anyhow!("Unknown XHS_CACHE_BACKEND: {}", other)),
+        };
+        let ttls = parse_ttls(&std::env::var("XHS_CACHE_TTLS").unwrap_or_default())?;
+        Ok(Some(Self { storage, ttls }))
+    }
+}
+
+/// 在默认有效期上应用 `route=secs,...` 覆盖
+fn parse_ttls(spec: &str) -> Result<HashMap<String, Duration>> {
+    let mut ttls: HashMap<String, Duration> = DEFAULT_TTLS
+        .iter()
+        .map(|(route, secs)| (route.to_string(), Duration::from_secs(*secs)))
+        .collect();
+    for item in spec.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        let (route, secs) = item
+            .split_once('=')
+            .and_then(|(route, secs)| Some((route.trim(), secs.trim().parse::<u64>().ok()?)))
+            .ok_or_else(|| anyhow!("Invalid XHS_CACHE_TTLS entry: {}", item))?;
+        if secs == 0 {
+            ttls.remove(route);
+        } else {
+            ttls.insert(route.to_string(), Duration::from_secs(secs));
+        }
+    }
+    Ok(ttls)
+}
+
+/// 缓存的响应
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedResponse {
+    pub content_type: Option<String>,
+    pub body: String,
+    /// 写入时间 (ms)
+    pub stored_at: i64,
+}
+
+impl CachedResponse {
+    /// 已缓存的秒数
+    fn age_secs(&self) -> u64 {
+        (chrono::Utc::now().timestamp_millis() - self.stored_at).max(0) as u64 / 1000
+    }
+}
+
+enum CacheBackend {
+    Memory(MemoryBackend),
+    #[cfg(feature = "redis")]
+    Redis(RedisBackend),
+}
+
+/// 读接口响应缓存
+pub struct ResponseCache {
+    backend: CacheBackend,
+    ttls: HashMap<String, Duration>,
+    description: String,
+}
+
+impl ResponseCache {
+    pub async fn open(config: &CacheConfig) -> Result<Self> {
+        let (backend, description) = match &config.storage {
+            CacheStorage::Memory { max_entries } => (
+                CacheBackend::Memory(MemoryBackend::new(*max_entries)),
+                format!("memory: max {} entries", max_entries),
+            ),
+            #[cfg(feature = "redis")]
+            CacheStorage::Redis { url } => (
+                CacheBackend::Redis(RedisBackend::connect(url).await?),
+                format!("redis: {}", crate::config::mask_password(url)),
+            ),
+        };
+        let mut routes: Vec<_> = config.ttls.iter().map(|(route, ttl)| format!("{}={}s", route, ttl.as_secs())).collect();
+        routes.sort();
+        tracing::info!("[Cache] Caching responses ({}): {}", description, routes.join(", "));
+        Ok(Self { backend, ttls: config.ttls.clone(), description })
+    }
+
+    /// 按环境变量打开，未启用时返回 None
+    pub async fn from_env() -> Result<Option<Self>> {
+        match CacheConfig::from_env()? {
+            Some(config) => Ok(Some(Self::open(&config).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// 后端描述，如 "memory: max 10000 entries"
+    pub fn describe(&self) -> &str {
+        &self.description
+    }
+
+    /// 检查后端可用（就绪探针）
+    pub async fn ping(&self) -> Result<()> {
+        match &self.backend {
+            CacheBackend::Memory(_) => Ok(()),
+            #[cfg(feature = "redis")]
+            CacheBackend::Redis(b) => b.ping().await,
+        }
+    }
+
+    /// 路由的有效期，不缓存的路由返回 None
+    pub fn ttl(&self, route: &str) -> Option<Duration> {
+        self.ttls.get(route).copied()
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<CachedResponse>> {
+        match &self.backend {
+            CacheBackend::Memory(b) => Ok(b.get(key).await),
+            #[cfg(feature = "redis")]
+            CacheBackend::Redis(b) => b.get(key).await,
+        }
+    }
+
+    async fn put(&self, key: &str, entry: &CachedResponse, ttl: Duration) -> Result<()> {
+        match &self.backend {
+            CacheBackend::Memory(b) => {
+                b.put(key, entry, ttl).await;
+                Ok(())
+            }
+            #[cfg(feature = "redis")]
+            CacheBackend::Redis(b) => b.put(key, entry, ttl).await,
+        }
+    }
+}
+
+/// 请求头 `Cache-Control` 中与读取缓存相关的指令
+#[derive(Debug, Default)]
+struct Directives {
+    no_cache: bool,
+    no_store: bool,
+    max_age: Option<u64>,
+}
+
+impl Directives {
+    fn parse(headers: &HeaderMap) -> Self {
+        let mut directives = Self::default();
+        for value in headers.get_all(header::CACHE_CONTROL).iter().filter_map(|v| v.to_str().ok()) {
+            for directive in value.split(',').map(|d| d.trim().to_ascii_lowercase()) {
+                match directive.split_once('=') {
+                    Some(("max-age", secs)) => directives.max_age = secs.trim_matches('"').parse().ok(),
+                    _ if directive == "no-cache" => directives.no_cache = true,
+                    _ if directive == "no-store" => directives.no_store = true,
+                    _ => {}
+                }
+            }
+        }
+        directives
+    }
+}
+
+/// 路由中间件 (`route_layer`)：未启用缓存或路由不在缓存列表时直接放行
+pub async fn middleware(State(state): State<Arc<AppState>>, request: Request, next: Next) -> Response {
+    let bypass = crate::api::dry_run::is_active() || crate::api::raw::is_active();
+    let Some(cache) = state.response_cache.clone().filter(|_| !bypass) else {
+        return next.run(request).await;
+    };
+    let Some(ttl) = request.extensions().get::<MatchedPath>().and_then(|route| cache.ttl(route.as_str())) else {
+        return next.run(request).await;
+    };
+    let directives = Directives::parse(request.headers());
+    if directives.no_store {
+        return with_cache_status(next.run(request).await, "BYPASS");
+    }
+
+    let (parts, body) = request.into_parts();
+    let body = match axum::body::to_bytes(body, MAX_REQUEST_BYTES).await {
+        Ok(body) => body,
+        Err(_) => return StatusCode::PAYLOAD_TOO_LARGE.into_response(),
+    };
+    let account = match state.auth.try_get_credentials().await {
+        Ok(Some(creds)) => creds.user_id,
+        _ => "guest".to_string(),
+    };
+    let key = format!(
+        "{:x}",
+        Sha256::new()
+            .chain_update(account.as_bytes())
+            .chain_update([0])
+            .chain_update(parts.method.as_str().as_bytes())
+            .chain_update([0])
+            .chain_update(parts.uri.path_and_query().map(|pq| pq.as_str()).unwrap_or("").as_bytes())
+            .chain_update([0])
+            .chain_update(&body)
+            .finalize()
+    );
+
+    if !directives.no_cache {
+        match cache.get(&key).await {
+            Ok(Some(entry)) => {
+                let age = entry.age_secs();
+                if age < ttl.as_secs() && directives.max_age.is_none_or(|max| age <= max) {
+                    tracing::debug!("[Cache] HIT {} (age {}s)", parts.uri.path(), age);
+                    return hit(entry, age, ttl);
+                }
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!("[Cache] Lookup failed, fetching from upstream: {}", e),
+        }
+    }
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+    let (mut parts, body) = response.into_parts();
+    let body = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    };
+    if let Some(entry) = cacheable(parts.status, &parts.headers, &body) {
+        if let Err(e) = cache.put(&key, &entry, ttl).await {
+            tracing::warn!("[Cache] Failed to store response: {}", e);
+        }
+        parts.headers.insert(header::CACHE_CONTROL, max_age(ttl.as_secs()));
+    }
+    with_cache_status(Response::from_parts(parts, Body::from(body)), "MISS")
+}
+
+/// 可缓存的响应：200、JSON、`success` 不为 false
+fn cacheable(status: StatusCode, headers: &HeaderMap, body: &[u8]) -> Option<CachedResponse> {
+    let content_type = headers.get(header::CONTENT_TYPE).and_then(|v| v.to_str().ok());
+    if status != StatusCode::OK || !content_type.is_some_and(|t| t.starts_with("application/json")) || body.len() > MAX_RESPONSE_BYTES {
+        return None;
+    }
+    let text = std::str::from_utf8(body).ok()?;
+    let value: serde_json::Value = serde_json::from_str(text).ok()?;
+    if value.get("success").and_then(|s| s.as_bool()) == Some(false) {
+        return None;
+    }
+    Some(CachedResponse {
+        content_type: content_type.map(str::to_string),
+        body: text.to_string(),
+        stored_at: chrono::Utc::now().timestamp_millis(),
+    })
+}
+
+fn hit(entry: CachedResponse, age: u64, ttl: Duration) -> Response {
+    let mut response = Response::new(Body::from(entry.body));
+    let headers = response.headers_mut();
+    if let Some(content_type) = entry.content_type.and_then(|t| HeaderValue::from_str(&t).ok()) {
+        headers.insert(header::CONTENT_TYPE, content_type);
+    }
+    headers.insert(header::AGE, HeaderValue::from(age));
+    headers.insert(header::CACHE_CONTROL, max_age(ttl.as_secs() - age));
+    with_cache_status(response, "HIT")
+}
+
+fn max_age(secs: u64) -> HeaderValue {
+    HeaderValue::from_str(&format!("private, max-age={}", secs)).expect("valid header value")
+}
+
+fn with_cache_status(mut response: Response, status: &'static str) -> Response {
+    response.headers_mut().insert("x-cache", HeaderValue::from_static(status));
+    response
+}
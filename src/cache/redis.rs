@@ -0,0 +1,49 @@
+//! Redis 响应缓存后端
+//!
+//! 每条响应一个 `xhs:cache:{key}` 字符串键 (JSON)，由 Redis 按有效期自动过期，
+//! 多个实例指向同一 Redis 时共享缓存。
+
+use anyhow::{anyhow, Result};
+use redis::aio::ConnectionManager;
+use redis::AsyncCommands;
+use std::time::Duration;
+
+use super::CachedResponse;
+
+/// 键前缀
+const KEY_PREFIX: &str = "xhs:cache:";
+
+/// 启动时连接 Redis 的超时
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub struct RedisBackend {
+    /// 断线后自动重连
+    conn: ConnectionManager,
+}
+
+impl RedisBackend {
+    pub async fn connect(url: &str) -> Result<Self> {
+        let client = redis::Client::open(url)?;
+        let conn = tokio::time::timeout(CONNECT_TIMEOUT, ConnectionManager::new(client))
+            .await
+            .map_err(|_| anyhow!("Timed out connecting to Redis at {}", crate::config::mask_password(url)))??;
+        Ok(Self { conn })
+    }
+
+    /// 检查 Redis 可连接
+    pub async fn ping(&self) -> Result<()> {
+        let _: String = redis::cmd("PING").query_async(&mut self.conn.clone()).await?;
+        Ok(())
+    }
+
+    pub async fn get(&self, key: &str) -> Result<Option<CachedResponse>> {
+        let value: Option<String> = self.conn.clone().get(format!("{}{}", KEY_PREFIX, key)).await?;
+        Ok(value.and_then(|v| serde_json::from_str(&v).ok()))
+    }
+
+    pub async fn put(&self, key: &str, response: &CachedResponse, ttl: Duration) -> Result<()> {
+        let value = serde_json::to_string(response)?;
+        let _: () = self.conn.clone().set_ex(format!("{}{}", KEY_PREFIX, key), value, ttl.as_secs().max(1)).await?;
+        Ok(())
+    }
+}
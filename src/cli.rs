@@ -0,0 +1,445 @@
+//! 命令行子命令 (`xhs-rs <command>`)
+//!
+//! 不启动 HTTP 服务，直接在终端调用库函数:
+//!
+//! - `xhs-rs serve`（默认）: 启动 HTTP 服务
+//! - `xhs-rs login`: 终端扫码登录，凭证写入 cookie.json
+//! - `xhs-rs search <keyword>`: 搜索笔记
+//! - `xhs-rs note <url|note_id>`: 查看笔记详情
+//! - `xhs-rs download <url|note_id>`: 下载笔记的图片 / 视频（或直接下载 CDN 链接）
+//! - `xhs-rs token <tenant>`: 签发多租户 JWT（需 XHS_JWT_SECRET）
+//! - `xhs-rs migrate`: 把凭据 / 存储签名与归档数据复制到另一个存储位置或后端
+//!
+//! 笔记链接支持 `/explore/<id>?xsec_token=...`、`/discovery/item/<id>?xsec_token=...` 与 xhslink.com 短链，
+//! 只给 note_id 时需通过 `--token` 提供 xsec_token。
+
+use anyhow::{anyhow, bail, Result};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::api::login::{self, QrCodeState};
+use crate::api::media::download::{download_media_in, DownloadRequest};
+use crate::api::media::images::{get_image_urls, ImagesRequest};
+use crate::api::media::video::{get_video_urls, VideoRequest};
+use crate::api::note::cache::NoteDetailCache;
+use crate::api::note::detail::{fetch_note_detail, NoteDetailRequest};
+use crate::api::note::resolve::resolve_note_url;
+use crate::api::search::search_notes;
+use crate::api::XhsApiClient;
+use crate::archive::{ArchiveConfig, ResponseArchive};
+use crate::auth::{AuthService, CredentialStorage, UserCredentials};
+use crate::client::XhsClient;
+use crate::models::search::{SearchNoteType, SearchNotesRequest, SearchSort};
+use crate::tenant::jwt::{JwtKey, ADMIN_ROLE};
+use crate::utils::print_qr_to_terminal;
+use crate::utils::url::note_url;
+
+/// 扫码状态轮询间隔
+const QR_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Parser)]
+#[command(name = "xhs-rs", version, about = "小红书 API 工具：HTTP 服务与命令行")]
+pub struct Cli {
+    /// 加载 config/<profile>.env（也可用 XHS_PROFILE）
+    #[arg(long, global = true)]
+    pub profile: Option<String>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// 启动 HTTP 服务（默认）
+    Serve,
+    /// 终端扫码登录
+    Login,
+    /// 搜索笔记
+    Search(SearchArgs),
+    /// 查看笔记详情
+    Note(NoteArgs),
+    /// 下载笔记图片 / 视频
+    Download(DownloadArgs),
+    /// 签发多租户 JWT
+    Token(TokenArgs),
+    /// 迁移凭据与归档数据到另一个存储后端
+    Migrate(MigrateArgs),
+}
+
+impl Command {
+    /// 除 serve 外的子命令只输出结果，日志默认降为 warn
+    pub fn is_serve(&self) -> bool {
+        matches!(self, Command::Serve)
+    }
+}
+
+/// 排序方式
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum SortArg {
+    General,
+    Popular,
+    Latest,
+}
+
+impl SortArg {
+    fn as_api(&self) -> SearchSort {
+        match self {
+            SortArg::General => SearchSort::General,
+            SortArg::Popular => SearchSort::PopularityDescending,
+            SortArg::Latest => SearchSort::TimeDescending,
+        }
+    }
+}
+
+/// 笔记类型
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum NoteTypeArg {
+    All,
+    Image,
+    Video,
+}
+
+impl NoteTypeArg {
+    fn as_api(&self) -> SearchNoteType {
+        match self {
+            NoteTypeArg::All => SearchNoteType::All,
+            NoteTypeArg::Image => SearchNoteType::Image,
+            NoteTypeArg::Video => SearchNoteType::Video,
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct SearchArgs {
+    /// 搜索关键词
+    pub keyword: String,
+    /// 页码
+    #[arg(long, default_value_t = 1)]
+    pub page: i32,
+    #[arg(long, value_enum, default_value_t = SortArg::General)]
+    pub sort: SortArg,
+    #[arg(long = "type", value_enum, default_value_t = NoteTypeArg::All)]
+    pub note_type: NoteTypeArg,
+    /// 输出原始 JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct NoteArgs {
+    /// 笔记链接或 note_id
+    pub target: String,
+    /// xsec_token（链接中不含时必填）
+    #[arg(long)]
+    pub token: Option<String>,
+    /// 输出原始 JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct DownloadArgs {
+    /// 笔记链接、note_id 或 xhscdn 媒体链接
+    pub target: String,
+    /// xsec_token（链接中不含时必填）
+    #[arg(long)]
+    pub token: Option<String>,
+    /// 保存目录（默认 XHS_DOWNLOAD_ROOT）
+    #[arg(long)]
+    pub out: Option<PathBuf>,
+    /// 下载使用的代理（`direct` 表示直连）
+    #[arg(long)]
+    pub proxy: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct TokenArgs {
+    /// 租户 ID（写入 `sub`）
+    pub tenant: String,
+    /// 签发管理员令牌（使用部署自身的账号，可访问全部接口）
+    #[arg(long)]
+    pub admin: bool,
+    /// 有效期（天）
+    #[arg(long, default_value_t = 30)]
+    pub ttl_days: i64,
+}
+
+#[derive(Debug, Args)]
+pub struct MigrateArgs {
+    /// 源归档：目录或 mongodb:// 连接串（默认使用 XHS_ARCHIVE_* 配置的归档）
+    #[arg(long)]
+    pub from: Option<String>,
+    /// 目标归档：目录或 mongodb:// 连接串（不指定则不迁移归档）
+    #[arg(long)]
+    pub to: Option<String>,
+    /// 源 Mongo 数据库名
+    #[arg(long, default_value = "xhs_archive")]
+    pub from_db: String,
+    /// 目标 Mongo 数据库名
+    #[arg(long, default_value = "xhs_archive")]
+    pub to_db: String,
+    /// 源凭据文件（同目录的 .signatures.json 一并迁移）
+    #[arg(long, default_value = "cookie.json")]
+    pub credentials_from: PathBuf,
+    /// 目标凭据文件（不指定则不迁移凭据）
+    #[arg(long)]
+    pub credentials_to: Option<PathBuf>,
+}
+
+/// 执行子命令
+pub async fn run(command: Command) -> Result<()> {
+    match command {
+        Command::Serve => crate::server::start_server().await,
+        Command::Login => {
+            let auth = AuthService::new(PathBuf::from("cookie.json")).await?;
+            qr_login(&auth).await
+        }
+        Command::Search(args) => search(&api_client().await?, args).await,
+        Command::Note(args) => note(&api_client().await?, args).await,
+        Command::Download(args) => download(args).await,
+        Command::Token(args) => token(args),
+        Command::Migrate(args) => migrate(args).await,
+    }
+}
+
+/// 与 HTTP 服务相同的客户端配置（cookie.json + 行为画像）
+async fn api_client() -> Result<XhsApiClient> {
+    let auth = Arc::new(AuthService::new(PathBuf::from("cookie.json")).await?);
+    if auth.try_get_credentials().await?.is_none() {
+        bail!("cookie.json 中没有可用凭证，请先运行 `xhs-rs login`");
+    }
+    let activity = Arc::new(crate::activity::ActivityGovernor::from_env()?);
+    Ok(XhsApiClient::new(XhsClient::new()?, auth).with_activity(activity))
+}
+
+/// 终端扫码登录
+async fn qr_login(auth: &AuthService) -> Result<()> {
+    let guest_cookies = login::fetch_guest_cookies().await?;
+    let created = login::create_qrcode(&guest_cookies).await?;
+    let mut qr = created.data.ok_or_else(|| anyhow!("二维码数据缺失"))?;
+    print_qr_to_terminal(&qr.url, "请使用小红书 App 扫码登录")?;
+
+    loop {
+        tokio::time::sleep(QR_POLL_INTERVAL).await;
+        let (resp, new_cookies) = login::check_qrcode_status(&guest_cookies, &qr.qr_id, &qr.code).await?;
+        match resp.state() {
+            QrCodeState::Waiting | QrCodeState::Scanned | QrCodeState::Cancelled => continue,
+            QrCodeState::Expired => {
+                qr = login::create_qrcode(&guest_cookies).await?
+                    .data
+                    .ok_or_else(|| anyhow!("二维码数据缺失"))?;
+                print_qr_to_terminal(&qr.url, "二维码已过期，请重新扫码")?;
+            }
+            QrCodeState::Confirmed => {
+                // 只使用登录后同步的完整 Cookie，不与游客 Cookie 合并（避免 461）
+                let cookies = new_cookies.ok_or_else(|| anyhow!("登录已确认但未返回 Cookie"))?;
+                let user_id = resp.data
+                    .and_then(|d| d.login_info)
+                    .and_then(|info| info.user_id)
+                    .unwrap_or_else(|| "unknown".to_string());
+                auth.save_credentials(&UserCredentials::new(user_id.clone(), cookies, None)).await?;
+                println!("✅ 登录成功: {}", user_id);
+                return Ok(());
+            }
+            QrCodeState::Conflict | QrCodeState::Error => {
+                bail!("扫码登录失败: {:?} {}", resp.state(), resp.msg.unwrap_or_default());
+            }
+        }
+    }
+}
+
+async fn search(api: &XhsApiClient, args: SearchArgs) -> Result<()> {
+    let req = SearchNotesRequest {
+        page: args.page,
+        sort: args.sort.as_api(),
+        note_type: args.note_type.as_api(),
+        ..SearchNotesRequest::new(args.keyword.clone())
+    };
+    let result = search_notes(api, req).await?;
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+        return Ok(());
+    }
+
+    let items = result.data.map(|d| d.items).unwrap_or_default();
+    println!("搜索 \"{}\" 第 {} 页: {} 条结果", args.keyword, args.page, items.len());
+    for item in &items {
+        let Some(card) = &item.note_card else { continue };
+        let kind = if card.note_type.as_deref() == Some("video") { "视频" } else { "图文" };
+        let author = card.user.as_ref().and_then(|u| u.nickname.as_deref()).unwrap_or("-");
+        let likes = card.interact_info.as_ref().and_then(|i| i.liked_count.as_deref()).unwrap_or("0");
+        println!(
+            "\n[{}] {} - {} (♥ {})",
+            kind,
+            card.display_title.as_deref().unwrap_or("(无标题)"),
+            author,
+            likes,
+        );
+        println!("  {}", note_url(&item.id, item.xsec_token.as_deref(), Some("pc_search")));
+    }
+    Ok(())
+}
+
+async fn note(api: &XhsApiClient, args: NoteArgs) -> Result<()> {
+    let (note_id, xsec_token) = resolve_note(&args.target, args.token).await?;
+    let cache = NoteDetailCache::new(Duration::ZERO);
+    let detail = fetch_note_detail(api, &cache, NoteDetailRequest {
+        source_note_id: note_id.clone(),
+        image_formats: vec!["jpg".to_string(), "webp".to_string(), "avif".to_string()],
+        extra: Some(serde_json::json!({ "need_body_topic": "1" })),
+        xsec_source: "pc_feed".to_string(),
+        xsec_token,
+    }).await?;
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&detail)?);
+        return Ok(());
+    }
+
+    let card = detail.note_card().ok_or_else(|| anyhow!("笔记 {} 详情为空", note_id))?;
+    let text = |pointer: &str| card.pointer(pointer).and_then(|v| v.as_str()).unwrap_or("").to_string();
+    println!("{}", text("/title"));
+    println!("作者: {}  类型: {}", text("/user/nickname"), text("/type"));
+    println!(
+        "点赞 {}  收藏 {}  评论 {}",
+        text("/interact_info/liked_count"),
+        text("/interact_info/collected_count"),
+        text("/interact_info/comment_count"),
+    );
+    let desc = text("/desc");
+    if !desc.is_empty() {
+        println!("\n{}", desc);
+    }
+    Ok(())
+}
+
+async fn download(args: DownloadArgs) -> Result<()> {
+    let out = args.out.unwrap_or_else(crate::config::download_root);
+
+    // CDN 媒体链接直接下载
+    if let Ok(url) = url::Url::parse(&args.target) {
+        if url.host_str().is_some_and(|h| h.ends_with("xhscdn.com")) {
+            let name = url.path_segments()
+                .and_then(|mut s| s.next_back())
+                .filter(|s| !s.is_empty())
+                .unwrap_or("media");
+            return save(&out, &args.target, PathBuf::from(name), args.proxy).await;
+        }
+    }
+
+    let api = api_client().await?;
+    let (note_id, xsec_token) = resolve_note(&args.target, args.token).await?;
+    let dir = PathBuf::from(&note_id);
+
+    let videos = get_video_urls(&api, VideoRequest {
+        note_id: note_id.clone(),
+        xsec_token: xsec_token.clone(),
+    }).await?;
+    if let Some(data) = videos.data.filter(|d| !d.videos.is_empty()) {
+        // 视频列表已按文件大小降序排列，取画质最高的一条
+        println!("{} - {} (视频)", data.title, data.author);
+        return save(&out, &data.videos[0].url, dir.join("video.mp4"), args.proxy).await;
+    }
+
+    let images = get_image_urls(&api, ImagesRequest { note_id: note_id.clone(), xsec_token }).await?;
+    let data = images.data.ok_or_else(|| anyhow!("笔记 {} 无可下载媒体: {}", note_id, images.msg.unwrap_or_default()))?;
+    println!("{} - {} ({} 张图片，{} 张实况图)", data.title, data.author, data.image_count, data.live_photo_count);
+    for image in &data.images {
+        save(&out, &image.url_original, dir.join(format!("{}.jpg", image.index)), args.proxy.clone()).await?;
+        if let Some(motion) = &image.motion {
+            save(&out, &motion.url, dir.join(format!("{}.mp4", image.index)), args.proxy.clone()).await?;
+        }
+    }
+    Ok(())
+}
+
+fn token(args: TokenArgs) -> Result<()> {
+    let key = JwtKey::from_env().ok_or_else(|| anyhow!("请先设置 XHS_JWT_SECRET"))?;
+    let role = args.admin.then_some(ADMIN_ROLE);
+    println!("{}", key.issue(&args.tenant, role, args.ttl_days * 86_400)?);
+    Ok(())
+}
+
+/// 下载到 `out` 下的相对路径 `path`
+async fn save(out: &Path, url: &str, path: PathBuf, proxy: Option<String>) -> Result<()> {
+    let resp = download_media_in(out, DownloadRequest {
+        url: url.to_string(),
+        save_path: path.to_string_lossy().into_owned(),
+        proxy,
+        ..Default::default()
+    }, |_, _| {}).await?;
+    match resp.data {
+        Some(saved) => println!("  ✓ {} ({} bytes)", saved.saved_path, saved.file_size),
+        None => bail!("下载失败: {}", resp.msg.unwrap_or_default()),
+    }
+    Ok(())
+}
+
+/// 从笔记链接（含短链）或 note_id 解析 (note_id, xsec_token)
+async fn resolve_note(target: &str, token: Option<String>) -> Result<(String, String)> {
+    let (note_id, url_token) = match url::Url::parse(target) {
+        Ok(_) => {
+            let resolved = resolve_note_url(target).await
+                .map_err(|e| anyhow!("无法从链接中识别笔记 ID: {} ({})", target, e))?;
+            (resolved.note_id, resolved.xsec_token)
+        }
+        Err(_) => (target.to_string(), None),
+    };
+    let xsec_token = token.or(url_token)
+        .ok_or_else(|| anyhow!("缺少 xsec_token：请使用带 xsec_token 的笔记链接或传入 --token"))?;
+    Ok((note_id, xsec_token))
+}
+
+async fn migrate(args: MigrateArgs) -> Result<()> {
+    if args.to.is_none() && args.credentials_to.is_none() {
+        bail!("请指定 --to（归档）或 --credentials-to（凭据）");
+    }
+
+    if let Some(target) = &args.credentials_to {
+        if target == &args.credentials_from {
+            bail!("--credentials-from 与 --credentials-to 相同");
+        }
+        let source = CredentialStorage::new(args.credentials_from.clone()).await?;
+        let credentials = source.read_credentials().await?
+            .ok_or_else(|| anyhow!("{} 中没有凭据", args.credentials_from.display()))?;
+        let signatures = source.api_signatures().await?;
+        if let Some(parent) = target.parent().filter(|p| !p.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let destination = CredentialStorage::new(target.clone()).await?;
+        destination.save_credentials(&credentials).await?;
+        if !signatures.is_empty() {
+            destination.save_api_signatures(&signatures).await?;
+        }
+        println!(
+            "✅ 凭据 {} ({}) 与 {} 个存储签名 → {}",
+            credentials.user_id,
+            if credentials.is_valid { "有效" } else { "已失效" },
+            signatures.len(),
+            target.display()
+        );
+    }
+
+    if let Some(to) = &args.to {
+        if args.from.as_deref() == Some(to.as_str()) && args.from_db == args.to_db {
+            bail!("源归档与目标归档相同: {}", to);
+        }
+        let source_config = match &args.from {
+            Some(from) => ArchiveConfig::from_location(from, &args.from_db, 9)?,
+            None => ArchiveConfig::from_env()?
+                .ok_or_else(|| anyhow!("未配置归档（XHS_ARCHIVE_BACKEND），请用 --from 指定源归档"))?,
+        };
+        let source = ResponseArchive::open(&source_config).await?;
+        let target = ResponseArchive::open(&ArchiveConfig::from_location(to, &args.to_db, 9)?).await?;
+        let report = source.migrate_to(&target).await?;
+        println!("✅ 归档 {} → {}", source.describe(), target.describe());
+        println!("  字典:     {}", report.dictionaries);
+        println!("  原始响应: {}（已存在跳过 {}）", report.responses, report.responses_skipped);
+        println!("  笔记:     {}", report.notes);
+        println!("  变更记录: {}", report.revisions);
+        println!("  评论:     {}", report.comments);
+        println!("  笔记事件: {}", report.events);
+    }
+    Ok(())
+}
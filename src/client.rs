@@ -1,26 +1,353 @@
-use std::sync::Arc;
-use reqwest::{Client, cookie::Jar};
-use anyhow::Result;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use reqwest::{Client, ClientBuilder, NoProxy, Proxy, cookie::Jar};
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use anyhow::{anyhow, Result};
+
+use crate::api::vcr::Cassette;
+
+/// Proxy value that forces a direct connection, ignoring XHS_PROXY
+pub const DIRECT: &str = "direct";
+
+/// Default browser User-Agent of the main client
+const DEFAULT_USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
+
+/// Cached shared clients above this count are dropped (per-request proxies are caller-supplied)
+const MAX_SHARED_CLIENTS: usize = 32;
+
+/// Create a reqwest builder for XHS-bound traffic, routed through the configured proxy
+///
+/// `proxy` overrides XHS_PROXY for this client (`http://`, `https://`, `socks5://` or
+/// `socks5h://` URL, optionally with `user:pass@`); `"direct"` disables proxying.
+/// Traffic to the local signing Agent should keep using a plain client.
+pub fn client_builder(proxy: Option<&str>) -> Result<ClientBuilder> {
+    let builder = Client::builder();
+    let proxy = proxy
+        .map(str::to_string)
+        .or_else(crate::config::proxy_url)
+        .filter(|p| !p.is_empty());
+    match proxy.as_deref() {
+        None => Ok(builder),
+        Some(DIRECT) => Ok(builder.no_proxy()),
+        Some(url) => {
+            let proxy = Proxy::all(url)
+                .map_err(|e| anyhow!("Invalid proxy {}: {}", url, e))?
+                .no_proxy(crate::config::no_proxy().and_then(|list| NoProxy::from_string(&list)));
+            Ok(builder.proxy(proxy))
+        }
+    }
+}
+
+/// TLS / HTTP2 fingerprint presented to XHS, set via XHS_TLS_PROFILE or [`XhsClientBuilder::tls_profile`]
+///
+/// XHS risk control correlates the TLS ClientHello (JA3/JA4) and HTTP/2 SETTINGS with the
+/// claimed Chrome User-Agent; the platform OpenSSL handshake looks nothing like Chrome.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TlsProfile {
+    /// Platform TLS stack (native-tls) with reqwest's HTTP/2 defaults
+    #[default]
+    Native,
+    /// rustls with Chrome's cipher suite / key-share order and ALPN, plus Chrome's HTTP/2 window
+    /// and header-list settings. Requires the `tls-impersonate` feature. rustls sends no GREASE,
+    /// ALPS or certificate compression, so the JA3 is close to but not identical with real Chrome.
+    Chrome,
+}
+
+impl TlsProfile {
+    /// XHS_TLS_PROFILE: `native` (default) or `chrome`
+    pub fn from_env() -> Result<Self> {
+        match std::env::var("XHS_TLS_PROFILE").unwrap_or_default().trim().to_lowercase().as_str() {
+            "" | "native" | "default" => Ok(Self::Native),
+            "chrome" => Ok(Self::Chrome),
+            other => Err(anyhow!("Invalid XHS_TLS_PROFILE '{}' (expected native or chrome)", other)),
+        }
+    }
+
+    /// Apply the TLS backend and HTTP/2 settings of this profile
+    fn apply(self, builder: ClientBuilder) -> Result<ClientBuilder> {
+        match self {
+            Self::Native => Ok(builder),
+            #[cfg(feature = "tls-impersonate")]
+            Self::Chrome => Ok(builder
+                .use_preconfigured_tls(chrome_tls_config()?)
+                .http2_initial_stream_window_size(6 * 1024 * 1024)
+                .http2_initial_connection_window_size(15 * 1024 * 1024)
+                .http2_max_header_list_size(256 * 1024)),
+            #[cfg(not(feature = "tls-impersonate"))]
+            Self::Chrome => Err(anyhow!("TLS profile 'chrome' requires building with the tls-impersonate feature")),
+        }
+    }
+}
+
+/// rustls client config ordered like Chrome's ClientHello
+#[cfg(feature = "tls-impersonate")]
+fn chrome_tls_config() -> Result<rustls::ClientConfig> {
+    use rustls::crypto::ring::{cipher_suite::*, kx_group, default_provider};
+
+    let provider = rustls::crypto::CryptoProvider {
+        cipher_suites: vec![
+            TLS13_AES_128_GCM_SHA256,
+            TLS13_AES_256_GCM_SHA384,
+            TLS13_CHACHA20_POLY1305_SHA256,
+            TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256,
+            TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256,
+            TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384,
+            TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384,
+            TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256,
+            TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256,
+        ],
+        kx_groups: vec![kx_group::X25519, kx_group::SECP256R1, kx_group::SECP384R1],
+        ..default_provider()
+    };
+    let roots = rustls::RootCertStore { roots: webpki_roots::TLS_SERVER_ROOTS.to_vec() };
+    let mut config = rustls::ClientConfig::builder_with_provider(std::sync::Arc::new(provider))
+        .with_protocol_versions(&[&rustls::version::TLS13, &rustls::version::TLS12])?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(config)
+}
+
+/// HTTP options for [`XhsClient`] and the process-wide clients used by Creator Center and media downloads
+///
+/// Unset options keep reqwest's defaults (no overall timeout, unlimited idle connections per host).
+#[derive(Debug, Clone, Default)]
+pub struct XhsClientBuilder {
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    pool_idle_timeout: Option<Duration>,
+    pool_max_idle_per_host: Option<usize>,
+    http2_prior_knowledge: bool,
+    http2_keep_alive_interval: Option<Duration>,
+    http2_adaptive_window: bool,
+    /// None follows XHS_PROXY
+    proxy: Option<String>,
+    default_headers: HeaderMap,
+    user_agent: Option<String>,
+    /// None follows XHS_TLS_PROFILE
+    tls_profile: Option<TlsProfile>,
+    /// None follows XHS_VCR_MODE
+    cassette: Option<Arc<Cassette>>,
+}
+
+impl XhsClientBuilder {
+    /// Overall per-request timeout (connect + headers + body)
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// TCP / TLS connect timeout
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// How long idle pooled connections are kept open
+    pub fn pool_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.pool_idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Maximum idle pooled connections per host
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.pool_max_idle_per_host = Some(max);
+        self
+    }
+
+    /// Speak HTTP/2 without ALPN negotiation
+    pub fn http2_prior_knowledge(mut self, enabled: bool) -> Self {
+        self.http2_prior_knowledge = enabled;
+        self
+    }
+
+    /// Interval of HTTP/2 PING frames that keep idle connections alive
+    pub fn http2_keep_alive_interval(mut self, interval: Duration) -> Self {
+        self.http2_keep_alive_interval = Some(interval);
+        self
+    }
+
+    /// Use adaptive HTTP/2 flow-control windows
+    pub fn http2_adaptive_window(mut self, enabled: bool) -> Self {
+        self.http2_adaptive_window = enabled;
+        self
+    }
+
+    /// Explicit proxy instead of XHS_PROXY (`"direct"` for none), see [`client_builder`]
+    pub fn proxy(mut self, proxy: impl Into<String>) -> Self {
+        self.proxy = Some(proxy.into());
+        self
+    }
+
+    /// Headers sent with every request (per-request headers take precedence)
+    pub fn default_headers(mut self, headers: HeaderMap) -> Self {
+        self.default_headers = headers;
+        self
+    }
+
+    /// User-Agent of the main client (signed requests still send the account fingerprint's UA)
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// TLS / HTTP2 fingerprint instead of XHS_TLS_PROFILE
+    pub fn tls_profile(mut self, profile: TlsProfile) -> Self {
+        self.tls_profile = Some(profile);
+        self
+    }
+
+    /// Record signed XHS traffic to, or replay it from, `cassette` instead of XHS_VCR_MODE (see [`crate::api::vcr`])
+    ///
+    /// Only the main client uses the cassette; shared Creator Center / media clients always go live.
+    pub fn cassette(mut self, cassette: Arc<Cassette>) -> Self {
+        self.cassette = Some(cassette);
+        self
+    }
+
+    /// Build the main client with a fresh cookie store
+    pub fn build(self) -> Result<XhsClient> {
+        XhsClient::build(self, Arc::new(Jar::default()))
+    }
+
+    /// Use these options for the process-wide Creator Center / media download clients
+    ///
+    /// Clients already handed out keep their options; later calls get rebuilt ones.
+    pub fn install(self) {
+        *shared_clients() = SharedClients { builder: self, clients: HashMap::new() };
+    }
+
+    /// reqwest builder with these options applied; `proxy` overrides the configured proxy
+    pub(crate) fn reqwest_builder(&self, proxy: Option<&str>) -> Result<ClientBuilder> {
+        self.apply(client_builder(proxy.or(self.proxy.as_deref()))?)
+    }
+
+    /// Apply the configured options on top of `builder`
+    fn apply(&self, builder: ClientBuilder) -> Result<ClientBuilder> {
+        let profile = match self.tls_profile {
+            Some(profile) => profile,
+            None => TlsProfile::from_env()?,
+        };
+        let mut builder = profile.apply(builder.default_headers(self.default_headers.clone()))?;
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = self.pool_idle_timeout {
+            builder = builder.pool_idle_timeout(timeout);
+        }
+        if let Some(max) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max);
+        }
+        if self.http2_prior_knowledge {
+            builder = builder.http2_prior_knowledge();
+        }
+        if let Some(interval) = self.http2_keep_alive_interval {
+            builder = builder.http2_keep_alive_interval(interval).http2_keep_alive_while_idle(true);
+        }
+        if self.http2_adaptive_window {
+            builder = builder.http2_adaptive_window(true);
+        }
+        Ok(builder)
+    }
+}
+
+/// Process-wide clients, keyed by purpose and proxy
+struct SharedClients {
+    builder: XhsClientBuilder,
+    clients: HashMap<String, Client>,
+}
+
+fn shared_clients() -> std::sync::MutexGuard<'static, SharedClients> {
+    static SHARED: std::sync::OnceLock<Mutex<SharedClients>> = std::sync::OnceLock::new();
+    SHARED
+        .get_or_init(|| Mutex::new(SharedClients { builder: XhsClientBuilder::default(), clients: HashMap::new() }))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+/// Process-wide client for `purpose`, built once from the installed [`XhsClientBuilder`] and reused
+///
+/// Shared clients have no cookie store; callers send cookies explicitly. `customize` sets
+/// purpose-specific defaults (installed options take precedence) and runs only when the client
+/// is (re)built, so it must not depend on per-call state.
+pub fn shared_client(
+    purpose: &str,
+    proxy: Option<&str>,
+    customize: impl FnOnce(ClientBuilder) -> ClientBuilder,
+) -> Result<Client> {
+    let key = format!("{}|{}", purpose, proxy.unwrap_or_default());
+    let mut shared = shared_clients();
+    if let Some(client) = shared.clients.get(&key) {
+        return Ok(client.clone());
+    }
+    let base = client_builder(proxy.or(shared.builder.proxy.as_deref()))?;
+    let client = shared.builder.apply(customize(base))?.build()?;
+    if shared.clients.len() >= MAX_SHARED_CLIENTS {
+        shared.clients.clear();
+    }
+    shared.clients.insert(key, client.clone());
+    Ok(client)
+}
+
+/// Drop cached shared clients so they are rebuilt with the current XHS_PROXY / XHS_NO_PROXY
+pub fn reset_shared_clients() {
+    shared_clients().clients.clear();
+}
 
 #[derive(Clone)]
 pub struct XhsClient {
     http_client: Client,
     cookie_store: Arc<Jar>,
+    /// Options the client was built with, reused by `reconnect`
+    options: XhsClientBuilder,
 }
 
 impl XhsClient {
     pub fn new() -> Result<Self> {
-        let cookie_store = Arc::new(Jar::default());
+        Self::builder().build()
+    }
+
+    /// Configure timeouts, connection pool, HTTP/2, proxy and default headers
+    pub fn builder() -> XhsClientBuilder {
+        XhsClientBuilder::default()
+    }
+
+    /// Same as `new`, but with an explicit proxy instead of XHS_PROXY (`"direct"` for none)
+    pub fn with_proxy(proxy: Option<&str>) -> Result<Self> {
+        match proxy {
+            Some(proxy) => Self::builder().proxy(proxy).build(),
+            None => Self::new(),
+        }
+    }
+
+    /// Rebuild the underlying client (re-reading XHS_PROXY / XHS_NO_PROXY), keeping the cookie store
+    pub fn reconnect(&self) -> Result<Self> {
+        Self::build(self.options.clone(), self.cookie_store.clone())
+    }
+
+    fn build(mut options: XhsClientBuilder, cookie_store: Arc<Jar>) -> Result<Self> {
+        // Resolved once, so `reconnect` keeps recording to / replaying from the same cassette
+        if options.cassette.is_none() {
+            options.cassette = Cassette::from_env()?.map(Arc::new);
+        }
+
         // Configure the client with a standard browser User-Agent
-        let client = Client::builder()
+        let client = options
+            .reqwest_builder(None)?
             .cookie_store(true)
             .cookie_provider(cookie_store.clone())
-            .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")
+            .user_agent(options.user_agent.as_deref().unwrap_or(DEFAULT_USER_AGENT))
             .build()?;
 
         Ok(Self {
             http_client: client,
             cookie_store,
+            options,
         })
     }
 
@@ -31,4 +358,20 @@ impl XhsClient {
     pub fn get_cookie_store(&self) -> Arc<Jar> {
         self.cookie_store.clone()
     }
+
+    /// Cassette signed requests are recorded to / replayed from, if any
+    pub fn cassette(&self) -> Option<&Arc<Cassette>> {
+        self.options.cassette.as_ref()
+    }
+
+    /// Send a request built with this client and read the whole response, through the cassette if one is attached
+    pub async fn execute(&self, request: reqwest::Request) -> crate::error::Result<(StatusCode, HeaderMap, String)> {
+        if let Some(cassette) = &self.options.cassette {
+            return cassette.execute(&self.http_client, request).await;
+        }
+        let response = self.http_client.execute(request).await?;
+        let status = response.status();
+        let headers = response.headers().clone();
+        Ok((status, headers, response.text().await?))
+    }
 }
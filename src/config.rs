@@ -2,7 +2,210 @@
 //!
 //! 统一管理应用配置，支持环境变量覆盖
 
-use std::sync::LazyLock;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{LazyLock, OnceLock, RwLock};
+use utoipa::ToSchema;
+
+// ============================================================================
+// 环境配置文件 (Profiles)
+// ============================================================================
+
+/// 当前生效的配置 profile
+static ACTIVE_PROFILE: OnceLock<String> = OnceLock::new();
+
+/// 从命令行参数中解析 `--profile <name>` / `--profile=<name>`，其次读取 XHS_PROFILE
+pub fn profile_from_args(args: &[String]) -> Option<String> {
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--profile" {
+            return iter.next().cloned();
+        }
+        if let Some(name) = arg.strip_prefix("--profile=") {
+            return Some(name.to_string());
+        }
+    }
+    std::env::var("XHS_PROFILE").ok().filter(|p| !p.is_empty())
+}
+
+/// 加载 profile 配置文件 `{XHS_CONFIG_DIR:-config}/{profile}.env`
+///
+/// 优先级: 进程环境变量 > profile 文件 > .env。
+/// 必须在读取任何配置之前调用（main 开头）。
+pub fn load_profile(profile: Option<&str>) -> anyhow::Result<()> {
+    let _ = PROCESS_ENV_KEYS.set(
+        RELOADABLE_KEYS.iter().copied().filter(|key| std::env::var_os(key).is_some()).collect(),
+    );
+    let mut profile_file = None;
+    if let Some(name) = profile {
+        let dir = std::env::var("XHS_CONFIG_DIR").unwrap_or_else(|_| "config".to_string());
+        let path = PathBuf::from(dir).join(format!("{}.env", name));
+        if !path.exists() {
+            return Err(anyhow::anyhow!("Profile config not found: {}", path.display()));
+        }
+        dotenv::from_path(&path)
+            .map_err(|e| anyhow::anyhow!("Failed to load {}: {}", path.display(), e))?;
+        profile_file = Some(path);
+    }
+    dotenv::dotenv().ok();
+    
+    let _ = PROFILE_FILE.set(profile_file);
+    let _ = ACTIVE_PROFILE.set(profile.unwrap_or("default").to_string());
+    Ok(())
+}
+
+/// 当前 profile 名称（未指定时为 "default"）
+pub fn active_profile() -> &'static str {
+    ACTIVE_PROFILE.get().map(String::as_str).unwrap_or("default")
+}
+
+// ============================================================================
+// 配置热加载 (Hot Reload)
+// ============================================================================
+
+/// 运行时修改配置文件即可生效的配置项，其余配置修改后需重启
+pub const RELOADABLE_KEYS: &[&str] = &[
+    "XHS_RATE_LIMIT_RPM",
+    "XHS_RATE_LIMIT_BURST",
+    "XHS_RATE_LIMIT_ENDPOINTS",
+    "XHS_PROXY",
+    "XHS_NO_PROXY",
+    "XHS_WEBHOOK_URLS",
+    "XHS_WEBHOOK_SECRET",
+    "XHS_LOG_LEVEL",
+];
+
+/// 启动时已在进程环境变量中的可热加载项（优先级高于配置文件，热加载不覆盖）
+static PROCESS_ENV_KEYS: OnceLock<Vec<&'static str>> = OnceLock::new();
+
+/// 已加载的 profile 配置文件
+static PROFILE_FILE: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+/// 热加载后的可热加载项取值与时间 (ms)；未热加载过时为 None，沿用启动时的环境变量
+static RELOADED: RwLock<Option<(HashMap<String, String>, i64)>> = RwLock::new(None);
+
+/// 可热加载项的当前取值
+fn reloadable_var(key: &str) -> Option<String> {
+    match RELOADED.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        Some((values, _)) => values.get(key).cloned(),
+        None => std::env::var(key).ok(),
+    }
+}
+
+/// 热加载读取的配置文件（profile 文件在前，优先级更高）
+pub fn config_files() -> Vec<PathBuf> {
+    PROFILE_FILE.get().cloned().flatten().into_iter().chain([PathBuf::from(".env")]).collect()
+}
+
+/// 重新读取配置文件中的可热加载项，返回取值有变化的配置项
+///
+/// 优先级与启动时一致: 进程环境变量 > profile 文件 > .env；从文件中删除的配置项恢复默认值
+pub fn reload() -> anyhow::Result<Vec<&'static str>> {
+    let process_keys = PROCESS_ENV_KEYS.get().map(Vec::as_slice).unwrap_or_default();
+    let mut values = HashMap::new();
+    for key in process_keys {
+        if let Ok(value) = std::env::var(key) {
+            values.insert(key.to_string(), value);
+        }
+    }
+    for path in config_files() {
+        // .env 可以不存在，profile 文件必须存在
+        if !path.exists() && PROFILE_FILE.get().is_none_or(|profile| profile.as_ref() != Some(&path)) {
+            continue;
+        }
+        // 与启动时 dotenv::from_path 使用同一解析器，但只读取、不写入进程环境变量
+        #[allow(deprecated)]
+        let iter = dotenv::from_path_iter(&path)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?;
+        for item in iter {
+            let (key, value) = item.map_err(|e| anyhow::anyhow!("Failed to parse {}: {}", path.display(), e))?;
+            if RELOADABLE_KEYS.contains(&key.as_str()) && !values.contains_key(&key) {
+                values.insert(key, value);
+            }
+        }
+    }
+
+    let changed = RELOADABLE_KEYS
+        .iter()
+        .copied()
+        .filter(|key| reloadable_var(key).as_ref() != values.get(*key))
+        .collect();
+    *RELOADED.write().unwrap_or_else(|e| e.into_inner()) = Some((values, chrono::Utc::now().timestamp_millis()));
+    Ok(changed)
+}
+
+/// 最近一次热加载的时间 (ms)
+pub fn last_reload() -> Option<i64> {
+    RELOADED.read().unwrap_or_else(|e| e.into_inner()).as_ref().map(|(_, at)| *at)
+}
+
+/// 可热加载项的来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigSource {
+    /// 进程环境变量
+    Env,
+    /// profile 文件或 .env
+    File,
+    /// 未设置，使用默认值
+    Default,
+}
+
+/// 一个可热加载项的当前取值
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ConfigSetting {
+    pub key: String,
+    /// 当前取值（密钥与代理密码已脱敏）
+    pub value: Option<String>,
+    pub source: ConfigSource,
+}
+
+/// 所有可热加载项的当前取值
+pub fn reloadable_settings() -> Vec<ConfigSetting> {
+    let process_keys = PROCESS_ENV_KEYS.get().map(Vec::as_slice).unwrap_or_default();
+    RELOADABLE_KEYS
+        .iter()
+        .map(|key| {
+            let value = reloadable_var(key);
+            let source = match &value {
+                _ if process_keys.contains(key) => ConfigSource::Env,
+                Some(_) => ConfigSource::File,
+                None => ConfigSource::Default,
+            };
+            let value = value.map(|value| match *key {
+                "XHS_WEBHOOK_SECRET" => "******".to_string(),
+                "XHS_PROXY" => mask_password(&value),
+                _ => value,
+            });
+            ConfigSetting { key: key.to_string(), value, source }
+        })
+        .collect()
+}
+
+/// 连接串中的密码替换为 ******
+pub(crate) fn mask_password(url: &str) -> String {
+    match url::Url::parse(url) {
+        Ok(mut parsed) if parsed.password().is_some() => {
+            let _ = parsed.set_password(Some("******"));
+            parsed.to_string()
+        }
+        _ => url.to_string(),
+    }
+}
+
+/// 是否监听配置文件变更，通过 XHS_CONFIG_WATCH 配置（0 / false / off 关闭），默认开启
+pub fn config_watch_enabled() -> bool {
+    std::env::var("XHS_CONFIG_WATCH")
+        .map(|v| !matches!(v.trim().to_lowercase().as_str(), "0" | "false" | "off" | "no"))
+        .unwrap_or(true)
+}
+
+/// 控制台日志级别，通过 XHS_LOG_LEVEL 配置（trace / debug / info / warn / error），
+/// 未设置时服务端为 info、命令行子命令为 warn；可热加载
+pub fn log_level() -> Option<tracing::Level> {
+    reloadable_var("XHS_LOG_LEVEL").and_then(|v| v.trim().parse().ok())
+}
 
 /// Agent 配置
 pub struct AgentConfig {
@@ -39,3 +242,517 @@ pub fn get_agent_url() -> &'static str {
 pub fn is_container_mode() -> bool {
     AGENT_CONFIG.is_container_mode
 }
+
+/// 笔记详情缓存有效期（秒），通过 XHS_NOTE_CACHE_TTL_SECS 配置，0 表示禁用
+pub fn note_cache_ttl() -> std::time::Duration {
+    let secs = std::env::var("XHS_NOTE_CACHE_TTL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(60);
+    std::time::Duration::from_secs(secs)
+}
+
+/// 凭据存储是否为内存（XHS_STORAGE=memory），默认 file 使用 JSON 文件
+pub fn is_memory_storage() -> bool {
+    std::env::var("XHS_STORAGE").is_ok_and(|v| v == "memory")
+}
+
+/// 录制 / 回放 (XHS_VCR_MODE) 使用的 cassette 文件，通过 XHS_VCR_CASSETTE 配置，默认 fixtures/vcr/cassette.json
+pub fn vcr_cassette_path() -> std::path::PathBuf {
+    std::env::var("XHS_VCR_CASSETTE")
+        .ok()
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| "fixtures/vcr/cassette.json".to_string())
+        .into()
+}
+
+/// 存储签名（HAR 导入）的有效期（小时），通过 XHS_STORED_SIGNATURE_TTL_HOURS 配置，默认 0 表示不过期
+pub fn stored_signature_ttl() -> Option<chrono::Duration> {
+    std::env::var("XHS_STORED_SIGNATURE_TTL_HOURS")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|hours| *hours > 0)
+        .map(chrono::Duration::hours)
+}
+
+/// MongoDB 连接池上限，通过 XHS_MONGO_MAX_POOL_SIZE 配置，未设置时使用连接串 / 驱动默认值（10）
+pub fn mongo_max_pool_size() -> Option<u32> {
+    std::env::var("XHS_MONGO_MAX_POOL_SIZE").ok().and_then(|v| v.parse().ok()).filter(|n| *n > 0)
+}
+
+/// MongoDB 连接池保持的最少连接数，通过 XHS_MONGO_MIN_POOL_SIZE 配置
+pub fn mongo_min_pool_size() -> Option<u32> {
+    std::env::var("XHS_MONGO_MIN_POOL_SIZE").ok().and_then(|v| v.parse().ok())
+}
+
+/// MongoDB 建立连接超时（毫秒），通过 XHS_MONGO_CONNECT_TIMEOUT_MS 配置，驱动默认 10000
+pub fn mongo_connect_timeout() -> Option<std::time::Duration> {
+    std::env::var("XHS_MONGO_CONNECT_TIMEOUT_MS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|ms| *ms > 0)
+        .map(std::time::Duration::from_millis)
+}
+
+/// MongoDB 选择可用节点的超时（毫秒），通过 XHS_MONGO_SERVER_SELECTION_TIMEOUT_MS 配置，驱动默认 30000
+pub fn mongo_server_selection_timeout() -> Option<std::time::Duration> {
+    std::env::var("XHS_MONGO_SERVER_SELECTION_TIMEOUT_MS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|ms| *ms > 0)
+        .map(std::time::Duration::from_millis)
+}
+
+/// 下载根目录，通过 XHS_DOWNLOAD_ROOT 配置，默认 ./downloads
+pub fn download_root() -> std::path::PathBuf {
+    std::env::var("XHS_DOWNLOAD_ROOT")
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|_| std::path::PathBuf::from("./downloads"))
+}
+
+/// 单个下载文件的大小上限（字节），通过 XHS_DOWNLOAD_MAX_FILE_MB 配置（单位 MB），默认不限制
+pub fn download_max_file_bytes() -> Option<u64> {
+    std::env::var("XHS_DOWNLOAD_MAX_FILE_MB")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|mb| *mb > 0)
+        .map(|mb| mb * 1024 * 1024)
+}
+
+/// 下载根目录的总容量配额（字节），通过 XHS_DOWNLOAD_QUOTA_MB 配置（单位 MB），默认不限制
+pub fn download_quota_bytes() -> Option<u64> {
+    std::env::var("XHS_DOWNLOAD_QUOTA_MB")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|mb| *mb > 0)
+        .map(|mb| mb * 1024 * 1024)
+}
+
+/// S3 兼容对象存储的服务地址，通过 XHS_S3_ENDPOINT 配置（如 https://s3.us-east-1.amazonaws.com、
+/// http://minio:9000），未设置时不启用对象存储下载目标
+pub fn s3_endpoint() -> Option<String> {
+    std::env::var("XHS_S3_ENDPOINT")
+        .ok()
+        .map(|v| v.trim().trim_end_matches('/').to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// 对象存储桶名，通过 XHS_S3_BUCKET 配置
+pub fn s3_bucket() -> Option<String> {
+    std::env::var("XHS_S3_BUCKET").ok().filter(|v| !v.trim().is_empty())
+}
+
+/// 对象存储区域，通过 XHS_S3_REGION 配置，默认 us-east-1
+pub fn s3_region() -> String {
+    std::env::var("XHS_S3_REGION")
+        .ok()
+        .filter(|v| !v.trim().is_empty())
+        .unwrap_or_else(|| "us-east-1".to_string())
+}
+
+/// 对象存储访问密钥 (access key, secret key)，通过 XHS_S3_ACCESS_KEY / XHS_S3_SECRET_KEY 配置
+pub fn s3_credentials() -> Option<(String, String)> {
+    let access_key = std::env::var("XHS_S3_ACCESS_KEY").ok().filter(|v| !v.is_empty())?;
+    let secret_key = std::env::var("XHS_S3_SECRET_KEY").ok().filter(|v| !v.is_empty())?;
+    Some((access_key, secret_key))
+}
+
+/// 对象键前缀，通过 XHS_S3_PREFIX 配置（如 "xhs/"），默认无前缀
+pub fn s3_prefix() -> String {
+    std::env::var("XHS_S3_PREFIX").unwrap_or_default()
+}
+
+/// 分片上传的分片大小（字节），通过 XHS_S3_PART_MB 配置（单位 MB），默认 8MB，最小 5MB
+pub fn s3_part_size() -> usize {
+    std::env::var("XHS_S3_PART_MB")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(8)
+        .max(5)
+        * 1024 * 1024
+}
+
+/// 签名文件链接默认有效期（秒），通过 XHS_FILE_URL_TTL_SECS 配置，默认 3600，最长 7 天
+pub fn file_url_ttl() -> u64 {
+    std::env::var("XHS_FILE_URL_TTL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(3600)
+}
+
+/// 相同 POST 请求的去重窗口（秒），通过 XHS_POST_DEDUP_SECS 配置，默认 5，0 表示禁用
+pub fn post_dedup_window() -> std::time::Duration {
+    let secs = std::env::var("XHS_POST_DEDUP_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(5);
+    std::time::Duration::from_secs(secs)
+}
+
+/// 通知长轮询的上游轮询间隔（秒），通过 XHS_NOTIFICATION_POLL_SECS 配置，默认 5，最小 2
+pub fn notification_poll_interval() -> std::time::Duration {
+    let secs = std::env::var("XHS_NOTIFICATION_POLL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(5);
+    std::time::Duration::from_secs(secs.max(2))
+}
+
+/// 二维码登录 SSE 的最长持续时间（秒），通过 XHS_QR_EVENTS_MAX_SECS 配置，默认 600；
+/// 0 表示不限（无人值守的登录终端一直展示自动刷新的二维码）
+pub fn qr_events_max_duration() -> Option<std::time::Duration> {
+    let secs = std::env::var("XHS_QR_EVENTS_MAX_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(600);
+    (secs > 0).then(|| std::time::Duration::from_secs(secs))
+}
+
+/// 上游维护期间的探针间隔（秒），通过 XHS_MAINTENANCE_PROBE_SECS 配置，默认 60，最小 10
+pub fn maintenance_probe_interval() -> std::time::Duration {
+    let secs = std::env::var("XHS_MAINTENANCE_PROBE_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(60);
+    std::time::Duration::from_secs(secs.max(10))
+}
+
+/// 维护结束后的放行爬坡时长（秒），通过 XHS_MAINTENANCE_RAMP_SECS 配置，默认 600，0 表示立即全量恢复
+pub fn maintenance_ramp() -> std::time::Duration {
+    let secs = std::env::var("XHS_MAINTENANCE_RAMP_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(600);
+    std::time::Duration::from_secs(secs)
+}
+
+/// 定时发布调度器的检查间隔（秒），通过 XHS_SCHEDULE_TICK_SECS 配置，默认 30，最小 5
+pub fn schedule_tick_interval() -> std::time::Duration {
+    let secs = std::env::var("XHS_SCHEDULE_TICK_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(30);
+    std::time::Duration::from_secs(secs.max(5))
+}
+
+/// 定时抓取任务调度器的检查间隔（秒），通过 XHS_JOBS_TICK_SECS 配置，默认 30，最小 5
+pub fn crawl_jobs_tick_interval() -> std::time::Duration {
+    let secs = std::env::var("XHS_JOBS_TICK_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(30);
+    std::time::Duration::from_secs(secs.max(5))
+}
+
+/// 关键词监控调度器的检查间隔（秒），通过 XHS_MONITORS_TICK_SECS 配置，默认 30，最小 5
+pub fn monitors_tick_interval() -> std::time::Duration {
+    let secs = std::env::var("XHS_MONITORS_TICK_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(30);
+    std::time::Duration::from_secs(secs.max(5))
+}
+
+/// 用户关注列表调度器的检查间隔（秒），通过 XHS_WATCH_TICK_SECS 配置，默认 30，最小 5
+pub fn watch_tick_interval() -> std::time::Duration {
+    let secs = std::env::var("XHS_WATCH_TICK_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(30);
+    std::time::Duration::from_secs(secs.max(5))
+}
+
+/// XhsApiClient 请求最大尝试次数（含首次），通过 XHS_RETRY_MAX_ATTEMPTS 配置，默认 3，1 表示不重试
+pub fn retry_max_attempts() -> u32 {
+    std::env::var("XHS_RETRY_MAX_ATTEMPTS")
+        .ok()
+        .and_then(|v| v.parse::<u32>().ok())
+        .unwrap_or(3)
+        .max(1)
+}
+
+/// 首次重试的基础退避（毫秒），通过 XHS_RETRY_BASE_MS 配置，默认 300
+pub fn retry_base_delay() -> std::time::Duration {
+    let millis = std::env::var("XHS_RETRY_BASE_MS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(300);
+    std::time::Duration::from_millis(millis)
+}
+
+/// 单次重试退避上限（毫秒），通过 XHS_RETRY_MAX_MS 配置，默认 5000
+pub fn retry_max_delay() -> std::time::Duration {
+    let millis = std::env::var("XHS_RETRY_MAX_MS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(5000);
+    std::time::Duration::from_millis(millis)
+}
+
+/// 全局出站限流：每分钟请求数与突发容量，通过 XHS_RATE_LIMIT_RPM（默认 120，0 表示禁用）
+/// 与 XHS_RATE_LIMIT_BURST（默认 10）配置
+pub fn rate_limit_global() -> (u32, u32) {
+    let rpm = reloadable_var("XHS_RATE_LIMIT_RPM")
+        .and_then(|v| v.parse::<u32>().ok())
+        .unwrap_or(120);
+    let burst = reloadable_var("XHS_RATE_LIMIT_BURST")
+        .and_then(|v| v.parse::<u32>().ok())
+        .unwrap_or(10);
+    (rpm, burst.max(1))
+}
+
+/// 按接口限流，通过 XHS_RATE_LIMIT_ENDPOINTS 配置
+///
+/// 格式: `接口=每分钟请求数[:突发容量]`，逗号分隔，接口为 endpoint key 或 URI 前缀，
+/// 例如 `/api/sns/web/v1/search=20:3,/api/sns/web/v1/feed=30`；未指定突发容量时为 1
+pub fn rate_limit_endpoints() -> Vec<(String, u32, u32)> {
+    let Some(spec) = reloadable_var("XHS_RATE_LIMIT_ENDPOINTS") else {
+        return Vec::new();
+    };
+    spec.split(',')
+        .filter_map(|entry| {
+            let (endpoint, rate) = entry.trim().split_once('=')?;
+            let (rpm, burst) = match rate.split_once(':') {
+                Some((rpm, burst)) => (rpm.trim().parse().ok()?, burst.trim().parse().ok()?),
+                None => (rate.trim().parse().ok()?, 1),
+            };
+            Some((endpoint.trim().to_string(), rpm, u32::max(burst, 1)))
+        })
+        .filter(|(endpoint, rpm, _)| !endpoint.is_empty() && *rpm > 0)
+        .collect()
+}
+
+/// 461 熔断的初始冷却期（秒），通过 XHS_RISK_COOLDOWN_SECS 配置，默认 60，连续触发时翻倍
+pub fn risk_cooldown() -> std::time::Duration {
+    let secs = std::env::var("XHS_RISK_COOLDOWN_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(60);
+    std::time::Duration::from_secs(secs.max(1))
+}
+
+/// 461 熔断的冷却期上限（秒），通过 XHS_RISK_COOLDOWN_MAX_SECS 配置，默认 1800
+pub fn risk_max_cooldown() -> std::time::Duration {
+    let secs = std::env::var("XHS_RISK_COOLDOWN_MAX_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(1800);
+    std::time::Duration::from_secs(secs)
+}
+
+/// 熔断期间请求的最长排队时间（秒），通过 XHS_RISK_MAX_WAIT_SECS 配置，默认 0（直接拒绝）
+pub fn risk_max_wait() -> std::time::Duration {
+    let secs = std::env::var("XHS_RISK_MAX_WAIT_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(0);
+    std::time::Duration::from_secs(secs)
+}
+
+/// 同时熔断的接口数达到该值时熔断整个账号，通过 XHS_RISK_ACCOUNT_THRESHOLD 配置，默认 3，0 表示不启用
+pub fn risk_account_threshold() -> usize {
+    std::env::var("XHS_RISK_ACCOUNT_THRESHOLD")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(3)
+}
+
+/// 访问 XHS 的出站代理，通过 XHS_PROXY 配置（http / https / socks5 / socks5h URL），
+/// 未设置时沿用 reqwest 默认行为（读取系统 HTTP(S)_PROXY）
+pub fn proxy_url() -> Option<String> {
+    reloadable_var("XHS_PROXY").filter(|v| !v.trim().is_empty())
+}
+
+/// 不走代理的主机列表（逗号分隔），通过 XHS_NO_PROXY 配置
+pub fn no_proxy() -> Option<String> {
+    reloadable_var("XHS_NO_PROXY").filter(|v| !v.trim().is_empty())
+}
+
+/// 是否启用拟人节奏模式，通过 XHS_STEALTH 配置（1 / true / on），默认关闭
+pub fn stealth_enabled() -> bool {
+    std::env::var("XHS_STEALTH")
+        .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "on" | "yes"))
+        .unwrap_or(false)
+}
+
+/// 是否启用访客浏览模式，通过 XHS_GUEST_BROWSING 配置（1 / true / on），默认关闭
+pub fn guest_browsing_enabled() -> bool {
+    std::env::var("XHS_GUEST_BROWSING")
+        .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "on" | "yes"))
+        .unwrap_or(false)
+}
+
+/// 拟人模式下每次请求前的随机停顿区间（毫秒），通过 XHS_STEALTH_DELAY_MS 配置（如 "800-3500"），默认 800-3500
+pub fn stealth_delay_range() -> (u64, u64) {
+    std::env::var("XHS_STEALTH_DELAY_MS")
+        .ok()
+        .and_then(|v| {
+            let (min, max) = v.split_once('-')?;
+            let (min, max) = (min.trim().parse::<u64>().ok()?, max.trim().parse::<u64>().ok()?);
+            Some((min.min(max), min.max(max)))
+        })
+        .unwrap_or((800, 3500))
+}
+
+/// 拟人模式下的持续请求速率上限（每分钟），通过 XHS_STEALTH_MAX_RPM 配置，默认 20
+pub fn stealth_max_rpm() -> u32 {
+    std::env::var("XHS_STEALTH_MAX_RPM")
+        .ok()
+        .and_then(|v| v.parse::<u32>().ok())
+        .unwrap_or(20)
+        .max(1)
+}
+
+/// 拟人模式下插入"刷新热搜 / 首页"浏览动作的概率，通过 XHS_STEALTH_DECOY_RATE 配置，默认 0.08
+pub fn stealth_decoy_rate() -> f64 {
+    std::env::var("XHS_STEALTH_DECOY_RATE")
+        .ok()
+        .and_then(|v| v.parse::<f64>().ok())
+        .unwrap_or(0.08)
+        .clamp(0.0, 1.0)
+}
+
+/// 通知 webhook 地址，通过 XHS_WEBHOOK_URLS 配置（逗号分隔），为空时不启动通知监听
+pub fn webhook_urls() -> Vec<String> {
+    reloadable_var("XHS_WEBHOOK_URLS")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// webhook HMAC-SHA256 签名密钥，通过 XHS_WEBHOOK_SECRET 配置，未设置时不签名
+pub fn webhook_secret() -> Option<String> {
+    reloadable_var("XHS_WEBHOOK_SECRET").filter(|s| !s.is_empty())
+}
+
+/// 通知监听的检查间隔（秒），通过 XHS_WEBHOOK_POLL_SECS 配置，默认 60，最小 15
+pub fn webhook_poll_interval() -> std::time::Duration {
+    let secs = std::env::var("XHS_WEBHOOK_POLL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(60);
+    std::time::Duration::from_secs(secs.max(15))
+}
+
+/// 启动时与定期预生成签名的接口（endpoint key，逗号分隔），通过 XHS_SIGNATURE_PREWARM 配置，
+/// 默认 `user_me,search_trending`，设为 `off` 关闭
+pub fn signature_prewarm_endpoints() -> Vec<String> {
+    let value = std::env::var("XHS_SIGNATURE_PREWARM").unwrap_or_else(|_| "user_me,search_trending".to_string());
+    if value.trim().eq_ignore_ascii_case("off") {
+        return Vec::new();
+    }
+    value.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect()
+}
+
+/// 签名预热间隔（秒），预生成的签名在一个间隔后过期，通过 XHS_SIGNATURE_PREWARM_SECS 配置，默认 120，最小 10
+pub fn signature_prewarm_interval() -> std::time::Duration {
+    let secs = std::env::var("XHS_SIGNATURE_PREWARM_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(120);
+    std::time::Duration::from_secs(secs.max(10))
+}
+
+/// 合并签名请求的等待窗口（毫秒），通过 XHS_SIGNATURE_BATCH_WINDOW_MS 配置，默认 5，0 表示关闭
+pub fn signature_batch_window() -> std::time::Duration {
+    let ms = std::env::var("XHS_SIGNATURE_BATCH_WINDOW_MS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(5);
+    std::time::Duration::from_millis(ms)
+}
+
+/// 一次合并签名的最大请求数，通过 XHS_SIGNATURE_BATCH_MAX 配置，默认 20，最小 2
+pub fn signature_batch_max() -> usize {
+    std::env::var("XHS_SIGNATURE_BATCH_MAX")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(20)
+        .max(2)
+}
+
+/// 异步下载任务的并发数，通过 XHS_DOWNLOAD_WORKERS 配置，默认 4，最小 1
+pub fn download_workers() -> usize {
+    std::env::var("XHS_DOWNLOAD_WORKERS")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(4)
+        .max(1)
+}
+
+/// 大文件分段并发下载的连接数，通过 XHS_DOWNLOAD_SEGMENTS 配置，默认 4；设为 1 关闭分段下载
+pub fn download_segments() -> usize {
+    std::env::var("XHS_DOWNLOAD_SEGMENTS")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(4)
+        .clamp(1, 16)
+}
+
+/// 分段下载的分段大小（字节），通过 XHS_DOWNLOAD_SEGMENT_MB 配置（单位 MB），默认 8MB
+///
+/// 不超过一个分段的文件仍是单连接下载
+pub fn download_segment_size() -> u64 {
+    std::env::var("XHS_DOWNLOAD_SEGMENT_MB")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(8)
+        .max(1)
+        * 1024 * 1024
+}
+
+/// 内置 HTTPS 配置
+#[derive(Debug, Clone)]
+pub enum TlsConfig {
+    /// PEM 格式的证书链与私钥文件
+    Files { cert: std::path::PathBuf, key: std::path::PathBuf },
+    /// 通过 ACME (TLS-ALPN-01) 自动申请与续期 Let's Encrypt 证书
+    Acme {
+        domains: Vec<String>,
+        /// 联系邮箱
+        contact: Vec<String>,
+        /// 账号与证书缓存目录
+        cache_dir: std::path::PathBuf,
+        /// 使用 Let's Encrypt 测试环境
+        staging: bool,
+    },
+}
+
+/// 内置 HTTPS，未配置时返回 None（纯 HTTP，适合部署在反向代理之后）
+///
+/// - XHS_TLS_CERT / XHS_TLS_KEY: PEM 证书链与私钥路径，需同时设置
+/// - XHS_TLS_ACME_DOMAINS: 逗号分隔的域名，改为通过 ACME 自动申请证书（与证书文件二选一）
+/// - XHS_TLS_ACME_EMAIL: 联系邮箱（逗号分隔，可选）
+/// - XHS_TLS_ACME_CACHE: 账号与证书缓存目录，默认 ./acme
+/// - XHS_TLS_ACME_STAGING: 使用 Let's Encrypt 测试环境（1 / true / on），默认正式环境
+pub fn tls_config() -> anyhow::Result<Option<TlsConfig>> {
+    let var = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+    let list = |name: &str| -> Vec<String> {
+        var(name)
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+            .collect()
+    };
+    let domains = list("XHS_TLS_ACME_DOMAINS");
+    match (var("XHS_TLS_CERT"), var("XHS_TLS_KEY")) {
+        (Some(_), Some(_)) if !domains.is_empty() => {
+            Err(anyhow::anyhow!("XHS_TLS_CERT/XHS_TLS_KEY and XHS_TLS_ACME_DOMAINS are mutually exclusive"))
+        }
+        (Some(cert), Some(key)) => Ok(Some(TlsConfig::Files { cert: cert.into(), key: key.into() })),
+        (Some(_), None) | (None, Some(_)) => Err(anyhow::anyhow!("XHS_TLS_CERT and XHS_TLS_KEY must be set together")),
+        (None, None) if domains.is_empty() => Ok(None),
+        (None, None) => Ok(Some(TlsConfig::Acme {
+            domains,
+            contact: list("XHS_TLS_ACME_EMAIL"),
+            cache_dir: var("XHS_TLS_ACME_CACHE").unwrap_or_else(|| "./acme".to_string()).into(),
+            staging: var("XHS_TLS_ACME_STAGING")
+                .is_some_and(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "on" | "yes")),
+        })),
+    }
+}
@@ -0,0 +1,405 @@
+//! 多实例协调模块 (Crawl Coordination)
+//!
+//! 多个服务实例（不同账号 / 不同出口 IP）共享一个 MongoDB 工作队列，
+//! 通过租约 (lease) 把关键词监控、笔记归档等周期性抓取任务分片到各实例执行，
+//! 使独立部署的服务组成一个小型抓取集群，且同一任务不会被重复抓取：
+//!
+//! - 任务以 `task_key` 去重（如 `keyword:露营`），重复入队只更新参数
+//! - 实例通过 `findOneAndUpdate` 原子地领取到期任务，并在租约期内独占
+//! - 任务完成后按 `interval_secs` 重新排期，租约释放
+//! - 实例崩溃时租约自然过期，任务由其他实例接管
+//!
+//! 未配置 `XHS_COORD_MONGO_URI` 时为单实例模式，调用方直接在本地执行任务。
+
+use anyhow::{anyhow, Result};
+use mongodb::bson::{doc, Document};
+use mongodb::options::ReturnDocument;
+use mongodb::Collection;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::api::maintenance::MaintenanceGate;
+
+/// 默认租约时长（秒）
+const DEFAULT_LEASE_SECS: u64 = 300;
+
+/// 队列为空时的轮询间隔
+const IDLE_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// 协调配置（环境变量）
+#[derive(Debug, Clone)]
+pub struct CoordinationConfig {
+    /// MongoDB 连接串 (XHS_COORD_MONGO_URI)
+    pub mongo_uri: String,
+    /// 数据库名 (XHS_COORD_DB，默认 xhs_coord)
+    pub database: String,
+    /// 实例 ID (XHS_INSTANCE_ID，默认随机生成)
+    pub instance_id: String,
+    /// 租约时长 (XHS_COORD_LEASE_SECS，默认 300)
+    pub lease: Duration,
+}
+
+impl CoordinationConfig {
+    /// 从环境变量读取，未配置 XHS_COORD_MONGO_URI 时返回 None（单实例模式）
+    pub fn from_env() -> Option<Self> {
+        let mongo_uri = std::env::var("XHS_COORD_MONGO_URI").ok()?;
+        let database = std::env::var("XHS_COORD_DB").unwrap_or_else(|_| "xhs_coord".to_string());
+        let instance_id = std::env::var("XHS_INSTANCE_ID")
+            .unwrap_or_else(|_| format!("xhs-{}", &uuid::Uuid::new_v4().to_string()[..8]));
+        let lease_secs = std::env::var("XHS_COORD_LEASE_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_LEASE_SECS);
+
+        Some(Self {
+            mongo_uri,
+            database,
+            instance_id,
+            lease: Duration::from_secs(lease_secs),
+        })
+    }
+}
+
+/// 抓取任务
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct CrawlTask {
+    /// 任务唯一键，如 "keyword:露营"
+    #[serde(rename = "_id")]
+    pub key: String,
+    /// 任务类型，如 "keyword_monitor"、"archive"
+    pub kind: String,
+    /// 任务参数
+    #[serde(default)]
+    pub payload: serde_json::Value,
+    /// 执行间隔（秒），0 表示一次性任务
+    #[serde(default)]
+    pub interval_secs: u64,
+    /// 下次可执行时间 (ms)
+    pub next_run_at: i64,
+    /// 当前持有租约的实例
+    #[serde(default)]
+    pub lease_owner: Option<String>,
+    /// 租约到期时间 (ms)
+    #[serde(default)]
+    pub lease_until: i64,
+    /// 连续失败次数
+    #[serde(default)]
+    pub attempts: u32,
+    /// 最近一次错误
+    #[serde(default)]
+    pub last_error: Option<String>,
+    /// 最近更新时间 (ms)
+    #[serde(default)]
+    pub updated_at: i64,
+}
+
+/// 集群实例心跳
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct InstanceInfo {
+    /// 实例 ID
+    #[serde(rename = "_id")]
+    pub instance_id: String,
+    /// 启动时间 (ms)
+    pub started_at: i64,
+    /// 最近心跳时间 (ms)
+    pub heartbeat_at: i64,
+}
+
+/// 基于 MongoDB 的租约工作队列
+pub struct WorkQueue {
+    tasks: Collection<CrawlTask>,
+    instances: Collection<InstanceInfo>,
+    instance_id: String,
+    lease: Duration,
+    started_at: i64,
+    maintenance: Option<Arc<MaintenanceGate>>,
+}
+
+impl WorkQueue {
+    /// 连接 MongoDB 并初始化队列
+    pub async fn connect(config: &CoordinationConfig) -> Result<Self> {
+        let client = crate::utils::storage::mongo_client(&config.mongo_uri)
+            .await
+            .map_err(|e| anyhow!("Failed to connect to coordination MongoDB: {}", e))?;
+        let db = client.database(&config.database);
+
+        let queue = Self {
+            tasks: db.collection("crawl_tasks"),
+            instances: db.collection("instances"),
+            instance_id: config.instance_id.clone(),
+            lease: config.lease,
+            started_at: now_millis(),
+            maintenance: None,
+        };
+        queue.heartbeat().await?;
+
+        tracing::info!(
+            "[Coordination] Joined crawl cluster as {} (db: {}, lease: {}s)",
+            queue.instance_id, config.database, queue.lease.as_secs()
+        );
+        Ok(queue)
+    }
+
+    /// 绑定上游维护闸门：维护期间暂停领取任务，恢复期按放行比例领取
+    pub fn with_maintenance(mut self, gate: Arc<MaintenanceGate>) -> Self {
+        self.maintenance = Some(gate);
+        self
+    }
+
+    /// 当前实例 ID
+    pub fn instance_id(&self) -> &str {
+        &self.instance_id
+    }
+
+    /// 检查数据库可连接（就绪探针）
+    pub async fn ping(&self) -> Result<()> {
+        crate::utils::storage::ping_mongo(&self.tasks).await
+    }
+
+    /// 入队（按 key 去重）
+    ///
+    /// 已存在的任务只更新 kind / payload / interval，不影响其排期与租约
+    pub async fn enqueue(
+        &self,
+        key: &str,
+        kind: &str,
+        payload: serde_json::Value,
+        interval_secs: u64,
+    ) -> Result<()> {
+        let now = now_millis();
+        let payload = mongodb::bson::to_bson(&payload)?;
+        self.tasks
+            .update_one(
+                doc! { "_id": key },
+                doc! {
+                    "$set": {
+                        "kind": kind,
+                        "payload": payload,
+                        "interval_secs": interval_secs as i64,
+                        "updated_at": now,
+                    },
+                    "$setOnInsert": {
+                        "next_run_at": now,
+                        "lease_owner": null,
+                        "lease_until": 0_i64,
+                        "attempts": 0,
+                        "last_error": null,
+                    },
+                },
+            )
+            .upsert(true)
+            .await?;
+        Ok(())
+    }
+
+    /// 删除任务
+    pub async fn remove(&self, key: &str) -> Result<bool> {
+        let result = self.tasks.delete_one(doc! { "_id": key }).await?;
+        Ok(result.deleted_count > 0)
+    }
+
+    /// 原子领取一个到期且未被租用的任务
+    pub async fn claim(&self, kinds: &[&str]) -> Result<Option<CrawlTask>> {
+        let now = now_millis();
+        let task = self
+            .tasks
+            .find_one_and_update(
+                doc! {
+                    "kind": { "$in": kinds },
+                    "next_run_at": { "$lte": now },
+                    "lease_until": { "$lt": now },
+                },
+                doc! {
+                    "$set": {
+                        "lease_owner": &self.instance_id,
+                        "lease_until": now + self.lease.as_millis() as i64,
+                        "updated_at": now,
+                    },
+                    "$inc": { "attempts": 1 },
+                },
+            )
+            .sort(doc! { "next_run_at": 1 })
+            .return_document(ReturnDocument::After)
+            .await?;
+        Ok(task)
+    }
+
+    /// 续租（长任务执行期间调用），租约已被他人接管时返回 false
+    pub async fn renew(&self, key: &str) -> Result<bool> {
+        let now = now_millis();
+        let result = self
+            .tasks
+            .update_one(
+                self.owned_filter(key),
+                doc! { "$set": {
+                    "lease_until": now + self.lease.as_millis() as i64,
+                    "updated_at": now,
+                } },
+            )
+            .await?;
+        Ok(result.matched_count > 0)
+    }
+
+    /// 完成任务并释放租约
+    ///
+    /// 周期任务按 interval 重新排期；一次性任务成功后删除，失败则稍后重试
+    pub async fn complete(&self, task: &CrawlTask, outcome: std::result::Result<(), String>) -> Result<()> {
+        let now = now_millis();
+        if task.interval_secs == 0 && outcome.is_ok() {
+            self.tasks.delete_one(self.owned_filter(&task.key)).await?;
+            return Ok(());
+        }
+
+        // 失败重试间隔：不超过一个租约周期，也不晚于正常排期
+        let interval_ms = task.interval_secs as i64 * 1000;
+        let lease_ms = self.lease.as_millis() as i64;
+        let retry_delay = if interval_ms == 0 { lease_ms } else { lease_ms.min(interval_ms) };
+        let (next_run_at, last_error, attempts): (i64, Option<String>, Option<u32>) = match outcome {
+            Ok(()) => (now + interval_ms, None, Some(0)),
+            Err(e) => (now + retry_delay, Some(e), None),
+        };
+
+        let mut set: Document = doc! {
+            "next_run_at": next_run_at,
+            "lease_owner": null,
+            "lease_until": 0_i64,
+            "last_error": last_error,
+            "updated_at": now,
+        };
+        if let Some(attempts) = attempts {
+            set.insert("attempts", attempts as i64);
+        }
+        self.tasks
+            .update_one(self.owned_filter(&task.key), doc! { "$set": set })
+            .await?;
+        Ok(())
+    }
+
+    /// 放弃租约（如实例准备退出），任务立即可被其他实例领取
+    pub async fn release(&self, key: &str) -> Result<()> {
+        self.tasks
+            .update_one(
+                self.owned_filter(key),
+                doc! { "$set": { "lease_owner": null, "lease_until": 0_i64, "updated_at": now_millis() } },
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// 上报心跳
+    pub async fn heartbeat(&self) -> Result<()> {
+        self.instances
+            .update_one(
+                doc! { "_id": &self.instance_id },
+                doc! {
+                    "$set": { "heartbeat_at": now_millis() },
+                    "$setOnInsert": { "started_at": self.started_at },
+                },
+            )
+            .upsert(true)
+            .await?;
+        Ok(())
+    }
+
+    /// 列出所有任务
+    pub async fn list_tasks(&self) -> Result<Vec<CrawlTask>> {
+        let mut cursor = self.tasks.find(doc! {}).sort(doc! { "next_run_at": 1 }).await?;
+        let mut tasks = Vec::new();
+        while cursor.advance().await? {
+            tasks.push(cursor.deserialize_current()?);
+        }
+        Ok(tasks)
+    }
+
+    /// 列出存活实例（最近两个租约周期内有心跳）
+    pub async fn list_instances(&self) -> Result<Vec<InstanceInfo>> {
+        let since = now_millis() - 2 * self.lease.as_millis() as i64;
+        let mut cursor = self
+            .instances
+            .find(doc! { "heartbeat_at": { "$gte": since } })
+            .await?;
+        let mut instances = Vec::new();
+        while cursor.advance().await? {
+            instances.push(cursor.deserialize_current()?);
+        }
+        Ok(instances)
+    }
+
+    /// 持续领取并执行指定类型的任务
+    ///
+    /// 执行期间每 1/3 个租约周期续租一次；`handler` 返回 Err 时记录错误并稍后重试；
+    /// 队列为空时休眠后继续轮询
+    pub async fn run_worker<F, Fut>(&self, kinds: &[&str], handler: F)
+    where
+        F: Fn(CrawlTask) -> Fut,
+        Fut: Future<Output = std::result::Result<(), String>>,
+    {
+        loop {
+            if self.maintenance.as_ref().is_some_and(|gate| !gate.accepts_jobs()) {
+                tokio::time::sleep(IDLE_POLL_INTERVAL).await;
+                continue;
+            }
+            match self.claim(kinds).await {
+                Ok(Some(task)) => {
+                    tracing::info!("[Coordination] {} claimed task {}", self.instance_id, task.key);
+                    let outcome = self.run_leased(&task.key, handler(task.clone())).await;
+                    if let Err(ref e) = outcome {
+                        tracing::warn!("[Coordination] Task {} failed: {}", task.key, e);
+                    }
+                    if let Err(e) = self.complete(&task, outcome).await {
+                        tracing::error!("[Coordination] Failed to complete task {}: {}", task.key, e);
+                    }
+                }
+                Ok(None) => tokio::time::sleep(IDLE_POLL_INTERVAL).await,
+                Err(e) => {
+                    tracing::error!("[Coordination] Claim failed: {}", e);
+                    tokio::time::sleep(IDLE_POLL_INTERVAL).await;
+                }
+            }
+        }
+    }
+
+    /// 执行任务，期间定期续租，避免长任务的租约过期后被其他实例重复领取
+    async fn run_leased<Fut>(&self, key: &str, task: Fut) -> std::result::Result<(), String>
+    where
+        Fut: Future<Output = std::result::Result<(), String>>,
+    {
+        tokio::pin!(task);
+        let period = (self.lease / 3).max(Duration::from_secs(1));
+        let mut renewal = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+        loop {
+            tokio::select! {
+                outcome = &mut task => return outcome,
+                _ = renewal.tick() => match self.renew(key).await {
+                    Ok(true) => {}
+                    Ok(false) => tracing::warn!("[Coordination] Lease on task {} was taken over", key),
+                    Err(e) => tracing::warn!("[Coordination] Failed to renew lease on task {}: {}", key, e),
+                },
+            }
+        }
+    }
+
+    /// 仅匹配本实例持有的任务
+    fn owned_filter(&self, key: &str) -> Document {
+        doc! { "_id": key, "lease_owner": &self.instance_id }
+    }
+}
+
+/// 启动心跳后台任务
+pub fn spawn_heartbeat(queue: Arc<WorkQueue>) {
+    let interval = (queue.lease / 3).max(Duration::from_secs(5));
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(interval).await;
+            if let Err(e) = queue.heartbeat().await {
+                tracing::warn!("[Coordination] Heartbeat failed: {}", e);
+            }
+        }
+    });
+}
+
+fn now_millis() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
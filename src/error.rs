@@ -0,0 +1,194 @@
+//! 统一错误类型 (XhsError)
+//!
+//! `XhsApiClient`、Web 端接口（搜索 / 笔记 / 信息流 / 用户 / 通知 / 扫码登录）、媒体下载 / 打包 / 对象存储上传，
+//! 以及创作者中心的粉丝与笔记数据接口返回 `XhsError`，调用方可按错误类型分别处理
+//! （重新登录 / 退避重试 / 更换签名），不必匹配错误消息字符串。
+//!
+//! 仍返回 `anyhow::Result` 的公共接口：创作者中心其余接口（账号信息 / 草稿 / 评论管理 / 发布）、
+//! 本地任务存储（定时发布 / 关键词监控 / 采集任务 / 用户关注）、本地文件工具
+//! （`media::sandbox` / `signed_url` / `sidecar`）与 `from_env` 配置加载。
+//!
+//! 内部模块仍使用 anyhow；`From<anyhow::Error>` 会先尝试还原被包装的 `XhsError`，
+//! 因此经过 anyhow 传递的维护 / 限流等错误不会丢失类型。
+//!
+//! 启用 `server` feature 时实现 `IntoResponse`，按错误类型映射 HTTP 状态码，
+//! 错误体保持原有的 `{code, success, msg, data}` 结构并附加 `error_kind` 与 `request_id`（见 `response`）。
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// 统一结果类型
+pub type Result<T, E = XhsError> = std::result::Result<T, E>;
+
+/// XHS 接口错误
+#[derive(Debug, thiserror::Error)]
+pub enum XhsError {
+    /// 未登录（没有可用凭据）
+    #[error("Not logged in: {0}")]
+    NotLoggedIn(String),
+    /// 登录已失效 (401)
+    #[error("Login expired: {0}")]
+    LoginExpired(String),
+    /// 无法生成签名（Agent 不可用且没有可回退的存储签名）
+    #[error("Signature failed: {0}")]
+    SignatureFailed(String),
+    /// 上游拒绝签名 (406)
+    #[error("XHS 签名无效 (406): {body}")]
+    InvalidSignature406 { body: String },
+    /// 上游风控 (461)
+    #[error("XHS 风控触发 (461): 请稍后重试或更换关键词。Response: {body}")]
+    RiskControl461 { body: String },
+    /// 上游维护中（维护闸门暂停 / 恢复爬坡中）
+    #[error("{0}")]
+    Maintenance(String),
+    /// 风控熔断中（近期收到 461，冷却期内拒绝请求）
+    #[error("XHS 风控熔断中 ({scope})，请在 {retry_after} 秒后重试")]
+    RiskCircuitOpen { scope: String, retry_after: u64 },
+    /// 账号被限制（业务码 300011）
+    #[error("XHS 账号异常: {0}")]
+    AccountRestricted(String),
+    /// 内容不存在或不可见（业务码 -510000 / -510001）
+    #[error("内容不可用 ({code}): {msg}")]
+    ContentUnavailable { code: i64, msg: String },
+    /// 其他业务失败（HTTP 200 但 success=false）
+    #[error("XHS 业务错误 ({code}): {msg}")]
+    Business { code: i64, msg: String },
+    /// 账号需要完成验证码（滑块 / 图形验证），完成并更新 Cookie 前暂停请求
+    #[error("XHS 要求完成验证码，请在浏览器中打开 {url} 完成验证后更新 Cookie")]
+    VerificationRequired { url: String },
+    /// 行为画像拒绝（活跃时段外 / 每日上限 / 请求配比）
+    #[error("{0}")]
+    Throttled(String),
+    /// 其他上游 HTTP 错误
+    #[error("XHS API 错误 ({status}): {body}")]
+    Upstream { status: u16, body: String },
+    /// 响应解析失败
+    #[error("Parse error: {0}")]
+    Parse(String),
+    /// 网络错误（连接失败 / 超时）
+    #[error("Network error: {0}")]
+    Network(#[from] reqwest::Error),
+    /// 试运行：请求已构建但未发送（见 `api::dry_run`）
+    #[error("Dry run: request was built but not sent")]
+    DryRun,
+    /// 其他错误
+    #[error(transparent)]
+    Other(anyhow::Error),
+}
+
+impl From<anyhow::Error> for XhsError {
+    fn from(error: anyhow::Error) -> Self {
+        let error = match error.downcast::<XhsError>() {
+            Ok(xhs) => return xhs,
+            Err(error) => error,
+        };
+        let error = match error.downcast::<reqwest::Error>() {
+            Ok(e) => return XhsError::Network(e),
+            Err(error) => error,
+        };
+        match error.downcast::<serde_json::Error>() {
+            Ok(e) => XhsError::Parse(e.to_string()),
+            Err(error) => XhsError::Other(error),
+        }
+    }
+}
+
+impl From<serde_json::Error> for XhsError {
+    fn from(error: serde_json::Error) -> Self {
+        XhsError::Parse(error.to_string())
+    }
+}
+
+impl XhsError {
+    /// 错误类型标识（响应体 `error_kind`）
+    pub fn kind(&self) -> &'static str {
+        match self {
+            XhsError::NotLoggedIn(_) => "not_logged_in",
+            XhsError::LoginExpired(_) => "login_expired",
+            XhsError::SignatureFailed(_) => "signature_failed",
+            XhsError::InvalidSignature406 { .. } => "invalid_signature",
+            XhsError::RiskControl461 { .. } => "risk_control",
+            XhsError::RiskCircuitOpen { .. } => "risk_circuit_open",
+            XhsError::Maintenance(_) => "maintenance",
+            XhsError::AccountRestricted(_) => "account_restricted",
+            XhsError::VerificationRequired { .. } => "verification_required",
+            XhsError::ContentUnavailable { .. } => "content_unavailable",
+            XhsError::Business { .. } => "business",
+            XhsError::Throttled(_) => "throttled",
+            XhsError::Upstream { .. } => "upstream",
+            XhsError::Parse(_) => "parse",
+            XhsError::Network(_) => "network",
+            XhsError::DryRun => "dry_run",
+            XhsError::Other(_) => "internal",
+        }
+    }
+
+    /// 对应的 HTTP 状态码
+    pub fn status_code(&self) -> u16 {
+        match self {
+            XhsError::NotLoggedIn(_) | XhsError::LoginExpired(_) => 401,
+            XhsError::RiskControl461 { .. } | XhsError::RiskCircuitOpen { .. } | XhsError::Throttled(_) => 429,
+            XhsError::AccountRestricted(_) | XhsError::VerificationRequired { .. } => 403,
+            XhsError::ContentUnavailable { .. } => 404,
+            XhsError::SignatureFailed(_) | XhsError::Maintenance(_) => 503,
+            XhsError::Network(e) if e.is_timeout() => 504,
+            XhsError::InvalidSignature406 { .. }
+            | XhsError::Upstream { .. }
+            | XhsError::Business { .. }
+            | XhsError::Parse(_)
+            | XhsError::Network(_) => 502,
+            XhsError::DryRun => 200,
+            XhsError::Other(_) => 500,
+        }
+    }
+
+    /// 是否值得稍后重试（风控 / 维护 / 网络）
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            XhsError::RiskControl461 { .. } | XhsError::Maintenance(_) | XhsError::Network(_)
+        )
+    }
+}
+
+/// 错误响应体
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ApiErrorBody {
+    /// 固定为 -1
+    pub code: i32,
+    /// 固定为 false
+    pub success: bool,
+    pub msg: String,
+    /// 固定为 null
+    #[schema(value_type = Option<Object>)]
+    pub data: Option<serde_json::Value>,
+    /// 错误类型: not_logged_in / login_expired / signature_failed / invalid_signature /
+    /// risk_control / risk_circuit_open / maintenance / account_restricted / verification_required / content_unavailable / business /
+    /// throttled / upstream / parse / network / dry_run / internal
+    /// （启用多租户时网关另有 unauthorized / forbidden）
+    pub error_kind: String,
+    /// 请求 ID（同响应头 x-request-id）
+    pub request_id: Option<String>,
+}
+
+impl From<&XhsError> for ApiErrorBody {
+    fn from(error: &XhsError) -> Self {
+        Self {
+            code: -1,
+            success: false,
+            msg: error.to_string(),
+            data: None,
+            error_kind: error.kind().to_string(),
+            request_id: crate::response::current_request_id(),
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+impl axum::response::IntoResponse for XhsError {
+    fn into_response(self) -> axum::response::Response {
+        let status = axum::http::StatusCode::from_u16(self.status_code())
+            .unwrap_or(axum::http::StatusCode::INTERNAL_SERVER_ERROR);
+        (status, axum::Json(ApiErrorBody::from(&self))).into_response()
+    }
+}
@@ -0,0 +1,58 @@
+//! Activity Profile HTTP Handlers
+//!
+//! Handles: activity/status
+
+use axum::{
+    extract::State,
+    response::IntoResponse,
+};
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::activity::{ActivityUsage, RequestCategory, DEFAULT_ACCOUNT};
+use crate::api::pacing::StealthStatus;
+use crate::api::rate_limit::BucketStatus;
+use crate::response::ApiResponse;
+use crate::server::AppState;
+
+/// 行为画像状态
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct ActivityStatus {
+    /// 当日用量
+    pub usage: ActivityUsage,
+    /// 按配比建议的下一类请求（供调度/预热参考）
+    pub next_category: Option<RequestCategory>,
+    /// 出站限流令牌桶（全局桶在前，未配置时为空）
+    pub rate_limits: Vec<BucketStatus>,
+    /// 拟人节奏模式
+    pub stealth: StealthStatus,
+}
+
+/// 行为画像状态
+///
+/// 返回当前登录账号（未登录时为默认绑定）的画像、是否处于活跃时段、当日各类请求用量以及出站限流状态
+#[utoipa::path(
+    get,
+    path = "/api/activity/status",
+    tag = "Activity",
+    summary = "行为画像状态",
+    description = "画像配置见 XHS_ACTIVITY_PROFILES（默认 activity_profiles.json，不存在时不做限制）；限流配置见 XHS_RATE_LIMIT_RPM / XHS_RATE_LIMIT_BURST / XHS_RATE_LIMIT_ENDPOINTS；拟人模式见 XHS_STEALTH",
+    responses(
+        (status = 200, description = "当日用量", body = ApiResponse<ActivityStatus>)
+    )
+)]
+pub async fn activity_status_handler(
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let account = match state.auth.try_get_credentials().await {
+        Ok(Some(credentials)) => credentials.user_id,
+        _ => DEFAULT_ACCOUNT.to_string(),
+    };
+    let activity = state.api.activity();
+    ApiResponse::ok(ActivityStatus {
+        usage: activity.usage(&account),
+        next_category: activity.next_category(&account),
+        rate_limits: state.api.rate_limiter().status(),
+        stealth: state.api.pacer().status(),
+    })
+}
@@ -0,0 +1,500 @@
+//! Admin HTTP Handlers
+//!
+//! Handles: admin/risk, admin/audit, admin/config, admin/accounts, admin/import-har, admin/credentials
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::api;
+use crate::api::rate_limit::BucketStatus;
+use crate::api::risk::RiskStatus;
+use crate::api::rotation::AccountPoolStatus;
+use crate::auth::har::{self, Har};
+use crate::auth::export::{export_credentials, ExportFormat};
+use crate::auth::{AuthService, UserCredentials};
+use crate::audit::{AuditFilter, AuditRecord, DEFAULT_AUDIT_LIMIT, MAX_AUDIT_LIMIT};
+use crate::config::{self, ConfigSetting};
+use crate::response::ApiResponse;
+use crate::server::AppState;
+use crate::telemetry;
+
+/// 风控熔断状态
+///
+/// 收到 461 后对应接口进入熔断，冷却期按连续触发次数翻倍；多个接口同时熔断时整个账号熔断
+#[utoipa::path(
+    get,
+    path = "/api/admin/risk",
+    tag = "Admin",
+    summary = "风控熔断状态",
+    description = "冷却期见 XHS_RISK_COOLDOWN_SECS（默认 60）/ XHS_RISK_COOLDOWN_MAX_SECS（默认 1800），账号熔断阈值见 XHS_RISK_ACCOUNT_THRESHOLD（默认 3），排队上限见 XHS_RISK_MAX_WAIT_SECS（默认 0）",
+    responses(
+        (status = 200, description = "账号与各接口的熔断状态", body = ApiResponse<RiskStatus>)
+    )
+)]
+pub async fn risk_status_handler(
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    ApiResponse::ok(state.api.risk().status())
+}
+
+/// 审计日志查询参数
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct AuditQueryParams {
+    /// 按接口路径过滤，如 /api/sns/web/v1/search/notes
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    /// 按账号 user_id 过滤
+    #[serde(default)]
+    pub account: Option<String>,
+    /// 按 HTTP 请求 ID 过滤（响应头 / 响应体中的 request_id）
+    #[serde(default)]
+    pub request_id: Option<String>,
+    /// 按 HTTP 状态码过滤，如 461
+    #[serde(default)]
+    pub status: Option<u16>,
+    /// 只返回失败的请求
+    #[serde(default)]
+    pub errors_only: bool,
+    /// 发出时间下限 (ms)
+    #[serde(default)]
+    pub since: Option<i64>,
+    /// 发出时间上限 (ms)
+    #[serde(default)]
+    pub until: Option<i64>,
+    /// 返回最近的条数，默认 100，最大 1000
+    #[serde(default = "default_audit_limit")]
+    pub limit: usize,
+}
+
+fn default_audit_limit() -> usize { DEFAULT_AUDIT_LIMIT }
+
+/// 上游请求审计日志
+///
+/// 每个发往小红书的签名请求一条记录（含重试），用于排查风控前后的请求规律
+#[utoipa::path(
+    get,
+    path = "/api/admin/audit",
+    tag = "Admin",
+    summary = "上游请求审计日志",
+    description = "需设置 XHS_AUDIT_BACKEND=file|mongo。记录接口、状态码、业务码、耗时、账号、签名方式 (algo / stored)、错误类型与截断后的响应体（XHS_AUDIT_BODY_BYTES，默认 2048），保留 XHS_AUDIT_RETENTION_DAYS 天（默认 7）",
+    params(AuditQueryParams),
+    responses(
+        (status = 200, description = "最近的审计记录（按发出时间倒序）", body = ApiResponse<Vec<AuditRecord>>)
+    )
+)]
+pub async fn audit_log_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<AuditQueryParams>,
+) -> impl IntoResponse {
+    let Some(audit) = state.api.audit() else {
+        return ApiResponse::error("Audit log is disabled (set XHS_AUDIT_BACKEND=file|mongo)");
+    };
+
+    let filter = AuditFilter {
+        endpoint: params.endpoint,
+        account: params.account,
+        request_id: params.request_id,
+        status: params.status,
+        errors_only: params.errors_only,
+        since: params.since,
+        until: params.until,
+        limit: params.limit.clamp(1, MAX_AUDIT_LIMIT),
+    };
+    ApiResponse::from_result(audit.query(&filter).await)
+}
+
+/// 当前生效配置
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct EffectiveConfig {
+    /// 热加载读取的配置文件（profile 文件在前）
+    pub files: Vec<String>,
+    /// 是否监听配置文件变更（XHS_CONFIG_WATCH）
+    pub watching: bool,
+    /// 最近一次热加载时间 (ms)，启动后未热加载过时为空
+    pub reloaded_at: Option<i64>,
+    /// 控制台日志级别
+    pub log_level: Option<String>,
+    /// 可热加载项的当前取值
+    pub settings: Vec<ConfigSetting>,
+    /// 当前生效的出站限流
+    pub rate_limits: Vec<BucketStatus>,
+}
+
+/// 当前生效配置
+///
+/// 可热加载的配置项修改配置文件后无需重启即生效
+#[utoipa::path(
+    get,
+    path = "/api/admin/config",
+    tag = "Admin",
+    summary = "当前生效配置",
+    description = "可热加载项：XHS_RATE_LIMIT_RPM / XHS_RATE_LIMIT_BURST / XHS_RATE_LIMIT_ENDPOINTS、XHS_PROXY / XHS_NO_PROXY、XHS_WEBHOOK_URLS / XHS_WEBHOOK_SECRET、XHS_LOG_LEVEL。修改 profile 配置文件（--profile）或 .env 后自动重新加载；进程环境变量中设置的配置项 (`source: env`) 优先，不会被配置文件覆盖。XHS_WEBHOOK_SECRET 与代理密码已脱敏",
+    responses(
+        (status = 200, description = "可热加载项的取值与来源", body = ApiResponse<EffectiveConfig>)
+    )
+)]
+pub async fn config_handler(
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    ApiResponse::ok(EffectiveConfig {
+        files: config::config_files().iter().map(|f| f.display().to_string()).collect(),
+        watching: config::config_watch_enabled(),
+        reloaded_at: config::last_reload(),
+        log_level: telemetry::log_level().map(|level| level.to_string().to_lowercase()),
+        settings: config::reloadable_settings(),
+        rate_limits: state.api.rate_limiter().status(),
+    })
+}
+
+const ACCOUNT_POOL_DISABLED: &str = "Account rotation is disabled (set XHS_ACCOUNT_POOL)";
+
+/// 账号池与隔离状态
+///
+/// 列出参与轮换的账号、上次使用时间、窗口内风控信号次数与隔离状态
+#[utoipa::path(
+    get,
+    path = "/api/admin/accounts",
+    tag = "Admin",
+    summary = "账号池与隔离状态",
+    description = "需设置 XHS_ACCOUNT_POOL。账号在 XHS_QUARANTINE_WINDOW_SECS（默认 600）内收到 XHS_QUARANTINE_THRESHOLD（默认 3）次 461 / 419 / 验证码响应时隔离 XHS_QUARANTINE_SECS（默认 3600）秒，账号异常（300011）立即隔离；隔离期间不参与轮换",
+    responses(
+        (status = 200, description = "各账号状态", body = ApiResponse<AccountPoolStatus>)
+    )
+)]
+pub async fn account_pool_handler(
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    match state.api.account_pool() {
+        Some(pool) => ApiResponse::ok(pool.status().await),
+        None => ApiResponse::error(ACCOUNT_POOL_DISABLED),
+    }
+}
+
+/// 解除账号隔离
+#[utoipa::path(
+    post,
+    path = "/api/admin/accounts/{index}/release",
+    tag = "Admin",
+    summary = "解除账号隔离",
+    description = "提前解除隔离并清零风控信号计数，账号立即重新参与轮换",
+    params(("index" = usize, Path, description = "账号下标（见 /api/admin/accounts，0 为主账号）")),
+    responses(
+        (status = 200, description = "解除后的账号池状态", body = ApiResponse<AccountPoolStatus>)
+    )
+)]
+pub async fn release_account_handler(
+    State(state): State<Arc<AppState>>,
+    Path(index): Path<usize>,
+) -> impl IntoResponse {
+    let Some(pool) = state.api.account_pool() else {
+        return ApiResponse::error(ACCOUNT_POOL_DISABLED);
+    };
+    let released = pool.release(index);
+    let status = pool.status().await;
+    if released {
+        ApiResponse::ok(status)
+    } else {
+        ApiResponse::error(format!("Account #{} is not quarantined", index)).with_data(status)
+    }
+}
+
+/// HAR 导入参数
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct ImportHarParams {
+    /// 是否调用 /user/me 校验 Cookie（默认 true）；为 false 时账号取自 HAR 中录制的 /user/me 响应
+    pub validate: Option<bool>,
+}
+
+/// 导入的签名
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct ImportedSignature {
+    /// 签名存储的 key（如 search_trending / home_feed_recommend）
+    pub endpoint: String,
+    pub method: Option<String>,
+    pub url: Option<String>,
+    #[schema(value_type = String)]
+    pub captured_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// HAR 导入结果
+#[derive(Debug, Default, Serialize, utoipa::ToSchema)]
+pub struct HarImport {
+    /// HAR 中的请求数
+    pub entries: usize,
+    /// 是否导入了登录 Cookie（HAR 中没有 web_session 时只导入签名）
+    pub cookies_imported: bool,
+    pub user_id: Option<String>,
+    pub nickname: Option<String>,
+    /// 存入签名存储的接口签名（每个接口保留最新一条）
+    pub signatures: Vec<ImportedSignature>,
+    /// 带签名但无法对应到签名 key 的接口路径（未导入）
+    pub unmapped: Vec<String>,
+}
+
+/// 导入 HAR
+///
+/// 从浏览器导出的 HAR 中提取 Cookie 与各接口签名，作为存储签名兜底，无需运行 Playwright 抓取脚本
+#[utoipa::path(
+    post,
+    path = "/api/admin/import-har",
+    tag = "Admin",
+    summary = "导入 HAR（Cookie 与存储签名）",
+    description = "请求体为浏览器开发者工具 Network 面板导出的 HAR（`curl --data-binary @xhs.har`，最大 64 MB）。提取发往 xiaohongshu.com 的 Cookie，以及 edith.xiaohongshu.com 上带 `x-s` / `x-t` 的请求（URL、请求体、x-s-common 与 trace ID），按接口保存到 `cookie.signatures.json`，供 Agent 不可用时的存储签名兜底使用。HAR 含 web_session 时 Cookie 经 /user/me 校验后保存为当前凭据（`validate=false` 时账号取自 HAR 中录制的 /user/me 响应）。",
+    params(ImportHarParams),
+    request_body(content = Object, description = "HAR 文档", content_type = "application/json"),
+    responses(
+        (status = 200, description = "导入结果（失败时 data 为已完成的部分）", body = ApiResponse<HarImport>),
+        (status = 400, description = "不是有效的 HAR", body = ApiResponse<HarImport>)
+    )
+)]
+pub async fn import_har_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<ImportHarParams>,
+    body: axum::body::Bytes,
+) -> impl IntoResponse {
+    let har: Har = match serde_json::from_slice(&body) {
+        Ok(har) => har,
+        Err(e) => {
+            return (StatusCode::BAD_REQUEST, ApiResponse::<HarImport>::error(format!("Invalid HAR: {}", e))).into_response();
+        }
+    };
+    let import = har::parse_har(&har);
+    tracing::info!(
+        "[HAR] {} entries: {} cookie(s), {} signature(s), {} unmapped signed path(s)",
+        import.entries, import.cookies.len(), import.signatures.len(), import.unmapped.len()
+    );
+
+    let mut result = HarImport {
+        entries: import.entries,
+        signatures: import.signatures.iter().map(|sig| ImportedSignature {
+            endpoint: sig.endpoint.clone(),
+            method: sig.method.clone(),
+            url: sig.request_url.clone(),
+            captured_at: sig.captured_at,
+        }).collect(),
+        unmapped: import.unmapped,
+        ..Default::default()
+    };
+
+    // 先保存签名：Agent 不可用时 /user/me 校验也能用上刚导入的签名
+    if !import.signatures.is_empty() {
+        if let Err(e) = state.auth.save_endpoint_signatures(&import.signatures).await {
+            return ApiResponse::error(format!("Failed to save signatures: {}", e)).with_data(result).into_response();
+        }
+    }
+
+    if !import.cookies.contains_key("web_session") {
+        if result.signatures.is_empty() {
+            return ApiResponse::error("HAR 中没有小红书的 Cookie 或接口签名").with_data(result).into_response();
+        }
+        return ApiResponse::ok(result).into_response();
+    }
+
+    let creds = UserCredentials::new("unknown".to_string(), import.cookies, None);
+    let user = if params.validate.unwrap_or(true) {
+        match api::user::get_user_with_credentials(&state.api, &creds).await {
+            Ok(me) if me.data.guest => Err("Cookie 不是有效的登录态（游客会话）".to_string()),
+            Ok(me) => Ok((me.data.user_id, me.data.nickname)),
+            Err(e) => Err(format!("Cookie 校验失败: {}", e)),
+        }
+    } else {
+        import.user_id
+            .map(|user_id| (user_id, None))
+            .ok_or_else(|| "HAR 中没有已登录的 /user/me 响应，无法确定账号，请使用 validate=true".to_string())
+    };
+    let (user_id, nickname) = match user {
+        Ok(user) => user,
+        Err(e) => return ApiResponse::error(e).with_data(result).into_response(),
+    };
+
+    let creds = UserCredentials { user_id: user_id.clone(), ..creds };
+    if let Err(e) = state.auth.save_credentials(&creds).await {
+        return ApiResponse::error(e.to_string()).with_data(result).into_response();
+    }
+    tracing::info!("[HAR] Imported cookies saved for user: {}", user_id);
+    result.cookies_imported = true;
+    result.user_id = Some(user_id);
+    result.nickname = nickname;
+    ApiResponse::ok(result).into_response()
+}
+
+/// 已存储的账号凭据（Cookie 已遮蔽）
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct StoredAccount {
+    /// 账号下标（与 /api/admin/accounts 一致，0 为当前账号 cookie.json）
+    pub index: usize,
+    /// 凭据文件
+    pub source: String,
+    /// 是否为当前账号（写接口、通知等始终使用）
+    pub active: bool,
+    /// 是否存有凭据
+    pub stored: bool,
+    pub user_id: Option<String>,
+    /// 凭据是否有效（登录过期或手动失效后为 false）
+    pub valid: bool,
+    /// 超过 7 天未更新，可能已过期
+    pub potentially_expired: bool,
+    #[schema(value_type = Option<String>)]
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// 遮蔽后的 Cookie
+    pub cookies: Option<String>,
+}
+
+/// 当前账号 + 账号池中的账号（未启用账号池时只有当前账号）
+fn credential_slots(state: &AppState) -> Vec<(String, Arc<AuthService>)> {
+    match state.api.account_pool() {
+        Some(pool) => pool.accounts().to_vec(),
+        None => vec![("cookie.json".to_string(), state.auth.clone())],
+    }
+}
+
+async fn credentials_response(state: &AppState, error: Option<String>) -> ApiResponse<Vec<StoredAccount>> {
+    let mut accounts = Vec::new();
+    for (index, (source, auth)) in credential_slots(state).into_iter().enumerate() {
+        let creds = auth.stored_credentials().await.ok().flatten();
+        accounts.push(StoredAccount {
+            index,
+            source,
+            active: index == 0,
+            stored: creds.is_some(),
+            user_id: creds.as_ref().map(|c| c.user_id.clone()),
+            valid: creds.as_ref().is_some_and(|c| c.is_valid),
+            potentially_expired: creds.as_ref().is_some_and(|c| c.is_potentially_expired()),
+            updated_at: creds.as_ref().map(|c| c.updated_at),
+            cookies: creds.as_ref().map(|c| export_credentials(c, ExportFormat::Header, true)),
+        });
+    }
+    match error {
+        Some(error) => ApiResponse::error(error).with_data(accounts),
+        None => ApiResponse::ok(accounts),
+    }
+}
+
+/// 账号凭据列表
+#[utoipa::path(
+    get,
+    path = "/api/admin/credentials",
+    tag = "Admin",
+    summary = "账号凭据列表",
+    description = "列出当前账号（cookie.json）与 XHS_ACCOUNT_POOL 中各账号的存储凭据，含已失效的凭据；Cookie 值已遮蔽",
+    responses(
+        (status = 200, description = "账号凭据", body = ApiResponse<Vec<StoredAccount>>)
+    )
+)]
+pub async fn list_credentials_handler(
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    credentials_response(&state, None).await
+}
+
+/// 使账号凭据失效
+#[utoipa::path(
+    post,
+    path = "/api/admin/credentials/{index}/invalidate",
+    tag = "Admin",
+    summary = "使账号凭据失效",
+    description = "标记凭据失效（保留文件与浏览器指纹），该账号不再发出请求，重新登录或导入 Cookie 后恢复",
+    params(("index" = usize, Path, description = "账号下标（见 /api/admin/credentials）")),
+    responses(
+        (status = 200, description = "操作后的账号凭据", body = ApiResponse<Vec<StoredAccount>>)
+    )
+)]
+pub async fn invalidate_credentials_handler(
+    State(state): State<Arc<AppState>>,
+    Path(index): Path<usize>,
+) -> impl IntoResponse {
+    let error = match credential_slots(&state).get(index) {
+        None => Some(format!("Account #{} does not exist", index)),
+        Some((source, auth)) => match auth.invalidate_credentials().await {
+            Ok(()) => {
+                tracing::warn!("[Admin] Invalidated credentials of account #{} ({})", index, source);
+                None
+            }
+            Err(e) => Some(e.to_string()),
+        },
+    };
+    credentials_response(&state, error).await
+}
+
+/// 删除账号凭据
+#[utoipa::path(
+    delete,
+    path = "/api/admin/credentials/{index}",
+    tag = "Admin",
+    summary = "删除账号凭据",
+    description = "删除凭据文件及其导入的存储签名（浏览器指纹随之丢失，重新登录会分配新指纹）",
+    params(("index" = usize, Path, description = "账号下标（见 /api/admin/credentials）")),
+    responses(
+        (status = 200, description = "操作后的账号凭据", body = ApiResponse<Vec<StoredAccount>>)
+    )
+)]
+pub async fn delete_credentials_handler(
+    State(state): State<Arc<AppState>>,
+    Path(index): Path<usize>,
+) -> impl IntoResponse {
+    let error = match credential_slots(&state).get(index) {
+        None => Some(format!("Account #{} does not exist", index)),
+        Some((source, auth)) => match auth.delete_credentials().await {
+            Ok(true) => {
+                tracing::warn!("[Admin] Deleted credentials of account #{} ({})", index, source);
+                None
+            }
+            Ok(false) => Some(format!("Account #{} has no stored credentials", index)),
+            Err(e) => Some(e.to_string()),
+        },
+    };
+    credentials_response(&state, error).await
+}
+
+/// 设为当前账号
+#[utoipa::path(
+    post,
+    path = "/api/admin/credentials/{index}/promote",
+    tag = "Admin",
+    summary = "设为当前账号",
+    description = "把账号池中的账号与当前账号（cookie.json）交换凭据：该账号成为当前账号，原当前账号移入其凭据文件（原先没有凭据时该文件被删除）",
+    params(("index" = usize, Path, description = "账号池中的账号下标（>= 1，见 /api/admin/credentials）")),
+    responses(
+        (status = 200, description = "操作后的账号凭据", body = ApiResponse<Vec<StoredAccount>>)
+    )
+)]
+pub async fn promote_credentials_handler(
+    State(state): State<Arc<AppState>>,
+    Path(index): Path<usize>,
+) -> impl IntoResponse {
+    let error = match promote(&state, index).await {
+        Ok(()) => None,
+        Err(e) => Some(e.to_string()),
+    };
+    credentials_response(&state, error).await
+}
+
+async fn promote(state: &AppState, index: usize) -> anyhow::Result<()> {
+    if index == 0 {
+        anyhow::bail!("Account #0 is already the active account");
+    }
+    let slots = credential_slots(state);
+    let (source, auth) = slots.get(index)
+        .ok_or_else(|| anyhow::anyhow!("Account #{} does not exist", index))?;
+    let promoted = auth.stored_credentials().await?
+        .ok_or_else(|| anyhow::anyhow!("Account #{} has no stored credentials", index))?;
+    if !promoted.is_valid {
+        anyhow::bail!("Credentials of account #{} are invalid, log in again first", index);
+    }
+
+    let previous = state.auth.stored_credentials().await?;
+    state.auth.save_credentials(&promoted).await?;
+    match previous {
+        Some(previous) => auth.save_credentials(&previous).await?,
+        None => {
+            auth.delete_credentials().await?;
+        }
+    }
+    tracing::warn!("[Admin] Promoted account #{} ({}, {}) to active", index, source, promoted.user_id);
+    Ok(())
+}
@@ -0,0 +1,553 @@
+//! Archive HTTP Handlers
+//!
+//! Handles: archive (list), archive/{id}, archive/dictionary, archive/note, archive/notes, archive/notes/{note_id}/history, archive/notes/{note_id}/comments, archive/export
+
+use axum::{
+    body::Body,
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use futures_util::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::api::note::detail::{fetch_note_detail, NoteDetailRequest};
+use crate::api::note::comments::NoteComment;
+use crate::archive::comments::{CommentFilter, DEFAULT_COMMENT_LIMIT, MAX_COMMENT_LIMIT};
+use crate::archive::history::{FieldChange, HistoryFilter, NoteRevision, DEFAULT_HISTORY_LIMIT, MAX_HISTORY_LIMIT};
+use crate::archive::export::{self, ExportColumn, ArchiveExportFormat, ParquetExport};
+use crate::archive::notes::{ArchivedNote, NoteFilter, DEFAULT_NOTE_LIMIT, MAX_NOTE_LIMIT};
+use crate::archive::{ArchiveEntry, DictionaryInfo, ResponseArchive, DEFAULT_DICT_SIZE};
+use crate::response::ApiResponse;
+use crate::server::AppState;
+
+const ARCHIVE_DISABLED: &str = "Response archive is disabled (set XHS_ARCHIVE_BACKEND=file|mongo)";
+
+/// 归档列表查询参数
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct ArchiveListParams {
+    /// 按接口路径过滤，如 /api/sns/web/v1/homefeed
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    /// 返回条数，默认 50，最大 500
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+fn default_limit() -> usize { 50 }
+
+/// 归档列表
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct ArchiveList {
+    pub entries: Vec<ArchiveEntry>,
+    /// 本页原始总大小 / 压缩后总大小
+    pub compression_ratio: Option<f64>,
+}
+
+/// 字典训练请求
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+#[schema(example = json!({"samples": 1000, "max_size": 114688}))]
+pub struct TrainDictionaryRequest {
+    /// 使用最近多少条归档作为样本，默认 1000
+    #[serde(default = "default_samples")]
+    pub samples: usize,
+    /// 字典大小上限（字节），默认 112KB
+    #[serde(default = "default_max_size")]
+    pub max_size: usize,
+}
+
+fn default_samples() -> usize { 1000 }
+fn default_max_size() -> usize { DEFAULT_DICT_SIZE }
+
+/// 原始响应归档列表
+#[utoipa::path(
+    get,
+    path = "/api/archive",
+    tag = "Archive",
+    summary = "归档列表",
+    params(ArchiveListParams),
+    responses(
+        (status = 200, description = "最近的归档条目（不含正文）", body = ApiResponse<ArchiveList>)
+    )
+)]
+pub async fn archive_list_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<ArchiveListParams>,
+) -> impl IntoResponse {
+    let Some(archive) = state.api.archive() else {
+        return ApiResponse::error(ARCHIVE_DISABLED);
+    };
+
+    let result = archive.recent(params.endpoint.as_deref(), params.limit.clamp(1, 500)).await;
+    ApiResponse::from_result(result.map(|entries| {
+        let raw: u64 = entries.iter().map(|e| e.raw_size).sum();
+        let stored: u64 = entries.iter().map(|e| e.stored_size).sum();
+        ArchiveList {
+            compression_ratio: (stored > 0).then(|| raw as f64 / stored as f64),
+            entries,
+        }
+    }))
+}
+
+/// 读取归档原始响应
+///
+/// 透明解压后原样返回上游 JSON
+#[utoipa::path(
+    get,
+    path = "/api/archive/{id}",
+    tag = "Archive",
+    summary = "读取归档",
+    params(
+        ("id" = String, Path, description = "归档 ID")
+    ),
+    responses(
+        (status = 200, description = "原始上游响应 JSON"),
+        (status = 404, description = "归档不存在")
+    )
+)]
+pub async fn archive_read_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let Some(archive) = state.api.archive() else {
+        return (StatusCode::SERVICE_UNAVAILABLE, ApiResponse::<()>::error(ARCHIVE_DISABLED)).into_response();
+    };
+
+    match archive.read(&id).await {
+        Ok(Some((_, raw))) => ([(header::CONTENT_TYPE, "application/json")], raw).into_response(),
+        Ok(None) => (StatusCode::NOT_FOUND, ApiResponse::<()>::error("Archive not found")).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, ApiResponse::<()>::error(e.to_string())).into_response(),
+    }
+}
+
+/// 训练归档压缩字典
+///
+/// 用最近的归档响应训练 zstd 字典，之后的新归档使用该字典压缩；旧归档仍可正常读取
+#[utoipa::path(
+    post,
+    path = "/api/archive/dictionary",
+    tag = "Archive",
+    summary = "训练压缩字典",
+    request_body = TrainDictionaryRequest,
+    responses(
+        (status = 200, description = "训练结果", body = ApiResponse<DictionaryInfo>)
+    )
+)]
+pub async fn archive_train_dictionary_handler(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<TrainDictionaryRequest>,
+) -> impl IntoResponse {
+    let Some(archive) = state.api.archive() else {
+        return ApiResponse::error(ARCHIVE_DISABLED);
+    };
+
+    ApiResponse::from_result(archive.train_dictionary(req.samples.max(1), req.max_size).await)
+}
+
+/// 笔记归档请求
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+pub struct ArchiveNoteRequest {
+    /// 笔记 ID (必填)
+    pub note_id: String,
+    /// xsec_token (必填，从 feed/search 结果获取)
+    pub xsec_token: String,
+}
+
+/// 笔记归档结果
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct ArchiveNoteResult {
+    pub note: ArchivedNote,
+    /// 相对上一次归档变化的字段（首次归档为空）
+    pub changes: Vec<FieldChange>,
+}
+
+/// 笔记归档查询参数
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct ArchivedNotesParams {
+    /// 作者 user_id
+    #[serde(default)]
+    pub user_id: Option<String>,
+    /// 笔记类型: normal (图文) / video
+    #[serde(default)]
+    pub note_type: Option<String>,
+    /// 包含该话题标签
+    #[serde(default)]
+    pub tag: Option<String>,
+    /// 标题或正文包含该关键词
+    #[serde(default)]
+    pub keyword: Option<String>,
+    /// 最近归档时间下限 (ms)
+    #[serde(default)]
+    pub since: Option<i64>,
+    /// 最近归档时间上限 (ms)
+    #[serde(default)]
+    pub until: Option<i64>,
+    /// 跳过条数，默认 0
+    #[serde(default)]
+    pub offset: usize,
+    /// 返回条数，默认 50，最大 500
+    #[serde(default = "default_note_limit")]
+    pub limit: usize,
+}
+
+fn default_note_limit() -> usize { DEFAULT_NOTE_LIMIT }
+
+/// 归档笔记
+///
+/// 抓取笔记详情，把结构化详情与媒体地址持久化到归档后端
+#[utoipa::path(
+    post,
+    path = "/api/archive/note",
+    tag = "Archive",
+    summary = "归档笔记",
+    description = "抓取笔记详情并保存结构化详情（标题、正文、作者、标签、发布时间、互动数据）与图片 / 视频地址。同一笔记重复归档时覆盖为最新快照，保留首次归档时间并累加归档次数，并记录标题 / 正文 / 标签与互动数据相对上一次的变化（见 `/api/archive/notes/{note_id}/history`）。",
+    request_body = ArchiveNoteRequest,
+    responses(
+        (status = 200, description = "归档结果", body = ApiResponse<ArchiveNoteResult>),
+        (status = 404, description = "笔记不存在", body = ApiResponse<ArchiveNoteResult>)
+    )
+)]
+pub async fn archive_note_handler(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<ArchiveNoteRequest>,
+) -> impl IntoResponse {
+    let failure = |status: StatusCode, error: String| {
+        (status, ApiResponse::error(error))
+    };
+    let Some(archive) = state.api.archive() else {
+        return failure(StatusCode::OK, ARCHIVE_DISABLED.to_string());
+    };
+
+    let detail = match fetch_note_detail(&state.api, &state.note_cache, NoteDetailRequest::new(&req.note_id, &req.xsec_token)).await {
+        Ok(detail) => detail,
+        Err(e) => return failure(StatusCode::OK, e.to_string()),
+    };
+    let Some(note_card) = detail.note_card() else {
+        return failure(StatusCode::NOT_FOUND, detail.msg.clone().unwrap_or_else(|| "Note not found".to_string()));
+    };
+    let note = match ArchivedNote::from_note_card(&req.note_id, &req.xsec_token, note_card) {
+        Ok(note) => note,
+        Err(e) => return failure(StatusCode::OK, e.to_string()),
+    };
+
+    match archive.store_note(note).await {
+        Ok((note, revision)) => {
+            tracing::info!(
+                "[Archive] Archived note {} (#{}, {} change(s))",
+                note.note_id, note.archive_count, revision.changes.len()
+            );
+            let changes = revision.changes;
+            (StatusCode::OK, ApiResponse::ok(ArchiveNoteResult { note, changes }))
+        }
+        Err(e) => failure(StatusCode::OK, e.to_string()),
+    }
+}
+
+/// 查询归档笔记
+#[utoipa::path(
+    get,
+    path = "/api/archive/notes",
+    tag = "Archive",
+    summary = "查询归档笔记",
+    params(ArchivedNotesParams),
+    responses(
+        (status = 200, description = "按最近归档时间倒序", body = ApiResponse<Vec<ArchivedNote>>)
+    )
+)]
+pub async fn archived_notes_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<ArchivedNotesParams>,
+) -> impl IntoResponse {
+    let Some(archive) = state.api.archive() else {
+        return ApiResponse::error(ARCHIVE_DISABLED);
+    };
+
+    let filter = NoteFilter {
+        user_id: params.user_id,
+        note_type: params.note_type,
+        tag: params.tag,
+        keyword: params.keyword,
+        since: params.since,
+        until: params.until,
+        offset: params.offset,
+        limit: params.limit.clamp(1, MAX_NOTE_LIMIT),
+    };
+    ApiResponse::from_result(archive.query_notes(&filter).await)
+}
+
+/// 笔记变更记录查询参数
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct NoteHistoryParams {
+    /// 归档时间下限 (ms)
+    #[serde(default)]
+    pub since: Option<i64>,
+    /// 归档时间上限 (ms)
+    #[serde(default)]
+    pub until: Option<i64>,
+    /// 只返回修改了标题 / 正文 / 标签的记录
+    #[serde(default)]
+    pub edits_only: bool,
+    /// 返回最近的条数，默认 100，最大 1000
+    #[serde(default = "default_history_limit")]
+    pub limit: usize,
+}
+
+fn default_history_limit() -> usize { DEFAULT_HISTORY_LIMIT }
+
+/// 笔记变更记录
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct NoteHistory {
+    pub note_id: String,
+    /// 按归档时间正序
+    pub revisions: Vec<NoteRevision>,
+}
+
+/// 笔记变更记录
+///
+/// 每次归档一条：当时的互动数据，以及相对上一次归档变化的字段
+#[utoipa::path(
+    get,
+    path = "/api/archive/notes/{note_id}/history",
+    tag = "Archive",
+    summary = "笔记变更记录",
+    description = "返回笔记每次归档时的点赞 / 收藏 / 评论 / 分享数（按时间正序，可直接绘制趋势），以及标题、正文、标签的修改（`old` / `new`）与互动数据的增量（`delta`）。首次归档的记录没有变更项。定时 `note` 任务（`/api/jobs`）可用于周期性采样。",
+    params(
+        ("note_id" = String, Path, description = "笔记 ID"),
+        NoteHistoryParams
+    ),
+    responses(
+        (status = 200, description = "变更记录", body = ApiResponse<NoteHistory>)
+    )
+)]
+pub async fn note_history_handler(
+    State(state): State<Arc<AppState>>,
+    Path(note_id): Path<String>,
+    Query(params): Query<NoteHistoryParams>,
+) -> impl IntoResponse {
+    let Some(archive) = state.api.archive() else {
+        return ApiResponse::error(ARCHIVE_DISABLED);
+    };
+
+    let filter = HistoryFilter {
+        since: params.since,
+        until: params.until,
+        edits_only: params.edits_only,
+        limit: params.limit.clamp(1, MAX_HISTORY_LIMIT),
+    };
+    let result = archive.note_history(&note_id, &filter).await;
+    ApiResponse::from_result(result.map(|revisions| NoteHistory { note_id, revisions }))
+}
+
+/// 归档评论查询参数
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct ArchivedCommentsParams {
+    /// 只返回该一级评论下的子评论
+    #[serde(default)]
+    pub root_comment_id: Option<String>,
+    /// 跳过条数，默认 0
+    #[serde(default)]
+    pub offset: usize,
+    /// 返回条数，默认 100，最大 1000
+    #[serde(default = "default_comment_limit")]
+    pub limit: usize,
+}
+
+fn default_comment_limit() -> usize { DEFAULT_COMMENT_LIMIT }
+
+/// 归档评论
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct ArchivedComments {
+    pub note_id: String,
+    /// 按评论时间正序
+    pub comments: Vec<NoteComment>,
+}
+
+/// 查询归档评论
+#[utoipa::path(
+    get,
+    path = "/api/archive/notes/{note_id}/comments",
+    tag = "Archive",
+    summary = "查询归档评论",
+    description = "读取 `/api/note/comments/all`（`archive=true`）保存的评论，一级评论与子评论混排，按评论时间正序；子评论的 `root_comment_id` 指向所属一级评论。",
+    params(
+        ("note_id" = String, Path, description = "笔记 ID"),
+        ArchivedCommentsParams
+    ),
+    responses(
+        (status = 200, description = "归档评论", body = ApiResponse<ArchivedComments>)
+    )
+)]
+pub async fn archived_comments_handler(
+    State(state): State<Arc<AppState>>,
+    Path(note_id): Path<String>,
+    Query(params): Query<ArchivedCommentsParams>,
+) -> impl IntoResponse {
+    let Some(archive) = state.api.archive() else {
+        return ApiResponse::error(ARCHIVE_DISABLED);
+    };
+
+    let filter = CommentFilter {
+        root_comment_id: params.root_comment_id,
+        offset: params.offset,
+        limit: params.limit.clamp(1, MAX_COMMENT_LIMIT),
+    };
+    let result = archive.query_comments(&note_id, &filter).await;
+    ApiResponse::from_result(result.map(|comments| ArchivedComments { note_id, comments }))
+}
+
+/// 归档导出查询参数
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct ArchiveExportParams {
+    /// 导出格式: csv / jsonl (默认) / parquet
+    #[serde(default)]
+    #[param(inline)]
+    pub format: ArchiveExportFormat,
+    /// 逗号分隔的列名，默认全部列
+    #[serde(default)]
+    pub columns: Option<String>,
+    /// 作者 user_id
+    #[serde(default)]
+    pub user_id: Option<String>,
+    /// 笔记类型: normal (图文) / video
+    #[serde(default)]
+    pub note_type: Option<String>,
+    /// 包含该话题标签
+    #[serde(default)]
+    pub tag: Option<String>,
+    /// 标题或正文包含该关键词
+    #[serde(default)]
+    pub keyword: Option<String>,
+    /// 最近归档时间下限 (ms)
+    #[serde(default)]
+    pub since: Option<i64>,
+    /// 最近归档时间上限 (ms)
+    #[serde(default)]
+    pub until: Option<i64>,
+    /// 最多导出条数，默认不限
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+/// 导出分页状态：按最近归档时间倒序逐页读取
+struct ExportPages {
+    archive: Arc<ResponseArchive>,
+    filter: NoteFilter,
+    remaining: usize,
+}
+
+impl ExportPages {
+    async fn next(&mut self) -> anyhow::Result<Option<Vec<ArchivedNote>>> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.filter.limit = self.remaining.min(MAX_NOTE_LIMIT);
+        let notes = self.archive.query_notes(&self.filter).await?;
+        if notes.is_empty() {
+            return Ok(None);
+        }
+        self.remaining = if notes.len() < self.filter.limit { 0 } else { self.remaining - notes.len() };
+        self.filter.offset += notes.len();
+        Ok(Some(notes))
+    }
+}
+
+/// 导出归档笔记
+///
+/// 按条件导出归档笔记（每条笔记一行），可直接用 pandas / DuckDB 读取
+#[utoipa::path(
+    get,
+    path = "/api/archive/export",
+    tag = "Archive",
+    summary = "导出归档笔记",
+    description = "按最近归档时间倒序导出笔记归档，过滤条件同 `/api/archive/notes`。\n\n可选列（`columns`，逗号分隔，默认全部）：`note_id`, `xsec_token`, `note_type`, `title`, `desc`, `user_id`, `nickname`, `tags`（逗号连接）, `publish_time`, `last_update_time`, `ip_location`, `liked_count`, `collected_count`, `comment_count`, `share_count`, `image_count`, `video_url`（最高画质）, `first_archived_at`, `archived_at`, `archive_count`。时间列为毫秒时间戳。\n\n`csv` / `jsonl` 边查边流式输出；`parquet`（snappy 压缩，每页一个 row group）写完后整体返回。",
+    params(ArchiveExportParams),
+    responses(
+        (status = 200, description = "导出文件（Content-Disposition: attachment）", content_type = "application/octet-stream"),
+        (status = 400, description = "未知列名"),
+        (status = 503, description = "未启用归档")
+    )
+)]
+pub async fn archive_export_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<ArchiveExportParams>,
+) -> impl IntoResponse {
+    let Some(archive) = state.api.archive() else {
+        return (StatusCode::SERVICE_UNAVAILABLE, ApiResponse::<()>::error(ARCHIVE_DISABLED)).into_response();
+    };
+    let columns = match ExportColumn::parse_list(params.columns.as_deref()) {
+        Ok(columns) if !columns.is_empty() => columns,
+        Ok(_) => return (StatusCode::BAD_REQUEST, ApiResponse::<()>::error("No export columns given")).into_response(),
+        Err(e) => return (StatusCode::BAD_REQUEST, ApiResponse::<()>::error(e)).into_response(),
+    };
+
+    let format = params.format;
+    let mut pages = ExportPages {
+        archive: archive.clone(),
+        filter: NoteFilter {
+            user_id: params.user_id,
+            note_type: params.note_type,
+            tag: params.tag,
+            keyword: params.keyword,
+            since: params.since,
+            until: params.until,
+            offset: 0,
+            limit: MAX_NOTE_LIMIT,
+        },
+        remaining: params.limit.unwrap_or(usize::MAX),
+    };
+    let headers = [
+        (header::CONTENT_TYPE, format.content_type().to_string()),
+        (
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"notes_{}.{}\"", chrono::Local::now().format("%Y%m%d_%H%M%S"), format.extension()),
+        ),
+    ];
+
+    if format == ArchiveExportFormat::Parquet {
+        let written = async {
+            let mut export = ParquetExport::new(columns)?;
+            let mut rows = 0;
+            while let Some(notes) = pages.next().await? {
+                rows += notes.len();
+                export.write(&notes)?;
+            }
+            Ok::<_, anyhow::Error>((rows, export.finish()?))
+        }.await;
+        return match written {
+            Ok((rows, data)) => {
+                tracing::info!("[Archive] Exported {} notes as parquet ({} bytes)", rows, data.len());
+                (headers, data).into_response()
+            }
+            Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, ApiResponse::<()>::error(e.to_string())).into_response(),
+        };
+    }
+
+    // 逐页编码后发送；中途失败时以错误结束响应体，让客户端感知导出不完整
+    let body = futures_util::stream::try_unfold((pages, true), move |(mut pages, first)| {
+        let columns = columns.clone();
+        async move {
+            let notes = match pages.next().await? {
+                Some(notes) => notes,
+                // CSV 即使没有数据也输出表头
+                None if first && format == ArchiveExportFormat::Csv => Vec::new(),
+                None => return Ok(None),
+            };
+            let chunk = match format {
+                ArchiveExportFormat::Csv => export::csv_rows(&notes, &columns, first)?,
+                _ => export::jsonl_rows(&notes, &columns)?,
+            };
+            if notes.is_empty() {
+                pages.remaining = 0;
+            }
+            Ok::<_, anyhow::Error>(Some((chunk, (pages, false))))
+        }
+    })
+    .map_err(|e| {
+        tracing::error!("[Archive] Export failed: {}", e);
+        std::io::Error::other(e.to_string())
+    });
+
+    (headers, Body::from_stream(body)).into_response()
+}
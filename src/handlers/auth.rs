@@ -1,17 +1,29 @@
 //! Authentication HTTP Handlers
 //! 
-//! Handles: guest-init, qrcode/create, qrcode/status
+//! Handles: guest-init, qrcode/create, qrcode/status, qrcode/status/wait, qrcode/events, import-cookies, export, health, status,
+//! verification
 
 use axum::{
-    extract::State,
-    response::IntoResponse,
+    extract::{Query, State},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse,
+    },
     Json,
 };
 use std::sync::Arc;
+use std::time::Duration;
 
 use crate::api;
+use crate::auth::LoginSession;
 use crate::server::AppState;
-use crate::api::login::{GuestInitResponse, CreateQrCodeResponse, PollStatusResponse};
+use crate::api::login::{
+    GuestSession, QrCodeInfo, QrStatus, QrCodeSession, QrCodeState,
+    LoginSessionParams, QrStatusWaitParams, QrStatusWait,
+    ImportCookiesRequest, ImportedAccount,
+    ExportCredentialsParams, ExportedCredentials,
+};
+use crate::response::ApiResponse;
 
 // ============================================================================
 // Handlers
@@ -19,15 +31,16 @@ use crate::api::login::{GuestInitResponse, CreateQrCodeResponse, PollStatusRespo
 
 /// 初始化访客登录会话
 ///
-/// 通过 Playwright 获取访客 Cookie，存储到内存中供后续 QR 登录使用
+/// 本地生成 a1/webId 并激活访客会话（失败时回退到 Agent 的 Playwright 流程），
+/// 保存为登录会话并返回 session_token 供后续 QR 登录使用
 #[utoipa::path(
     post,
     path = "/api/auth/guest-init",
     tag = "auth",
     summary = "初始化访客会话",
-    description = "获取访客 Cookie，这是 QR 登录的第一步",
+    description = "获取访客 Cookie 并创建登录会话，这是 QR 登录的第一步。返回的 `session_token` 传给 qrcode/create、qrcode/status、qrcode/status/wait、qrcode/events（不传时使用最近的登录会话）；会话保存在存储后端，服务重启或多实例部署时仍可继续登录",
     responses(
-        (status = 200, description = "访客 Cookie", body = GuestInitResponse)
+        (status = 200, description = "访客 Cookie", body = ApiResponse<GuestSession>)
     )
 )]
 pub async fn guest_init_handler(
@@ -35,28 +48,21 @@ pub async fn guest_init_handler(
 ) -> impl IntoResponse {
     tracing::info!("Guest init requested");
     
-    match api::login::fetch_guest_cookies().await {
-        Ok(cookies) => {
-            // Store cookies in state
-            {
-                let mut guest = state.guest_cookies.write().await;
-                *guest = Some(cookies.clone());
-            }
-            
+    let result = match api::login::fetch_guest_cookies().await {
+        Ok(cookies) => state.login_sessions.create(cookies).await,
+        Err(e) => Err(e.into()),
+    };
+    match result {
+        Ok(session) => {
             tracing::info!("Guest cookies obtained successfully");
-            Json(GuestInitResponse {
-                success: true,
-                cookies: Some(cookies),
-                error: None,
-            }).into_response()
+            ApiResponse::ok(GuestSession {
+                session_token: Some(session.token),
+                cookies: session.guest_cookies,
+            })
         }
         Err(e) => {
             tracing::error!("Failed to get guest cookies: {}", e);
-            Json(GuestInitResponse {
-                success: false,
-                cookies: None,
-                error: Some(e.to_string()),
-            }).into_response()
+            ApiResponse::error(e.to_string())
         }
     }
 }
@@ -69,149 +75,205 @@ pub async fn guest_init_handler(
     path = "/api/auth/qrcode/create",
     tag = "auth",
     summary = "创建登录二维码",
-    description = "需要先调用 guest-init 获取访客 Cookie",
+    description = "需要先调用 guest-init 获取访客 Cookie，expires_in 为二维码剩余有效秒数",
+    params(LoginSessionParams),
     responses(
-        (status = 200, description = "二维码信息", body = CreateQrCodeResponse)
+        (status = 200, description = "二维码信息", body = ApiResponse<QrCodeInfo>)
     )
 )]
 pub async fn create_qrcode_handler(
     State(state): State<Arc<AppState>>,
+    Query(params): Query<LoginSessionParams>,
 ) -> impl IntoResponse {
-    // Get guest cookies
-    let cookies = {
-        let guard = state.guest_cookies.read().await;
-        guard.clone()
+    let result = match state.login_sessions.get(params.session_token.as_deref()).await {
+        Ok(Some(session)) => create_qrcode_session(&state, &session).await,
+        Ok(None) => Err(anyhow::anyhow!("请先调用 /api/auth/guest-init 获取访客 Cookie")),
+        Err(e) => Err(e),
     };
     
-    let cookies = match cookies {
-        Some(c) => c,
-        None => {
-            return Json(CreateQrCodeResponse {
-                success: false,
-                qr_url: None,
-                qr_id: None,
-                code: None,
-                error: Some("请先调用 /api/auth/guest-init 获取访客 Cookie".to_string()),
-            }).into_response();
+    match result {
+        Ok(session) => {
+            state.qr_watcher.reset().await;
+            ApiResponse::ok(QrCodeInfo {
+                expires_in: Some(session.remaining_secs()),
+                qr_url: session.url,
+                qr_id: session.qr_id,
+                code: session.code,
+            })
         }
-    };
+        Err(e) => ApiResponse::error(e.to_string()),
+    }
+}
+
+/// Create a QR code and store it in the login session
+async fn create_qrcode_session(
+    state: &AppState,
+    login: &LoginSession,
+) -> anyhow::Result<QrCodeSession> {
+    let resp = api::login::create_qrcode(&login.guest_cookies).await?;
+    if !resp.success {
+        return Err(anyhow::anyhow!(resp.msg.unwrap_or_else(|| "QR code create failed".to_string())));
+    }
+    let data = resp.data.ok_or_else(|| anyhow::anyhow!("QR code data missing"))?;
     
-    match api::login::create_qrcode(&cookies).await {
-        Ok(resp) => {
-            if resp.success {
-                if let Some(data) = resp.data {
-                    // Store qr_id and code for polling
-                    {
-                        let mut info = state.qrcode_info.write().await;
-                        *info = Some((data.qr_id.clone(), data.code.clone()));
-                    }
-                    
-                    Json(CreateQrCodeResponse {
-                        success: true,
-                        qr_url: Some(data.url),
-                        qr_id: Some(data.qr_id),
-                        code: Some(data.code),
-                        error: None,
-                    }).into_response()
-                } else {
-                    Json(CreateQrCodeResponse {
-                        success: false,
-                        qr_url: None,
-                        qr_id: None,
-                        code: None,
-                        error: Some("QR code data missing".to_string()),
-                    }).into_response()
-                }
-            } else {
-                Json(CreateQrCodeResponse {
-                    success: false,
-                    qr_url: None,
-                    qr_id: None,
-                    code: None,
-                    error: resp.msg,
-                }).into_response()
-            }
-        }
+    // Store qr_id and code for polling
+    let session = QrCodeSession::new(&data);
+    if !state.login_sessions.set_qrcode(&login.token, Some(&session)).await? {
+        return Err(anyhow::anyhow!("登录会话已过期，请重新调用 /api/auth/guest-init"));
+    }
+    Ok(session)
+}
+
+/// Replace an expired QR code and report it to the client
+///
+/// On failure the expired code stays in the session so the next poll retries;
+/// an unattended login screen keeps recovering instead of stalling on a dead code.
+async fn regenerate_qrcode(state: &AppState, login: &LoginSession) -> ApiResponse<QrStatus> {
+    tracing::info!("QR code expired, creating a new one...");
+    match create_qrcode_session(state, login).await {
+        Ok(session) => ApiResponse::ok(QrStatus {
+            remaining_secs: Some(session.remaining_secs()),
+            regenerated: true,
+            qr_url: Some(session.url),
+            ..QrStatus::new(QrCodeState::Waiting)
+        }),
         Err(e) => {
-            Json(CreateQrCodeResponse {
-                success: false,
-                qr_url: None,
-                qr_id: None,
-                code: None,
-                error: Some(e.to_string()),
-            }).into_response()
+            tracing::warn!("Failed to regenerate QR code, retrying on next poll: {}", e);
+            ApiResponse::error(format!("二维码已过期，重新创建失败（下次轮询时重试）: {}", e))
+                .with_data(QrStatus::new(QrCodeState::Expired))
         }
     }
 }
 
 /// 轮询二维码登录状态
 ///
-/// - code_status=0: 等待扫码
-/// - code_status=1: 已扫码，等待确认
-/// - code_status=2: 登录成功
+/// - waiting (0): 等待扫码
+/// - scanned (1): 已扫码，等待确认
+/// - confirmed (2): 登录成功
+/// - expired (3): 二维码过期，服务端自动重新创建并返回新的 qr_url (regenerated=true)；
+///   重新创建失败时返回 expired，下次轮询自动重试
+/// - cancelled (4): 用户在手机端取消，可继续等待重新扫码
+/// - conflict (5): 二维码已被其他设备确认，需重新创建
+/// - error (-1): 请求失败
 #[utoipa::path(
     get,
     path = "/api/auth/qrcode/status",
     tag = "auth",
     summary = "轮询二维码状态",
-    description = "轮询直到 code_status=2 表示登录成功",
+    description = "轮询直到 state=confirmed 表示登录成功；remaining_secs 为二维码剩余有效秒数",
+    params(LoginSessionParams),
     responses(
-        (status = 200, description = "二维码状态", body = PollStatusResponse)
+        (status = 200, description = "二维码状态", body = ApiResponse<QrStatus>)
     )
 )]
 pub async fn poll_qrcode_status_handler(
     State(state): State<Arc<AppState>>,
+    Query(params): Query<LoginSessionParams>,
 ) -> impl IntoResponse {
-    // Get guest cookies
-    let cookies = {
-        let guard = state.guest_cookies.read().await;
-        guard.clone()
+    poll_qrcode(&state, params.session_token.as_deref()).await
+}
+
+/// 长轮询二维码状态
+///
+/// 保持连接直到状态变化（或到达 confirmed / conflict / error 终态）或等待超时，
+/// 替代客户端每隔几秒一次的短轮询。服务端共享一个状态观察器：无论多少请求在等待同一登录会话，
+/// 上游每 2 秒最多轮询一次；二维码过期时同样自动重新创建 (regenerated=true)。
+#[utoipa::path(
+    get,
+    path = "/api/auth/qrcode/status/wait",
+    tag = "auth",
+    summary = "长轮询二维码状态",
+    description = "传入上次看到的 `state`（不传则以当前状态为基准），状态变化、二维码重新生成或到达终态时立即返回 changed=true；超过 `wait`（默认 30s，最长 60s）返回当前状态与 changed=false",
+    params(QrStatusWaitParams),
+    responses(
+        (status = 200, description = "状态变化或超时", body = ApiResponse<QrStatusWait>)
+    )
+)]
+pub async fn wait_qrcode_status_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<QrStatusWaitParams>,
+) -> impl IntoResponse {
+    let started = std::time::Instant::now();
+    let wait = match api::notification::poll::parse_wait(params.wait.as_deref()) {
+        Ok(wait) => wait,
+        Err(e) => return wait_result(QrStatus::error(e.to_string()), false, 0),
     };
-    
-    let cookies = match cookies {
-        Some(c) => c,
-        None => {
-            return Json(PollStatusResponse {
-                success: false,
-                code_status: -1,
-                login_info: None,
-                new_cookies: None,
-                error: Some("请先调用 /api/auth/guest-init".to_string()),
-            }).into_response();
+    let deadline = started + wait;
+    let mut baseline = params.state;
+    let token = params.session_token.as_deref();
+    let key = token.unwrap_or_default();
+    loop {
+        let response = state.qr_watcher.latest(key, || poll_qrcode(&state, token)).await;
+        let (qr_state, regenerated) = response.data.as_ref()
+            .map_or((QrCodeState::Error, false), |status| (status.state, status.regenerated));
+        let baseline_state = *baseline.get_or_insert(qr_state);
+        let finished = matches!(qr_state, QrCodeState::Confirmed | QrCodeState::Conflict | QrCodeState::Error);
+        let changed = qr_state != baseline_state || regenerated || finished;
+        let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+        if changed || remaining.is_zero() {
+            return wait_result(response, changed, started.elapsed().as_millis() as u64);
         }
+        let next_poll = state.qr_watcher.until_next_poll(key).await.max(Duration::from_millis(100));
+        tokio::time::sleep(next_poll.min(remaining)).await;
+    }
+}
+
+/// Wrap a QR status in the qrcode/status/wait result
+fn wait_result(response: ApiResponse<QrStatus>, changed: bool, waited_ms: u64) -> ApiResponse<QrStatusWait> {
+    ApiResponse {
+        code: response.code,
+        success: response.success,
+        msg: response.msg,
+        data: response.data.map(|status| QrStatusWait { changed, waited_ms, status }),
+        request_id: response.request_id,
+    }
+}
+
+/// Poll the QR code of a login session once; saves credentials when the login is confirmed
+async fn poll_qrcode(state: &AppState, token: Option<&str>) -> ApiResponse<QrStatus> {
+    let login = match state.login_sessions.get(token).await {
+        Ok(Some(login)) => login,
+        Ok(None) => return QrStatus::error("请先调用 /api/auth/guest-init"),
+        Err(e) => return QrStatus::error(format!("读取登录会话失败: {}", e)),
     };
     
-    // Get qr_id and code
-    let qrcode_info = {
-        let guard = state.qrcode_info.read().await;
-        guard.clone()
-    };
-    
-    let (qr_id, code) = match qrcode_info {
-        Some(info) => info,
+    // Get current QR session
+    let session = match login.qrcode.clone() {
+        Some(session) => session,
         None => {
-            return Json(PollStatusResponse {
-                success: false,
-                code_status: -1,
-                login_info: None,
-                new_cookies: None,
-                error: Some("请先调用 /api/auth/qrcode/create".to_string()),
-            }).into_response();
+            return QrStatus::error("请先调用 /api/auth/qrcode/create");
         }
     };
     
-    match api::login::check_qrcode_status(&cookies, &qr_id, &code).await {
+    if session.is_expired() {
+        return regenerate_qrcode(state, &login).await;
+    }
+    
+    match api::login::check_qrcode_status(&login.guest_cookies, &session.qr_id, &session.code).await {
         Ok((resp, new_cookies)) => {
-            let code_status = resp.data
-                .as_ref()
-                .and_then(|d| d.code_status)
-                .unwrap_or(-1);
-            
+            let qr_state = resp.state();
             let login_info = resp.data.as_ref().and_then(|d| d.login_info.clone());
             
+            let error = match qr_state {
+                QrCodeState::Expired => {
+                    return regenerate_qrcode(state, &login).await;
+                }
+                QrCodeState::Conflict => {
+                    // The QR code can no longer be used by this session
+                    clear_qrcode(state, &login).await;
+                    Some("二维码已在其他设备确认，请重新创建".to_string())
+                }
+                QrCodeState::Cancelled => Some("用户已取消登录，可重新扫码".to_string()),
+                QrCodeState::Error => Some(resp.msg.clone().unwrap_or_else(|| "未知二维码状态".to_string())),
+                _ => None,
+            };
+            
             // If login success, use FULL synced cookies (NOT merged with guest cookies)
+            let mut save_error = None;
             // This prevents 461 errors caused by mixing guest and user cookies
-            if code_status == 2 {
+            if qr_state == QrCodeState::Confirmed {
+                clear_qrcode(state, &login).await;
+                
                 if let Some(ref new_c) = new_cookies {
                     // FULL REPLACEMENT: Use only the synced cookies, do NOT merge with guest cookies
                     let final_cookies = new_c.clone();
@@ -237,27 +299,421 @@ pub async fn poll_qrcode_status_handler(
                         }
                         Err(e) => {
                             tracing::error!("Failed to save credentials: {}", e);
+                            save_error = Some(format!("登录成功但凭证保存失败: {}", e));
                         }
                     }
                 }
             }
             
-            Json(PollStatusResponse {
-                success: resp.success,
-                code_status,
+            let status = QrStatus {
+                remaining_secs: Some(session.remaining_secs()),
+                qr_url: Some(session.url),
                 login_info,
                 new_cookies,
-                error: None,
-            }).into_response()
+                ..QrStatus::new(qr_state)
+            };
+            let success = resp.success && qr_state != QrCodeState::Error && save_error.is_none();
+            match save_error.or(error) {
+                Some(msg) if !success => ApiResponse::error(msg).with_data(status),
+                Some(msg) => ApiResponse::ok(status).with_msg(msg),
+                None if !success => ApiResponse::error(resp.msg.clone().unwrap_or_else(|| "二维码状态查询失败".to_string()))
+                    .with_data(status),
+                None => ApiResponse::ok(status),
+            }
         }
+        Err(e) => QrStatus::error(e.to_string()),
+    }
+}
+
+/// Drop the QR code of a finished login session
+async fn clear_qrcode(state: &AppState, login: &LoginSession) {
+    if let Err(e) = state.login_sessions.set_qrcode(&login.token, None).await {
+        tracing::warn!("Failed to clear QR code of login session: {}", e);
+    }
+}
+
+/// Interval of the server-side polling task behind qrcode/events
+const QR_EVENTS_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// 订阅二维码登录进度 (SSE)
+///
+/// 服务端每 2 秒轮询一次二维码状态，状态变化时推送事件，无需客户端轮询 qrcode/status：
+///
+/// - `waiting` / `scanned` / `cancelled`: 状态变化，data 为 `ApiResponse<QrStatus>`（不含 Cookie）
+/// - `regenerated`: 二维码过期后已自动重新创建，需重新渲染 qr_url
+/// - `expired`: 二维码已过期但重新创建失败，流保持打开并在下次轮询时重试
+/// - `confirmed`: 手机端已确认
+/// - `success`: 凭证已保存，data 为 `{"user_id": "..."}`，随后关闭流
+/// - `error` / `timeout`: 登录失败或超过 `XHS_QR_EVENTS_MAX_SECS`（默认 600 秒，0 为不限）未完成，随后关闭流
+#[utoipa::path(
+    get,
+    path = "/api/auth/qrcode/events",
+    tag = "auth",
+    summary = "订阅二维码登录进度 (SSE)",
+    description = "需先调用 qrcode/create。返回 text/event-stream，事件: waiting / scanned / cancelled / regenerated / expired / confirmed / success / error / timeout；二维码过期时自动重新创建并推送 regenerated（重新创建失败时推送 expired 并持续重试）；success 事件携带保存的 user_id 后结束",
+    params(LoginSessionParams),
+    responses(
+        (status = 200, description = "事件流，每个事件 data 为 ApiResponse<QrStatus>（success 事件为 {user_id}）", content_type = "text/event-stream", body = ApiResponse<QrStatus>)
+    )
+)]
+pub async fn qrcode_events_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<LoginSessionParams>,
+) -> impl IntoResponse {
+    let (tx, rx) = tokio::sync::mpsc::channel::<Event>(8);
+    let request_id = crate::response::current_request_id();
+    tokio::spawn(async move {
+        let deadline = crate::config::qr_events_max_duration().map(|max| tokio::time::Instant::now() + max);
+        let mut last_state = None;
+        loop {
+            let mut response = poll_qrcode(&state, params.session_token.as_deref()).await;
+            response.request_id = request_id.clone();
+            let Some(status) = response.data.as_mut() else {
+                return;
+            };
+            status.new_cookies = None;
+            let (qr_state, regenerated) = (status.state, status.regenerated);
+            
+            let name = match qr_state {
+                QrCodeState::Error | QrCodeState::Conflict => "error",
+                _ if regenerated => "regenerated",
+                QrCodeState::Confirmed if !response.success => "error",
+                other => {
+                    if last_state == Some(other) {
+                        ""
+                    } else {
+                        qr_event_name(other)
+                    }
+                }
+            };
+            last_state = Some(qr_state);
+            
+            if !name.is_empty() {
+                let event = Event::default().event(name).json_data(&response).unwrap_or_default();
+                if tx.send(event).await.is_err() {
+                    // Client went away
+                    return;
+                }
+            }
+            
+            match name {
+                "error" => return,
+                "confirmed" => {
+                    let user_id = response.data
+                        .and_then(|status| status.login_info)
+                        .and_then(|info| info.user_id)
+                        .unwrap_or_else(|| "unknown".to_string());
+                    let event = Event::default()
+                        .event("success")
+                        .json_data(serde_json::json!({ "user_id": user_id }))
+                        .unwrap_or_default();
+                    let _ = tx.send(event).await;
+                    return;
+                }
+                _ => {}
+            }
+            
+            if deadline.is_some_and(|deadline| tokio::time::Instant::now() >= deadline) {
+                let _ = tx.send(Event::default().event("timeout").data("二维码登录超时")).await;
+                return;
+            }
+            tokio::time::sleep(QR_EVENTS_POLL_INTERVAL).await;
+        }
+    });
+    
+    let stream = futures_util::stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|event| (Ok::<_, std::convert::Infallible>(event), rx))
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+fn qr_event_name(state: QrCodeState) -> &'static str {
+    match state {
+        QrCodeState::Waiting => "waiting",
+        QrCodeState::Scanned => "scanned",
+        QrCodeState::Confirmed => "confirmed",
+        QrCodeState::Expired => "expired",
+        QrCodeState::Cancelled => "cancelled",
+        QrCodeState::Conflict | QrCodeState::Error => "error",
+    }
+}
+
+/// 导入浏览器 Cookie
+///
+/// 接收浏览器中复制的 Cookie 字符串（或 JSON 键值对），
+/// 调用 /user/me 校验登录态后保存为当前凭据，可跳过扫码登录流程
+#[utoipa::path(
+    post,
+    path = "/api/auth/import-cookies",
+    tag = "auth",
+    summary = "导入 Cookie",
+    description = "导入已有的浏览器登录 Cookie（字符串或 JSON 对象），校验通过后保存到 cookie.json",
+    request_body = ImportCookiesRequest,
+    responses(
+        (status = 200, description = "导入结果", body = ApiResponse<ImportedAccount>)
+    )
+)]
+pub async fn import_cookies_handler(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<ImportCookiesRequest>,
+) -> impl IntoResponse {
+    let cookies = req.cookies.into_cookies();
+    
+    if !cookies.contains_key("web_session") {
+        return ApiResponse::error("Cookie 中缺少 web_session，请确认已在浏览器中登录");
+    }
+    
+    tracing::info!("Importing {} cookies, validating via /user/me...", cookies.len());
+    
+    let creds = crate::auth::credentials::UserCredentials::new(
+        "unknown".to_string(),
+        cookies,
+        None, // No x_s_common in pure algo mode
+    );
+    
+    let me = match api::user::get_user_with_credentials(&state.api, &creds).await {
+        Ok(me) => me,
         Err(e) => {
-            Json(PollStatusResponse {
-                success: false,
-                code_status: -1,
-                login_info: None,
-                new_cookies: None,
-                error: Some(e.to_string()),
-            }).into_response()
+            tracing::warn!("Imported cookies failed validation: {}", e);
+            return ApiResponse::error(format!("Cookie 校验失败: {}", e));
         }
+    };
+    
+    if me.data.guest {
+        return ApiResponse::error("Cookie 不是有效的登录态（游客会话）");
+    }
+    
+    let creds = crate::auth::credentials::UserCredentials {
+        user_id: me.data.user_id.clone(),
+        ..creds
+    };
+    
+    let account = ImportedAccount { user_id: me.data.user_id, nickname: me.data.nickname };
+    if let Err(e) = state.auth.save_credentials(&creds).await {
+        tracing::error!("Failed to save imported credentials: {}", e);
+        return ApiResponse::error(e.to_string()).with_data(account);
     }
+    
+    tracing::info!("Imported cookies saved for user: {}", creds.user_id);
+    ApiResponse::ok(account)
+}
+
+/// 导出当前登录凭据
+///
+/// 将当前 Cookie 导出为其他工具可用的格式：
+/// - header: Cookie 请求头字符串
+/// - netscape: cookies.txt (curl / yt-dlp 等)
+/// - playwright: storage_state JSON
+#[utoipa::path(
+    get,
+    path = "/api/auth/export",
+    tag = "auth",
+    summary = "导出凭据",
+    description = "导出当前登录 Cookie，支持 header / netscape / playwright 三种格式。masked=true 时遮蔽 Cookie 值，便于安全分享",
+    params(ExportCredentialsParams),
+    responses(
+        (status = 200, description = "导出结果", body = ApiResponse<ExportedCredentials>)
+    )
+)]
+pub async fn export_credentials_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<ExportCredentialsParams>,
+) -> impl IntoResponse {
+    let creds = match state.auth.try_get_credentials().await {
+        Ok(Some(creds)) => creds,
+        Ok(None) => return ApiResponse::error("Not logged in. Please login first."),
+        Err(e) => return ApiResponse::error(e.to_string()),
+    };
+    
+    let content = crate::auth::export::export_credentials(&creds, params.format, params.masked);
+    
+    ApiResponse::ok(ExportedCredentials {
+        format: params.format.as_str().to_string(),
+        masked: params.masked,
+        user_id: creds.user_id,
+        content,
+    })
+}
+
+/// 凭据健康状态
+///
+/// 返回用户账号与创作者账号的有效性、凭据年龄、最近成功请求时间、
+/// 近一小时 406/461 次数，以及后台定期校验结果
+#[utoipa::path(
+    get,
+    path = "/api/auth/health",
+    tag = "auth",
+    summary = "凭据健康状态",
+    description = "查看各账号凭据是否需要重新登录（后台每 XHS_HEALTH_CHECK_INTERVAL_SECS 秒校验一次，默认 600）",
+    responses(
+        (status = 200, description = "各账号健康状态", body = ApiResponse<Vec<crate::auth::health::AccountHealth>>)
+    )
+)]
+pub async fn credential_health_handler(
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let user_creds = state.auth.try_get_credentials().await.ok().flatten();
+    let creator_creds = state.creator_auth.try_get_credentials().await.ok().flatten();
+    
+    ApiResponse::ok(vec![
+        state.auth.health().snapshot("user", user_creds.as_ref()),
+        state.creator_auth.health().snapshot("creator", creator_creds.as_ref()),
+    ])
+}
+
+/// 登录状态查询参数
+#[derive(Debug, Clone, Default, serde::Deserialize, utoipa::IntoParams)]
+pub struct AuthStatusParams {
+    /// 为 true 时立即请求 user/me 与创作者信息接口校验凭据（默认只返回最近一次校验结果）
+    #[serde(default)]
+    pub validate: bool,
+}
+
+/// 单个账号的登录状态
+#[derive(Debug, serde::Serialize, utoipa::ToSchema)]
+pub struct AuthSessionStatus {
+    /// 已保存且有效的凭据
+    pub logged_in: bool,
+    pub user_id: Option<String>,
+    /// 凭据距上次更新的秒数
+    pub cookie_age_secs: Option<i64>,
+    /// 最近一次校验结果（后台定期校验或 validate=true）
+    pub last_validation: Option<crate::auth::health::HealthCheck>,
+    /// 建议重新登录
+    pub needs_relogin: bool,
+}
+
+impl From<crate::auth::health::AccountHealth> for AuthSessionStatus {
+    fn from(health: crate::auth::health::AccountHealth) -> Self {
+        Self {
+            logged_in: health.logged_in,
+            user_id: health.user_id,
+            cookie_age_secs: health.age_secs,
+            last_validation: health.last_check,
+            needs_relogin: health.needs_relogin,
+        }
+    }
+}
+
+/// 两个账号的登录状态
+#[derive(Debug, serde::Serialize, utoipa::ToSchema)]
+#[schema(example = json!({
+    "user": {
+        "logged_in": true,
+        "user_id": "5ff0e6410000000001008400",
+        "cookie_age_secs": 86400,
+        "last_validation": {"checked_at": "2026-01-01T08:00:00Z", "ok": true, "error": null},
+        "needs_relogin": false
+    },
+    "creator": {
+        "logged_in": false,
+        "user_id": null,
+        "cookie_age_secs": null,
+        "last_validation": null,
+        "needs_relogin": true
+    }
+}))]
+pub struct AuthStatus {
+    /// 主站账号 (cookie.json)
+    pub user: AuthSessionStatus,
+    /// 创作者中心账号 (cookie-creator.json)
+    pub creator: AuthSessionStatus,
+}
+
+/// 登录状态
+///
+/// 汇总主站与创作者中心两个账号的登录状态，无需分别请求 /api/user/me 与创作者信息接口
+#[utoipa::path(
+    get,
+    path = "/api/auth/status",
+    tag = "auth",
+    summary = "登录状态",
+    description = "返回主站与创作者中心账号是否已登录、user_id、凭据年龄与最近一次校验结果。默认不请求上游（校验结果来自后台定期校验）；`validate=true` 时立即校验两个账号并返回最新结果",
+    params(AuthStatusParams),
+    responses(
+        (status = 200, description = "两个账号的登录状态", body = ApiResponse<AuthStatus>)
+    )
+)]
+pub async fn auth_status_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<AuthStatusParams>,
+) -> impl IntoResponse {
+    if params.validate {
+        tokio::join!(
+            crate::auth::health::check_user(&state),
+            crate::auth::health::check_creator(&state),
+        );
+    }
+    let user_creds = state.auth.try_get_credentials().await.ok().flatten();
+    let creator_creds = state.creator_auth.try_get_credentials().await.ok().flatten();
+    
+    ApiResponse::ok(AuthStatus {
+        user: state.auth.health().snapshot("user", user_creds.as_ref()).into(),
+        creator: state.creator_auth.health().snapshot("creator", creator_creds.as_ref()).into(),
+    })
+}
+
+/// 验证码挑战状态
+#[derive(Debug, serde::Serialize, utoipa::ToSchema)]
+pub struct VerificationStatus {
+    /// 待完成的挑战（按出现时间排序），为空表示没有被暂停的账号
+    pub pending: Vec<crate::api::verification::VerificationChallenge>,
+    /// 本次手动解除的挑战数（仅 resolve）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolved: Option<usize>,
+}
+
+/// 解除验证码挑战参数
+#[derive(Debug, Clone, Default, serde::Deserialize, utoipa::IntoParams)]
+pub struct ResolveVerificationParams {
+    /// 只解除该账号的挑战（默认全部）
+    #[serde(default)]
+    pub user_id: Option<String>,
+}
+
+/// 待完成的验证码挑战
+///
+/// 上游要求滑块 / 图形验证时，对应账号的请求暂停（错误类型 verification_required），
+/// 在已登录该账号的浏览器中打开 `url` 完成验证并更新 Cookie 后自动恢复
+#[utoipa::path(
+    get,
+    path = "/api/auth/verification",
+    tag = "auth",
+    summary = "待完成的验证码挑战",
+    description = "列出被要求验证的账号与验证页链接。出现新挑战时同时推送 webhook（`X-Xhs-Event: verification_required`，地址为 XHS_WEBHOOK_URLS），供人工或 Playwright Agent 处理。完成验证后通过扫码登录或 import-cookies 更新 Cookie，下一次请求即自动恢复",
+    responses(
+        (status = 200, description = "待完成的挑战", body = ApiResponse<VerificationStatus>)
+    )
+)]
+pub async fn verification_handler(
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    ApiResponse::ok(VerificationStatus {
+        pending: state.api.verification().pending(),
+        resolved: None,
+    })
+}
+
+/// 手动解除验证码挑战
+///
+/// 验证已完成但 Cookie 未变化时使用，恢复被暂停的请求
+#[utoipa::path(
+    post,
+    path = "/api/auth/verification/resolve",
+    tag = "auth",
+    summary = "手动解除验证码挑战",
+    description = "解除指定账号（或全部）的挑战并恢复请求；若验证实际未完成，下一次请求会再次触发挑战",
+    params(ResolveVerificationParams),
+    responses(
+        (status = 200, description = "解除后仍待完成的挑战", body = ApiResponse<VerificationStatus>)
+    )
+)]
+pub async fn resolve_verification_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<ResolveVerificationParams>,
+) -> impl IntoResponse {
+    let resolved = state.api.verification().resolve(params.user_id.as_deref());
+    ApiResponse::ok(VerificationStatus {
+        pending: state.api.verification().pending(),
+        resolved: Some(resolved),
+    })
 }
@@ -0,0 +1,123 @@
+//! Cluster Coordination HTTP Handlers
+//!
+//! Handles: cluster/status, cluster/tasks
+
+use axum::{
+    extract::State,
+    response::IntoResponse,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::coordination::{CrawlTask, InstanceInfo};
+use crate::response::ApiResponse;
+use crate::server::AppState;
+
+// ============================================================================
+// Request / Response Models
+// ============================================================================
+
+/// 集群状态
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct ClusterStatus {
+    /// "cluster" 或 "standalone"
+    pub mode: String,
+    /// 当前实例 ID (集群模式)
+    pub instance_id: Option<String>,
+    /// 存活实例列表
+    pub instances: Vec<InstanceInfo>,
+    /// 任务列表
+    pub tasks: Vec<CrawlTask>,
+}
+
+/// 入队请求
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+#[schema(example = json!({
+    "key": "keyword:露营",
+    "kind": "keyword_monitor",
+    "payload": {"keyword": "露营"},
+    "interval_secs": 600
+}))]
+pub struct EnqueueTaskRequest {
+    /// 任务唯一键
+    pub key: String,
+    /// 任务类型
+    pub kind: String,
+    /// 任务参数
+    #[serde(default)]
+    pub payload: serde_json::Value,
+    /// 执行间隔（秒），0 表示一次性任务
+    #[serde(default)]
+    pub interval_secs: u64,
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// 集群状态
+///
+/// 查看协调队列中的存活实例与任务分片情况
+#[utoipa::path(
+    get,
+    path = "/api/cluster/status",
+    tag = "Cluster",
+    summary = "集群状态",
+    description = "返回当前实例 ID、存活实例和任务租约情况。未配置 XHS_COORD_MONGO_URI 时为 standalone 模式",
+    responses(
+        (status = 200, description = "集群状态", body = ApiResponse<ClusterStatus>)
+    )
+)]
+pub async fn cluster_status_handler(
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let Some(queue) = state.coordinator.as_ref() else {
+        return ApiResponse::ok(ClusterStatus {
+            mode: "standalone".to_string(),
+            instance_id: None,
+            instances: vec![],
+            tasks: vec![],
+        });
+    };
+    
+    let result = async {
+        let instances = queue.list_instances().await?;
+        let tasks = queue.list_tasks().await?;
+        anyhow::Ok((instances, tasks))
+    }.await;
+    
+    ApiResponse::from_result(result.map(|(instances, tasks)| ClusterStatus {
+        mode: "cluster".to_string(),
+        instance_id: Some(queue.instance_id().to_string()),
+        instances,
+        tasks,
+    }))
+}
+
+/// 提交抓取任务
+///
+/// 将任务加入共享队列，由集群中任一实例领取执行（按 key 去重）
+#[utoipa::path(
+    post,
+    path = "/api/cluster/tasks",
+    tag = "Cluster",
+    summary = "提交抓取任务",
+    request_body = EnqueueTaskRequest,
+    responses(
+        (status = 200, description = "入队结果")
+    )
+)]
+pub async fn cluster_enqueue_handler(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<EnqueueTaskRequest>,
+) -> impl IntoResponse {
+    let Some(queue) = state.coordinator.as_ref() else {
+        return ApiResponse::<()>::error("Coordination disabled. Set XHS_COORD_MONGO_URI to enable cluster mode.").into_response();
+    };
+    
+    match queue.enqueue(&req.key, &req.kind, req.payload, req.interval_secs).await {
+        Ok(()) => ApiResponse::done(format!("Task {} enqueued", req.key)).into_response(),
+        Err(e) => ApiResponse::<()>::error(e.to_string()).into_response(),
+    }
+}
@@ -4,9 +4,10 @@
 
 use axum::{Json, response::IntoResponse, extract::State};
 use std::sync::Arc;
+use crate::response::ApiResponse;
 use crate::server::AppState;
 use crate::api::creator::{auth, models::{CreatorQrcodeCreateRequest, CreatorQrcodeStatusRequest}};
-use crate::api::login::{GuestInitResponse, CreateQrCodeResponse};
+use crate::api::login::{GuestSession, QrCodeInfo};
 
 /// 1. 初始化创作者访客会话
 ///
@@ -16,25 +17,12 @@ use crate::api::login::{GuestInitResponse, CreateQrCodeResponse};
     path = "/api/creator/auth/guest-init",
     tag = "Creator",
     responses(
-        (status = 200, description = "Guest session initialized", body = GuestInitResponse)
+        (status = 200, description = "Guest session initialized", body = ApiResponse<GuestSession>)
     )
 )]
 pub async fn creator_guest_init_handler() -> impl IntoResponse {
-    match auth::fetch_creator_guest_cookies().await {
-        Ok(cookies) => Json(GuestInitResponse {
-            success: true,
-            cookies: Some(cookies),
-            error: None,
-        }),
-        Err(e) => {
-            let resp = GuestInitResponse {
-                success: false,
-                cookies: None,
-                error: Some(e.to_string()),
-            };
-            Json(resp)
-        },
-    }
+    ApiResponse::from_result(auth::fetch_creator_guest_cookies().await
+        .map(|cookies| GuestSession { session_token: None, cookies }))
 }
 
 /// 2. 申请创作者登录二维码
@@ -46,33 +34,25 @@ pub async fn creator_guest_init_handler() -> impl IntoResponse {
     tag = "Creator",
     request_body = CreatorQrcodeCreateRequest, 
     responses(
-        (status = 200, description = "QR Code created", body = CreateQrCodeResponse)
+        (status = 200, description = "QR Code created", body = ApiResponse<QrCodeInfo>)
     )
 )]
 pub async fn creator_create_qrcode_handler(
     Json(payload): Json<CreatorQrcodeCreateRequest>
 ) -> impl IntoResponse {
     match auth::create_creator_qrcode(&payload.cookies).await {
-        Ok(response) => {
-            let resp = CreateQrCodeResponse {
-                success: response.success,
-                qr_url: response.data.as_ref().map(|d| d.url.clone()),
-                qr_id: response.data.as_ref().map(|d| d.qr_id.clone()),
-                code: response.data.as_ref().map(|d| d.code.clone()),
-                error: response.msg,
-            };
-            Json(resp)
+        Ok(response) => match response.data.filter(|_| response.success) {
+            Some(data) => ApiResponse::ok(QrCodeInfo {
+                qr_url: data.url,
+                qr_id: data.qr_id,
+                code: data.code,
+                expires_in: None,
+            }),
+            None => ApiResponse::error(response.msg.unwrap_or_else(|| "QR code create failed".to_string())),
         },
         Err(e) => {
             tracing::error!("Create QR failed: {}", e);
-            let resp = CreateQrCodeResponse {
-                success: false,
-                qr_url: None,
-                qr_id: None,
-                code: None,
-                error: Some(e.to_string()),
-            };
-            Json(resp)
+            ApiResponse::error(e.to_string())
         }
     }
 }
@@ -120,12 +100,9 @@ pub async fn creator_check_qrcode_status(
                     obj.insert("new_cookies".to_string(), serde_json::to_value(nc).unwrap_or_default());
                 }
             }
-            Json(json)
+            Json(json).into_response()
         },
-        Err(e) => Json(serde_json::json!({
-            "success": false,
-            "error": e.to_string()
-        })),
+        Err(e) => ApiResponse::<()>::error(e.to_string()).into_response(),
     }
 }
 
@@ -140,38 +117,16 @@ use crate::api::creator::{info, models::{CreatorUserInfo, CreatorHomeInfo}};
     path = "/api/galaxy/user/info",
     tag = "Creator",
     responses(
-        (status = 200, description = "User info retrieved", body = CreatorUserInfo)
+        (status = 200, description = "User info retrieved", body = ApiResponse<CreatorUserInfo>)
     )
 )]
 pub async fn creator_user_info_handler(
     State(state): State<Arc<AppState>>,
 ) -> impl IntoResponse {
-    // 1. Get credentials from creator_auth
-    let cookies_result = state.creator_auth.try_get_credentials().await;
-    
-    let cookies = match cookies_result {
-        Ok(Some(creds)) => creds.cookies.clone(),
-        Ok(None) => return Json(serde_json::json!({
-            "success": false,
-            "error": "Not logged in (Creator). Please login first."
-        })).into_response(),
-        Err(e) => return Json(serde_json::json!({
-            "success": false,
-            "error": e.to_string()
-        })).into_response(),
-    };
-    
-    // 2. Call API
-    match info::get_creator_user_info(&cookies).await {
-        Ok(info) => Json(serde_json::json!({
-            "success": true,
-            "data": info
-        })).into_response(),
-        Err(e) => Json(serde_json::json!({
-            "success": false, 
-            "error": e.to_string()
-        })).into_response(),
-    }
+    ApiResponse::from_result(match creator_cookies(&state).await {
+        Ok(cookies) => info::get_creator_user_info(&cookies).await.map_err(|e| e.to_string()),
+        Err(e) => Err(e),
+    })
 }
 
 /// 5. 获取创作者主页信息
@@ -182,36 +137,503 @@ pub async fn creator_user_info_handler(
     path = "/api/galaxy/creator/home/personal_info",
     tag = "Creator",
     responses(
-        (status = 200, description = "Home info retrieved", body = CreatorHomeInfo)
+        (status = 200, description = "Home info retrieved", body = ApiResponse<CreatorHomeInfo>)
     )
 )]
 pub async fn creator_home_info_handler(
     State(state): State<Arc<AppState>>,
 ) -> impl IntoResponse {
-    // 1. Get credentials from creator_auth
-    let cookies_result = state.creator_auth.try_get_credentials().await;
-    
-    let cookies = match cookies_result {
-        Ok(Some(creds)) => creds.cookies.clone(),
-        Ok(None) => return Json(serde_json::json!({
-            "success": false,
-            "error": "Not logged in (Creator). Please login first."
-        })).into_response(),
-        Err(e) => return Json(serde_json::json!({
-            "success": false,
-            "error": e.to_string()
-        })).into_response(),
+    ApiResponse::from_result(match creator_cookies(&state).await {
+        Ok(cookies) => info::get_creator_home_info(&cookies).await.map_err(|e| e.to_string()),
+        Err(e) => Err(e),
+    })
+}
+
+/// Load the stored Creator cookies, or a user-facing error
+async fn creator_cookies(state: &AppState) -> Result<std::collections::HashMap<String, String>, String> {
+    match state.creator_auth.try_get_credentials().await {
+        Ok(Some(creds)) => Ok(creds.cookies),
+        Ok(None) => Err("Not logged in (Creator). Please login first.".to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+use crate::api::creator::publish::{self, PublishImageRequest, PublishedNote};
+
+/// 6. 发布图文笔记
+///
+/// 上传图片（下载根目录内的文件或 base64）并发布图文笔记，话题按名称自动匹配
+#[utoipa::path(
+    post,
+    path = "/api/creator/publish/image",
+    tag = "Creator",
+    request_body = PublishImageRequest,
+    responses(
+        (status = 200, description = "Publish result", body = ApiResponse<PublishedNote>)
+    )
+)]
+pub async fn creator_publish_image_handler(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<PublishImageRequest>,
+) -> impl IntoResponse {
+    let cookies = match creator_cookies(&state).await {
+        Ok(cookies) => cookies,
+        Err(e) => return ApiResponse::error(e),
     };
-    
-    // 2. Call API
-    match info::get_creator_home_info(&cookies).await {
-        Ok(info) => Json(serde_json::json!({
-            "success": true,
-            "data": info
-        })).into_response(),
-        Err(e) => Json(serde_json::json!({
-            "success": false, 
-            "error": e.to_string()
-        })).into_response(),
+
+    let mut images = Vec::with_capacity(req.images.len());
+    for (index, source) in req.images.iter().enumerate() {
+        match publish::load_image(source, |path| state.file_signer.resolve(path)).await {
+            Ok(image) => images.push(image),
+            Err(e) => return ApiResponse::error(format!("Image {}: {}", index + 1, e)),
+        }
     }
+
+    match publish::publish_image_note(&cookies, &req, images).await {
+        Ok(published) => ApiResponse::ok(published),
+        Err(e) => {
+            tracing::error!("Publish image note failed: {}", e);
+            ApiResponse::error(e.to_string())
+        }
+    }
+}
+
+use crate::api::creator::publish::jobs::PublishProgress;
+use crate::api::creator::publish::video::{self, PublishVideoRequest, PublishVideoJob};
+
+/// 7. 发布视频笔记
+///
+/// 分片上传下载根目录内的 MP4 文件并发布视频笔记，封面可上传图片或按时间点选帧。
+/// 上传在后台进行，立即返回 job_id，通过 `/api/creator/publish/jobs/{job_id}` 查询进度
+#[utoipa::path(
+    post,
+    path = "/api/creator/publish/video",
+    tag = "Creator",
+    request_body = PublishVideoRequest,
+    responses(
+        (status = 200, description = "Publish job started", body = ApiResponse<PublishVideoJob>)
+    )
+)]
+pub async fn creator_publish_video_handler(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<PublishVideoRequest>,
+) -> impl IntoResponse {
+    let cookies = match creator_cookies(&state).await {
+        Ok(cookies) => cookies,
+        Err(e) => return ApiResponse::error(e),
+    };
+    let video_path = match state.file_signer.resolve(&req.video_path) {
+        Ok(path) => path,
+        Err(e) => return ApiResponse::error(format!("Video: {}", e)),
+    };
+    let cover = match &req.cover {
+        Some(source) => match publish::load_image(source, |path| state.file_signer.resolve(path)).await {
+            Ok(image) => Some(image),
+            Err(e) => return ApiResponse::error(format!("Cover: {}", e)),
+        },
+        None => None,
+    };
+    let total_bytes = match tokio::fs::metadata(&video_path).await {
+        Ok(meta) => meta.len(),
+        Err(e) => return ApiResponse::error(e.to_string()),
+    };
+
+    let job = state.publish_jobs.create(total_bytes);
+    let job_id = job.snapshot().job_id;
+    tokio::spawn(async move {
+        let result = video::publish_video_note(&cookies, &req, video_path, cover, &job).await;
+        if let Err(ref e) = result {
+            tracing::error!("Publish video note failed: {}", e);
+        }
+        job.finish(result);
+    });
+
+    ApiResponse::ok(PublishVideoJob { job_id })
+}
+
+/// 8. 查询发布任务进度
+#[utoipa::path(
+    get,
+    path = "/api/creator/publish/jobs/{job_id}",
+    tag = "Creator",
+    params(
+        ("job_id" = String, Path, description = "发布任务 ID")
+    ),
+    responses(
+        (status = 200, description = "Job progress", body = ApiResponse<PublishProgress>),
+        (status = 404, description = "Job not found", body = ApiResponse<PublishProgress>)
+    )
+)]
+pub async fn creator_publish_job_handler(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(job_id): axum::extract::Path<String>,
+) -> impl IntoResponse {
+    match state.publish_jobs.get(&job_id) {
+        Some(progress) => (axum::http::StatusCode::OK, ApiResponse::ok(progress)),
+        None => (axum::http::StatusCode::NOT_FOUND, ApiResponse::error("Publish job not found")),
+    }
+}
+
+use crate::api::creator::drafts::{self, Draft, DraftList, DraftListParams, DraftUpdate};
+
+/// 9. 草稿列表
+#[utoipa::path(
+    get,
+    path = "/api/creator/drafts",
+    tag = "Creator",
+    params(DraftListParams),
+    responses(
+        (status = 200, description = "Drafts", body = ApiResponse<DraftList>)
+    )
+)]
+pub async fn creator_drafts_handler(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Query(params): axum::extract::Query<DraftListParams>,
+) -> impl IntoResponse {
+    let result = match creator_cookies(&state).await {
+        Ok(cookies) => drafts::list_drafts(&cookies, &params).await.map_err(|e| e.to_string()),
+        Err(e) => Err(e),
+    };
+    ApiResponse::from_result(result.map(|(drafts, has_more)| DraftList { drafts, has_more }))
+}
+
+/// 10. 草稿详情
+#[utoipa::path(
+    get,
+    path = "/api/creator/drafts/{draft_id}",
+    tag = "Creator",
+    params(
+        ("draft_id" = String, Path, description = "草稿 ID")
+    ),
+    responses(
+        (status = 200, description = "Draft", body = ApiResponse<Draft>)
+    )
+)]
+pub async fn creator_draft_handler(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(draft_id): axum::extract::Path<String>,
+) -> impl IntoResponse {
+    let result = match creator_cookies(&state).await {
+        Ok(cookies) => drafts::get_draft(&cookies, &draft_id).await.map_err(|e| e.to_string()),
+        Err(e) => Err(e),
+    };
+    ApiResponse::from_result(result)
+}
+
+/// 11. 更新草稿
+///
+/// 只更新请求中出现的字段（标题 / 正文 / 图片顺序）
+#[utoipa::path(
+    put,
+    path = "/api/creator/drafts/{draft_id}",
+    tag = "Creator",
+    params(
+        ("draft_id" = String, Path, description = "草稿 ID")
+    ),
+    request_body = DraftUpdate,
+    responses(
+        (status = 200, description = "Update result")
+    )
+)]
+pub async fn creator_update_draft_handler(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(draft_id): axum::extract::Path<String>,
+    Json(update): Json<DraftUpdate>,
+) -> impl IntoResponse {
+    let result = match creator_cookies(&state).await {
+        Ok(cookies) => drafts::update_draft(&cookies, &draft_id, &update).await.map_err(|e| e.to_string()),
+        Err(e) => Err(e),
+    };
+    ApiResponse::from_result(result)
+}
+
+/// 12. 删除草稿
+#[utoipa::path(
+    delete,
+    path = "/api/creator/drafts/{draft_id}",
+    tag = "Creator",
+    params(
+        ("draft_id" = String, Path, description = "草稿 ID")
+    ),
+    responses(
+        (status = 200, description = "Delete result")
+    )
+)]
+pub async fn creator_delete_draft_handler(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(draft_id): axum::extract::Path<String>,
+) -> impl IntoResponse {
+    let result = match creator_cookies(&state).await {
+        Ok(cookies) => drafts::delete_draft(&cookies, &draft_id).await.map_err(|e| e.to_string()),
+        Err(e) => Err(e),
+    };
+    ApiResponse::from_result(result)
+}
+
+use crate::api::creator::fans::{self, FansAnalytics, FansPortraitParams};
+
+/// 13. 粉丝画像
+///
+/// 粉丝总量 / 新增 / 流失，以及性别、年龄、地域、活跃时段、兴趣分布
+#[utoipa::path(
+    get,
+    path = "/api/creator/fans/portrait",
+    tag = "Creator",
+    params(FansPortraitParams),
+    responses(
+        (status = 200, description = "Fans analytics", body = ApiResponse<FansAnalytics>)
+    )
+)]
+pub async fn creator_fans_portrait_handler(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Query(params): axum::extract::Query<FansPortraitParams>,
+) -> impl IntoResponse {
+    let cookies = match creator_cookies(&state).await {
+        Ok(cookies) => cookies,
+        Err(e) => return ApiResponse::error(e),
+    };
+
+    let (overview, portrait) = tokio::join!(
+        fans::get_fans_overview(&cookies, params.days),
+        fans::get_fans_portrait(&cookies),
+    );
+    match (overview, portrait) {
+        (Ok(overview), Ok(portrait)) => ApiResponse::ok(FansAnalytics {
+            overview: Some(overview),
+            portrait: Some(portrait),
+        }),
+        (overview, portrait) => {
+            let error = [overview.as_ref().err(), portrait.as_ref().err()]
+                .into_iter()
+                .flatten()
+                .map(|e| e.to_string())
+                .collect::<Vec<_>>()
+                .join("; ");
+            ApiResponse::error(error).with_data(FansAnalytics {
+                overview: overview.ok(),
+                portrait: portrait.ok(),
+            })
+        }
+    }
+}
+
+use crate::api::creator::notes::{self, CreatorNotesData, CreatorNotesParams};
+
+/// 14. 笔记管理列表
+///
+/// 创作者已发布笔记，含审核状态、可见性与互动数据，支持按状态筛选与分页
+#[utoipa::path(
+    get,
+    path = "/api/creator/notes",
+    tag = "Creator",
+    params(CreatorNotesParams),
+    responses(
+        (status = 200, description = "Creator notes", body = ApiResponse<CreatorNotesData>)
+    )
+)]
+pub async fn creator_notes_handler(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Query(params): axum::extract::Query<CreatorNotesParams>,
+) -> impl IntoResponse {
+    let result = match creator_cookies(&state).await {
+        Ok(cookies) => notes::get_creator_notes(&cookies, &params).await.map_err(|e| e.to_string()),
+        Err(e) => Err(e),
+    };
+    ApiResponse::from_result(result)
+}
+
+use crate::api::creator::comments::{self, CreatorCommentsData, CreatorCommentsParams, ReplyCommentRequest,
+    HideCommentRequest, DeleteCommentParams, CommentMutation};
+
+fn comment_result(result: Result<Option<String>, String>) -> ApiResponse<CommentMutation> {
+    ApiResponse::from_result(result.map(|comment_id| CommentMutation { comment_id }))
+}
+
+/// 15. 评论管理列表
+///
+/// 创作者所有笔记（或指定笔记）下的评论
+#[utoipa::path(
+    get,
+    path = "/api/creator/comments",
+    tag = "Creator",
+    params(CreatorCommentsParams),
+    responses(
+        (status = 200, description = "Comments", body = ApiResponse<CreatorCommentsData>)
+    )
+)]
+pub async fn creator_comments_handler(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Query(params): axum::extract::Query<CreatorCommentsParams>,
+) -> impl IntoResponse {
+    let result = match creator_cookies(&state).await {
+        Ok(cookies) => comments::list_comments(&cookies, &params).await.map_err(|e| e.to_string()),
+        Err(e) => Err(e),
+    };
+    ApiResponse::from_result(result)
+}
+
+/// 16. 回复评论
+#[utoipa::path(
+    post,
+    path = "/api/creator/comments/{comment_id}/reply",
+    tag = "Creator",
+    params(
+        ("comment_id" = String, Path, description = "被回复的评论 ID")
+    ),
+    request_body = ReplyCommentRequest,
+    responses(
+        (status = 200, description = "Reply result", body = ApiResponse<CommentMutation>)
+    )
+)]
+pub async fn creator_reply_comment_handler(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(comment_id): axum::extract::Path<String>,
+    Json(req): Json<ReplyCommentRequest>,
+) -> impl IntoResponse {
+    comment_result(match creator_cookies(&state).await {
+        Ok(cookies) => comments::reply_comment(&cookies, &comment_id, &req).await.map_err(|e| e.to_string()),
+        Err(e) => Err(e),
+    })
+}
+
+/// 17. 删除评论
+#[utoipa::path(
+    delete,
+    path = "/api/creator/comments/{comment_id}",
+    tag = "Creator",
+    params(
+        ("comment_id" = String, Path, description = "评论 ID"),
+        DeleteCommentParams
+    ),
+    responses(
+        (status = 200, description = "Delete result", body = ApiResponse<CommentMutation>)
+    )
+)]
+pub async fn creator_delete_comment_handler(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(comment_id): axum::extract::Path<String>,
+    axum::extract::Query(params): axum::extract::Query<DeleteCommentParams>,
+) -> impl IntoResponse {
+    comment_result(match creator_cookies(&state).await {
+        Ok(cookies) => comments::delete_comment(&cookies, &params.note_id, &comment_id).await
+            .map(|_| None)
+            .map_err(|e| e.to_string()),
+        Err(e) => Err(e),
+    })
+}
+
+/// 18. 隐藏 / 取消隐藏评论
+#[utoipa::path(
+    post,
+    path = "/api/creator/comments/{comment_id}/hide",
+    tag = "Creator",
+    params(
+        ("comment_id" = String, Path, description = "评论 ID")
+    ),
+    request_body = HideCommentRequest,
+    responses(
+        (status = 200, description = "Hide result", body = ApiResponse<CommentMutation>)
+    )
+)]
+pub async fn creator_hide_comment_handler(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(comment_id): axum::extract::Path<String>,
+    Json(req): Json<HideCommentRequest>,
+) -> impl IntoResponse {
+    comment_result(match creator_cookies(&state).await {
+        Ok(cookies) => comments::hide_comment(&cookies, &comment_id, &req).await
+            .map(|_| None)
+            .map_err(|e| e.to_string()),
+        Err(e) => Err(e),
+    })
+}
+
+use crate::api::creator::publish::schedule::{self, ScheduledPost, SchedulePostRequest, ScheduleListParams};
+
+fn schedule_result(result: anyhow::Result<ScheduledPost>) -> ApiResponse<ScheduledPost> {
+    ApiResponse::from_result(result)
+}
+
+/// 19. 定时发布
+///
+/// 将图文 / 视频笔记加入定时发布队列，到达 publish_at 时由后台调度器自动发布。
+/// 队列持久化存储（XHS_SCHEDULE_BACKEND），重启后继续生效
+#[utoipa::path(
+    post,
+    path = "/api/creator/schedule",
+    tag = "Creator",
+    request_body = SchedulePostRequest,
+    responses(
+        (status = 200, description = "Scheduled post", body = ApiResponse<ScheduledPost>)
+    )
+)]
+pub async fn creator_schedule_handler(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<SchedulePostRequest>,
+) -> impl IntoResponse {
+    if let Err(e) = schedule::validate(&req.content, |path| state.file_signer.resolve(path)) {
+        return schedule_result(Err(e));
+    }
+    schedule_result(state.publish_schedule.schedule(req).await)
+}
+
+/// 20. 定时发布队列
+#[utoipa::path(
+    get,
+    path = "/api/creator/schedule",
+    tag = "Creator",
+    params(ScheduleListParams),
+    responses(
+        (status = 200, description = "Scheduled posts", body = ApiResponse<Vec<ScheduledPost>>)
+    )
+)]
+pub async fn creator_schedule_list_handler(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Query(params): axum::extract::Query<ScheduleListParams>,
+) -> impl IntoResponse {
+    ApiResponse::from_result(state.publish_schedule.list(params.status).await)
+}
+
+/// 21. 查询定时发布结果
+#[utoipa::path(
+    get,
+    path = "/api/creator/schedule/{id}",
+    tag = "Creator",
+    params(
+        ("id" = String, Path, description = "定时发布 ID")
+    ),
+    responses(
+        (status = 200, description = "Scheduled post", body = ApiResponse<ScheduledPost>)
+    )
+)]
+pub async fn creator_scheduled_post_handler(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+) -> impl IntoResponse {
+    schedule_result(match state.publish_schedule.get(&id).await {
+        Ok(Some(post)) => Ok(post),
+        Ok(None) => Err(anyhow::anyhow!("Scheduled post {} not found", id)),
+        Err(e) => Err(e),
+    })
+}
+
+/// 22. 取消定时发布
+///
+/// 仅未开始发布 (pending) 的条目可以取消
+#[utoipa::path(
+    delete,
+    path = "/api/creator/schedule/{id}",
+    tag = "Creator",
+    params(
+        ("id" = String, Path, description = "定时发布 ID")
+    ),
+    responses(
+        (status = 200, description = "Cancelled post", body = ApiResponse<ScheduledPost>)
+    )
+)]
+pub async fn creator_cancel_schedule_handler(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+) -> impl IntoResponse {
+    schedule_result(state.publish_schedule.cancel(&id).await)
 }
@@ -0,0 +1,118 @@
+//! Debug HTTP Handlers
+//!
+//! Handles: debug/sign
+
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Instant;
+use utoipa::ToSchema;
+
+use crate::api::login::CookieInput;
+use crate::response::ApiResponse;
+use crate::server::AppState;
+
+/// 签名调试请求
+#[derive(Debug, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "method": "POST",
+    "uri": "/api/sns/web/v1/search/notes",
+    "payload": {"keyword": "咖啡", "page": 1, "page_size": 20}
+}))]
+pub struct DebugSignRequest {
+    /// GET / POST，默认 GET
+    #[serde(default = "default_method")]
+    pub method: String,
+    /// 接口路径，GET 请求包含查询参数（如 `/api/sns/web/v1/user/otherinfo?target_user_id=xxx`）
+    pub uri: String,
+    /// POST 请求体
+    #[serde(default)]
+    pub payload: Option<serde_json::Value>,
+    /// 用于签名的 Cookie（字符串或键值对），不传时使用当前账号的 Cookie
+    #[serde(default)]
+    pub cookies: Option<CookieInput>,
+}
+
+fn default_method() -> String {
+    "GET".to_string()
+}
+
+/// 生成的签名请求头（与真实请求中的请求头同名）
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SignedHeaders {
+    #[serde(rename = "x-s")]
+    pub x_s: String,
+    #[serde(rename = "x-t")]
+    pub x_t: String,
+    #[serde(rename = "x-s-common")]
+    pub x_s_common: String,
+    #[serde(rename = "x-b3-traceid")]
+    pub x_b3_traceid: String,
+    #[serde(rename = "x-xray-traceid")]
+    pub x_xray_traceid: String,
+}
+
+/// 签名调试结果
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DebugSignature {
+    /// 签名来源（目前只有 Python Agent）
+    pub signer: String,
+    pub method: String,
+    pub uri: String,
+    pub headers: SignedHeaders,
+    /// 签名耗时（毫秒）
+    pub elapsed_ms: u64,
+}
+
+/// 生成签名（不发送请求）
+///
+/// 返回指定请求的 x-s / x-t / x-s-common 与 trace ID，便于与浏览器抓包对比排查 406
+#[utoipa::path(
+    post,
+    path = "/api/debug/sign",
+    tag = "Debug",
+    summary = "生成签名（不发送请求）",
+    description = "按 method / uri / payload 调用签名器生成 `x-s`、`x-t`、`x-s-common`、`x-b3-traceid`、`x-xray-traceid`，只返回结果不请求小红书，便于与浏览器抓包逐项对比、排查签名错误 (406)。默认使用当前账号的 Cookie，也可通过 `cookies` 指定。",
+    request_body = DebugSignRequest,
+    responses(
+        (status = 200, description = "签名结果", body = ApiResponse<DebugSignature>),
+        (status = 400, description = "参数错误"),
+        (status = 503, description = "签名器不可用")
+    )
+)]
+pub async fn debug_sign_handler(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<DebugSignRequest>,
+) -> impl IntoResponse {
+    let method = req.method.trim().to_uppercase();
+    let invalid = if method != "GET" && method != "POST" {
+        Some(format!("Unsupported method '{}' (expected GET or POST)", req.method))
+    } else if !req.uri.starts_with('/') {
+        Some("uri must be a path starting with '/', e.g. /api/sns/web/v1/homefeed".to_string())
+    } else {
+        None
+    };
+    if let Some(msg) = invalid {
+        return (StatusCode::BAD_REQUEST, ApiResponse::<DebugSignature>::error(msg)).into_response();
+    }
+
+    let payload = if method == "POST" { req.payload } else { None };
+    let cookies = req.cookies.map(CookieInput::into_cookies);
+    let started = Instant::now();
+    match state.api.sign_only(&method, &req.uri, payload, cookies).await {
+        Ok(signature) => ApiResponse::ok(DebugSignature {
+            signer: "agent".to_string(),
+            method,
+            uri: req.uri,
+            headers: SignedHeaders {
+                x_s: signature.x_s,
+                x_t: signature.x_t,
+                x_s_common: signature.x_s_common,
+                x_b3_traceid: signature.x_b3_traceid,
+                x_xray_traceid: signature.x_xray_traceid,
+            },
+            elapsed_ms: started.elapsed().as_millis() as u64,
+        }).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
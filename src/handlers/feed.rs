@@ -1,17 +1,89 @@
 //! Feed HTTP Handlers
 //! 
-//! Handles: homefeed/recommend
+//! Handles: homefeed/recommend, /ws/feed (streaming)
 
 use axum::{
-    extract::State,
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
     response::IntoResponse,
     Json,
 };
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
 
 use crate::api;
+use crate::api::feed::cursor::FeedCursor;
+use crate::error::XhsError;
+use crate::models::feed::HomefeedItem;
 use crate::server::AppState;
 
+/// 推送间隔下限（秒），避免单个连接把请求打满
+const MIN_STREAM_INTERVAL_SECS: u64 = 5;
+/// 推送间隔上限（秒）
+const MAX_STREAM_INTERVAL_SECS: u64 = 600;
+/// 连续失败多少次后关闭连接
+const MAX_STREAM_FAILURES: u32 = 5;
+
+// ============================================================================
+// Query Parameter Structs
+// ============================================================================
+
+/// /ws/feed 连接参数
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct FeedStreamParams {
+    /// 频道: recommend/fashion/food/...，默认 recommend
+    #[serde(default = "default_stream_category")]
+    pub category: String,
+    /// 翻页间隔（秒），默认 15，范围 5-600
+    #[serde(default = "default_stream_interval")]
+    pub interval_secs: u64,
+}
+
+fn default_stream_category() -> String { "recommend".to_string() }
+fn default_stream_interval() -> u64 { 15 }
+
+/// 客户端发送的控制消息（JSON 文本帧，字段均可选）
+#[derive(Debug, Default, Deserialize, utoipa::ToSchema)]
+#[schema(example = json!({"category": "food", "interval_secs": 30}))]
+pub struct FeedStreamControl {
+    /// 切换频道（从第一页重新开始）
+    #[serde(default)]
+    pub category: Option<String>,
+    /// 调整翻页间隔（秒）
+    #[serde(default)]
+    pub interval_secs: Option<u64>,
+    /// 暂停 / 恢复推送
+    #[serde(default)]
+    pub paused: Option<bool>,
+}
+
+/// 服务端推送的消息
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FeedStreamMessage {
+    /// 当前推送设置（连接建立与每次控制消息后发送）
+    Settings {
+        category: String,
+        interval_secs: u64,
+        paused: bool,
+    },
+    /// 一页新内容（已剔除本连接推送过的笔记）
+    Items {
+        category: String,
+        page: u32,
+        items: Vec<HomefeedItem>,
+    },
+    /// 拉取失败，`retry_in_secs` 后重试
+    Error {
+        error_kind: String,
+        msg: String,
+        retry_in_secs: u64,
+    },
+}
 
 // ============================================================================
 // Handlers
@@ -26,11 +98,130 @@ pub async fn homefeed_recommend_handler(
 ) -> impl IntoResponse {
     match api::feed::recommend::get_homefeed_recommend(&state.api).await {
         Ok(res) => Json(res).into_response(),
-        Err(e) => Json(serde_json::json!({
-            "code": -1,
-            "success": false,
-            "msg": e.to_string(),
-            "data": null
-        })).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// 主页发现-实时推送 (WebSocket)
+///
+/// 服务端在后台按间隔翻页，把新笔记以 JSON 文本帧推给客户端
+#[utoipa::path(
+    get,
+    path = "/ws/feed",
+    tag = "Feed",
+    summary = "主页发现-实时推送 (WebSocket)",
+    description = "升级为 WebSocket 后，服务端按 `interval_secs` 在后台翻页，并推送 `FeedStreamMessage`（`settings` / `items` / `error`）。\n\n客户端可随时发送 `FeedStreamControl` JSON 切换频道、调整间隔或暂停。同一连接内已推送过的笔记不会重复推送。请求同样受出站限流与 461 熔断约束。",
+    params(FeedStreamParams),
+    responses(
+        (status = 101, description = "Switching Protocols，之后推送 FeedStreamMessage", body = FeedStreamMessage),
+    )
+)]
+pub async fn feed_stream_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<FeedStreamParams>,
+    ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| stream_feed(state, socket, params))
+}
+
+async fn send_message(socket: &mut WebSocket, message: &FeedStreamMessage) -> bool {
+    match serde_json::to_string(message) {
+        Ok(text) => socket.send(Message::Text(text)).await.is_ok(),
+        Err(_) => false,
+    }
+}
+
+async fn stream_feed(state: Arc<AppState>, mut socket: WebSocket, params: FeedStreamParams) {
+    let clamp = |secs: u64| secs.clamp(MIN_STREAM_INTERVAL_SECS, MAX_STREAM_INTERVAL_SECS);
+    let mut interval = Duration::from_secs(clamp(params.interval_secs));
+    let mut paused = false;
+    let mut cursor = FeedCursor::new(params.category);
+    let mut failures = 0u32;
+    let mut next_at = Instant::now();
+
+    tracing::info!("[FeedStream] Client connected (category: {})", cursor.category);
+    let settings = |cursor: &FeedCursor, interval: Duration, paused: bool| FeedStreamMessage::Settings {
+        category: cursor.category.clone(),
+        interval_secs: interval.as_secs(),
+        paused,
+    };
+    if !send_message(&mut socket, &settings(&cursor, interval, paused)).await {
+        return;
     }
+
+    loop {
+        tokio::select! {
+            incoming = socket.recv() => {
+                let text = match incoming {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => continue,
+                };
+                let control: FeedStreamControl = match serde_json::from_str(&text) {
+                    Ok(control) => control,
+                    Err(e) => {
+                        let error = FeedStreamMessage::Error {
+                            error_kind: "invalid_control".to_string(),
+                            msg: e.to_string(),
+                            retry_in_secs: 0,
+                        };
+                        if !send_message(&mut socket, &error).await {
+                            break;
+                        }
+                        continue;
+                    }
+                };
+                if let Some(category) = control.category.filter(|c| *c != cursor.category) {
+                    cursor = FeedCursor::new(category);
+                    next_at = Instant::now();
+                }
+                if let Some(secs) = control.interval_secs {
+                    interval = Duration::from_secs(clamp(secs));
+                }
+                if let Some(p) = control.paused {
+                    paused = p;
+                }
+                if !send_message(&mut socket, &settings(&cursor, interval, paused)).await {
+                    break;
+                }
+            }
+            _ = tokio::time::sleep_until(next_at), if !paused => {
+                let message = match api::feed::category::fetch_category_feed(&state.api, &cursor.category, cursor.next_request()).await {
+                    Ok(resp) => {
+                        failures = 0;
+                        next_at = Instant::now() + interval;
+                        let (cursor_score, items) = resp.data
+                            .map(|d| (d.cursor_score, d.items))
+                            .unwrap_or_default();
+                        state.note_cache.observe_items(&items).await;
+                        FeedStreamMessage::Items {
+                            category: cursor.category.clone(),
+                            page: cursor.page + 1,
+                            items: cursor.advance(cursor_score, items),
+                        }
+                    }
+                    Err(e) => {
+                        failures += 1;
+                        let retry_in = match &e {
+                            XhsError::RiskCircuitOpen { retry_after, .. } => Duration::from_secs(*retry_after).max(interval),
+                            _ => interval,
+                        };
+                        next_at = Instant::now() + retry_in;
+                        tracing::warn!("[FeedStream] {} page {} failed ({}/{}): {}", cursor.category, cursor.page + 1, failures, MAX_STREAM_FAILURES, e);
+                        FeedStreamMessage::Error {
+                            error_kind: e.kind().to_string(),
+                            msg: e.to_string(),
+                            retry_in_secs: retry_in.as_secs(),
+                        }
+                    }
+                };
+                if !send_message(&mut socket, &message).await || failures >= MAX_STREAM_FAILURES {
+                    break;
+                }
+            }
+        }
+    }
+
+    let _ = socket.send(Message::Close(None)).await;
+    tracing::info!("[FeedStream] Client disconnected (category: {}, pages: {})", cursor.category, cursor.page);
 }
@@ -0,0 +1,128 @@
+//! Crawl Job HTTP Handlers
+//!
+//! Handles: jobs (list / create), jobs/{id} (get / replace / delete)
+
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    Json,
+};
+use std::sync::Arc;
+
+use crate::api::crawl::{CrawlJob, CrawlJobRequest};
+use crate::response::ApiResponse;
+use crate::server::AppState;
+
+fn job_result(result: anyhow::Result<CrawlJob>) -> ApiResponse<CrawlJob> {
+    ApiResponse::from_result(result)
+}
+
+/// 创建定时抓取任务
+///
+/// 注册后立即执行一次，之后每 interval_secs 秒执行；任务持久化存储（XHS_JOBS_BACKEND），重启后继续生效
+#[utoipa::path(
+    post,
+    path = "/api/jobs",
+    tag = "Jobs",
+    summary = "创建定时抓取任务",
+    description = "任务类型：\n- `search`: 按关键词搜索笔记（`pages` 页，默认 1）\n- `feed`: 抓取主页频道第一页（`category`，默认 recommend）\n- `note`: 抓取笔记详情并写入笔记归档（需启用 XHS_ARCHIVE_BACKEND）\n\n任务经 XhsApiClient 串行执行，受限流 / 拟人节奏 / 风控熔断 / 维护闸门约束；启用 XHS_ARCHIVE_BACKEND 时上游响应写入归档。`interval_secs` 最小 60。",
+    request_body = CrawlJobRequest,
+    responses(
+        (status = 200, description = "创建的任务", body = ApiResponse<CrawlJob>)
+    )
+)]
+pub async fn create_job_handler(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<CrawlJobRequest>,
+) -> impl IntoResponse {
+    job_result(state.crawl_jobs.create(req).await)
+}
+
+/// 定时抓取任务列表
+#[utoipa::path(
+    get,
+    path = "/api/jobs",
+    tag = "Jobs",
+    summary = "定时抓取任务列表",
+    responses(
+        (status = 200, description = "全部任务（按创建时间排序），含最近一次执行结果", body = ApiResponse<Vec<CrawlJob>>)
+    )
+)]
+pub async fn list_jobs_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    ApiResponse::from_result(state.crawl_jobs.list().await)
+}
+
+/// 查询定时抓取任务
+#[utoipa::path(
+    get,
+    path = "/api/jobs/{id}",
+    tag = "Jobs",
+    summary = "查询定时抓取任务",
+    params(
+        ("id" = String, Path, description = "任务 ID")
+    ),
+    responses(
+        (status = 200, description = "任务详情", body = ApiResponse<CrawlJob>)
+    )
+)]
+pub async fn get_job_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    job_result(match state.crawl_jobs.get(&id).await {
+        Ok(Some(job)) => Ok(job),
+        Ok(None) => Err(anyhow::anyhow!("Job {} not found", id)),
+        Err(e) => Err(e),
+    })
+}
+
+/// 修改定时抓取任务
+///
+/// 整体替换名称、任务内容、间隔与启用状态，保留执行记录；任务内容变化或重新启用时立即执行一次
+#[utoipa::path(
+    put,
+    path = "/api/jobs/{id}",
+    tag = "Jobs",
+    summary = "修改定时抓取任务",
+    params(
+        ("id" = String, Path, description = "任务 ID")
+    ),
+    request_body = CrawlJobRequest,
+    responses(
+        (status = 200, description = "修改后的任务", body = ApiResponse<CrawlJob>)
+    )
+)]
+pub async fn update_job_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(req): Json<CrawlJobRequest>,
+) -> impl IntoResponse {
+    job_result(state.crawl_jobs.update(&id, req).await)
+}
+
+/// 删除定时抓取任务
+///
+/// 正在执行的任务本轮执行完后不再排期
+#[utoipa::path(
+    delete,
+    path = "/api/jobs/{id}",
+    tag = "Jobs",
+    summary = "删除定时抓取任务",
+    params(
+        ("id" = String, Path, description = "任务 ID")
+    ),
+    responses(
+        (status = 200, description = "删除结果", body = ApiResponse<CrawlJob>)
+    )
+)]
+pub async fn delete_job_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let job = match state.crawl_jobs.get(&id).await {
+        Ok(Some(job)) => job,
+        Ok(None) => return job_result(Err(anyhow::anyhow!("Job {} not found", id))),
+        Err(e) => return job_result(Err(e)),
+    };
+    job_result(state.crawl_jobs.delete(&id).await.map(|_| job))
+}
@@ -0,0 +1,65 @@
+//! Link HTTP Handlers
+//!
+//! Handles: links/parse
+
+use axum::{
+    extract::Query,
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::error::XhsError;
+use crate::response::ApiResponse;
+use crate::utils::url::{self, XhsLink};
+
+/// 链接解析参数
+#[derive(Debug, Clone, Deserialize, utoipa::IntoParams)]
+pub struct ParseLinkParams {
+    /// 小红书链接、xhslink.com 短链或包含链接的分享文案
+    pub url: String,
+}
+
+/// 链接解析结果
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[schema(example = json!({
+    "link": {"type": "user", "user_id": "5ff0e6410000000001008400", "xsec_token": "ABx8fQ2y1example=", "xsec_source": "app_share"},
+    "share_url": "https://www.xiaohongshu.com/user/profile/5ff0e6410000000001008400?xsec_token=ABx8fQ2y1example%3D&xsec_source=app_share",
+    "resolved_url": "https://www.xiaohongshu.com/user/profile/5ff0e6410000000001008400?xsec_token=ABx8fQ2y1example=&xsec_source=app_share&share_id=abc"
+}))]
+pub struct ParsedLink {
+    pub link: XhsLink,
+    /// 规范链接（只保留 xsec_token / xsec_source）
+    pub share_url: String,
+    /// 短链展开后的原始链接
+    pub resolved_url: String,
+}
+
+/// 解析小红书链接
+///
+/// 展开 xhslink.com 短链，识别笔记 / 用户主页 / 话题链接并生成规范链接
+#[utoipa::path(
+    get,
+    path = "/api/links/parse",
+    tag = "Links",
+    summary = "解析小红书链接",
+    description = "接受网页链接、`xhslink.com` 短链或包含链接的分享文案，逐跳跟随短链重定向，返回类型化标识（`note` 的 note_id / `user` 的 user_id / `topic` 的 page_id，笔记与用户附带 xsec_token）及去掉跟踪参数的规范链接。只需笔记时可用 `/api/note/resolve`。",
+    params(ParseLinkParams),
+    responses(
+        (status = 200, description = "解析结果", body = ApiResponse<ParsedLink>),
+        (status = 400, description = "无法识别的链接"),
+        (status = 502, description = "短链请求失败")
+    )
+)]
+pub async fn parse_link_handler(Query(params): Query<ParseLinkParams>) -> impl IntoResponse {
+    match url::resolve(&params.url).await {
+        Ok((link, resolved)) => ApiResponse::ok(ParsedLink {
+            share_url: link.share_url(),
+            link,
+            resolved_url: resolved.to_string(),
+        }).into_response(),
+        Err(XhsError::Other(e)) => (StatusCode::BAD_REQUEST, ApiResponse::<ParsedLink>::error(e.to_string())).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
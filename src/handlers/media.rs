@@ -1,15 +1,23 @@
 //! Media HTTP Handlers
 //!
-//! Handles: video URL extraction, image URL extraction, media download
+//! Handles: video URL extraction, image URL extraction, media download jobs, note zip bundles,
+//! signed file URLs
 
 use axum::{
-    extract::State,
-    response::IntoResponse,
+    body::Body,
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
+use futures_util::StreamExt;
 use std::sync::Arc;
+use tokio_util::io::ReaderStream;
 
 use crate::api::media;
+use crate::api::media::sidecar::NoteMetadata;
+use crate::api::note::detail::{fetch_note_detail, NoteDetailRequest};
+use crate::response::ApiResponse;
 use crate::server::AppState;
 
 // ============================================================================
@@ -37,11 +45,7 @@ pub async fn video_handler(
 ) -> impl IntoResponse {
     match media::video::get_video_urls(&state.api, req).await {
         Ok(res) => Json(res).into_response(),
-        Err(e) => Json(serde_json::json!({
-            "success": false,
-            "msg": e.to_string(),
-            "data": null
-        })).into_response(),
+        Err(e) => ApiResponse::<()>::error(e.to_string()).into_response(),
     }
 }
 
@@ -54,7 +58,7 @@ pub async fn video_handler(
     path = "/api/note/images",
     tag = "Media",
     summary = "图片地址解析",
-    description = "从图文笔记中提取所有图片的下载 URL。返回两个版本：url_watermark (有水印) 和 url_original (无水印)",
+    description = "从图文笔记中提取所有图片的下载 URL。返回两个版本：url_watermark (有水印) 和 url_original (无水印)。实况图 (Live Photo) 标记 live_photo = true，并在 motion 中返回画质最高的动态视频地址",
     request_body = media::images::ImagesRequest,
     responses(
         (status = 200, description = "图片地址列表", body = media::images::ImagesResponse),
@@ -67,38 +71,250 @@ pub async fn images_handler(
 ) -> impl IntoResponse {
     match media::images::get_image_urls(&state.api, req).await {
         Ok(res) => Json(res).into_response(),
-        Err(e) => Json(serde_json::json!({
-            "success": false,
-            "msg": e.to_string(),
-            "data": null
-        })).into_response(),
+        Err(e) => ApiResponse::<()>::error(e.to_string()).into_response(),
     }
 }
 
 /// 下载媒体文件
 ///
-/// 将视频或图片下载到服务端本地目录
+/// 提交后台下载任务，立即返回 job_id；通过 /api/media/jobs/{job_id} 查询进度
 #[utoipa::path(
     post,
     path = "/api/media/download",
     tag = "Media",
     summary = "媒体下载",
-    description = "将视频或图片文件下载到服务端本地指定路径，支持 xhscdn.com 域名",
+    description = "将视频或图片文件下载到服务端下载根目录（XHS_DOWNLOAD_ROOT）内的指定路径，支持 xhscdn.com 域名；越出根目录的路径与超出大小上限 / 磁盘配额的下载会被拒绝。\n\n默认异步执行：返回 202 与 job_id，下载在并发受限（XHS_DOWNLOAD_WORKERS）的后台队列中进行，通过 /api/media/jobs/{job_id} 查询进度；`wait=true` 时同步等待并返回下载结果。\n\n`include_metadata = true`（需同时提供 note_id 与 xsec_token）时下载完成后在文件旁写入笔记元数据 sidecar（video.mp4 → video.meta.json，`metadata_format = yaml` 时为 .meta.yaml）。",
+    params(media::jobs::DownloadParams),
     request_body = media::download::DownloadRequest,
     responses(
-        (status = 200, description = "下载结果", body = media::download::DownloadResponse),
+        (status = 202, description = "下载任务已提交", body = ApiResponse<media::jobs::DownloadProgress>),
+        (status = 200, description = "wait=true 时的下载结果", body = media::download::DownloadResponse),
+        (status = 400, description = "include_metadata 缺少 note_id / xsec_token"),
+        (status = 404, description = "include_metadata 时笔记不存在"),
         (status = 500, description = "下载失败")
     )
 )]
 pub async fn download_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<media::jobs::DownloadParams>,
     Json(req): Json<media::download::DownloadRequest>,
 ) -> impl IntoResponse {
-    match media::download::download_media(req).await {
+    let metadata = match download_metadata(&state, &req).await {
+        Ok(metadata) => metadata,
+        Err(response) => return response,
+    };
+    if !params.wait {
+        let job = state.download_jobs.submit(req, metadata);
+        return (StatusCode::ACCEPTED, ApiResponse::ok(job)).into_response();
+    }
+    
+    let (format, target) = (req.metadata_format, req.target);
+    let result = match media::download::download_media(req).await {
+        Ok(mut res) => match metadata {
+            Some(metadata) => media::sidecar::attach_sidecar(&mut res, &metadata, format, target).await.map(|_| res),
+            None => Ok(res),
+        },
+        Err(e) => Err(e.into()),
+    };
+    match result {
         Ok(res) => Json(res).into_response(),
-        Err(e) => Json(serde_json::json!({
-            "success": false,
-            "msg": e.to_string(),
-            "data": null
-        })).into_response(),
+        Err(e) => ApiResponse::<()>::error(e.to_string()).into_response(),
+    }
+}
+
+/// include_metadata 时获取笔记元数据（在排队下载前完成，笔记不可用时直接报错）
+async fn download_metadata(
+    state: &AppState,
+    req: &media::download::DownloadRequest,
+) -> Result<Option<NoteMetadata>, Response> {
+    if !req.include_metadata {
+        return Ok(None);
+    }
+    let (Some(note_id), Some(xsec_token)) = (req.note_id.as_deref(), req.xsec_token.as_deref()) else {
+        return Err((StatusCode::BAD_REQUEST, ApiResponse::<()>::error("include_metadata requires note_id and xsec_token")).into_response());
+    };
+    let detail = fetch_note_detail(&state.api, &state.note_cache, NoteDetailRequest::new(note_id, xsec_token))
+        .await
+        .map_err(IntoResponse::into_response)?;
+    let Some(note_card) = detail.note_card() else {
+        return Err((StatusCode::NOT_FOUND, ApiResponse::<()>::error(detail.msg.clone().unwrap_or_else(|| "Note not found".to_string()))).into_response());
+    };
+    NoteMetadata::from_note_card(note_id, note_card)
+        .map(Some)
+        .map_err(IntoResponse::into_response)
+}
+
+/// 查询下载任务进度
+#[utoipa::path(
+    get,
+    path = "/api/media/jobs/{job_id}",
+    tag = "Media",
+    summary = "下载任务进度",
+    description = "status: queued / running / done / failed；downloaded_bytes 与 total_bytes 为实时进度",
+    params(
+        ("job_id" = String, Path, description = "下载任务 ID")
+    ),
+    responses(
+        (status = 200, description = "任务进度", body = ApiResponse<media::jobs::DownloadProgress>),
+        (status = 404, description = "任务不存在", body = ApiResponse<media::jobs::DownloadProgress>)
+    )
+)]
+pub async fn download_job_handler(
+    State(state): State<Arc<AppState>>,
+    Path(job_id): Path<String>,
+) -> impl IntoResponse {
+    match state.download_jobs.get(&job_id) {
+        Some(job) => (StatusCode::OK, ApiResponse::ok(job)),
+        None => (StatusCode::NOT_FOUND, ApiResponse::error(format!("Download job {} not found", job_id))),
+    }
+}
+
+/// 笔记打包下载
+///
+/// 下载笔记的全部图片 / 视频与元数据，以 zip 流式返回
+#[utoipa::path(
+    post,
+    path = "/api/media/bundle",
+    tag = "Media",
+    summary = "笔记打包下载",
+    description = "一次调用归档整篇笔记：图文笔记打包全部无水印图片（实况图附带 N.mp4 动态视频），视频笔记打包最高画质视频与封面，默认附带 metadata.json（标题、正文、作者、标签、发布时间、互动数据；`metadata_format = yaml` 时为 metadata.yaml）与原始 note_card.json，`include_metadata = false` 时不附带。\n\n压缩包边下载边传输，不在服务端落盘；传输中途下载失败时连接会被中断，客户端收到的压缩包不完整。\n\n`target = s3` 时压缩包以分片上传写入 XHS_S3_* 配置的对象存储（对象键为 `key`，默认 `{note_id}.zip`，自动加 XHS_S3_PREFIX 前缀），返回 JSON 结果而非压缩包。",
+    request_body = media::bundle::BundleRequest,
+    responses(
+        (status = 200, description = "zip 压缩包；target = s3 时为上传结果", content(
+            ("application/zip"),
+            (ApiResponse<media::bundle::BundleUploadData> = "application/json")
+        )),
+        (status = 404, description = "笔记不存在或没有可下载的媒体"),
+        (status = 500, description = "获取笔记详情失败")
+    )
+)]
+pub async fn bundle_handler(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<media::bundle::BundleRequest>,
+) -> impl IntoResponse {
+    let detail = match fetch_note_detail(&state.api, &state.note_cache, NoteDetailRequest::new(&req.note_id, &req.xsec_token)).await {
+        Ok(detail) => detail,
+        Err(e) => return e.into_response(),
+    };
+    let Some(note_card) = detail.note_card().cloned() else {
+        return (StatusCode::NOT_FOUND, ApiResponse::<()>::error(detail.msg.unwrap_or_else(|| "Note not found".to_string()))).into_response();
+    };
+    if media::bundle::bundle_entries(&note_card).is_empty() {
+        return (StatusCode::NOT_FOUND, ApiResponse::<()>::error("No media found in this note")).into_response();
+    }
+
+    if req.target == media::download::DownloadTarget::S3 {
+        let format = req.include_metadata.then_some(req.metadata_format);
+        let key = req.key.clone().unwrap_or_else(|| format!("{}.zip", req.note_id));
+        let uploaded = match media::s3::S3Client::from_env() {
+            Ok(s3) => media::bundle::upload_bundle(&s3, &key, &req.note_id, &note_card, format, req.proxy.as_deref()).await,
+            Err(e) => Err(e),
+        };
+        let resp = match uploaded {
+            Ok(data) => {
+                tracing::info!("[MediaBundle] Uploaded {} to {} ({} media files)", req.note_id, data.location, data.media_count);
+                ApiResponse::ok(data)
+            }
+            Err(e) => {
+                tracing::error!("[MediaBundle] {} upload failed: {:#}", req.note_id, e);
+                ApiResponse::error(format!("{:#}", e))
+            }
+        };
+        return resp.into_response();
+    }
+
+    // 打包任务写入管道一端，响应体读取另一端
+    let (reader, writer) = tokio::io::duplex(64 * 1024);
+    let disposition = format!("attachment; filename=\"{}.zip\"", req.note_id);
+    let note_id = req.note_id.clone();
+    let task = tokio::spawn(async move {
+        let format = req.include_metadata.then_some(req.metadata_format);
+        media::bundle::write_bundle(&req.note_id, &note_card, format, req.proxy.as_deref(), writer).await
+    });
+    // 打包失败时以错误结束响应体，让客户端感知压缩包不完整
+    let outcome = futures_util::stream::once(async move {
+        match task.await {
+            Ok(Ok(count)) => {
+                tracing::info!("[MediaBundle] Streamed {} ({} media files)", note_id, count);
+                None
+            }
+            Ok(Err(e)) => {
+                tracing::error!("[MediaBundle] {} failed: {}", note_id, e);
+                Some(Err(std::io::Error::other(e.to_string())))
+            }
+            Err(e) => Some(Err(std::io::Error::other(e.to_string()))),
+        }
+    }).filter_map(futures_util::future::ready);
+    let body = Body::from_stream(ReaderStream::new(reader).chain(outcome));
+
+    (
+        [
+            (header::CONTENT_TYPE, "application/zip".to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    ).into_response()
+}
+
+/// 生成临时文件链接
+///
+/// 为下载根目录 (XHS_DOWNLOAD_ROOT) 内的文件生成带签名、限时有效的 /files/{token} 链接，
+/// 供管理后台或 Webhook 引用，不暴露服务端文件路径
+#[utoipa::path(
+    post,
+    path = "/api/media/share",
+    tag = "Media",
+    summary = "生成临时文件链接",
+    description = "为已下载文件生成限时签名链接（默认有效期 XHS_FILE_URL_TTL_SECS 秒）",
+    request_body = media::signed_url::SignFileRequest,
+    responses(
+        (status = 200, description = "签名链接", body = ApiResponse<media::signed_url::SignedFile>)
+    )
+)]
+pub async fn share_file_handler(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<media::signed_url::SignFileRequest>,
+) -> impl IntoResponse {
+    match state.file_signer.sign(&req.path, req.ttl_secs) {
+        Ok((token, expires_at)) => ApiResponse::ok(media::signed_url::SignedFile {
+            url: format!("/files/{}", token),
+            expires_at,
+        }),
+        Err(e) => ApiResponse::error(e.to_string()),
+    }
+}
+
+/// 访问临时文件链接
+///
+/// 校验签名与有效期后返回文件内容，不提供目录浏览
+#[utoipa::path(
+    get,
+    path = "/files/{token}",
+    tag = "Media",
+    summary = "访问临时文件链接",
+    params(
+        ("token" = String, Path, description = "由 /api/media/share 生成的签名令牌")
+    ),
+    responses(
+        (status = 200, description = "文件内容"),
+        (status = 403, description = "签名无效或已过期"),
+        (status = 404, description = "文件不存在")
+    )
+)]
+pub async fn serve_file_handler(
+    State(state): State<Arc<AppState>>,
+    Path(token): Path<String>,
+) -> impl IntoResponse {
+    let path = match state.file_signer.verify(&token) {
+        Ok(path) => path,
+        Err(e) => return (StatusCode::FORBIDDEN, e.to_string()).into_response(),
+    };
+    
+    match tokio::fs::read(&path).await {
+        Ok(bytes) => (
+            [(header::CONTENT_TYPE, media::signed_url::content_type_for(&path))],
+            bytes,
+        ).into_response(),
+        Err(_) => (StatusCode::NOT_FOUND, "File not found").into_response(),
     }
 }
@@ -0,0 +1,58 @@
+//! Metrics HTTP Handlers
+//!
+//! Handles: metrics/signature
+
+use axum::{
+    extract::Query,
+    response::IntoResponse,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::response::ApiResponse;
+use crate::signature::metrics::{AgentLatencyMetrics, EndpointStrategyMetrics, SIGNATURE_METRICS};
+
+/// 签名指标查询参数
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct SignatureMetricsParams {
+    /// 统计窗口（分钟），默认 5，最大 60
+    #[serde(default = "default_minutes")]
+    pub minutes: usize,
+}
+
+fn default_minutes() -> usize { 5 }
+
+/// 签名指标
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct SignatureMetrics {
+    /// 统计窗口（分钟）
+    pub minutes: usize,
+    pub endpoints: Vec<EndpointStrategyMetrics>,
+    /// Agent 签名调用耗时与失败 / 超时次数
+    pub agent: AgentLatencyMetrics,
+}
+
+/// 签名策略指标
+///
+/// 各接口纯算法签名成功/失败次数、存储签名兜底次数，最近窗口内的算法失败率，
+/// 以及 Agent 签名调用的耗时直方图与超时次数
+#[utoipa::path(
+    get,
+    path = "/api/metrics/signature",
+    tag = "Metrics",
+    summary = "签名策略指标",
+    description = "失败率持续超过 XHS_SIGNATURE_ALERT_RATIO 达 XHS_SIGNATURE_ALERT_MINUTES 分钟时向 XHS_SIGNATURE_ALERT_WEBHOOK 告警。`agent` 为 Agent 签名调用耗时（5s 超时计入 timeouts，超过 XHS_SIGNATURE_SLOW_MS 计入 slow 并记 warn 日志）",
+    params(SignatureMetricsParams),
+    responses(
+        (status = 200, description = "签名指标", body = ApiResponse<SignatureMetrics>)
+    )
+)]
+pub async fn signature_metrics_handler(
+    Query(params): Query<SignatureMetricsParams>,
+) -> impl IntoResponse {
+    let minutes = params.minutes.clamp(1, 60);
+    ApiResponse::ok(SignatureMetrics {
+        minutes,
+        endpoints: SIGNATURE_METRICS.snapshot(minutes),
+        agent: SIGNATURE_METRICS.agent_snapshot(minutes),
+    })
+}
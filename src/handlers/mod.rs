@@ -9,6 +9,18 @@ pub mod user;
 pub mod feed;
 pub mod media;
 pub mod creator;
+pub mod cluster;
+pub mod activity;
+pub mod tag;
+pub mod links;
+pub mod metrics;
+pub mod system;
+pub mod archive;
+pub mod admin;
+pub mod jobs;
+pub mod monitors;
+pub mod watch;
+pub mod debug;
 
 // Re-export all handlers for convenient access
 pub use search::*;
@@ -18,3 +30,15 @@ pub use user::*;
 pub use feed::*;
 pub use media::*;
 pub use creator::*;
+pub use cluster::*;
+pub use activity::*;
+pub use tag::*;
+pub use links::*;
+pub use metrics::*;
+pub use system::*;
+pub use archive::*;
+pub use admin::*;
+pub use jobs::*;
+pub use monitors::*;
+pub use watch::*;
+pub use debug::*;
@@ -0,0 +1,127 @@
+//! Keyword Monitor HTTP Handlers
+//!
+//! Handles: monitors (list / create), monitors/{id} (get / replace / delete)
+
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    Json,
+};
+use std::sync::Arc;
+
+use crate::api::monitor::{KeywordMonitor, MonitorRequest};
+use crate::response::ApiResponse;
+use crate::server::AppState;
+
+fn monitor_result(result: anyhow::Result<KeywordMonitor>) -> ApiResponse<KeywordMonitor> {
+    ApiResponse::from_result(result.map(KeywordMonitor::without_seen))
+}
+
+/// 创建关键词监控
+///
+/// 注册后立即执行一次建立基线（不告警），之后每 interval_secs 秒搜索一次，新出现且满足过滤条件的笔记触发告警
+#[utoipa::path(
+    post,
+    path = "/api/monitors",
+    tag = "Monitors",
+    summary = "创建关键词监控",
+    description = "定期按关键词搜索笔记（`pages` 页，默认 1，最多 5；`sort` 默认 time_descending），与已报告的笔记 ID 对比，新笔记满足 `filters` 时：\n- 记录日志事件，并写入监控的 `recent_matches`\n- POST 到 `webhook_urls`（未配置时使用 XHS_WEBHOOK_URLS，均未配置则只记录日志），请求头 `X-Xhs-Event: keyword_monitor`，配置 XHS_WEBHOOK_SECRET 时带 `X-Xhs-Signature`\n\n过滤条件：`min_liked_count` 最低点赞数、`include_words` 标题需包含其一、`exclude_words` 标题不得包含、`exclude_user_ids` 排除作者。只记住已报告的笔记，之后才满足条件（如点赞数增长）的笔记仍会报告一次。\n\n首次执行只建立基线；修改关键词 / 排序 / 笔记类型后重新建立基线。`interval_secs` 最小 60。",
+    request_body = MonitorRequest,
+    responses(
+        (status = 200, description = "创建的监控", body = ApiResponse<KeywordMonitor>)
+    )
+)]
+pub async fn create_monitor_handler(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<MonitorRequest>,
+) -> impl IntoResponse {
+    monitor_result(state.monitors.create(req).await)
+}
+
+/// 关键词监控列表
+#[utoipa::path(
+    get,
+    path = "/api/monitors",
+    tag = "Monitors",
+    summary = "关键词监控列表",
+    responses(
+        (status = 200, description = "全部监控（按创建时间排序），含最近一次执行结果与最近命中的笔记", body = ApiResponse<Vec<KeywordMonitor>>)
+    )
+)]
+pub async fn list_monitors_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    ApiResponse::from_result(state.monitors.list().await
+        .map(|monitors| monitors.into_iter().map(KeywordMonitor::without_seen).collect::<Vec<_>>()))
+}
+
+/// 查询关键词监控
+#[utoipa::path(
+    get,
+    path = "/api/monitors/{id}",
+    tag = "Monitors",
+    summary = "查询关键词监控",
+    params(
+        ("id" = String, Path, description = "监控 ID")
+    ),
+    responses(
+        (status = 200, description = "监控详情", body = ApiResponse<KeywordMonitor>)
+    )
+)]
+pub async fn get_monitor_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    monitor_result(match state.monitors.get(&id).await {
+        Ok(Some(monitor)) => Ok(monitor),
+        Ok(None) => Err(anyhow::anyhow!("Monitor {} not found", id)),
+        Err(e) => Err(e),
+    })
+}
+
+/// 修改关键词监控
+///
+/// 整体替换配置，保留执行记录与命中记录；修改关键词 / 排序 / 笔记类型时清空已报告记录并立即重新建立基线
+#[utoipa::path(
+    put,
+    path = "/api/monitors/{id}",
+    tag = "Monitors",
+    summary = "修改关键词监控",
+    params(
+        ("id" = String, Path, description = "监控 ID")
+    ),
+    request_body = MonitorRequest,
+    responses(
+        (status = 200, description = "修改后的监控", body = ApiResponse<KeywordMonitor>)
+    )
+)]
+pub async fn update_monitor_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(req): Json<MonitorRequest>,
+) -> impl IntoResponse {
+    monitor_result(state.monitors.update(&id, req).await)
+}
+
+/// 删除关键词监控
+#[utoipa::path(
+    delete,
+    path = "/api/monitors/{id}",
+    tag = "Monitors",
+    summary = "删除关键词监控",
+    params(
+        ("id" = String, Path, description = "监控 ID")
+    ),
+    responses(
+        (status = 200, description = "删除结果", body = ApiResponse<KeywordMonitor>)
+    )
+)]
+pub async fn delete_monitor_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let monitor = match state.monitors.get(&id).await {
+        Ok(Some(monitor)) => monitor,
+        Ok(None) => return monitor_result(Err(anyhow::anyhow!("Monitor {} not found", id))),
+        Err(e) => return monitor_result(Err(e)),
+    };
+    monitor_result(state.monitors.delete(&id).await.map(|_| monitor))
+}
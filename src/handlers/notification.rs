@@ -1,6 +1,6 @@
 //! Notification HTTP Handlers
 //! 
-//! Handles: mentions, connections, likes
+//! Handles: mentions, connections, likes, poll
 
 use axum::{
     extract::State,
@@ -10,6 +10,7 @@ use axum::{
 use std::sync::Arc;
 
 use crate::api;
+use crate::response::ApiResponse;
 use crate::server::AppState;
 
 // ============================================================================
@@ -38,12 +39,7 @@ pub async fn mentions_handler(
 ) -> impl IntoResponse {
     match api::notification::mentions::get_mentions_with_params(&state.api, params).await {
         Ok(res) => Json(res).into_response(),
-        Err(e) => Json(serde_json::json!({
-            "code": -1,
-            "success": false,
-            "msg": e.to_string(),
-            "data": null
-        })).into_response(),
+        Err(e) => e.into_response(),
     }
 }
 
@@ -69,12 +65,7 @@ pub async fn connections_handler(
 ) -> impl IntoResponse {
     match api::notification::connections::get_connections_with_params(&state.api, params).await {
         Ok(res) => Json(res).into_response(),
-        Err(e) => Json(serde_json::json!({
-            "code": -1,
-            "success": false,
-            "msg": e.to_string(),
-            "data": null
-        })).into_response(),
+        Err(e) => e.into_response(),
     }
 }
 
@@ -100,11 +91,67 @@ pub async fn likes_handler(
 ) -> impl IntoResponse {
     match api::notification::likes::get_likes_with_params(&state.api, params).await {
         Ok(res) => Json(res).into_response(),
-        Err(e) => Json(serde_json::json!({
-            "code": -1,
-            "success": false,
-            "msg": e.to_string(),
-            "data": null
-        })).into_response(),
+        Err(e) => e.into_response(),
     }
 }
+
+/// 通知长轮询
+/// 
+/// 开始等待时获取最新未读数作为基准，之后服务端按安全间隔轮询未读数 (多个等待中的请求共享同一次上游请求)，
+/// 出现新通知时立即返回，并附带增加类别的第一页消息；超时则返回 changed=false
+#[utoipa::path(
+    get,
+    path = "/api/notification/poll",
+    tag = "xhs",
+    summary = "通知长轮询",
+    description = "wait 最长 60s (默认 30s)；上游轮询间隔由 XHS_NOTIFICATION_POLL_SECS 配置 (默认 5s)",
+    params(api::notification::poll::PollParams),
+    responses(
+        (status = 200, description = "新通知或超时", body = ApiResponse<api::notification::poll::NotificationPoll>)
+    )
+)]
+pub async fn notification_poll_handler(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Query(params): axum::extract::Query<api::notification::poll::PollParams>,
+) -> impl IntoResponse {
+    use api::notification::poll::{parse_wait, NotificationPoll};
+    
+    let wait = match parse_wait(params.wait.as_deref()) {
+        Ok(wait) => wait,
+        Err(e) => return ApiResponse::error(e.to_string()),
+    };
+    
+    let started = std::time::Instant::now();
+    let (baseline, unread, changed) = match state.notification_poller.wait_for_change(&state.api, wait).await {
+        Ok(result) => result,
+        Err(e) => return ApiResponse::error(e.to_string()),
+    };
+    
+    // 仅拉取有新增的类别
+    let mut response = NotificationPoll {
+        changed,
+        waited_ms: started.elapsed().as_millis() as u64,
+        baseline: baseline.clone(),
+        unread: unread.clone(),
+        mentions: None,
+        likes: None,
+        connections: None,
+    };
+    if unread.mentions > baseline.mentions {
+        response.mentions = api::notification::get_mentions(&state.api).await.ok()
+            .and_then(|r| r.data)
+            .map(|d| d.message_list);
+    }
+    if unread.likes > baseline.likes {
+        response.likes = api::notification::get_likes(&state.api).await.ok()
+            .and_then(|r| r.data)
+            .map(|d| d.message_list);
+    }
+    if unread.connections > baseline.connections {
+        response.connections = api::notification::get_connections(&state.api).await.ok()
+            .and_then(|r| r.data)
+            .map(|d| d.message_list);
+    }
+    
+    ApiResponse::ok(response)
+}
@@ -1,18 +1,27 @@
 //! Search-related HTTP Handlers
 //! 
-//! Handles: trending, recommend, notes, onebox, filter, usersearch
+//! Handles: trending, recommend, notes, notes/all, onebox, filter, usersearch
 
 use axum::{
+    body::Body,
     extract::{State, Query},
+    http::header,
     response::IntoResponse,
     Json,
 };
+use futures_util::StreamExt;
 use std::sync::Arc;
+use tokio::sync::mpsc;
 
 use crate::api;
+use crate::error::XhsError;
+use crate::response::ApiResponse;
 use crate::server::AppState;
+use crate::models::feed::HomefeedItem;
 use crate::models::search::{
     SearchNotesRequest, SearchNotesResponse,
+    SearchAllNotesRequest, SearchAllNotesData, SearchAllNotesSummary,
+    SearchAllStreamLine, SEARCH_ALL_MAX_COUNT,
     SearchOneboxRequest, SearchOneboxResponse,
     SearchFilterResponse,
     SearchUserRequest, SearchUserResponse,
@@ -56,12 +65,7 @@ pub async fn query_trending_handler(
 ) -> impl IntoResponse {
     match api::search::query_trending(&state.api).await {
         Ok(res) => Json(res).into_response(),
-        Err(e) => Json(serde_json::json!({
-            "code": -1,
-            "success": false,
-            "msg": e.to_string(),
-            "data": null
-        })).into_response(),
+        Err(e) => e.into_response(),
     }
 }
 
@@ -86,12 +90,7 @@ pub async fn search_recommend_handler(
 ) -> impl IntoResponse {
     match api::search::recommend_search(&state.api, &params.keyword).await {
         Ok(res) => Json(res).into_response(),
-        Err(e) => Json(serde_json::json!({
-            "code": -1,
-            "success": false,
-            "msg": e.to_string(),
-            "data": null
-        })).into_response(),
+        Err(e) => e.into_response(),
     }
 }
 
@@ -113,14 +112,113 @@ pub async fn search_notes_handler(
     Json(req): Json<SearchNotesRequest>,
 ) -> impl IntoResponse {
     match api::search::search_notes(&state.api, req).await {
-        Ok(res) => Json(res).into_response(),
-        Err(e) => Json(serde_json::json!({
-            "code": -1,
-            "success": false,
-            "msg": e.to_string(),
-            "data": null
-        })).into_response(),
+        Ok(res) => {
+            if let Some(ref data) = res.data {
+                state.note_cache.observe_items(&data.items).await;
+            }
+            Json(res).into_response()
+        }
+        Err(e) => e.into_response(),
+    }
+}
+
+/// 自动翻页搜索笔记
+///
+/// 服务端翻页直到凑够 count 条（去重后）或没有更多结果
+#[utoipa::path(
+    post,
+    path = "/api/search/notes/all",
+    tag = "Search",
+    summary = "自动翻页搜索笔记",
+    description = "按关键词自动翻页搜索，各页共用同一 `search_id`（未传时自动生成），跨页重复的笔记只返回一次，直到凑够 `count` 条（默认 100，最大 1000）或 `has_more=false`。每页请求照常经过出站限流 / 拟人节奏 / 风控熔断。\n\n- 默认：返回 JSON，`data.items` 为笔记列表；中途失败时返回已获取的部分并在 `data.error` 中说明（首页即失败时按普通错误返回）\n- `stream=true`：`application/x-ndjson` 逐条返回，每行一个 `{\"type\":\"item\", ...}`，最后一行为 `{\"type\":\"summary\", ...}`",
+    request_body = SearchAllNotesRequest,
+    responses(
+        (status = 200, description = "笔记列表，或 stream=true 时的 NDJSON 流", body = ApiResponse<SearchAllNotesData>)
+    )
+)]
+pub async fn search_all_notes_handler(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<SearchAllNotesRequest>,
+) -> impl IntoResponse {
+    let mut search = req.search;
+    if search.search_id.as_deref().is_none_or(str::is_empty) {
+        search.search_id = Some(api::search::generate_search_id());
+    }
+    let limit = req.count.clamp(1, SEARCH_ALL_MAX_COUNT);
+    let (tx, mut rx) = mpsc::channel::<HomefeedItem>(64);
+    let crawler = tokio::spawn(async move { collect_search(&state, search, limit, tx).await });
+
+    if !req.stream {
+        let mut items = Vec::new();
+        while let Some(item) = rx.recv().await {
+            items.push(item);
+        }
+        return match crawler.await {
+            Ok((_, Some(e))) if items.is_empty() => e.into_response(),
+            Ok((summary, _)) => ApiResponse::ok(SearchAllNotesData { items, summary }).into_response(),
+            Err(e) => XhsError::Other(anyhow::anyhow!("Search task failed: {}", e)).into_response(),
+        };
+    }
+
+    let lines = futures_util::stream::unfold((rx, Some(crawler)), |(mut rx, crawler)| async move {
+        let line = match rx.recv().await {
+            Some(item) => SearchAllStreamLine::Item(Box::new(item)),
+            None => {
+                // 发送端已关闭，搜索结束，最后输出统计
+                let summary = crawler?.await.map(|(summary, _)| summary).unwrap_or_else(|e| SearchAllNotesSummary {
+                    error: Some(format!("Search task failed: {}", e)),
+                    ..Default::default()
+                });
+                let mut line = serde_json::to_vec(&SearchAllStreamLine::Summary(summary)).unwrap_or_default();
+                line.push(b'\n');
+                return Some((Ok::<_, std::io::Error>(line), (rx, None)));
+            }
+        };
+        let mut bytes = serde_json::to_vec(&line).unwrap_or_default();
+        bytes.push(b'\n');
+        Some((Ok(bytes), (rx, crawler)))
+    });
+
+    ([(header::CONTENT_TYPE, "application/x-ndjson")], Body::from_stream(lines)).into_response()
+}
+
+/// 翻页搜索并逐条发送到 `tx`（接收端关闭时停止），返回统计与中途遇到的错误
+async fn collect_search(
+    state: &AppState,
+    search: SearchNotesRequest,
+    limit: usize,
+    tx: mpsc::Sender<HomefeedItem>,
+) -> (SearchAllNotesSummary, Option<XhsError>) {
+    let mut summary = SearchAllNotesSummary { search_id: search.search_id.clone(), ..Default::default() };
+    let keyword = search.keyword.clone();
+    let mut items = std::pin::pin!(state.api.search_notes_stream(search));
+    let mut error = None;
+    while let Some(result) = items.next().await {
+        match result {
+            Ok(item) => {
+                state.note_cache.observe_items(std::slice::from_ref(&item)).await;
+                if tx.send(item).await.is_err() {
+                    break;
+                }
+                summary.count += 1;
+                if summary.count >= limit {
+                    summary.truncated = true;
+                    break;
+                }
+            }
+            Err(e) => {
+                summary.error = Some(e.to_string());
+                error = Some(e);
+                break;
+            }
+        }
     }
+    tracing::info!(
+        "[SearchAll] '{}': {} note(s){}",
+        keyword, summary.count,
+        summary.error.as_deref().map(|e| format!(", stopped: {}", e)).unwrap_or_default()
+    );
+    (summary, error)
 }
 
 /// 搜索 OneBox
@@ -142,12 +240,7 @@ pub async fn search_onebox_handler(
 ) -> impl IntoResponse {
     match api::search::search_onebox(&state.api, req).await {
         Ok(res) => Json(res).into_response(),
-        Err(e) => Json(serde_json::json!({
-            "code": -1,
-            "success": false,
-            "msg": e.to_string(),
-            "data": null
-        })).into_response(),
+        Err(e) => e.into_response(),
     }
 }
 
@@ -172,12 +265,7 @@ pub async fn search_filter_handler(
 ) -> impl IntoResponse {
     match api::search::search_filter(&state.api, &params.keyword, &params.search_id).await {
         Ok(res) => Json(res).into_response(),
-        Err(e) => Json(serde_json::json!({
-            "code": -1,
-            "success": false,
-            "msg": e.to_string(),
-            "data": null
-        })).into_response(),
+        Err(e) => e.into_response(),
     }
 }
 
@@ -200,11 +288,6 @@ pub async fn search_user_handler(
 ) -> impl IntoResponse {
     match api::search::search_user(&state.api, req).await {
         Ok(res) => Json(res).into_response(),
-        Err(e) => Json(serde_json::json!({
-            "code": -1,
-            "success": false,
-            "msg": e.to_string(),
-            "data": null
-        })).into_response(),
+        Err(e) => e.into_response(),
     }
 }
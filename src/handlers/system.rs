@@ -0,0 +1,104 @@
+//! System HTTP Handlers
+//!
+//! Handles: healthz, readyz, about, system/maintenance
+
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::about::AboutInfo;
+use crate::api::maintenance::MaintenanceStatus;
+use crate::readiness::ReadinessReport;
+use crate::response::ApiResponse;
+use crate::server::AppState;
+
+/// 存活检查响应
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct HealthzResponse {
+    /// 固定为 "ok"
+    pub status: String,
+    /// 当前配置 profile (--profile / XHS_PROFILE，未指定时为 default)
+    pub profile: String,
+    /// 服务版本
+    pub version: String,
+}
+
+/// 存活检查
+///
+/// 返回服务状态与当前生效的配置 profile
+#[utoipa::path(
+    get,
+    path = "/healthz",
+    tag = "System",
+    summary = "存活检查",
+    responses(
+        (status = 200, description = "服务正常", body = HealthzResponse)
+    )
+)]
+pub async fn healthz_handler() -> impl IntoResponse {
+    Json(HealthzResponse {
+        status: "ok".to_string(),
+        profile: crate::config::active_profile().to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+    })
+}
+
+/// 就绪检查
+///
+/// 检查存储后端、签名 Agent 与登录凭证，任一必需组件异常时返回 503
+#[utoipa::path(
+    get,
+    path = "/readyz",
+    tag = "System",
+    summary = "就绪检查",
+    description = "单项检查超时 3 秒；XHS_READYZ_IGNORE（逗号分隔的组件名或前缀，如 credentials,storage.audit）中的组件只展示不影响就绪状态",
+    responses(
+        (status = 200, description = "所有必需组件正常", body = ReadinessReport),
+        (status = 503, description = "存在异常的必需组件", body = ReadinessReport)
+    )
+)]
+pub async fn readyz_handler(
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let report = ReadinessReport::collect(&state).await;
+    let status = if report.is_ready() { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    (status, Json(report))
+}
+
+/// 实例信息
+///
+/// 版本、启用的 features、存储后端、签名策略与已挂载路由，与启动横幅内容一致
+#[utoipa::path(
+    get,
+    path = "/api/about",
+    tag = "System",
+    summary = "实例信息",
+    description = "启动横幅由 XHS_STARTUP_BANNER 控制：full（默认）/ compact / off",
+    responses(
+        (status = 200, description = "实例能力概览", body = AboutInfo)
+    )
+)]
+pub async fn about_handler(
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    Json(AboutInfo::collect(&state).await)
+}
+
+/// 上游维护状态
+///
+/// 检测到 XHS 维护标记后所有出站请求与队列任务暂停，探针成功后按比例逐步恢复
+#[utoipa::path(
+    get,
+    path = "/api/system/maintenance",
+    tag = "System",
+    summary = "上游维护状态",
+    description = "探针间隔见 XHS_MAINTENANCE_PROBE_SECS（默认 60），恢复爬坡时长见 XHS_MAINTENANCE_RAMP_SECS（默认 600）",
+    responses(
+        (status = 200, description = "normal / paused / ramping", body = ApiResponse<MaintenanceStatus>)
+    )
+)]
+pub async fn maintenance_status_handler(
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    ApiResponse::ok(state.api.maintenance().status())
+}
@@ -0,0 +1,41 @@
+//! Tag Page HTTP Handlers
+//!
+//! Handles: tag/{tag_id}/notes
+
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+    Json,
+};
+use std::sync::Arc;
+
+use crate::api;
+use crate::server::AppState;
+
+/// 话题页笔记流
+///
+/// 获取话题落地页的笔记列表，支持 最热/最新 排序与游标分页
+#[utoipa::path(
+    get,
+    path = "/api/tag/{tag_id}/notes",
+    tag = "Tag",
+    summary = "话题页笔记流",
+    description = "与关键词搜索不同，结果限定在话题内。首次请求 cursor 为空，后续使用响应中的 cursor",
+    params(
+        ("tag_id" = String, Path, description = "话题 ID (page_id)"),
+        api::tag::TagNotesParams
+    ),
+    responses(
+        (status = 200, description = "话题笔记列表", body = api::tag::TagNotesResponse)
+    )
+)]
+pub async fn tag_notes_handler(
+    State(state): State<Arc<AppState>>,
+    Path(tag_id): Path<String>,
+    Query(params): Query<api::tag::TagNotesParams>,
+) -> impl IntoResponse {
+    match api::tag::get_tag_notes(&state.api, &tag_id, params).await {
+        Ok(res) => Json(res).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
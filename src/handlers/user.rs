@@ -1,17 +1,20 @@
 //! User HTTP Handlers
 //! 
-//! Handles: user/me
+//! Handles: user/me, user/{user_id}/notes, user/boards, user/followings, user/followers
 
 use axum::{
-    extract::State,
+    extract::{Path, Query, State},
     response::IntoResponse,
     Json,
 };
 use std::sync::Arc;
 
 use crate::api;
+use crate::api::user::boards::{BoardListParams, BoardListResponse, BoardNotesParams, BoardNotesResponse,
+    CreateBoardRequest, BoardMutationResponse};
+use crate::api::user::follows::{FollowKind, FollowListParams, FollowListResponse};
 use crate::server::AppState;
-use crate::models::user::UserMeResponse;
+use crate::models::user::{UserMeResponse, UserPostedParams, UserPostedResponse};
 
 // ============================================================================
 // Handlers
@@ -34,11 +37,173 @@ pub async fn user_me_handler(
 ) -> impl IntoResponse {
     match api::user::get_current_user(&state.api).await {
         Ok(res) => Json(res).into_response(),
-        Err(e) => Json(serde_json::json!({
-            "code": -1,
-            "success": false,
-            "msg": e.to_string(),
-            "data": null
-        })).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// 用户主页-笔记
+/// 
+/// 获取指定用户发布的全部笔记，按 cursor 翻页直到 has_more=false
+#[utoipa::path(
+    get,
+    path = "/api/user/{user_id}/notes",
+    tag = "xhs",
+    summary = "用户笔记列表",
+    params(
+        ("user_id" = String, Path, description = "用户ID"),
+        UserPostedParams
+    ),
+    responses(
+        (status = 200, description = "用户发布的笔记列表", body = UserPostedResponse)
+    )
+)]
+pub async fn user_posted_handler(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<String>,
+    Query(params): Query<UserPostedParams>,
+) -> impl IntoResponse {
+    match api::user::get_user_posted(&state.api, &user_id, params).await {
+        Ok(res) => Json(res).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// 收藏-专辑列表
+/// 
+/// 获取当前用户（或指定 user_id）的收藏专辑
+#[utoipa::path(
+    get,
+    path = "/api/user/boards",
+    tag = "xhs",
+    summary = "专辑列表",
+    params(BoardListParams),
+    responses(
+        (status = 200, description = "专辑列表", body = BoardListResponse)
+    )
+)]
+pub async fn user_boards_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<BoardListParams>,
+) -> impl IntoResponse {
+    match api::user::boards::get_boards(&state.api, params).await {
+        Ok(res) => Json(res).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// 收藏-创建专辑
+#[utoipa::path(
+    post,
+    path = "/api/user/boards",
+    tag = "xhs",
+    summary = "创建专辑",
+    request_body = CreateBoardRequest,
+    responses(
+        (status = 200, description = "创建专辑", body = BoardMutationResponse)
+    )
+)]
+pub async fn create_board_handler(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<CreateBoardRequest>,
+) -> impl IntoResponse {
+    match api::user::boards::create_board(&state.api, req).await {
+        Ok(res) => Json(res).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// 收藏-删除专辑
+#[utoipa::path(
+    delete,
+    path = "/api/user/boards/{board_id}",
+    tag = "xhs",
+    summary = "删除专辑",
+    params(
+        ("board_id" = String, Path, description = "专辑ID")
+    ),
+    responses(
+        (status = 200, description = "删除专辑", body = BoardMutationResponse)
+    )
+)]
+pub async fn delete_board_handler(
+    State(state): State<Arc<AppState>>,
+    Path(board_id): Path<String>,
+) -> impl IntoResponse {
+    match api::user::boards::delete_board(&state.api, &board_id).await {
+        Ok(res) => Json(res).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// 收藏-专辑内笔记
+/// 
+/// 按 cursor 翻页获取专辑内的全部笔记
+#[utoipa::path(
+    get,
+    path = "/api/user/boards/{board_id}/notes",
+    tag = "xhs",
+    summary = "专辑内笔记",
+    params(
+        ("board_id" = String, Path, description = "专辑ID"),
+        BoardNotesParams
+    ),
+    responses(
+        (status = 200, description = "专辑内笔记", body = BoardNotesResponse)
+    )
+)]
+pub async fn board_notes_handler(
+    State(state): State<Arc<AppState>>,
+    Path(board_id): Path<String>,
+    Query(params): Query<BoardNotesParams>,
+) -> impl IntoResponse {
+    match api::user::boards::get_board_notes(&state.api, &board_id, params).await {
+        Ok(res) => Json(res).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// 关系-关注列表
+/// 
+/// 获取当前用户（或指定 user_id）关注的用户，按 cursor 翻页
+#[utoipa::path(
+    get,
+    path = "/api/user/followings",
+    tag = "xhs",
+    summary = "关注列表",
+    params(FollowListParams),
+    responses(
+        (status = 200, description = "关注列表", body = FollowListResponse)
+    )
+)]
+pub async fn user_followings_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<FollowListParams>,
+) -> impl IntoResponse {
+    match api::user::follows::get_follow_list(&state.api, FollowKind::Followings, params).await {
+        Ok(res) => Json(res).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// 关系-粉丝列表
+/// 
+/// 获取当前用户（或指定 user_id）的粉丝，按 cursor 翻页
+#[utoipa::path(
+    get,
+    path = "/api/user/followers",
+    tag = "xhs",
+    summary = "粉丝列表",
+    params(FollowListParams),
+    responses(
+        (status = 200, description = "粉丝列表", body = FollowListResponse)
+    )
+)]
+pub async fn user_followers_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<FollowListParams>,
+) -> impl IntoResponse {
+    match api::user::follows::get_follow_list(&state.api, FollowKind::Followers, params).await {
+        Ok(res) => Json(res).into_response(),
+        Err(e) => e.into_response(),
     }
 }
@@ -0,0 +1,185 @@
+//! User Watch List HTTP Handlers
+//!
+//! Handles: watch/users (list / add), watch/users/{user_id} (get / replace / remove), watch/events
+
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::api::watch::{WatchUserRequest, WatchedUser};
+use crate::archive::events::{EventFilter, NoteEvent, NoteEventKind, DEFAULT_EVENT_LIMIT, MAX_EVENT_LIMIT};
+use crate::response::ApiResponse;
+use crate::server::AppState;
+
+fn user_result(result: anyhow::Result<WatchedUser>) -> ApiResponse<WatchedUser> {
+    ApiResponse::from_result(result.map(WatchedUser::without_known))
+}
+
+/// 添加关注用户
+///
+/// 添加后立即拉取一次主页笔记列表建立基线（不推送），之后每 interval_secs 秒检查一次
+#[utoipa::path(
+    post,
+    path = "/api/watch/users",
+    tag = "Watch",
+    summary = "添加关注用户",
+    description = "定期拉取用户主页笔记列表（`pages` 页，每页 30 条，默认 1，最多 5），与上次结果对比：\n- `published`: 新发布的笔记\n- `deleted`: 从主页消失的笔记（删除或设为仅自己可见）\n\n只检查仍可见的最旧笔记之前的已知笔记，因新笔记发布而滑出抓取范围的旧笔记不算删除；列表突然为空时视为请求异常，不产生删除事件。\n\n事件记录在用户的 `recent_events`，启用 XHS_ARCHIVE_BACKEND 时写入归档（`/api/watch/events` 查询），并 POST 到 `webhook_urls`（未配置时使用 XHS_WEBHOOK_URLS，均未配置则不推送），请求头 `X-Xhs-Event: user_watch`。`interval_secs` 最小 60。",
+    request_body = WatchUserRequest,
+    responses(
+        (status = 200, description = "关注的用户", body = ApiResponse<WatchedUser>)
+    )
+)]
+pub async fn watch_user_handler(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<WatchUserRequest>,
+) -> impl IntoResponse {
+    user_result(state.watch_list.add(req).await)
+}
+
+/// 关注用户列表
+#[utoipa::path(
+    get,
+    path = "/api/watch/users",
+    tag = "Watch",
+    summary = "关注用户列表",
+    responses(
+        (status = 200, description = "全部关注用户（按添加时间排序），含最近一次检查结果与最近事件", body = ApiResponse<Vec<WatchedUser>>)
+    )
+)]
+pub async fn list_watched_users_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    ApiResponse::from_result(
+        state.watch_list.list().await
+            .map(|users| users.into_iter().map(WatchedUser::without_known).collect::<Vec<_>>()),
+    )
+}
+
+/// 查询关注用户
+#[utoipa::path(
+    get,
+    path = "/api/watch/users/{user_id}",
+    tag = "Watch",
+    summary = "查询关注用户",
+    params(
+        ("user_id" = String, Path, description = "用户 ID")
+    ),
+    responses(
+        (status = 200, description = "关注详情", body = ApiResponse<WatchedUser>)
+    )
+)]
+pub async fn get_watched_user_handler(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<String>,
+) -> impl IntoResponse {
+    user_result(match state.watch_list.get(&user_id).await {
+        Ok(Some(user)) => Ok(user),
+        Ok(None) => Err(anyhow::anyhow!("User {} is not watched", user_id)),
+        Err(e) => Err(e),
+    })
+}
+
+/// 修改关注用户
+///
+/// 整体替换配置，保留主页快照与事件记录；重新启用时立即检查一次
+#[utoipa::path(
+    put,
+    path = "/api/watch/users/{user_id}",
+    tag = "Watch",
+    summary = "修改关注用户",
+    params(
+        ("user_id" = String, Path, description = "用户 ID")
+    ),
+    request_body = WatchUserRequest,
+    responses(
+        (status = 200, description = "修改后的关注", body = ApiResponse<WatchedUser>)
+    )
+)]
+pub async fn update_watched_user_handler(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<String>,
+    Json(req): Json<WatchUserRequest>,
+) -> impl IntoResponse {
+    user_result(state.watch_list.update(&user_id, req).await)
+}
+
+/// 取消关注用户
+#[utoipa::path(
+    delete,
+    path = "/api/watch/users/{user_id}",
+    tag = "Watch",
+    summary = "取消关注用户",
+    params(
+        ("user_id" = String, Path, description = "用户 ID")
+    ),
+    responses(
+        (status = 200, description = "取消结果", body = ApiResponse<WatchedUser>)
+    )
+)]
+pub async fn unwatch_user_handler(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<String>,
+) -> impl IntoResponse {
+    let user = match state.watch_list.get(&user_id).await {
+        Ok(Some(user)) => user,
+        Ok(None) => return user_result(Err(anyhow::anyhow!("User {} is not watched", user_id))),
+        Err(e) => return user_result(Err(e)),
+    };
+    user_result(state.watch_list.remove(&user_id).await.map(|_| user))
+}
+
+/// 笔记事件查询参数
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct WatchEventsParams {
+    /// 作者 user_id
+    #[serde(default)]
+    pub user_id: Option<String>,
+    /// 事件类型: published / deleted
+    #[serde(default)]
+    #[param(inline)]
+    pub kind: Option<NoteEventKind>,
+    /// 检测时间下限 (ms)
+    #[serde(default)]
+    pub since: Option<i64>,
+    /// 检测时间上限 (ms)
+    #[serde(default)]
+    pub until: Option<i64>,
+    /// 返回条数，默认 50，最大 500
+    #[serde(default = "default_event_limit")]
+    pub limit: usize,
+}
+
+fn default_event_limit() -> usize { DEFAULT_EVENT_LIMIT }
+
+/// 查询笔记事件
+///
+/// 读取归档中关注用户的发布 / 删除事件（需启用 XHS_ARCHIVE_BACKEND）
+#[utoipa::path(
+    get,
+    path = "/api/watch/events",
+    tag = "Watch",
+    summary = "查询笔记事件",
+    params(WatchEventsParams),
+    responses(
+        (status = 200, description = "按检测时间倒序", body = ApiResponse<Vec<NoteEvent>>)
+    )
+)]
+pub async fn watch_events_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<WatchEventsParams>,
+) -> impl IntoResponse {
+    let Some(archive) = state.api.archive() else {
+        return ApiResponse::error("Response archive is disabled (set XHS_ARCHIVE_BACKEND=file|mongo)");
+    };
+
+    let filter = EventFilter {
+        user_id: params.user_id,
+        kind: params.kind,
+        since: params.since,
+        until: params.until,
+        limit: params.limit.clamp(1, MAX_EVENT_LIMIT),
+    };
+    ApiResponse::from_result(archive.query_events(&filter).await)
+}
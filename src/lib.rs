@@ -1,15 +1,63 @@
+//! XHS-RS-TOOLS
+//!
+//! Cargo features（默认 `server`）:
+//!
+//! | 组合 | 包含内容 |
+//! | :--- | :--- |
+//! | `default-features = false` | HTTP 客户端 (`XhsApiClient`)、签名、认证、模型、文件归档 |
+//! | `+ qrcode` | 终端二维码打印 (`utils::print_qr_to_terminal`) |
+//! | `+ mongodb` | 多实例协调队列 (`coordination`)、MongoDB 归档后端 |
+//! | `+ agent-manager` | Python Agent 子进程管理 (`agent_manager`) |
+//! | `+ otel` | OpenTelemetry OTLP 导出 (`telemetry::otlp`) |
+//! | `+ export` | 归档导出为 CSV / Parquet (`archive::export`，依赖 parquet / arrow / csv) |
+//! | `+ tls-impersonate` | Chrome 风格 TLS ClientHello / HTTP2 设置 (`client::TlsProfile::Chrome`，`XHS_TLS_PROFILE=chrome`)，不包含在 `server` 中 |
+//! | `+ strict-parse` | 上游响应模型拒绝未知字段 (`models::parse`)，用于开发 / CI 发现上游结构变化，不包含在 `server` 中 |
+//! | `+ swagger` | Swagger UI 静态资源（仅在 `server` 中使用） |
+//! | `server` (默认) | 以上全部 + axum 路由 / handlers / OpenAPI 文档 / `xhs-rs` 二进制与命令行 |
+//!
+//! 只需 HTTP 客户端 + 模型的嵌入方:
+//! ```toml
+//! xhs-rs = { version = "0.1", default-features = false, features = ["qrcode"] }
+//! ```
+
 pub mod api;
+pub mod error;  // 统一错误类型 (XhsError)
+pub mod response;  // 统一响应信封 (ApiResponse) 与请求 ID
 pub mod auth;  // New authentication module
 pub mod client;
 pub mod models;
 pub mod utils;
+#[cfg(feature = "server")]
 pub mod server;
+#[cfg(feature = "server")]
 pub mod handlers;  // HTTP request handlers (modular)
+#[cfg(feature = "server")]
 pub mod openapi;   // OpenAPI documentation
+#[cfg(feature = "server")]
+pub mod about;     // 启动横幅与 /api/about
+#[cfg(feature = "server")]
+pub mod readiness; // /readyz 就绪检查
+#[cfg(feature = "server")]
+pub mod cli;       // xhs-rs 命令行子命令
+#[cfg(feature = "server")]
+pub mod tenant;    // 多租户 JWT 认证
+#[cfg(feature = "server")]
+pub mod tls;       // 内置 HTTPS (证书文件 / ACME)
+#[cfg(feature = "server")]
+pub mod reload;    // 配置热加载
+#[cfg(feature = "server")]
+pub mod cache;     // 读接口响应缓存 (内存 / Redis)
 pub mod signature;  // 纯算法签名服务模块
+#[cfg(feature = "agent-manager")]
 pub mod agent_manager;  // Python Agent 进程管理
 pub mod config;  // 配置管理 (环境变量)
+#[cfg(feature = "mongodb")]
+pub mod coordination;  // 多实例抓取协调 (MongoDB 租约队列)
+pub mod activity;  // 账号行为画像 (活跃时段 / 配比 / 每日上限)
+pub mod archive;  // 原始响应归档 (zstd 压缩)
+pub mod audit;  // 上游请求审计日志
+pub mod telemetry;  // 链路追踪 span 与 OTLP 导出
 
 pub use client::XhsClient;
+pub use error::XhsError;
 pub use auth::{UserCredentials, CredentialStorage, AuthService};
-
@@ -1,13 +1,25 @@
-use xhs_rs::server;
 use xhs_rs::agent_manager;
+use xhs_rs::config;
+use xhs_rs::cli::{self, Cli, Command};
+use clap::Parser;
 use tracing::{info, warn, error};
+use tracing_subscriber::filter::{filter_fn, FilterExt, LevelFilter};
 use tracing_subscriber::fmt::time::OffsetTime;
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::reload;
+use xhs_rs::telemetry::{self, otlp::OtlpTracing};
 use time::UtcOffset;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    // Load environment variables from .env file
-    dotenv::dotenv().ok();
+    // Load profile config (config/<profile>.env) and .env
+    let args: Vec<String> = std::env::args().collect();
+    let profile = config::profile_from_args(&args);
+    config::load_profile(profile.as_deref())?;
+    
+    // No subcommand: run the HTTP server as before
+    let command = Cli::parse().command.unwrap_or(Command::Serve);
     
     // Initialize logging with local timezone
     let offset = UtcOffset::current_local_offset().unwrap_or(UtcOffset::from_hms(8, 0, 0).unwrap());
@@ -15,11 +27,36 @@ async fn main() -> anyhow::Result<()> {
         "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3]"
     ));
     
-    tracing_subscriber::fmt()
+    // CLI subcommands print their results to stdout; keep their logs quiet and on stderr
+    let (default_level, writer) = if command.is_serve() {
+        (tracing::Level::INFO, BoxMakeWriter::new(std::io::stdout))
+    } else {
+        (tracing::Level::WARN, BoxMakeWriter::new(std::io::stderr))
+    };
+    // XHS_LOG_LEVEL 可热加载，级别过滤放在 reload 层中
+    let level = config::log_level().unwrap_or(default_level);
+    let (level_filter, level_handle) = reload::Layer::new(LevelFilter::from_level(level));
+    // 控制台只输出事件（不带 span 上下文）；配置 OTLP 时 span 另行导出
+    let otlp = OtlpTracing::from_env()?;
+    let fmt_layer = tracing_subscriber::fmt::layer()
         .with_timer(timer)
+        .with_writer(writer)
+        .with_filter(filter_fn(|meta| meta.is_event()).and(level_filter));
+    let otel_layer = otlp.as_ref().map(|otlp| otlp.layer().with_filter(LevelFilter::INFO));
+    tracing_subscriber::registry()
+        .with(fmt_layer)
+        .with(otel_layer)
         .init();
+    telemetry::register_log_level(default_level, level, move |level| {
+        level_handle.reload(LevelFilter::from_level(level)).map_err(|e| e.to_string())
+    });
     
-    info!("Starting XHS Rust Tools Server...");
+    if command.is_serve() {
+        info!("Starting XHS Rust Tools Server (profile: {})...", config::active_profile());
+    }
+    if let Some(otlp) = &otlp {
+        info!("[Telemetry] Exporting spans via OTLP to {}", otlp.endpoint());
+    }
     
     // 自动启动 Python Signature Agent (除非设置了 SKIP_LOCAL_AGENT)
     if std::env::var("SKIP_LOCAL_AGENT").is_err() {
@@ -37,10 +74,16 @@ async fn main() -> anyhow::Result<()> {
     // 设置 Ctrl+C 信号处理，确保清理 Agent
     let shutdown = tokio::signal::ctrl_c();
     
+    let is_serve = command.is_serve();
+    let mut outcome = Ok(());
     tokio::select! {
-        result = server::start_server() => {
+        result = cli::run(command) => {
             if let Err(e) = result {
-                error!("Server error: {}", e);
+                if is_serve {
+                    error!("Server error: {}", e);
+                } else {
+                    outcome = Err(e);
+                }
             }
         }
         _ = shutdown => {
@@ -50,7 +93,12 @@ async fn main() -> anyhow::Result<()> {
     
     // 清理 Agent 进程
     agent_manager::stop_agent();
-    info!("Server stopped");
+    if let Some(otlp) = &otlp {
+        otlp.shutdown();
+    }
+    if is_serve {
+        info!("Server stopped");
+    }
 
-    Ok(())
+    outcome
 }
@@ -0,0 +1,9 @@
+//! OpenAPI 示例数据
+//!
+//! 由 build.rs 从 `fixtures/openapi/*.json`（脱敏后的真实接口响应录制）生成，
+//! 每个文件对应一个同名函数，在模型上通过
+//! `#[schema(example = crate::models::examples::homefeed_response)]` 引用。
+//!
+//! 模型新增字段后重新录制对应 fixture 即可，无需手工维护示例 JSON。
+
+include!(concat!(env!("OUT_DIR"), "/openapi_examples.rs"));
@@ -1,7 +1,9 @@
 pub mod recommend;
+pub mod tuning;
 
 // Re-export common types for convenience
 pub use recommend::{
     HomefeedRequest, HomefeedResponse, HomefeedData, HomefeedItem,
     NoteCard, NoteUser, NoteCover, CoverImageInfo, InteractInfo, NoteVideo, VideoCapa,
 };
+pub use tuning::FeedTuning;
@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
+use super::tuning::{FeedTuning, REFRESH_TYPE_INITIAL};
+use crate::models::partial::{parse_items, ItemParseError};
+
 /// Homefeed request body - 主页发现请求参数
 /// 
 /// 详细分页规则请参阅 `doc/homefeed_pagination.md`
@@ -58,42 +61,43 @@ pub struct HomefeedRequest {
     pub need_filter_image: bool,
 }
 
-fn default_num() -> i32 { 47 }
-fn default_refresh_type() -> i32 { 1 }
+fn default_num() -> i32 { FeedTuning::INITIAL.num }
+fn default_refresh_type() -> i32 { REFRESH_TYPE_INITIAL }
 fn default_category() -> String { "homefeed_recommend".to_string() }
-fn default_need_num() -> i32 { 22 }
+fn default_need_num() -> i32 { FeedTuning::INITIAL.need_num }
 fn default_image_formats() -> Vec<String> { vec!["jpg".to_string(), "webp".to_string(), "avif".to_string()] }
 
-impl Default for HomefeedRequest {
-    fn default() -> Self {
+impl HomefeedRequest {
+    /// 按 refresh_type 对应的 [`FeedTuning`] 预设构造请求
+    pub fn with_tuning(category: String, refresh_type: i32) -> Self {
+        let tuning = FeedTuning::for_refresh_type(refresh_type);
         Self {
             cursor_score: String::new(),
-            num: 47,
-            refresh_type: 1,
-            note_index: 34,
+            num: tuning.num,
+            refresh_type,
+            note_index: tuning.note_index,
             unread_begin_note_id: String::new(),
             unread_end_note_id: String::new(),
             unread_note_count: 0,
-            category: "homefeed_recommend".to_string(),
+            category,
             search_key: String::new(),
-            need_num: 22,
-            image_formats: vec!["jpg".to_string(), "webp".to_string(), "avif".to_string()],
+            need_num: tuning.need_num,
+            image_formats: default_image_formats(),
             need_filter_image: false,
         }
     }
 }
 
+impl Default for HomefeedRequest {
+    fn default() -> Self {
+        Self::with_tuning(default_category(), REFRESH_TYPE_INITIAL)
+    }
+}
+
 /// Homefeed response - 主页发现响应
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
-#[schema(example = json!({
-    "code": 0,
-    "success": true,
-    "msg": "成功",
-    "data": {
-        "cursor_score": "1.7681358649530034E9",
-        "items": []
-    }
-}))]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
+#[schema(example = crate::models::examples::homefeed_response)]
 pub struct HomefeedResponse {
     pub code: i32,
     pub success: bool,
@@ -101,21 +105,42 @@ pub struct HomefeedResponse {
     pub msg: Option<String>,
     #[serde(default)]
     pub data: Option<HomefeedData>,
+    /// 是否为短时间内相同请求的复用结果（未真正请求上游）
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub deduplicated: bool,
 }
 
 /// 主页内容数据
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(from = "RawHomefeedData")]
 pub struct HomefeedData {
     /// 分页游标
-    #[serde(default)]
     pub cursor_score: Option<String>,
-    /// 笔记列表
-    #[serde(default)]
+    /// 笔记列表（仅包含解析成功的条目）
     pub items: Vec<HomefeedItem>,
+    /// 解析失败的条目
+    pub parse_errors: Vec<ItemParseError>,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
+struct RawHomefeedData {
+    #[serde(default)]
+    cursor_score: Option<String>,
+    #[serde(default)]
+    items: Vec<serde_json::Value>,
+}
+
+impl From<RawHomefeedData> for HomefeedData {
+    fn from(raw: RawHomefeedData) -> Self {
+        let (items, parse_errors) = parse_items(raw.items);
+        Self { cursor_score: raw.cursor_score, items, parse_errors }
+    }
 }
 
 /// 单条笔记项
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
 #[schema(example = json!({
     "id": "69539b19000000002202c106",
     "model_type": "note",
@@ -145,6 +170,7 @@ pub struct HomefeedItem {
 
 /// 笔记卡片信息
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
 #[schema(example = json!({
     "type": "normal",
     "display_title": "男生没方向，一定要去闯闯的6个职业！",
@@ -176,6 +202,7 @@ pub struct NoteCard {
 
 /// 笔记作者信息
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
 #[schema(example = json!({
     "user_id": "664ec6ef0000000007004173",
     "nickname": "小李学姐爱学习",
@@ -201,6 +228,7 @@ pub struct NoteUser {
 
 /// 笔记封面信息
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
 pub struct NoteCover {
     /// 宽度
     #[serde(default)]
@@ -224,6 +252,7 @@ pub struct NoteCover {
 
 /// 封面图片信息
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
 pub struct CoverImageInfo {
     /// 场景类型 (WB_PRV, WB_DFT)
     #[serde(default)]
@@ -235,9 +264,17 @@ pub struct CoverImageInfo {
 
 /// 笔记互动信息
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
+///
+/// 计数为上游展示文本（如 "1008"、"1.2万"、"10万+"），数值见 `InteractInfo::parse_count`。
+/// Feed 卡片通常只返回点赞，搜索结果与详情接口会返回全部字段。
 #[schema(example = json!({
     "liked": false,
-    "liked_count": "1008"
+    "liked_count": "1008",
+    "collected": false,
+    "collected_count": "652",
+    "comment_count": "87",
+    "share_count": "143"
 }))]
 pub struct InteractInfo {
     /// 是否已点赞
@@ -246,10 +283,44 @@ pub struct InteractInfo {
     /// 点赞数
     #[serde(default)]
     pub liked_count: Option<String>,
+    /// 是否已收藏
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collected: Option<bool>,
+    /// 收藏数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collected_count: Option<String>,
+    /// 评论数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment_count: Option<String>,
+    /// 分享数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub share_count: Option<String>,
+    /// 是否已关注作者
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub followed: Option<bool>,
+}
+
+impl InteractInfo {
+    /// 解析展示计数："1008" -> 1008，"1.2万" -> 12000，"10万+" -> 100000
+    pub fn parse_count(text: &str) -> Option<i64> {
+        let text = text.trim().trim_end_matches('+');
+        let (number, multiplier) = if let Some(n) = text.strip_suffix('万').or_else(|| text.strip_suffix('w')) {
+            (n, 10_000.0)
+        } else if let Some(n) = text.strip_suffix('亿') {
+            (n, 100_000_000.0)
+        } else if let Some(n) = text.strip_suffix('k') {
+            (n, 1_000.0)
+        } else {
+            (text, 1.0)
+        };
+        let value: f64 = number.trim().parse().ok()?;
+        Some((value * multiplier).round() as i64)
+    }
 }
 
 /// 视频信息
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
 pub struct NoteVideo {
     /// 视频能力信息
     #[serde(default)]
@@ -258,6 +329,7 @@ pub struct NoteVideo {
 
 /// 视频能力信息
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
 pub struct VideoCapa {
     /// 视频时长(秒)
     #[serde(default)]
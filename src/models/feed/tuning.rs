@@ -0,0 +1,63 @@
+//! Homefeed 分页参数调优 (Feed Tuning)
+//!
+//! 集中管理 homefeed payload 中 `num` / `need_num` / `note_index` 的取值。
+//! 这些数值来自对 Web 端真实请求的抓包观察，payload 不一致（如首次加载
+//! 与滚动加载使用不同的 num）会增加签名校验失败或触发风控的概率。
+//!
+//! 分页规则详见 `doc/homefeed_pagination.md`
+
+/// 首次加载 (进入频道页)
+pub const REFRESH_TYPE_INITIAL: i32 = 1;
+/// 滚动加载更多
+pub const REFRESH_TYPE_SCROLL: i32 = 3;
+
+/// Homefeed 请求的数量参数预设
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeedTuning {
+    /// 请求数量，Web 端固定为 43
+    pub num: i32,
+    /// 期望返回数量，实际由服务端决定（通常 ≤ need_num）
+    pub need_num: i32,
+    /// 该 refresh_type 下首个请求使用的 note_index
+    pub note_index: i32,
+}
+
+impl FeedTuning {
+    /// 首次加载 (refresh_type=1)：浏览器进入频道页时发出的首个请求
+    pub const INITIAL: FeedTuning = FeedTuning {
+        num: 43,
+        need_num: 18,
+        note_index: 35,
+    };
+
+    /// 滚动加载 (refresh_type=3)：num/need_num 与首次加载保持一致，
+    /// note_index 需按 [`FeedTuning::next_note_index`] 累加计算
+    pub const SCROLL: FeedTuning = FeedTuning {
+        num: 43,
+        need_num: 18,
+        note_index: 0,
+    };
+
+    /// 根据 refresh_type 选择预设，未知值按首次加载处理
+    pub fn for_refresh_type(refresh_type: i32) -> FeedTuning {
+        match refresh_type {
+            REFRESH_TYPE_SCROLL => Self::SCROLL,
+            _ => Self::INITIAL,
+        }
+    }
+
+    /// 计算下一页的 note_index
+    ///
+    /// - 第二次请求: 0 + 首次返回数量 + 1
+    /// - 后续请求: 上次 note_index + 上次返回数量 + 1
+    pub fn next_note_index(prev_note_index: i32, returned: usize, is_first_page: bool) -> i32 {
+        let base = if is_first_page { 0 } else { prev_note_index };
+        base + returned as i32 + 1
+    }
+}
+
+impl Default for FeedTuning {
+    fn default() -> Self {
+        Self::INITIAL
+    }
+}
@@ -1,4 +1,7 @@
+pub mod examples;
 pub mod feed;
 pub mod login;
+pub mod parse;
+pub mod partial;
 pub mod search;
 pub mod user;
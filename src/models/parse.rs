@@ -0,0 +1,41 @@
+//! 上游响应解析模式
+//!
+//! 默认宽松：模型忽略未声明的字段，上游新增字段不影响解析。
+//! 启用 `strict-parse` feature 时，上游响应模型带 `deny_unknown_fields`，任何未建模的字段都会使解析失败，
+//! 用于在开发 / CI 中尽早发现上游结构变化；生产构建不要启用。
+//!
+//! 两种模式下解析失败都会给出出错字段的完整路径（如 `data.items[3].note_card.cover: invalid type ...`）。
+
+use serde::de::DeserializeOwned;
+
+use crate::error::XhsError;
+
+/// 是否为严格模式（`strict-parse` feature）
+pub const STRICT: bool = cfg!(feature = "strict-parse");
+
+/// 解析上游响应体，错误信息包含出错字段的路径
+pub fn from_str<T: DeserializeOwned>(text: &str) -> Result<T, XhsError> {
+    let mut deserializer = serde_json::Deserializer::from_str(text);
+    let value = serde_path_to_error::deserialize(&mut deserializer).map_err(|e| {
+        let message = describe(&e);
+        if STRICT {
+            tracing::warn!("[Parse] {} does not match upstream: {}", std::any::type_name::<T>(), message);
+        }
+        XhsError::Parse(message)
+    })?;
+    deserializer.end()?;
+    Ok(value)
+}
+
+/// 解析 JSON 值，错误信息包含出错字段的路径
+pub fn from_value<T: DeserializeOwned>(value: serde_json::Value) -> Result<T, String> {
+    serde_path_to_error::deserialize(value).map_err(|e| describe(&e))
+}
+
+/// `path: error`；出错位置在顶层时只有错误信息
+fn describe(error: &serde_path_to_error::Error<serde_json::Error>) -> String {
+    match error.path().to_string().as_str() {
+        "." => error.inner().to_string(),
+        path => format!("{}: {}", path, error.inner()),
+    }
+}
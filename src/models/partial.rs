@@ -0,0 +1,47 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// 原始片段最大长度（字符）
+const RAW_SNIPPET_CHARS: usize = 200;
+
+/// 列表中单个元素的解析失败信息
+///
+/// 列表按元素逐个解析，个别元素结构异常时不影响整页返回
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "index": 3,
+    "error": "missing field `id`",
+    "raw": "{\"model_type\":\"note\",\"note_card\":{...}}"
+}))]
+pub struct ItemParseError {
+    /// 元素在原始列表中的下标
+    pub index: usize,
+    /// serde 错误信息（含出错字段的路径）
+    pub error: String,
+    /// 原始 JSON 片段（截断）
+    pub raw: String,
+}
+
+/// 逐个解析列表元素，返回成功的元素与失败信息
+pub fn parse_items<T: DeserializeOwned>(values: Vec<serde_json::Value>) -> (Vec<T>, Vec<ItemParseError>) {
+    let mut items = Vec::with_capacity(values.len());
+    let mut errors = Vec::new();
+
+    for (index, value) in values.into_iter().enumerate() {
+        let raw = value.to_string();
+        match super::parse::from_value::<T>(value) {
+            Ok(item) => items.push(item),
+            Err(e) => {
+                tracing::warn!("[Parse] Skipped list item {}: {}", index, e);
+                errors.push(ItemParseError {
+                    index,
+                    error: e,
+                    raw: raw.chars().take(RAW_SNIPPET_CHARS).collect(),
+                });
+            }
+        }
+    }
+
+    (items, errors)
+}
@@ -2,8 +2,10 @@ use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 use super::feed::HomefeedItem;
+use super::partial::{parse_items, ItemParseError};
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
 pub struct QueryTrendingResponse {
     pub code: i32,
     pub success: bool,
@@ -14,6 +16,7 @@ pub struct QueryTrendingResponse {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
 pub struct QueryTrendingData {
     pub word_request_id: String,
     pub title: String,
@@ -22,6 +25,7 @@ pub struct QueryTrendingData {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
 pub struct TrendingQuery {
     pub title: String,
     pub desc: Option<String>,
@@ -32,6 +36,7 @@ pub struct TrendingQuery {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
 pub struct TrendingHintWord {
     #[serde(rename = "type")]
     pub hint_type: String,
@@ -42,6 +47,7 @@ pub struct TrendingHintWord {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
 #[schema(example = json!({
     "code": 0,
     "success": true,
@@ -63,6 +69,7 @@ pub struct SearchRecommendResponse {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
 pub struct SearchRecommendData {
     pub search_cpl_id: Option<String>,
     pub word_request_id: Option<String>,
@@ -71,6 +78,7 @@ pub struct SearchRecommendData {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
 pub struct SugItem {
     #[serde(rename = "type")]
     pub item_type: String,
@@ -80,22 +88,151 @@ pub struct SugItem {
 
 // =================== Search Notes ===================
 
+/// 排序方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchSort {
+    /// 综合
+    #[default]
+    General,
+    /// 最新
+    TimeDescending,
+    /// 最多点赞
+    PopularityDescending,
+}
+
+impl SearchSort {
+    /// 上游 sort / sort_type 取值
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::General => "general",
+            Self::TimeDescending => "time_descending",
+            Self::PopularityDescending => "popularity_descending",
+        }
+    }
+}
+
+/// 笔记类型
+///
+/// 也接受旧版整数取值: 0=all, 1=image, 2=video
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchNoteType {
+    /// 不限
+    #[default]
+    All,
+    /// 图文笔记
+    Image,
+    /// 视频笔记
+    Video,
+}
+
+impl SearchNoteType {
+    /// 上游 note_type 取值
+    pub fn code(self) -> i32 {
+        match self {
+            Self::All => 0,
+            Self::Image => 1,
+            Self::Video => 2,
+        }
+    }
+
+    /// filter_note_type 标签
+    fn tag(self) -> &'static str {
+        match self {
+            Self::All => "不限",
+            Self::Image => "普通笔记",
+            Self::Video => "视频笔记",
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SearchNoteType {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Code(i64),
+            Name(String),
+        }
+        match Raw::deserialize(deserializer)? {
+            Raw::Code(0) => Ok(Self::All),
+            Raw::Code(1) => Ok(Self::Image),
+            Raw::Code(2) => Ok(Self::Video),
+            Raw::Name(name) if name == "all" => Ok(Self::All),
+            Raw::Name(name) if name == "image" => Ok(Self::Image),
+            Raw::Name(name) if name == "video" => Ok(Self::Video),
+            Raw::Code(code) => Err(serde::de::Error::custom(format!("invalid note_type {}, expected 0, 1 or 2", code))),
+            Raw::Name(name) => Err(serde::de::Error::unknown_variant(&name, &["all", "image", "video"])),
+        }
+    }
+}
+
+/// 发布时间
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchTimeRange {
+    /// 不限
+    #[default]
+    Any,
+    /// 一天内
+    Day,
+    /// 一周内
+    Week,
+    /// 半年内
+    HalfYear,
+}
+
+impl SearchTimeRange {
+    /// filter_note_time 标签
+    fn tag(self) -> &'static str {
+        match self {
+            Self::Any => "不限",
+            Self::Day => "一天内",
+            Self::Week => "一周内",
+            Self::HalfYear => "半年内",
+        }
+    }
+}
+
+/// 搜索范围
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchNoteRange {
+    /// 不限
+    #[default]
+    Any,
+    /// 已看过
+    Viewed,
+    /// 未看过
+    NotViewed,
+    /// 已关注
+    Followed,
+}
+
+impl SearchNoteRange {
+    /// filter_note_range 标签
+    fn tag(self) -> &'static str {
+        match self {
+            Self::Any => "不限",
+            Self::Viewed => "已看过",
+            Self::NotViewed => "未看过",
+            Self::Followed => "已关注",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[schema(example = json!({
     "keyword": "搜索关键词",
     "page": 1,
     "page_size": 20,
     "sort": "general",
-    "note_type": 0,
+    "note_type": "all",
+    "time_range": "any",
+    "note_range": "any",
     "search_id": "search_id_example",
     "ext_flags": [],
-    "filters": [
-        {"tags": ["general"], "type": "sort_type"},
-        {"tags": ["不限"], "type": "filter_note_type"},
-        {"tags": ["不限"], "type": "filter_note_time"},
-        {"tags": ["不限"], "type": "filter_note_range"},
-        {"tags": ["不限"], "type": "filter_pos_distance"}
-    ],
     "geo": "",
     "image_formats": ["jpg", "webp", "avif"]
 }))]
@@ -107,19 +244,21 @@ pub struct SearchNotesRequest {
     pub page_size: i32,
     #[serde(default)]
     pub search_id: Option<String>,
-    #[serde(default = "default_sort")]
-    pub sort: String,
-    /// 笔记类型: 0=综合(默认), 1=图文, 2=视频
+    /// 排序方式
+    #[serde(default)]
+    pub sort: SearchSort,
+    /// 笔记类型
     #[serde(default)]
-    pub note_type: i32,
+    pub note_type: SearchNoteType,
+    /// 发布时间
+    #[serde(default)]
+    pub time_range: SearchTimeRange,
+    /// 搜索范围
+    #[serde(default)]
+    pub note_range: SearchNoteRange,
     /// 扩展筛选标志 (通常为空数组)
     #[serde(default)]
     pub ext_flags: Vec<serde_json::Value>,
-    /// 筛选条件 (必需字段)
-    /// 
-    /// 包含排序和筛选类型，使用默认值即可
-    #[serde(default = "default_filters")]
-    pub filters: Vec<SearchFilterOption>,
     #[serde(default)]
     pub geo: String,
     #[serde(default = "default_image_formats")]
@@ -128,16 +267,40 @@ pub struct SearchNotesRequest {
 
 fn default_page() -> i32 { 1 }
 fn default_page_size() -> i32 { 20 }
-fn default_sort() -> String { "general".to_string() }
 fn default_image_formats() -> Vec<String> { vec!["jpg".to_string(), "webp".to_string(), "avif".to_string()] }
-fn default_filters() -> Vec<SearchFilterOption> {
-    vec![
-        SearchFilterOption { tags: vec!["general".to_string()], filter_type: "sort_type".to_string() },
-        SearchFilterOption { tags: vec!["不限".to_string()], filter_type: "filter_note_type".to_string() },
-        SearchFilterOption { tags: vec!["不限".to_string()], filter_type: "filter_note_time".to_string() },
-        SearchFilterOption { tags: vec!["不限".to_string()], filter_type: "filter_note_range".to_string() },
-        SearchFilterOption { tags: vec!["不限".to_string()], filter_type: "filter_pos_distance".to_string() },
-    ]
+
+impl SearchNotesRequest {
+    /// 关键词搜索第一页，其余条件取默认值
+    pub fn new(keyword: impl Into<String>) -> Self {
+        Self {
+            keyword: keyword.into(),
+            page: default_page(),
+            page_size: default_page_size(),
+            search_id: None,
+            sort: SearchSort::default(),
+            note_type: SearchNoteType::default(),
+            time_range: SearchTimeRange::default(),
+            note_range: SearchNoteRange::default(),
+            ext_flags: Vec::new(),
+            geo: String::new(),
+            image_formats: default_image_formats(),
+        }
+    }
+
+    /// 上游 filters 载荷（与 sort / note_type 始终一致）
+    pub fn filters(&self) -> Vec<SearchFilterOption> {
+        let filter = |filter_type: &str, tag: &str| SearchFilterOption {
+            tags: vec![tag.to_string()],
+            filter_type: filter_type.to_string(),
+        };
+        vec![
+            filter("sort_type", self.sort.as_str()),
+            filter("filter_note_type", self.note_type.tag()),
+            filter("filter_note_time", self.time_range.tag()),
+            filter("filter_note_range", self.note_range.tag()),
+            filter("filter_pos_distance", "不限"),
+        ]
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -148,6 +311,8 @@ pub struct SearchFilterOption {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
+#[schema(example = crate::models::examples::search_notes_response)]
 pub struct SearchNotesResponse {
     pub code: i32,
     pub success: bool,
@@ -155,16 +320,97 @@ pub struct SearchNotesResponse {
     pub msg: Option<String>,
     #[serde(default)]
     pub data: Option<SearchNotesData>,
+    /// 是否为短时间内相同请求的复用结果（未真正请求上游）
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub deduplicated: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(from = "RawSearchNotesData")]
 pub struct SearchNotesData {
     /// 搜索会话ID (用于关联 onebox 等后续请求)
-    #[serde(default)]
     pub search_id: Option<String>,
     pub has_more: bool,
+    /// 笔记列表（仅包含解析成功的条目）
+    pub items: Vec<HomefeedItem>,
+    /// 解析失败的条目
+    pub parse_errors: Vec<ItemParseError>,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
+struct RawSearchNotesData {
+    #[serde(default)]
+    search_id: Option<String>,
+    #[serde(default)]
+    has_more: bool,
+    #[serde(default)]
+    items: Vec<serde_json::Value>,
+}
+
+impl From<RawSearchNotesData> for SearchNotesData {
+    fn from(raw: RawSearchNotesData) -> Self {
+        let (items, parse_errors) = parse_items(raw.items);
+        Self { search_id: raw.search_id, has_more: raw.has_more, items, parse_errors }
+    }
+}
+
+// =================== Search Notes (auto-pagination) ===================
+
+/// 单次自动翻页搜索最多返回的笔记数
+pub const SEARCH_ALL_MAX_COUNT: usize = 1000;
+
+/// 自动翻页搜索请求
+///
+/// 除 `count` / `stream` 外与 [`SearchNotesRequest`] 相同（`page` 为起始页）
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+#[schema(example = json!({
+    "keyword": "杭州旅游",
+    "count": 100,
+    "sort": "general",
+    "note_type": 0
+}))]
+pub struct SearchAllNotesRequest {
+    #[serde(flatten)]
+    pub search: SearchNotesRequest,
+    /// 目标笔记数（去重后），默认 100，最大 1000
+    #[serde(default = "default_search_all_count")]
+    pub count: usize,
+    /// 以 NDJSON 逐条流式返回
+    #[serde(default)]
+    pub stream: bool,
+}
+
+fn default_search_all_count() -> usize { 100 }
+
+/// 自动翻页搜索统计
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct SearchAllNotesSummary {
+    /// 各页共用的搜索会话ID
+    pub search_id: Option<String>,
+    /// 返回的笔记数
+    pub count: usize,
+    /// 是否因达到 count 提前停止（上游可能还有更多结果）
+    pub truncated: bool,
+    /// 中途失败的原因（已返回的笔记仍然有效）
     #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// 自动翻页搜索结果
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SearchAllNotesData {
     pub items: Vec<HomefeedItem>,
+    #[serde(flatten)]
+    pub summary: SearchAllNotesSummary,
+}
+
+/// 流式响应的每一行：笔记逐条输出，最后一行为统计
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SearchAllStreamLine {
+    Item(Box<HomefeedItem>),
+    Summary(SearchAllNotesSummary),
 }
 
 // =================== Search OneBox ===================
@@ -184,18 +430,23 @@ pub struct SearchOneboxRequest {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
 pub struct SearchOneboxResponse {
     pub code: i32,
     pub success: bool,
     #[serde(default)]
     pub msg: Option<String>,
     #[serde(default)]
-    pub data: Option<serde_json::Value>, 
+    pub data: Option<serde_json::Value>,
+    /// 是否为短时间内相同请求的复用结果（未真正请求上游）
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub deduplicated: bool,
 }
 
 // =================== Search Filter ===================
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
 pub struct SearchFilterResponse {
     pub code: i32,
     pub success: bool,
@@ -206,12 +457,14 @@ pub struct SearchFilterResponse {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
 pub struct SearchFilterData {
     #[serde(default)]
     pub filters: Vec<FilterItem>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
 pub struct FilterItem {
     #[serde(rename = "type")]
     pub filter_type: String,
@@ -222,6 +475,7 @@ pub struct FilterItem {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
 pub struct FilterTag {
     pub id: String,
     pub name: String,
@@ -261,6 +515,8 @@ fn default_page_size_15() -> i32 { 15 }
 fn default_biz_type_user() -> String { "web_search_user".to_string() }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
+#[schema(example = crate::models::examples::search_user_response)]
 pub struct SearchUserResponse {
     pub code: i32,
     pub success: bool,
@@ -268,16 +524,39 @@ pub struct SearchUserResponse {
     pub msg: Option<String>,
     #[serde(default)]
     pub data: Option<SearchUserData>,
+    /// 是否为短时间内相同请求的复用结果（未真正请求上游）
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub deduplicated: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(from = "RawSearchUserData")]
 pub struct SearchUserData {
     pub has_more: bool,
-    #[serde(default)]
+    /// 用户列表（仅包含解析成功的条目）
     pub users: Vec<SearchUserItem>,
+    /// 解析失败的条目
+    pub parse_errors: Vec<ItemParseError>,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
+struct RawSearchUserData {
+    #[serde(default)]
+    has_more: bool,
+    #[serde(default)]
+    users: Vec<serde_json::Value>,
+}
+
+impl From<RawSearchUserData> for SearchUserData {
+    fn from(raw: RawSearchUserData) -> Self {
+        let (users, parse_errors) = parse_items(raw.users);
+        Self { has_more: raw.has_more, users, parse_errors }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
 pub struct SearchUserItem {
     pub id: String,
     pub name: String,
@@ -1,7 +1,11 @@
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
+use super::feed::{InteractInfo, NoteCover, NoteUser};
+use super::partial::{parse_items, ItemParseError};
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
 #[schema(example = json!({
     "code": 0,
     "success": true,
@@ -25,6 +29,7 @@ pub struct UserMeResponse {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
 #[schema(example = json!({
     "user_id": "5ceac80d00000000xxxxxxxx",
     "red_id": "123456789",
@@ -45,3 +50,117 @@ pub struct UserInfo {
     pub images: Option<String>,
     pub imageb: Option<String>,
 }
+
+// =================== User Posted Notes ===================
+
+/// 用户笔记列表请求参数
+#[derive(Debug, Clone, Deserialize, utoipa::IntoParams)]
+pub struct UserPostedParams {
+    /// 分页游标，首次请求为空，后续使用响应中的 cursor
+    #[serde(default)]
+    pub cursor: Option<String>,
+    /// 每页数量
+    #[serde(default = "default_posted_num")]
+    #[param(default = 30, minimum = 1, maximum = 30)]
+    pub num: i32,
+    /// 安全令牌（从用户主页链接或笔记作者信息中获取，可选）
+    #[serde(default)]
+    pub xsec_token: Option<String>,
+    /// 令牌来源，默认 pc_feed
+    #[serde(default = "default_xsec_source")]
+    pub xsec_source: String,
+}
+
+fn default_posted_num() -> i32 { 30 }
+fn default_xsec_source() -> String { "pc_feed".to_string() }
+
+impl Default for UserPostedParams {
+    fn default() -> Self {
+        Self {
+            cursor: None,
+            num: default_posted_num(),
+            xsec_token: None,
+            xsec_source: default_xsec_source(),
+        }
+    }
+}
+
+/// 用户笔记列表响应
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
+#[schema(example = crate::models::examples::user_posted_response)]
+pub struct UserPostedResponse {
+    #[serde(default)]
+    pub code: i32,
+    pub success: bool,
+    #[serde(default)]
+    pub msg: Option<String>,
+    #[serde(default)]
+    pub data: Option<UserPostedData>,
+}
+
+/// 用户笔记列表数据
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(from = "RawUserPostedData")]
+pub struct UserPostedData {
+    /// 下一页游标
+    pub cursor: Option<String>,
+    pub has_more: bool,
+    /// 笔记列表（仅包含解析成功的条目）
+    pub notes: Vec<PostedNote>,
+    /// 解析失败的条目
+    pub parse_errors: Vec<ItemParseError>,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
+struct RawUserPostedData {
+    #[serde(default)]
+    cursor: Option<String>,
+    #[serde(default)]
+    has_more: bool,
+    #[serde(default)]
+    notes: Vec<serde_json::Value>,
+}
+
+impl From<RawUserPostedData> for UserPostedData {
+    fn from(raw: RawUserPostedData) -> Self {
+        let (notes, parse_errors) = parse_items(raw.notes);
+        Self { cursor: raw.cursor, has_more: raw.has_more, notes, parse_errors }
+    }
+}
+
+/// 用户发布的笔记
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "strict-parse", serde(deny_unknown_fields))]
+#[schema(example = json!({
+    "note_id": "69539b19000000002202c106",
+    "type": "normal",
+    "display_title": "笔记标题",
+    "xsec_token": "ABgmZhb7UheMUTk-zbKLSjLizyXRfHgBLRwTg3lxgVx_s=",
+    "user": {},
+    "cover": {},
+    "interact_info": {}
+}))]
+pub struct PostedNote {
+    /// 笔记ID
+    pub note_id: String,
+    /// 笔记类型 (normal, video)
+    #[serde(rename = "type", default)]
+    pub note_type: Option<String>,
+    /// 展示标题
+    #[serde(default)]
+    pub display_title: Option<String>,
+    /// 安全Token（用于获取详情）
+    #[serde(default)]
+    pub xsec_token: Option<String>,
+    /// 作者信息
+    #[serde(default)]
+    pub user: Option<NoteUser>,
+    /// 封面信息
+    #[serde(default)]
+    pub cover: Option<NoteCover>,
+    /// 互动信息
+    #[serde(default)]
+    pub interact_info: Option<InteractInfo>,
+}
@@ -5,38 +5,96 @@
 use utoipa::OpenApi;
 
 use crate::{
+    error::ApiErrorBody,
     models::{
+        partial::ItemParseError,
         feed::{HomefeedRequest, HomefeedResponse, HomefeedData, HomefeedItem, NoteCard, NoteUser, NoteCover, CoverImageInfo, InteractInfo, NoteVideo, VideoCapa},
         search::{QueryTrendingResponse, QueryTrendingData, TrendingQuery, TrendingHintWord, SearchRecommendResponse, SearchRecommendData, SugItem,
             SearchNotesRequest, SearchNotesResponse, SearchNotesData, SearchFilterOption,
+            SearchSort, SearchNoteType, SearchTimeRange, SearchNoteRange,
+            SearchAllNotesRequest, SearchAllNotesData, SearchAllNotesSummary, SearchAllStreamLine,
             SearchOneboxRequest, SearchOneboxResponse,
             SearchFilterResponse, SearchFilterData, FilterItem, FilterTag,
             SearchUserRequest, SearchUserResponse, SearchUserData, SearchUserItem
         },
-        user::{UserMeResponse, UserInfo},
+        user::{UserMeResponse, UserInfo, UserPostedResponse, UserPostedData, PostedNote},
     },
     api::notification::{
         mentions::{MentionsResponse, MentionsData},
         connections::{ConnectionsResponse, ConnectionsData},
         likes::{LikesResponse, LikesData},
+        poll::{NotificationPoll, UnreadCount},
     },
-    api::login::{GuestInitResponse, CreateQrCodeResponse, PollStatusResponse, QrCodeStatusData, LoginInfo},
+    api::login::{GuestSession, QrCodeInfo, QrStatus, QrStatusWait, QrCodeState, QrCodeStatusData, LoginInfo,
+        CookieInput, ImportCookiesRequest, ImportedAccount, ExportedCredentials},
+    activity::{ActivityUsage, RequestCategory},
+    api::user::boards::{Board, BoardListResponse, BoardListData, BoardNotesResponse, BoardNotesData,
+        CreateBoardRequest, BoardMutationResponse},
+    api::user::follows::{FollowUser, FollowListResponse, FollowListData},
+    api::tag::{TagSort, TagNotesResponse, TagNotesData, TagNote},
+    utils::url::XhsLink,
+    signature::metrics::{AgentLatency, AgentLatencyMetrics, EndpointStrategyMetrics, LatencyBucket, StrategyCounts},
+    audit::{AuditRecord, SignatureMode},
+    config::{ConfigSetting, ConfigSource},
+    readiness::{ReadinessReport, ComponentStatus},
+    archive::{ArchiveEntry, DictionaryInfo, notes::ArchivedNote, history::{FieldChange, NoteField, NoteRevision}},
+    api::maintenance::{MaintenanceStatus, MaintenancePhase, CanaryResult},
+    api::rate_limit::BucketStatus,
+    api::pacing::StealthStatus,
+    api::risk::{RiskStatus, CircuitStatus, CircuitState},
+    api::rotation::{AccountPoolStatus, PooledAccount, Quarantine, RotationStrategy},
+    api::verification::VerificationChallenge,
+    about::{AboutInfo, StorageInfo, SigningStrategy, RouteInfo},
+    auth::export::ExportFormat,
+    auth::health::{AccountHealth, HealthCheck},
     api::note::detail::{NoteDetailRequest, NoteDetailResponse},
+    api::note::stats::NoteStats,
+    api::note::resolve::ResolvedNote,
+    api::note::comments::{AllCommentsRequest, CommentCrawlSummary, CommentStreamLine, NoteComment},
     api::media::{
         video::{VideoRequest, VideoResponse, VideoData, VideoItem},
         images::{ImagesRequest, ImagesResponse, ImagesData, ImageItem},
-        download::{DownloadRequest, DownloadResponse, DownloadData},
+        download::{DownloadRequest, DownloadResponse, DownloadData, DownloadTarget},
+        bundle::{BundleRequest, BundleUploadData},
+        sidecar::SidecarFormat,
+        jobs::{DownloadProgress, DownloadStatus},
+        signed_url::{SignFileRequest, SignedFile},
     },
     handlers::search as search_handlers,
     handlers::auth as auth_handlers,
     handlers::notification as notification_handlers,
     handlers::user as user_handlers,
-
+    handlers::feed as feed_handlers,
     handlers::media as media_handlers,
     handlers::creator as creator_handlers,
+    handlers::cluster as cluster_handlers,
+    handlers::activity as activity_handlers,
+    handlers::tag as tag_handlers,
+    handlers::links as links_handlers,
+    handlers::metrics as metrics_handlers,
+    handlers::system as system_handlers,
+    handlers::archive as archive_handlers,
+    handlers::admin as admin_handlers,
+    handlers::jobs as jobs_handlers,
+    handlers::monitors as monitor_handlers,
+    handlers::watch as watch_handlers,
+    handlers::debug as debug_handlers,
+    coordination::{CrawlTask, InstanceInfo},
     api,
+    api::crawl::{CrawlJob, JobTask, CrawlJobRequest},
+    api::monitor::{KeywordMonitor, MonitorFilters, MonitorMatch, MonitorRequest},
+    api::watch::{WatchedUser, WatchUserRequest},
+    archive::events::{NoteEvent, NoteEventKind},
     api::creator::{
-        models::{CreatorQrcodeCreateRequest, CreatorQrcodeStatusRequest, CreatorUserInfo, CreatorHomeInfo, CreatorGrowInfo}
+        models::{CreatorQrcodeCreateRequest, CreatorQrcodeStatusRequest, CreatorUserInfo, CreatorHomeInfo, CreatorGrowInfo},
+        publish::{PublishImageRequest, PublishedNote, ImageSource, Topic,
+            video::{PublishVideoRequest, PublishVideoJob}, jobs::{PublishProgress, PublishStage},
+            schedule::{ScheduledPost, ScheduledContent, ScheduleStatus, SchedulePostRequest}},
+        drafts::{Draft, DraftUpdate, DraftList},
+        fans::{FansPortrait, FansOverview, DistributionItem, FansAnalytics},
+        notes::{CreatorNote, CreatorNoteStatus, CreatorNotesData},
+        comments::{CreatorComment, CommentUser, CreatorCommentsData, ReplyCommentRequest,
+            HideCommentRequest, CommentMutation},
     }
 };
 
@@ -46,47 +104,184 @@ use crate::{
         search_handlers::query_trending_handler,
         search_handlers::search_recommend_handler,
         search_handlers::search_notes_handler,
+        search_handlers::search_all_notes_handler,
         search_handlers::search_onebox_handler,
         search_handlers::search_filter_handler,
         search_handlers::search_user_handler,
         user_handlers::user_me_handler,
+        user_handlers::user_posted_handler,
+        user_handlers::user_boards_handler,
+        user_handlers::create_board_handler,
+        user_handlers::delete_board_handler,
+        user_handlers::board_notes_handler,
+        user_handlers::user_followings_handler,
+        user_handlers::user_followers_handler,
         auth_handlers::guest_init_handler,
         auth_handlers::create_qrcode_handler,
         auth_handlers::poll_qrcode_status_handler,
+        auth_handlers::wait_qrcode_status_handler,
+        auth_handlers::qrcode_events_handler,
+        auth_handlers::import_cookies_handler,
+        auth_handlers::export_credentials_handler,
+        auth_handlers::credential_health_handler,
+        auth_handlers::auth_status_handler,
+        auth_handlers::verification_handler,
+        auth_handlers::resolve_verification_handler,
         api::feed::category::get_category_feed,
+        feed_handlers::feed_stream_handler,
         api::note::page::get_note_page,
+        api::note::comments::crawl_all_comments,
         api::note::detail::get_note_detail,
+        api::note::stats::get_note_stats,
+        api::note::resolve::resolve_note,
         notification_handlers::mentions_handler,
         notification_handlers::connections_handler,
         notification_handlers::likes_handler,
+        notification_handlers::notification_poll_handler,
         media_handlers::images_handler,
         media_handlers::download_handler,
+        media_handlers::download_job_handler,
+        media_handlers::bundle_handler,
+        media_handlers::share_file_handler,
+        media_handlers::serve_file_handler,
         creator_handlers::creator_guest_init_handler,
         creator_handlers::creator_create_qrcode_handler,
         creator_handlers::creator_check_qrcode_status,
         creator_handlers::creator_user_info_handler,
         creator_handlers::creator_home_info_handler,
+        creator_handlers::creator_publish_image_handler,
+        creator_handlers::creator_publish_video_handler,
+        creator_handlers::creator_publish_job_handler,
+        creator_handlers::creator_schedule_handler,
+        creator_handlers::creator_schedule_list_handler,
+        creator_handlers::creator_scheduled_post_handler,
+        creator_handlers::creator_cancel_schedule_handler,
+        creator_handlers::creator_drafts_handler,
+        creator_handlers::creator_draft_handler,
+        creator_handlers::creator_update_draft_handler,
+        creator_handlers::creator_delete_draft_handler,
+        creator_handlers::creator_fans_portrait_handler,
+        creator_handlers::creator_notes_handler,
+        creator_handlers::creator_comments_handler,
+        creator_handlers::creator_reply_comment_handler,
+        creator_handlers::creator_delete_comment_handler,
+        creator_handlers::creator_hide_comment_handler,
+        cluster_handlers::cluster_status_handler,
+        cluster_handlers::cluster_enqueue_handler,
+        activity_handlers::activity_status_handler,
+        tag_handlers::tag_notes_handler,
+        links_handlers::parse_link_handler,
+        metrics_handlers::signature_metrics_handler,
+        system_handlers::healthz_handler,
+        system_handlers::readyz_handler,
+        system_handlers::about_handler,
+        system_handlers::maintenance_status_handler,
+        admin_handlers::risk_status_handler,
+        admin_handlers::audit_log_handler,
+        admin_handlers::config_handler,
+        admin_handlers::account_pool_handler,
+        admin_handlers::release_account_handler,
+        admin_handlers::import_har_handler,
+        admin_handlers::list_credentials_handler,
+        admin_handlers::invalidate_credentials_handler,
+        admin_handlers::delete_credentials_handler,
+        admin_handlers::promote_credentials_handler,
+        debug_handlers::debug_sign_handler,
+        archive_handlers::archive_list_handler,
+        archive_handlers::archive_read_handler,
+        archive_handlers::archive_train_dictionary_handler,
+        archive_handlers::archive_note_handler,
+        archive_handlers::archived_notes_handler,
+        archive_handlers::note_history_handler,
+        archive_handlers::archived_comments_handler,
+        archive_handlers::archive_export_handler,
+        jobs_handlers::create_job_handler,
+        jobs_handlers::list_jobs_handler,
+        jobs_handlers::get_job_handler,
+        jobs_handlers::update_job_handler,
+        jobs_handlers::delete_job_handler,
+        monitor_handlers::create_monitor_handler,
+        monitor_handlers::list_monitors_handler,
+        monitor_handlers::get_monitor_handler,
+        monitor_handlers::update_monitor_handler,
+        monitor_handlers::delete_monitor_handler,
+        watch_handlers::watch_user_handler,
+        watch_handlers::list_watched_users_handler,
+        watch_handlers::get_watched_user_handler,
+        watch_handlers::update_watched_user_handler,
+        watch_handlers::unwatch_user_handler,
+        watch_handlers::watch_events_handler,
     ),
     components(
         schemas(
-            GuestInitResponse, CreateQrCodeResponse, PollStatusResponse, QrCodeStatusData, LoginInfo,
+            ApiErrorBody,
+            GuestSession, QrCodeInfo, QrStatus, QrStatusWait, QrCodeState, QrCodeStatusData, LoginInfo,
+            CookieInput, ImportCookiesRequest, ImportedAccount,
+            ExportFormat, ExportedCredentials,
+            AccountHealth, HealthCheck,
+            auth_handlers::AuthStatus, auth_handlers::AuthSessionStatus,
+            auth_handlers::VerificationStatus, VerificationChallenge,
             QueryTrendingResponse, QueryTrendingData, TrendingQuery, TrendingHintWord,
             SearchRecommendResponse, SearchRecommendData, SugItem,
             SearchNotesRequest, SearchNotesResponse, SearchNotesData, SearchFilterOption,
+            SearchSort, SearchNoteType, SearchTimeRange, SearchNoteRange,
+            SearchAllNotesRequest, SearchAllNotesData, SearchAllNotesSummary, SearchAllStreamLine,
             SearchOneboxRequest, SearchOneboxResponse,
             SearchFilterResponse, SearchFilterData, FilterItem, FilterTag,
             SearchUserRequest, SearchUserResponse, SearchUserData, SearchUserItem,
-            UserMeResponse, UserInfo,
+            UserMeResponse, UserInfo, UserPostedResponse, UserPostedData, PostedNote,
+            Board, BoardListResponse, BoardListData, BoardNotesResponse, BoardNotesData, CreateBoardRequest, BoardMutationResponse,
+            FollowUser, FollowListResponse, FollowListData,
             MentionsResponse, MentionsData,
             ConnectionsResponse, ConnectionsData,
             LikesResponse, LikesData,
+            NotificationPoll, UnreadCount,
+            ItemParseError,
             HomefeedRequest, HomefeedResponse, HomefeedData, HomefeedItem, NoteCard, NoteUser, NoteCover, CoverImageInfo, InteractInfo, NoteVideo, VideoCapa,
-            NoteDetailRequest, NoteDetailResponse,
+            feed_handlers::FeedStreamControl, feed_handlers::FeedStreamMessage,
+            NoteDetailRequest, NoteDetailResponse, NoteStats, ResolvedNote,
+            AllCommentsRequest, CommentCrawlSummary, CommentStreamLine, NoteComment,
             VideoRequest, VideoResponse, VideoData, VideoItem,
             ImagesRequest, ImagesResponse, ImagesData, ImageItem,
-            DownloadRequest, DownloadResponse, DownloadData,
+            DownloadRequest, DownloadResponse, DownloadData, DownloadTarget,
+            BundleRequest, BundleUploadData, SidecarFormat,
+            DownloadProgress, DownloadStatus,
+            SignFileRequest, SignedFile,
             CreatorQrcodeCreateRequest, CreatorQrcodeStatusRequest,
-            CreatorUserInfo, CreatorHomeInfo, CreatorGrowInfo
+            CreatorUserInfo, CreatorHomeInfo, CreatorGrowInfo,
+            PublishImageRequest, PublishedNote, ImageSource, Topic,
+            PublishVideoRequest, PublishVideoJob, PublishProgress, PublishStage,
+            Draft, DraftUpdate, DraftList,
+            FansPortrait, FansOverview, DistributionItem, FansAnalytics,
+            CreatorNote, CreatorNoteStatus, CreatorNotesData,
+            CreatorComment, CommentUser, CreatorCommentsData, ReplyCommentRequest,
+            HideCommentRequest, CommentMutation,
+            ScheduledPost, ScheduledContent, ScheduleStatus, SchedulePostRequest,
+            CrawlTask, InstanceInfo,
+            cluster_handlers::ClusterStatus, cluster_handlers::EnqueueTaskRequest,
+            activity_handlers::ActivityStatus, ActivityUsage, RequestCategory, BucketStatus, StealthStatus,
+            TagSort, TagNotesResponse, TagNotesData, TagNote,
+            XhsLink, links_handlers::ParsedLink,
+            metrics_handlers::SignatureMetrics, EndpointStrategyMetrics, StrategyCounts, AgentLatencyMetrics, AgentLatency, LatencyBucket,
+            system_handlers::HealthzResponse,
+            ReadinessReport, ComponentStatus,
+            MaintenanceStatus, MaintenancePhase, CanaryResult,
+            AboutInfo, StorageInfo, SigningStrategy, RouteInfo,
+            archive_handlers::ArchiveList, archive_handlers::TrainDictionaryRequest,
+            archive_handlers::ArchiveNoteRequest, archive_handlers::ArchiveNoteResult,
+            archive_handlers::NoteHistory, NoteRevision, FieldChange, NoteField, archive_handlers::ArchivedComments,
+            ArchiveEntry, DictionaryInfo, ArchivedNote,
+            CrawlJob, JobTask, CrawlJobRequest,
+            KeywordMonitor, MonitorFilters, MonitorMatch, MonitorRequest,
+            WatchedUser, WatchUserRequest, NoteEvent, NoteEventKind,
+            RiskStatus, CircuitStatus, CircuitState,
+            AuditRecord, SignatureMode,
+            admin_handlers::EffectiveConfig, ConfigSetting, ConfigSource,
+            AccountPoolStatus, PooledAccount, Quarantine, RotationStrategy,
+            admin_handlers::HarImport, admin_handlers::ImportedSignature,
+            admin_handlers::StoredAccount,
+            api::dry_run::PreparedRequest, api::dry_run::PreparedHeader,
+            debug_handlers::DebugSignRequest, debug_handlers::DebugSignature, debug_handlers::SignedHeaders
         )
     ),
     tags(
@@ -94,9 +289,21 @@ use crate::{
         (name = "auth", description = "用户认证 (User Auth)"),
         (name = "Creator", description = "创作者中心认证 (Creator Auth)"),
         (name = "Feed", description = "主页发现频道：recommend(推荐)、fashion(穿搭)、food(美食)、cosmetics(彩妆)、movie_and_tv(影视)、career(职场)、love(情感)、household_product(家居)、gaming(游戏)、travel(旅行)、fitness(健身)"),
-        (name = "Note", description = "笔记相关接口：detail(详情)、page(评论)、video(视频地址)"),
+        (name = "Note", description = "笔记相关接口：detail(详情)、resolve(链接解析)、stats(互动数据)、page(评论)、video(视频地址)"),
         (name = "Media", description = "媒体文件操作：video(视频地址解析)、images(图片地址解析)、download(通用媒体下载)"),
-        (name = "Search", description = "搜索相关接口：notes(笔记)、usersearch(用户)、onebox(聚合)、recommend(推荐)、filter(筛选)")
+        (name = "Search", description = "搜索相关接口：notes(笔记)、notes/all(自动翻页)、usersearch(用户)、onebox(聚合)、recommend(推荐)、filter(筛选)"),
+        (name = "Cluster", description = "多实例抓取协调：status(集群状态)、tasks(任务入队)"),
+        (name = "Activity", description = "账号行为画像：活跃时段、请求配比、每日上限"),
+        (name = "Tag", description = "话题页：notes(话题笔记流，最热/最新)"),
+        (name = "Links", description = "链接工具：parse(展开 xhslink.com 短链，解析笔记 / 用户主页 / 话题链接并生成规范链接)"),
+        (name = "Metrics", description = "运行指标：signature(签名策略与兜底比例)"),
+        (name = "System", description = "系统接口：healthz(存活检查，含当前配置 profile)、readyz(就绪检查：存储 / 签名 Agent / 登录凭证)、about(版本 / features / 存储 / 签名策略 / 路由清单)、maintenance(上游维护检测与暂停状态)"),
+        (name = "Archive", description = "原始响应归档：zstd 压缩存储 (文件 / MongoDB)，读取时透明解压；笔记归档：持久化结构化详情与媒体地址并按条件查询"),
+        (name = "Admin", description = "运维接口：risk(461 风控熔断状态)、audit(上游请求审计日志)、config(当前生效配置 / 热加载)、accounts(账号池)、credentials(账号凭据管理)、import-har(从 HAR 导入 Cookie 与存储签名)"),
+        (name = "Debug", description = "调试接口：sign(只生成签名不发送请求，便于与浏览器抓包对比)"),
+        (name = "Jobs", description = "定时抓取任务：按间隔执行关键词搜索 / 频道快照 / 笔记归档，持久化存储"),
+        (name = "Monitors", description = "关键词监控：定期搜索关键词，新笔记满足过滤条件时记录日志并推送 webhook"),
+        (name = "Watch", description = "用户关注列表：定期检查用户主页，发现新发布 / 删除的笔记时写入归档并推送 webhook")
     )
 )]
 pub struct ApiDoc;
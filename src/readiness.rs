@@ -0,0 +1,140 @@
+//! 就绪检查 (Readiness)
+//!
+//! `/readyz` 逐项检查服务对外可用所依赖的组件，供 Kubernetes 等编排系统判断是否转发流量:
+//! - `storage.*`: 已启用的存储后端（文件后端检查目录可写，MongoDB 执行 `ping`）
+//! - `agent`: 签名 Agent 可用 ([`SignatureService::is_agent_available`])
+//! - `credentials`: 已登录且凭证未被标记失效
+//!
+//! 各项并发执行，单项超时 [`CHECK_TIMEOUT`]。`XHS_READYZ_IGNORE`（逗号分隔的组件名或前缀，
+//! 如 `credentials,storage.audit`）中的组件照常检查与展示，但不影响就绪状态。
+//! `/healthz` 只表示进程存活，不做这些检查。
+
+use anyhow::{anyhow, Result};
+use futures_util::future::{join_all, BoxFuture};
+use futures_util::FutureExt;
+use serde::Serialize;
+use std::future::Future;
+use std::time::{Duration, Instant};
+use utoipa::ToSchema;
+
+use crate::server::AppState;
+use crate::signature::SignatureService;
+
+/// 单项检查超时，避免不可达的 MongoDB 阻塞探针
+pub const CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// 单个组件的检查结果
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ComponentStatus {
+    /// 组件名，如 agent、credentials、storage.crawl_jobs
+    pub name: String,
+    pub healthy: bool,
+    /// 是否影响就绪状态（XHS_READYZ_IGNORE 中的组件为 false）
+    pub required: bool,
+    /// 后端描述，检查失败时为失败原因
+    pub detail: String,
+    /// 检查耗时 (ms)
+    pub latency_ms: u64,
+}
+
+/// 就绪检查结果
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ReadinessReport {
+    /// ready / not_ready
+    pub status: String,
+    pub components: Vec<ComponentStatus>,
+}
+
+impl ReadinessReport {
+    /// 所有必需组件均正常
+    pub fn is_ready(&self) -> bool {
+        self.components.iter().all(|c| c.healthy || !c.required)
+    }
+
+    pub async fn collect(state: &AppState) -> Self {
+        let mut checks: Vec<(String, BoxFuture<'_, Result<String>>)> = vec![
+            storage("publish_schedule", state.publish_schedule.describe(), state.publish_schedule.ping()),
+            storage("crawl_jobs", state.crawl_jobs.describe(), state.crawl_jobs.ping()),
+            storage("monitors", state.monitors.describe(), state.monitors.ping()),
+            storage("watch_list", state.watch_list.describe(), state.watch_list.ping()),
+            storage("login_sessions", state.login_sessions.describe(), state.login_sessions.ping()),
+        ];
+        if let Some(archive) = state.api.archive() {
+            checks.push(storage("archive", archive.describe(), archive.ping()));
+        }
+        if let Some(audit) = state.api.audit() {
+            checks.push(storage("audit", audit.describe(), audit.ping()));
+        }
+        if let Some(cache) = &state.response_cache {
+            checks.push(storage("response_cache", cache.describe(), cache.ping()));
+        }
+        if let Some(queue) = &state.coordinator {
+            checks.push(storage("coordination", &format!("mongo: instance {}", queue.instance_id()), queue.ping()));
+        }
+
+        let agent_url = crate::config::get_agent_url().to_string();
+        checks.push((
+            "agent".to_string(),
+            async move {
+                if SignatureService::new().is_agent_available().await {
+                    Ok(agent_url)
+                } else {
+                    Err(anyhow!("Agent at {} is not available", agent_url))
+                }
+            }
+            .boxed(),
+        ));
+        checks.push((
+            "credentials".to_string(),
+            async move {
+                match state.auth.try_get_credentials().await? {
+                    Some(creds) if creds.is_valid => Ok(format!("user {}", creds.user_id)),
+                    Some(creds) => Err(anyhow!("Credentials of user {} are marked invalid, please log in again", creds.user_id)),
+                    None => Err(anyhow!("Not logged in")),
+                }
+            }
+            .boxed(),
+        ));
+
+        let ignored = ignored_components();
+        let components = join_all(checks.into_iter().map(|(name, check)| {
+            let required = !ignored.iter().any(|prefix| name.starts_with(prefix.as_str()));
+            async move {
+                let started = Instant::now();
+                let result = tokio::time::timeout(CHECK_TIMEOUT, check)
+                    .await
+                    .unwrap_or_else(|_| Err(anyhow!("Timed out after {}s", CHECK_TIMEOUT.as_secs())));
+                let (healthy, detail) = match result {
+                    Ok(detail) => (true, detail),
+                    Err(e) => (false, e.to_string()),
+                };
+                ComponentStatus { name, healthy, required, detail, latency_ms: started.elapsed().as_millis() as u64 }
+            }
+        }))
+        .await;
+
+        let mut report = Self { status: String::new(), components };
+        report.status = if report.is_ready() { "ready" } else { "not_ready" }.to_string();
+        report
+    }
+}
+
+/// 存储检查：成功时以后端描述作为 detail
+fn storage<'a>(
+    name: &str,
+    describe: &str,
+    ping: impl Future<Output = Result<()>> + Send + 'a,
+) -> (String, BoxFuture<'a, Result<String>>) {
+    let describe = describe.to_string();
+    (format!("storage.{}", name), ping.map(|r| r.map(|_| describe)).boxed())
+}
+
+/// XHS_READYZ_IGNORE 中的组件名 / 前缀
+fn ignored_components() -> Vec<String> {
+    std::env::var("XHS_READYZ_IGNORE")
+        .unwrap_or_default()
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
@@ -0,0 +1,116 @@
+//! 配置热加载 (Hot Reload)
+//!
+//! 监听 profile 配置文件（`--profile`）与 .env，修改后重新读取 [`config::RELOADABLE_KEYS`]
+//! 并立即生效，无需重启:
+//! - `XHS_RATE_LIMIT_*`: 出站限流器（租户在 tenants.json 中单独设置的限流不受影响）
+//! - `XHS_PROXY` / `XHS_NO_PROXY`: 重建 XhsApiClient 的 HTTP 客户端（Cookie 保留）；
+//!   并丢弃下载 / 创作者中心共用的进程级客户端，下次请求按新代理重建
+//! - `XHS_WEBHOOK_URLS` / `XHS_WEBHOOK_SECRET`: 通知 webhook 下一轮检查生效，关键词监控 / 用户关注下次投递生效
+//! - `XHS_LOG_LEVEL`: 控制台日志级别
+//!
+//! 另外监听 `XHS_ACTIVITY_PROFILES` 指向的行为画像文件，修改后重新加载各账号画像（当日用量保留）。
+//!
+//! 进程环境变量中设置的配置项优先级更高，不会被配置文件覆盖。`XHS_CONFIG_WATCH=off` 关闭监听。
+//! 当前生效的取值见 `GET /api/admin/config`。
+
+use anyhow::Result;
+use notify::{RecursiveMode, Watcher};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::activity;
+use crate::config;
+use crate::server::AppState;
+use crate::telemetry;
+use crate::tenant::TenantGateway;
+
+/// 编辑器保存时可能连续产生多个事件，合并后再加载
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// 启动配置文件监听
+pub fn spawn_watcher(state: Arc<AppState>, gateway: Option<Arc<TenantGateway>>) -> Result<()> {
+    if !config::config_watch_enabled() {
+        return Ok(());
+    }
+    let mut files = config::config_files();
+    files.push(PathBuf::from(activity::profiles_path()));
+    let names: Vec<OsString> = files.iter().filter_map(|f| f.file_name().map(OsString::from)).collect();
+
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        if let Ok(event) = event {
+            if !event.kind.is_access() && event.paths.iter().any(|p| is_config_path(p, &names)) {
+                let _ = tx.send(());
+            }
+        }
+    })?;
+    // 监听所在目录：编辑器与 ConfigMap 更新通常是替换文件而不是原地写入
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    for file in &files {
+        let dir = file.parent().filter(|d| !d.as_os_str().is_empty()).unwrap_or(Path::new(".")).to_path_buf();
+        if !dirs.contains(&dir) {
+            watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+            dirs.push(dir);
+        }
+    }
+    tracing::info!(
+        "[Config] Watching {} for changes",
+        files.iter().map(|f| f.display().to_string()).collect::<Vec<_>>().join(", ")
+    );
+
+    tokio::spawn(async move {
+        let _watcher = watcher;
+        while rx.recv().await.is_some() {
+            tokio::time::sleep(DEBOUNCE).await;
+            while rx.try_recv().is_ok() {}
+            if let Err(e) = apply(&state, gateway.as_deref()) {
+                tracing::warn!("[Config] Reload failed, keeping the current settings: {}", e);
+            }
+        }
+    });
+    Ok(())
+}
+
+/// 配置文件本身，或 Kubernetes ConfigMap 挂载目录中被替换的 `..data` 链接
+fn is_config_path(path: &Path, names: &[OsString]) -> bool {
+    path.file_name()
+        .is_some_and(|name| names.iter().any(|n| n == name) || name.to_string_lossy().starts_with(".."))
+}
+
+/// 重新读取配置文件并应用有变化的配置项，返回这些配置项
+pub fn apply(state: &AppState, gateway: Option<&TenantGateway>) -> Result<Vec<&'static str>> {
+    match state.api.activity().reload_from_env() {
+        Ok(true) => tracing::info!("[Config] Reloaded activity profiles from {}", activity::profiles_path()),
+        Ok(false) => {}
+        Err(e) => tracing::warn!("[Config] Keeping the current activity profiles: {}", e),
+    }
+    if let Some(gateway) = gateway {
+        gateway.reload_activity();
+    }
+    let changed = config::reload()?;
+    if changed.is_empty() {
+        return Ok(changed);
+    }
+    tracing::info!("[Config] Reloaded {}", changed.join(", "));
+
+    if changed.iter().any(|key| key.starts_with("XHS_RATE_LIMIT_")) {
+        state.api.rate_limiter().reload_from_env();
+    }
+    if changed.iter().any(|key| matches!(*key, "XHS_PROXY" | "XHS_NO_PROXY")) {
+        crate::client::reset_shared_clients();
+        if let Err(e) = state.api.reload_proxy() {
+            tracing::warn!("[Config] Failed to apply proxy settings: {}", e);
+        }
+    }
+    if changed.contains(&"XHS_LOG_LEVEL") {
+        if let Err(e) = telemetry::set_log_level(config::log_level()) {
+            tracing::warn!("[Config] Failed to change log level: {}", e);
+        }
+    }
+    if let Some(gateway) = gateway {
+        gateway.apply_reload(&changed);
+    }
+    Ok(changed)
+}
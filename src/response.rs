@@ -0,0 +1,108 @@
+//! 统一响应信封 (ApiResponse)
+//!
+//! handler 自行构造的响应统一使用 `ApiResponse<T>`：`{code, success, msg, data, request_id}`，
+//! 成功时 `code = 0`，失败时 `code = -1`。`XhsError` 的错误响应（`ApiErrorBody`）同样带有 `request_id`。
+//! 直接转发的上游响应模型（`models::*`、笔记 / 媒体 / 用户等库函数的返回类型）、探针与文件 / 流式响应不套信封。
+//!
+//! `request_id` 取自请求头 `x-request-id`（没有时生成），由 [`request_id`] 中间件在处理请求期间记录，
+//! 并写回响应头 `x-request-id`。HTTP 请求的 tracing span 与该请求发出的上游审计记录（`AuditRecord::request_id`）
+//! 都带有同一个 ID，便于按日志 / 审计记录定位单个请求。
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// 请求 ID 的请求头 / 响应头
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// 客户端传入的请求 ID 超过此长度时重新生成
+#[cfg(feature = "server")]
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    /// 当前请求的 ID
+    static REQUEST_ID: String;
+}
+
+/// 当前请求的 ID（不在请求处理中时为 None）
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+/// 响应信封
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ApiResponse<T> {
+    /// 0 成功 / -1 失败
+    pub code: i32,
+    pub success: bool,
+    pub msg: Option<String>,
+    pub data: Option<T>,
+    /// 请求 ID（同响应头 x-request-id）
+    pub request_id: Option<String>,
+}
+
+impl<T> ApiResponse<T> {
+    /// 成功响应
+    pub fn ok(data: T) -> Self {
+        Self { code: 0, success: true, msg: None, data: Some(data), request_id: current_request_id() }
+    }
+
+    /// 失败响应（HTTP 状态码由调用方决定，默认 200）
+    pub fn error(msg: impl Into<String>) -> Self {
+        Self { code: -1, success: false, msg: Some(msg.into()), data: None, request_id: current_request_id() }
+    }
+
+    /// `Ok` 为成功响应，`Err` 为失败响应
+    pub fn from_result<E: std::fmt::Display>(result: Result<T, E>) -> Self {
+        match result {
+            Ok(data) => Self::ok(data),
+            Err(e) => Self::error(e.to_string()),
+        }
+    }
+
+    /// 附加数据（失败响应也可带上当前状态）
+    pub fn with_data(mut self, data: T) -> Self {
+        self.data = Some(data);
+        self
+    }
+
+    /// 附加说明
+    pub fn with_msg(mut self, msg: impl Into<String>) -> Self {
+        self.msg = Some(msg.into());
+        self
+    }
+}
+
+impl ApiResponse<()> {
+    /// 没有数据的成功响应
+    pub fn done(msg: impl Into<String>) -> Self {
+        Self { code: 0, success: true, msg: Some(msg.into()), data: None, request_id: current_request_id() }
+    }
+}
+
+#[cfg(feature = "server")]
+impl<T: Serialize> axum::response::IntoResponse for ApiResponse<T> {
+    fn into_response(mut self) -> axum::response::Response {
+        // 缓存 / 共享的响应以当前请求的 ID 为准
+        if let Some(id) = current_request_id() {
+            self.request_id = Some(id);
+        }
+        axum::Json(self).into_response()
+    }
+}
+
+/// 中间件：记录请求 ID（请求头 x-request-id，没有或过长时生成）并写回响应头
+#[cfg(feature = "server")]
+pub async fn request_id(request: axum::extract::Request, next: axum::middleware::Next) -> axum::response::Response {
+    let id = request.headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty() && value.len() <= MAX_REQUEST_ID_LEN)
+        .map(str::to_string)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().simple().to_string());
+    let mut response = REQUEST_ID.scope(id.clone(), next.run(request)).await;
+    if let Ok(value) = axum::http::HeaderValue::from_str(&id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
@@ -4,35 +4,78 @@
 //! All handlers are delegated to the `handlers` module.
 
 use axum::{
-    routing::{get, post},
+    extract::DefaultBodyLimit,
+    middleware,
+    routing::{delete, get, post},
     Router,
 };
 use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::sync::RwLock;
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
 use crate::{
-    api::{self, XhsApiClient},
-    auth::AuthService,
+    activity::ActivityGovernor,
+    archive::ResponseArchive,
+    audit::{self, AuditLog},
+    api::{self, XhsApiClient, crawl::CrawlJobs, login::QrStatusWatcher, media::{jobs::DownloadJobs, signed_url::FileSigner}, monitor::KeywordMonitors, watch::WatchList, note::cache::NoteDetailCache,
+        notification::poll::NotificationPoller, rotation::AccountPool,
+        creator::publish::{jobs::PublishJobs, schedule::{self, PublishSchedule}}},
+    auth::{AuthService, LoginSessions},
+    cache::{self, ResponseCache},
     client::XhsClient,
+    config,
+    coordination::{self, CoordinationConfig, WorkQueue},
     handlers,
     openapi::ApiDoc,
+    reload,
+    signature::policy::SignaturePolicies,
+    telemetry,
+    tenant::{self, TenantGateway},
+    tls,
 };
 
+/// Browser HAR exports easily exceed axum's default 2 MB body limit
+const HAR_BODY_LIMIT: usize = 64 * 1024 * 1024;
+
 // ============================================================================
 // Application State
 // ============================================================================
 
+///
+/// With JWT tenants enabled each tenant gets its own copy holding its own
+/// `api` / `auth` / `creator_auth` / login session / notification poller;
+/// the `Arc` fields are shared with the deployment's state.
 pub struct AppState {
     pub api: XhsApiClient,
     pub auth: Arc<AuthService>,
     pub creator_auth: Arc<AuthService>,
-    /// Guest cookies for QR login (populated by guest-init)
-    pub guest_cookies: Arc<RwLock<Option<std::collections::HashMap<String, String>>>>,
-    /// Current QR code info (qr_id, code)
-    pub qrcode_info: Arc<RwLock<Option<(String, String)>>>,
+    /// Persisted QR login flows keyed by the session token from guest-init
+    pub login_sessions: Arc<LoginSessions>,
+    /// Shared upstream QR status poller behind /api/auth/qrcode/status/wait
+    pub qr_watcher: Arc<QrStatusWatcher>,
+    /// Note detail cache (invalidated by feed/search observations)
+    pub note_cache: Arc<NoteDetailCache>,
+    /// Opt-in cache of idempotent read responses (None = disabled)
+    pub response_cache: Option<Arc<ResponseCache>>,
+    /// Shared crawl work queue (None = standalone mode)
+    pub coordinator: Option<Arc<WorkQueue>>,
+    /// Signed /files/{token} links for the download root
+    pub file_signer: Arc<FileSigner>,
+    /// Shared upstream poller behind /api/notification/poll
+    pub notification_poller: NotificationPoller,
+    /// Background Creator publish jobs (video uploads)
+    pub publish_jobs: Arc<PublishJobs>,
+    /// Background media downloads behind /api/media/download
+    pub download_jobs: Arc<DownloadJobs>,
+    /// Persistent queue of scheduled Creator posts
+    pub publish_schedule: Arc<PublishSchedule>,
+    /// Persistent recurring crawl jobs behind /api/jobs
+    pub crawl_jobs: Arc<CrawlJobs>,
+    /// Persistent keyword monitors behind /api/monitors
+    pub monitors: Arc<KeywordMonitors>,
+    /// Persistent user watch list behind /api/watch/users
+    pub watch_list: Arc<WatchList>,
 }
 
 // ============================================================================
@@ -40,6 +83,9 @@ pub struct AppState {
 // ============================================================================
 
 pub async fn start_server() -> anyhow::Result<()> {
+    // Validate HTTPS settings before any other initialization
+    let tls_config = config::tls_config()?;
+
     // Initialize AuthService (uses JSON file storage)
     tracing::info!("Initializing AuthService with JSON file storage...");
     let auth = Arc::new(AuthService::new(PathBuf::from("cookie.json")).await?);
@@ -48,51 +94,170 @@ pub async fn start_server() -> anyhow::Result<()> {
     let creator_auth = Arc::new(AuthService::new(PathBuf::from("cookie-creator.json")).await?);
     
     let client = XhsClient::new()?;
-    let api = XhsApiClient::new(client, auth.clone());
+    let activity = Arc::new(ActivityGovernor::from_env()?);
+    let mut api = XhsApiClient::new(client, auth.clone()).with_activity(activity);
+    if let Some(archive) = ResponseArchive::from_env().await? {
+        api = api.with_archive(Arc::new(archive));
+    }
+    if let Some(log) = AuditLog::from_env().await? {
+        let log = Arc::new(log);
+        audit::spawn_pruner(log.clone());
+        api = api.with_audit(log);
+    }
+    api = api.with_signature_policy(Arc::new(SignaturePolicies::from_env()?));
+    if let Some(pool) = AccountPool::from_env(auth.clone()).await? {
+        api = api.with_account_pool(Arc::new(pool));
+    }
     
     // Initialize shared state for login flow
-    let guest_cookies = Arc::new(RwLock::new(None));
-    let qrcode_info = Arc::new(RwLock::new(None));
+    let login_sessions = Arc::new(LoginSessions::from_env().await?);
+    
+    let note_cache = Arc::new(NoteDetailCache::new(config::note_cache_ttl()));
+    let response_cache = ResponseCache::from_env().await?.map(Arc::new);
+    
+    // Join the crawl cluster if a coordination MongoDB is configured
+    let coordinator = match CoordinationConfig::from_env() {
+        Some(coord_config) => {
+            let queue = Arc::new(
+                WorkQueue::connect(&coord_config).await?.with_maintenance(api.maintenance().clone()),
+            );
+            coordination::spawn_heartbeat(queue.clone());
+            Some(queue)
+        }
+        None => {
+            tracing::info!("XHS_COORD_MONGO_URI not set, running in standalone mode");
+            None
+        }
+    };
+    
+    let state = Arc::new(AppState {
+        api,
+        auth,
+        creator_auth,
+        login_sessions,
+        qr_watcher: Arc::new(QrStatusWatcher::default()),
+        note_cache,
+        response_cache,
+        coordinator,
+        file_signer: Arc::new(FileSigner::from_env()),
+        notification_poller: NotificationPoller::new(config::notification_poll_interval()),
+        publish_jobs: Arc::new(PublishJobs::new()),
+        download_jobs: Arc::new(DownloadJobs::default()),
+        publish_schedule: Arc::new(PublishSchedule::from_env().await?),
+        crawl_jobs: Arc::new(CrawlJobs::from_env().await?),
+        monitors: Arc::new(KeywordMonitors::from_env().await?),
+        watch_list: Arc::new(WatchList::from_env().await?),
+    });
     
-    let state = Arc::new(AppState { api, auth, creator_auth, guest_cookies, qrcode_info });
+    crate::auth::health::spawn_health_checker(state.clone());
+    crate::signature::metrics::spawn_fallback_alert();
+    crate::signature::prewarm::spawn_prewarm(state.clone());
+    api::maintenance::spawn_canary(state.clone());
+    api::risk::spawn_probe(state.clone());
+    api::notification::webhook::spawn_watcher(state.clone());
+    schedule::spawn_scheduler(state.clone());
+    api::crawl::spawn_scheduler(state.clone());
+    api::monitor::spawn_scheduler(state.clone());
+    api::watch::spawn_scheduler(state.clone());
 
-    let app = Router::new()
-        // Swagger UI
+    let gateway = TenantGateway::from_env(&state, |state| traced(routes(state))).await?;
+    crate::about::AboutInfo::collect(&state).await.log_banner();
+
+    // Open without a token even when JWT tenants are enabled
+    let public = Router::new()
         .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi()))
-        
+        .route("/files/:token", get(handlers::serve_file_handler))
+        .route("/healthz", get(handlers::healthz_handler))
+        .route("/readyz", get(handlers::readyz_handler));
+    let gateway = gateway.map(Arc::new);
+    reload::spawn_watcher(state.clone(), gateway.clone())?;
+    let app = match gateway {
+        // Tenant routers carry their own tracing, so the gateway fallback is left out of it
+        Some(gateway) => traced(public)
+            .with_state(state)
+            .merge(Router::new().fallback(tenant::dispatch).with_state(gateway)),
+        None => traced(public.merge(routes(&state))).with_state(state),
+    };
+    // Outermost, so gateway rejections and every handler see the same request ID
+    let app = app.layer(middleware::from_fn(crate::response::request_id));
+
+    // Get port from environment variable, default to 3000
+    let port = std::env::var("PORT")
+        .or_else(|_| std::env::var("XHS_API_PORT"))
+        .unwrap_or_else(|_| "3000".to_string());
+    
+    let addr = format!("0.0.0.0:{}", port);
+    let listener = tokio::net::TcpListener::bind(&addr).await?;
+    
+    let scheme = if tls_config.is_some() { "https" } else { "http" };
+    tracing::info!("Server running on {}://{}/swagger-ui/ (profile: {})", scheme, addr, config::active_profile());
+    match tls_config {
+        Some(tls_config) => tls::serve(listener, app, tls_config).await?,
+        None => axum::serve(listener, app).await?,
+    }
+
+    Ok(())
+}
+
+/// Routes behind JWT auth when tenants are enabled
+fn routes(state: &Arc<AppState>) -> Router<Arc<AppState>> {
+    Router::new()
         // Search routes
         .route("/api/search/trending", get(handlers::query_trending_handler))
         .route("/api/search/recommend", get(handlers::search_recommend_handler))
         .route("/api/search/notes", post(handlers::search_notes_handler))
+        .route("/api/search/notes/all", post(handlers::search_all_notes_handler))
         .route("/api/search/onebox", post(handlers::search_onebox_handler))
         .route("/api/search/filter", get(handlers::search_filter_handler))
         .route("/api/search/usersearch", post(handlers::search_user_handler))
         
         // User routes
         .route("/api/user/me", get(handlers::user_me_handler))
+        .route("/api/user/:user_id/notes", get(handlers::user_posted_handler))
+        .route("/api/user/boards", get(handlers::user_boards_handler).post(handlers::create_board_handler))
+        .route("/api/user/boards/:board_id", delete(handlers::delete_board_handler))
+        .route("/api/user/boards/:board_id/notes", get(handlers::board_notes_handler))
+        .route("/api/user/followings", get(handlers::user_followings_handler))
+        .route("/api/user/followers", get(handlers::user_followers_handler))
         
         // Feed routes
         .route("/api/feed/homefeed/recommend", post(handlers::homefeed_recommend_handler))
         .route("/api/feed/homefeed/:category", post(api::feed::category::get_category_feed))
+        .route("/ws/feed", get(handlers::feed_stream_handler))
         
         // Note routes
         .route("/api/note/page", get(api::note::page::get_note_page))
+        .route("/api/note/comments/all", post(api::note::comments::crawl_all_comments))
         .route("/api/note/detail", post(api::note::detail::get_note_detail))
+        .route("/api/note/resolve", get(api::note::resolve::resolve_note))
+        .route("/api/note/:note_id/stats", get(api::note::stats::get_note_stats))
         
         // Notification routes
         .route("/api/notification/mentions", get(handlers::mentions_handler))
         .route("/api/notification/connections", get(handlers::connections_handler))
         .route("/api/notification/likes", get(handlers::likes_handler))
+        .route("/api/notification/poll", get(handlers::notification_poll_handler))
         
         // Media routes
         .route("/api/note/video", post(handlers::video_handler))
         .route("/api/note/images", post(handlers::images_handler))
         .route("/api/media/download", post(handlers::download_handler))
+        .route("/api/media/jobs/:job_id", get(handlers::download_job_handler))
+        .route("/api/media/bundle", post(handlers::bundle_handler))
+        .route("/api/media/share", post(handlers::share_file_handler))
         
         // Auth routes
         .route("/api/auth/guest-init", post(handlers::guest_init_handler))
         .route("/api/auth/qrcode/create", post(handlers::create_qrcode_handler))
         .route("/api/auth/qrcode/status", get(handlers::poll_qrcode_status_handler))
+        .route("/api/auth/qrcode/status/wait", get(handlers::wait_qrcode_status_handler))
+        .route("/api/auth/qrcode/events", get(handlers::qrcode_events_handler))
+        .route("/api/auth/import-cookies", post(handlers::import_cookies_handler))
+        .route("/api/auth/export", get(handlers::export_credentials_handler))
+        .route("/api/auth/health", get(handlers::credential_health_handler))
+        .route("/api/auth/status", get(handlers::auth_status_handler))
+        .route("/api/auth/verification", get(handlers::verification_handler))
+        .route("/api/auth/verification/resolve", post(handlers::resolve_verification_handler))
         
         // Creator routes
         .route("/api/creator/auth/guest-init", post(handlers::creator_guest_init_handler))
@@ -103,20 +268,83 @@ pub async fn start_server() -> anyhow::Result<()> {
         .route("/api/galaxy/user/info", get(handlers::creator_user_info_handler))
         .route("/api/galaxy/creator/home/personal_info", get(handlers::creator_home_info_handler))
         
-        // Middleware
-        .layer(tower_http::trace::TraceLayer::new_for_http())
-        .with_state(state);
+        // Creator Publish routes
+        .route("/api/creator/publish/image", post(handlers::creator_publish_image_handler))
+        .route("/api/creator/publish/video", post(handlers::creator_publish_video_handler))
+        .route("/api/creator/publish/jobs/:job_id", get(handlers::creator_publish_job_handler))
+        .route("/api/creator/schedule", get(handlers::creator_schedule_list_handler)
+            .post(handlers::creator_schedule_handler))
+        .route("/api/creator/schedule/:id", get(handlers::creator_scheduled_post_handler)
+            .delete(handlers::creator_cancel_schedule_handler))
+        .route("/api/creator/drafts", get(handlers::creator_drafts_handler))
+        .route("/api/creator/drafts/:draft_id", get(handlers::creator_draft_handler)
+            .put(handlers::creator_update_draft_handler)
+            .delete(handlers::creator_delete_draft_handler))
+        .route("/api/creator/fans/portrait", get(handlers::creator_fans_portrait_handler))
+        .route("/api/creator/notes", get(handlers::creator_notes_handler))
+        .route("/api/creator/comments", get(handlers::creator_comments_handler))
+        .route("/api/creator/comments/:comment_id", delete(handlers::creator_delete_comment_handler))
+        .route("/api/creator/comments/:comment_id/reply", post(handlers::creator_reply_comment_handler))
+        .route("/api/creator/comments/:comment_id/hide", post(handlers::creator_hide_comment_handler))
+        
+        // Cluster routes
+        .route("/api/cluster/status", get(handlers::cluster_status_handler))
+        .route("/api/cluster/tasks", post(handlers::cluster_enqueue_handler))
+        .route("/api/activity/status", get(handlers::activity_status_handler))
+        .route("/api/tag/:tag_id/notes", get(handlers::tag_notes_handler))
+        .route("/api/links/parse", get(handlers::parse_link_handler))
+        .route("/api/metrics/signature", get(handlers::signature_metrics_handler))
+        .route("/api/archive", get(handlers::archive_list_handler))
+        .route("/api/archive/dictionary", post(handlers::archive_train_dictionary_handler))
+        .route("/api/archive/note", post(handlers::archive_note_handler))
+        .route("/api/archive/notes", get(handlers::archived_notes_handler))
+        .route("/api/archive/notes/:note_id/history", get(handlers::note_history_handler))
+        .route("/api/archive/notes/:note_id/comments", get(handlers::archived_comments_handler))
+        .route("/api/archive/export", get(handlers::archive_export_handler))
 
-    // Get port from environment variable, default to 3000
-    let port = std::env::var("PORT")
-        .or_else(|_| std::env::var("XHS_API_PORT"))
-        .unwrap_or_else(|_| "3000".to_string());
-    
-    let addr = format!("0.0.0.0:{}", port);
-    let listener = tokio::net::TcpListener::bind(&addr).await?;
-    
-    tracing::info!("Server running on http://{}/swagger-ui/", addr);
-    axum::serve(listener, app).await?;
+        // Scheduled crawl jobs
+        .route("/api/jobs", get(handlers::list_jobs_handler).post(handlers::create_job_handler))
+        .route("/api/jobs/:id", get(handlers::get_job_handler)
+            .put(handlers::update_job_handler)
+            .delete(handlers::delete_job_handler))
 
-    Ok(())
+        // Keyword monitors
+        .route("/api/monitors", get(handlers::list_monitors_handler).post(handlers::create_monitor_handler))
+        .route("/api/monitors/:id", get(handlers::get_monitor_handler)
+            .put(handlers::update_monitor_handler)
+            .delete(handlers::delete_monitor_handler))
+
+        // User watch list
+        .route("/api/watch/users", get(handlers::list_watched_users_handler).post(handlers::watch_user_handler))
+        .route("/api/watch/users/:user_id", get(handlers::get_watched_user_handler)
+            .put(handlers::update_watched_user_handler)
+            .delete(handlers::unwatch_user_handler))
+        .route("/api/watch/events", get(handlers::watch_events_handler))
+        .route("/api/archive/:id", get(handlers::archive_read_handler))
+        .route("/api/system/maintenance", get(handlers::maintenance_status_handler))
+        .route("/api/admin/risk", get(handlers::risk_status_handler))
+        .route("/api/admin/audit", get(handlers::audit_log_handler))
+        .route("/api/admin/config", get(handlers::config_handler))
+        .route("/api/admin/accounts", get(handlers::account_pool_handler))
+        .route("/api/admin/accounts/:index/release", post(handlers::release_account_handler))
+        .route("/api/admin/credentials", get(handlers::list_credentials_handler))
+        .route("/api/admin/credentials/:index", delete(handlers::delete_credentials_handler))
+        .route("/api/admin/credentials/:index/invalidate", post(handlers::invalidate_credentials_handler))
+        .route("/api/admin/credentials/:index/promote", post(handlers::promote_credentials_handler))
+        .route("/api/admin/import-har", post(handlers::import_har_handler).layer(DefaultBodyLimit::max(HAR_BODY_LIMIT)))
+        .route("/api/debug/sign", post(handlers::debug_sign_handler))
+        .route("/api/about", get(handlers::about_handler))
+        // Cached read endpoints are selected by matched route, so this must stay a route layer
+        .route_layer(middleware::from_fn_with_state(state.clone(), cache::middleware))
+        // Outside the cache, so raw passthrough always reaches upstream
+        .route_layer(middleware::from_fn(api::raw::middleware))
+        // Outermost, so dry runs bypass the response cache
+        .route_layer(middleware::from_fn(api::dry_run::middleware))
+}
+
+/// Request spans (see `telemetry`)
+fn traced<S: Clone + Send + Sync + 'static>(router: Router<S>) -> Router<S> {
+    router.layer(tower_http::trace::TraceLayer::new_for_http()
+        .make_span_with(telemetry::http_request_span)
+        .on_response(telemetry::record_http_response))
 }
@@ -0,0 +1,164 @@
+//! 合并签名 (Batch Signing)
+//!
+//! 批量笔记详情、全量评论等并发场景会同时发出多个签名请求，逐个调用 Agent 的 `/sign`
+//! 需要多次往返。`SignBatcher` 把 `XHS_SIGNATURE_BATCH_WINDOW_MS`（默认 5，0 关闭）毫秒内
+//! 到达的签名请求合并为一次 `/sign/batch` 调用，最多 `XHS_SIGNATURE_BATCH_MAX`（默认 20）个：
+//!
+//! - 窗口内只有一个请求时仍调用 `/sign`
+//! - Agent 不支持 `/sign/batch`（404 / 405）时退回逐个调用，10 分钟后再尝试批量接口
+//! - 合并发送在独立任务中进行，调用方被取消不影响同批的其他请求
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+
+use super::{agent_error, sign_one, AgentTimeout, Signature, SignRequest, SignResponse, AGENT_TIMEOUT};
+use crate::config::get_agent_url;
+
+/// Agent 不支持批量接口后，多久再尝试一次
+const REPROBE_AFTER: Duration = Duration::from_secs(600);
+
+#[derive(Serialize)]
+struct BatchSignRequest<'a> {
+    requests: Vec<&'a SignRequest>,
+}
+
+#[derive(Deserialize)]
+struct BatchSignResponse {
+    results: Vec<SignResponse>,
+}
+
+/// 等待合并发送的签名请求
+struct Pending {
+    request: SignRequest,
+    reply: oneshot::Sender<Result<Signature>>,
+}
+
+/// 签名请求合并器
+pub struct SignBatcher {
+    client: reqwest::Client,
+    window: Duration,
+    max: usize,
+    /// 当前窗口内的请求
+    pending: Mutex<Vec<Pending>>,
+    /// 最近一次发现 Agent 不支持 `/sign/batch` 的时间
+    unsupported_at: Mutex<Option<Instant>>,
+}
+
+impl SignBatcher {
+    /// 按 XHS_SIGNATURE_BATCH_WINDOW_MS / XHS_SIGNATURE_BATCH_MAX 构建，窗口为 0 时返回 None
+    pub fn from_config(client: reqwest::Client) -> Option<Self> {
+        let window = crate::config::signature_batch_window();
+        if window.is_zero() {
+            return None;
+        }
+        Some(Self {
+            client,
+            window,
+            max: crate::config::signature_batch_max(),
+            pending: Mutex::new(Vec::new()),
+            unsupported_at: Mutex::new(None),
+        })
+    }
+
+    /// Agent 是否（可能）支持 `/sign/batch`
+    pub fn is_supported(&self) -> bool {
+        self.unsupported_at
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_none_or(|at| at.elapsed() >= REPROBE_AFTER)
+    }
+
+    /// 加入当前窗口，等待合并发送的结果
+    pub async fn sign(self: &Arc<Self>, request: SignRequest) -> Result<Signature> {
+        let (reply, result) = oneshot::channel();
+        let full = {
+            let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+            pending.push(Pending { request, reply });
+            if pending.len() >= self.max {
+                Some(std::mem::take(&mut *pending))
+            } else {
+                if pending.len() == 1 {
+                    // 窗口的第一个请求负责定时发送
+                    let batcher = self.clone();
+                    tokio::spawn(async move {
+                        tokio::time::sleep(batcher.window).await;
+                        let batch = std::mem::take(&mut *batcher.pending.lock().unwrap_or_else(|e| e.into_inner()));
+                        batcher.flush(batch).await;
+                    });
+                }
+                None
+            }
+        };
+        if let Some(batch) = full {
+            let batcher = self.clone();
+            tokio::spawn(async move { batcher.flush(batch).await });
+        }
+        result.await.unwrap_or_else(|_| Err(anyhow!("Batched signing request was dropped")))
+    }
+
+    async fn flush(&self, mut batch: Vec<Pending>) {
+        match batch.len() {
+            0 => {}
+            1 => {
+                let pending = batch.remove(0);
+                let _ = pending.reply.send(sign_one(&self.client, &pending.request).await);
+            }
+            _ => match self.sign_batch(&batch).await {
+                Ok(Some(results)) => {
+                    for (pending, result) in batch.into_iter().zip(results) {
+                        let _ = pending.reply.send(result.into_signature());
+                    }
+                }
+                Ok(None) => {
+                    tracing::info!("[SignatureService] Agent has no /sign/batch, signing {} requests one by one", batch.len());
+                    *self.unsupported_at.lock().unwrap_or_else(|e| e.into_inner()) = Some(Instant::now());
+                    let results = futures_util::future::join_all(
+                        batch.iter().map(|pending| sign_one(&self.client, &pending.request)),
+                    ).await;
+                    for (pending, result) in batch.into_iter().zip(results) {
+                        let _ = pending.reply.send(result);
+                    }
+                }
+                Err(e) => {
+                    let timeout = e.is::<AgentTimeout>();
+                    let msg = e.to_string();
+                    for pending in batch {
+                        let error = if timeout { AgentTimeout(msg.clone()).into() } else { anyhow!("{}", msg) };
+                        let _ = pending.reply.send(Err(error));
+                    }
+                }
+            },
+        }
+    }
+
+    /// 调用 `/sign/batch`；Agent 不支持该接口时返回 None
+    async fn sign_batch(&self, batch: &[Pending]) -> Result<Option<Vec<SignResponse>>> {
+        tracing::debug!("[SignatureService] Calling Agent batch: {} requests", batch.len());
+        let body = BatchSignRequest { requests: batch.iter().map(|pending| &pending.request).collect() };
+        let response = self.client
+            .post(format!("{}/sign/batch", get_agent_url()))
+            .json(&body)
+            .timeout(AGENT_TIMEOUT)
+            .send()
+            .await
+            .map_err(|e| agent_error(e, "Agent connection failed", ". Is agent_server.py running?"))?;
+        if matches!(response.status(), reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::METHOD_NOT_ALLOWED) {
+            return Ok(None);
+        }
+        let response: BatchSignResponse = response
+            .json()
+            .await
+            .map_err(|e| agent_error(e, "Failed to parse Agent batch response", ""))?;
+        if response.results.len() != batch.len() {
+            return Err(anyhow!(
+                "Agent batch returned {} results for {} requests",
+                response.results.len(),
+                batch.len()
+            ));
+        }
+        Ok(Some(response.results))
+    }
+}
@@ -0,0 +1,401 @@
+//! 签名策略指标 (Signature Strategy Metrics)
+//!
+//! 按接口统计纯算法签名的成功/失败次数与存储签名兜底次数。
+//! 算法失败率持续升高是签名算法失效的最早信号，因此按分钟分桶，
+//! 当失败率连续 N 分钟超过阈值时向 Webhook 发送告警。
+//!
+//! 告警配置:
+//! - `XHS_SIGNATURE_ALERT_WEBHOOK`: 告警地址（未设置则不告警）
+//! - `XHS_SIGNATURE_ALERT_RATIO`: 失败率阈值，默认 0.5
+//! - `XHS_SIGNATURE_ALERT_MINUTES`: 连续超阈值分钟数，默认 5
+//!
+//! 同时记录每次 Agent 签名调用的耗时直方图与结果（成功 / 失败 / 超时），
+//! 用于判断请求失败是否源于 Agent 签名超时。超过 `XHS_SIGNATURE_SLOW_MS`（默认 1000）
+//! 的慢调用与失败调用以 warn 级别记录日志。
+
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+
+/// 保留的分钟桶数量
+const WINDOW_MINUTES: usize = 60;
+
+/// 每分钟至少多少次算法签名才参与告警判断
+const ALERT_MIN_SAMPLES: u64 = 5;
+
+/// Agent 签名耗时直方图的桶上界（毫秒），最后一个桶为 +Inf
+pub const LATENCY_BUCKETS_MS: &[u64] = &[50, 100, 250, 500, 1000, 2000, 5000];
+
+/// 全局签名指标
+pub static SIGNATURE_METRICS: LazyLock<SignatureMetrics> = LazyLock::new(SignatureMetrics::default);
+
+/// 单接口计数
+#[derive(Debug, Clone, Default, Serialize, utoipa::ToSchema)]
+pub struct StrategyCounts {
+    /// 纯算法签名成功
+    pub algo_ok: u64,
+    /// 纯算法签名失败
+    pub algo_failed: u64,
+    /// 使用存储签名兜底
+    pub stored: u64,
+}
+
+impl StrategyCounts {
+    fn add(&mut self, other: &StrategyCounts) {
+        self.algo_ok += other.algo_ok;
+        self.algo_failed += other.algo_failed;
+        self.stored += other.stored;
+    }
+
+    /// 算法失败率 (失败 / 算法尝试)
+    pub fn fallback_ratio(&self) -> f64 {
+        let attempts = self.algo_ok + self.algo_failed;
+        if attempts == 0 { 0.0 } else { self.algo_failed as f64 / attempts as f64 }
+    }
+}
+
+/// 一次 Agent 签名调用的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgentOutcome {
+    Ok,
+    Failed,
+    /// 超过 Agent 请求超时时间
+    Timeout,
+}
+
+/// Agent 签名调用耗时统计
+#[derive(Debug, Clone, Default)]
+struct LatencyHistogram {
+    calls: u64,
+    failed: u64,
+    timeouts: u64,
+    slow: u64,
+    sum_ms: u64,
+    max_ms: u64,
+    /// 与 `LATENCY_BUCKETS_MS` 对应，多出的最后一个为 +Inf
+    buckets: [u64; LATENCY_BUCKETS_MS.len() + 1],
+}
+
+impl LatencyHistogram {
+    fn record(&mut self, elapsed_ms: u64, outcome: AgentOutcome, slow: bool) {
+        self.calls += 1;
+        match outcome {
+            AgentOutcome::Ok => {}
+            AgentOutcome::Failed => self.failed += 1,
+            AgentOutcome::Timeout => self.timeouts += 1,
+        }
+        if slow {
+            self.slow += 1;
+        }
+        self.sum_ms += elapsed_ms;
+        self.max_ms = self.max_ms.max(elapsed_ms);
+        let index = LATENCY_BUCKETS_MS.iter().position(|le| elapsed_ms <= *le).unwrap_or(LATENCY_BUCKETS_MS.len());
+        self.buckets[index] += 1;
+    }
+
+    fn add(&mut self, other: &LatencyHistogram) {
+        self.calls += other.calls;
+        self.failed += other.failed;
+        self.timeouts += other.timeouts;
+        self.slow += other.slow;
+        self.sum_ms += other.sum_ms;
+        self.max_ms = self.max_ms.max(other.max_ms);
+        for (bucket, count) in self.buckets.iter_mut().zip(other.buckets) {
+            *bucket += count;
+        }
+    }
+
+    /// 分位数估计：落入的桶的上界（+Inf 桶取最大值）
+    fn quantile(&self, q: f64) -> u64 {
+        if self.calls == 0 {
+            return 0;
+        }
+        let rank = (self.calls as f64 * q).ceil() as u64;
+        let mut seen = 0;
+        for (index, count) in self.buckets.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return LATENCY_BUCKETS_MS.get(index).copied().unwrap_or(self.max_ms).min(self.max_ms);
+            }
+        }
+        self.max_ms
+    }
+
+    fn snapshot(&self) -> AgentLatency {
+        let mut cumulative = 0;
+        AgentLatency {
+            calls: self.calls,
+            failed: self.failed,
+            timeouts: self.timeouts,
+            slow: self.slow,
+            avg_ms: self.sum_ms.checked_div(self.calls).unwrap_or(0),
+            max_ms: self.max_ms,
+            p50_ms: self.quantile(0.5),
+            p95_ms: self.quantile(0.95),
+            p99_ms: self.quantile(0.99),
+            buckets: self.buckets.iter().enumerate()
+                .map(|(index, count)| {
+                    cumulative += count;
+                    LatencyBucket { le_ms: LATENCY_BUCKETS_MS.get(index).copied(), count: cumulative }
+                })
+                .collect(),
+        }
+    }
+}
+
+/// 耗时直方图的一个桶（累计计数）
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct LatencyBucket {
+    /// 桶上界（毫秒），None 为 +Inf
+    pub le_ms: Option<u64>,
+    /// 耗时不超过上界的调用次数
+    pub count: u64,
+}
+
+/// Agent 签名调用耗时快照
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct AgentLatency {
+    pub calls: u64,
+    /// 失败（不含超时）
+    pub failed: u64,
+    /// 超时
+    pub timeouts: u64,
+    /// 超过 XHS_SIGNATURE_SLOW_MS 的调用
+    pub slow: u64,
+    pub avg_ms: u64,
+    pub max_ms: u64,
+    /// 分位数为桶上界估计值
+    pub p50_ms: u64,
+    pub p95_ms: u64,
+    pub p99_ms: u64,
+    pub buckets: Vec<LatencyBucket>,
+}
+
+/// Agent 签名调用指标
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct AgentLatencyMetrics {
+    /// 进程启动以来累计
+    pub total: AgentLatency,
+    /// 最近 N 分钟
+    pub recent: AgentLatency,
+}
+
+/// 单接口指标快照
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct EndpointStrategyMetrics {
+    pub endpoint: String,
+    /// 进程启动以来累计
+    pub total: StrategyCounts,
+    /// 最近 N 分钟
+    pub recent: StrategyCounts,
+    /// 最近 N 分钟的算法失败率
+    pub recent_fallback_ratio: f64,
+}
+
+struct MinuteBucket {
+    minute: i64,
+    counts: HashMap<String, StrategyCounts>,
+    agent: LatencyHistogram,
+}
+
+#[derive(Default)]
+struct MetricsState {
+    totals: BTreeMap<String, StrategyCounts>,
+    agent: LatencyHistogram,
+    buckets: VecDeque<MinuteBucket>,
+}
+
+impl MetricsState {
+    fn bucket(&mut self) -> &mut MinuteBucket {
+        let minute = chrono::Utc::now().timestamp() / 60;
+        if self.buckets.back().is_none_or(|b| b.minute != minute) {
+            self.buckets.push_back(MinuteBucket { minute, counts: HashMap::new(), agent: LatencyHistogram::default() });
+            while self.buckets.len() > WINDOW_MINUTES {
+                self.buckets.pop_front();
+            }
+        }
+        self.buckets.back_mut().unwrap()
+    }
+
+    fn current(&mut self, endpoint: &str) -> (&mut StrategyCounts, &mut StrategyCounts) {
+        self.bucket();
+        let bucket = self.buckets.back_mut().unwrap();
+        (
+            self.totals.entry(endpoint.to_string()).or_default(),
+            bucket.counts.entry(endpoint.to_string()).or_default(),
+        )
+    }
+}
+
+/// 签名策略指标
+#[derive(Default)]
+pub struct SignatureMetrics {
+    state: Mutex<MetricsState>,
+}
+
+impl SignatureMetrics {
+    /// 记录一次纯算法签名结果
+    pub fn record_algo(&self, endpoint: &str, ok: bool) {
+        let mut state = self.state.lock().unwrap();
+        let (total, bucket) = state.current(endpoint_label(endpoint));
+        if ok {
+            total.algo_ok += 1;
+            bucket.algo_ok += 1;
+        } else {
+            total.algo_failed += 1;
+            bucket.algo_failed += 1;
+        }
+    }
+
+    /// 记录一次存储签名兜底
+    pub fn record_stored(&self, endpoint: &str) {
+        let mut state = self.state.lock().unwrap();
+        let (total, bucket) = state.current(endpoint_label(endpoint));
+        total.stored += 1;
+        bucket.stored += 1;
+    }
+
+    /// 记录一次 Agent 签名调用的耗时与结果；慢调用与失败调用记 warn 日志
+    pub fn record_agent_call(&self, uri: &str, elapsed: Duration, outcome: AgentOutcome) {
+        let elapsed_ms = elapsed.as_millis() as u64;
+        let slow = elapsed_ms > slow_call_threshold_ms();
+        match outcome {
+            AgentOutcome::Timeout => tracing::warn!("[SignatureMetrics] Agent signing timed out for {} after {}ms", endpoint_label(uri), elapsed_ms),
+            AgentOutcome::Failed => tracing::warn!("[SignatureMetrics] Agent signing failed for {} after {}ms", endpoint_label(uri), elapsed_ms),
+            AgentOutcome::Ok if slow => tracing::warn!("[SignatureMetrics] Slow Agent signing for {}: {}ms", endpoint_label(uri), elapsed_ms),
+            AgentOutcome::Ok => {}
+        }
+        let mut state = self.state.lock().unwrap();
+        state.agent.record(elapsed_ms, outcome, slow);
+        state.bucket().agent.record(elapsed_ms, outcome, slow);
+    }
+
+    /// Agent 签名调用耗时（recent 统计最近 `minutes` 分钟）
+    pub fn agent_snapshot(&self, minutes: usize) -> AgentLatencyMetrics {
+        let state = self.state.lock().unwrap();
+        let since = chrono::Utc::now().timestamp() / 60 - minutes as i64;
+        let mut recent = LatencyHistogram::default();
+        for bucket in state.buckets.iter().filter(|b| b.minute > since) {
+            recent.add(&bucket.agent);
+        }
+        AgentLatencyMetrics { total: state.agent.snapshot(), recent: recent.snapshot() }
+    }
+
+    /// 各接口指标（recent 统计最近 `minutes` 分钟）
+    pub fn snapshot(&self, minutes: usize) -> Vec<EndpointStrategyMetrics> {
+        let state = self.state.lock().unwrap();
+        let since = chrono::Utc::now().timestamp() / 60 - minutes as i64;
+        state.totals.iter()
+            .map(|(endpoint, total)| {
+                let mut recent = StrategyCounts::default();
+                for bucket in state.buckets.iter().filter(|b| b.minute > since) {
+                    if let Some(counts) = bucket.counts.get(endpoint) {
+                        recent.add(counts);
+                    }
+                }
+                EndpointStrategyMetrics {
+                    endpoint: endpoint.clone(),
+                    total: total.clone(),
+                    recent_fallback_ratio: recent.fallback_ratio(),
+                    recent,
+                }
+            })
+            .collect()
+    }
+
+    /// 最近 `minutes` 个完整分钟是否每分钟都超过阈值（所有接口合计）
+    fn sustained_above(&self, threshold: f64, minutes: usize) -> Option<f64> {
+        let state = self.state.lock().unwrap();
+        let current = chrono::Utc::now().timestamp() / 60;
+        let mut ratios = Vec::with_capacity(minutes);
+        for minute in (current - minutes as i64)..current {
+            let mut counts = StrategyCounts::default();
+            if let Some(bucket) = state.buckets.iter().find(|b| b.minute == minute) {
+                bucket.counts.values().for_each(|c| counts.add(c));
+            }
+            if counts.algo_ok + counts.algo_failed < ALERT_MIN_SAMPLES || counts.fallback_ratio() <= threshold {
+                return None;
+            }
+            ratios.push(counts.fallback_ratio());
+        }
+        Some(ratios.iter().sum::<f64>() / ratios.len().max(1) as f64)
+    }
+}
+
+/// 去掉查询参数，避免同一接口按参数分散计数
+fn endpoint_label(endpoint: &str) -> &str {
+    endpoint.split('?').next().unwrap_or(endpoint)
+}
+
+/// 慢调用阈值（毫秒），通过 XHS_SIGNATURE_SLOW_MS 配置，默认 1000
+fn slow_call_threshold_ms() -> u64 {
+    std::env::var("XHS_SIGNATURE_SLOW_MS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(1000)
+}
+
+/// 告警配置
+struct AlertConfig {
+    webhook: String,
+    ratio: f64,
+    minutes: usize,
+}
+
+impl AlertConfig {
+    fn from_env() -> Option<Self> {
+        let webhook = std::env::var("XHS_SIGNATURE_ALERT_WEBHOOK").ok().filter(|w| !w.is_empty())?;
+        let ratio = std::env::var("XHS_SIGNATURE_ALERT_RATIO")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0.5);
+        let minutes = std::env::var("XHS_SIGNATURE_ALERT_MINUTES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(5usize)
+            .clamp(1, WINDOW_MINUTES - 1);
+        Some(Self { webhook, ratio, minutes })
+    }
+}
+
+/// 启动失败率告警检查（每分钟一次，恢复前不重复告警）
+pub fn spawn_fallback_alert() {
+    let Some(config) = AlertConfig::from_env() else {
+        return;
+    };
+    tracing::info!(
+        "[SignatureMetrics] Alert enabled: ratio > {} for {} min -> {}",
+        config.ratio, config.minutes, config.webhook
+    );
+
+    tokio::spawn(async move {
+        let client = reqwest::Client::new();
+        let mut firing = false;
+        loop {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            match SIGNATURE_METRICS.sustained_above(config.ratio, config.minutes) {
+                Some(ratio) if !firing => {
+                    firing = true;
+                    tracing::error!("[SignatureMetrics] Algo fallback ratio {:.2} above {} for {} min", ratio, config.ratio, config.minutes);
+                    let payload = serde_json::json!({
+                        "event": "signature_fallback_ratio",
+                        "ratio": ratio,
+                        "threshold": config.ratio,
+                        "minutes": config.minutes,
+                        "endpoints": SIGNATURE_METRICS.snapshot(config.minutes),
+                    });
+                    if let Err(e) = client.post(&config.webhook).json(&payload)
+                        .timeout(Duration::from_secs(10))
+                        .send()
+                        .await
+                    {
+                        tracing::warn!("[SignatureMetrics] Alert webhook failed: {}", e);
+                    }
+                }
+                Some(_) => {}
+                None => firing = false,
+            }
+        }
+    });
+}
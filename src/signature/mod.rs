@@ -2,14 +2,31 @@
 //!
 //! 提供两种签名获取策略：
 //! 1. **纯算法 (Pure Algorithm)**: 调用 Python Agent 的 `/sign` 端点，使用 xhshow 库生成签名
-//! 2. **浏览器捕获 (Browser Capture)**: 从 MongoDB 读取之前通过 Playwright 捕获的签名（兜底）
+//! 2. **浏览器捕获 (Browser Capture)**: 读取从浏览器 HAR 导入的签名（兜底）
 //!
-//! 默认优先使用纯算法，失败时自动降级到浏览器捕获。
+//! 默认优先使用纯算法，失败时自动降级到浏览器捕获；按接口调整见 [`policy`]。
+//! 高频接口的纯算法签名可预先生成，见 [`prewarm`]；并发的签名请求合并为一次 Agent 调用，见 [`batch`]。
+
+pub mod batch;
+pub mod metrics;
+pub mod policy;
+pub mod prewarm;
 
 use anyhow::{Result, anyhow};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
 use crate::config::get_agent_url;
+use batch::SignBatcher;
+use metrics::AgentOutcome;
+
+/// Agent 签名请求超时
+const AGENT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Agent 签名请求超时（超过 [`AGENT_TIMEOUT`]），签名指标据此区分超时与其他失败
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+pub struct AgentTimeout(pub String);
 
 /// 签名请求结构
 #[derive(Debug, Serialize)]
@@ -45,16 +62,38 @@ pub struct Signature {
     pub x_xray_traceid: String,
 }
 
+impl SignResponse {
+    fn into_signature(self) -> Result<Signature> {
+        if !self.success {
+            return Err(anyhow!(
+                "Agent signing failed: {}",
+                self.error.unwrap_or_else(|| "Unknown error".to_string())
+            ));
+        }
+        Ok(Signature {
+            x_s: self.x_s.unwrap_or_default(),
+            x_t: self.x_t.unwrap_or_default(),
+            x_s_common: self.x_s_common.unwrap_or_default(),
+            x_b3_traceid: self.x_b3_traceid.unwrap_or_default(),
+            x_xray_traceid: self.x_xray_traceid.unwrap_or_default(),
+        })
+    }
+}
+
 /// 签名服务 - 提供签名获取的统一接口
 pub struct SignatureService {
     client: reqwest::Client,
+    /// 未关闭合并签名（XHS_SIGNATURE_BATCH_WINDOW_MS=0）时存在
+    batcher: Option<Arc<SignBatcher>>,
 }
 
 impl SignatureService {
     /// 创建签名服务实例
     pub fn new() -> Self {
+        let client = reqwest::Client::new();
         Self {
-            client: reqwest::Client::new(),
+            batcher: SignBatcher::from_config(client.clone()).map(Arc::new),
+            client,
         }
     }
 
@@ -74,6 +113,31 @@ impl SignatureService {
         uri: &str,
         cookies: HashMap<String, String>,
         payload: Option<serde_json::Value>,
+    ) -> Result<Signature> {
+        let span = tracing::info_span!(
+            "signature.agent",
+            http.request.method = %method.to_uppercase(),
+            uri = %uri.split('?').next().unwrap_or(uri),
+            otel.status_code = tracing::field::Empty,
+            otel.status_description = tracing::field::Empty,
+        );
+        let started = std::time::Instant::now();
+        let result = crate::telemetry::traced(span, self.request_agent(method, uri, cookies, payload)).await;
+        let outcome = match &result {
+            Ok(_) => AgentOutcome::Ok,
+            Err(e) if e.is::<AgentTimeout>() => AgentOutcome::Timeout,
+            Err(_) => AgentOutcome::Failed,
+        };
+        metrics::SIGNATURE_METRICS.record_agent_call(uri, started.elapsed(), outcome);
+        result
+    }
+
+    async fn request_agent(
+        &self,
+        method: &str,
+        uri: &str,
+        cookies: HashMap<String, String>,
+        payload: Option<serde_json::Value>,
     ) -> Result<Signature> {
         let request = SignRequest {
             method: method.to_uppercase(),
@@ -83,37 +147,11 @@ impl SignatureService {
             payload,
         };
 
-        let url = format!("{}/sign", get_agent_url());
-        
         tracing::debug!("[SignatureService] Calling Agent: {} {}", method, uri);
-        
-        let response = self.client
-            .post(&url)
-            .json(&request)
-            .timeout(std::time::Duration::from_secs(5))
-            .send()
-            .await
-            .map_err(|e| anyhow!("Agent connection failed: {}. Is agent_server.py running?", e))?;
-
-        let sign_resp: SignResponse = response
-            .json()
-            .await
-            .map_err(|e| anyhow!("Failed to parse Agent response: {}", e))?;
-
-        if !sign_resp.success {
-            return Err(anyhow!(
-                "Agent signing failed: {}",
-                sign_resp.error.unwrap_or_else(|| "Unknown error".to_string())
-            ));
+        match &self.batcher {
+            Some(batcher) if batcher.is_supported() => batcher.sign(request).await,
+            _ => sign_one(&self.client, &request).await,
         }
-
-        Ok(Signature {
-            x_s: sign_resp.x_s.unwrap_or_default(),
-            x_t: sign_resp.x_t.unwrap_or_default(),
-            x_s_common: sign_resp.x_s_common.unwrap_or_default(),
-            x_b3_traceid: sign_resp.x_b3_traceid.unwrap_or_default(),
-            x_xray_traceid: sign_resp.x_xray_traceid.unwrap_or_default(),
-        })
     }
 
     /// 检查 Agent 是否可用
@@ -132,6 +170,36 @@ impl Default for SignatureService {
     }
 }
 
+/// 单次调用 Agent 的 `/sign`
+async fn sign_one(client: &reqwest::Client, request: &SignRequest) -> Result<Signature> {
+    let request = client
+        .post(format!("{}/sign", get_agent_url()))
+        .json(request)
+        .timeout(AGENT_TIMEOUT);
+    // 把 trace context 传给 Agent，Agent 接入 OpenTelemetry 后可串起签名耗时
+    #[cfg(feature = "otel")]
+    let request = crate::telemetry::otlp::inject_context(request);
+    let response = request
+        .send()
+        .await
+        .map_err(|e| agent_error(e, "Agent connection failed", ". Is agent_server.py running?"))?;
+
+    let sign_resp: SignResponse = response
+        .json()
+        .await
+        .map_err(|e| agent_error(e, "Failed to parse Agent response", ""))?;
+    sign_resp.into_signature()
+}
+
+/// 把请求 Agent 时的错误转为 anyhow 错误，超时转为 [`AgentTimeout`]
+fn agent_error(e: reqwest::Error, context: &str, hint: &str) -> anyhow::Error {
+    if e.is_timeout() {
+        AgentTimeout(format!("{}: timed out after {}s", context, AGENT_TIMEOUT.as_secs())).into()
+    } else {
+        anyhow!("{}: {}{}", context, e, hint)
+    }
+}
+
 /// 将 Cookie 字符串解析为 HashMap
 pub fn parse_cookie_string(cookie_str: &str) -> HashMap<String, String> {
     let mut cookies = HashMap::new();
@@ -0,0 +1,118 @@
+//! 签名来源策略 (Signature Source Policy)
+//!
+//! 决定每个接口使用哪种签名、按什么顺序尝试：
+//! - `algo_only`: 只用 Python Agent 纯算法签名
+//! - `stored_only`: 只用浏览器捕获的存储签名（HAR 导入，见 `/api/admin/import-har`）
+//! - `algo_then_stored`（默认）: 纯算法优先，失败时回退到存储签名
+//! - `stored_then_algo`: 存储签名优先，没有可用的存储签名时再用纯算法
+//!
+//! 配置 (环境变量，启动时读取，取值不区分大小写):
+//! - `XHS_SIGNATURE_POLICY`: 默认策略
+//! - `XHS_SIGNATURE_POLICY_ENDPOINTS`: 按接口覆盖，`接口=策略` 逗号分隔，接口为 endpoint key 或 URI 前缀
+//!   （与 `XHS_RATE_LIMIT_ENDPOINTS` 相同，最长前缀优先），如 `user_me=stored_only,/api/sns/web/v1/search=algo_only`
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::audit::SignatureMode;
+
+/// 一个接口的签名来源策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SignaturePolicy {
+    AlgoOnly,
+    StoredOnly,
+    #[default]
+    AlgoThenStored,
+    StoredThenAlgo,
+}
+
+impl SignaturePolicy {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "algo_only" | "algo" => Some(Self::AlgoOnly),
+            "stored_only" | "stored" => Some(Self::StoredOnly),
+            "algo_then_stored" => Some(Self::AlgoThenStored),
+            "stored_then_algo" => Some(Self::StoredThenAlgo),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::AlgoOnly => "algo_only",
+            Self::StoredOnly => "stored_only",
+            Self::AlgoThenStored => "algo_then_stored",
+            Self::StoredThenAlgo => "stored_then_algo",
+        }
+    }
+
+    /// 依次尝试的签名方式
+    pub fn order(&self) -> &'static [SignatureMode] {
+        match self {
+            Self::AlgoOnly => &[SignatureMode::Algo],
+            Self::StoredOnly => &[SignatureMode::Stored],
+            Self::AlgoThenStored => &[SignatureMode::Algo, SignatureMode::Stored],
+            Self::StoredThenAlgo => &[SignatureMode::Stored, SignatureMode::Algo],
+        }
+    }
+}
+
+/// 默认策略 + 按接口覆盖
+#[derive(Debug, Clone, Default)]
+pub struct SignaturePolicies {
+    default: SignaturePolicy,
+    endpoints: Vec<(String, SignaturePolicy)>,
+}
+
+impl SignaturePolicies {
+    pub fn new(default: SignaturePolicy, endpoints: Vec<(String, SignaturePolicy)>) -> Self {
+        Self { default, endpoints }
+    }
+
+    /// 按 XHS_SIGNATURE_POLICY / XHS_SIGNATURE_POLICY_ENDPOINTS 构建，取值无效时报错
+    pub fn from_env() -> Result<Self> {
+        let default = match std::env::var("XHS_SIGNATURE_POLICY") {
+            Ok(value) if !value.trim().is_empty() => SignaturePolicy::parse(&value)
+                .ok_or_else(|| anyhow!("Invalid XHS_SIGNATURE_POLICY '{}' (expected algo_only / stored_only / algo_then_stored / stored_then_algo)", value))?,
+            _ => SignaturePolicy::default(),
+        };
+        let mut endpoints = Vec::new();
+        for entry in std::env::var("XHS_SIGNATURE_POLICY_ENDPOINTS").unwrap_or_default().split(',') {
+            let entry = entry.trim();
+            if entry.is_empty() {
+                continue;
+            }
+            let (endpoint, policy) = entry.split_once('=')
+                .ok_or_else(|| anyhow!("Invalid XHS_SIGNATURE_POLICY_ENDPOINTS entry '{}' (expected endpoint=policy)", entry))?;
+            let policy = SignaturePolicy::parse(policy)
+                .ok_or_else(|| anyhow!("Invalid signature policy '{}' for {}", policy.trim(), endpoint.trim()))?;
+            endpoints.push((endpoint.trim().to_string(), policy));
+        }
+        Ok(Self::new(default, endpoints))
+    }
+
+    /// 接口（endpoint key 或 URI）适用的策略：完全匹配或最长 URI 前缀，否则为默认策略
+    pub fn for_endpoint(&self, endpoint: &str) -> SignaturePolicy {
+        let path = endpoint.split('?').next().unwrap_or(endpoint);
+        self.endpoints
+            .iter()
+            .filter(|(key, _)| key == endpoint || (key.starts_with('/') && path.starts_with(key.as_str())))
+            .max_by_key(|(key, _)| key.len())
+            .map(|(_, policy)| *policy)
+            .unwrap_or(self.default)
+    }
+
+    /// 如 `algo_then_stored (user_me=stored_only)`
+    pub fn describe(&self) -> String {
+        if self.endpoints.is_empty() {
+            return self.default.as_str().to_string();
+        }
+        let overrides: Vec<String> = self.endpoints
+            .iter()
+            .map(|(endpoint, policy)| format!("{}={}", endpoint, policy.as_str()))
+            .collect();
+        format!("{} ({})", self.default.as_str(), overrides.join(", "))
+    }
+}
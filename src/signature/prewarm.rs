@@ -0,0 +1,93 @@
+//! 签名预热 (Signature Pre-warming)
+//!
+//! Python Agent 冷启动后的第一次签名明显偏慢，重启后第一个用户请求会因此变慢。
+//! 启动时与之后每隔 `XHS_SIGNATURE_PREWARM_SECS` 秒，为 `XHS_SIGNATURE_PREWARM` 中的高频接口
+//! 用主账号 Cookie 预先生成纯算法签名，放入 [`SignatureCache`]：
+//!
+//! - 只有 URI 与请求体固定的接口可以预热（如 user_me / search_trending / notification_*），
+//!   请求体每次不同的接口（home_feed_* / search_notes）会被跳过
+//! - 预生成的签名只使用一次，超过一个预热间隔未被使用即过期
+//! - 签名来源策略为 `stored_only` 的接口不预热；未登录时只调用一次 Agent 使其保持预热
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::Signature;
+
+#[cfg(feature = "server")]
+use crate::server::AppState;
+#[cfg(feature = "server")]
+use std::sync::Arc;
+
+/// 预生成签名的缓存键：方法 + URI + 请求体 + Cookie
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    method: String,
+    uri: String,
+    payload: Option<String>,
+    cookies: String,
+}
+
+impl CacheKey {
+    fn new(method: &str, uri: &str, cookies: &HashMap<String, String>, payload: Option<&serde_json::Value>) -> Self {
+        let cookies: BTreeMap<&String, &String> = cookies.iter().collect();
+        Self {
+            method: method.to_uppercase(),
+            uri: uri.to_string(),
+            payload: payload.map(|p| p.to_string()),
+            cookies: cookies.into_iter().map(|(k, v)| format!("{}={}", k, v)).collect::<Vec<_>>().join("; "),
+        }
+    }
+}
+
+/// 预生成的纯算法签名，取用一次即移除
+#[derive(Debug, Default)]
+pub struct SignatureCache {
+    entries: Mutex<HashMap<CacheKey, (Signature, Instant)>>,
+}
+
+impl SignatureCache {
+    pub fn insert(
+        &self,
+        method: &str,
+        uri: &str,
+        cookies: &HashMap<String, String>,
+        payload: Option<&serde_json::Value>,
+        signature: Signature,
+    ) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.insert(CacheKey::new(method, uri, cookies, payload), (signature, Instant::now()));
+    }
+
+    /// 取出未过期（生成后不超过 `ttl`）的签名
+    pub fn take(
+        &self,
+        method: &str,
+        uri: &str,
+        cookies: &HashMap<String, String>,
+        payload: Option<&serde_json::Value>,
+        ttl: Duration,
+    ) -> Option<Signature> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.retain(|_, (_, generated)| generated.elapsed() < ttl);
+        entries.remove(&CacheKey::new(method, uri, cookies, payload)).map(|(signature, _)| signature)
+    }
+}
+
+#[cfg(feature = "server")]
+/// 启动签名预热：立即执行一次，之后按 XHS_SIGNATURE_PREWARM_SECS 间隔重复
+pub fn spawn_prewarm(state: Arc<AppState>) {
+    let endpoints = crate::config::signature_prewarm_endpoints();
+    if endpoints.is_empty() {
+        return;
+    }
+    let interval = crate::config::signature_prewarm_interval();
+    tokio::spawn(async move {
+        loop {
+            let warmed = state.api.prewarm_signatures(&endpoints).await;
+            tracing::debug!("[Prewarm] {} signature(s) ready", warmed);
+            tokio::time::sleep(interval).await;
+        }
+    });
+}
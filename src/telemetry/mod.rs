@@ -0,0 +1,127 @@
+//! 链路追踪 (Tracing Spans)
+//!
+//! 请求链路用 `tracing` span 描述，与是否导出无关：
+//! - `http.request`: 一次 HTTP 请求（server 的 TraceLayer）
+//! - `xhs.request`: 一次上游接口调用（含重试），`xhs.admit` 为其中的限流 / 熔断 / 拟人节奏等待
+//! - `signature.agent`: 向 Python Agent 请求签名
+//! - `media.download` / `media.segment` / `media.bundle`: CDN 下载
+//!
+//! 启用 `otel` feature 并配置 OTLP 地址后，这些 span 经 [`otlp`] 导出到 OpenTelemetry 后端。
+//! span 字段中的 `otel.status_code` / `otel.status_description` 由 tracing-opentelemetry 映射为 span 状态。
+
+#[cfg(feature = "otel")]
+pub mod otlp;
+
+use std::fmt::Display;
+use std::future::Future;
+use std::sync::{Mutex, OnceLock};
+
+use tracing::field::Empty;
+use tracing::{Instrument, Level, Span};
+
+/// 上游接口调用的 span（`endpoint` 的查询参数含 xsec_token 等，不写入 span）
+pub fn request_span(method: &'static str, endpoint: &str) -> Span {
+    let endpoint = endpoint.split('?').next().unwrap_or(endpoint);
+    tracing::info_span!(
+        "xhs.request",
+        otel.name = %format!("{} {}", method, endpoint),
+        http.request.method = method,
+        endpoint = %endpoint,
+        http.response.status_code = Empty,
+        otel.status_code = Empty,
+        otel.status_description = Empty,
+    )
+}
+
+/// 在 `span` 中执行 `future`，失败时把错误记录为 span 状态
+pub async fn traced<T, E: Display>(span: Span, future: impl Future<Output = Result<T, E>>) -> Result<T, E> {
+    let result = future.instrument(span.clone()).await;
+    if let Err(e) = &result {
+        record_error(&span, e);
+    }
+    result
+}
+
+/// 把 span 状态标记为错误（span 未声明 otel.status_* 字段时忽略）
+pub fn record_error(span: &Span, error: &impl Display) {
+    span.record("otel.status_code", "error");
+    span.record("otel.status_description", tracing::field::display(error));
+}
+
+/// 记录响应状态码到当前 span
+pub fn record_status(status: u16) {
+    Span::current().record("http.response.status_code", status);
+}
+
+/// HTTP 请求的 span（server TraceLayer 的 make_span_with）
+///
+/// 按路由模板命名（如 `GET /api/user/:user_id/notes`），记录请求 ID（x-request-id），
+/// 请求头带 `traceparent` 时接续调用方的链路
+#[cfg(feature = "server")]
+pub fn http_request_span<B>(request: &axum::http::Request<B>) -> Span {
+    let route = request
+        .extensions()
+        .get::<axum::extract::MatchedPath>()
+        .map_or_else(|| request.uri().path().to_string(), |p| p.as_str().to_string());
+    let span = tracing::info_span!(
+        "http.request",
+        otel.name = %format!("{} {}", request.method(), route),
+        otel.kind = "server",
+        http.request.method = %request.method(),
+        http.route = %route,
+        url.path = %request.uri().path(),
+        request_id = crate::response::current_request_id().as_deref(),
+        http.response.status_code = Empty,
+        otel.status_code = Empty,
+        otel.status_description = Empty,
+    );
+    #[cfg(feature = "otel")]
+    otlp::set_parent_from_headers(&span, request.headers());
+    span
+}
+
+/// 记录响应状态码（server TraceLayer 的 on_response），5xx 标记为错误
+#[cfg(feature = "server")]
+pub fn record_http_response<B>(response: &axum::http::Response<B>, _latency: std::time::Duration, span: &Span) {
+    let status = response.status();
+    span.record("http.response.status_code", status.as_u16());
+    if status.is_server_error() {
+        record_error(span, &status);
+    }
+}
+
+/// 控制台日志级别的运行时切换（由 main 初始化日志时注册）
+struct LogLevelControl {
+    /// 未配置 XHS_LOG_LEVEL 时的级别
+    default: Level,
+    current: Mutex<Level>,
+    apply: Box<dyn Fn(Level) -> Result<(), String> + Send + Sync>,
+}
+
+static LOG_LEVEL: OnceLock<LogLevelControl> = OnceLock::new();
+
+/// 注册日志级别切换函数，`level` 为当前级别
+pub fn register_log_level(
+    default: Level,
+    level: Level,
+    apply: impl Fn(Level) -> Result<(), String> + Send + Sync + 'static,
+) {
+    let _ = LOG_LEVEL.set(LogLevelControl { default, current: Mutex::new(level), apply: Box::new(apply) });
+}
+
+/// 当前控制台日志级别（未注册时为 None）
+pub fn log_level() -> Option<Level> {
+    LOG_LEVEL.get().map(|control| *control.current.lock().unwrap())
+}
+
+/// 切换控制台日志级别，None 恢复默认级别；未注册时返回错误
+pub fn set_log_level(level: Option<Level>) -> Result<(), String> {
+    let control = LOG_LEVEL.get().ok_or("Log level is not reloadable")?;
+    let level = level.unwrap_or(control.default);
+    let mut current = control.current.lock().unwrap();
+    if *current != level {
+        (control.apply)(level)?;
+        *current = level;
+    }
+    Ok(())
+}
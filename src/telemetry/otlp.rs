@@ -0,0 +1,121 @@
+//! OTLP 导出 (OpenTelemetry)
+//!
+//! 把 `tracing` span 经 OTLP/HTTP (protobuf) 导出，批量异步发送，不阻塞请求。
+//! 配置沿用 OpenTelemetry 标准环境变量:
+//! - `OTEL_EXPORTER_OTLP_ENDPOINT` / `OTEL_EXPORTER_OTLP_TRACES_ENDPOINT`: 导出地址，设置后启用
+//!   （如 `http://otel-collector:4318`）
+//! - `OTEL_EXPORTER_OTLP_HEADERS`: 附加请求头，如 `authorization=Bearer xxx`
+//! - `OTEL_SERVICE_NAME`: 服务名，默认 xhs-rs；`OTEL_RESOURCE_ATTRIBUTES` 附加资源属性
+//! - `OTEL_TRACES_SAMPLER` / `OTEL_TRACES_SAMPLER_ARG`: 采样策略，默认全部采样
+//! - `OTEL_SDK_DISABLED=true`: 强制关闭
+//!
+//! 入站请求的 W3C `traceparent` 作为 `http.request` 的父 span，
+//! 发往 Python Agent 的签名请求附带 `traceparent`，便于串起整条链路。
+
+use anyhow::Result;
+use opentelemetry::propagation::{Extractor, Injector};
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::{Protocol, WithExportConfig};
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use opentelemetry_sdk::Resource;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use tracing::Span;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::registry::LookupSpan;
+
+const DEFAULT_SERVICE_NAME: &str = "xhs-rs";
+
+/// OTLP 导出器（进程退出前调用 [`OtlpTracing::shutdown`] 发送剩余 span）
+pub struct OtlpTracing {
+    provider: SdkTracerProvider,
+    endpoint: String,
+}
+
+impl OtlpTracing {
+    /// 按环境变量初始化，未配置导出地址时返回 None
+    pub fn from_env() -> Result<Option<Self>> {
+        let disabled = std::env::var("OTEL_SDK_DISABLED").is_ok_and(|v| v.eq_ignore_ascii_case("true"));
+        let endpoint = std::env::var("OTEL_EXPORTER_OTLP_TRACES_ENDPOINT")
+            .or_else(|_| std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT"))
+            .ok()
+            .filter(|e| !e.trim().is_empty());
+        let Some(endpoint) = endpoint.filter(|_| !disabled) else {
+            return Ok(None);
+        };
+
+        // 地址、请求头、超时由 exporter 自行读取 OTEL_EXPORTER_OTLP_* 环境变量
+        let exporter = opentelemetry_otlp::SpanExporter::builder()
+            .with_http()
+            .with_protocol(Protocol::HttpBinary)
+            .build()?;
+        let mut resource = Resource::builder();
+        if std::env::var("OTEL_SERVICE_NAME").is_err() {
+            resource = resource.with_service_name(DEFAULT_SERVICE_NAME);
+        }
+        let provider = SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_resource(resource.build())
+            .build();
+
+        opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+        opentelemetry::global::set_tracer_provider(provider.clone());
+        Ok(Some(Self { provider, endpoint }))
+    }
+
+    /// 导出地址
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    /// 把 tracing span 转为 OpenTelemetry span 的 subscriber layer
+    pub fn layer<S>(&self) -> tracing_opentelemetry::OpenTelemetryLayer<S, opentelemetry_sdk::trace::Tracer>
+    where
+        S: tracing::Subscriber + for<'span> LookupSpan<'span>,
+    {
+        tracing_opentelemetry::layer().with_tracer(self.provider.tracer(DEFAULT_SERVICE_NAME))
+    }
+
+    /// 发送缓冲中的 span 并关闭导出器
+    pub fn shutdown(&self) {
+        if let Err(e) = self.provider.shutdown() {
+            eprintln!("[Telemetry] Failed to flush spans: {}", e);
+        }
+    }
+}
+
+/// 以请求头中的 W3C trace context 作为 `span` 的父 span
+pub fn set_parent_from_headers(span: &Span, headers: &HeaderMap) {
+    let parent = opentelemetry::global::get_text_map_propagator(|p| p.extract(&HeaderExtractor(headers)));
+    let _ = span.set_parent(parent);
+}
+
+/// 把当前 span 的 trace context 写入出站请求头
+pub fn inject_context(request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+    let mut headers = HeaderMap::new();
+    let context = Span::current().context();
+    opentelemetry::global::get_text_map_propagator(|p| p.inject_context(&context, &mut HeaderInjector(&mut headers)));
+    request.headers(headers)
+}
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|v| v.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(HeaderName::as_str).collect()
+    }
+}
+
+struct HeaderInjector<'a>(&'a mut HeaderMap);
+
+impl Injector for HeaderInjector<'_> {
+    fn set(&mut self, key: &str, value: String) {
+        if let (Ok(name), Ok(value)) = (HeaderName::from_bytes(key.as_bytes()), HeaderValue::from_str(&value)) {
+            self.0.insert(name, value);
+        }
+    }
+}
@@ -0,0 +1,199 @@
+//! HS256 JWT 签发与校验
+//!
+//! 只支持 `alg: HS256`；`exp` 必填，`nbf` 可选，两者允许 60 秒时钟偏差。
+//! 配置 `XHS_JWT_ISSUER` 时签发的令牌带 `iss`，校验时要求一致。
+
+use anyhow::{anyhow, bail, Result};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// `exp` / `nbf` 允许的时钟偏差 (秒)
+const LEEWAY_SECS: i64 = 60;
+
+/// 管理员令牌的 `role`
+pub const ADMIN_ROLE: &str = "admin";
+
+#[derive(Serialize, Deserialize)]
+struct Header {
+    alg: String,
+    #[serde(default)]
+    typ: Option<String>,
+}
+
+/// 令牌声明
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Claims {
+    /// 租户 ID（管理员令牌可为任意值）
+    pub sub: String,
+    /// 过期时间 (Unix 秒)
+    pub exp: i64,
+    /// 签发时间 (Unix 秒)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iat: Option<i64>,
+    /// 生效时间 (Unix 秒)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nbf: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iss: Option<String>,
+    /// `admin` 表示管理员令牌
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+}
+
+impl Claims {
+    pub fn is_admin(&self) -> bool {
+        self.role.as_deref() == Some(ADMIN_ROLE)
+    }
+}
+
+/// HS256 密钥
+pub struct JwtKey {
+    secret: Vec<u8>,
+    issuer: Option<String>,
+}
+
+impl JwtKey {
+    pub fn new(secret: Vec<u8>, issuer: Option<String>) -> Self {
+        Self { secret, issuer }
+    }
+
+    /// 读取 XHS_JWT_SECRET / XHS_JWT_ISSUER，未设置密钥时返回 None
+    pub fn from_env() -> Option<Self> {
+        let secret = std::env::var("XHS_JWT_SECRET").ok().filter(|s| !s.is_empty())?;
+        let issuer = std::env::var("XHS_JWT_ISSUER").ok().filter(|s| !s.is_empty());
+        Some(Self::new(secret.into_bytes(), issuer))
+    }
+
+    /// 签发令牌，有效期 `ttl_secs` 秒
+    pub fn issue(&self, sub: &str, role: Option<&str>, ttl_secs: i64) -> Result<String> {
+        let now = chrono::Utc::now().timestamp();
+        let claims = Claims {
+            sub: sub.to_string(),
+            exp: now + ttl_secs,
+            iat: Some(now),
+            nbf: None,
+            iss: self.issuer.clone(),
+            role: role.map(str::to_string),
+        };
+        let header = Header { alg: "HS256".to_string(), typ: Some("JWT".to_string()) };
+        let signing_input = format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(serde_json::to_vec(&header)?),
+            URL_SAFE_NO_PAD.encode(serde_json::to_vec(&claims)?),
+        );
+        let signature = URL_SAFE_NO_PAD.encode(self.mac().chain_update(signing_input.as_bytes()).finalize().into_bytes());
+        Ok(format!("{}.{}", signing_input, signature))
+    }
+
+    /// 校验签名与有效期，返回声明
+    pub fn verify(&self, token: &str) -> Result<Claims> {
+        let mut parts = token.split('.');
+        let (Some(header), Some(payload), Some(signature), None) = (parts.next(), parts.next(), parts.next(), parts.next()) else {
+            bail!("Malformed token");
+        };
+        let header: Header = serde_json::from_slice(&decode(header)?).map_err(|_| anyhow!("Malformed token"))?;
+        if header.alg != "HS256" {
+            bail!("Unsupported token algorithm: {}", header.alg);
+        }
+        self.mac()
+            .chain_update(signing_input(token).as_bytes())
+            .verify_slice(&decode(signature)?)
+            .map_err(|_| anyhow!("Invalid token signature"))?;
+
+        let claims: Claims = serde_json::from_slice(&decode(payload)?).map_err(|e| anyhow!("Invalid token claims: {}", e))?;
+        let now = chrono::Utc::now().timestamp();
+        if claims.exp + LEEWAY_SECS < now {
+            bail!("Token expired");
+        }
+        if claims.nbf.is_some_and(|nbf| nbf - LEEWAY_SECS > now) {
+            bail!("Token not yet valid");
+        }
+        if let Some(issuer) = &self.issuer {
+            if claims.iss.as_ref() != Some(issuer) {
+                bail!("Unexpected token issuer");
+            }
+        }
+        Ok(claims)
+    }
+
+    fn mac(&self) -> HmacSha256 {
+        HmacSha256::new_from_slice(&self.secret).expect("HMAC accepts any key length")
+    }
+}
+
+/// 被签名的 `header.payload` 部分
+fn signing_input(token: &str) -> &str {
+    &token[..token.rfind('.').unwrap_or(token.len())]
+}
+
+fn decode(part: &str) -> Result<Vec<u8>> {
+    URL_SAFE_NO_PAD.decode(part).map_err(|_| anyhow!("Malformed token"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key() -> JwtKey {
+        JwtKey::new(b"test-secret".to_vec(), None)
+    }
+
+    /// 用 `key` 签名任意声明（绕过 `issue` 固定的 exp / nbf）
+    fn sign(key: &JwtKey, claims: &serde_json::Value) -> String {
+        let header = URL_SAFE_NO_PAD.encode(br#"{"alg":"HS256","typ":"JWT"}"#);
+        let input = format!("{}.{}", header, URL_SAFE_NO_PAD.encode(claims.to_string()));
+        let signature = URL_SAFE_NO_PAD.encode(key.mac().chain_update(input.as_bytes()).finalize().into_bytes());
+        format!("{}.{}", input, signature)
+    }
+
+    #[test]
+    fn verifies_issued_token() {
+        let token = key().issue("alice", Some(ADMIN_ROLE), 3600).unwrap();
+        let claims = key().verify(&token).unwrap();
+        assert_eq!(claims.sub, "alice");
+        assert!(claims.is_admin());
+    }
+
+    #[test]
+    fn rejects_wrong_secret_and_tampering() {
+        let token = key().issue("alice", None, 3600).unwrap();
+        assert!(JwtKey::new(b"other".to_vec(), None).verify(&token).is_err());
+
+        let (input, signature) = token.rsplit_once('.').unwrap();
+        let (header, _) = input.split_once('.').unwrap();
+        let forged = URL_SAFE_NO_PAD.encode(br#"{"sub":"alice","exp":9999999999,"role":"admin"}"#);
+        assert!(key().verify(&format!("{}.{}.{}", header, forged, signature)).is_err());
+        assert!(key().verify(input).is_err());
+        assert!(key().verify(&format!("{}.x", token)).is_err());
+    }
+
+    #[test]
+    fn rejects_other_algorithms() {
+        let header = URL_SAFE_NO_PAD.encode(br#"{"alg":"none"}"#);
+        let payload = URL_SAFE_NO_PAD.encode(br#"{"sub":"alice","exp":9999999999}"#);
+        let err = key().verify(&format!("{}.{}.", header, payload)).unwrap_err();
+        assert!(err.to_string().contains("Unsupported token algorithm"));
+    }
+
+    #[test]
+    fn enforces_expiry_and_not_before_with_leeway() {
+        let now = chrono::Utc::now().timestamp();
+        let key = key();
+        assert!(key.verify(&sign(&key, &serde_json::json!({"sub": "a", "exp": now - LEEWAY_SECS / 2}))).is_ok());
+        assert!(key.verify(&sign(&key, &serde_json::json!({"sub": "a", "exp": now - LEEWAY_SECS - 5}))).is_err());
+        assert!(key.verify(&sign(&key, &serde_json::json!({"sub": "a", "exp": now + 60, "nbf": now + LEEWAY_SECS / 2}))).is_ok());
+        assert!(key.verify(&sign(&key, &serde_json::json!({"sub": "a", "exp": now + 600, "nbf": now + LEEWAY_SECS + 5}))).is_err());
+    }
+
+    #[test]
+    fn checks_issuer_when_configured() {
+        let with_issuer = JwtKey::new(b"test-secret".to_vec(), Some("xhs".to_string()));
+        assert!(with_issuer.verify(&with_issuer.issue("alice", None, 60).unwrap()).is_ok());
+        assert!(with_issuer.verify(&key().issue("alice", None, 60).unwrap()).is_err());
+    }
+}
@@ -0,0 +1,319 @@
+//! 多租户 (Multi-tenant JWT Auth)
+//!
+//! 设置 `XHS_JWT_SECRET` 后，除 `/healthz`、`/readyz`、`/files/{token}` 与 Swagger UI 外的所有接口都需要
+//! 携带 `Authorization: Bearer <JWT>`（HS256，WebSocket / SSE 可改用 `?access_token=`）。
+//!
+//! - 租户令牌: `sub` 为租户 ID。每个租户有独立的凭据文件（含创作者中心）、扫码登录会话、
+//!   行为画像、风控熔断、出站限流与发布任务列表，请求只会使用该租户自己的账号；
+//!   定时任务 / 监控 / 归档 / 服务端下载等部署级共享接口（[`ADMIN_ONLY_PREFIXES`]）对租户返回 403。
+//! - 管理员令牌: `role: "admin"`，使用部署自身的账号 (cookie.json)，可访问全部接口。
+//!
+//! 令牌可用 `xhs-rs token <tenant>` 签发。配置 (环境变量):
+//! - `XHS_JWT_SECRET`: HS256 密钥，设置后启用
+//! - `XHS_JWT_ISSUER`: 可选，签发与校验 `iss`
+//! - `XHS_TENANTS_FILE`: 租户列表 (JSON 数组)，默认 ./tenants.json
+//! - `XHS_TENANTS_DIR`: 未指定凭据文件时的存放目录，默认 ./tenants（`{dir}/{id}/cookie.json`）
+//!
+//! ```json
+//! [{"id": "alice", "rate_limit_rpm": 30, "rate_limit_burst": 3}, {"id": "bob"}]
+//! ```
+
+pub mod jwt;
+
+use anyhow::{anyhow, Result};
+use axum::{
+    extract::{Request, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json, Router,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+use crate::activity::ActivityGovernor;
+use crate::api::creator::publish::jobs::PublishJobs;
+use crate::api::notification::poll::NotificationPoller;
+use crate::api::rate_limit::RateLimiter;
+use crate::api::XhsApiClient;
+use crate::auth::AuthService;
+use crate::client::XhsClient;
+use crate::error::ApiErrorBody;
+use crate::server::AppState;
+use jwt::JwtKey;
+
+/// 只允许管理员令牌访问的路由前缀（部署级共享资源）
+pub const ADMIN_ONLY_PREFIXES: &[&str] = &[
+    "/api/jobs",
+    "/api/monitors",
+    "/api/watch",
+    "/api/archive",
+    "/api/cluster",
+    "/api/admin",
+    "/api/creator/schedule",
+    // 下载根目录、签名密钥与下载任务列表为部署共享
+    "/api/media/download",
+    "/api/media/jobs",
+    "/api/media/bundle",
+    "/api/media/share",
+    "/api/system",
+    "/api/metrics",
+    "/api/about",
+    "/api/debug",
+];
+
+/// 租户配置
+#[derive(Debug, Clone, Deserialize)]
+pub struct TenantConfig {
+    /// 租户 ID，即令牌的 `sub`
+    pub id: String,
+    /// 凭据文件，默认 {XHS_TENANTS_DIR}/{id}/cookie.json
+    #[serde(default)]
+    pub credentials_file: Option<PathBuf>,
+    /// 创作者中心凭据文件，默认 {XHS_TENANTS_DIR}/{id}/cookie-creator.json
+    #[serde(default)]
+    pub creator_credentials_file: Option<PathBuf>,
+    /// 全局出站限流（每分钟请求数），默认同 XHS_RATE_LIMIT_RPM
+    #[serde(default)]
+    pub rate_limit_rpm: Option<u32>,
+    /// 突发容量，默认同 XHS_RATE_LIMIT_BURST
+    #[serde(default)]
+    pub rate_limit_burst: Option<u32>,
+    /// 停用的租户令牌返回 403
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool { true }
+
+impl TenantConfig {
+    fn validate(&self) -> Result<()> {
+        let valid = !self.id.is_empty()
+            && self.id.len() <= 64
+            && self.id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(anyhow!("Invalid tenant id: {:?}", self.id));
+        }
+        Ok(())
+    }
+
+    /// 租户的限流配置，未单独设置的部分取全局配置
+    fn rate_limits(&self) -> (u32, u32, Vec<(String, u32, u32)>) {
+        let (rpm, burst) = crate::config::rate_limit_global();
+        (
+            self.rate_limit_rpm.unwrap_or(rpm),
+            self.rate_limit_burst.unwrap_or(burst),
+            crate::config::rate_limit_endpoints(),
+        )
+    }
+
+    fn rate_limiter(&self) -> RateLimiter {
+        let (rpm, burst, endpoints) = self.rate_limits();
+        RateLimiter::new(rpm, burst, endpoints)
+    }
+}
+
+/// 读取租户列表
+pub async fn load_tenants(path: &std::path::Path) -> Result<Vec<TenantConfig>> {
+    let tenants: Vec<TenantConfig> = match tokio::fs::read_to_string(path).await {
+        Ok(content) if content.trim().is_empty() => Vec::new(),
+        Ok(content) => serde_json::from_str(&content)
+            .map_err(|e| anyhow!("Invalid tenants file {}: {}", path.display(), e))?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(e.into()),
+    };
+    for (i, tenant) in tenants.iter().enumerate() {
+        tenant.validate()?;
+        if tenants[..i].iter().any(|t| t.id == tenant.id) {
+            return Err(anyhow!("Duplicate tenant id: {}", tenant.id));
+        }
+    }
+    Ok(tenants)
+}
+
+/// 为租户构建独立的 AppState：账号相关部分独立，其余与部署共享
+async fn tenant_state(base: &AppState, tenant: &TenantConfig, dir: &std::path::Path) -> Result<AppState> {
+    let tenant_dir = dir.join(&tenant.id);
+    let credentials = tenant.credentials_file.clone().unwrap_or_else(|| tenant_dir.join("cookie.json"));
+    let creator_credentials = tenant.creator_credentials_file
+        .clone()
+        .unwrap_or_else(|| tenant_dir.join("cookie-creator.json"));
+    for path in [&credentials, &creator_credentials] {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+    }
+
+    let auth = Arc::new(AuthService::new(credentials).await?);
+    let mut api = XhsApiClient::new(XhsClient::new()?, auth.clone())
+        .with_activity(Arc::new(ActivityGovernor::from_env()?))
+        .with_rate_limiter(tenant.rate_limiter())
+        .with_maintenance(base.api.maintenance().clone())
+        .with_signature_policy(base.api.signature_policy().clone());
+    if let Some(archive) = base.api.archive() {
+        api = api.with_archive(archive.clone());
+    }
+    if let Some(audit) = base.api.audit() {
+        api = api.with_audit(audit.clone());
+    }
+
+    Ok(AppState {
+        api,
+        auth,
+        creator_auth: Arc::new(AuthService::new(creator_credentials).await?),
+        login_sessions: Arc::new(base.login_sessions.scoped(&tenant.id)),
+        qr_watcher: Arc::new(crate::api::login::QrStatusWatcher::default()),
+        notification_poller: NotificationPoller::new(crate::config::notification_poll_interval()),
+        note_cache: base.note_cache.clone(),
+        response_cache: base.response_cache.clone(),
+        coordinator: base.coordinator.clone(),
+        file_signer: base.file_signer.clone(),
+        publish_jobs: Arc::new(PublishJobs::new()),
+        download_jobs: base.download_jobs.clone(),
+        publish_schedule: base.publish_schedule.clone(),
+        crawl_jobs: base.crawl_jobs.clone(),
+        monitors: base.monitors.clone(),
+        watch_list: base.watch_list.clone(),
+    })
+}
+
+/// JWT 网关：校验令牌后把请求转发到对应租户（或管理员）的路由
+pub struct TenantGateway {
+    key: JwtKey,
+    admin: Router,
+    tenants: HashMap<String, Router>,
+    /// 配置热加载时逐个应用
+    states: Vec<(TenantConfig, Arc<AppState>)>,
+    description: String,
+}
+
+impl TenantGateway {
+    /// `XHS_JWT_SECRET` 未设置时返回 None。
+    /// `routes` 为受保护的路由表，分别绑定部署与各租户的 AppState
+    pub async fn from_env(
+        state: &Arc<AppState>,
+        routes: impl Fn(&Arc<AppState>) -> Router<Arc<AppState>>,
+    ) -> Result<Option<Self>> {
+        let path = std::env::var("XHS_TENANTS_FILE")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from("./tenants.json"));
+        let Some(key) = JwtKey::from_env() else {
+            if std::env::var("XHS_TENANTS_FILE").is_ok() {
+                return Err(anyhow!("XHS_TENANTS_FILE requires XHS_JWT_SECRET"));
+            }
+            return Ok(None);
+        };
+        let dir = std::env::var("XHS_TENANTS_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from("./tenants"));
+
+        let mut tenants = HashMap::new();
+        let mut states = Vec::new();
+        for tenant in load_tenants(&path).await? {
+            if !tenant.enabled {
+                tracing::info!("[Tenant] {} is disabled", tenant.id);
+                continue;
+            }
+            let tenant_state = Arc::new(tenant_state(state, &tenant, &dir).await?);
+            crate::auth::health::spawn_health_checker(tenant_state.clone());
+            crate::api::risk::spawn_probe(tenant_state.clone());
+            tenants.insert(tenant.id.clone(), routes(&tenant_state).with_state(tenant_state.clone()));
+            states.push((tenant, tenant_state));
+        }
+
+        let description = format!("jwt: {} ({} tenant(s))", path.display(), tenants.len());
+        tracing::info!("[Tenant] JWT auth enabled, {}", description);
+        Ok(Some(Self { key, admin: routes(state).with_state(state.clone()), tenants, states, description }))
+    }
+
+    /// 描述，如 "jwt: ./tenants.json (2 tenant(s))"
+    pub fn describe(&self) -> &str {
+        &self.description
+    }
+
+    /// 重新加载各租户的行为画像
+    pub fn reload_activity(&self) {
+        for (tenant, state) in &self.states {
+            if let Err(e) = state.api.activity().reload_from_env() {
+                tracing::warn!("[Tenant] Keeping the current activity profiles for {}: {}", tenant.id, e);
+            }
+        }
+    }
+
+    /// 把热加载的限流与代理配置应用到各租户
+    pub fn apply_reload(&self, changed: &[&str]) {
+        let rate_limits = changed.iter().any(|key| key.starts_with("XHS_RATE_LIMIT_"));
+        let proxy = changed.iter().any(|key| matches!(*key, "XHS_PROXY" | "XHS_NO_PROXY"));
+        for (tenant, state) in &self.states {
+            if rate_limits {
+                let (rpm, burst, endpoints) = tenant.rate_limits();
+                state.api.rate_limiter().reconfigure(rpm, burst, endpoints);
+            }
+            if proxy {
+                if let Err(e) = state.api.reload_proxy() {
+                    tracing::warn!("[Tenant] Failed to apply proxy settings for {}: {}", tenant.id, e);
+                }
+            }
+        }
+    }
+}
+
+/// 受保护路由的入口（网关路由的 fallback）
+pub async fn dispatch(State(gateway): State<Arc<TenantGateway>>, request: Request) -> Response {
+    let Some(token) = bearer_token(&request) else {
+        return reject(StatusCode::UNAUTHORIZED, "Missing bearer token");
+    };
+    let claims = match gateway.key.verify(&token) {
+        Ok(claims) => claims,
+        Err(e) => return reject(StatusCode::UNAUTHORIZED, &e.to_string()),
+    };
+
+    let router = if claims.is_admin() {
+        gateway.admin.clone()
+    } else {
+        let path = request.uri().path();
+        if ADMIN_ONLY_PREFIXES.iter().any(|p| path == *p || path.starts_with(&format!("{}/", p))) {
+            return reject(StatusCode::FORBIDDEN, "This endpoint requires an admin token");
+        }
+        match gateway.tenants.get(&claims.sub) {
+            Some(router) => router.clone(),
+            None => return reject(StatusCode::FORBIDDEN, &format!("Unknown or disabled tenant: {}", claims.sub)),
+        }
+    };
+    match router.oneshot(request).await {
+        Ok(response) => response,
+        Err(never) => match never {},
+    }
+}
+
+/// `Authorization: Bearer` 或 `?access_token=`（WebSocket / SSE）
+fn bearer_token(request: &Request) -> Option<String> {
+    let header = request.headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer ").or_else(|| v.strip_prefix("bearer ")))
+        .map(|t| t.trim().to_string());
+    header.or_else(|| {
+        url::form_urlencoded::parse(request.uri().query()?.as_bytes())
+            .find(|(k, _)| k == "access_token")
+            .map(|(_, v)| v.into_owned())
+    })
+}
+
+fn reject(status: StatusCode, msg: &str) -> Response {
+    let body = ApiErrorBody {
+        code: -1,
+        success: false,
+        msg: msg.to_string(),
+        data: None,
+        error_kind: if status == StatusCode::UNAUTHORIZED { "unauthorized" } else { "forbidden" }.to_string(),
+        request_id: crate::response::current_request_id(),
+    };
+    let mut response = (status, Json(body)).into_response();
+    if status == StatusCode::UNAUTHORIZED {
+        response.headers_mut().insert(header::WWW_AUTHENTICATE, header::HeaderValue::from_static("Bearer"));
+    }
+    response
+}
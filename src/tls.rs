@@ -0,0 +1,192 @@
+//! 内置 HTTPS (Native TLS)
+//!
+//! 无反向代理的部署由服务自身终止 TLS，配置见 [`crate::config::tls_config`]:
+//! - 证书文件: `XHS_TLS_CERT` / `XHS_TLS_KEY` (PEM)，每分钟检查修改时间，续期后自动加载
+//! - ACME: `XHS_TLS_ACME_DOMAINS`，通过 TLS-ALPN-01 在同一端口完成验证并自动续期，
+//!   因此监听端口需能从公网 443 访问；账号与证书缓存在 `XHS_TLS_ACME_CACHE`
+//!
+//! 通过 ALPN 同时支持 HTTP/2 与 HTTP/1.1，WebSocket 升级照常可用。
+
+use anyhow::{anyhow, Result};
+use axum::Router;
+use futures_util::StreamExt;
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use hyper_util::server::conn::auto;
+use hyper_util::service::TowerToHyperService;
+use rustls_acme::caches::DirCache;
+use rustls_acme::futures_rustls::rustls::{self, ServerConfig};
+use rustls_acme::futures_rustls::server::TlsStream;
+use rustls_acme::futures_rustls::{LazyConfigAcceptor, TlsAcceptor};
+use rustls_acme::{is_tls_alpn_challenge, AcmeConfig};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_util::compat::{Compat, FuturesAsyncReadCompatExt, TokioAsyncReadCompatExt};
+
+use crate::config::TlsConfig;
+
+/// TLS 握手超时
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 证书文件的检查间隔
+const RELOAD_INTERVAL: Duration = Duration::from_secs(60);
+
+enum Acceptor {
+    /// 证书文件，续期后整体替换
+    Files(Arc<RwLock<Arc<ServerConfig>>>),
+    Acme {
+        /// TLS-ALPN-01 验证连接使用
+        challenge: Arc<ServerConfig>,
+        default: Arc<ServerConfig>,
+    },
+}
+
+impl Acceptor {
+    async fn new(config: TlsConfig) -> Result<Self> {
+        match config {
+            TlsConfig::Files { cert, key } => {
+                let server_config = Arc::new(RwLock::new(load_config(&cert, &key)?));
+                tracing::info!("[TLS] Serving certificate {}", cert.display());
+                spawn_reloader(server_config.clone(), cert, key);
+                Ok(Self::Files(server_config))
+            }
+            TlsConfig::Acme { domains, contact, cache_dir, staging } => {
+                tokio::fs::create_dir_all(&cache_dir).await?;
+                tracing::info!(
+                    "[TLS] Requesting certificates for {} via ACME ({}, cache: {})",
+                    domains.join(", "),
+                    if staging { "Let's Encrypt staging" } else { "Let's Encrypt" },
+                    cache_dir.display()
+                );
+                let mut state = AcmeConfig::new(domains)
+                    .contact(contact.iter().map(|c| format!("mailto:{}", c.trim_start_matches("mailto:"))))
+                    .cache(DirCache::new(cache_dir))
+                    .directory_lets_encrypt(!staging)
+                    .state();
+                let mut default = ServerConfig::builder().with_no_client_auth().with_cert_resolver(state.resolver());
+                default.alpn_protocols = alpn_protocols();
+                let challenge = state.challenge_rustls_config();
+
+                // 申请 / 续期由状态流驱动
+                tokio::spawn(async move {
+                    while let Some(event) = state.next().await {
+                        match event {
+                            Ok(ok) => tracing::info!("[TLS] ACME: {:?}", ok),
+                            Err(e) => tracing::warn!("[TLS] ACME: {}", e),
+                        }
+                    }
+                });
+                Ok(Self::Acme { challenge, default: Arc::new(default) })
+            }
+        }
+    }
+
+    /// 完成握手；ACME 验证连接返回 None
+    async fn accept(&self, tcp: TcpStream) -> std::io::Result<Option<TlsStream<Compat<TcpStream>>>> {
+        match self {
+            Self::Files(config) => {
+                let config = config.read().unwrap_or_else(|e| e.into_inner()).clone();
+                TlsAcceptor::from(config).accept(tcp.compat()).await.map(Some)
+            }
+            Self::Acme { challenge, default } => {
+                let start = LazyConfigAcceptor::new(rustls::server::Acceptor::default(), tcp.compat()).await?;
+                if is_tls_alpn_challenge(&start.client_hello()) {
+                    tracing::info!("[TLS] Answering TLS-ALPN-01 validation request");
+                    start.into_stream(challenge.clone()).await?;
+                    return Ok(None);
+                }
+                start.into_stream(default.clone()).await.map(Some)
+            }
+        }
+    }
+}
+
+/// 以 HTTPS 提供服务，直到监听出错
+pub async fn serve(listener: TcpListener, app: Router, config: TlsConfig) -> Result<()> {
+    let acceptor = Arc::new(Acceptor::new(config).await?);
+    let builder = auto::Builder::new(TokioExecutor::new());
+    loop {
+        let (tcp, peer) = match listener.accept().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                // 与 axum::serve 一致：文件描述符耗尽等错误时稍后重试
+                tracing::warn!("[TLS] Failed to accept connection: {}", e);
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                continue;
+            }
+        };
+        let acceptor = acceptor.clone();
+        let builder = builder.clone();
+        let service = TowerToHyperService::new(app.clone());
+        tokio::spawn(async move {
+            let tls = match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(tcp)).await {
+                Ok(Ok(Some(tls))) => tls,
+                Ok(Ok(None)) => return,
+                Ok(Err(e)) => {
+                    tracing::debug!("[TLS] Handshake with {} failed: {}", peer, e);
+                    return;
+                }
+                Err(_) => {
+                    tracing::debug!("[TLS] Handshake with {} timed out", peer);
+                    return;
+                }
+            };
+            if let Err(e) = builder.serve_connection_with_upgrades(TokioIo::new(tls.compat()), service).await {
+                tracing::debug!("[TLS] Connection from {} closed: {}", peer, e);
+            }
+        });
+    }
+}
+
+fn alpn_protocols() -> Vec<Vec<u8>> {
+    vec![b"h2".to_vec(), b"http/1.1".to_vec()]
+}
+
+fn load_config(cert: &Path, key: &Path) -> Result<Arc<ServerConfig>> {
+    let open = |path: &Path| {
+        std::fs::File::open(path)
+            .map(std::io::BufReader::new)
+            .map_err(|e| anyhow!("Failed to open {}: {}", path.display(), e))
+    };
+    let certs = rustls_pemfile::certs(&mut open(cert)?).collect::<Result<Vec<_>, _>>()?;
+    if certs.is_empty() {
+        return Err(anyhow!("No certificate found in {}", cert.display()));
+    }
+    let private_key = rustls_pemfile::private_key(&mut open(key)?)?
+        .ok_or_else(|| anyhow!("No private key found in {}", key.display()))?;
+    let mut config = ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(certs, private_key)
+        .map_err(|e| anyhow!("Invalid certificate or key: {}", e))?;
+    config.alpn_protocols = alpn_protocols();
+    Ok(Arc::new(config))
+}
+
+/// 证书或私钥文件修改后重新加载；加载失败时保留旧证书，下次检查再试
+fn spawn_reloader(config: Arc<RwLock<Arc<ServerConfig>>>, cert: PathBuf, key: PathBuf) {
+    tokio::spawn(async move {
+        let mut loaded = modified(&cert, &key).await;
+        loop {
+            tokio::time::sleep(RELOAD_INTERVAL).await;
+            let current = modified(&cert, &key).await;
+            if current == loaded {
+                continue;
+            }
+            match load_config(&cert, &key) {
+                Ok(reloaded) => {
+                    *config.write().unwrap_or_else(|e| e.into_inner()) = reloaded;
+                    loaded = current;
+                    tracing::info!("[TLS] Reloaded certificate {}", cert.display());
+                }
+                Err(e) => tracing::warn!("[TLS] Failed to reload certificate, keeping the previous one: {}", e),
+            }
+        }
+    });
+}
+
+async fn modified(cert: &Path, key: &Path) -> Option<(SystemTime, SystemTime)> {
+    let cert = tokio::fs::metadata(cert).await.ok()?.modified().ok()?;
+    let key = tokio::fs::metadata(key).await.ok()?.modified().ok()?;
+    Some((cert, key))
+}
@@ -0,0 +1,133 @@
+//! 设备标识 (a1 / webId) 生成
+//!
+//! 与网页端 JS 同算法：
+//! `a1 = (hex(毫秒时间戳) + 30 位 [0-9a-z] 随机串 + 平台码 + "0" + "000" + CRC32(前面部分))[..52]`，
+//! `webId = md5(a1)`。
+//!
+//! [`generate_a1`] 对相同输入总是得到相同结果；[`DeviceIdentity::from_seed`] 由种子派生随机串，
+//! 可为同一账号 / 配置复现同一设备，[`DeviceIdentity::new`] 为每次调用生成新设备。
+//! 纯 Rust 访客初始化与浏览器指纹使用这里生成的设备标识，无需 Playwright。
+
+use rand::Rng;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// a1 固定长度
+pub const A1_LEN: usize = 52;
+
+/// a1 随机段长度
+const ENTROPY_LEN: usize = 30;
+
+/// a1 随机段字符集（网页端 JS 使用小写字母 + 数字）
+const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+
+/// 设备平台，决定 a1 中的平台码
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DevicePlatform {
+    Ios,
+    Android,
+    MacOs,
+    Linux,
+    #[default]
+    Windows,
+}
+
+impl DevicePlatform {
+    /// a1 平台码（网页端 getPlatformCode）
+    pub fn code(self) -> u8 {
+        match self {
+            Self::Ios => 1,
+            Self::Android => 2,
+            Self::MacOs => 3,
+            Self::Linux => 4,
+            Self::Windows => 5,
+        }
+    }
+
+    /// 由 `sec-ch-ua-platform` 取值（可带引号，如 `"macOS"`）推断，未知平台按 Windows 处理
+    pub fn from_client_hint(platform: &str) -> Self {
+        match platform.trim_matches('"').to_lowercase().as_str() {
+            "ios" => Self::Ios,
+            "android" => Self::Android,
+            "macos" | "mac os" => Self::MacOs,
+            "linux" | "chrome os" | "chromeos" => Self::Linux,
+            _ => Self::Windows,
+        }
+    }
+}
+
+/// 一台设备的 a1 / webId Cookie
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceIdentity {
+    pub a1: String,
+    pub web_id: String,
+}
+
+impl DeviceIdentity {
+    /// 新设备：当前时间 + 随机串
+    pub fn new(platform: DevicePlatform) -> Self {
+        let mut rng = rand::thread_rng();
+        let entropy: String = (0..ENTROPY_LEN)
+            .map(|_| ALPHABET[rng.gen_range(0..ALPHABET.len())] as char)
+            .collect();
+        Self::from_a1(generate_a1(now_millis(), &entropy, platform))
+    }
+
+    /// 由种子派生的设备：相同的种子、创建时间与平台总是得到相同的 a1 / webId
+    pub fn from_seed(seed: &str, created_ms: u64, platform: DevicePlatform) -> Self {
+        let digest = Sha256::digest(seed.as_bytes());
+        let entropy: String = digest
+            .iter()
+            .cycle()
+            .take(ENTROPY_LEN)
+            .map(|b| ALPHABET[*b as usize % ALPHABET.len()] as char)
+            .collect();
+        Self::from_a1(generate_a1(created_ms, &entropy, platform))
+    }
+
+    /// 已有 a1 的设备（webId 由 a1 派生）
+    pub fn from_a1(a1: String) -> Self {
+        let web_id = generate_web_id(&a1);
+        Self { a1, web_id }
+    }
+
+    /// 写入 Cookie 集合（覆盖已有的 a1 / webId）
+    pub fn apply_to(&self, cookies: &mut HashMap<String, String>) {
+        cookies.insert("a1".to_string(), self.a1.clone());
+        cookies.insert("webId".to_string(), self.web_id.clone());
+    }
+}
+
+/// 生成 a1：hex(时间戳) + 随机串 + 平台码 + "0000" + CRC32 校验，截断为 52 位
+pub fn generate_a1(timestamp_ms: u64, entropy: &str, platform: DevicePlatform) -> String {
+    let base = format!("{:x}{}{}0000", timestamp_ms, entropy, platform.code());
+    let mut a1 = format!("{}{}", base, crc32(base.as_bytes()));
+    a1.truncate(A1_LEN);
+    a1
+}
+
+/// 由 a1 派生 webId (md5(a1))
+pub fn generate_web_id(a1: &str) -> String {
+    format!("{:x}", md5::compute(a1.as_bytes()))
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// CRC32 (IEEE)，与 JS 端 a1 校验位一致
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
@@ -1,5 +1,10 @@
+pub mod device;
 pub mod sign;
+pub mod storage;
+pub mod url;
+#[cfg(feature = "qrcode")]
 pub mod qrcode;
 
+#[cfg(feature = "qrcode")]
 pub use qrcode::{QrCodeResult, generate_qr_ascii, print_qr_to_terminal};
 
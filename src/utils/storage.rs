@@ -0,0 +1,128 @@
+//! 存储连通性检查，供就绪探针 (`/readyz`) 使用；各 MongoDB 存储共用的客户端；
+//! 以及文件后端共用的 JSON 数组存储 [`JsonFileStore`]
+
+use anyhow::{anyhow, Result};
+use serde::{de::DeserializeOwned, Serialize};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use tokio::sync::Mutex;
+
+/// 整个集合保存在一个 JSON 数组文件中，每次修改整体重写（临时文件 + rename 原子替换）。
+/// 读改写在进程内串行，适合单实例；多实例共享时使用 MongoDB 后端
+pub struct JsonFileStore<T> {
+    path: PathBuf,
+    /// 串行化读改写
+    lock: Mutex<()>,
+    _items: PhantomData<fn() -> T>,
+}
+
+impl<T: Serialize + DeserializeOwned> JsonFileStore<T> {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path, lock: Mutex::new(()), _items: PhantomData }
+    }
+
+    /// 文件可读且所在目录可写
+    pub async fn ping(&self) -> Result<()> {
+        self.load().await.map_err(|e| anyhow!("{}: {}", self.path.display(), e))?;
+        check_dir(self.path.parent().unwrap_or(Path::new("."))).await
+    }
+
+    /// 读取全部条目（文件不存在或为空时为空列表）
+    pub async fn load(&self) -> Result<Vec<T>> {
+        let _guard = self.lock.lock().await;
+        self.read().await
+    }
+
+    /// 对全部条目执行 `f`，`f` 报告有修改时写回
+    pub async fn modify<R>(&self, f: impl FnOnce(&mut Vec<T>) -> (bool, R)) -> Result<R> {
+        let _guard = self.lock.lock().await;
+        let mut items = self.read().await?;
+        let (changed, value) = f(&mut items);
+        if changed {
+            self.save(&items).await?;
+        }
+        Ok(value)
+    }
+
+    async fn read(&self) -> Result<Vec<T>> {
+        match tokio::fs::read_to_string(&self.path).await {
+            Ok(content) if content.trim().is_empty() => Ok(Vec::new()),
+            Ok(content) => Ok(serde_json::from_str(&content)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn save(&self, items: &[T]) -> Result<()> {
+        if let Some(dir) = self.path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        let tmp = self.path.with_extension("json.tmp");
+        tokio::fs::write(&tmp, serde_json::to_string_pretty(items)?).await?;
+        tokio::fs::rename(&tmp, &self.path).await?;
+        Ok(())
+    }
+}
+
+/// 文件存储目录可写；目录尚未创建时检查最近的已存在上级目录（首次写入时会自动创建）
+pub async fn check_dir(dir: &Path) -> Result<()> {
+    let dir = if dir.as_os_str().is_empty() { Path::new(".") } else { dir };
+    let mut current = Some(dir);
+    while let Some(path) = current {
+        match tokio::fs::metadata(path).await {
+            Ok(meta) if !meta.is_dir() => return Err(anyhow!("{} is not a directory", path.display())),
+            Ok(meta) if meta.permissions().readonly() => return Err(anyhow!("{} is read-only", path.display())),
+            Ok(_) => return Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                current = path
+                    .parent()
+                    .map(|p| if p.as_os_str().is_empty() { Path::new(".") } else { p })
+                    .filter(|p| *p != path);
+            }
+            Err(e) => return Err(anyhow!("{}: {}", path.display(), e)),
+        }
+    }
+    Ok(())
+}
+
+/// 对集合所在的 MongoDB 部署执行 `ping`
+#[cfg(feature = "mongodb")]
+pub async fn ping_mongo<T: Send + Sync>(collection: &mongodb::Collection<T>) -> Result<()> {
+    collection
+        .client()
+        .database("admin")
+        .run_command(mongodb::bson::doc! { "ping": 1 })
+        .await?;
+    Ok(())
+}
+
+/// 连接串相同的 MongoDB 存储共用的客户端（各自一个连接池会成倍占用连接）
+#[cfg(feature = "mongodb")]
+static MONGO_CLIENTS: std::sync::LazyLock<tokio::sync::Mutex<std::collections::HashMap<String, mongodb::Client>>> =
+    std::sync::LazyLock::new(Default::default);
+
+/// 按连接串获取 MongoDB 客户端：同一连接串复用同一连接池，
+/// 池大小与超时按 XHS_MONGO_* 覆盖连接串中的设置
+#[cfg(feature = "mongodb")]
+pub async fn mongo_client(uri: &str) -> mongodb::error::Result<mongodb::Client> {
+    let mut clients = MONGO_CLIENTS.lock().await;
+    if let Some(client) = clients.get(uri) {
+        return Ok(client.clone());
+    }
+    let mut options = mongodb::options::ClientOptions::parse(uri).await?;
+    if let Some(size) = crate::config::mongo_max_pool_size() {
+        options.max_pool_size = Some(size);
+    }
+    if let Some(size) = crate::config::mongo_min_pool_size() {
+        options.min_pool_size = Some(size);
+    }
+    if let Some(timeout) = crate::config::mongo_connect_timeout() {
+        options.connect_timeout = Some(timeout);
+    }
+    if let Some(timeout) = crate::config::mongo_server_selection_timeout() {
+        options.server_selection_timeout = Some(timeout);
+    }
+    let client = mongodb::Client::with_options(options)?;
+    clients.insert(uri.to_string(), client.clone());
+    Ok(client)
+}
@@ -0,0 +1,218 @@
+//! 小红书链接工具
+//!
+//! - [`extract_url`]: 从 App 分享文案中取出第一个小红书链接
+//! - [`expand_short_link`]: 逐跳跟随 xhslink.com 短链的重定向
+//! - [`XhsLink::parse`]: 把笔记 / 用户主页 / 话题链接解析为类型化标识
+//! - [`XhsLink::share_url`]、[`note_url`]、[`user_url`]、[`topic_url`]: 生成规范链接
+//!
+//! 支持的链接形式：
+//! - 笔记: `/explore/<note_id>`、`/discovery/item/<note_id>`、`/user/profile/<user_id>/<note_id>`
+//! - 用户主页: `/user/profile/<user_id>`
+//! - 话题: `/page/topics/<page_id>`
+
+use reqwest::header::LOCATION;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use url::Url;
+use utoipa::ToSchema;
+
+use crate::error::{Result, XhsError};
+
+/// 网页端域名
+const WEB_HOST: &str = "xiaohongshu.com";
+
+/// 短链域名
+const SHORT_LINK_HOST: &str = "xhslink.com";
+
+/// 短链最多跟随的重定向次数
+const MAX_REDIRECTS: usize = 5;
+
+/// 短链请求超时
+const EXPAND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 解析后的小红书链接
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum XhsLink {
+    /// 笔记
+    Note {
+        note_id: String,
+        xsec_token: Option<String>,
+        xsec_source: Option<String>,
+    },
+    /// 用户主页
+    User {
+        user_id: String,
+        xsec_token: Option<String>,
+        xsec_source: Option<String>,
+    },
+    /// 话题页
+    Topic { page_id: String },
+}
+
+impl XhsLink {
+    /// 解析网页端链接（不发请求）；短链需先经 [`expand_short_link`] 展开
+    pub fn parse(url: &Url) -> Option<Self> {
+        if !is_host(url, WEB_HOST) {
+            return None;
+        }
+        let segments: Vec<&str> = url.path_segments()?.filter(|s| !s.is_empty()).collect();
+        let query = |key: &str| {
+            url.query_pairs()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.into_owned())
+                .filter(|v| !v.is_empty())
+        };
+        let link = match segments.as_slice() {
+            ["explore", id] | ["discovery", "item", id] | ["user", "profile", _, id] => XhsLink::Note {
+                note_id: id.to_string(),
+                xsec_token: query("xsec_token"),
+                xsec_source: query("xsec_source"),
+            },
+            ["user", "profile", id] => XhsLink::User {
+                user_id: id.to_string(),
+                xsec_token: query("xsec_token"),
+                xsec_source: query("xsec_source"),
+            },
+            ["page", "topics", id] => XhsLink::Topic { page_id: id.to_string() },
+            _ => return None,
+        };
+        Some(link)
+    }
+
+    /// 规范链接（去掉 App 追加的跟踪参数，只保留 xsec_token / xsec_source）
+    pub fn share_url(&self) -> String {
+        match self {
+            XhsLink::Note { note_id, xsec_token, xsec_source } => {
+                note_url(note_id, xsec_token.as_deref(), xsec_source.as_deref())
+            }
+            XhsLink::User { user_id, xsec_token, xsec_source } => {
+                user_url(user_id, xsec_token.as_deref(), xsec_source.as_deref())
+            }
+            XhsLink::Topic { page_id } => topic_url(page_id),
+        }
+    }
+}
+
+/// 笔记链接 `https://www.xiaohongshu.com/explore/<note_id>?xsec_token=...&xsec_source=...`
+///
+/// 没有 xsec_token 时不带参数；没有 xsec_source 时为 pc_feed
+pub fn note_url(note_id: &str, xsec_token: Option<&str>, xsec_source: Option<&str>) -> String {
+    with_token(format!("https://www.{}/explore/{}", WEB_HOST, note_id), xsec_token, xsec_source)
+}
+
+/// 用户主页链接 `https://www.xiaohongshu.com/user/profile/<user_id>`
+pub fn user_url(user_id: &str, xsec_token: Option<&str>, xsec_source: Option<&str>) -> String {
+    with_token(format!("https://www.{}/user/profile/{}", WEB_HOST, user_id), xsec_token, xsec_source)
+}
+
+/// 话题页链接 `https://www.xiaohongshu.com/page/topics/<page_id>`
+pub fn topic_url(page_id: &str) -> String {
+    format!("https://www.{}/page/topics/{}", WEB_HOST, page_id)
+}
+
+fn with_token(mut url: String, xsec_token: Option<&str>, xsec_source: Option<&str>) -> String {
+    if let Some(token) = xsec_token {
+        url.push_str(&format!(
+            "?xsec_token={}&xsec_source={}",
+            urlencoding::encode(token),
+            urlencoding::encode(xsec_source.unwrap_or("pc_feed")),
+        ));
+    }
+    url
+}
+
+/// 取输入中第一个小红书链接（分享文案中的链接后常紧跟中文说明，前面可能还有其他链接）；省略协议时按 https 处理
+pub fn extract_url(input: &str) -> Option<Url> {
+    let mut starts = input.match_indices("http")
+        .map(|(start, _)| &input[start..])
+        .filter(|rest| rest.starts_with("http://") || rest.starts_with("https://"))
+        .peekable();
+    if starts.peek().is_none() {
+        return parse_xhs_url(&format!("https://{}", input.trim()));
+    }
+    starts.find_map(parse_xhs_url)
+}
+
+/// 解析到第一个空白或非 ASCII 字符为止，只接受小红书网页端与短链域名
+fn parse_xhs_url(candidate: &str) -> Option<Url> {
+    let end = candidate
+        .find(|c: char| c.is_whitespace() || !c.is_ascii())
+        .unwrap_or(candidate.len());
+    let url = Url::parse(&candidate[..end]).ok()?;
+    (is_host(&url, WEB_HOST) || is_short_link(&url)).then_some(url)
+}
+
+/// 是否为 xhslink.com 短链
+pub fn is_short_link(url: &Url) -> bool {
+    is_host(url, SHORT_LINK_HOST)
+}
+
+/// 逐跳跟随短链的重定向，直到离开短链域名（不是短链时原样返回）
+///
+/// 到达网页端链接即停止，不会继续跟到登录页而丢失 xsec_token
+pub async fn expand_short_link(mut url: Url) -> Result<Url> {
+    if !is_short_link(&url) {
+        return Ok(url);
+    }
+    let client = crate::client::shared_client("short-link", None, |builder| {
+        builder.timeout(EXPAND_TIMEOUT).redirect(reqwest::redirect::Policy::none())
+    })?;
+    for _ in 0..MAX_REDIRECTS {
+        let response = client.get(url.clone()).send().await?;
+        let status = response.status();
+        let location = response.headers()
+            .get(LOCATION)
+            .and_then(|v| v.to_str().ok())
+            .filter(|_| status.is_redirection())
+            .ok_or_else(|| XhsError::Upstream {
+                status: status.as_u16(),
+                body: format!("Short link {} did not redirect", url),
+            })?;
+        url = url.join(location).map_err(|e| XhsError::Parse(format!("Invalid redirect {}: {}", location, e)))?;
+        if !is_short_link(&url) {
+            return Ok(url);
+        }
+    }
+    Err(XhsError::Other(anyhow::anyhow!("Too many redirects (max {})", MAX_REDIRECTS)))
+}
+
+/// 从链接 / 短链 / 分享文案解析，返回 (链接, 展开后的链接)
+///
+/// 无法识别的输入返回 `XhsError::Other`，短链请求失败返回 `XhsError::Network`
+pub async fn resolve(input: &str) -> Result<(XhsLink, Url)> {
+    let url = extract_url(input)
+        .ok_or_else(|| XhsError::Other(anyhow::anyhow!("No xiaohongshu.com or xhslink.com link found in input")))?;
+    let url = expand_short_link(url).await?;
+    let link = XhsLink::parse(&url)
+        .ok_or_else(|| XhsError::Other(anyhow::anyhow!("Unrecognized xiaohongshu.com link: {}", url)))?;
+    Ok((link, url))
+}
+
+/// `url` 的主机是否为 `domain` 或其子域名
+fn is_host(url: &Url, domain: &str) -> bool {
+    url.host_str().is_some_and(|host| host == domain || host.ends_with(&format!(".{}", domain)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_url_skips_links_to_other_hosts() {
+        let input = "来自 https://example.com/ad 的推荐 http://xhslink.com/a/AbC123 复制后打开【小红书】";
+        let url = extract_url(input).expect("xhs link");
+        assert_eq!(url.as_str(), "http://xhslink.com/a/AbC123");
+    }
+
+    #[test]
+    fn extract_url_rejects_text_without_xhs_links() {
+        assert!(extract_url("see https://example.com/post and http://example.org").is_none());
+    }
+
+    #[test]
+    fn extract_url_assumes_https_without_scheme() {
+        let url = extract_url(" www.xiaohongshu.com/explore/6948a1c2000000001f00d3e1 ").expect("xhs link");
+        assert_eq!(url.as_str(), "https://www.xiaohongshu.com/explore/6948a1c2000000001f00d3e1");
+    }
+}
@@ -0,0 +1,52 @@
+//! Replays a recorded cassette through the library API: no account, agent or network needed.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use xhs_rs::api::note::cache::NoteDetailCache;
+use xhs_rs::api::note::detail::{fetch_note_detail, NoteDetailRequest};
+use xhs_rs::api::search::search_notes;
+use xhs_rs::api::vcr::Cassette;
+use xhs_rs::api::XhsApiClient;
+use xhs_rs::models::search::SearchNotesRequest;
+use xhs_rs::{AuthService, UserCredentials, XhsClient};
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/vcr").join(name)
+}
+
+async fn replay_client(cassette: &str) -> XhsApiClient {
+    std::env::set_var("XHS_STORAGE", "memory");
+    let auth = AuthService::new(PathBuf::from("vcr-replay-cookie.json")).await.unwrap();
+    let cookies = HashMap::from([
+        ("a1".to_string(), "vcr-a1".to_string()),
+        ("web_session".to_string(), "vcr-session".to_string()),
+    ]);
+    auth.save_credentials(&UserCredentials::new("vcr-user".to_string(), cookies, None)).await.unwrap();
+
+    let cassette = Cassette::replay(fixture(cassette)).unwrap();
+    let client = XhsClient::builder().cassette(Arc::new(cassette)).build().unwrap();
+    XhsApiClient::new(client, Arc::new(auth))
+}
+
+#[tokio::test]
+async fn search_then_note_detail_from_cassette() {
+    let api = replay_client("search_and_detail.json").await;
+
+    let search = search_notes(&api, SearchNotesRequest::new("咖啡馆")).await.unwrap();
+    assert!(search.success);
+    let data = search.data.expect("search data");
+    assert!(data.has_more);
+    assert!(data.parse_errors.is_empty());
+    let note = data.items.iter().find(|item| item.model_type.as_deref() == Some("note")).expect("note item");
+    assert_eq!(note.id, "6948a1c2000000001f00d3e1");
+
+    let cache = NoteDetailCache::new(Duration::from_secs(60));
+    let token = note.xsec_token.clone().expect("xsec_token");
+    let detail = fetch_note_detail(&api, &cache, NoteDetailRequest::new(&note.id, token)).await.unwrap();
+    assert!(detail.success);
+    let title = detail.note_card().and_then(|card| card["title"].as_str());
+    assert_eq!(title, Some("男生没方向，一定要去闯闯的6个职业！"));
+}